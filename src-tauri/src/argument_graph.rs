@@ -0,0 +1,459 @@
+// Argument graph extraction for debate transcripts.
+// Each message of a debate run is read in order by an extractor profile, which lists the claims it
+// makes and how they support or attack earlier claims. Claims become nodes (with the source message
+// and speaker), relations become edges; the graph is stored in `argument_nodes`/`argument_edges`
+// and can be exported as JSON, GraphML or Mermaid.
+
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::tournament::extract_json_object;
+use crate::types::PromptPacket;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Most recent claims shown to the extractor when reading the next message.
+const MAX_PRIOR_CLAIMS: usize = 60;
+const MAX_CLAIMS_PER_MESSAGE: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentNode {
+    pub id: String,
+    pub claim: String,
+    pub message_id: String,
+    pub profile_id: Option<String>,
+    pub speaker: String,
+    pub round_index: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    /// "supports" or "attacks"
+    pub relation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentGraph {
+    pub run_id: String,
+    pub nodes: Vec<ArgumentNode>,
+    pub edges: Vec<ArgumentEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractedClaim {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    claim: String,
+    #[serde(default)]
+    supports: Vec<String>,
+    #[serde(default)]
+    attacks: Vec<String>,
+}
+
+struct SourceMessage {
+    id: String,
+    author_type: String,
+    profile_id: Option<String>,
+    speaker: String,
+    round_index: Option<i32>,
+    text: String,
+}
+
+fn load_messages(db: &Database, run_id: &str) -> Result<Vec<SourceMessage>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT m.id, m.author_type, m.profile_id, p.name, m.round_index, m.text, m.author_label
+             FROM messages m LEFT JOIN prompt_profiles p ON p.id = m.profile_id
+             WHERE m.run_id = ?1 ORDER BY m.round_index, m.turn_index, m.created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            let author_type: String = row.get(1)?;
+            let name: Option<String> = row.get(3)?;
+            let speaker = match author_type.as_str() {
+                "agent" => name.unwrap_or_else(|| "Agent".to_string()),
+                "moderator" => "Moderator".to_string(),
+                _ => row.get::<_, Option<String>>(6)?.unwrap_or_else(|| "User".to_string()),
+            };
+            Ok(SourceMessage {
+                id: row.get(0)?,
+                author_type,
+                profile_id: row.get(2)?,
+                speaker,
+                round_index: row.get(4)?,
+                text: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut messages = Vec::new();
+    for row in rows {
+        messages.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    Ok(messages)
+}
+
+fn extraction_prompt(question: &str, prior: &[(String, &ArgumentNode)], message: &SourceMessage) -> String {
+    let existing = if prior.is_empty() {
+        "(none yet)".to_string()
+    } else {
+        prior
+            .iter()
+            .map(|(key, node)| format!("{}: {} — {}", key, node.speaker, node.claim))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    format!(
+        "Debate question:\n{}\n\nClaims made so far (id: speaker — claim):\n{}\n\nNew message by {}:\n{}\n\n\
+        Task: List the distinct claims made in the new message (at most {}), each as one short standalone sentence. \
+        For each claim, list the ids of claims it supports and the ids of claims it attacks. You may refer to \
+        existing ids or to the ids of other new claims (new1, new2, ...). Skip pleasantries and restatements.\n\
+        Reply as JSON: {{\"claims\": [{{\"id\": \"new1\", \"claim\": \"...\", \"supports\": [\"c3\"], \"attacks\": [\"c1\"]}}]}}",
+        question, existing, message.speaker, message.text, MAX_CLAIMS_PER_MESSAGE
+    )
+}
+
+/// Extract the argument graph of a debate run and store it, replacing any previous graph.
+/// Fails without touching the stored graph when any message could not be analysed.
+pub async fn extract(db: &Database, run_id: &str, extractor_profile_id: &str) -> Result<ArgumentGraph, String> {
+    let (question, provider_account_id, model_name): (String, String, String) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let question = conn_guard
+            .query_row(
+                "SELECT s.user_question FROM runs r JOIN sessions s ON s.id = r.session_id WHERE r.id = ?1",
+                [run_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to load debate question: {}", e))?;
+        let (provider, model) = conn_guard
+            .query_row(
+                "SELECT provider_account_id, model_name FROM prompt_profiles WHERE id = ?1",
+                [extractor_profile_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Failed to load extractor profile: {}", e))?;
+        (question, provider, model)
+    };
+    let messages = load_messages(db, run_id)?;
+
+    let mut nodes: Vec<ArgumentNode> = Vec::new();
+    let mut edges: Vec<ArgumentEdge> = Vec::new();
+    for message in messages.iter().filter(|m| m.author_type != "moderator" && !m.text.trim().is_empty()) {
+        let start = nodes.len().saturating_sub(MAX_PRIOR_CLAIMS);
+        let prior: Vec<(String, &ArgumentNode)> = nodes[start..]
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (format!("c{}", start + i + 1), n))
+            .collect();
+        let packet = PromptPacket {
+            global_instructions: Some("You extract argument structure from debates. Reply with a single JSON object only.".to_string()),
+            persona_instructions: "You are a careful, neutral argument analyst.".to_string(),
+            user_message: extraction_prompt(&question, &prior, message),
+            conversation_context: None,
+            params_json: json!({ "temperature": 0.0 }),
+            stream: false,
+        };
+        let (response, _, _) = complete_resolving_hybrid(db, &provider_account_id, &model_name, &packet, 120, None)
+            .await
+            .map_err(|e| format!("Claim extraction failed for a message by {}: {}", message.speaker, e))?;
+        let claims: Vec<ExtractedClaim> = extract_json_object(&response.text)
+            .and_then(|v| v.get("claims").cloned())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .ok_or_else(|| format!("Claim extraction for a message by {} did not return a claims list", message.speaker))?;
+
+        // Reference keys -> node ids: existing "cN" plus this message's own "newN"
+        let mut keys: HashMap<String, String> = prior.iter().map(|(k, n)| (k.clone(), n.id.clone())).collect();
+        let claims: Vec<(String, ExtractedClaim)> = claims
+            .into_iter()
+            .filter(|c| !c.claim.trim().is_empty())
+            .take(MAX_CLAIMS_PER_MESSAGE)
+            .map(|c| (Uuid::new_v4().to_string(), c))
+            .collect();
+        for (node_id, claim) in &claims {
+            if !claim.id.is_empty() {
+                keys.insert(claim.id.clone(), node_id.clone());
+            }
+            nodes.push(ArgumentNode {
+                id: node_id.clone(),
+                claim: claim.claim.trim().to_string(),
+                message_id: message.id.clone(),
+                profile_id: message.profile_id.clone(),
+                speaker: message.speaker.clone(),
+                round_index: message.round_index,
+            });
+        }
+        for (node_id, claim) in &claims {
+            for (relation, targets) in [("supports", &claim.supports), ("attacks", &claim.attacks)] {
+                for target in targets.iter().filter_map(|t| keys.get(t.trim())) {
+                    if target != node_id {
+                        edges.push(ArgumentEdge {
+                            id: Uuid::new_v4().to_string(),
+                            source: node_id.clone(),
+                            target: target.clone(),
+                            relation: relation.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let graph = ArgumentGraph { run_id: run_id.to_string(), nodes, edges };
+    save(db, &graph, extractor_profile_id)?;
+    Ok(graph)
+}
+
+fn save(db: &Database, graph: &ArgumentGraph, extractor_profile_id: &str) -> Result<(), String> {
+    let conn = db.get_connection();
+    let mut conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let tx = conn_guard.transaction().map_err(|e| format!("Database error: {}", e))?;
+    tx.execute("DELETE FROM argument_edges WHERE run_id = ?1", [&graph.run_id])
+        .map_err(|e| format!("Failed to clear argument edges: {}", e))?;
+    tx.execute("DELETE FROM argument_nodes WHERE run_id = ?1", [&graph.run_id])
+        .map_err(|e| format!("Failed to clear argument nodes: {}", e))?;
+    let now = chrono::Utc::now().to_rfc3339();
+    for (position, node) in graph.nodes.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO argument_nodes (id, run_id, position, claim, message_id, profile_id, speaker, round_index, extractor_profile_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                node.id,
+                graph.run_id,
+                position as i64,
+                node.claim,
+                node.message_id,
+                node.profile_id,
+                node.speaker,
+                node.round_index,
+                extractor_profile_id,
+                now
+            ],
+        )
+        .map_err(|e| format!("Failed to save argument node: {}", e))?;
+    }
+    for edge in &graph.edges {
+        tx.execute(
+            "INSERT INTO argument_edges (id, run_id, source_node_id, target_node_id, relation, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![edge.id, graph.run_id, edge.source, edge.target, edge.relation, now],
+        )
+        .map_err(|e| format!("Failed to save argument edge: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("Database error: {}", e))
+}
+
+pub fn load(db: &Database, run_id: &str) -> Result<ArgumentGraph, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT id, claim, message_id, profile_id, speaker, round_index FROM argument_nodes
+             WHERE run_id = ?1 ORDER BY position",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let nodes = stmt
+        .query_map([run_id], |row| {
+            Ok(ArgumentNode {
+                id: row.get(0)?,
+                claim: row.get(1)?,
+                message_id: row.get(2)?,
+                profile_id: row.get(3)?,
+                speaker: row.get(4)?,
+                round_index: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare("SELECT id, source_node_id, target_node_id, relation FROM argument_edges WHERE run_id = ?1 ORDER BY created_at")
+        .map_err(|e| format!("Database error: {}", e))?;
+    let edges = stmt
+        .query_map([run_id], |row| {
+            Ok(ArgumentEdge {
+                id: row.get(0)?,
+                source: row.get(1)?,
+                target: row.get(2)?,
+                relation: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    Ok(ArgumentGraph { run_id: run_id.to_string(), nodes, edges })
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn to_graphml(graph: &ArgumentGraph) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+        <key id=\"claim\" for=\"node\" attr.name=\"claim\" attr.type=\"string\"/>\n  \
+        <key id=\"speaker\" for=\"node\" attr.name=\"speaker\" attr.type=\"string\"/>\n  \
+        <key id=\"profile_id\" for=\"node\" attr.name=\"profile_id\" attr.type=\"string\"/>\n  \
+        <key id=\"message_id\" for=\"node\" attr.name=\"message_id\" attr.type=\"string\"/>\n  \
+        <key id=\"round_index\" for=\"node\" attr.name=\"round_index\" attr.type=\"int\"/>\n  \
+        <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n",
+    );
+    out.push_str(&format!("  <graph id=\"{}\" edgedefault=\"directed\">\n", xml_escape(&graph.run_id)));
+    for node in &graph.nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.id)));
+        out.push_str(&format!("      <data key=\"claim\">{}</data>\n", xml_escape(&node.claim)));
+        out.push_str(&format!("      <data key=\"speaker\">{}</data>\n", xml_escape(&node.speaker)));
+        if let Some(profile_id) = &node.profile_id {
+            out.push_str(&format!("      <data key=\"profile_id\">{}</data>\n", xml_escape(profile_id)));
+        }
+        out.push_str(&format!("      <data key=\"message_id\">{}</data>\n", xml_escape(&node.message_id)));
+        if let Some(round) = node.round_index {
+            out.push_str(&format!("      <data key=\"round_index\">{}</data>\n", round));
+        }
+        out.push_str("    </node>\n");
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n      <data key=\"relation\">{}</data>\n    </edge>\n",
+            xml_escape(&edge.id),
+            xml_escape(&edge.source),
+            xml_escape(&edge.target),
+            xml_escape(&edge.relation)
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+/// Mermaid flowchart: solid arrows for supports, dotted for attacks.
+pub fn to_mermaid(graph: &ArgumentGraph) -> String {
+    let index: HashMap<&str, usize> = graph.nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i + 1)).collect();
+    let label = |text: &str| text.replace('"', "#quot;").replace('\n', " ");
+    let mut out = String::from("flowchart TD\n");
+    for (i, node) in graph.nodes.iter().enumerate() {
+        out.push_str(&format!("    n{}[\"{}: {}\"]\n", i + 1, label(&node.speaker), label(&node.claim)));
+    }
+    for edge in &graph.edges {
+        if let (Some(s), Some(t)) = (index.get(edge.source.as_str()), index.get(edge.target.as_str())) {
+            let arrow = if edge.relation == "attacks" { "-.->" } else { "-->" };
+            out.push_str(&format!("    n{} {}|{}| n{}\n", s, arrow, edge.relation, t));
+        }
+    }
+    out
+}
+
+/// Serialize in "json", "graphml" or "mermaid".
+pub fn export(graph: &ArgumentGraph, format: &str) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(graph).map_err(|e| format!("Failed to serialize graph: {}", e)),
+        "graphml" => Ok(to_graphml(graph)),
+        "mermaid" => Ok(to_mermaid(graph)),
+        other => Err(format!("Unsupported argument graph format: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ArgumentGraph {
+        let node = |id: &str, claim: &str, speaker: &str| ArgumentNode {
+            id: id.to_string(),
+            claim: claim.to_string(),
+            message_id: format!("m-{}", id),
+            profile_id: Some(format!("p-{}", speaker)),
+            speaker: speaker.to_string(),
+            round_index: Some(0),
+        };
+        ArgumentGraph {
+            run_id: "run-1".to_string(),
+            nodes: vec![node("a", "Taxes should rise", "Alice"), node("b", "Growth <matters> \"more\"", "Bob")],
+            edges: vec![ArgumentEdge {
+                id: "e1".to_string(),
+                source: "b".to_string(),
+                target: "a".to_string(),
+                relation: "attacks".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_graphml_escapes_claims() {
+        let xml = to_graphml(&sample());
+        assert!(xml.contains("Growth &lt;matters&gt; &quot;more&quot;"));
+        assert!(xml.contains("<edge id=\"e1\" source=\"b\" target=\"a\">"));
+        assert!(xml.contains("<data key=\"message_id\">m-a</data>"));
+    }
+
+    #[test]
+    fn test_mermaid_uses_indices_and_relation_styles() {
+        let mermaid = to_mermaid(&sample());
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("n2[\"Bob: Growth <matters> #quot;more#quot;\"]"));
+        assert!(mermaid.contains("n2 -.->|attacks| n1"));
+        assert!(export(&sample(), "dot").is_err());
+    }
+
+    fn test_db() -> (Database, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("panther-argument-graph-{}.db", Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json) VALUES
+                     ('p-Alice', 'Alice', 'acc', 'gpt', 'You are Alice', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Taxes', 'Raise taxes?', 'debate');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('run-1', 's1', '[\"p-Alice\"]', 'complete', '{}');
+                 INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text)
+                     VALUES ('m-a', 'run-1', 'agent', 'p-Alice', 0, 0, 'Taxes should rise.');",
+            )
+            .unwrap();
+        }
+        (db, path)
+    }
+
+    #[test]
+    fn test_save_replaces_the_stored_graph() {
+        let (db, path) = test_db();
+        save(&db, &sample(), "p-Alice").unwrap();
+        let loaded = load(&db, "run-1").unwrap();
+        assert_eq!(loaded.nodes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(loaded.edges.len(), 1);
+
+        let smaller = ArgumentGraph { edges: Vec::new(), nodes: sample().nodes[..1].to_vec(), ..sample() };
+        save(&db, &smaller, "p-Alice").unwrap();
+        let loaded = load(&db, "run-1").unwrap();
+        assert_eq!((loaded.nodes.len(), loaded.edges.len()), (1, 0));
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_failed_extraction_keeps_the_stored_graph() {
+        let (db, path) = test_db();
+        save(&db, &sample(), "p-Alice").unwrap();
+
+        // The extractor's provider type is unsupported, so every extraction call fails
+        assert!(extract(&db, "run-1", "p-Alice").await.is_err());
+        let loaded = load(&db, "run-1").unwrap();
+        assert_eq!((loaded.nodes.len(), loaded.edges.len()), (2, 1));
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,51 @@
+// MCP server over stdio - lets MCP-aware editors and agents use Panther profiles and knowledge.
+// Use: cargo run --bin panther-mcp-server
+// Client config example: { "command": "panther-mcp-server", "env": { "PANTHER_USER_ID": "<optional>" } }
+//
+// stdout carries protocol messages only; logs go to stderr.
+
+use brain_stormer_lib::run_mcp_server;
+use brain_stormer_lib::Database;
+use std::env;
+use std::path::PathBuf;
+
+fn resolve_db_path() -> PathBuf {
+    if let Ok(p) = env::var("PANTHER_DB_PATH") {
+        return PathBuf::from(p);
+    }
+
+    #[cfg(windows)]
+    {
+        if let Ok(p) = env::var("APPDATA") {
+            return PathBuf::from(p).join("panther").join("panther.db");
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        if let Ok(h) = env::var("HOME") {
+            return PathBuf::from(h)
+                .join(".local")
+                .join("share")
+                .join("panther")
+                .join("panther.db");
+        }
+    }
+
+    PathBuf::from("panther.db")
+}
+
+#[tokio::main]
+async fn main() {
+    let db_path = resolve_db_path();
+    if let Some(parent) = db_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    eprintln!("Panther MCP server (stdio)");
+    eprintln!("Database: {}", db_path.display());
+
+    let db = Database::new(db_path).expect("Failed to initialize database");
+    let user_id = env::var("PANTHER_USER_ID").ok().filter(|u| !u.is_empty());
+
+    run_mcp_server(db, user_id).await;
+}
@@ -0,0 +1,690 @@
+// Profile export/import as portable character cards.
+//
+// Cards follow the community character-card v2 layout (`chara_card_v2`): the shared fields
+// (name, description, personality, system_prompt, tags) let other tools read a Panther profile,
+// and everything Panther needs to recreate the profile exactly lives under
+// `data.extensions.panther`. A card is either a JSON file or a PNG image carrying the same JSON
+// base64-encoded in a `tEXt` chunk named `chara`.
+//
+// Cards never contain provider credentials or account ids. On import the provider is resolved by
+// the provider type and model recorded in the card; cards from other tools get a persona built
+// from their description, personality, scenario and example dialogue.
+
+use crate::commands::{self, CreateProfileRequest};
+use crate::db::Database;
+use crate::report::html::avatar_data_url;
+use crate::report::ReportFile;
+use crate::types::CharacterDefinition;
+use base64::Engine;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+
+pub const CARD_SPEC: &str = "chara_card_v2";
+pub const CARD_SPEC_VERSION: &str = "2.0";
+pub const PANTHER_EXTENSION_VERSION: i64 = 1;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// Text chunk keywords used by card tools (v2 and v3)
+const CARD_KEYWORDS: &[&str] = &["chara", "ccv3"];
+const PLACEHOLDER_SIZE: u32 = 256;
+const PHOTO_FETCH_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardFormat {
+    Json,
+    Png,
+}
+
+impl CardFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(CardFormat::Json),
+            "png" => Ok(CardFormat::Png),
+            other => Err(format!("Unknown card format '{}': use json or png", other)),
+        }
+    }
+}
+
+/// Everything needed to recreate a Panther profile, stored under `data.extensions.panther`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PantherExtension {
+    #[serde(default)]
+    pub format_version: i64,
+    pub provider_type: Option<String>,
+    pub model_name: Option<String>,
+    pub persona_prompt: Option<String>,
+    pub character_definition: Option<Value>,
+    pub model_features: Option<Value>,
+    pub params: Option<Value>,
+    /// data: URL; absent when the PNG card image is the photo itself
+    pub photo: Option<String>,
+    /// The PNG card image was generated because the profile has no PNG photo
+    #[serde(default)]
+    pub placeholder_image: bool,
+    pub voice_gender: Option<String>,
+    pub voice_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CardImportRequest {
+    /// Card JSON, or the base64 PNG when `base64` is set
+    pub content: String,
+    #[serde(default)]
+    pub base64: bool,
+    /// Overrides provider resolution
+    pub provider_account_id: Option<String>,
+    /// Model to use when the card does not name one (or to override it)
+    pub model_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CardImportResult {
+    pub profile_id: String,
+    pub name: String,
+    pub provider_account_id: String,
+    pub model_name: String,
+    /// The card came from Panther (exact profile) rather than another tool
+    pub panther_card: bool,
+    pub warnings: Vec<String>,
+}
+
+struct ProfileRow {
+    name: String,
+    provider_type: Option<String>,
+    model_name: String,
+    persona_prompt: String,
+    character_definition: Option<Value>,
+    model_features: Option<Value>,
+    params: Value,
+    photo_url: Option<String>,
+    voice_gender: Option<String>,
+    voice_uri: Option<String>,
+    version: Option<i64>,
+}
+
+fn parse_json(text: Option<String>) -> Option<Value> {
+    text.and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn load_profile(conn: &Connection, profile_id: &str) -> Result<ProfileRow, String> {
+    conn.query_row(
+        "SELECT p.name, a.provider_type, p.model_name, p.persona_prompt, p.character_definition_json,
+                p.model_features_json, p.params_json, p.photo_url, p.voice_gender, p.voice_uri,
+                (SELECT MAX(version) FROM profile_versions v WHERE v.profile_id = p.id)
+         FROM prompt_profiles p LEFT JOIN provider_accounts a ON a.id = p.provider_account_id
+         WHERE p.id = ?1",
+        [profile_id],
+        |row| {
+            Ok(ProfileRow {
+                name: row.get(0)?,
+                provider_type: row.get(1)?,
+                model_name: row.get(2)?,
+                persona_prompt: row.get(3)?,
+                character_definition: parse_json(row.get(4)?),
+                model_features: parse_json(row.get(5)?),
+                params: parse_json(row.get(6)?).unwrap_or(json!({})),
+                photo_url: row.get(7)?,
+                voice_gender: row.get(8)?,
+                voice_uri: row.get(9)?,
+                version: row.get(10)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| "Profile not found".to_string())
+}
+
+/// Photo as a data: URL; remote photos are downloaded so the card is self-contained
+async fn photo_data_url(photo_url: &str) -> Option<String> {
+    if !(photo_url.starts_with("http://") || photo_url.starts_with("https://")) {
+        return avatar_data_url(photo_url);
+    }
+    let response = reqwest::Client::new()
+        .get(photo_url)
+        .timeout(std::time::Duration::from_secs(PHOTO_FETCH_TIMEOUT_SECS))
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    let mime = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or(v).trim().to_string())
+        .filter(|m| m.starts_with("image/"))?;
+    let bytes = response.bytes().await.ok()?;
+    Some(format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes)))
+}
+
+fn decode_data_url(url: &str) -> Option<(String, Vec<u8>)> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let mime = header.strip_suffix(";base64")?;
+    let bytes = base64::engine::general_purpose::STANDARD.decode(data.trim()).ok()?;
+    Some((mime.to_string(), bytes))
+}
+
+fn card_json(profile: &ProfileRow, extension: &PantherExtension) -> Value {
+    let character: Option<CharacterDefinition> =
+        profile.character_definition.clone().and_then(|v| serde_json::from_value(v).ok());
+    let description = character
+        .as_ref()
+        .map(|c| match &c.background {
+            Some(background) if !background.trim().is_empty() => format!("{}\n\n{}", c.role, background),
+            _ => c.role.clone(),
+        })
+        .unwrap_or_default();
+    json!({
+        "spec": CARD_SPEC,
+        "spec_version": CARD_SPEC_VERSION,
+        "data": {
+            "name": profile.name,
+            "description": description,
+            "personality": character.as_ref().map(|c| c.personality.join(", ")).unwrap_or_default(),
+            "scenario": "",
+            "first_mes": "",
+            "mes_example": "",
+            "creator_notes": "Exported from Panther",
+            "system_prompt": profile.persona_prompt,
+            "post_history_instructions": "",
+            "alternate_greetings": [],
+            "tags": character.as_ref().map(|c| c.expertise.clone()).unwrap_or_default(),
+            "creator": "",
+            "character_version": profile.version.map(|v| format!("v{}", v)).unwrap_or_default(),
+            "extensions": { "panther": extension },
+        }
+    })
+}
+
+/// Generated definitions record the provider account they came from; account ids stay local
+fn portable_character(mut definition: Value) -> Value {
+    if let Some(provenance) = definition.get_mut("provenance").and_then(|p| p.as_object_mut()) {
+        provenance.insert("provider_account_id".to_string(), json!(""));
+    }
+    definition
+}
+
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+        .take(60)
+        .collect();
+    if stem.is_empty() { "character".to_string() } else { stem }
+}
+
+/// Export a profile as a JSON or PNG character card
+pub async fn export(db: &Database, profile_id: &str, format: CardFormat) -> Result<ReportFile, String> {
+    let profile = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        load_profile(&conn_guard, profile_id)?
+    };
+    let photo = match profile.photo_url.as_deref().filter(|p| !p.is_empty()) {
+        Some(url) => photo_data_url(url).await,
+        None => None,
+    };
+    let mut extension = PantherExtension {
+        format_version: PANTHER_EXTENSION_VERSION,
+        provider_type: profile.provider_type.clone(),
+        model_name: Some(profile.model_name.clone()),
+        persona_prompt: Some(profile.persona_prompt.clone()),
+        character_definition: profile.character_definition.clone().map(portable_character),
+        model_features: profile.model_features.clone(),
+        params: Some(profile.params.clone()),
+        photo: photo.clone(),
+        placeholder_image: false,
+        voice_gender: profile.voice_gender.clone(),
+        voice_uri: profile.voice_uri.clone(),
+    };
+    let stem = file_stem(&profile.name);
+
+    match format {
+        CardFormat::Json => {
+            let card = card_json(&profile, &extension);
+            Ok(ReportFile {
+                file_name: format!("{}.json", stem),
+                mime_type: "application/json".to_string(),
+                content: serde_json::to_string_pretty(&card).map_err(|e| format!("Failed to serialize card: {}", e))?,
+                base64: false,
+            })
+        }
+        CardFormat::Png => {
+            let png_photo = photo.as_deref().and_then(decode_data_url).filter(|(mime, bytes)| {
+                mime == "image/png" && bytes.starts_with(PNG_SIGNATURE)
+            });
+            let image = match png_photo {
+                Some((_, bytes)) => {
+                    extension.photo = None;
+                    bytes
+                }
+                None => {
+                    extension.placeholder_image = true;
+                    placeholder_png(&profile.name)?
+                }
+            };
+            let card = serde_json::to_string(&card_json(&profile, &extension))
+                .map_err(|e| format!("Failed to serialize card: {}", e))?;
+            let png = embed_card(&image, &card)?;
+            Ok(ReportFile {
+                file_name: format!("{}.png", stem),
+                mime_type: "image/png".to_string(),
+                content: base64::engine::general_purpose::STANDARD.encode(png),
+                base64: true,
+            })
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PNG chunks
+// ---------------------------------------------------------------------------
+
+struct Chunk<'a> {
+    kind: [u8; 4],
+    data: &'a [u8],
+}
+
+fn read_chunks(png: &[u8]) -> Result<Vec<Chunk<'_>>, String> {
+    if !png.starts_with(PNG_SIGNATURE) {
+        return Err("Not a PNG file".to_string());
+    }
+    let mut chunks = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 12 <= png.len() {
+        let len = u32::from_be_bytes([png[pos], png[pos + 1], png[pos + 2], png[pos + 3]]) as usize;
+        let kind = [png[pos + 4], png[pos + 5], png[pos + 6], png[pos + 7]];
+        let end = pos + 8 + len;
+        if end + 4 > png.len() {
+            return Err("Truncated PNG chunk".to_string());
+        }
+        chunks.push(Chunk { kind, data: &png[pos + 8..end] });
+        pos = end + 4;
+        if &kind == b"IEND" {
+            break;
+        }
+    }
+    Ok(chunks)
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut crc = flate2::Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+fn text_keyword<'a>(chunk: &Chunk<'a>) -> Option<&'a str> {
+    if &chunk.kind != b"tEXt" {
+        return None;
+    }
+    let end = chunk.data.iter().position(|b| *b == 0)?;
+    std::str::from_utf8(&chunk.data[..end]).ok()
+}
+
+/// Copy of `png` with the card in a `chara` text chunk (replacing any earlier card)
+pub fn embed_card(png: &[u8], card_json: &str) -> Result<Vec<u8>, String> {
+    let chunks = read_chunks(png)?;
+    let mut text = b"chara\0".to_vec();
+    text.extend_from_slice(base64::engine::general_purpose::STANDARD.encode(card_json).as_bytes());
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    for chunk in &chunks {
+        if text_keyword(chunk).is_some_and(|k| CARD_KEYWORDS.contains(&k)) {
+            continue;
+        }
+        if &chunk.kind == b"IEND" {
+            write_chunk(&mut out, b"tEXt", &text);
+        }
+        write_chunk(&mut out, &chunk.kind, chunk.data);
+    }
+    Ok(out)
+}
+
+/// Card JSON embedded in a PNG; v3 (`ccv3`) is preferred over v2 when both are present
+pub fn extract_card(png: &[u8]) -> Result<Option<String>, String> {
+    let chunks = read_chunks(png)?;
+    for keyword in CARD_KEYWORDS.iter().rev() {
+        let found = chunks.iter().find(|c| text_keyword(c) == Some(keyword));
+        if let Some(chunk) = found {
+            let encoded = &chunk.data[keyword.len() + 1..];
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim_ascii())
+                .map_err(|e| format!("Invalid card data in PNG: {}", e))?;
+            return String::from_utf8(bytes).map(Some).map_err(|e| format!("Invalid card text in PNG: {}", e));
+        }
+    }
+    Ok(None)
+}
+
+/// Solid square avatar whose colour is derived from the name
+fn placeholder_png(name: &str) -> Result<Vec<u8>, String> {
+    let hash = name.bytes().fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+    let rgb = [64 + (hash & 0x7f) as u8, 64 + ((hash >> 8) & 0x7f) as u8, 64 + ((hash >> 16) & 0x7f) as u8];
+
+    // Each scanline: filter type 0 (none), then the pixels
+    let mut row = vec![0u8];
+    for _ in 0..PLACEHOLDER_SIZE {
+        row.extend_from_slice(&rgb);
+    }
+    let raw = row.repeat(PLACEHOLDER_SIZE as usize);
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&raw).map_err(|e| format!("Failed to encode image: {}", e))?;
+    let idat = encoder.finish().map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&PLACEHOLDER_SIZE.to_be_bytes());
+    ihdr.extend_from_slice(&PLACEHOLDER_SIZE.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB, no interlace
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &ihdr);
+    write_chunk(&mut out, b"IDAT", &idat);
+    write_chunk(&mut out, b"IEND", &[]);
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+fn text<'a>(data: &'a Value, key: &str) -> &'a str {
+    data.get(key).and_then(|v| v.as_str()).map(str::trim).unwrap_or_default()
+}
+
+/// Persona for cards made by other tools; `{{char}}` / `{{user}}` placeholders are filled in
+fn foreign_persona(data: &Value, name: &str) -> String {
+    let mut sections = Vec::new();
+    if !text(data, "system_prompt").is_empty() {
+        sections.push(text(data, "system_prompt").to_string());
+    }
+    sections.push(format!("You are {}.", name));
+    for (label, key) in [
+        ("", "description"),
+        ("Personality: ", "personality"),
+        ("Scenario: ", "scenario"),
+        ("Example dialogue:\n", "mes_example"),
+        ("", "post_history_instructions"),
+    ] {
+        if !text(data, key).is_empty() {
+            sections.push(format!("{}{}", label, text(data, key)));
+        }
+    }
+    sections.join("\n\n").replace("{{char}}", name).replace("{{user}}", "the user")
+}
+
+fn foreign_character(data: &Value, name: &str) -> CharacterDefinition {
+    let description = text(data, "description").replace("{{char}}", name).replace("{{user}}", "the user");
+    let role: String = description.lines().next().unwrap_or_default().chars().take(120).collect();
+    CharacterDefinition {
+        name: name.to_string(),
+        role: if role.is_empty() { "Character".to_string() } else { role },
+        personality: text(data, "personality")
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+        expertise: data
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| tags.iter().filter_map(|t| t.as_str()).map(String::from).collect())
+            .unwrap_or_default(),
+        communication_style: String::new(),
+        background: (!description.is_empty()).then_some(description),
+        goals: None,
+        constraints: None,
+        provenance: None,
+    }
+}
+
+/// Provider account for the card: explicit choice, then an account of the card's provider type
+/// already used with the card's model, then any account of that type, then the first account
+fn resolve_provider(
+    conn: &Connection,
+    provider_type: Option<&str>,
+    model_name: &str,
+    user_id: Option<&str>,
+) -> Result<(String, bool), String> {
+    conn.query_row(
+        "SELECT a.id, a.provider_type = ?1 FROM provider_accounts a
+         WHERE a.user_id IS ?3 OR a.user_id IS NULL
+         ORDER BY a.provider_type = ?1 DESC,
+                  EXISTS (SELECT 1 FROM prompt_profiles p WHERE p.provider_account_id = a.id AND p.model_name = ?2) DESC,
+                  a.created_at
+         LIMIT 1",
+        rusqlite::params![provider_type.unwrap_or_default(), model_name, user_id],
+        |row| Ok((row.get(0)?, row.get::<_, bool>(1)?)),
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| "Add a provider before importing a character card".to_string())
+}
+
+/// Most recently used model on a provider account (for cards that name no model)
+fn recent_model(conn: &Connection, provider_account_id: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT model_name FROM prompt_profiles WHERE provider_account_id = ?1 ORDER BY updated_at DESC LIMIT 1",
+        [provider_account_id],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Create a profile from a JSON or PNG character card
+pub async fn import(db: &Database, request: CardImportRequest, user_id: Option<String>) -> Result<CardImportResult, String> {
+    let (card_text, image) = if request.base64 {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(request.content.trim())
+            .map_err(|e| format!("Invalid file data: {}", e))?;
+        if bytes.starts_with(PNG_SIGNATURE) {
+            let card = extract_card(&bytes)?.ok_or("This PNG does not contain a character card")?;
+            (card, Some(bytes))
+        } else {
+            (String::from_utf8(bytes).map_err(|_| "Card file is neither PNG nor JSON".to_string())?, None)
+        }
+    } else {
+        (request.content, None)
+    };
+    let card: Value = serde_json::from_str(&card_text).map_err(|e| format!("Invalid character card JSON: {}", e))?;
+    // v2/v3 cards wrap the fields in `data`; v1 cards are flat
+    let data = if card.get("spec").is_some() { card.get("data").cloned().unwrap_or(json!({})) } else { card.clone() };
+    let name = match text(&data, "name") {
+        "" => return Err("Character card has no name".to_string()),
+        name => name.to_string(),
+    };
+    let extension: Option<PantherExtension> = data
+        .get("extensions")
+        .and_then(|e| e.get("panther"))
+        .and_then(|e| serde_json::from_value(e.clone()).ok());
+    let panther_card = extension.is_some();
+    let extension = extension.unwrap_or_default();
+    let mut warnings = Vec::new();
+
+    let chosen_model = request.model_name.clone().filter(|m| !m.trim().is_empty());
+    let card_model = chosen_model.clone().or_else(|| extension.model_name.clone());
+    let (provider_account_id, model_name) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        // The card's model only makes sense on an account of the card's provider type
+        let mut other_provider_type = false;
+        let provider_account_id = match &request.provider_account_id {
+            Some(id) => {
+                conn_guard
+                    .query_row(
+                        "SELECT 1 FROM provider_accounts WHERE id = ?1 AND (user_id IS ?2 OR user_id IS NULL)",
+                        rusqlite::params![id, user_id],
+                        |_| Ok(()),
+                    )
+                    .optional()
+                    .map_err(|e| format!("Database error: {}", e))?
+                    .ok_or_else(|| format!("Provider account {} not found", id))?;
+                id.clone()
+            }
+            None => {
+                let (id, type_matched) = resolve_provider(
+                    &conn_guard,
+                    extension.provider_type.as_deref(),
+                    card_model.as_deref().unwrap_or_default(),
+                    user_id.as_deref(),
+                )?;
+                if let (Some(provider_type), false) = (&extension.provider_type, type_matched) {
+                    warnings.push(format!("No {} provider found; using another provider account", provider_type));
+                    other_provider_type = true;
+                }
+                id
+            }
+        };
+        let model = if other_provider_type { chosen_model } else { card_model };
+        let model_name = match model {
+            Some(model) => model,
+            None => {
+                let model = recent_model(&conn_guard, &provider_account_id)
+                    .ok_or("The card's model is not available; choose one for the imported profile")?;
+                if let (true, Some(card_model)) = (other_provider_type, &extension.model_name) {
+                    warnings.push(format!("Using {} instead of the card's model {}", model, card_model));
+                }
+                model
+            }
+        };
+        (provider_account_id, model_name)
+    };
+
+    let persona_prompt = extension
+        .persona_prompt
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| foreign_persona(&data, &name));
+    let character_definition = match extension.character_definition.clone() {
+        Some(def) => Some(def),
+        None if panther_card => None,
+        None => serde_json::to_value(foreign_character(&data, &name)).ok(),
+    };
+    let photo_url = extension.photo.clone().or_else(|| {
+        let bytes = image.filter(|_| !extension.placeholder_image)?;
+        Some(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(bytes)))
+    });
+
+    let request = CreateProfileRequest {
+        name: name.clone(),
+        provider_account_id: provider_account_id.clone(),
+        model_name: model_name.clone(),
+        persona_prompt,
+        character_definition_json: character_definition,
+        model_features_json: extension.model_features.clone(),
+        params_json: extension.params.clone().unwrap_or(json!({})),
+        photo_url,
+        voice_gender: extension.voice_gender.clone(),
+        voice_uri: extension.voice_uri.clone(),
+    };
+    let profile_id = commands::create_profile_impl(db, request, user_id).await?;
+    Ok(CardImportResult { profile_id, name, provider_account_id, model_name, panther_card, warnings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_png_card_round_trip() {
+        let path = std::env::temp_dir().join(format!("panther-card-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('local', 'ollama', 'Ollama');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json, character_definition_json)
+                     VALUES ('p1', 'Ana Lyst', 'local', 'llama3', 'You are Ana.', '{\"temperature\":0.3}',
+                             '{\"name\":\"Ana\",\"role\":\"Analyst\",\"personality\":[\"calm\"],\"expertise\":[\"data\"],\"communication_style\":\"terse\"}');",
+            )
+            .unwrap();
+        }
+
+        let file = export(&db, "p1", CardFormat::Png).await.unwrap();
+        let png = base64::engine::general_purpose::STANDARD.decode(&file.content).unwrap();
+        let card: Value = serde_json::from_str(&extract_card(&png).unwrap().unwrap()).unwrap();
+        assert_eq!(card["spec"], CARD_SPEC);
+        assert_eq!(card["data"]["personality"], "calm");
+        assert_eq!(card["data"]["extensions"]["panther"]["provider_type"], "ollama");
+
+        let imported = import(&db, CardImportRequest { content: file.content, base64: true, provider_account_id: None, model_name: None }, None)
+            .await
+            .unwrap();
+        assert!(imported.panther_card);
+        assert_eq!((imported.provider_account_id.as_str(), imported.model_name.as_str()), ("local", "llama3"));
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            let (persona, params, photo): (String, String, Option<String>) = conn
+                .query_row(
+                    "SELECT persona_prompt, params_json, photo_url FROM prompt_profiles WHERE id = ?1",
+                    [&imported.profile_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .unwrap();
+            assert_eq!(persona, "You are Ana.");
+            assert_eq!(params, r#"{"temperature":0.3}"#);
+            assert_eq!(photo, None);
+        }
+
+        let foreign = json!({ "name": "Bob", "description": "A pirate.", "personality": "loud, cheerful", "scenario": "{{char}} meets {{user}}." });
+        let imported = import(&db, CardImportRequest { content: foreign.to_string(), base64: false, provider_account_id: None, model_name: Some("gpt-4o".to_string()) }, None)
+            .await
+            .unwrap();
+        assert!(!imported.panther_card);
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            let persona: String = conn
+                .query_row("SELECT persona_prompt FROM prompt_profiles WHERE id = ?1", [&imported.profile_id], |row| row.get(0))
+                .unwrap();
+            assert_eq!(persona, "You are Bob.\n\nA pirate.\n\nPersonality: loud, cheerful\n\nScenario: Bob meets the user.");
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_import_on_another_provider_type_uses_that_providers_model() {
+        let path = std::env::temp_dir().join(format!("panther-card-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO users (id, username, email, password_hash) VALUES ('u1', 'u1', 'u1@example.com', 'x'), ('u2', 'u2', 'u2@example.com', 'x');
+                 INSERT INTO provider_accounts (id, provider_type, display_name, user_id) VALUES ('acc', 'openai', 'OpenAI', 'u1');
+                 INSERT INTO provider_accounts (id, provider_type, display_name, user_id) VALUES ('theirs', 'openai', 'OpenAI', 'u2');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                     VALUES ('p1', 'Ana', 'acc', 'gpt-4o', 'You are Ana.', '{}');",
+            )
+            .unwrap();
+        }
+        let card = json!({
+            "spec": CARD_SPEC,
+            "data": { "name": "Lia", "extensions": { "panther": { "provider_type": "ollama", "model_name": "llama3" } } }
+        });
+        let request = |provider_account_id: Option<&str>| CardImportRequest {
+            content: card.to_string(),
+            base64: false,
+            provider_account_id: provider_account_id.map(str::to_string),
+            model_name: None,
+        };
+
+        let imported = import(&db, request(None), Some("u1".to_string())).await.unwrap();
+        assert_eq!((imported.provider_account_id.as_str(), imported.model_name.as_str()), ("acc", "gpt-4o"));
+        assert_eq!(imported.warnings.len(), 2);
+
+        let err = import(&db, request(Some("theirs")), Some("u1".to_string())).await.unwrap_err();
+        assert!(err.contains("not found"), "{}", err);
+        std::fs::remove_file(path).ok();
+    }
+}
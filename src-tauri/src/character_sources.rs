@@ -0,0 +1,594 @@
+// Character generation from several sources (web pages, local documents, pasted transcripts).
+//
+// Every source is extracted to text and given a short id (S1, S2, ...). The model is asked to cite
+// those ids for each value it produces, and the citations are kept in the definition's
+// `provenance` together with a hash of every source's text. `refresh` re-extracts the sources of
+// a saved profile and regenerates the definition only when one of them changed; sources that
+// cannot be read (e.g. a page while offline) count as unchanged.
+
+use crate::commands_import::extract_text_from_file;
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::types::{CharacterDefinition, CharacterProvenance, CharacterSource, FieldProvenance, PromptPacket, SourceRecord};
+use regex::Regex;
+use reqwest::Client;
+use rusqlite::OptionalExtension;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Per-source and total characters sent to the model
+const SOURCE_CHAR_LIMIT: usize = 6000;
+const TOTAL_CHAR_LIMIT: usize = 15000;
+const GENERATION_TIMEOUT_SECS: u64 = 120;
+
+pub type CancellationTokens = Mutex<HashMap<String, bool>>;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateFromSourcesRequest {
+    pub sources: Vec<CharacterSource>,
+    pub person_name: Option<String>,
+    pub provider_account_id: String,
+    pub model_name: String,
+    pub cancellation_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedCharacter {
+    pub character: CharacterDefinition,
+    /// Extracted text of all sources, labelled by source id
+    pub extracted_text: String,
+    /// Sources that could not be read and were left out
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshResult {
+    /// False when no source changed (or, without `force`, a source could not be read) and nothing was regenerated
+    pub regenerated: bool,
+    /// Ids of sources whose text changed
+    pub changed_sources: Vec<String>,
+    /// Ids of sources that could not be read
+    pub failed_sources: Vec<String>,
+    pub character: CharacterDefinition,
+    pub warnings: Vec<String>,
+}
+
+fn is_cancelled(tokens: Option<(&CancellationTokens, &str)>) -> bool {
+    tokens
+        .and_then(|(tokens, token)| tokens.lock().ok().map(|t| t.get(token).copied().unwrap_or(false)))
+        .unwrap_or(false)
+}
+
+fn check_cancelled(tokens: Option<(&CancellationTokens, &str)>) -> Result<(), String> {
+    if is_cancelled(tokens) {
+        Err("Generation cancelled by user".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn http_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn html_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let body_selector = Selector::parse("body").unwrap();
+    let body_text = document
+        .select(&body_selector)
+        .next()
+        .map(|body| body.text().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default();
+    let re = Regex::new(r"\s+").unwrap();
+    re.replace_all(&body_text, " ").trim().to_string()
+}
+
+/// Text of one source
+pub async fn extract(client: &Client, source: &CharacterSource) -> Result<String, String> {
+    let text = match source {
+        CharacterSource::Url { url } => {
+            let response = client.get(url).send().await.map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+            if !response.status().is_success() {
+                return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()));
+            }
+            let html = response.text().await.map_err(|e| format!("Failed to read {}: {}", url, e))?;
+            html_text(&html)
+        }
+        CharacterSource::Document { path } => {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || extract_text_from_file(&path))
+                .await
+                .map_err(|e| format!("Document extraction failed: {}", e))??
+        }
+        CharacterSource::Transcript { text, .. } => text.trim().to_string(),
+    };
+    if text.trim().is_empty() {
+        return Err(format!("No text found in {}", label(source)));
+    }
+    Ok(text)
+}
+
+/// Local documents are read from this machine's disk, which only the desktop app may do; the
+/// HTTP API is reachable from the network and must not turn into a file reader
+pub fn reject_local_documents(sources: &[CharacterSource]) -> Result<(), String> {
+    match sources.iter().find(|s| matches!(s, CharacterSource::Document { .. })) {
+        Some(source) => Err(format!("Local documents cannot be read over the HTTP API: {}", label(source))),
+        None => Ok(()),
+    }
+}
+
+pub fn label(source: &CharacterSource) -> String {
+    match source {
+        CharacterSource::Url { url } => url.clone(),
+        CharacterSource::Document { path } => std::path::Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone()),
+        CharacterSource::Transcript { label, .. } => {
+            label.clone().filter(|l| !l.trim().is_empty()).unwrap_or_else(|| "Pasted transcript".to_string())
+        }
+    }
+}
+
+fn hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn truncate_chars(text: &str, limit: usize) -> &str {
+    text.char_indices().nth(limit).map(|(i, _)| &text[..i]).unwrap_or(text)
+}
+
+/// Extract every source; unreadable ones are reported as warnings
+async fn collect(
+    sources: &[CharacterSource],
+    tokens: Option<(&CancellationTokens, &str)>,
+) -> Result<(Vec<(SourceRecord, String)>, Vec<String>), String> {
+    let client = http_client()?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut extracted = Vec::new();
+    let mut warnings = Vec::new();
+    for (index, source) in sources.iter().enumerate() {
+        check_cancelled(tokens)?;
+        match extract(&client, source).await {
+            Ok(text) => {
+                let record = SourceRecord {
+                    id: format!("S{}", index + 1),
+                    source: source.clone(),
+                    content_hash: hash(&text),
+                    chars: text.chars().count(),
+                    extracted_at: now.clone(),
+                };
+                extracted.push((record, text));
+            }
+            Err(e) => warnings.push(e),
+        }
+    }
+    Ok((extracted, warnings))
+}
+
+fn combined_text(extracted: &[(SourceRecord, String)]) -> String {
+    extracted
+        .iter()
+        .map(|(record, text)| {
+            format!("=== [{}] {} ===\n{}\n", record.id, label(&record.source), truncate_chars(text, SOURCE_CHAR_LIMIT))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn analysis_prompt(person_name: Option<&str>, source_count: usize, text: &str) -> String {
+    let name_instruction = person_name
+        .map(|name| format!("\n\nIMPORTANT: The person you are analyzing is named \"{0}\". Focus on information about THIS SPECIFIC PERSON. If multiple people are mentioned, prioritize information about {0}.", name))
+        .unwrap_or_default();
+    format!(
+        "You are an expert at analyzing professional profiles and extracting EXACT character information. Build a character definition from the {} source(s) below. Each source is labelled with an id such as [S1].{}\n\n\
+        CRITICAL INSTRUCTIONS:\n\
+        - Extract information EXACTLY as it appears in the sources\n\
+        - Do NOT invent or assume information that is not present\n\
+        - For every value, list the ids of the sources that support it in \"sources\"\n\
+        - Leave arrays empty rather than guessing\n\n\
+        Extract: name; role (professional role or title); personality (3-7 traits); expertise (3-10 areas); communication_style; background (2-4 sentences); goals (0-5); constraints (values or limits, optional).\n\n\
+        Sources:\n{}\n\n\
+        Return ONLY a valid JSON object with this exact structure:\n\
+        {{\n\
+          \"name\": \"exact name\",\n\
+          \"role\": {{\"value\": \"role/title\", \"sources\": [\"S1\"]}},\n\
+          \"personality\": [{{\"value\": \"trait\", \"sources\": [\"S1\", \"S2\"]}}],\n\
+          \"expertise\": [{{\"value\": \"area\", \"sources\": [\"S2\"]}}],\n\
+          \"communication_style\": {{\"value\": \"description\", \"sources\": [\"S1\"]}},\n\
+          \"background\": {{\"value\": \"background\", \"sources\": [\"S1\"]}},\n\
+          \"goals\": [{{\"value\": \"goal\", \"sources\": [\"S3\"]}}],\n\
+          \"constraints\": [{{\"value\": \"constraint\", \"sources\": [\"S3\"]}}]\n\
+        }}",
+        source_count,
+        name_instruction,
+        truncate_chars(text, TOTAL_CHAR_LIMIT)
+    )
+}
+
+/// JSON object in a model reply, with or without code fences
+pub fn parse_reply_json(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if let Ok(json) = serde_json::from_str::<Value>(text) {
+        return Ok(json);
+    }
+    let json_str = if let Some(start) = text.find("```json").map(|i| i + 7).or_else(|| text.find("```").map(|i| i + 3)) {
+        let end = text[start..].find("```").map(|i| start + i).unwrap_or(text.len());
+        text[start..end].trim()
+    } else {
+        let json_start = text.find('{').unwrap_or(0);
+        let json_end = text.rfind('}').map(|i| i + 1).unwrap_or(text.len());
+        &text[json_start..json_end.max(json_start)]
+    };
+    serde_json::from_str(json_str).map_err(|e| {
+        format!(
+            "Failed to parse character definition JSON: {}\n\nFull response: {}",
+            e,
+            truncate_chars(text, 500)
+        )
+    })
+}
+
+/// A cited value: `{"value", "sources"}` or a bare string (no citation)
+fn cited(value: &Value, known: &[&str]) -> Option<(String, Vec<String>)> {
+    let (text, sources) = match value {
+        Value::String(s) => (s.clone(), Vec::new()),
+        Value::Object(o) => (
+            o.get("value").and_then(|v| v.as_str())?.to_string(),
+            o.get("sources")
+                .and_then(|v| v.as_array())
+                .map(|ids| ids.iter().filter_map(|id| id.as_str()).map(|id| id.trim().trim_matches(['[', ']']).to_string()).collect())
+                .unwrap_or_default(),
+        ),
+        _ => return None,
+    };
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    // Drop citations of sources that do not exist
+    let mut sources: Vec<String> = sources.into_iter().filter(|id| known.contains(&id.as_str())).collect();
+    sources.dedup();
+    Some((text, sources))
+}
+
+/// Definition and per-value provenance from the model's JSON
+pub fn definition_from_reply(reply: &Value, known: &[&str]) -> (CharacterDefinition, Vec<FieldProvenance>) {
+    let mut fields = Vec::new();
+    let single = |key: &str, fields: &mut Vec<FieldProvenance>| -> Option<String> {
+        let (value, sources) = cited(reply.get(key)?, known)?;
+        fields.push(FieldProvenance { field: key.to_string(), value: value.clone(), sources });
+        Some(value)
+    };
+    let role = single("role", &mut fields);
+    let communication_style = single("communication_style", &mut fields);
+    let background = single("background", &mut fields);
+    let mut list = |key: &str| -> Vec<String> {
+        let items = reply.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        items
+            .iter()
+            .filter_map(|item| cited(item, known))
+            .map(|(value, sources)| {
+                fields.push(FieldProvenance { field: key.to_string(), value: value.clone(), sources });
+                value
+            })
+            .collect()
+    };
+    let personality = list("personality");
+    let expertise = list("expertise");
+    let goals = list("goals");
+    let constraints = list("constraints");
+    let name = match reply.get("name") {
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Object(o)) => o.get("value").and_then(|v| v.as_str()).unwrap_or_default().trim().to_string(),
+        _ => String::new(),
+    };
+    let character = CharacterDefinition {
+        name,
+        role: role.unwrap_or_else(|| "Unknown".to_string()),
+        personality,
+        expertise,
+        communication_style: communication_style.unwrap_or_else(|| "Professional".to_string()),
+        background,
+        goals: (!goals.is_empty()).then_some(goals),
+        constraints: (!constraints.is_empty()).then_some(constraints),
+        provenance: None,
+    };
+    (character, fields)
+}
+
+async fn generate_from_extracted(
+    db: &Database,
+    extracted: Vec<(SourceRecord, String)>,
+    person_name: Option<String>,
+    provider_account_id: &str,
+    model_name: &str,
+    tokens: Option<(&CancellationTokens, &str)>,
+) -> Result<(CharacterDefinition, String), String> {
+    if extracted.is_empty() {
+        return Err("No text content found in any of the provided sources".to_string());
+    }
+    let text = combined_text(&extracted);
+    check_cancelled(tokens)?;
+
+    let packet = PromptPacket {
+        global_instructions: None,
+        persona_instructions: "You are an expert at analyzing professional profiles and extracting structured character information. You MUST return ONLY valid JSON with no markdown formatting, no code blocks, no explanations, and no additional text. The response must be a valid JSON object that can be parsed directly.".to_string(),
+        user_message: analysis_prompt(person_name.as_deref(), extracted.len(), &text),
+        conversation_context: None,
+        params_json: json!({ "temperature": 0.3, "max_tokens": 2500 }),
+        stream: false,
+    };
+    let (response, _used_provider, _used_model) =
+        complete_resolving_hybrid(db, provider_account_id, model_name, &packet, GENERATION_TIMEOUT_SECS, None)
+            .await
+            .map_err(|e| format!("LLM analysis failed: {}", e))?;
+    check_cancelled(tokens)?;
+
+    let reply = parse_reply_json(&response.text)?;
+    let known: Vec<&str> = extracted.iter().map(|(record, _)| record.id.as_str()).collect();
+    let (mut character, fields) = definition_from_reply(&reply, &known);
+    if character.name.is_empty() || character.name == "Unknown" {
+        match person_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            Some(name) => character.name = name.to_string(),
+            None => {
+                return Err(format!(
+                    "Character definition is missing required 'name' field. Received JSON: {}",
+                    serde_json::to_string_pretty(&reply).unwrap_or_else(|_| "Invalid JSON".to_string())
+                ))
+            }
+        }
+    }
+    character.provenance = Some(CharacterProvenance {
+        sources: extracted.into_iter().map(|(record, _)| record).collect(),
+        fields,
+        person_name,
+        provider_account_id: provider_account_id.to_string(),
+        model_name: model_name.to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    });
+    Ok((character, text))
+}
+
+/// Generate a character definition from web pages, documents and transcripts
+pub async fn generate(
+    db: &Database,
+    request: GenerateFromSourcesRequest,
+    tokens: Option<&CancellationTokens>,
+) -> Result<GeneratedCharacter, String> {
+    if request.sources.is_empty() {
+        return Err("Add at least one source".to_string());
+    }
+    let token = request.cancellation_token.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    if let Some(tokens) = tokens {
+        tokens.lock().map_err(|e| format!("Failed to lock cancellation tokens: {}", e))?.insert(token.clone(), false);
+    }
+    let cancel = tokens.map(|t| (t, token.as_str()));
+
+    let result = async {
+        let (extracted, warnings) = collect(&request.sources, cancel).await?;
+        let (character, extracted_text) = generate_from_extracted(
+            db,
+            extracted,
+            request.person_name.clone().filter(|n| !n.trim().is_empty()),
+            &request.provider_account_id,
+            &request.model_name,
+            cancel,
+        )
+        .await?;
+        Ok(GeneratedCharacter { character, extracted_text, warnings })
+    }
+    .await;
+
+    if let Some(tokens) = tokens {
+        if let Ok(mut tokens) = tokens.lock() {
+            tokens.remove(&token);
+        }
+    }
+    result
+}
+
+/// Regenerate a saved profile's definition when any of its sources changed (or always, with `force`).
+/// A source that cannot be read blocks regeneration unless `force`, which regenerates without it.
+/// Without `allow_documents` (HTTP callers) local document sources count as unreadable.
+pub async fn refresh(db: &Database, profile_id: &str, force: bool, allow_documents: bool) -> Result<RefreshResult, String> {
+    let (definition_json, profile_provider, profile_model) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .query_row(
+                "SELECT character_definition_json, provider_account_id, model_name FROM prompt_profiles WHERE id = ?1",
+                [profile_id],
+                |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| "Profile not found".to_string())?
+    };
+    let current: CharacterDefinition = definition_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .ok_or_else(|| "This profile has no character definition".to_string())?;
+    let provenance = current
+        .provenance
+        .clone()
+        .ok_or_else(|| "This character was not generated from sources, so it cannot be refreshed".to_string())?;
+
+    let client = http_client()?;
+    let mut warnings = Vec::new();
+    let mut changed_sources = Vec::new();
+    let mut failed_sources = Vec::new();
+    let mut extracted = Vec::new();
+    let now = chrono::Utc::now().to_rfc3339();
+    for record in &provenance.sources {
+        let extracted_text = if allow_documents {
+            extract(&client, &record.source).await
+        } else {
+            match reject_local_documents(std::slice::from_ref(&record.source)) {
+                Ok(()) => extract(&client, &record.source).await,
+                Err(e) => Err(e),
+            }
+        };
+        match extracted_text {
+            Ok(text) => {
+                let content_hash = hash(&text);
+                if content_hash != record.content_hash {
+                    changed_sources.push(record.id.clone());
+                }
+                let refreshed = SourceRecord {
+                    content_hash,
+                    chars: text.chars().count(),
+                    extracted_at: now.clone(),
+                    ..record.clone()
+                };
+                extracted.push((refreshed, text));
+            }
+            Err(e) => {
+                failed_sources.push(record.id.clone());
+                warnings.push(if force {
+                    format!("{} could not be read and was left out of the regenerated character: {}", record.id, e)
+                } else {
+                    format!("{} could not be read: {}", record.id, e)
+                });
+            }
+        }
+    }
+    if !force && (changed_sources.is_empty() || !failed_sources.is_empty()) {
+        return Ok(RefreshResult { regenerated: false, changed_sources, failed_sources, character: current, warnings });
+    }
+
+    // The generating provider may be gone (or blank in imported cards); fall back to the profile's
+    let provider_exists = !provenance.provider_account_id.is_empty() && {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .query_row("SELECT 1 FROM provider_accounts WHERE id = ?1", [&provenance.provider_account_id], |_| Ok(()))
+            .optional()
+            .map_err(|e| format!("Database error: {}", e))?
+            .is_some()
+    };
+    let (provider_account_id, model_name) = if provider_exists {
+        (provenance.provider_account_id.clone(), provenance.model_name.clone())
+    } else {
+        (profile_provider, profile_model)
+    };
+    let (character, _) =
+        generate_from_extracted(db, extracted, provenance.person_name.clone(), &provider_account_id, &model_name, None).await?;
+
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute(
+            "UPDATE prompt_profiles SET character_definition_json = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![
+                serde_json::to_string(&character).map_err(|e| format!("Failed to serialize character: {}", e))?,
+                now,
+                profile_id
+            ],
+        )
+        .map_err(|e| format!("Failed to save character: {}", e))?;
+    Ok(RefreshResult { regenerated: true, changed_sources, failed_sources, character, warnings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance_keeps_only_known_sources() {
+        let reply = json!({
+            "name": "Ana",
+            "role": { "value": "Data engineer", "sources": ["S1"] },
+            "personality": ["curious", { "value": "patient", "sources": ["[S2]", "S9"] }],
+            "expertise": [{ "value": "Rust", "sources": ["S1", "S2"] }, { "value": " ", "sources": ["S1"] }],
+            "communication_style": "Direct",
+            "goals": []
+        });
+        let (character, fields) = definition_from_reply(&reply, &["S1", "S2"]);
+        assert_eq!(character.role, "Data engineer");
+        assert_eq!(character.personality, ["curious", "patient"]);
+        assert_eq!(character.expertise, ["Rust"]);
+        assert_eq!(character.goals, None);
+
+        let cited: Vec<(&str, &str, Vec<String>)> =
+            fields.iter().map(|f| (f.field.as_str(), f.value.as_str(), f.sources.clone())).collect();
+        assert!(cited.contains(&("personality", "patient", vec!["S2".to_string()])));
+        assert!(cited.contains(&("expertise", "Rust", vec!["S1".to_string(), "S2".to_string()])));
+        assert!(cited.contains(&("communication_style", "Direct", vec![])));
+    }
+
+    #[test]
+    fn test_http_callers_cannot_read_local_documents() {
+        let transcript = CharacterSource::Transcript { label: None, text: "hi".to_string() };
+        assert!(reject_local_documents(std::slice::from_ref(&transcript)).is_ok());
+        let sources = [transcript, CharacterSource::Document { path: "/etc/passwd".to_string() }];
+        assert!(reject_local_documents(&sources).unwrap_err().contains("passwd"));
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_source_blocks_refresh_without_force() {
+        let path = std::env::temp_dir().join(format!("panther-character-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        let record = |id: &str, source: CharacterSource| SourceRecord {
+            id: id.to_string(),
+            source,
+            content_hash: "stale".to_string(),
+            chars: 0,
+            extracted_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let character = CharacterDefinition {
+            name: "Ana".to_string(),
+            role: "Data engineer".to_string(),
+            personality: vec![],
+            expertise: vec![],
+            communication_style: "Direct".to_string(),
+            background: None,
+            goals: None,
+            constraints: None,
+            provenance: Some(CharacterProvenance {
+                sources: vec![
+                    record("S1", CharacterSource::Transcript { label: None, text: "Ana now leads the platform team".to_string() }),
+                    record("S2", CharacterSource::Document { path: "/nonexistent/panther/ana.pdf".to_string() }),
+                ],
+                fields: vec![],
+                person_name: Some("Ana".to_string()),
+                provider_account_id: "acc".to_string(),
+                model_name: "gpt".to_string(),
+                generated_at: "2026-01-01T00:00:00Z".to_string(),
+            }),
+        };
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch("INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');").unwrap();
+            conn.execute(
+                "INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json, character_definition_json)
+                 VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}', ?1)",
+                [serde_json::to_string(&character).unwrap()],
+            )
+            .unwrap();
+        }
+
+        let result = refresh(&db, "p1", false, true).await.unwrap();
+        assert!(!result.regenerated);
+        assert_eq!(result.changed_sources, ["S1"]);
+        assert_eq!(result.failed_sources, ["S2"]);
+        assert!(result.warnings[0].starts_with("S2 could not be read"), "{:?}", result.warnings);
+        let stored: String = db
+            .get_connection()
+            .lock()
+            .unwrap()
+            .query_row("SELECT character_definition_json FROM prompt_profiles WHERE id = 'p1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, serde_json::to_string(&character).unwrap());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,380 @@
+// Branching profile conversations
+//
+// chat_messages form a tree through `parent_message_id`: editing a user message or regenerating a
+// reply adds a sibling instead of overwriting the original. `profile_conversations.active_message_id`
+// (`profile_chat_state` for a profile's default chat) marks the selected message; the active branch
+// is its path from the root, continued through the newest child at each level. Only the active
+// branch is shown and sent to the model as context.
+
+use crate::types::Message;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const PREVIEW_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatNode {
+    pub id: String,
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+    pub parent_message_id: Option<String>,
+    /// Alternatives at this position (including this message), oldest first.
+    /// Only filled for messages returned by `active_path`.
+    pub sibling_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchOption {
+    pub id: String,
+    pub role: String,
+    pub preview: String,
+    pub created_at: String,
+}
+
+/// A position on the active branch with more than one alternative
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchPoint {
+    /// Message the alternatives follow (None for alternative opening messages)
+    pub parent_message_id: Option<String>,
+    pub options: Vec<BranchOption>,
+    /// Index of the option on the active branch
+    pub active_index: usize,
+}
+
+struct Tree {
+    nodes: HashMap<String, ChatNode>,
+    /// Ids in creation order
+    order: Vec<String>,
+    children: HashMap<Option<String>, Vec<String>>,
+}
+
+impl Tree {
+    fn load(conn: &Connection, profile_id: &str, conversation_id: Option<&str>) -> Result<Self, String> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, role, content, created_at, parent_message_id FROM chat_messages
+                 WHERE profile_id = ?1 AND COALESCE(conversation_id, '') = COALESCE(?2, '')
+                 ORDER BY created_at, rowid",
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![profile_id, conversation_id], |row| {
+                Ok(ChatNode {
+                    id: row.get(0)?,
+                    role: row.get(1)?,
+                    content: row.get(2)?,
+                    created_at: row.get(3)?,
+                    parent_message_id: row.get(4)?,
+                    sibling_ids: Vec::new(),
+                })
+            })
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        let mut tree = Tree { nodes: HashMap::new(), order: Vec::new(), children: HashMap::new() };
+        for row in rows {
+            let node = row.map_err(|e| format!("Row error: {}", e))?;
+            tree.order.push(node.id.clone());
+            tree.nodes.insert(node.id.clone(), node);
+        }
+        for id in &tree.order {
+            let parent = tree.parent_of(id);
+            tree.children.entry(parent).or_default().push(id.clone());
+        }
+        Ok(tree)
+    }
+
+    /// Parent id, treating messages whose parent was deleted as roots
+    fn parent_of(&self, id: &str) -> Option<String> {
+        self.nodes
+            .get(id)
+            .and_then(|n| n.parent_message_id.clone())
+            .filter(|p| self.nodes.contains_key(p))
+    }
+
+    fn siblings(&self, id: &str) -> &[String] {
+        self.children.get(&self.parent_of(id)).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Root-to-message ids
+    fn path_to(&self, id: &str) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut current = Some(id.to_string()).filter(|id| self.nodes.contains_key(id));
+        while let Some(id) = current {
+            // Guard against cycles from hand-edited data
+            if path.len() > self.order.len() {
+                break;
+            }
+            current = self.parent_of(&id);
+            path.push(id);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Path through `selected` (or the newest message), extended through the newest children
+    fn active_ids(&self, selected: Option<&str>) -> Vec<String> {
+        let start = selected
+            .filter(|id| self.nodes.contains_key(*id))
+            .map(str::to_string)
+            .or_else(|| self.order.last().cloned());
+        let Some(start) = start else { return Vec::new() };
+        let mut path = self.path_to(&start);
+        let mut current = start;
+        while let Some(next) = self.children.get(&Some(current.clone())).and_then(|c| c.last()) {
+            if path.len() > self.order.len() {
+                break;
+            }
+            path.push(next.clone());
+            current = next.clone();
+        }
+        path
+    }
+}
+
+fn selected_message(conn: &Connection, profile_id: &str, conversation_id: Option<&str>) -> Result<Option<String>, String> {
+    let selected = match conversation_id {
+        Some(conversation_id) => conn.query_row(
+            "SELECT active_message_id FROM profile_conversations WHERE id = ?1",
+            [conversation_id],
+            |row| row.get::<_, Option<String>>(0),
+        ),
+        None => conn.query_row(
+            "SELECT active_message_id FROM profile_chat_state WHERE profile_id = ?1",
+            [profile_id],
+            |row| row.get::<_, Option<String>>(0),
+        ),
+    };
+    selected
+        .optional()
+    .map(Option::flatten)
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+/// Messages on the active branch, oldest first
+pub fn active_path(conn: &Connection, profile_id: &str, conversation_id: Option<&str>) -> Result<Vec<ChatNode>, String> {
+    let tree = Tree::load(conn, profile_id, conversation_id)?;
+    let selected = selected_message(conn, profile_id, conversation_id)?;
+    Ok(tree
+        .active_ids(selected.as_deref())
+        .iter()
+        .filter_map(|id| {
+            let mut node = tree.nodes.get(id)?.clone();
+            node.sibling_ids = tree.siblings(id).to_vec();
+            Some(node)
+        })
+        .collect())
+}
+
+/// Forks along the active branch
+pub fn branch_points(conn: &Connection, profile_id: &str, conversation_id: Option<&str>) -> Result<Vec<BranchPoint>, String> {
+    let tree = Tree::load(conn, profile_id, conversation_id)?;
+    let selected = selected_message(conn, profile_id, conversation_id)?;
+    let mut points = Vec::new();
+    for id in tree.active_ids(selected.as_deref()) {
+        let siblings = tree.siblings(&id);
+        if siblings.len() < 2 {
+            continue;
+        }
+        let options = siblings
+            .iter()
+            .filter_map(|s| tree.nodes.get(s))
+            .map(|n| BranchOption {
+                id: n.id.clone(),
+                role: n.role.clone(),
+                preview: n.content.chars().take(PREVIEW_CHARS).collect(),
+                created_at: n.created_at.clone(),
+            })
+            .collect();
+        points.push(BranchPoint {
+            parent_message_id: tree.parent_of(&id),
+            options,
+            active_index: siblings.iter().position(|s| *s == id).unwrap_or(0),
+        });
+    }
+    Ok(points)
+}
+
+/// Make the branch through `message_id` the active one of its conversation (None: the default chat)
+pub fn select(conn: &Connection, conversation_id: Option<&str>, message_id: &str) -> Result<(), String> {
+    let profile_id: String = conn
+        .query_row(
+            "SELECT profile_id FROM chat_messages WHERE id = ?1 AND COALESCE(conversation_id, '') = COALESCE(?2, '')",
+            rusqlite::params![message_id, conversation_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Message not found in this conversation".to_string())?;
+    match conversation_id {
+        Some(conversation_id) => conn.execute(
+            "UPDATE profile_conversations SET active_message_id = ?1 WHERE id = ?2",
+            rusqlite::params![message_id, conversation_id],
+        ),
+        None => conn.execute(
+            "INSERT INTO profile_chat_state (profile_id, active_message_id) VALUES (?1, ?2)
+             ON CONFLICT(profile_id) DO UPDATE SET active_message_id = excluded.active_message_id",
+            rusqlite::params![profile_id, message_id],
+        ),
+    }
+    .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// A stored message with its parent, for edits and regenerations
+pub fn get_message(conn: &Connection, message_id: &str) -> Result<ChatNode, String> {
+    conn.query_row(
+        "SELECT id, role, content, created_at, parent_message_id FROM chat_messages WHERE id = ?1",
+        [message_id],
+        |row| {
+            Ok(ChatNode {
+                id: row.get(0)?,
+                role: row.get(1)?,
+                content: row.get(2)?,
+                created_at: row.get(3)?,
+                parent_message_id: row.get(4)?,
+                sibling_ids: Vec::new(),
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| "Message not found".to_string())
+}
+
+/// Newest message on the active branch; new turns are attached below it
+pub fn active_leaf(conn: &Connection, profile_id: &str, conversation_id: Option<&str>) -> Result<Option<String>, String> {
+    let tree = Tree::load(conn, profile_id, conversation_id)?;
+    let selected = selected_message(conn, profile_id, conversation_id)?;
+    Ok(tree.active_ids(selected.as_deref()).pop())
+}
+
+/// Model context for a turn attached below `anchor`: the root-to-anchor path
+pub fn context_for(
+    conn: &Connection,
+    profile_id: &str,
+    conversation_id: Option<&str>,
+    anchor: Option<&str>,
+) -> Result<Vec<Message>, String> {
+    let Some(anchor) = anchor else { return Ok(Vec::new()) };
+    let tree = Tree::load(conn, profile_id, conversation_id)?;
+    let run_id = conversation_id.map(str::to_string).unwrap_or_else(|| format!("chat-{}", profile_id));
+    Ok(tree
+        .path_to(anchor)
+        .iter()
+        .filter_map(|id| tree.nodes.get(id))
+        .map(|n| Message {
+            id: n.id.clone(),
+            run_id: run_id.clone(),
+            author_type: if n.role == "user" { "user".to_string() } else { "assistant".to_string() },
+            profile_id: if n.role == "user" { None } else { Some(profile_id.to_string()) },
+            round_index: None,
+            turn_index: None,
+            text: n.content.clone(),
+            created_at: n.created_at.clone(),
+            provider_metadata_json: None,
+        })
+        .collect())
+}
+
+/// Store a message below `parent` and make it the active branch's tip
+pub fn append(
+    conn: &Connection,
+    profile_id: &str,
+    conversation_id: Option<&str>,
+    parent: Option<&str>,
+    role: &str,
+    content: &str,
+    created_at: &str,
+) -> Result<String, String> {
+    append_as(conn, profile_id, conversation_id, parent, role, content, created_at, None)
+}
+
+/// `append`, recording the user the message belongs to
+#[allow(clippy::too_many_arguments)]
+pub fn append_as(
+    conn: &Connection,
+    profile_id: &str,
+    conversation_id: Option<&str>,
+    parent: Option<&str>,
+    role: &str,
+    content: &str,
+    created_at: &str,
+    user_id: Option<&str>,
+) -> Result<String, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO chat_messages (id, profile_id, role, content, created_at, conversation_id, parent_message_id, user_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![id, profile_id, role, content, created_at, conversation_id, parent, user_id],
+    )
+    .map_err(|e| format!("Failed to save {} message: {}", role, e))?;
+    select(conn, conversation_id, &id)?;
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edits_and_regenerations_branch() {
+        let db_path = std::env::temp_dir().join(format!("panther-branches-{}.db", uuid::Uuid::new_v4()));
+        let db = crate::db::Database::new(db_path.clone()).unwrap();
+        let conn = db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute_batch(
+            "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+             INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                 VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}');
+             INSERT INTO profile_conversations (id, profile_id, title) VALUES ('c1', 'p1', 'Chat');",
+        )
+        .unwrap();
+        let add = |parent: Option<&str>, role: &str, text: &str, at: &str| {
+            append(&conn, "p1", Some("c1"), parent, role, text, at).unwrap()
+        };
+        let u1 = add(None, "user", "Hi", "2026-01-01T00:00:01Z");
+        let a1 = add(Some(&u1), "assistant", "Hello", "2026-01-01T00:00:02Z");
+        let u2 = add(Some(&a1), "user", "Tea?", "2026-01-01T00:00:03Z");
+        let _a2 = add(Some(&u2), "assistant", "Green", "2026-01-01T00:00:04Z");
+        // Edit the second question, then regenerate the first reply
+        let u2b = add(Some(&a1), "user", "Coffee?", "2026-01-01T00:00:05Z");
+        let a2b = add(Some(&u2b), "assistant", "Espresso", "2026-01-01T00:00:06Z");
+        let a1b = add(Some(&u1), "assistant", "Hey", "2026-01-01T00:00:07Z");
+
+        let texts = |path: Vec<ChatNode>| path.into_iter().map(|n| n.content).collect::<Vec<_>>();
+        assert_eq!(texts(active_path(&conn, "p1", Some("c1")).unwrap()), ["Hi", "Hey"]);
+
+        // Switching back to the first reply follows its newest descendants
+        select(&conn, Some("c1"), &a1).unwrap();
+        let path = active_path(&conn, "p1", Some("c1")).unwrap();
+        assert_eq!(texts(path.clone()), ["Hi", "Hello", "Coffee?", "Espresso"]);
+        assert_eq!(path[1].sibling_ids, vec![a1.clone(), a1b]);
+        assert_eq!(path[2].sibling_ids, vec![u2.clone(), u2b]);
+
+        let points = branch_points(&conn, "p1", Some("c1")).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].parent_message_id.as_deref(), Some(a1.as_str()));
+        assert_eq!(points[1].active_index, 1);
+
+        let context = context_for(&conn, "p1", Some("c1"), Some(&a2b)).unwrap();
+        assert_eq!(context.iter().map(|m| m.author_type.as_str()).collect::<Vec<_>>(), ["user", "assistant", "user", "assistant"]);
+        assert!(!context.iter().any(|m| m.text == "Tea?"));
+
+        select(&conn, Some("c1"), &u2).unwrap();
+        assert_eq!(active_leaf(&conn, "p1", Some("c1")).unwrap(), Some(_a2.clone()));
+        assert!(select(&conn, Some("c1"), "missing").is_err());
+
+        // The default chat keeps its own selection
+        let d1 = append(&conn, "p1", None, None, "user", "Default", "2026-01-01T00:00:08Z").unwrap();
+        let d2 = append(&conn, "p1", None, Some(&d1), "assistant", "One", "2026-01-01T00:00:09Z").unwrap();
+        let _d2b = append(&conn, "p1", None, Some(&d1), "assistant", "Two", "2026-01-01T00:00:10Z").unwrap();
+        assert!(select(&conn, None, &u1).is_err());
+        select(&conn, None, &d2).unwrap();
+        assert_eq!(texts(active_path(&conn, "p1", None).unwrap()), ["Default", "One"]);
+        assert_eq!(active_leaf(&conn, "p1", Some("c1")).unwrap(), Some(_a2));
+        drop(conn);
+        std::fs::remove_file(db_path).ok();
+    }
+}
@@ -1,24 +1,44 @@
 // Cline agent execution loop with tool approval
+//
+// Observe-act loop: each iteration asks the model for its next tool requests, stores them in
+// `cline_tool_executions` (tagged with the iteration), runs the auto-approved ones, waits for the
+// user to approve or reject the rest through `cline_approve_tool`, and feeds the results back as
+// the next user message. The run ends when the model sets `"done": true` or stops requesting tools,
+// when the iteration or token budget is spent, or when the run is cancelled.
 
 use crate::db::Database;
 use crate::providers::get_adapter;
 use crate::ProviderAccount;
+use crate::tools::ToolResult;
 use crate::types::{PromptPacket, Message};
 use crate::cline::tools::ClineToolRequest;
-use crate::cline::checkpoints::create_checkpoint;
+use crate::cline::checkpoints::{checkpoint_before_tool, create_checkpoint};
 use crate::cline::context_builder::ContextBuilder;
+use crate::cline::error_monitor::{self, ErrorMonitor, LinterError};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 use chrono::Utc;
 
+/// Tool output kept in the observation sent back to the model
+const MAX_RESULT_CHARS: usize = 4000;
+const APPROVAL_POLL_MS: u64 = 500;
+/// Diagnostics listed to the model per iteration
+const MAX_DIAGNOSTICS: usize = 30;
+/// Tools whose success changes files the linters and compilers look at
+const WRITE_TOOLS: &[&str] = &["workspace_write", "system_file_write", "file_delete"];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClineTaskResult {
     pub run_id: String,
     pub status: String,
     pub summary: String,
     pub tool_executions: Vec<ToolExecution>,
+    pub iterations: u32,
+    pub total_tokens: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,11 +49,62 @@ pub struct ToolExecution {
     pub tool_params: Value,
     pub approval_status: String,
     pub result: Option<Value>,
+    #[serde(default)]
+    pub iteration: u32,
+}
+
+/// Limits and approval policy for one agent run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClineLoopConfig {
+    /// Model calls before the run stops with `limit_reached`
+    pub max_iterations: u32,
+    /// Tokens reported by the provider across all iterations; None for no budget
+    pub max_total_tokens: Option<u64>,
+    /// Tool types executed without asking, e.g. "workspace_read" or "search_code"
+    pub auto_approve: Vec<String>,
+    /// Tools still pending after this long are rejected so the loop can move on
+    pub approval_timeout_secs: u64,
+    /// Run compilers/linters after file changes and report new problems to the model
+    pub check_diagnostics: bool,
+    /// Quiet period after the last write before diagnostics run
+    pub diagnostics_debounce_ms: u64,
+}
+
+impl Default for ClineLoopConfig {
+    fn default() -> Self {
+        ClineLoopConfig {
+            max_iterations: 10,
+            max_total_tokens: Some(200_000),
+            auto_approve: Vec::new(),
+            approval_timeout_secs: 600,
+            check_diagnostics: true,
+            diagnostics_debounce_ms: 1500,
+        }
+    }
 }
 
+/// Progress event emitted to the UI as `panther://cline_progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct ClineProgress {
+    pub run_id: String,
+    pub iteration: u32,
+    /// thinking, awaiting_approval, tool_result, diagnostics, iteration_complete, complete,
+    /// limit_reached, cancelled or failed
+    pub stage: String,
+    pub message: String,
+    pub tool_executions: Vec<ToolExecution>,
+    pub total_tokens: u64,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(ClineProgress) + Send + Sync>;
+
 pub struct ClineAgentLoop {
     db: Database,
     workspace_path: PathBuf,
+    config: ClineLoopConfig,
+    run_id: Option<String>,
+    on_progress: Option<ProgressCallback>,
 }
 
 impl ClineAgentLoop {
@@ -41,6 +112,38 @@ impl ClineAgentLoop {
         ClineAgentLoop {
             db,
             workspace_path,
+            config: ClineLoopConfig::default(),
+            run_id: None,
+            on_progress: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: ClineLoopConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Use a run id chosen by the caller so it can match progress events before the task returns
+    pub fn with_run_id(mut self, run_id: Option<String>) -> Self {
+        self.run_id = run_id;
+        self
+    }
+
+    pub fn with_progress(mut self, on_progress: ProgressCallback) -> Self {
+        self.on_progress = Some(on_progress);
+        self
+    }
+
+    fn emit(&self, run_id: &str, iteration: u32, stage: &str, message: String, tool_executions: Vec<ToolExecution>, total_tokens: u64) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(ClineProgress {
+                run_id: run_id.to_string(),
+                iteration,
+                stage: stage.to_string(),
+                message,
+                tool_executions,
+                total_tokens,
+            });
         }
     }
     
@@ -52,7 +155,7 @@ impl ClineAgentLoop {
         model_name: String,
         conversation_context: Option<Vec<serde_json::Value>>,
     ) -> Result<ClineTaskResult, String> {
-        let run_id = Uuid::new_v4().to_string();
+        let run_id = self.run_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let now = Utc::now().to_rfc3339();
         
         // Create run record
@@ -108,13 +211,16 @@ impl ClineAgentLoop {
         });
         eprintln!("💾 Checkpoint task spawned, continuing with LLM request...");
         
+        // Tools of configured MCP servers become `mcp_call` requests
+        let mcp_tools = describe_mcp_tools(&crate::mcp::tool_catalog().await);
+        
         // Build system prompt for Cline agent - request structured JSON with tool calls
         let system_prompt = format!(
             "You are Cline, an advanced AI coding assistant with FULL SYSTEM ACCESS and powerful tools.\n\
             CRITICAL: You MUST respond with ONLY valid JSON. NO markdown, NO code blocks, NO explanations, NO text before or after.\n\
             Your ENTIRE response must be a single valid JSON object starting with {{ and ending with }}.\n\
             Example of CORRECT format:\n\
-            {{\"summary\":\"Create Python script\",\"steps\":[{{\"description\":\"Step 1\"}}],\"tool_requests\":[{{\"type\":\"workspace_write\",\"path\":\"script.py\",\"content\":\"print(\\\"hello\\\")\"}}]}}\n\n\
+            {{\"summary\":\"Create Python script\",\"steps\":[{{\"description\":\"Step 1\"}}],\"tool_requests\":[{{\"type\":\"workspace_write\",\"path\":\"script.py\",\"content\":\"print(\\\"hello\\\")\"}}],\"done\":false}}\n\n\
             Required JSON schema:\n\
             {{\n\
               \"summary\": \"brief description of what you will do\",\n\
@@ -134,7 +240,8 @@ impl ClineAgentLoop {
                   \"type\": \"directory_create\",\n\
                   \"path\": \"directory path\"\n\
                 }}\n\
-              ]\n\
+              ],\n\
+              \"done\": false\n\
             }}\n\n\
             Available tool types:\n\
             - workspace_write: Create/edit a file (path, content)\n\
@@ -142,13 +249,19 @@ impl ClineAgentLoop {
             - terminal: Run a command (command, cwd optional)\n\
             - directory_create: Create directory (path)\n\
             - file_delete: Delete file (path)\n\
-            - analyze_ast: Analyze code structure (path)\n\
+            - analyze_ast: Outline a file: functions, classes/structs, impls, imports, exports with line ranges (path)\n\
+            - find_symbol: Find definitions and references of a name (name, path optional file or directory)\n\
             - search_files: Search for files (pattern, regex)\n\
             - search_code: Search code (pattern, language)\n\
             - browser_launch: Launch browser (url)\n\
             - browser_click: Click element (selector)\n\
             - browser_type: Type text (selector, text)\n\
-            - browser_screenshot: Take screenshot\n\n\
+            - browser_screenshot: Take screenshot\n\
+            {}\n\
+            WORKING IN STEPS:\n\
+            - After your tool requests run you receive their results (output, errors, rejections) in the next message\n\
+            - Use the results to decide the next tool requests; read files before editing them when unsure\n\
+            - When the task is finished, reply with \"done\": true, a final summary and no tool_requests\n\n\
             CRITICAL JSON RULES:\n\
             - Use forward slashes (/) in file paths\n\
             - Use RELATIVE paths only - just the filename or relative path from current directory\n\
@@ -156,43 +269,28 @@ impl ClineAgentLoop {
             - The content field must contain FULL file content as a JSON string\n\
             - Escape quotes: use \\\" for quotes inside strings\n\
             - Your response must be ONLY the JSON object, nothing else\n\
-            - Each tool request will require user approval before execution\n\n\
+            - Each tool request may require user approval before execution\n\n\
             Current workspace directory: {:?}\n\
             Workspace context:\n{}",
-            self.workspace_path, context
+            mcp_tools, self.workspace_path, context
         );
         
         eprintln!("📤 Sending request to LLM (provider: {}, model: {})", provider.provider_type, model_name);
         // Convert conversation context to Message format if provided
-        let conversation_messages: Option<Vec<Message>> = conversation_context.as_ref().map(|ctx| {
-            ctx.iter().enumerate().map(|(idx, msg)| {
-                let role = msg.get("role").and_then(|v| v.as_str()).unwrap_or("user");
-                let content = msg.get("content").and_then(|v| v.as_str()).unwrap_or("");
-                Message {
-                    id: format!("cline-msg-{}", idx),
-                    run_id: "".to_string(), // Not needed for conversation context
-                    author_type: if role == "user" { "user" } else { "assistant" }.to_string(),
-                    profile_id: None,
-                    round_index: Some(idx as i32),
-                    turn_index: Some(idx as i32),
-                    text: content.to_string(),
-                    created_at: Utc::now().to_rfc3339(),
-                    provider_metadata_json: None,
-                }
-            }).collect()
-        });
-        
-        let packet = PromptPacket {
-            global_instructions: Some(system_prompt),
-            persona_instructions: "You are Cline, a helpful and careful coding assistant.".to_string(),
-            user_message: task.clone(),
-            conversation_context: conversation_messages,
-            params_json: json!({
-                "temperature": 0.4,
-                "max_tokens": 4096
-            }),
-            stream: false,
-        };
+        let mut history: Vec<Message> = conversation_context
+            .as_ref()
+            .map(|ctx| {
+                ctx.iter().map(|msg| {
+                    let role = msg.get("role").and_then(|v| v.as_str()).unwrap_or("user");
+                    let content = msg.get("content").and_then(|v| v.as_str()).unwrap_or("");
+                    (role, content)
+                }).collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (role, content))| Self::context_message(idx, role, content))
+            .collect();
         
         let adapter = get_adapter(&provider.provider_type)
             .map_err(|e| {
@@ -200,63 +298,265 @@ impl ClineAgentLoop {
                 format!("Failed to get adapter: {}", e)
             })?;
         
-        eprintln!("⏳ Waiting for LLM response...");
-        // Get initial response from LLM
-        let response = adapter.complete(&packet, &provider, &model_name).await
-            .map_err(|e| {
-                eprintln!("❌ LLM error: {}", e);
-                format!("LLM error: {}", e)
-            })?;
-        
-        eprintln!("✅ LLM response received ({} chars)", response.text.len());
-        eprintln!("📄 Full LLM response:\n{}", response.text);
+        let mut user_message = task.clone();
+        let mut tool_executions: Vec<ToolExecution> = Vec::new();
+        let mut total_tokens: u64 = 0;
+        let mut iteration: u32 = 0;
+        let mut summary = String::new();
+        let mut monitor = ErrorMonitor::new(resolve_workspace_path(&self.workspace_path.to_string_lossy()));
         
-        // Parse tool requests from LLM response
-        eprintln!("🔍 Parsing tool requests from LLM response...");
-        let tool_executions = match Self::parse_tool_requests_from_response(
-            &self.db,
-            &run_id,
-            &response.text,
-            &self.workspace_path,
-        ).await {
-            Ok(executions) => {
-                eprintln!("✅ Parsed {} tool execution(s)", executions.len());
-                executions
+        let status = loop {
+            if self.is_cancelled(&run_id)? {
+                break "cancelled";
             }
-            Err(e) => {
-                eprintln!("⚠️ Failed to parse tool requests (non-fatal): {}", e);
-                eprintln!("ℹ️ Treating as text-only response");
-                Vec::new()
+            if iteration >= self.config.max_iterations {
+                eprintln!("⏹️ Iteration limit ({}) reached", self.config.max_iterations);
+                break "limit_reached";
+            }
+            if self.config.max_total_tokens.is_some_and(|budget| total_tokens >= budget) {
+                eprintln!("⏹️ Token budget reached ({} tokens)", total_tokens);
+                break "limit_reached";
+            }
+            iteration += 1;
+            self.emit(&run_id, iteration, "thinking", format!("Iteration {}: waiting for the model", iteration), Vec::new(), total_tokens);
+            
+            let packet = PromptPacket {
+                global_instructions: Some(system_prompt.clone()),
+                persona_instructions: "You are Cline, a helpful and careful coding assistant.".to_string(),
+                user_message: user_message.clone(),
+                conversation_context: if history.is_empty() { None } else { Some(history.clone()) },
+                params_json: json!({
+                    "temperature": 0.4,
+                    "max_tokens": 4096
+                }),
+                stream: false,
+            };
+            
+            eprintln!("⏳ Waiting for LLM response (iteration {})...", iteration);
+            let response = match adapter.complete(&packet, &provider, &model_name).await {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("❌ LLM error: {}", e);
+                    let error = format!("LLM error: {}", e);
+                    self.finish_run(&run_id, "failed", Some(&error), iteration, total_tokens)?;
+                    self.emit(&run_id, iteration, "failed", error.clone(), Vec::new(), total_tokens);
+                    return Err(error);
+                }
+            };
+            
+            eprintln!("✅ LLM response received ({} chars)", response.text.len());
+            total_tokens += usage_total_tokens(&response.usage_json);
+            if let Err(e) = crate::token_usage::record_token_usage(
+                &self.db,
+                Some(&provider.id),
+                &model_name,
+                &response.usage_json,
+                "cline_agent",
+                None,
+                Some(json!({ "run_id": run_id, "iteration": iteration })),
+            ) {
+                eprintln!("⚠️ Failed to record token usage: {}", e);
+            }
+            
+            summary = Self::extract_summary_from_response(&response.text)
+                .unwrap_or_else(|| response.text.clone());
+            let done = signals_done(&response.text);
+            
+            // Parse tool requests from LLM response
+            eprintln!("🔍 Parsing tool requests from LLM response...");
+            let requested = match Self::parse_tool_requests_from_response(
+                &self.db,
+                &run_id,
+                iteration,
+                tool_executions.len() as i32,
+                &response.text,
+                &self.workspace_path,
+            ).await {
+                Ok(executions) => {
+                    eprintln!("✅ Parsed {} tool execution(s)", executions.len());
+                    executions
+                }
+                Err(e) => {
+                    eprintln!("⚠️ Failed to parse tool requests (non-fatal): {}", e);
+                    eprintln!("ℹ️ Treating as text-only response");
+                    Vec::new()
+                }
+            };
+            
+            history.push(Self::context_message(history.len(), "user", &user_message));
+            history.push(Self::context_message(history.len(), "assistant", &response.text));
+            
+            if requested.is_empty() {
+                break "complete";
+            }
+            
+            // Diagnostics before the first change to a project, so only new problems are reported
+            let planned = write_targets(requested.iter());
+            if self.config.check_diagnostics && !planned.is_empty() {
+                self.emit(&run_id, iteration, "diagnostics", "Recording diagnostics before changes".to_string(), Vec::new(), total_tokens);
+                monitor.capture_baseline(&planned).await;
+            }
+            
+            let resolved = self.resolve_tools(&run_id, iteration, requested, total_tokens).await?;
+            user_message = format_tool_results(iteration, &resolved);
+            
+            let mut new_errors: Vec<LinterError> = Vec::new();
+            if self.config.check_diagnostics {
+                let written = write_targets(resolved.iter().filter(|t| tool_succeeded(t)));
+                if !written.is_empty() {
+                    for path in written {
+                        monitor.record_write(path);
+                    }
+                    self.emit(&run_id, iteration, "diagnostics", "Checking changed files for new problems".to_string(), Vec::new(), total_tokens);
+                    new_errors = monitor
+                        .new_errors_after_writes(Duration::from_millis(self.config.diagnostics_debounce_ms))
+                        .await;
+                }
+            }
+            if !new_errors.is_empty() {
+                eprintln!("🩺 {} new diagnostic(s) after iteration {}", new_errors.len(), iteration);
+                user_message.push_str(&format!(
+                    "\n\nNEW PROBLEMS reported by compilers/linters after your changes (fix them before finishing):\n{}",
+                    error_monitor::format_for_agent(&new_errors, MAX_DIAGNOSTICS)
+                ));
+                self.emit(&run_id, iteration, "diagnostics", format!("{} new problem(s) after changes", new_errors.len()), Vec::new(), total_tokens);
+            }
+            self.emit(&run_id, iteration, "iteration_complete", summary.clone(), resolved.clone(), total_tokens);
+            tool_executions.extend(resolved);
+            
+            if self.is_cancelled(&run_id)? {
+                break "cancelled";
+            }
+            // Finishing with freshly introduced errors gets one more look from the model
+            if done && new_errors.is_empty() {
+                break "complete";
             }
         };
         
-        // Update run status
-        {
-            let conn = self.db.get_connection();
-            let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-            conn_guard.execute(
-                "UPDATE cline_runs SET status = ?1, finished_at = ?2 WHERE id = ?3",
-                rusqlite::params!["complete", Utc::now().to_rfc3339(), run_id],
-            )
-            .map_err(|e| format!("Failed to update run: {}", e))?;
-        }
-        
-        // Extract summary from parsed JSON if available
-        let summary = Self::extract_summary_from_response(&response.text)
-            .unwrap_or_else(|| response.text.clone());
+        self.finish_run(&run_id, status, None, iteration, total_tokens)?;
+        self.emit(&run_id, iteration, status, summary.clone(), tool_executions.clone(), total_tokens);
+        eprintln!("🏁 Cline run {} finished: {} after {} iteration(s), {} tokens", run_id, status, iteration, total_tokens);
         
         Ok(ClineTaskResult {
             run_id,
-            status: "complete".to_string(),
+            status: status.to_string(),
             summary,
             tool_executions,
+            iterations: iteration,
+            total_tokens,
         })
     }
     
+    fn context_message(idx: usize, role: &str, content: &str) -> Message {
+        Message {
+            id: format!("cline-msg-{}", idx),
+            run_id: "".to_string(), // Not needed for conversation context
+            author_type: if role == "user" { "user" } else { "assistant" }.to_string(),
+            profile_id: None,
+            round_index: Some(idx as i32),
+            turn_index: Some(idx as i32),
+            text: content.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            provider_metadata_json: None,
+        }
+    }
+    
+    fn is_cancelled(&self, run_id: &str) -> Result<bool, String> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let status: String = conn_guard
+            .query_row("SELECT status FROM cline_runs WHERE id = ?1", [run_id], |row| row.get(0))
+            .map_err(|e| format!("Run not found: {}", e))?;
+        Ok(status == "cancelled")
+    }
+    
+    /// Status changes never overwrite a cancellation made by `cline_cancel_task`
+    fn set_run_status(&self, run_id: &str, status: &str) -> Result<(), String> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard.execute(
+            "UPDATE cline_runs SET status = ?1 WHERE id = ?2 AND status != 'cancelled'",
+            rusqlite::params![status, run_id],
+        )
+        .map_err(|e| format!("Failed to update run: {}", e))?;
+        Ok(())
+    }
+    
+    fn finish_run(&self, run_id: &str, status: &str, error: Option<&str>, iterations: u32, total_tokens: u64) -> Result<(), String> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard.execute(
+            "UPDATE cline_runs SET status = ?1, error_text = ?2, finished_at = ?3, iterations = ?4, total_tokens = ?5 WHERE id = ?6",
+            rusqlite::params![status, error, Utc::now().to_rfc3339(), iterations, total_tokens as i64, run_id],
+        )
+        .map_err(|e| format!("Failed to update run: {}", e))?;
+        Ok(())
+    }
+    
+    /// Run auto-approved tools, then wait until the user has approved or rejected the rest.
+    /// Returns the iteration's executions with their final status and results.
+    async fn resolve_tools(
+        &self,
+        run_id: &str,
+        iteration: u32,
+        requested: Vec<ToolExecution>,
+        total_tokens: u64,
+    ) -> Result<Vec<ToolExecution>, String> {
+        let workspace_path = resolve_workspace_path(&self.workspace_path.to_string_lossy());
+        for execution in requested.iter().filter(|t| self.config.auto_approve.contains(&t.tool_type)) {
+            eprintln!("⚡ Auto-approving {} ({})", execution.tool_type, execution.id);
+            checkpoint_before_tool(&self.db, run_id, &execution.id, execution.step_index, &execution.tool_type, &self.workspace_path).await;
+            let result = execute_tool_params(&execution.tool_params, &workspace_path)
+                .await
+                .unwrap_or_else(ToolResult::err);
+            let result_json = serde_json::to_value(&result).map_err(|e| format!("Failed to serialize result: {}", e))?;
+            record_tool_outcome(&self.db, &execution.id, "approved", Some(&result_json))?;
+            let mut done = execution.clone();
+            done.approval_status = "approved".to_string();
+            done.result = Some(result_json);
+            self.emit(run_id, iteration, "tool_result", format!("{} ran automatically", execution.tool_type), vec![done], total_tokens);
+        }
+        
+        let pending = load_iteration_tools(&self.db, run_id, iteration)?
+            .into_iter()
+            .filter(|t| t.approval_status == "pending")
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return load_iteration_tools(&self.db, run_id, iteration);
+        }
+        
+        self.set_run_status(run_id, "awaiting_approval")?;
+        self.emit(run_id, iteration, "awaiting_approval", format!("{} tool(s) waiting for approval", pending.len()), pending, total_tokens);
+        let deadline = Instant::now() + Duration::from_secs(self.config.approval_timeout_secs);
+        loop {
+            let tools = load_iteration_tools(&self.db, run_id, iteration)?;
+            // "executing" means approved and still running in the approval command
+            if tools.iter().all(|t| !matches!(t.approval_status.as_str(), "pending" | "executing")) || self.is_cancelled(run_id)? {
+                break;
+            }
+            if Instant::now() >= deadline {
+                eprintln!("⏱️ Approval timed out for run {} iteration {}", run_id, iteration);
+                let timed_out = json!({ "success": false, "output": "", "error": "Not approved before the approval timeout", "extra_json": null });
+                for tool in tools.iter().filter(|t| t.approval_status == "pending") {
+                    record_tool_outcome(&self.db, &tool.id, "rejected", Some(&timed_out))?;
+                }
+                // Tools approved just before the deadline are left to finish
+                if tools.iter().all(|t| t.approval_status != "executing") {
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(APPROVAL_POLL_MS)).await;
+        }
+        self.set_run_status(run_id, "running")?;
+        load_iteration_tools(&self.db, run_id, iteration)
+    }
+    
     /// Parse tool requests from LLM JSON response
     async fn parse_tool_requests_from_response(
         db: &Database,
         run_id: &str,
+        iteration: u32,
+        step_offset: i32,
         response_text: &str,
         workspace_path: &PathBuf,
     ) -> Result<Vec<ToolExecution>, String> {
@@ -422,6 +722,7 @@ impl ClineAgentLoop {
                 
                 // Create tool execution record
                 let tool_id = Uuid::new_v4().to_string();
+                let step_index = step_offset + idx as i32;
                 let tool_params = serde_json::to_value(&cline_tool)
                     .map_err(|e| format!("Failed to serialize tool: {}", e))?;
                 
@@ -430,14 +731,15 @@ impl ClineAgentLoop {
                     let conn = db.get_connection();
                     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
                     conn_guard.execute(
-                        "INSERT INTO cline_tool_executions (id, run_id, step_index, tool_type, tool_params_json, approval_status) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        "INSERT INTO cline_tool_executions (id, run_id, step_index, tool_type, tool_params_json, approval_status, iteration) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
                         rusqlite::params![
                             tool_id,
                             run_id,
-                            idx as i32,
+                            step_index,
                             tool_type,
                             serde_json::to_string(&tool_params).map_err(|e| format!("JSON error: {}", e))?,
-                            "pending"
+                            "pending",
+                            iteration
                         ],
                     )
                     .map_err(|e| format!("Failed to store tool execution: {}", e))?;
@@ -447,11 +749,12 @@ impl ClineAgentLoop {
                 
                 tool_executions.push(ToolExecution {
                     id: tool_id,
-                    step_index: idx as i32,
+                    step_index,
                     tool_type: tool_type.to_string(),
                     tool_params,
                     approval_status: "pending".to_string(),
                     result: None,
+                    iteration,
                 });
             }
             
@@ -546,6 +849,15 @@ impl ClineAgentLoop {
                     path: normalize_path(path),
                 })
             }
+            "find_symbol" => {
+                let name = tool_req.get("name")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing 'name' in find_symbol")?;
+                Ok(ClineToolRequest::FindSymbol {
+                    name: name.to_string(),
+                    path: tool_req.get("path").and_then(|v| v.as_str()).map(normalize_path),
+                })
+            }
             "search_files" => {
                 let pattern = tool_req.get("pattern")
                     .and_then(|v| v.as_str())
@@ -595,6 +907,22 @@ impl ClineAgentLoop {
                     text: text.to_string(),
                 })
             }
+            "mcp_call" => {
+                let server = tool_req.get("server")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing 'server' in mcp_call")?;
+                let tool = tool_req.get("tool")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing 'tool' in mcp_call")?;
+                Ok(ClineToolRequest::MCPCall {
+                    server: server.to_string(),
+                    tool: tool.to_string(),
+                    params: tool_req.get("arguments")
+                        .or_else(|| tool_req.get("params"))
+                        .cloned()
+                        .unwrap_or_else(|| json!({})),
+                })
+            }
             "browser_screenshot" => {
                 Ok(ClineToolRequest::BrowserScreenshot {
                     full_page: tool_req.get("full_page").and_then(|v| v.as_bool()),
@@ -718,6 +1046,7 @@ impl ClineAgentLoop {
             ClineToolRequest::BrowserExecuteJS { .. } => "browser_execute_js",
             ClineToolRequest::BrowserCookies { .. } => "browser_cookies",
             ClineToolRequest::AnalyzeAST { .. } => "analyze_ast",
+            ClineToolRequest::FindSymbol { .. } => "find_symbol",
             ClineToolRequest::SearchFiles { .. } => "search_files",
             ClineToolRequest::SearchCode { .. } => "search_code",
             ClineToolRequest::MCPCall { .. } => "mcp_call",
@@ -754,6 +1083,237 @@ impl ClineAgentLoop {
             tool_params,
             approval_status: "pending".to_string(),
             result: None,
+            iteration: 0,
         })
     }
 }
+
+/// Resolve a run's workspace path; relative paths are taken from the user's home directory
+pub(crate) fn resolve_workspace_path(raw: &str) -> String {
+    if std::path::Path::new(raw).is_absolute() {
+        return raw.to_string();
+    }
+    let home = if cfg!(windows) {
+        std::env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users".to_string())
+    } else {
+        std::env::var("HOME").unwrap_or_else(|_| "/home".to_string())
+    };
+    std::path::Path::new(&home)
+        .join(raw)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Execute stored tool params with a hard timeout (`timeout_seconds`, default 90) so a hanging
+/// command never blocks the caller. Errors only when the params are not a valid tool request.
+pub(crate) async fn execute_tool_params(tool_params: &Value, workspace_path: &str) -> Result<ToolResult, String> {
+    let cline_tool: ClineToolRequest = serde_json::from_value(tool_params.clone())
+        .map_err(|e| {
+            eprintln!("❌ Failed to deserialize tool: {} | Value: {:?}", e, tool_params);
+            format!("Failed to deserialize tool: {}", e)
+        })?;
+    let timeout_secs: u64 = tool_params
+        .get("timeout_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(90);
+
+    match tokio::time::timeout(
+        Duration::from_secs(timeout_secs),
+        crate::cline::tools::execute_cline_tool(cline_tool, workspace_path),
+    )
+    .await
+    {
+        Ok(tool_result) => Ok(tool_result),
+        Err(_) => {
+            eprintln!("⏱️ Tool execution timed out after {}s", timeout_secs);
+            Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Tool execution timed out after {} seconds", timeout_secs)),
+                extra_json: Some(json!({
+                    "timeout_seconds": timeout_secs,
+                    "status": "timeout"
+                })),
+            })
+        }
+    }
+}
+
+fn record_tool_outcome(db: &Database, tool_id: &str, status: &str, result: Option<&Value>) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let result_json = result
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("JSON error: {}", e))?;
+    conn_guard.execute(
+        "UPDATE cline_tool_executions SET approval_status = ?1, result_json = ?2, executed_at = ?3 WHERE id = ?4 AND approval_status = 'pending'",
+        rusqlite::params![status, result_json, Utc::now().to_rfc3339(), tool_id],
+    )
+    .map_err(|e| format!("Failed to update tool execution: {}", e))?;
+    Ok(())
+}
+
+fn load_iteration_tools(db: &Database, run_id: &str, iteration: u32) -> Result<Vec<ToolExecution>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT id, step_index, tool_type, tool_params_json, approval_status, result_json
+             FROM cline_tool_executions WHERE run_id = ?1 AND iteration = ?2 ORDER BY step_index",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map(rusqlite::params![run_id, iteration], |row| {
+            let params: Option<String> = row.get(3)?;
+            let result: Option<String> = row.get(5)?;
+            Ok(ToolExecution {
+                id: row.get(0)?,
+                step_index: row.get(1)?,
+                tool_type: row.get(2)?,
+                tool_params: params.and_then(|p| serde_json::from_str(&p).ok()).unwrap_or(Value::Null),
+                approval_status: row.get(4)?,
+                result: result.and_then(|r| serde_json::from_str(&r).ok()),
+                iteration,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Row error: {}", e))
+}
+
+/// `mcp_call` section of the system prompt: one line per tool with its argument schema
+fn describe_mcp_tools(tools: &[crate::mcp::CatalogTool]) -> String {
+    if tools.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from(
+        "- mcp_call: Call a tool on an MCP server (server, tool, arguments). Available MCP tools:\n",
+    );
+    for entry in tools {
+        out.push_str(&format!(
+            "  - server \"{}\", tool \"{}\": {} arguments schema: {}\n",
+            entry.server,
+            entry.tool.name,
+            entry.tool.description.as_deref().unwrap_or("(no description)"),
+            serde_json::to_string(&entry.tool.input_schema).unwrap_or_default()
+        ));
+    }
+    out
+}
+
+/// Total tokens of one call from the adapter's usage blob (total, else prompt + completion)
+fn usage_total_tokens(usage: &Option<Value>) -> u64 {
+    let Some(usage) = usage else { return 0 };
+    let field = |name: &str| usage.get(name).and_then(|v| v.as_u64());
+    field("total_tokens")
+        .unwrap_or_else(|| field("prompt_tokens").unwrap_or(0) + field("completion_tokens").unwrap_or(0))
+}
+
+/// Whether the model marked the task finished (`"done": true` in its JSON reply)
+fn signals_done(response_text: &str) -> bool {
+    let start = response_text.find('{');
+    let end = response_text.rfind('}');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str::<Value>(&response_text[start..=end])
+            .ok()
+            .and_then(|v| v.get("done").and_then(|d| d.as_bool()))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Absolute paths the write tools among `executions` touch (resolved like the write tools do)
+fn write_targets<'a>(executions: impl Iterator<Item = &'a ToolExecution>) -> Vec<PathBuf> {
+    executions
+        .filter(|t| WRITE_TOOLS.contains(&t.tool_type.as_str()))
+        .filter_map(|t| t.tool_params.get("path").and_then(|p| p.as_str()))
+        .map(|p| PathBuf::from(resolve_workspace_path(p)))
+        .collect()
+}
+
+fn tool_succeeded(execution: &ToolExecution) -> bool {
+    execution.approval_status == "approved"
+        && execution.result.as_ref().and_then(|r| r.get("success")).and_then(|s| s.as_bool()).unwrap_or(false)
+}
+
+/// Observation message sent back to the model after an iteration's tools were resolved
+fn format_tool_results(iteration: u32, executions: &[ToolExecution]) -> String {
+    let mut out = format!("Results of your tool requests (iteration {}):\n", iteration);
+    for execution in executions {
+        let target = ["path", "command", "pattern", "url", "selector"]
+            .iter()
+            .find_map(|key| execution.tool_params.get(*key).and_then(|v| v.as_str()))
+            .map(|t| format!(" `{}`", t))
+            .unwrap_or_default();
+        out.push_str(&format!("\n[step {}] {}{}: ", execution.step_index, execution.tool_type, target));
+        let result = execution.result.as_ref().and_then(|r| serde_json::from_value::<ToolResult>(r.clone()).ok());
+        match (execution.approval_status.as_str(), result) {
+            ("approved", Some(result)) => {
+                out.push_str(if result.success { "succeeded\n" } else { "failed\n" });
+                if !result.output.trim().is_empty() {
+                    out.push_str(&truncate_chars(result.output.trim(), MAX_RESULT_CHARS));
+                    out.push('\n');
+                }
+                if let Some(error) = result.error.filter(|e| !e.trim().is_empty()) {
+                    out.push_str(&format!("error: {}\n", truncate_chars(error.trim(), MAX_RESULT_CHARS)));
+                }
+            }
+            ("approved", None) => out.push_str("approved, but no result was recorded\n"),
+            ("rejected", Some(result)) if result.error.is_some() => {
+                out.push_str(&format!("not executed ({})\n", result.error.unwrap_or_default()));
+            }
+            ("rejected", _) => out.push_str("rejected by the user; do not retry it unchanged\n"),
+            (other, _) => out.push_str(&format!("{}\n", other)),
+        }
+    }
+    out.push_str("\nContinue the task using these results. Reply with the same JSON format; set \"done\": true when the task is complete.");
+    out
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max).collect();
+    format!("{}\n… (truncated)", kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(step_index: i32, tool_type: &str, params: Value, status: &str, result: Option<Value>) -> ToolExecution {
+        ToolExecution {
+            id: format!("t{}", step_index),
+            step_index,
+            tool_type: tool_type.to_string(),
+            tool_params: params,
+            approval_status: status.to_string(),
+            result,
+            iteration: 1,
+        }
+    }
+
+    #[test]
+    fn test_done_signal_and_tool_result_observation() {
+        assert!(signals_done("```json\n{\"summary\":\"Finished\",\"tool_requests\":[],\"done\":true}\n```"));
+        assert!(!signals_done("{\"summary\":\"Next\",\"done\":false}"));
+        assert!(!signals_done("plain text reply"));
+
+        assert_eq!(usage_total_tokens(&Some(json!({ "prompt_tokens": 30, "completion_tokens": 12 }))), 42);
+        assert_eq!(usage_total_tokens(&None), 0);
+
+        let long_output = "x".repeat(MAX_RESULT_CHARS + 100);
+        let executions = vec![
+            execution(0, "terminal", json!({ "type": "terminal", "command": "cargo test" }), "approved",
+                Some(json!({ "success": false, "output": long_output, "error": "exit code 101", "extra_json": null }))),
+            execution(1, "workspace_write", json!({ "type": "workspace_write", "path": "src/lib.rs" }), "rejected", None),
+        ];
+        let observation = format_tool_results(1, &executions);
+        assert!(observation.contains("[step 0] terminal `cargo test`: failed"));
+        assert!(observation.contains("error: exit code 101"));
+        assert!(observation.contains("(truncated)"));
+        assert!(observation.contains("[step 1] workspace_write `src/lib.rs`: rejected by the user"));
+        assert!(observation.len() < MAX_RESULT_CHARS + 1000);
+    }
+}
@@ -1,24 +1,88 @@
 // Workspace checkpoint system for Cline
+//
+// A checkpoint is a manifest of every file in the workspace (recursive, honouring .gitignore and
+// .ignore files) that maps relative paths to SHA-256 content hashes. Contents are stored once per
+// hash in `cline_checkpoint_blobs`, so the checkpoints taken before each tool execution only add
+// the files that changed. Restoring writes back changed files and deletes files added since.
 
+use crate::cline::agent_loop::resolve_workspace_path;
 use crate::db::Database;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::path::Path;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 use uuid::Uuid;
 use chrono::Utc;
 
+/// Larger files are listed as skipped and left untouched by restores
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Refuse to checkpoint directories like a home folder instead of hanging on them
+const MAX_FILES: usize = 50_000;
+/// Unified diff kept per file in `compare_checkpoint`
+const MAX_DIFF_CHARS: usize = 20_000;
+/// Tools that never change files; no checkpoint is taken before them
+const READ_ONLY_TOOLS: &[&str] = &[
+    "workspace_read",
+    "system_file_read",
+    "search_files",
+    "search_code",
+    "analyze_ast",
+    "find_symbol",
+    "process_list",
+    "system_info",
+    "environment_variables",
+    "registry_read",
+    "browser_screenshot",
+];
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[allow(dead_code)]
 pub struct Checkpoint {
     pub id: String,
     pub run_id: String,
     pub step_index: i32,
-    pub snapshot_json: Value,
+    /// Tool execution this checkpoint was taken before; None for manual and run-start checkpoints
+    pub tool_execution_id: Option<String>,
+    pub file_count: usize,
     pub created_at: String,
 }
 
+/// Stored as `snapshot_json`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct Manifest {
+    /// Relative path (`/`-separated) -> file
+    files: BTreeMap<String, ManifestFile>,
+    /// Files over MAX_FILE_BYTES or unreadable at snapshot time
+    #[serde(default)]
+    skipped: Vec<String>,
+    /// Converted from a top-level-only snapshot; restores never delete from these
+    #[serde(default)]
+    partial: bool,
+    timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct ManifestFile {
+    hash: String,
+    size: u64,
+    /// Nanoseconds since the epoch; 0 when unknown
+    #[serde(default)]
+    mtime: i64,
+    #[serde(default)]
+    executable: bool,
+}
+
+struct WorkspaceFile {
+    rel: String,
+    abs: PathBuf,
+    size: u64,
+    mtime: i64,
+    executable: bool,
+}
+
 /// Create a workspace checkpoint
 pub async fn create_checkpoint(
     db: &Database,
@@ -26,223 +90,586 @@ pub async fn create_checkpoint(
     step_index: i32,
     workspace_path: &Path,
 ) -> Result<String, String> {
-    // Snapshot all files in workspace
-    let snapshot = snapshot_workspace(workspace_path).await?;
-    
-    let checkpoint_id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
-    
+    insert_checkpoint(db, run_id, step_index, None, workspace_path).await
+}
+
+/// Checkpoint the workspace before a tool that may change it runs. Failures are logged, never
+/// returned, so a checkpoint problem does not block the tool.
+pub async fn checkpoint_before_tool(
+    db: &Database,
+    run_id: &str,
+    tool_execution_id: &str,
+    step_index: i32,
+    tool_type: &str,
+    workspace_path: &Path,
+) -> Option<String> {
+    if READ_ONLY_TOOLS.contains(&tool_type) {
+        return None;
+    }
+    match insert_checkpoint(db, run_id, step_index, Some(tool_execution_id), workspace_path).await {
+        Ok(id) => {
+            eprintln!("💾 Checkpoint {} before {} ({})", id, tool_type, tool_execution_id);
+            Some(id)
+        }
+        Err(e) => {
+            eprintln!("⚠️ Checkpoint before {} failed (non-fatal): {}", tool_type, e);
+            None
+        }
+    }
+}
+
+/// Checkpoints of a run, oldest first
+pub fn list_checkpoints(db: &Database, run_id: &str) -> Result<Vec<Checkpoint>, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    
-    conn_guard.execute(
-        "INSERT INTO cline_checkpoints (id, run_id, step_index, snapshot_json, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![
-            checkpoint_id,
-            run_id,
-            step_index,
-            serde_json::to_string(&snapshot).map_err(|e| format!("JSON error: {}", e))?,
-            now
-        ],
-    )
-    .map_err(|e| format!("Failed to create checkpoint: {}", e))?;
-    
-    Ok(checkpoint_id)
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT id, run_id, step_index, tool_execution_id, snapshot_json, created_at
+             FROM cline_checkpoints WHERE run_id = ?1 ORDER BY created_at, step_index",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            let snapshot_json: String = row.get(4)?;
+            Ok(Checkpoint {
+                id: row.get(0)?,
+                run_id: row.get(1)?,
+                step_index: row.get(2)?,
+                tool_execution_id: row.get(3)?,
+                file_count: file_count(&snapshot_json),
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Database error: {}", e))
 }
 
-/// Restore workspace to a checkpoint
+/// Restore workspace to a checkpoint: changed and deleted files are written back and files
+/// added since the checkpoint are removed
 pub async fn restore_checkpoint(
     db: &Database,
     checkpoint_id: &str,
     workspace_path: &Path,
 ) -> Result<(), String> {
-    let snapshot_json: String = {
-        let conn = db.get_connection();
-        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-        
-        conn_guard
-            .query_row(
-                "SELECT snapshot_json FROM cline_checkpoints WHERE id = ?1",
-                [checkpoint_id],
-                |row| row.get(0),
-            )
-            .map_err(|e| format!("Checkpoint not found: {}", e))?
-    };
-    
-    let snapshot: Value = serde_json::from_str(&snapshot_json)
-        .map_err(|e| format!("Failed to parse snapshot: {}", e))?;
-    
-    // Restore files from snapshot
-    restore_workspace_from_snapshot(workspace_path, &snapshot).await?;
-    
+    let root = workspace_root(workspace_path)?;
+    let snapshot_json = load_snapshot_json(db, checkpoint_id)?;
+    let db = db.clone();
+    let (written, deleted) = tokio::task::spawn_blocking(move || {
+        let manifest = parse_manifest(&db, &snapshot_json, &root)?;
+        restore_manifest(&db, &manifest, &root)
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))??;
+    eprintln!("⏪ Restored checkpoint {}: {} file(s) written, {} deleted", checkpoint_id, written, deleted);
     Ok(())
 }
 
-/// Compare current workspace with a checkpoint
+/// Compare current workspace with a checkpoint. Lists added, modified and deleted paths plus a
+/// unified line diff per changed text file.
 pub async fn compare_checkpoint(
     db: &Database,
     checkpoint_id: &str,
     workspace_path: &Path,
 ) -> Result<Value, String> {
-    let snapshot_json: String = {
-        let conn = db.get_connection();
-        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-        
-        conn_guard
-            .query_row(
-                "SELECT snapshot_json FROM cline_checkpoints WHERE id = ?1",
-                [checkpoint_id],
-                |row| row.get(0),
-            )
-            .map_err(|e| format!("Checkpoint not found: {}", e))?
+    let root = workspace_root(workspace_path)?;
+    let snapshot_json = load_snapshot_json(db, checkpoint_id)?;
+    let db = db.clone();
+    tokio::task::spawn_blocking(move || {
+        let manifest = parse_manifest(&db, &snapshot_json, &root)?;
+        compare_manifest(&db, &manifest, &root)
+    })
+    .await
+    .map_err(|e| format!("Compare task failed: {}", e))?
+}
+
+async fn insert_checkpoint(
+    db: &Database,
+    run_id: &str,
+    step_index: i32,
+    tool_execution_id: Option<&str>,
+    workspace_path: &Path,
+) -> Result<String, String> {
+    let root = workspace_root(workspace_path)?;
+    // Files unchanged since the run's last checkpoint keep their hash without being read again
+    let previous = latest_manifest(db, run_id)?;
+    let manifest = {
+        let db = db.clone();
+        tokio::task::spawn_blocking(move || snapshot_workspace(&db, &root, previous.as_ref()))
+            .await
+            .map_err(|e| format!("Snapshot task failed: {}", e))??
     };
-    
-    let checkpoint_snapshot: Value = serde_json::from_str(&snapshot_json)
+    eprintln!("📸 Snapshot: {} file(s), {} skipped", manifest.files.len(), manifest.skipped.len());
+
+    let checkpoint_id = Uuid::new_v4().to_string();
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard.execute(
+        "INSERT INTO cline_checkpoints (id, run_id, step_index, tool_execution_id, snapshot_json, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            checkpoint_id,
+            run_id,
+            step_index,
+            tool_execution_id,
+            serde_json::to_string(&manifest).map_err(|e| format!("JSON error: {}", e))?,
+            Utc::now().to_rfc3339()
+        ],
+    )
+    .map_err(|e| format!("Failed to create checkpoint: {}", e))?;
+
+    Ok(checkpoint_id)
+}
+
+fn workspace_root(workspace_path: &Path) -> Result<PathBuf, String> {
+    let root = PathBuf::from(resolve_workspace_path(&workspace_path.to_string_lossy()));
+    if !root.is_dir() {
+        return Err(format!("Workspace {} is not a directory", root.display()));
+    }
+    Ok(root)
+}
+
+fn load_snapshot_json(db: &Database, checkpoint_id: &str) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .query_row(
+            "SELECT snapshot_json FROM cline_checkpoints WHERE id = ?1",
+            [checkpoint_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Checkpoint not found: {}", e))
+}
+
+fn latest_manifest(db: &Database, run_id: &str) -> Result<Option<Manifest>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let snapshot_json: Option<String> = conn_guard
+        .query_row(
+            "SELECT snapshot_json FROM cline_checkpoints WHERE run_id = ?1 ORDER BY created_at DESC LIMIT 1",
+            [run_id],
+            |row| row.get(0),
+        )
+        .ok();
+    Ok(snapshot_json.and_then(|s| serde_json::from_str(&s).ok()))
+}
+
+fn file_count(snapshot_json: &str) -> usize {
+    let snapshot: Value = serde_json::from_str(snapshot_json).unwrap_or(Value::Null);
+    match snapshot.get("files") {
+        Some(Value::Object(files)) => files.len(),
+        Some(Value::Array(files)) => files.len(),
+        _ => 0,
+    }
+}
+
+/// Checkpoints created before manifests stored inline contents of top-level files only, with
+/// paths relative to the home directory. Their contents move into the blob store.
+fn parse_manifest(db: &Database, snapshot_json: &str, root: &Path) -> Result<Manifest, String> {
+    if let Ok(manifest) = serde_json::from_str::<Manifest>(snapshot_json) {
+        return Ok(manifest);
+    }
+    let legacy: Value = serde_json::from_str(snapshot_json)
         .map_err(|e| format!("Failed to parse snapshot: {}", e))?;
-    
-    let current_snapshot = snapshot_workspace(workspace_path).await?;
-    
-    // Generate diff
-    let diff = generate_diff(&checkpoint_snapshot, &current_snapshot);
-    
-    Ok(diff)
-}
-
-async fn snapshot_workspace(workspace_path: &Path) -> Result<Value, String> {
-    use crate::commands_workspace;
-    
-    eprintln!("📸 Starting workspace snapshot for: {:?}", workspace_path);
-    
-    // Limit snapshot to avoid hanging on large directories
-    // Only snapshot top-level files and directories, not recursive
-    let max_files = 1000; // Limit to prevent hanging
+    let files = legacy.get("files")
+        .and_then(|f| f.as_array())
+        .ok_or("Invalid snapshot format")?;
+    let mut manifest = Manifest {
+        partial: true,
+        timestamp: legacy.get("timestamp").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+        ..Default::default()
+    };
+    for file in files {
+        let (Some(path), Some(content)) = (
+            file.get("path").and_then(|p| p.as_str()),
+            file.get("content").and_then(|c| c.as_str()),
+        ) else {
+            continue;
+        };
+        let absolute = PathBuf::from(resolve_workspace_path(path));
+        let Ok(rel) = absolute.strip_prefix(root) else {
+            continue;
+        };
+        let hash = store_blob(db, content.as_bytes())?;
+        manifest.files.insert(
+            relative_key(rel),
+            ManifestFile { hash, size: content.len() as u64, mtime: 0, executable: false },
+        );
+    }
+    Ok(manifest)
+}
+
+/// Every file under `root` that git would not ignore; `.git` itself and symlinks are left out
+fn scan_workspace(root: &Path) -> Result<Vec<WorkspaceFile>, String> {
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .git_global(false)
+        .require_git(false)
+        .follow_links(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
     let mut files = Vec::new();
-    let mut file_count = 0;
-    
-    // Get top-level files only (not recursive to avoid hanging on large dirs like C:\Users\mirfa)
-    match commands_workspace::list_workspace_files(
-        workspace_path.to_str().map(|s| s.to_string())
-    ).await {
-        Ok(entries) => {
-            eprintln!("📁 Found {} entries in workspace", entries.len());
-            for entry in entries {
-                if file_count >= max_files {
-                    eprintln!("⚠️ Reached file limit ({}), stopping snapshot", max_files);
-                    break;
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("⚠️ Skipping unreadable entry: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let metadata = entry.metadata().map_err(|e| format!("Failed to stat {}: {}", entry.path().display(), e))?;
+        if files.len() >= MAX_FILES {
+            return Err(format!(
+                "Workspace has more than {} files; add a .gitignore or pick a smaller workspace",
+                MAX_FILES
+            ));
+        }
+        files.push(WorkspaceFile {
+            rel: relative_key(rel),
+            abs: entry.path().to_path_buf(),
+            size: metadata.len(),
+            mtime: mtime_nanos(&metadata),
+            executable: is_executable(&metadata),
+        });
+    }
+    Ok(files)
+}
+
+fn snapshot_workspace(db: &Database, root: &Path, previous: Option<&Manifest>) -> Result<Manifest, String> {
+    let mut manifest = Manifest { timestamp: Utc::now().to_rfc3339(), ..Default::default() };
+    for file in scan_workspace(root)? {
+        if file.size > MAX_FILE_BYTES {
+            manifest.skipped.push(file.rel);
+            continue;
+        }
+        let hash = match previous.and_then(|p| unchanged_since(p, &file)) {
+            Some(previous) => previous.hash.clone(),
+            None => match fs::read(&file.abs) {
+                Ok(bytes) => store_blob(db, &bytes)?,
+                // Vanished or locked since the walk; restores must leave it alone
+                Err(e) => {
+                    eprintln!("⚠️ Skipping {}: {}", file.rel, e);
+                    manifest.skipped.push(file.rel);
+                    continue;
                 }
-                
-                if !entry.is_dir {
-                    // Only snapshot files, not directories (to avoid recursive traversal)
-                    match commands_workspace::read_workspace_file(entry.path.clone()).await {
-                        Ok(content) => {
-                            // Use content hash for comparison (simple approach)
-                            let mut hasher = DefaultHasher::new();
-                            content.hash(&mut hasher);
-                            let hash = hasher.finish();
-                            
-                            files.push(json!({
-                                "path": entry.path,
-                                "content": content,
-                                "hash": format!("{:x}", hash)
-                            }));
-                            file_count += 1;
-                        }
-                        Err(_) => {
-                            // Skip files that can't be read
-                        }
-                    }
+            },
+        };
+        manifest.files.insert(
+            file.rel,
+            ManifestFile { hash, size: file.size, mtime: file.mtime, executable: file.executable },
+        );
+    }
+    Ok(manifest)
+}
+
+/// The manifest entry for `file` when its size and mtime match and the mtime is older than the
+/// manifest. A file written in the same clock tick as the snapshot can change again without its
+/// mtime moving, so those are always re-read (git's "racy clean" rule).
+fn unchanged_since<'a>(manifest: &'a Manifest, file: &WorkspaceFile) -> Option<&'a ManifestFile> {
+    let taken_at = chrono::DateTime::parse_from_rfc3339(&manifest.timestamp)
+        .ok()
+        .and_then(|t| t.timestamp_nanos_opt())?;
+    manifest
+        .files
+        .get(&file.rel)
+        .filter(|p| p.mtime != 0 && p.mtime == file.mtime && p.size == file.size && file.mtime < taken_at)
+}
+
+fn restore_manifest(db: &Database, manifest: &Manifest, root: &Path) -> Result<(usize, usize), String> {
+    let current: HashMap<String, WorkspaceFile> = scan_workspace(root)?
+        .into_iter()
+        .map(|f| (f.rel.clone(), f))
+        .collect();
+
+    let mut written = 0;
+    for (rel, file) in &manifest.files {
+        let path = root.join(rel);
+        if let Some(existing) = current.get(rel) {
+            if existing.size == file.size && fs::read(&path).map(|b| content_hash(&b) == file.hash).unwrap_or(false) {
+                if existing.executable != file.executable {
+                    set_executable(&path, file.executable)?;
                 }
+                continue;
             }
         }
-        Err(e) => {
-            eprintln!("⚠️ Failed to list workspace files: {}", e);
-            return Err(format!("Failed to list files: {}", e));
+        // A directory may have taken the file's place
+        if path.is_dir() {
+            fs::remove_dir_all(&path).map_err(|e| format!("Failed to remove {}: {}", rel, e))?;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory for {}: {}", rel, e))?;
         }
+        fs::write(&path, load_blob(db, &file.hash)?).map_err(|e| format!("Failed to restore file {}: {}", rel, e))?;
+        set_executable(&path, file.executable)?;
+        written += 1;
     }
-    
-    eprintln!("✅ Snapshot complete: {} files", files.len());
-    
-    Ok(json!({
-        "files": files,
-        "timestamp": Utc::now().to_rfc3339(),
-        "limited": file_count >= max_files
-    }))
-}
 
-async fn restore_workspace_from_snapshot(_workspace_path: &Path, snapshot: &Value) -> Result<(), String> {
-    use crate::commands_workspace;
-    
-    let files = snapshot.get("files")
-        .and_then(|f| f.as_array())
-        .ok_or("Invalid snapshot format")?;
-    
-    for file in files {
-        let path = file.get("path")
-            .and_then(|p| p.as_str())
-            .ok_or("Invalid file path in snapshot")?;
-        let content = file.get("content")
-            .and_then(|c| c.as_str())
-            .ok_or("Invalid file content in snapshot")?;
-        
-        // Call the Tauri command function directly (it's async and returns Result)
-        commands_workspace::write_workspace_file(path.to_string(), content.to_string()).await
-            .map_err(|e| format!("Failed to restore file {}: {}", path, e))?;
-    }
-    
-    Ok(())
+    let mut deleted = 0;
+    if !manifest.partial {
+        for (rel, file) in &current {
+            if manifest.files.contains_key(rel) || manifest.skipped.contains(rel) {
+                continue;
+            }
+            fs::remove_file(&file.abs).map_err(|e| format!("Failed to delete {}: {}", rel, e))?;
+            remove_empty_parents(&file.abs, root);
+            deleted += 1;
+        }
+    }
+    Ok((written, deleted))
 }
 
-fn generate_diff(checkpoint: &Value, current: &Value) -> Value {
-    let empty_vec: Vec<serde_json::Value> = Vec::new();
-    let checkpoint_files = checkpoint.get("files")
-        .and_then(|f| f.as_array())
-        .unwrap_or(&empty_vec);
-    let current_files = current.get("files")
-        .and_then(|f| f.as_array())
-        .unwrap_or(&empty_vec);
-    
+fn compare_manifest(db: &Database, manifest: &Manifest, root: &Path) -> Result<Value, String> {
+    let current = scan_workspace(root)?;
     let mut added = Vec::new();
     let mut modified = Vec::new();
     let mut deleted = Vec::new();
-    
-    // Create maps for easier lookup
-    let checkpoint_map: std::collections::HashMap<&str, &Value> = checkpoint_files
-        .iter()
-        .filter_map(|f| f.get("path").and_then(|p| p.as_str()).map(|p| (p, f)))
-        .collect();
-    
-    let current_map: std::collections::HashMap<&str, &Value> = current_files
-        .iter()
-        .filter_map(|f| f.get("path").and_then(|p| p.as_str()).map(|p| (p, f)))
-        .collect();
-    
-    // Find added and modified files
-    for file in current_files {
-        if let Some(path) = file.get("path").and_then(|p| p.as_str()) {
-            if let Some(checkpoint_file) = checkpoint_map.get(path) {
-                let checkpoint_hash = checkpoint_file.get("hash").and_then(|h| h.as_str());
-                let current_hash = file.get("hash").and_then(|h| h.as_str());
-                if checkpoint_hash != current_hash {
-                    modified.push(path);
-                }
-            } else {
-                added.push(path);
+    let mut files = Vec::new();
+
+    for file in &current {
+        if file.size > MAX_FILE_BYTES || manifest.skipped.contains(&file.rel) {
+            continue;
+        }
+        if unchanged_since(manifest, file).is_some() {
+            continue;
+        }
+        let before = manifest.files.get(&file.rel);
+        let Ok(after) = fs::read(&file.abs) else {
+            continue;
+        };
+        match before {
+            Some(before) if before.hash == content_hash(&after) => {}
+            Some(before) => {
+                files.push(file_diff(&file.rel, "modified", &load_blob(db, &before.hash)?, &after));
+                modified.push(file.rel.clone());
+            }
+            None if manifest.partial => {}
+            None => {
+                files.push(file_diff(&file.rel, "added", &[], &after));
+                added.push(file.rel.clone());
             }
         }
     }
-    
-    // Find deleted files
-    for file in checkpoint_files {
-        if let Some(path) = file.get("path").and_then(|p| p.as_str()) {
-            if !current_map.contains_key(path) {
-                deleted.push(path);
-            }
+
+    let present: std::collections::HashSet<&str> = current.iter().map(|f| f.rel.as_str()).collect();
+    for (rel, file) in &manifest.files {
+        if !present.contains(rel.as_str()) {
+            files.push(file_diff(rel, "deleted", &load_blob(db, &file.hash)?, &[]));
+            deleted.push(rel.clone());
         }
     }
-    
-    json!({
+    files.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+
+    Ok(json!({
         "added": added,
         "modified": modified,
-        "deleted": deleted
+        "deleted": deleted,
+        "files": files,
+        "partial": manifest.partial
+    }))
+}
+
+fn file_diff(path: &str, status: &str, before: &[u8], after: &[u8]) -> Value {
+    let (Ok(old), Ok(new)) = (std::str::from_utf8(before), std::str::from_utf8(after)) else {
+        return json!({ "path": path, "status": status, "binary": true, "additions": 0, "deletions": 0, "diff": null });
+    };
+    let diff = TextDiff::from_lines(old, new);
+    let mut additions = 0;
+    let mut deletions = 0;
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => additions += 1,
+            ChangeTag::Delete => deletions += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    let mut unified = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+    let truncated = unified.len() > MAX_DIFF_CHARS;
+    if truncated {
+        let mut cut = MAX_DIFF_CHARS;
+        while !unified.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        unified.truncate(cut);
+    }
+    json!({
+        "path": path,
+        "status": status,
+        "binary": false,
+        "additions": additions,
+        "deletions": deletions,
+        "diff": unified,
+        "truncated": truncated
     })
 }
+
+fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn store_blob(db: &Database, bytes: &[u8]) -> Result<String, String> {
+    let hash = content_hash(bytes);
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard.execute(
+        "INSERT OR IGNORE INTO cline_checkpoint_blobs (hash, size, content) VALUES (?1, ?2, ?3)",
+        rusqlite::params![hash, bytes.len() as i64, bytes],
+    )
+    .map_err(|e| format!("Failed to store file contents: {}", e))?;
+    Ok(hash)
+}
+
+fn load_blob(db: &Database, hash: &str) -> Result<Vec<u8>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .query_row("SELECT content FROM cline_checkpoint_blobs WHERE hash = ?1", [hash], |row| row.get(0))
+        .map_err(|e| format!("Checkpoint contents {} missing: {}", hash, e))
+}
+
+fn relative_key(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn mtime_nanos(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path).map_err(|e| format!("Failed to stat {}: {}", path.display(), e))?.permissions();
+    let mode = permissions.mode();
+    let wanted = if executable { mode | ((mode & 0o444) >> 2) } else { mode & !0o111 };
+    if wanted != mode {
+        permissions.set_mode(wanted);
+        fs::set_permissions(path, permissions).map_err(|e| format!("Failed to set permissions on {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> Result<(), String> {
+    Ok(())
+}
+
+/// Remove directories left empty by a deletion, up to (not including) the workspace root
+fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checkpoint_restore_and_compare() {
+        let db_path = std::env::temp_dir().join(format!("panther-checkpoints-{}.db", Uuid::new_v4()));
+        let db = Database::new(db_path.clone()).unwrap();
+        let workspace = std::env::temp_dir().join(format!("panther-workspace-{}", Uuid::new_v4()));
+        fs::create_dir_all(workspace.join("src/nested")).unwrap();
+        fs::write(workspace.join(".gitignore"), "target/\n").unwrap();
+        fs::write(workspace.join("src/main.rs"), "fn main() {\n    println!(\"hi\");\n}\n").unwrap();
+        fs::write(workspace.join("src/nested/util.rs"), "pub fn util() {}\n").unwrap();
+        fs::create_dir_all(workspace.join("target")).unwrap();
+        fs::write(workspace.join("target/out.bin"), [0u8, 159, 146, 150]).unwrap();
+        {
+            let conn = db.get_connection();
+            conn.lock().unwrap().execute(
+                "INSERT INTO cline_runs (id, task_description, provider_id, model_name, status, workspace_path, created_at)
+                 VALUES ('run', 'task', 'p', 'm', 'running', ?1, datetime('now'))",
+                [workspace.to_string_lossy()],
+            ).unwrap();
+        }
+
+        let first = create_checkpoint(&db, "run", 0, &workspace).await.unwrap();
+        let listed = list_checkpoints(&db, "run").unwrap();
+        assert_eq!(listed[0].file_count, 3, "ignored files stay out of the snapshot");
+
+        fs::write(workspace.join("src/main.rs"), "fn main() {\n    println!(\"bye\");\n}\n").unwrap();
+        fs::remove_file(workspace.join("src/nested/util.rs")).unwrap();
+        fs::create_dir_all(workspace.join("docs")).unwrap();
+        fs::write(workspace.join("docs/notes.md"), "new\n").unwrap();
+        let second = checkpoint_before_tool(&db, "run", "tool-1", 1, "workspace_write", &workspace).await;
+        assert!(second.is_some());
+        assert!(checkpoint_before_tool(&db, "run", "tool-2", 2, "workspace_read", &workspace).await.is_none());
+
+        let diff = compare_checkpoint(&db, &first, &workspace).await.unwrap();
+        assert_eq!(diff["modified"], json!(["src/main.rs"]));
+        assert_eq!(diff["added"], json!(["docs/notes.md"]));
+        assert_eq!(diff["deleted"], json!(["src/nested/util.rs"]));
+        let main = diff["files"].as_array().unwrap().iter().find(|f| f["path"] == "src/main.rs").unwrap();
+        assert!(main["diff"].as_str().unwrap().contains("-    println!(\"hi\");\n+    println!(\"bye\");"));
+        assert_eq!((main["additions"].as_u64(), main["deletions"].as_u64()), (Some(1), Some(1)));
+
+        restore_checkpoint(&db, &first, &workspace).await.unwrap();
+        assert_eq!(fs::read_to_string(workspace.join("src/main.rs")).unwrap(), "fn main() {\n    println!(\"hi\");\n}\n");
+        assert!(workspace.join("src/nested/util.rs").exists());
+        assert!(!workspace.join("docs").exists(), "added files and their empty directories are removed");
+        assert!(workspace.join("target/out.bin").exists(), "ignored files are left alone");
+        let diff = compare_checkpoint(&db, &first, &workspace).await.unwrap();
+        assert!(diff["files"].as_array().unwrap().is_empty());
+
+        fs::remove_dir_all(&workspace).ok();
+        fs::remove_file(&db_path).ok();
+    }
+
+    #[test]
+    fn test_snapshot_rehashes_files_as_new_as_the_previous_manifest() {
+        let db_path = std::env::temp_dir().join(format!("panther-checkpoints-{}.db", Uuid::new_v4()));
+        let db = Database::new(db_path.clone()).unwrap();
+        let workspace = std::env::temp_dir().join(format!("panther-workspace-{}", Uuid::new_v4()));
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(workspace.join("a.txt"), "one\n").unwrap();
+
+        let mut previous = snapshot_workspace(&db, &workspace, None).unwrap();
+        // Rewritten within the same mtime tick: same size, same mtime, different content
+        let entry = previous.files.get_mut("a.txt").unwrap();
+        entry.hash = content_hash(b"two\n");
+        let mtime = entry.mtime;
+
+        previous.timestamp = chrono::DateTime::from_timestamp_nanos(mtime).to_rfc3339();
+        let next = snapshot_workspace(&db, &workspace, Some(&previous)).unwrap();
+        assert_eq!(next.files["a.txt"].hash, content_hash(b"one\n"), "mtime not older than the manifest is re-read");
+
+        previous.timestamp = chrono::DateTime::from_timestamp_nanos(mtime + 1_000_000_000).to_rfc3339();
+        let next = snapshot_workspace(&db, &workspace, Some(&previous)).unwrap();
+        assert_eq!(next.files["a.txt"].hash, content_hash(b"two\n"), "older files keep their recorded hash");
+
+        fs::remove_dir_all(&workspace).ok();
+        fs::remove_file(&db_path).ok();
+    }
+}
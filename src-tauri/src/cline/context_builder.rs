@@ -1,8 +1,12 @@
 // Smart context builder for workspace understanding
 
 use std::path::Path;
+use crate::cline::tools::ast_tool::{self, FileOutline};
 use crate::commands_workspace;
 
+/// Budget for the symbol outline in the system prompt (characters)
+const OUTLINE_BUDGET: usize = 16_000;
+
 pub struct ContextBuilder;
 
 impl ContextBuilder {
@@ -13,10 +17,23 @@ impl ContextBuilder {
     ) -> Result<String, String> {
         let mut context_parts = Vec::new();
         
-        // 1. File structure
-        context_parts.push("## Workspace Structure\n".to_string());
-        let structure = Self::get_file_structure(workspace_path).await?;
-        context_parts.push(structure);
+        // 1. Symbol outline of source files; plain file list when there is no supported code
+        // Walking and parsing the workspace is blocking work
+        let root = workspace_path.to_path_buf();
+        let outline = tokio::task::spawn_blocking(move || Self::get_symbol_outline(&root))
+            .await
+            .unwrap_or_default();
+        match outline {
+            Some(outline) => {
+                context_parts.push("## Workspace Outline\n".to_string());
+                context_parts.push(outline);
+            }
+            None => {
+                context_parts.push("## Workspace Structure\n".to_string());
+                let structure = Self::get_file_structure(workspace_path).await?;
+                context_parts.push(structure);
+            }
+        }
         
         // 2. Recent changes (if git available)
         if let Ok(git_status) = Self::get_git_status(workspace_path).await {
@@ -42,6 +59,37 @@ impl ContextBuilder {
         Ok(context_parts.join("\n"))
     }
     
+    /// Per source file: its top-level symbols with line ranges (methods are left to analyze_ast).
+    /// The walk stops as soon as the budget is used up.
+    fn get_symbol_outline(workspace_path: &Path) -> Option<String> {
+        let mut files = ast_tool::source_files(workspace_path).peekable();
+        files.peek()?;
+        let mut outline = String::new();
+        for file in files {
+            let relative = file.strip_prefix(workspace_path).unwrap_or(&file).to_string_lossy().replace('\\', "/");
+            let entry = match ast_tool::outline_file(&file) {
+                Ok(file_outline) => Self::format_outline(&relative, &file_outline),
+                Err(_) => format!("{}\n", relative),
+            };
+            if outline.len() + entry.len() > OUTLINE_BUDGET {
+                outline.push_str("... more source files (use search_files / analyze_ast)\n");
+                break;
+            }
+            outline.push_str(&entry);
+        }
+        Some(outline)
+    }
+
+    fn format_outline(path: &str, outline: &FileOutline) -> String {
+        let mut entry = format!("{}\n", path);
+        let mut top_level: Vec<_> = outline.symbols().filter(|s| s.parent.is_none()).collect();
+        top_level.sort_by_key(|s| s.start_line);
+        for symbol in top_level {
+            entry.push_str(&format!("  {} {} (L{}-{})\n", symbol.kind, symbol.name, symbol.start_line, symbol.end_line));
+        }
+        entry
+    }
+
     async fn get_file_structure(workspace_path: &Path) -> Result<String, String> {
         let entries = commands_workspace::list_workspace_files(
             workspace_path.to_str().map(|s| s.to_string())
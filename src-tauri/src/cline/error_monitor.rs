@@ -1,8 +1,38 @@
 // Error monitoring system for linter/compiler errors
+//
+// Each changed file maps to the checkers of its language, run once per project root (the nearest
+// directory with Cargo.toml, tsconfig.json, an ESLint config, go.mod, ...). Checkers that are not
+// installed are skipped. During an agent run the first check of a root (of a file, for checkers
+// that lint single files) is the baseline, so only diagnostics the agent introduced are reported
+// back to it.
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::time::Instant;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Longest a single checker may run
+const CHECK_TIMEOUT_SECS: u64 = 180;
+/// How far up from a file to look for its project root
+const MAX_ROOT_DEPTH: usize = 8;
+const ESLINT_CONFIGS: &[&str] = &[
+    "eslint.config.js",
+    "eslint.config.mjs",
+    "eslint.config.cjs",
+    "eslint.config.ts",
+    ".eslintrc",
+    ".eslintrc.js",
+    ".eslintrc.cjs",
+    ".eslintrc.json",
+    ".eslintrc.yml",
+    ".eslintrc.yaml",
+];
+const PYTHON_ROOTS: &[&str] = &["pyproject.toml", "ruff.toml", ".ruff.toml", "setup.cfg", "setup.py"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct LinterError {
     pub file: String,
     pub line: Option<i32>,
@@ -12,39 +42,520 @@ pub struct LinterError {
     pub code: Option<String>,
 }
 
-#[allow(dead_code)]
+impl LinterError {
+    /// Identity across edits: line numbers shift when code above changes
+    fn key(&self) -> (String, Option<String>, String) {
+        (self.file.clone(), self.code.clone(), self.message.clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Checker {
+    Cargo,
+    Tsc,
+    Eslint,
+    Ruff,
+    GoVet,
+}
+
+impl Checker {
+    fn program(self) -> &'static str {
+        match self {
+            Checker::Cargo => "cargo",
+            Checker::Tsc | Checker::Eslint => if cfg!(windows) { "npx.cmd" } else { "npx" },
+            Checker::Ruff => "ruff",
+            Checker::GoVet => "go",
+        }
+    }
+
+    /// Lints the files it is given rather than the whole project
+    fn file_level(self) -> bool {
+        matches!(self, Checker::Eslint | Checker::Ruff)
+    }
+
+    /// Arguments; file-level checkers get the files (relative to the root), project checkers check everything
+    fn args(self, files: &[String]) -> Vec<String> {
+        let fixed: &[&str] = match self {
+            Checker::Cargo => &["check", "--message-format=json", "--quiet"],
+            Checker::Tsc => &["--no-install", "tsc", "--noEmit", "--pretty", "false"],
+            Checker::Eslint => &["--no-install", "eslint", "-f", "json"],
+            Checker::Ruff => &["check", "--output-format", "json", "--exit-zero"],
+            Checker::GoVet => &["vet", "./..."],
+        };
+        let mut args: Vec<String> = fixed.iter().map(|a| a.to_string()).collect();
+        if self.file_level() {
+            if files.is_empty() {
+                args.push(".".to_string());
+            } else {
+                args.extend(files.iter().cloned());
+            }
+        }
+        args
+    }
+
+    fn parse(self, stdout: &str, stderr: &str, root: &Path) -> Vec<LinterError> {
+        match self {
+            Checker::Cargo => parse_cargo(stdout, root),
+            Checker::Tsc => parse_tsc(stdout, root),
+            Checker::Eslint => parse_eslint(stdout),
+            Checker::Ruff => parse_ruff(stdout, root),
+            Checker::GoVet => parse_go_vet(stderr, root),
+        }
+    }
+}
+
+type CheckKey = (Checker, PathBuf);
+
 pub struct ErrorMonitor {
+    workspace: PathBuf,
     errors: Vec<LinterError>,
+    /// First result per checker and project root in this monitor's lifetime; for file-level
+    /// checkers, the first result of each file, collected as files are baselined
+    baselines: HashMap<CheckKey, Vec<LinterError>>,
+    /// Files (relative to their root) already in a file-level checker's baseline
+    baselined_files: HashSet<(CheckKey, String)>,
+    /// Latest result per checker and project root
+    latest: HashMap<CheckKey, Vec<LinterError>>,
+    /// Paths written since the last check, for the debounce
+    pending_writes: Vec<PathBuf>,
+    last_write: Option<Instant>,
 }
 
-#[allow(dead_code)]
 impl ErrorMonitor {
-    pub fn new() -> Self {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
         ErrorMonitor {
+            workspace: workspace.into(),
             errors: Vec::new(),
+            baselines: HashMap::new(),
+            baselined_files: HashSet::new(),
+            latest: HashMap::new(),
+            pending_writes: Vec::new(),
+            last_write: None,
         }
     }
-    
+
     /// Check for errors in a file
-    pub async fn check_file(&mut self, _path: &str) -> Result<Vec<LinterError>, String> {
-        // TODO: Implement language-specific linter execution
-        // For now, return empty list
-        Ok(Vec::new())
+    #[allow(dead_code)]
+    pub async fn check_file(&mut self, path: &str) -> Result<Vec<LinterError>, String> {
+        let absolute = self.absolute(path);
+        let display = self.display_path(&absolute);
+        let mut found = Vec::new();
+        for (key, files) in self.group_checks(std::slice::from_ref(&absolute)) {
+            found.extend(self.run(&key, &files).await?.into_iter().filter(|e| e.file == display));
+        }
+        self.clear_file_errors(&display);
+        self.errors.extend(found.iter().cloned());
+        Ok(found)
     }
-    
+
+    /// Run every checker that applies to a project found in the workspace
+    pub async fn check_workspace(&mut self) -> Vec<LinterError> {
+        let mut errors = Vec::new();
+        for key in self.workspace_checks() {
+            match self.run(&key, &[]).await {
+                Ok(found) => errors.extend(found),
+                Err(e) => eprintln!("⚠️ {:?} in {} failed: {}", key.0, key.1.display(), e),
+            }
+        }
+        self.errors = errors.clone();
+        errors
+    }
+
+    /// Record the state before the agent touches `paths`; roots (or, for file-level checkers,
+    /// files) already recorded keep their baseline
+    pub async fn capture_baseline(&mut self, paths: &[PathBuf]) {
+        for (key, files) in self.group_checks(paths) {
+            let files: Vec<String> = if key.0.file_level() {
+                files.into_iter().filter(|f| !self.baselined_files.contains(&(key.clone(), f.clone()))).collect()
+            } else if self.baselines.contains_key(&key) {
+                Vec::new()
+            } else {
+                files
+            };
+            if files.is_empty() {
+                continue;
+            }
+            match self.run(&key, &files).await {
+                Ok(found) => {
+                    if key.0.file_level() {
+                        self.baselined_files.extend(files.into_iter().map(|f| (key.clone(), f)));
+                    }
+                    self.baselines.entry(key).or_default().extend(found);
+                }
+                Err(e) => eprintln!("⚠️ Baseline {:?} in {} failed: {}", key.0, key.1.display(), e),
+            }
+        }
+    }
+
+    /// Note a write by the agent; checks wait until writes have settled
+    pub fn record_write(&mut self, path: PathBuf) {
+        if !self.pending_writes.contains(&path) {
+            self.pending_writes.push(path);
+        }
+        self.last_write = Some(Instant::now());
+    }
+
+    /// Wait until no write was recorded for `quiet`, then re-check the written files' projects and
+    /// return diagnostics that are not in the baseline
+    pub async fn new_errors_after_writes(&mut self, quiet: Duration) -> Vec<LinterError> {
+        if let Some(last_write) = self.last_write {
+            tokio::time::sleep_until(last_write + quiet).await;
+        }
+        let paths = std::mem::take(&mut self.pending_writes);
+        self.last_write = None;
+
+        let mut new_errors = Vec::new();
+        for (key, files) in self.group_checks(&paths) {
+            let current = match self.run(&key, &files).await {
+                Ok(found) => found,
+                Err(e) => {
+                    eprintln!("⚠️ {:?} in {} failed: {}", key.0, key.1.display(), e);
+                    continue;
+                }
+            };
+            let baseline = self.baselines.get(&key).map(Vec::as_slice).unwrap_or_default();
+            new_errors.extend(diff_errors(baseline, &current));
+            self.latest.insert(key, current);
+        }
+        self.errors = self.latest.values().flatten().cloned().collect();
+        new_errors
+    }
+
     /// Get all current errors
+    #[allow(dead_code)]
     pub fn get_errors(&self) -> &[LinterError] {
         &self.errors
     }
-    
+
     /// Clear errors for a file
+    #[allow(dead_code)]
     pub fn clear_file_errors(&mut self, path: &str) {
         self.errors.retain(|e| e.file != path);
     }
+
+    #[allow(dead_code)]
+    fn absolute(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        if path.is_absolute() { path.to_path_buf() } else { self.workspace.join(path) }
+    }
+
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    }
+
+    /// Checkers and roots for `paths`, with each root's files relative to it
+    fn group_checks(&self, paths: &[PathBuf]) -> Vec<(CheckKey, Vec<String>)> {
+        let mut groups: Vec<(CheckKey, Vec<String>)> = Vec::new();
+        for path in paths {
+            for key in checks_for(path, &self.workspace) {
+                let relative = path.strip_prefix(&key.1).unwrap_or(path).to_string_lossy().replace('\\', "/");
+                match groups.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, files)) => files.push(relative),
+                    None => groups.push((key, vec![relative])),
+                }
+            }
+        }
+        groups
+    }
+
+    fn workspace_checks(&self) -> Vec<CheckKey> {
+        let mut keys = Vec::new();
+        let walker = walkdir::WalkDir::new(&self.workspace)
+            .max_depth(3)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.depth() == 0 || !(name.starts_with('.') || matches!(name.as_ref(), "node_modules" | "target" | "dist" | "build" | "vendor"))
+            });
+        for entry in walker.filter_map(|e| e.ok()).filter(|e| e.file_type().is_dir()) {
+            let dir = entry.path();
+            let mut push = |checker: Checker| keys.push((checker, dir.to_path_buf()));
+            if dir.join("Cargo.toml").is_file() {
+                push(Checker::Cargo);
+            }
+            if dir.join("tsconfig.json").is_file() {
+                push(Checker::Tsc);
+            }
+            if has_eslint_config(dir) {
+                push(Checker::Eslint);
+            }
+            if PYTHON_ROOTS.iter().any(|m| dir.join(m).is_file()) {
+                push(Checker::Ruff);
+            }
+            if dir.join("go.mod").is_file() {
+                push(Checker::GoVet);
+            }
+        }
+        // Cargo workspaces and nested packages report the same errors from every member; keep the outermost
+        keys.sort_by_key(|(_, root)| root.components().count());
+        let mut unique: Vec<CheckKey> = Vec::new();
+        for key in keys {
+            let nested = matches!(key.0, Checker::Cargo | Checker::GoVet)
+                && unique.iter().any(|(c, root)| *c == key.0 && key.1.starts_with(root));
+            if !nested {
+                unique.push(key);
+            }
+        }
+        unique
+    }
+
+    async fn run(&self, key: &CheckKey, files: &[String]) -> Result<Vec<LinterError>, String> {
+        let (checker, root) = key;
+        // New files may not exist yet (baseline) or anymore (deleted); linters fail on missing paths.
+        // No files at all means the whole project.
+        let existing: Vec<String> = files.iter().filter(|f| root.join(f).exists()).cloned().collect();
+        if checker.file_level() && !files.is_empty() && existing.is_empty() {
+            return Ok(Vec::new());
+        }
+        let files = existing;
+        let mut command = tokio::process::Command::new(checker.program());
+        command
+            .args(checker.args(&files))
+            .current_dir(root)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                eprintln!("ℹ️ {} not installed; skipping {:?}", checker.program(), checker);
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(format!("Failed to start {}: {}", checker.program(), e)),
+        };
+        let output = tokio::time::timeout(Duration::from_secs(CHECK_TIMEOUT_SECS), child.wait_with_output())
+            .await
+            .map_err(|_| format!("{:?} timed out after {}s", checker, CHECK_TIMEOUT_SECS))?
+            .map_err(|e| format!("{:?} failed: {}", checker, e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut errors = checker.parse(&stdout, &stderr, root);
+        for error in &mut errors {
+            error.file = self.display_path(Path::new(&error.file));
+        }
+        Ok(errors)
+    }
+}
+
+/// Diagnostics in `current` beyond those in `baseline` (compared as multisets, ignoring positions)
+pub fn diff_errors(baseline: &[LinterError], current: &[LinterError]) -> Vec<LinterError> {
+    let mut remaining: HashMap<_, usize> = HashMap::new();
+    for error in baseline {
+        *remaining.entry(error.key()).or_default() += 1;
+    }
+    current
+        .iter()
+        .filter(|error| match remaining.get_mut(&error.key()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+fn has_eslint_config(dir: &Path) -> bool {
+    ESLINT_CONFIGS.iter().any(|c| dir.join(c).is_file())
+        || std::fs::read_to_string(dir.join("package.json")).is_ok_and(|p| p.contains("\"eslintConfig\""))
+}
+
+/// Nearest ancestor of `path` containing one of `markers`
+fn find_root(path: &Path, markers: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    path.ancestors().skip(1).take(MAX_ROOT_DEPTH).find(|dir| markers(dir)).map(Path::to_path_buf)
+}
+
+fn checks_for(path: &Path, workspace: &Path) -> Vec<CheckKey> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+    let eslint = || find_root(path, has_eslint_config).map(|root| (Checker::Eslint, root));
+    let mut keys = Vec::new();
+    match extension.as_str() {
+        "rs" => keys.extend(find_root(path, |d| d.join("Cargo.toml").is_file()).map(|r| (Checker::Cargo, r))),
+        "ts" | "tsx" | "mts" | "cts" => {
+            keys.extend(find_root(path, |d| d.join("tsconfig.json").is_file()).map(|r| (Checker::Tsc, r)));
+            keys.extend(eslint());
+        }
+        "js" | "jsx" | "mjs" | "cjs" => keys.extend(eslint()),
+        "py" => {
+            let root = find_root(path, |d| PYTHON_ROOTS.iter().any(|m| d.join(m).is_file()))
+                .unwrap_or_else(|| if path.starts_with(workspace) { workspace.to_path_buf() } else { path.parent().unwrap_or(path).to_path_buf() });
+            keys.push((Checker::Ruff, root));
+        }
+        "go" => keys.extend(find_root(path, |d| d.join("go.mod").is_file()).map(|r| (Checker::GoVet, r))),
+        _ => {}
+    }
+    keys
 }
 
-impl Default for ErrorMonitor {
-    fn default() -> Self {
-        Self::new()
+fn as_i32(value: &Value) -> Option<i32> {
+    value.as_i64().map(|v| v as i32)
+}
+
+/// `cargo check --message-format=json`: one JSON object per line, diagnostics as `compiler-message`
+pub fn parse_cargo(stdout: &str, root: &Path) -> Vec<LinterError> {
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|msg| msg["reason"] == "compiler-message")
+        .filter_map(|msg| {
+            let message = &msg["message"];
+            let level = message["level"].as_str()?;
+            if !matches!(level, "error" | "warning") {
+                return None;
+            }
+            let spans = message["spans"].as_array()?;
+            // Summaries like "aborting due to 2 previous errors" have no span
+            let span = spans.iter().find(|s| s["is_primary"] == true).or_else(|| spans.first())?;
+            // Paths are relative to the workspace root, which `cargo metadata` would give; the
+            // manifest path of the package is the next best anchor
+            let file_name = span["file_name"].as_str()?;
+            let manifest_dir = msg["manifest_path"].as_str().and_then(|m| Path::new(m).parent()).unwrap_or(root);
+            let file = [manifest_dir, root]
+                .iter()
+                .map(|base| base.join(file_name))
+                .find(|p| p.exists())
+                .unwrap_or_else(|| root.join(file_name));
+            Some(LinterError {
+                file: file.to_string_lossy().to_string(),
+                line: as_i32(&span["line_start"]),
+                column: as_i32(&span["column_start"]),
+                message: message["message"].as_str().unwrap_or_default().to_string(),
+                severity: level.to_string(),
+                code: message["code"]["code"].as_str().map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// `tsc --pretty false`: `src/a.ts(3,7): error TS2322: Type 'string' is not assignable ...`
+pub fn parse_tsc(output: &str, root: &Path) -> Vec<LinterError> {
+    let pattern = regex::Regex::new(r"^(.+?)\((\d+),(\d+)\): (error|warning) (TS\d+): (.*)$").unwrap();
+    output
+        .lines()
+        .filter_map(|line| pattern.captures(line.trim_end()))
+        .map(|c| LinterError {
+            file: root.join(&c[1]).to_string_lossy().to_string(),
+            line: c[2].parse().ok(),
+            column: c[3].parse().ok(),
+            message: c[6].to_string(),
+            severity: c[4].to_string(),
+            code: Some(c[5].to_string()),
+        })
+        .collect()
+}
+
+/// `eslint -f json`: `[{ filePath, messages: [{ line, column, severity: 1|2, message, ruleId }] }]`
+pub fn parse_eslint(output: &str) -> Vec<LinterError> {
+    let Ok(Value::Array(files)) = serde_json::from_str::<Value>(output.trim()) else { return Vec::new() };
+    files
+        .iter()
+        .flat_map(|file| {
+            let path = file["filePath"].as_str().unwrap_or_default().to_string();
+            file["messages"].as_array().cloned().unwrap_or_default().into_iter().map(move |m| LinterError {
+                file: path.clone(),
+                line: as_i32(&m["line"]),
+                column: as_i32(&m["column"]),
+                message: m["message"].as_str().unwrap_or_default().to_string(),
+                severity: if m["severity"] == 2 { "error" } else { "warning" }.to_string(),
+                code: m["ruleId"].as_str().map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// `ruff check --output-format json`: `[{ filename, location: { row, column }, code, message }]`.
+/// Findings without a rule code are syntax errors.
+pub fn parse_ruff(output: &str, root: &Path) -> Vec<LinterError> {
+    let Ok(Value::Array(findings)) = serde_json::from_str::<Value>(output.trim()) else { return Vec::new() };
+    findings
+        .iter()
+        .map(|f| {
+            let code = f["code"].as_str().map(String::from);
+            LinterError {
+                file: root.join(f["filename"].as_str().unwrap_or_default()).to_string_lossy().to_string(),
+                line: as_i32(&f["location"]["row"]),
+                column: as_i32(&f["location"]["column"]),
+                message: f["message"].as_str().unwrap_or_default().to_string(),
+                severity: if code.as_deref().is_none_or(|c| c == "E999") { "error" } else { "warning" }.to_string(),
+                code,
+            }
+        })
+        .collect()
+}
+
+/// `go vet` reports on stderr: `./main.go:10:2: message`. Type-check failures come prefixed
+/// with `vet: ` and mean the package does not compile.
+pub fn parse_go_vet(stderr: &str, root: &Path) -> Vec<LinterError> {
+    let pattern = regex::Regex::new(r"^(vet: )?(.+?\.go):(\d+):(\d+): (.*)$").unwrap();
+    stderr
+        .lines()
+        .filter_map(|line| pattern.captures(line.trim_end()))
+        .map(|c| LinterError {
+            file: root.join(&c[2]).to_string_lossy().to_string(),
+            line: c[3].parse().ok(),
+            column: c[4].parse().ok(),
+            message: c[5].to_string(),
+            severity: if c.get(1).is_some() { "error" } else { "warning" }.to_string(),
+            code: None,
+        })
+        .collect()
+}
+
+/// Diagnostics as the agent sees them in the next message
+pub fn format_for_agent(errors: &[LinterError], limit: usize) -> String {
+    let mut lines: Vec<String> = errors
+        .iter()
+        .take(limit)
+        .map(|e| {
+            let position = match (e.line, e.column) {
+                (Some(line), Some(column)) => format!(":{}:{}", line, column),
+                (Some(line), None) => format!(":{}", line),
+                _ => String::new(),
+            };
+            let code = e.code.as_deref().map(|c| format!("[{}]", c)).unwrap_or_default();
+            format!("- {}{} {}{}: {}", e.file, position, e.severity, code, e.message)
+        })
+        .collect();
+    if errors.len() > limit {
+        lines.push(format!("- ... and {} more", errors.len() - limit));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsers_and_baseline_diff() {
+        let root = Path::new("/ws");
+        let cargo = r#"{"reason":"compiler-artifact","target":{}}
+{"reason":"compiler-message","manifest_path":"/ws/Cargo.toml","message":{"level":"error","message":"mismatched types","code":{"code":"E0308"},"spans":[{"file_name":"src/main.rs","line_start":4,"column_start":9,"is_primary":true}]}}
+{"reason":"compiler-message","manifest_path":"/ws/Cargo.toml","message":{"level":"error","message":"aborting due to 1 previous error","code":null,"spans":[]}}"#;
+        let errors = parse_cargo(cargo, root);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].file.as_str(), errors[0].line, errors[0].code.as_deref()), ("/ws/src/main.rs", Some(4), Some("E0308")));
+
+        let tsc = parse_tsc("src/a.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n", root);
+        assert_eq!((tsc[0].file.as_str(), tsc[0].column, tsc[0].code.as_deref()), ("/ws/src/a.ts", Some(7), Some("TS2322")));
+
+        let eslint = parse_eslint(r#"[{"filePath":"/ws/a.js","messages":[{"line":1,"column":5,"severity":1,"message":"'x' is unused","ruleId":"no-unused-vars"}]}]"#);
+        assert_eq!((eslint[0].severity.as_str(), eslint[0].code.as_deref()), ("warning", Some("no-unused-vars")));
+
+        let ruff = parse_ruff(r#"[{"filename":"/ws/app.py","location":{"row":2,"column":1},"code":null,"message":"SyntaxError: unexpected indent"}]"#, root);
+        assert_eq!((ruff[0].file.as_str(), ruff[0].severity.as_str()), ("/ws/app.py", "error"));
+
+        let vet = parse_go_vet("# example\nvet: ./main.go:5:2: undefined: foo\n./util.go:9:3: unreachable code\n", root);
+        assert_eq!(vet.len(), 2);
+        assert_eq!((vet[0].severity.as_str(), vet[1].severity.as_str()), ("error", "warning"));
+
+        // An existing error that moved lines is not new; a second copy of it is
+        let mut moved = errors[0].clone();
+        moved.line = Some(10);
+        let new = diff_errors(&errors, &[moved.clone(), moved, tsc[0].clone()]);
+        assert_eq!(new.len(), 2);
+        assert!(format_for_agent(&new, 1).ends_with("... and 1 more"));
     }
 }
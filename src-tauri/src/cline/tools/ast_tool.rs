@@ -1,32 +1,659 @@
-// AST analysis tool for code structure understanding
+// AST analysis tool for code structure understanding (tree-sitter)
+//
+// Supports Rust, TypeScript/TSX, JavaScript, Python and Go. `analyze_ast` outlines one file;
+// `find_symbol` looks a name up across files (definitions from the outlines, references from
+// identifier nodes). `ContextBuilder` reuses `outline_file` and `source_files` for the workspace outline.
 
 use crate::tools::ToolResult;
-use std::path::Path;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
 
-/// Analyze AST of a file to extract code structure
-pub async fn analyze_ast(path: &str) -> ToolResult {
-    // TODO: Implement AST analysis using tree-sitter
-    // For now, return placeholder
-    let file_path = Path::new(path);
-    let extension: &str = file_path.extension()
-        .and_then(|ext: &std::ffi::OsStr| ext.to_str())
-        .unwrap_or("unknown");
-    
-    // Placeholder response
-    let structure = serde_json::json!({
-        "path": path,
-        "language": extension,
-        "functions": [],
-        "classes": [],
-        "imports": [],
-        "exports": [],
-        "note": "AST analysis not yet implemented - requires tree-sitter integration"
-    });
-    
-    crate::tools::ToolResult {
+/// Files larger than this are skipped (generated bundles, lockfiles)
+const MAX_SOURCE_BYTES: u64 = 1_000_000;
+/// Directories never worth parsing
+const SKIP_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "out", "vendor", "venv", "__pycache__"];
+const MAX_REFERENCES: usize = 200;
+/// Files visited by one workspace walk before it stops
+const MAX_WALK_FILES: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    Rust,
+    TypeScript,
+    Tsx,
+    JavaScript,
+    Python,
+    Go,
+}
+
+impl Lang {
+    pub fn from_path(path: &Path) -> Option<Lang> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "rs" => Some(Lang::Rust),
+            "ts" | "mts" | "cts" => Some(Lang::TypeScript),
+            "tsx" => Some(Lang::Tsx),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Lang::JavaScript),
+            "py" | "pyi" => Some(Lang::Python),
+            "go" => Some(Lang::Go),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Lang::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Lang::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Lang::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Lang::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Lang::Python => tree_sitter_python::LANGUAGE.into(),
+            Lang::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    /// function, method, macro, struct, enum, trait, class, interface, type, module, impl
+    pub kind: String,
+    /// Declaration up to its body, whitespace collapsed
+    pub signature: String,
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Enclosing class / impl / receiver type for methods
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    pub exported: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Import {
+    pub text: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileOutline {
+    pub path: String,
+    pub language: Lang,
+    pub functions: Vec<Symbol>,
+    /// Structs, enums, traits, classes, interfaces, type aliases and modules
+    pub classes: Vec<Symbol>,
+    pub impls: Vec<Symbol>,
+    pub imports: Vec<Import>,
+    pub exports: Vec<String>,
+    /// The parser recovered from syntax errors; the outline may be incomplete
+    pub has_errors: bool,
+}
+
+impl FileOutline {
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.functions.iter().chain(&self.classes).chain(&self.impls)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reference {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    /// The source line, trimmed
+    pub text: String,
+    pub is_definition: bool,
+}
+
+fn parse(source: &str, lang: Lang) -> Result<tree_sitter::Tree, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&lang.grammar())
+        .map_err(|e| format!("Failed to load {:?} grammar: {}", lang, e))?;
+    parser.parse(source, None).ok_or_else(|| "Parser returned no tree".to_string())
+}
+
+/// Outline of one source file
+pub fn outline_source(path: &str, source: &str, lang: Lang) -> Result<FileOutline, String> {
+    let tree = parse(source, lang)?;
+    let root = tree.root_node();
+    let mut outline = FileOutline {
+        path: path.to_string(),
+        language: lang,
+        functions: Vec::new(),
+        classes: Vec::new(),
+        impls: Vec::new(),
+        imports: Vec::new(),
+        exports: Vec::new(),
+        has_errors: root.has_error(),
+    };
+    let mut walker = Walker { source, lang, outline: &mut outline };
+    walker.visit_children(root, None, false);
+
+    // Python and Go have no export syntax: public is a naming convention
+    if matches!(lang, Lang::Python | Lang::Go) {
+        let top_level: Vec<String> = outline
+            .symbols()
+            .filter(|s| s.parent.is_none() && s.exported)
+            .map(|s| s.name.clone())
+            .collect();
+        outline.exports = top_level;
+    }
+    outline.exports.dedup();
+    Ok(outline)
+}
+
+pub fn outline_file(path: &Path) -> Result<FileOutline, String> {
+    let lang = Lang::from_path(path).ok_or_else(|| format!("Unsupported language: {}", path.display()))?;
+    let source = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    outline_source(&path.to_string_lossy(), &source, lang)
+}
+
+struct Walker<'a> {
+    source: &'a str,
+    lang: Lang,
+    outline: &'a mut FileOutline,
+}
+
+impl<'a> Walker<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        node.utf8_text(self.source.as_bytes()).unwrap_or_default()
+    }
+
+    fn field_text(&self, node: Node, field: &str) -> Option<String> {
+        node.child_by_field_name(field).map(|n| self.text(n).to_string())
+    }
+
+    /// Declaration text before the body, on one line
+    fn signature(&self, node: Node) -> String {
+        let end = node
+            .child_by_field_name("body")
+            .map(|b| b.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let raw = &self.source[node.start_byte()..end];
+        let collapsed = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        let trimmed = collapsed.trim_end_matches(['{', ':', '=']).trim_end();
+        if trimmed.chars().count() > 200 {
+            format!("{}…", trimmed.chars().take(200).collect::<String>())
+        } else {
+            trimmed.to_string()
+        }
+    }
+
+    fn symbol(&self, node: Node, name: String, kind: &str, parent: Option<&str>, exported: bool) -> Symbol {
+        Symbol {
+            signature: self.signature(node),
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            parent: parent.map(String::from),
+            exported,
+            kind: kind.to_string(),
+            name,
+        }
+    }
+
+    fn import(&mut self, node: Node) {
+        let text = self.text(node).split_whitespace().collect::<Vec<_>>().join(" ");
+        self.outline.imports.push(Import { text, line: node.start_position().row + 1 });
+    }
+
+    fn visit_children(&mut self, node: Node, parent: Option<&str>, exported: bool) {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            self.visit(child, parent, exported);
+        }
+    }
+
+    fn visit(&mut self, node: Node, parent: Option<&str>, exported: bool) {
+        match self.lang {
+            Lang::Rust => self.visit_rust(node, parent),
+            Lang::TypeScript | Lang::Tsx | Lang::JavaScript => self.visit_js(node, parent, exported),
+            Lang::Python => self.visit_python(node, parent),
+            Lang::Go => self.visit_go(node),
+        }
+    }
+
+    fn visit_rust(&mut self, node: Node, parent: Option<&str>) {
+        let kind = match node.kind() {
+            "function_item" | "function_signature_item" => Some(if parent.is_some() { "method" } else { "function" }),
+            "macro_definition" => Some("macro"),
+            "struct_item" => Some("struct"),
+            "enum_item" => Some("enum"),
+            "union_item" => Some("union"),
+            "trait_item" => Some("trait"),
+            "type_item" => Some("type"),
+            "mod_item" => Some("module"),
+            _ => None,
+        };
+        match (node.kind(), kind) {
+            ("use_declaration" | "extern_crate_declaration", _) => self.import(node),
+            ("impl_item", _) => {
+                let ty = self.field_text(node, "type").unwrap_or_default();
+                let name = match self.field_text(node, "trait") {
+                    Some(tr) => format!("{} for {}", tr, ty),
+                    None => ty.clone(),
+                };
+                let symbol = self.symbol(node, name, "impl", None, false);
+                self.outline.impls.push(symbol);
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.visit_children(body, Some(&ty), false);
+                }
+            }
+            (_, Some(kind)) => {
+                let Some(name) = self.field_text(node, "name") else { return };
+                let exported = has_child_kind(node, "visibility_modifier");
+                if exported && parent.is_none() {
+                    self.outline.exports.push(name.clone());
+                }
+                let symbol = self.symbol(node, name.clone(), kind, parent, exported);
+                match kind {
+                    "function" | "method" | "macro" => self.outline.functions.push(symbol),
+                    _ => self.outline.classes.push(symbol),
+                }
+                // Trait bodies hold method signatures; module bodies hold more items
+                if let Some(body) = node.child_by_field_name("body") {
+                    match kind {
+                        "trait" => self.visit_children(body, Some(&name), false),
+                        "module" => self.visit_children(body, None, false),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_js(&mut self, node: Node, parent: Option<&str>, exported: bool) {
+        match node.kind() {
+            "import_statement" => self.import(node),
+            "export_statement" => {
+                if node.child_by_field_name("declaration").is_none() && node.child_by_field_name("value").is_none() {
+                    // export { a, b as c } [from '...']
+                    let mut names = Vec::new();
+                    collect_kind(node, "export_specifier", &mut names);
+                    for spec in names {
+                        let name = spec
+                            .child_by_field_name("alias")
+                            .or_else(|| spec.child_by_field_name("name"))
+                            .map(|n| self.text(n).to_string());
+                        self.outline.exports.extend(name);
+                    }
+                    if self.text(node).contains('*') {
+                        self.outline.exports.push("*".to_string());
+                    }
+                }
+                if self.text(node).starts_with("export default") {
+                    self.outline.exports.push("default".to_string());
+                }
+                self.visit_children(node, parent, true);
+            }
+            "function_declaration" | "generator_function_declaration" | "function_signature" => {
+                self.js_named(node, if parent.is_some() { "method" } else { "function" }, parent, exported)
+            }
+            "class_declaration" | "abstract_class_declaration" | "class" => {
+                let name = self.field_text(node, "name").unwrap_or_else(|| "default".to_string());
+                self.push_js_symbol(node, name.clone(), "class", None, exported);
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.visit_children(body, Some(&name), false);
+                }
+            }
+            "method_definition" | "method_signature" | "abstract_method_signature" => {
+                self.js_named(node, "method", parent, false)
+            }
+            "interface_declaration" => self.js_named(node, "interface", None, exported),
+            "type_alias_declaration" => self.js_named(node, "type", None, exported),
+            "enum_declaration" => self.js_named(node, "enum", None, exported),
+            "internal_module" | "module" => self.js_named(node, "module", None, exported),
+            "lexical_declaration" | "variable_declaration" => {
+                // const handler = (...) => {...} / function (...) {...}
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node.named_children(&mut cursor).collect();
+                for declarator in declarators.into_iter().filter(|d| d.kind() == "variable_declarator") {
+                    let Some(value) = declarator.child_by_field_name("value") else { continue };
+                    if !matches!(value.kind(), "arrow_function" | "function_expression" | "function" | "generator_function") {
+                        continue;
+                    }
+                    let Some(name) = self.field_text(declarator, "name") else { continue };
+                    let keyword = self.text(node).split_whitespace().next().unwrap_or("const");
+                    let mut symbol = self.symbol(value, name.clone(), "function", parent, exported);
+                    symbol.start_line = node.start_position().row + 1;
+                    symbol.signature = format!("{} {} = {}", keyword, name, self.signature(value));
+                    if exported {
+                        self.outline.exports.push(name);
+                    }
+                    self.outline.functions.push(symbol);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn js_named(&mut self, node: Node, kind: &str, parent: Option<&str>, exported: bool) {
+        if let Some(name) = self.field_text(node, "name") {
+            self.push_js_symbol(node, name, kind, parent, exported);
+        }
+    }
+
+    fn push_js_symbol(&mut self, node: Node, name: String, kind: &str, parent: Option<&str>, exported: bool) {
+        if exported {
+            self.outline.exports.push(name.clone());
+        }
+        let symbol = self.symbol(node, name, kind, parent, exported);
+        match kind {
+            "function" | "method" => self.outline.functions.push(symbol),
+            _ => self.outline.classes.push(symbol),
+        }
+    }
+
+    fn visit_python(&mut self, node: Node, parent: Option<&str>) {
+        match node.kind() {
+            "import_statement" | "import_from_statement" | "future_import_statement" => self.import(node),
+            "decorated_definition" => {
+                if let Some(definition) = node.child_by_field_name("definition") {
+                    self.visit_python(definition, parent);
+                    // Include decorators in the line range
+                    let target = if definition.kind() == "class_definition" {
+                        self.outline.classes.last_mut()
+                    } else {
+                        self.outline.functions.last_mut()
+                    };
+                    if let Some(symbol) = target {
+                        symbol.start_line = node.start_position().row + 1;
+                    }
+                }
+            }
+            "function_definition" => {
+                let Some(name) = self.field_text(node, "name") else { return };
+                let kind = if parent.is_some() { "method" } else { "function" };
+                let exported = !name.starts_with('_');
+                let symbol = self.symbol(node, name, kind, parent, exported);
+                self.outline.functions.push(symbol);
+            }
+            "class_definition" => {
+                let Some(name) = self.field_text(node, "name") else { return };
+                let exported = !name.starts_with('_');
+                let symbol = self.symbol(node, name.clone(), "class", parent, exported);
+                self.outline.classes.push(symbol);
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.visit_children(body, Some(&name), false);
+                }
+            }
+            // Definitions inside `if TYPE_CHECKING:` / try blocks at module level
+            "if_statement" | "try_statement" | "block" | "else_clause" | "except_clause" if parent.is_none() => {
+                self.visit_children(node, None, false)
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_go(&mut self, node: Node) {
+        let exported = |name: &str| name.chars().next().is_some_and(|c| c.is_uppercase());
+        match node.kind() {
+            "import_declaration" => {
+                let mut specs = Vec::new();
+                collect_kind(node, "import_spec", &mut specs);
+                for spec in specs {
+                    self.import(spec);
+                }
+            }
+            "function_declaration" => {
+                let Some(name) = self.field_text(node, "name") else { return };
+                let symbol = self.symbol(node, name.clone(), "function", None, exported(&name));
+                self.outline.functions.push(symbol);
+            }
+            "method_declaration" => {
+                let Some(name) = self.field_text(node, "name") else { return };
+                // func (s *Server) Start() -> receiver type "Server"
+                let mut receiver_types = Vec::new();
+                if let Some(receiver) = node.child_by_field_name("receiver") {
+                    collect_kind(receiver, "type_identifier", &mut receiver_types);
+                }
+                let receiver = receiver_types.first().map(|t| self.text(*t).to_string());
+                let symbol = self.symbol(node, name.clone(), "method", receiver.as_deref(), exported(&name));
+                self.outline.functions.push(symbol);
+            }
+            "type_declaration" => {
+                let mut cursor = node.walk();
+                let specs: Vec<Node> = node.named_children(&mut cursor).collect();
+                for spec in specs.into_iter().filter(|s| matches!(s.kind(), "type_spec" | "type_alias")) {
+                    let Some(name) = self.field_text(spec, "name") else { continue };
+                    let kind = match spec.child_by_field_name("type").map(|t| t.kind()) {
+                        Some("struct_type") => "struct",
+                        Some("interface_type") => "interface",
+                        _ => "type",
+                    };
+                    let mut symbol = self.symbol(spec, name.clone(), kind, None, exported(&name));
+                    symbol.signature = format!("type {}", symbol.signature);
+                    self.outline.classes.push(symbol);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn has_child_kind(node: Node, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.named_children(&mut cursor).any(|c| c.kind() == kind);
+    found
+}
+
+fn collect_kind<'t>(node: Node<'t>, kind: &str, out: &mut Vec<Node<'t>>) {
+    if node.kind() == kind {
+        out.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_kind(child, kind, out);
+    }
+}
+
+/// Identifier occurrences of `name` in a file
+pub fn find_references_in_source(path: &str, source: &str, lang: Lang, name: &str) -> Result<Vec<Reference>, String> {
+    let tree = parse(source, lang)?;
+    let outline = outline_source(path, source, lang)?;
+    let lines: Vec<&str> = source.lines().collect();
+
+    let mut references = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.child_count() == 0 {
+            if node.kind().ends_with("identifier") && node.utf8_text(source.as_bytes()) == Ok(name) {
+                let row = node.start_position().row;
+                // A definition's name node is its parent's `name` field, inside an outlined symbol of that name
+                let is_definition = node
+                    .parent()
+                    .and_then(|p| p.child_by_field_name("name"))
+                    .is_some_and(|n| n.id() == node.id())
+                    && outline.symbols().any(|s| s.name == name && s.start_line <= row + 1 && row < s.end_line);
+                references.push(Reference {
+                    path: path.to_string(),
+                    line: row + 1,
+                    column: node.start_position().column + 1,
+                    text: lines.get(row).map(|l| l.trim().to_string()).unwrap_or_default(),
+                    is_definition,
+                });
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    Ok(references)
+}
+
+/// Supported source files under `root` in path order, skipping gitignored, hidden, dependency and
+/// build directories. The walk is lazy (callers can stop early) and visits at most `MAX_WALK_FILES` files.
+pub fn source_files(root: &Path) -> impl Iterator<Item = PathBuf> {
+    ignore::WalkBuilder::new(root)
+        .git_global(false)
+        .require_git(false)
+        .follow_links(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|e| {
+            !(e.file_type().is_some_and(|t| t.is_dir()) && SKIP_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .take(MAX_WALK_FILES)
+        .filter(|e| Lang::from_path(e.path()).is_some())
+        .filter(|e| e.metadata().map(|m| m.len() <= MAX_SOURCE_BYTES).unwrap_or(false))
+        .map(|e| e.into_path())
+}
+
+/// Relative paths are tried against the workspace first, then the home directory like other workspace tools
+fn resolve_source_path(path: &str, workspace_path: &str) -> PathBuf {
+    let candidate = Path::new(workspace_path).join(path);
+    if Path::new(path).is_absolute() || candidate.exists() {
+        return candidate;
+    }
+    PathBuf::from(crate::cline::agent_loop::resolve_workspace_path(path))
+}
+
+fn json_result(value: serde_json::Value) -> ToolResult {
+    ToolResult {
         success: true,
-        output: serde_json::to_string_pretty(&structure).unwrap_or_default(),
+        output: serde_json::to_string_pretty(&value).unwrap_or_default(),
         error: None,
-        extra_json: Some(structure),
+        extra_json: Some(value),
+    }
+}
+
+/// Analyze AST of a file to extract code structure
+pub async fn analyze_ast(path: &str, workspace_path: &str) -> ToolResult {
+    let file_path = resolve_source_path(path, workspace_path);
+    match outline_file(&file_path) {
+        Ok(mut outline) => {
+            outline.path = path.to_string();
+            json_result(serde_json::to_value(&outline).unwrap_or_default())
+        }
+        Err(e) => ToolResult::err(e),
+    }
+}
+
+/// Find where `name` is defined and referenced, in one file or across a directory
+/// (default: the workspace)
+pub async fn find_symbol(name: &str, path: Option<&str>, workspace_path: &str) -> ToolResult {
+    let root = match path {
+        Some(p) => resolve_source_path(p, workspace_path),
+        None => PathBuf::from(workspace_path),
+    };
+    // Walking and parsing the tree is blocking work
+    let name = name.to_string();
+    match tokio::task::spawn_blocking(move || lookup_symbol(&name, root)).await {
+        Ok(value) => json_result(value),
+        Err(e) => ToolResult::err(format!("Symbol search failed: {}", e)),
+    }
+}
+
+fn lookup_symbol(name: &str, root: PathBuf) -> serde_json::Value {
+    let files = if root.is_file() { vec![root.clone()] } else { source_files(&root).collect() };
+    let base = if root.is_file() { root.parent().map(Path::to_path_buf).unwrap_or_default() } else { root.clone() };
+
+    let mut definitions = Vec::new();
+    let mut references = Vec::new();
+    let mut truncated = false;
+    for file in files {
+        let Some(lang) = Lang::from_path(&file) else { continue };
+        let Ok(source) = std::fs::read_to_string(&file) else { continue };
+        // Cheap filter before parsing
+        if !source.contains(name) {
+            continue;
+        }
+        let display = file.strip_prefix(&base).unwrap_or(&file).to_string_lossy().replace('\\', "/");
+        if let Ok(outline) = outline_source(&display, &source, lang) {
+            definitions.extend(
+                outline
+                    .symbols()
+                    .filter(|s| s.name == name)
+                    .map(|s| serde_json::json!({ "path": display, "symbol": s })),
+            );
+        }
+        if let Ok(found) = find_references_in_source(&display, &source, lang, name) {
+            references.extend(found);
+        }
+        if references.len() > MAX_REFERENCES {
+            references.truncate(MAX_REFERENCES);
+            truncated = true;
+            break;
+        }
+    }
+
+    serde_json::json!({
+        "name": name,
+        "definitions": definitions,
+        "references": references,
+        "truncated": truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outlines_per_language() {
+        let rust = "use std::fmt;\n\npub struct Point { x: i32 }\n\nimpl fmt::Display for Point {\n    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n        write!(f, \"{}\", self.x)\n    }\n}\n\nfn helper(a: i32) -> i32 {\n    a\n}\n";
+        let outline = outline_source("p.rs", rust, Lang::Rust).unwrap();
+        assert_eq!(outline.imports[0].text, "use std::fmt;");
+        assert_eq!(outline.classes[0].name, "Point");
+        assert_eq!(outline.impls[0].name, "fmt::Display for Point");
+        let fmt = outline.functions.iter().find(|f| f.name == "fmt").unwrap();
+        assert_eq!((fmt.kind.as_str(), fmt.parent.as_deref(), fmt.start_line, fmt.end_line), ("method", Some("Point"), 6, 8));
+        let helper = outline.functions.iter().find(|f| f.name == "helper").unwrap();
+        assert_eq!(helper.signature, "fn helper(a: i32) -> i32");
+        assert_eq!(outline.exports, vec!["Point"]);
+
+        let ts = "import { x } from './x';\nexport interface Props { a: string }\nexport class Widget {\n  render(): void {}\n}\nexport const useThing = (a: number) => a * 2;\nfunction local() {}\n";
+        let outline = outline_source("w.ts", ts, Lang::TypeScript).unwrap();
+        assert_eq!(outline.imports.len(), 1);
+        assert_eq!(outline.exports, vec!["Props", "Widget", "useThing"]);
+        assert!(outline.functions.iter().any(|f| f.name == "render" && f.parent.as_deref() == Some("Widget")));
+        assert!(outline.functions.iter().any(|f| f.name == "local" && !f.exported));
+
+        let py = "import os\n\nclass Store:\n    @property\n    def size(self):\n        return 0\n\ndef _private():\n    pass\n";
+        let outline = outline_source("s.py", py, Lang::Python).unwrap();
+        let size = outline.functions.iter().find(|f| f.name == "size").unwrap();
+        assert_eq!((size.parent.as_deref(), size.start_line), (Some("Store"), 4));
+        assert_eq!(outline.exports, vec!["Store"]);
+
+        let go = "package main\n\nimport (\n\t\"fmt\"\n)\n\ntype Server struct{}\n\nfunc (s *Server) Start() error { return nil }\n\nfunc main() { fmt.Println() }\n";
+        let outline = outline_source("m.go", go, Lang::Go).unwrap();
+        assert_eq!(outline.imports[0].text, "\"fmt\"");
+        assert_eq!(outline.classes[0].kind, "struct");
+        let start = outline.functions.iter().find(|f| f.name == "Start").unwrap();
+        assert_eq!(start.parent.as_deref(), Some("Server"));
+        assert_eq!(outline.exports, vec!["Server"]);
+    }
+
+    #[test]
+    fn test_find_references_marks_definition() {
+        let source = "fn helper() {}\n\nfn main() {\n    helper();\n    let helper_two = 1;\n}\n";
+        let refs = find_references_in_source("m.rs", source, Lang::Rust, "helper").unwrap();
+        assert_eq!(refs.len(), 2);
+        assert!(refs[0].is_definition && refs[0].line == 1);
+        assert!(!refs[1].is_definition && refs[1].text == "helper();");
+    }
+
+    #[test]
+    fn test_source_files_honours_gitignore() {
+        let root = std::env::temp_dir().join(format!("panther-ast-{}", uuid::Uuid::new_v4()));
+        for dir in ["src", "generated", "node_modules/pkg", ".cache"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["src/main.rs", "src/b.py", "src/notes.txt", "generated/api.ts", "node_modules/pkg/index.js", ".cache/c.go"] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+
+        let files: Vec<String> = source_files(&root)
+            .map(|f| f.strip_prefix(&root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        assert_eq!(files, ["src/b.py", "src/main.rs"]);
+        let _ = std::fs::remove_dir_all(root);
     }
 }
@@ -1,16 +1,40 @@
 // MCP (Model Context Protocol) tool integration
 
+use crate::mcp::{self, protocol};
 use crate::tools::ToolResult;
-use serde_json::Value;
+use serde_json::{json, Value};
 
-/// Execute MCP tool call
+/// Execute MCP tool call on a configured server. `tool` may also be `resources/read`
+/// with `{"uri": ...}` params to read a resource.
 pub async fn execute_mcp_tool(server: &str, tool: &str, params: &Value) -> ToolResult {
-    // TODO: Implement MCP protocol client
-    // For now, return placeholder
-    crate::tools::ToolResult::ok(format!(
-        "MCP tool call: server={}, tool={}, params={}",
-        server,
-        tool,
-        serde_json::to_string(params).unwrap_or_default()
-    ))
+    let client = match mcp::client(server).await {
+        Ok(client) => client,
+        Err(e) => return ToolResult::err(e),
+    };
+
+    let outcome = if tool == "resources/read" {
+        match params.get("uri").and_then(|u| u.as_str()) {
+            Some(uri) => client.read_resource(uri).await.map(|result| (result, false)),
+            None => return ToolResult::err("resources/read needs a 'uri' param".to_string()),
+        }
+    } else {
+        client.call_tool(tool, params.clone()).await.map(|result| {
+            let is_error = result.get("isError").and_then(|v| v.as_bool()).unwrap_or(false);
+            (result, is_error)
+        })
+    };
+
+    match outcome {
+        Ok((result, is_error)) => ToolResult {
+            success: !is_error,
+            output: protocol::result_text(&result),
+            error: is_error.then(|| format!("MCP tool {}/{} reported an error", server, tool)),
+            extra_json: Some(json!({ "server": server, "tool": tool, "result": result })),
+        },
+        Err(e) => {
+            // The connection may be dead (server exited); reconnect on the next call
+            mcp::disconnect(server).await;
+            ToolResult::err(e)
+        }
+    }
 }
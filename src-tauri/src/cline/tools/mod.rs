@@ -121,6 +121,10 @@ pub enum ClineToolRequest {
     AnalyzeAST {
         path: String,
     },
+    FindSymbol {
+        name: String,
+        path: Option<String>, // File or directory; defaults to the workspace
+    },
     SearchFiles {
         pattern: String,
         regex: bool,
@@ -190,7 +194,7 @@ pub mod search_tool;
 pub mod mcp_tool;
 
 pub use browser_tool::execute_browser_tool;
-pub use ast_tool::analyze_ast;
+pub use ast_tool::{analyze_ast, find_symbol};
 pub use search_tool::{search_files, search_code};
 pub use mcp_tool::execute_mcp_tool;
 
@@ -286,7 +290,10 @@ pub async fn execute_cline_tool(
         
         // Code analysis
         ClineToolRequest::AnalyzeAST { path } => {
-            analyze_ast(&path).await
+            analyze_ast(&path, workspace_path).await
+        }
+        ClineToolRequest::FindSymbol { name, path } => {
+            find_symbol(&name, path.as_deref(), workspace_path).await
         }
         ClineToolRequest::SearchFiles { pattern, regex, include_system } => {
             execute_enhanced_search(pattern, regex, include_system, workspace_path).await
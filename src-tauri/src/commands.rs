@@ -7,6 +7,8 @@ use crate::keychain::Keychain;
 use crate::providers::get_adapter;
 use crate::types::ProviderAccount;
 use crate::orchestrator::Orchestrator;
+use crate::run_state::{self, RunStatus};
+use crate::tournament::{RankedAnswer, TournamentConfig};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use uuid::Uuid;
@@ -783,14 +785,20 @@ pub async fn start_run(
     orchestrator: State<'_, Orchestrator>,
     run_id: String,
 ) -> Result<(), String> {
+    start_run_impl(&db, orchestrator.inner(), run_id).await
+}
+
+pub async fn start_run_impl(db: &Database, orchestrator: &Orchestrator, run_id: String) -> Result<(), String> {
     // Check if run is already running or complete
-    let conn = db.get_connection();
-    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    let status: Result<String, _> = conn_guard.query_row(
-        "SELECT status FROM runs WHERE id = ?1",
-        [&run_id],
-        |row| row.get(0),
-    );
+    let status: Result<String, _> = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard.query_row(
+            "SELECT status FROM runs WHERE id = ?1",
+            [&run_id],
+            |row| row.get(0),
+        )
+    };
     
     if let Ok(current_status) = status {
         if current_status == "running" || current_status == "complete" || current_status == "partial" {
@@ -799,11 +807,8 @@ pub async fn start_run(
         }
     }
     
-    // Use the shared orchestrator instance
-    let orchestrator_inner = orchestrator.inner();
-    
     // Run in background - clone the orchestrator for the task
-    let orchestrator_clone = orchestrator_inner.clone();
+    let orchestrator_clone = orchestrator.clone();
     tokio::spawn(async move {
         if let Err(e) = orchestrator_clone.run_parallel_brainstorm(run_id).await {
             eprintln!("Run execution error: {}", e);
@@ -818,14 +823,22 @@ pub async fn get_run_status(
     db: State<'_, Database>,
     run_id: String,
 ) -> Result<serde_json::Value, String> {
+    get_run_status_impl(&db, &run_id).await
+}
+
+pub async fn get_run_status_impl(db: &Database, run_id: &str) -> Result<serde_json::Value, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
     let run_data: Result<(String, String, Option<String>, String, String, Option<String>), _> = conn_guard.query_row(
         "SELECT status, started_at, finished_at, session_id, selected_profile_ids_json, error_message_safe FROM runs WHERE id = ?1",
-        [&run_id],
+        [run_id],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5).ok().flatten())),
     );
+    let run_settings: Option<serde_json::Value> = conn_guard
+        .query_row("SELECT run_settings_json FROM runs WHERE id = ?1", [run_id], |row| row.get::<_, String>(0))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
     
     let (status, started_at, finished_at, session_id, profile_ids_json, error_message) = run_data
         .map_err(|e| format!("Failed to load run: {}", e))?;
@@ -843,6 +856,10 @@ pub async fn get_run_status(
     if let Some(err) = error_message {
         result["error_message"] = serde_json::json!(err);
     }
+    // Runs created from a session template carry its debate defaults
+    if let Some(template) = run_settings.as_ref().and_then(|s| s.get("template")) {
+        result["template"] = template.clone();
+    }
     Ok(result)
 }
 
@@ -851,15 +868,19 @@ pub async fn get_run_results(
     db: State<'_, Database>,
     run_id: String,
 ) -> Result<Vec<serde_json::Value>, String> {
+    get_run_results_impl(&db, &run_id).await
+}
+
+pub async fn get_run_results_impl(db: &Database, run_id: &str) -> Result<Vec<serde_json::Value>, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
     
     let mut stmt = conn_guard
-        .prepare("SELECT id, profile_id, status, raw_output_text, error_message_safe, started_at, finished_at FROM run_results WHERE run_id = ?1 ORDER BY started_at")
+        .prepare("SELECT id, profile_id, status, raw_output_text, error_message_safe, started_at, finished_at, stage, parent_result_id FROM run_results WHERE run_id = ?1 ORDER BY started_at")
         .map_err(|e| format!("Database error: {}", e))?;
     
     let rows = stmt
-        .query_map([&run_id], |row| {
+        .query_map([run_id], |row| {
             Ok(serde_json::json!({
                 "id": row.get::<_, String>(0)?,
                 "profile_id": row.get::<_, String>(1)?,
@@ -868,6 +889,8 @@ pub async fn get_run_results(
                 "error_message_safe": row.get::<_, Option<String>>(4)?,
                 "started_at": row.get::<_, String>(5)?,
                 "finished_at": row.get::<_, Option<String>>(6)?,
+                "stage": row.get::<_, String>(7)?,
+                "parent_result_id": row.get::<_, Option<String>>(8)?,
             }))
         })
         .map_err(|e| format!("Database error: {}", e))?;
@@ -881,25 +904,148 @@ pub async fn get_run_results(
 }
 
 #[tauri::command]
-pub async fn cancel_run(
+pub async fn get_run_syntheses(
     db: State<'_, Database>,
-    orchestrator: State<'_, Orchestrator>,
     run_id: String,
-) -> Result<(), String> {
-    // Mark run as cancelled in orchestrator
-    orchestrator.cancel_run(&run_id).await;
-    
-    // Update run status in database
+) -> Result<Vec<serde_json::Value>, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    let finished_at = chrono::Utc::now().to_rfc3339();
-    conn_guard.execute(
-        "UPDATE runs SET status = 'cancelled', finished_at = ?1 WHERE id = ?2",
-        rusqlite::params![finished_at, run_id],
-    )
-    .map_err(|e| format!("Database error: {}", e))?;
     
-    Ok(())
+    let mut stmt = conn_guard
+        .prepare("SELECT id, method, synthesizer_profile_id, text, source_result_ids_json, created_at FROM syntheses WHERE run_id = ?1 ORDER BY created_at")
+        .map_err(|e| format!("Database error: {}", e))?;
+    
+    let rows = stmt
+        .query_map([&run_id], |row| {
+            let source_ids: Vec<String> = row
+                .get::<_, Option<String>>(4)?
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default();
+            Ok(serde_json::json!({
+                "id": row.get::<_, String>(0)?,
+                "method": row.get::<_, String>(1)?,
+                "synthesizer_profile_id": row.get::<_, Option<String>>(2)?,
+                "text": row.get::<_, String>(3)?,
+                "source_result_ids": source_ids,
+                "created_at": row.get::<_, String>(5)?,
+            }))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    
+    let mut syntheses = Vec::new();
+    for row in rows {
+        syntheses.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    
+    Ok(syntheses)
+}
+
+pub async fn cancel_run_impl(db: &Database, orchestrator: &Orchestrator, run_id: &str) -> Result<(), String> {
+    // Cancelling a run that already finished changes nothing
+    let finished = run_state::current_status(db, run_id)?
+        .and_then(|s| RunStatus::parse(&s))
+        .is_some_and(|s| s.is_terminal());
+    if finished {
+        return Ok(());
+    }
+
+    // Mark run as cancelled in orchestrator
+    orchestrator.cancel_run(run_id).await;
+    
+    // Persist the cancel flag; workers in any process stop at their next checkpoint
+    run_state::transition(db, run_id, RunStatus::Cancelled, "user_cancel")
+}
+
+#[tauri::command]
+pub async fn cancel_run(
+    db: State<'_, Database>,
+    orchestrator: State<'_, Orchestrator>,
+    run_id: String,
+) -> Result<(), String> {
+    cancel_run_impl(&db, &orchestrator, &run_id).await
+}
+
+pub async fn pause_run_impl(db: &Database, run_id: &str) -> Result<(), String> {
+    run_state::transition(db, run_id, RunStatus::Paused, "user_pause")
+}
+
+/// Pause a brainstorm or debate run; workers wait at their next checkpoint.
+#[tauri::command]
+pub async fn pause_run(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<(), String> {
+    pause_run_impl(&db, &run_id).await
+}
+
+/// Resume a paused run, or restart the worker of an interrupted / failed / partial run from its
+/// last completed result or turn. Returns the resume point.
+pub async fn resume_run_impl(db: &Database, orchestrator: &Orchestrator, run_id: &str) -> Result<serde_json::Value, String> {
+    let status = run_state::current_status(db, run_id)?
+        .ok_or_else(|| format!("Run not found: {}", run_id))?;
+    let resume_point = run_state::resume_point(db, run_id)?;
+
+    match status.as_str() {
+        "paused" => run_state::transition(db, run_id, RunStatus::Running, "user_resume")?,
+        "running" => {}
+        "interrupted" | "failed" | "partial" => {
+            run_state::mark_resumed(db, run_id, &["interrupted", "failed", "partial"])?;
+            if resume_point.kind == "brainstorm" {
+                let orchestrator_clone = orchestrator.clone();
+                let run_id_clone = run_id.to_string();
+                tokio::spawn(async move {
+                    if let Err(e) = orchestrator_clone.run_parallel_brainstorm(run_id_clone).await {
+                        eprintln!("Run resume error: {}", e);
+                    }
+                });
+            } else {
+                crate::commands_debate::spawn_debate_resume(db.clone(), run_id.to_string());
+            }
+        }
+        other => return Err(format!("Run {} cannot be resumed from status '{}'", run_id, other)),
+    }
+
+    serde_json::to_value(&resume_point).map_err(|e| format!("Serialization error: {}", e))
+}
+
+#[tauri::command]
+pub async fn resume_run(
+    db: State<'_, Database>,
+    orchestrator: State<'_, Orchestrator>,
+    run_id: String,
+) -> Result<serde_json::Value, String> {
+    resume_run_impl(&db, &orchestrator, &run_id).await
+}
+
+pub async fn list_interrupted_runs_impl(db: &Database) -> Result<Vec<serde_json::Value>, String> {
+    run_state::list_interrupted_runs(db)
+}
+
+/// Runs left behind by a quit or crash (found by the startup recovery pass).
+#[tauri::command]
+pub async fn list_interrupted_runs(
+    db: State<'_, Database>,
+) -> Result<Vec<serde_json::Value>, String> {
+    list_interrupted_runs_impl(&db).await
+}
+
+pub async fn get_run_state_impl(db: &Database, run_id: &str) -> Result<serde_json::Value, String> {
+    let status = run_state::current_status(db, run_id)?
+        .ok_or_else(|| format!("Run not found: {}", run_id))?;
+    Ok(serde_json::json!({
+        "run_id": run_id,
+        "status": status,
+        "resume_point": run_state::resume_point(db, run_id).ok(),
+        "history": run_state::state_history(db, run_id)?,
+    }))
+}
+
+#[tauri::command]
+pub async fn get_run_state(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<serde_json::Value, String> {
+    get_run_state_impl(&db, &run_id).await
 }
 
 #[tauri::command]
@@ -1064,6 +1210,24 @@ pub async fn continue_agent(
     Ok(new_result_id)
 }
 
+/// Judge the final answers of a run pairwise (or by rubric) and store the ranking in `evaluations`.
+#[tauri::command]
+pub async fn run_tournament(
+    db: State<'_, Database>,
+    run_id: String,
+    config: TournamentConfig,
+) -> Result<Vec<RankedAnswer>, String> {
+    crate::tournament::run_tournament(&db, &run_id, &config).await
+}
+
+#[tauri::command]
+pub async fn get_run_evaluations(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    crate::tournament::load_evaluations(&db, &run_id)
+}
+
 #[tauri::command]
 pub async fn generate_comparison_table(
     db: State<'_, Database>,
@@ -1084,13 +1248,14 @@ pub async fn generate_comparison_table(
         )
         .map_err(|e| format!("Failed to get session: {}", e))?;
         
-        // Get all results with profile names
+        // Final answer per profile (refine supersedes generate; critiques are not answers)
         let mut stmt = conn_guard
             .prepare("SELECT rr.profile_id, pp.name, rr.raw_output_text 
                       FROM run_results rr 
                       JOIN prompt_profiles pp ON pp.id = rr.profile_id 
                       WHERE rr.run_id = ?1 AND rr.status = 'complete' AND rr.raw_output_text IS NOT NULL
-                      ORDER BY rr.started_at")
+                        AND rr.stage != 'critique'
+                      ORDER BY rr.finished_at")
             .map_err(|e| format!("Database error: {}", e))?;
         
         let rows = stmt
@@ -1099,9 +1264,11 @@ pub async fn generate_comparison_table(
             })
             .map_err(|e| format!("Database error: {}", e))?;
         
-        let mut results = Vec::new();
+        let mut results: Vec<(String, String, Option<String>)> = Vec::new();
         for row in rows {
-            results.push(row.map_err(|e| format!("Row error: {}", e))?);
+            let result = row.map_err(|e| format!("Row error: {}", e))?;
+            results.retain(|(profile_id, _, _)| *profile_id != result.0);
+            results.push(result);
         }
         
         (question, results)
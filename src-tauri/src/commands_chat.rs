@@ -6,6 +6,10 @@ use crate::types::{PromptPacket, Message, CharacterDefinition};
 use crate::privacy::{PiiRedactor, PseudonymManager};
 use crate::commands_privacy::PrivacySettings;
 use crate::token_usage::record_token_usage;
+use crate::summary_memory::{self, SummaryMemoryConfig, SummaryScope};
+use crate::profile_memory::{self, ProfileMemory, ProfileMemoryConfig};
+use crate::chat_branches::{self, BranchPoint};
+use crate::group_chat::{self, GroupChatRequest, GroupChatTurn, GroupConversation, GroupMemberInput, GroupMessage};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tauri::State;
@@ -71,6 +75,20 @@ pub struct ChatRequest {
     /// Conversation ID for multi-conversation mode. If None, uses or creates default.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conversation_id: Option<String>,
+
+    /// Rolling summary memory. When enabled, conversation_context is replaced by the stored
+    /// summary plus the messages it does not cover yet. Falls back to params_json.summary_memory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary_memory: Option<SummaryMemoryConfig>,
+
+    /// Edit: store the message as an alternative to this user message, starting a new branch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_message_id: Option<String>,
+
+    /// Regenerate: answer the question before this assistant message again as an alternative reply.
+    /// `user_message` is ignored; the stored question is sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regenerate_message_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,7 +99,86 @@ pub struct AttachedDocument {
 
 // ChatResponse struct removed - not used
 
-pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Result<String, String> {
+/// Persona prompt extended with the character definition, as sent to the model
+pub(crate) fn build_persona(persona_prompt: &str, character_definition: Option<&CharacterDefinition>) -> String {
+    let Some(char_def) = character_definition else {
+        // If no character definition, just use the persona prompt as-is
+        return persona_prompt.to_string();
+    };
+    // Build a comprehensive persona from character definition
+    let mut persona_parts = vec![persona_prompt.to_string()];
+    
+    // Add character details
+    persona_parts.push(format!("\n\nYou are {}. Your role is: {}.", char_def.name, char_def.role));
+    
+    if !char_def.personality.is_empty() {
+        persona_parts.push(format!("\n\nYour personality traits: {}", char_def.personality.join(", ")));
+    }
+    
+    if !char_def.expertise.is_empty() {
+        persona_parts.push(format!("\n\nYour areas of expertise: {}", char_def.expertise.join(", ")));
+    }
+    
+    if !char_def.communication_style.is_empty() {
+        persona_parts.push(format!("\n\nYour communication style: {}", char_def.communication_style));
+    }
+    
+    if let Some(background) = &char_def.background {
+        if !background.is_empty() {
+            persona_parts.push(format!("\n\nYour background: {}", background));
+        }
+    }
+    
+    if let Some(goals) = &char_def.goals {
+        if !goals.is_empty() {
+            persona_parts.push(format!("\n\nYour goals and objectives: {}", goals.join(", ")));
+        }
+    }
+    
+    if let Some(constraints) = &char_def.constraints {
+        if !constraints.is_empty() {
+            persona_parts.push(format!("\n\nYour constraints and values: {}", constraints.join(", ")));
+        }
+    }
+    
+    // Minimal instruction: just stay in character, no restrictions
+    persona_parts.push("\n\nRespond naturally as this character. Use your personality and communication style.".to_string());
+    
+    persona_parts.join("")
+}
+
+pub async fn chat_with_profile_impl(db: &Database, mut request: ChatRequest) -> Result<String, String> {
+    // Where this turn attaches in the conversation tree: below the active branch's newest message,
+    // or next to the message being edited / regenerated
+    let conv_id = request.conversation_id.clone().filter(|c| !c.is_empty());
+    let (anchor, regenerated_question) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        if let Some(reply_id) = &request.regenerate_message_id {
+            let reply = chat_branches::get_message(&conn_guard, reply_id)?;
+            let question = reply
+                .parent_message_id
+                .as_deref()
+                .map(|id| chat_branches::get_message(&conn_guard, id))
+                .transpose()?
+                .filter(|q| q.role == "user")
+                .ok_or_else(|| "Only replies to a user message can be regenerated".to_string())?;
+            (question.parent_message_id.clone(), Some(question))
+        } else if let Some(message_id) = &request.edit_message_id {
+            let original = chat_branches::get_message(&conn_guard, message_id)?;
+            if original.role != "user" {
+                return Err("Only user messages can be edited".to_string());
+            }
+            (original.parent_message_id, None)
+        } else {
+            (chat_branches::active_leaf(&conn_guard, &request.profile_id, conv_id.as_deref())?, None)
+        }
+    };
+    if let Some(question) = &regenerated_question {
+        request.user_message = question.content.clone();
+    }
+    let branching = request.edit_message_id.is_some() || request.regenerate_message_id.is_some();
+
     // Load profile with character definition
     let (_profile_name, provider_account_id, model_name, persona_prompt, params_json_str, character_definition_json): (String, String, String, String, String, Option<String>) = {
         let conn = db.get_connection();
@@ -102,52 +199,7 @@ pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Resu
     let character_definition: Option<CharacterDefinition> = character_definition_json
         .and_then(|s| serde_json::from_str(&s).ok());
     
-    // Build enhanced persona prompt
-    let enhanced_persona = if let Some(char_def) = &character_definition {
-        // Build a comprehensive persona from character definition
-        let mut persona_parts = vec![persona_prompt.clone()];
-        
-        // Add character details
-        persona_parts.push(format!("\n\nYou are {}. Your role is: {}.", char_def.name, char_def.role));
-        
-        if !char_def.personality.is_empty() {
-            persona_parts.push(format!("\n\nYour personality traits: {}", char_def.personality.join(", ")));
-        }
-        
-        if !char_def.expertise.is_empty() {
-            persona_parts.push(format!("\n\nYour areas of expertise: {}", char_def.expertise.join(", ")));
-        }
-        
-        if !char_def.communication_style.is_empty() {
-            persona_parts.push(format!("\n\nYour communication style: {}", char_def.communication_style));
-        }
-        
-        if let Some(background) = &char_def.background {
-            if !background.is_empty() {
-                persona_parts.push(format!("\n\nYour background: {}", background));
-            }
-        }
-        
-        if let Some(goals) = &char_def.goals {
-            if !goals.is_empty() {
-                persona_parts.push(format!("\n\nYour goals and objectives: {}", goals.join(", ")));
-            }
-        }
-        
-        if let Some(constraints) = &char_def.constraints {
-            if !constraints.is_empty() {
-                persona_parts.push(format!("\n\nYour constraints and values: {}", constraints.join(", ")));
-            }
-        }
-        
-        // Minimal instruction: just stay in character, no restrictions
-        persona_parts.push("\n\nRespond naturally as this character. Use your personality and communication style.".to_string());
-        
-        persona_parts.join("")
-    } else {
-        // If no character definition, just use the persona prompt as-is
-        persona_prompt.clone()
-    };
+    let enhanced_persona = build_persona(&persona_prompt, character_definition.as_ref());
     
     // Add language instruction if specified
     let language_instruction = if let Some(lang) = &request.language {
@@ -178,10 +230,20 @@ pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Resu
         }
     }
     
+    // Long-term memories about the user (opt-in per profile)
+    let memory_config = ProfileMemoryConfig::from_params(&params_json).filter(|c| c.enabled);
+    let memory_context = match &memory_config {
+        Some(config) => profile_memory::recall(db, &request.profile_id, &request.user_message, config).unwrap_or_else(|e| {
+            eprintln!("[Chat] Memory recall failed: {}", e);
+            String::new()
+        }),
+        None => String::new(),
+    };
+
     let final_persona = if !language_instruction.is_empty() {
-        format!("{}{}{}", enhanced_persona, language_instruction, web_context)
+        format!("{}{}{}{}", enhanced_persona, memory_context, language_instruction, web_context)
     } else {
-        format!("{}{}", enhanced_persona, web_context)
+        format!("{}{}{}", enhanced_persona, memory_context, web_context)
     };
     
     // Use temperature from profile params as-is (no clamping)
@@ -245,11 +307,35 @@ pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Resu
         None
     };
     
+    let summary_config = request
+        .summary_memory
+        .clone()
+        .or_else(|| SummaryMemoryConfig::from_params(&params_json))
+        .filter(|c| c.enabled);
+    let (summary_scope, summary_scope_id) = match request.conversation_id.as_deref() {
+        Some(cid) if !cid.is_empty() => (SummaryScope::Conversation, cid.to_string()),
+        _ => (SummaryScope::Profile, profile_id.clone()),
+    };
+    // Context is the stored branch up to the anchor; callers without stored history may pass their own
+    let branch_context = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        chat_branches::context_for(&conn_guard, &profile_id, conv_id.as_deref(), anchor.as_deref())?
+    };
+    let conversation_context = if summary_config.is_some() {
+        let summary = summary_memory::load_summary(db, summary_scope, &summary_scope_id)?;
+        Some(summary_memory::compose_context(summary.as_ref(), &branch_context, "conversation"))
+    } else if anchor.is_some() || branching {
+        Some(branch_context)
+    } else {
+        request.conversation_context
+    };
+
     let packet = PromptPacket {
         global_instructions,
         persona_instructions: final_persona.clone(),
         user_message: message_to_send.clone(),
-        conversation_context: request.conversation_context,
+        conversation_context,
         params_json: params.clone(),
         stream: false,
     };
@@ -282,26 +368,21 @@ pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Resu
     let _ = (redaction_stats, privacy_applied); // Suppress unused warning for now
     
     // Save user message and assistant response to database
-    {
+    let user_msg_id = {
         let conn = db.get_connection();
         let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
         let now = chrono::Utc::now().to_rfc3339();
         let response_text = response.text.clone();
-        let conv_id = request.conversation_id.as_deref();
-
-        // Save user message
-        let user_msg_id = uuid::Uuid::new_v4().to_string();
-        conn_guard.execute(
-            "INSERT INTO chat_messages (id, profile_id, role, content, created_at, conversation_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![user_msg_id, profile_id, "user", user_message, now, conv_id],
-        ).map_err(|e| format!("Failed to save user message: {}", e))?;
-
-        // Save assistant response
-        let assistant_msg_id = uuid::Uuid::new_v4().to_string();
-        conn_guard.execute(
-            "INSERT INTO chat_messages (id, profile_id, role, content, created_at, conversation_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![assistant_msg_id, profile_id, "assistant", response_text, now, conv_id],
-        ).map_err(|e| format!("Failed to save assistant message: {}", e))?;
+        let conv_id = conv_id.as_deref();
+
+        // Save user message (a regenerated reply answers the existing one)
+        let user_msg_id = match regenerated_question {
+            Some(question) => question.id,
+            None => chat_branches::append(&conn_guard, &profile_id, conv_id, anchor.as_deref(), "user", &user_message, &now)?,
+        };
+
+        // Save assistant response; it becomes the tip of the active branch
+        chat_branches::append(&conn_guard, &profile_id, conv_id, Some(&user_msg_id), "assistant", &response_text, &now)?;
 
         // Update conversation updated_at
         if let Some(cid) = conv_id {
@@ -310,7 +391,8 @@ pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Resu
                 rusqlite::params![now, cid],
             ).ok();
         }
-    }
+        user_msg_id
+    };
 
     // Record token usage for this profile chat, if available
     let _ = record_token_usage(
@@ -320,8 +402,42 @@ pub async fn chat_with_profile_impl(db: &Database, request: ChatRequest) -> Resu
         &response.usage_json,
         "profile_chat",
         None,
-        None,
+        Some(json!({ "profile_id": profile_id, "conversation_id": request.conversation_id })),
     );
+
+    // Fold older messages into the summary in the background so the reply isn't delayed
+    if let Some(mut config) = summary_config {
+        config.summarizer_profile_id.get_or_insert_with(|| profile_id.clone());
+        let db = db.clone();
+        tokio::spawn(async move {
+            let speaker = |m: &Message| if m.author_type == "user" { "User".to_string() } else { "Assistant".to_string() };
+            if let Err(e) = summary_memory::refresh(&db, summary_scope, &summary_scope_id, &config, None, &speaker, false).await {
+                eprintln!("[Chat] Summary refresh failed: {}", e);
+            }
+        });
+    }
+
+    // Learn durable facts from this exchange in the background as well
+    if let Some(config) = memory_config {
+        let db = db.clone();
+        let reply = response.text.clone();
+        let conversation_id = conv_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = profile_memory::extract_and_store(
+                &db,
+                &profile_id,
+                conversation_id.as_deref(),
+                &user_msg_id,
+                &user_message,
+                &reply,
+                &config,
+            )
+            .await
+            {
+                eprintln!("[Chat] Memory extraction failed: {}", e);
+            }
+        });
+    }
     
     Ok(response.text)
 }
@@ -331,16 +447,8 @@ pub async fn chat_with_profile(db: State<'_, Database>, request: ChatRequest) ->
     chat_with_profile_impl(&db, request).await
 }
 
-fn map_chat_message_row(row: &rusqlite::Row, profile_id: &str) -> Result<serde_json::Value, rusqlite::Error> {
-    Ok(serde_json::json!({
-        "id": row.get::<_, String>(0)?,
-        "role": row.get::<_, String>(1)?,
-        "content": row.get::<_, String>(2)?,
-        "timestamp": row.get::<_, String>(3)?,
-        "profile_id": profile_id,
-    }))
-}
-
+/// Messages on the conversation's active branch, oldest first. `sibling_ids` lists the alternatives
+/// at each position so the UI can offer a branch switcher.
 pub async fn load_chat_messages_impl(
     db: &Database,
     profile_id: String,
@@ -348,37 +456,21 @@ pub async fn load_chat_messages_impl(
 ) -> Result<Vec<serde_json::Value>, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-
-    let messages = match &conversation_id {
-        Some(cid) => {
-            let mut stmt = conn_guard
-                .prepare("SELECT id, role, content, created_at FROM chat_messages WHERE profile_id = ?1 AND conversation_id = ?2 ORDER BY created_at ASC")
-                .map_err(|e| format!("Database error: {}", e))?;
-            let rows = stmt
-                .query_map(rusqlite::params![profile_id, cid], |row| map_chat_message_row(row, &profile_id))
-                .map_err(|e| format!("Database error: {}", e))?;
-            let mut v = Vec::new();
-            for row in rows {
-                v.push(row.map_err(|e| format!("Row error: {}", e))?);
-            }
-            v
-        }
-        None => {
-            let mut stmt = conn_guard
-                .prepare("SELECT id, role, content, created_at FROM chat_messages WHERE profile_id = ?1 AND (conversation_id IS NULL OR conversation_id = '') ORDER BY created_at ASC")
-                .map_err(|e| format!("Database error: {}", e))?;
-            let rows = stmt
-                .query_map(rusqlite::params![profile_id], |row| map_chat_message_row(row, &profile_id))
-                .map_err(|e| format!("Database error: {}", e))?;
-            let mut v = Vec::new();
-            for row in rows {
-                v.push(row.map_err(|e| format!("Row error: {}", e))?);
-            }
-            v
-        }
-    };
-
-    Ok(messages)
+    let path = chat_branches::active_path(&conn_guard, &profile_id, conversation_id.as_deref().filter(|c| !c.is_empty()))?;
+    Ok(path
+        .into_iter()
+        .map(|m| {
+            json!({
+                "id": m.id,
+                "role": m.role,
+                "content": m.content,
+                "timestamp": m.created_at,
+                "profile_id": profile_id,
+                "parent_message_id": m.parent_message_id,
+                "sibling_ids": m.sibling_ids,
+            })
+        })
+        .collect())
 }
 
 #[tauri::command]
@@ -390,24 +482,34 @@ pub async fn load_chat_messages(
     load_chat_messages_impl(&db, profile_id, conversation_id).await
 }
 
-pub async fn insert_chat_message_impl(db: &Database, profile_id: String, role: String, content: String, user_id: Option<String>) -> Result<String, String> {
+/// Append a message to the end of the active branch (e.g. an improved reply) and select it
+pub async fn insert_chat_message_impl(
+    db: &Database,
+    profile_id: String,
+    conversation_id: Option<String>,
+    role: String,
+    content: String,
+    user_id: Option<String>,
+) -> Result<String, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    let id = format!("msg-{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis());
+    let conversation_id = conversation_id.as_deref().filter(|c| !c.is_empty());
+    let parent = chat_branches::active_leaf(&conn_guard, &profile_id, conversation_id)?;
     let now = chrono::Utc::now().to_rfc3339();
-    conn_guard
-        .execute(
-            "INSERT INTO chat_messages (id, profile_id, role, content, created_at, user_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![id, profile_id, role, content, now, user_id],
-        )
-        .map_err(|e| format!("Failed to insert chat message: {}", e))?;
-    Ok(id)
+    chat_branches::append_as(&conn_guard, &profile_id, conversation_id, parent.as_deref(), &role, &content, &now, user_id.as_deref())
 }
 
 #[tauri::command]
 #[allow(non_snake_case)]
-pub async fn insert_chat_message(db: State<'_, Database>, profile_id: String, role: String, content: String, userId: Option<String>) -> Result<String, String> {
-    insert_chat_message_impl(&db, profile_id, role, content, userId).await
+pub async fn insert_chat_message(
+    db: State<'_, Database>,
+    profile_id: String,
+    conversation_id: Option<String>,
+    role: String,
+    content: String,
+    userId: Option<String>,
+) -> Result<String, String> {
+    insert_chat_message_impl(&db, profile_id, conversation_id, role, content, userId).await
 }
 
 pub async fn update_chat_message_content_impl(db: &Database, message_id: String, content: String) -> Result<(), String> {
@@ -427,6 +529,38 @@ pub async fn update_chat_message_content(db: State<'_, Database>, message_id: St
     update_chat_message_content_impl(&*db, message_id, content).await
 }
 
+pub async fn list_chat_branches_impl(
+    db: &Database,
+    profile_id: String,
+    conversation_id: Option<String>,
+) -> Result<Vec<BranchPoint>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    chat_branches::branch_points(&conn_guard, &profile_id, conversation_id.as_deref().filter(|c| !c.is_empty()))
+}
+
+#[tauri::command]
+pub async fn list_chat_branches(
+    db: State<'_, Database>,
+    profile_id: String,
+    conversation_id: Option<String>,
+) -> Result<Vec<BranchPoint>, String> {
+    list_chat_branches_impl(&db, profile_id, conversation_id).await
+}
+
+/// Show the branch through `message_id` (continuing through its newest replies). Without a
+/// conversation the selection applies to the profile's default chat.
+pub async fn switch_chat_branch_impl(db: &Database, conversation_id: Option<String>, message_id: String) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    chat_branches::select(&conn_guard, conversation_id.as_deref().filter(|c| !c.is_empty()), &message_id)
+}
+
+#[tauri::command]
+pub async fn switch_chat_branch(db: State<'_, Database>, conversation_id: Option<String>, message_id: String) -> Result<(), String> {
+    switch_chat_branch_impl(&db, conversation_id, message_id).await
+}
+
 pub async fn clear_chat_messages_impl(db: &Database, profile_id: String) -> Result<(), String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
@@ -436,6 +570,8 @@ pub async fn clear_chat_messages_impl(db: &Database, profile_id: String) -> Resu
         [&profile_id],
     )
     .map_err(|e| format!("Failed to clear chat messages: {}", e))?;
+    drop(conn_guard);
+    summary_memory::clear_summary(db, SummaryScope::Profile, &profile_id)?;
     
     Ok(())
 }
@@ -510,6 +646,8 @@ pub async fn delete_profile_conversation_impl(db: &Database, conversation_id: St
     conn_guard
         .execute("DELETE FROM profile_conversations WHERE id = ?1", [&conversation_id])
         .map_err(|e| format!("Failed to delete conversation: {}", e))?;
+    drop(conn_guard);
+    summary_memory::clear_summary(db, SummaryScope::Conversation, &conversation_id)?;
     Ok(())
 }
 
@@ -525,6 +663,8 @@ pub async fn clear_conversation_messages_impl(db: &Database, conversation_id: St
     conn_guard
         .execute("DELETE FROM chat_messages WHERE conversation_id = ?1", [&conversation_id])
         .map_err(|e| format!("Failed to clear conversation messages: {}", e))?;
+    drop(conn_guard);
+    summary_memory::clear_summary(db, SummaryScope::Conversation, &conversation_id)?;
     Ok(())
 }
 
@@ -533,6 +673,236 @@ pub async fn clear_conversation_messages(db: State<'_, Database>, conversation_i
     clear_conversation_messages_impl(&db, conversation_id).await
 }
 
+/// Stored rolling summary for a debate run, chat conversation or profile chat
+pub async fn get_conversation_summary_impl(
+    db: &Database,
+    scope: SummaryScope,
+    scope_id: String,
+) -> Result<Option<summary_memory::StoredSummary>, String> {
+    summary_memory::load_summary(db, scope, &scope_id)
+}
+
+#[tauri::command]
+pub async fn get_conversation_summary(
+    db: State<'_, Database>,
+    scope: SummaryScope,
+    scope_id: String,
+) -> Result<Option<summary_memory::StoredSummary>, String> {
+    get_conversation_summary_impl(&db, scope, scope_id).await
+}
+
+/// Replace the summary text with a user edit
+pub async fn update_conversation_summary_impl(
+    db: &Database,
+    scope: SummaryScope,
+    scope_id: String,
+    summary_text: String,
+) -> Result<Option<summary_memory::StoredSummary>, String> {
+    summary_memory::save_user_edit(db, scope, &scope_id, &summary_text)?;
+    summary_memory::load_summary(db, scope, &scope_id)
+}
+
+#[tauri::command]
+pub async fn update_conversation_summary(
+    db: State<'_, Database>,
+    scope: SummaryScope,
+    scope_id: String,
+    summary_text: String,
+) -> Result<Option<summary_memory::StoredSummary>, String> {
+    update_conversation_summary_impl(&db, scope, scope_id, summary_text).await
+}
+
+/// Fold everything outside the last-k window into the summary now
+pub async fn refresh_conversation_summary_impl(
+    db: &Database,
+    scope: SummaryScope,
+    scope_id: String,
+    config: Option<SummaryMemoryConfig>,
+) -> Result<Option<summary_memory::StoredSummary>, String> {
+    let mut config = config.unwrap_or_default();
+    if config.summarizer_profile_id.is_none() {
+        config.summarizer_profile_id = summary_memory::default_summarizer(db, scope, &scope_id);
+    }
+    let speaker = summary_memory::profile_speaker_names(db);
+    summary_memory::refresh(db, scope, &scope_id, &config, None, &speaker, true).await
+}
+
+#[tauri::command]
+pub async fn refresh_conversation_summary(
+    db: State<'_, Database>,
+    scope: SummaryScope,
+    scope_id: String,
+    config: Option<SummaryMemoryConfig>,
+) -> Result<Option<summary_memory::StoredSummary>, String> {
+    refresh_conversation_summary_impl(&db, scope, scope_id, config).await
+}
+
+/// Delete the stored summary; the full history is sent again until the next refresh
+pub async fn clear_conversation_summary_impl(db: &Database, scope: SummaryScope, scope_id: String) -> Result<(), String> {
+    summary_memory::clear_summary(db, scope, &scope_id)
+}
+
+#[tauri::command]
+pub async fn clear_conversation_summary(db: State<'_, Database>, scope: SummaryScope, scope_id: String) -> Result<(), String> {
+    clear_conversation_summary_impl(&db, scope, scope_id).await
+}
+
+// Group chat: several profiles in one persistent conversation
+
+pub async fn create_group_conversation_impl(
+    db: &Database,
+    title: Option<String>,
+    members: Vec<GroupMemberInput>,
+) -> Result<GroupConversation, String> {
+    group_chat::create(db, title, &members)
+}
+
+#[tauri::command]
+pub async fn create_group_conversation(
+    db: State<'_, Database>,
+    title: Option<String>,
+    members: Vec<GroupMemberInput>,
+) -> Result<GroupConversation, String> {
+    create_group_conversation_impl(&db, title, members).await
+}
+
+pub async fn list_group_conversations_impl(db: &Database) -> Result<Vec<GroupConversation>, String> {
+    group_chat::list(db)
+}
+
+#[tauri::command]
+pub async fn list_group_conversations(db: State<'_, Database>) -> Result<Vec<GroupConversation>, String> {
+    list_group_conversations_impl(&db).await
+}
+
+pub async fn update_group_conversation_impl(
+    db: &Database,
+    conversation_id: String,
+    title: Option<String>,
+    members: Option<Vec<GroupMemberInput>>,
+) -> Result<GroupConversation, String> {
+    group_chat::update(db, &conversation_id, title, members)
+}
+
+#[tauri::command]
+pub async fn update_group_conversation(
+    db: State<'_, Database>,
+    conversation_id: String,
+    title: Option<String>,
+    members: Option<Vec<GroupMemberInput>>,
+) -> Result<GroupConversation, String> {
+    update_group_conversation_impl(&db, conversation_id, title, members).await
+}
+
+pub async fn delete_group_conversation_impl(db: &Database, conversation_id: String) -> Result<(), String> {
+    group_chat::delete(db, &conversation_id)
+}
+
+#[tauri::command]
+pub async fn delete_group_conversation(db: State<'_, Database>, conversation_id: String) -> Result<(), String> {
+    delete_group_conversation_impl(&db, conversation_id).await
+}
+
+pub async fn load_group_messages_impl(db: &Database, conversation_id: String) -> Result<Vec<GroupMessage>, String> {
+    group_chat::load_messages(db, &conversation_id)
+}
+
+#[tauri::command]
+pub async fn load_group_messages(db: State<'_, Database>, conversation_id: String) -> Result<Vec<GroupMessage>, String> {
+    load_group_messages_impl(&db, conversation_id).await
+}
+
+/// Post a user message; mentioned profiles (or the auto-responders) reply in turn
+pub async fn send_group_message_impl(db: &Database, request: GroupChatRequest) -> Result<GroupChatTurn, String> {
+    group_chat::send(db, &request).await
+}
+
+#[tauri::command]
+pub async fn send_group_message(db: State<'_, Database>, request: GroupChatRequest) -> Result<GroupChatTurn, String> {
+    send_group_message_impl(&db, request).await
+}
+
+// Profile memory: long-term facts about the user (see `profile_memory`)
+
+fn with_connection<T>(db: &Database, f: impl FnOnce(&rusqlite::Connection) -> Result<T, String>) -> Result<T, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    f(&conn_guard)
+}
+
+pub async fn list_profile_memories_impl(db: &Database, profile_id: String) -> Result<Vec<ProfileMemory>, String> {
+    with_connection(db, |conn| profile_memory::list(conn, &profile_id))
+}
+
+#[tauri::command]
+pub async fn list_profile_memories(db: State<'_, Database>, profile_id: String) -> Result<Vec<ProfileMemory>, String> {
+    list_profile_memories_impl(&db, profile_id).await
+}
+
+/// Add a memory by hand; PII is redacted first when `redact_pii` is on
+pub async fn add_profile_memory_impl(
+    db: &Database,
+    profile_id: String,
+    kind: String,
+    content: String,
+) -> Result<Option<ProfileMemory>, String> {
+    let privacy = load_privacy_settings(db)?;
+    let content = profile_memory::redact(&privacy, &content, &profile_id);
+    with_connection(db, |conn| profile_memory::add(conn, &profile_id, &kind, &content, None, None, true))
+}
+
+#[tauri::command]
+pub async fn add_profile_memory(
+    db: State<'_, Database>,
+    profile_id: String,
+    kind: String,
+    content: String,
+) -> Result<Option<ProfileMemory>, String> {
+    add_profile_memory_impl(&db, profile_id, kind, content).await
+}
+
+pub async fn update_profile_memory_impl(
+    db: &Database,
+    memory_id: String,
+    content: String,
+    kind: Option<String>,
+) -> Result<ProfileMemory, String> {
+    let privacy = load_privacy_settings(db)?;
+    with_connection(db, |conn| {
+        let profile_id = profile_memory::get(conn, &memory_id)?.profile_id;
+        let content = profile_memory::redact(&privacy, &content, &profile_id);
+        profile_memory::update(conn, &memory_id, &content, kind.as_deref())
+    })
+}
+
+#[tauri::command]
+pub async fn update_profile_memory(
+    db: State<'_, Database>,
+    memory_id: String,
+    content: String,
+    kind: Option<String>,
+) -> Result<ProfileMemory, String> {
+    update_profile_memory_impl(&db, memory_id, content, kind).await
+}
+
+pub async fn delete_profile_memory_impl(db: &Database, memory_id: String) -> Result<(), String> {
+    with_connection(db, |conn| profile_memory::delete(conn, &memory_id))
+}
+
+#[tauri::command]
+pub async fn delete_profile_memory(db: State<'_, Database>, memory_id: String) -> Result<(), String> {
+    delete_profile_memory_impl(&db, memory_id).await
+}
+
+pub async fn clear_profile_memories_impl(db: &Database, profile_id: String) -> Result<usize, String> {
+    with_connection(db, |conn| profile_memory::clear(conn, &profile_id))
+}
+
+#[tauri::command]
+pub async fn clear_profile_memories(db: State<'_, Database>, profile_id: String) -> Result<usize, String> {
+    clear_profile_memories_impl(&db, profile_id).await
+}
+
 /// Normalize whitespace: collapse multiple spaces/newlines to single space.
 #[allow(dead_code)]
 fn normalize_whitespace(s: &str) -> String {
@@ -3,18 +3,19 @@
 use crate::db::Database;
 use crate::ProviderAccount;
 use crate::cline::ClineAgentLoop;
+use crate::cline::agent_loop::{resolve_workspace_path, ClineLoopConfig, ClineProgress};
+use crate::cline::checkpoints::Checkpoint;
 use crate::training_ingest;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
-use tauri::{State, AppHandle};
+use tauri::{State, AppHandle, Emitter};
 use chrono::Utc;
-use tokio::time::{timeout, Duration};
+use tokio::time::Duration;
 
-// Import LinterError only when needed
-#[allow(unused_imports)]
-use crate::cline::error_monitor::LinterError;
+use crate::cline::error_monitor::{ErrorMonitor, LinterError};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClineAgentTaskRequest {
@@ -24,6 +25,9 @@ pub struct ClineAgentTaskRequest {
     pub workspace_path: String,
     pub target_paths: Option<Vec<String>>,
     pub conversation_context: Option<Vec<serde_json::Value>>, // Previous messages for continuous chat
+    /// Client-chosen run id, so `panther://cline_progress` events can be matched while the task runs
+    pub run_id: Option<String>,
+    pub loop_config: Option<ClineLoopConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,6 +36,8 @@ pub struct ClineAgentTaskResponse {
     pub status: String,
     pub summary: String,
     pub tool_executions: Vec<ToolExecutionResponse>,
+    pub iterations: u32,
+    pub total_tokens: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -42,6 +48,7 @@ pub struct ToolExecutionResponse {
     pub tool_params: Value,
     pub approval_status: String,
     pub result: Option<Value>,
+    pub iteration: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -72,11 +79,13 @@ pub struct IngestClineTurnRequest {
     pub error_context: Option<String>,
 }
 
-/// Execute a Cline agent task
+/// Execute a Cline agent task: loops model calls and tool executions until the model is done.
+/// Tools outside `loop_config.auto_approve` wait for `cline_approve_tool`; progress is emitted
+/// as `panther://cline_progress`.
 #[tauri::command]
 pub async fn cline_agent_task(
     db: State<'_, Database>,
-    _app: AppHandle,
+    app: AppHandle,
     request: ClineAgentTaskRequest,
 ) -> Result<ClineAgentTaskResponse, String> {
     eprintln!("🚀 Cline agent task started: {}", request.task_description);
@@ -123,7 +132,14 @@ pub async fn cline_agent_task(
     eprintln!("🤖 Model: {}", request.model_name);
     
     // Clone database for agent loop (Database is Clone)
-    let agent_loop = ClineAgentLoop::new(db.inner().clone(), workspace_path);
+    let agent_loop = ClineAgentLoop::new(db.inner().clone(), workspace_path)
+        .with_config(request.loop_config.clone().unwrap_or_default())
+        .with_run_id(request.run_id.clone())
+        .with_progress(Arc::new(move |progress: ClineProgress| {
+            if let Err(e) = app.emit("panther://cline_progress", progress) {
+                eprintln!("❌ Failed to emit Cline progress: {}", e);
+            }
+        }));
     
     eprintln!("🔄 Executing agent task...");
     let result = agent_loop
@@ -153,6 +169,7 @@ pub async fn cline_agent_task(
             tool_params: te.tool_params,
             approval_status: te.approval_status,
             result: te.result,
+            iteration: te.iteration,
         })
         .collect();
     
@@ -161,6 +178,8 @@ pub async fn cline_agent_task(
         status: result.status.clone(),
         summary: result.summary.clone(),
         tool_executions: tool_executions.clone(),
+        iterations: result.iterations,
+        total_tokens: result.total_tokens,
     };
     
     eprintln!("📤 Returning response to frontend: run_id={}, status={}, summary_len={}, tools={}", 
@@ -178,7 +197,7 @@ pub async fn cline_approve_tool(
     eprintln!("🔧 Approving tool: {} (approved: {})", request.tool_id, request.approved);
     
     // Get tool execution details
-    let (tool_type, tool_params_json, _run_id, workspace_path_str): (String, String, String, String) = {
+    let (tool_type, tool_params_json, run_id, step_index, workspace_path_str): (String, String, String, i32, String) = {
         let conn = db.get_connection();
         // Don't hang forever waiting for the DB mutex.
         let start = Instant::now();
@@ -202,16 +221,16 @@ pub async fn cline_approve_tool(
         };
         
         let mut stmt = conn_guard.prepare(
-            "SELECT tool_type, tool_params_json, run_id FROM cline_tool_executions WHERE id = ?1"
+            "SELECT tool_type, tool_params_json, run_id, step_index FROM cline_tool_executions WHERE id = ?1"
         )
         .map_err(|e| {
             eprintln!("❌ Failed to prepare statement: {}", e);
             format!("Failed to prepare statement: {}", e)
         })?;
         
-        let tool_info: (String, String, String) = stmt.query_row(
+        let tool_info: (String, String, String, i32) = stmt.query_row(
             rusqlite::params![request.tool_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
         )
         .map_err(|e| {
             eprintln!("❌ Tool execution not found: {}", e);
@@ -222,110 +241,100 @@ pub async fn cline_approve_tool(
         
         // Get workspace path from run
         let mut run_stmt = conn_guard.prepare(
-            "SELECT workspace_path FROM cline_runs WHERE id = ?1"
+            "SELECT workspace_path, status FROM cline_runs WHERE id = ?1"
         )
         .map_err(|e| {
             eprintln!("❌ Failed to prepare run statement: {}", e);
             format!("Failed to prepare run statement: {}", e)
         })?;
         
-        let workspace_path_raw: String = run_stmt.query_row(
+        let (workspace_path_raw, run_status): (String, String) = run_stmt.query_row(
             rusqlite::params![tool_info.2.clone()],
-            |row| row.get(0)
+            |row| Ok((row.get(0)?, row.get(1)?))
         )
         .map_err(|e| {
             eprintln!("❌ Run not found: {}", e);
             format!("Run not found: {}", e)
         })?;
         
+        if !matches!(run_status.as_str(), "running" | "awaiting_approval") {
+            return Err(format!("Run is {}; its tools can no longer be approved or rejected", run_status));
+        }
+        
+        // Claim the tool: the loop may already have rejected it on approval timeout, and a
+        // second click must not run it twice.
+        let claimed_status = if request.approved { "executing" } else { "rejected" };
+        let claimed = conn_guard.execute(
+            "UPDATE cline_tool_executions SET approval_status = ?1 WHERE id = ?2 AND approval_status = 'pending'",
+            rusqlite::params![claimed_status, request.tool_id],
+        )
+        .map_err(|e| format!("Failed to update tool approval: {}", e))?;
+        if claimed == 0 {
+            return Err("Tool execution was already approved, rejected or timed out".to_string());
+        }
+        
         // Resolve workspace path to absolute path
-        let workspace_path = if std::path::Path::new(&workspace_path_raw).is_absolute() {
-            workspace_path_raw
-        } else {
-            // Resolve relative path from user home
-            let home = if cfg!(windows) {
-                std::env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users".to_string())
-            } else {
-                std::env::var("HOME").unwrap_or_else(|_| "/home".to_string())
-            };
-            std::path::Path::new(&home)
-                .join(&workspace_path_raw)
-                .to_string_lossy()
-                .to_string()
-        };
+        let workspace_path = crate::cline::agent_loop::resolve_workspace_path(&workspace_path_raw);
         
         eprintln!("📁 Workspace path (resolved): {}", workspace_path);
         
-        (tool_info.0, tool_info.1, tool_info.2, workspace_path)
+        (tool_info.0, tool_info.1, tool_info.2, tool_info.3, workspace_path)
     };
     
     let status = if request.approved { "approved" } else { "rejected" };
     
     // If approved, execute the tool
-    let execution_result: Option<Value> = if request.approved {
-        eprintln!("✅ Executing approved tool: {} (workspace: {})", tool_type, workspace_path_str);
+    let execution: Result<Option<Value>, String> = async {
+        if request.approved {
+            eprintln!("✅ Executing approved tool: {} (workspace: {})", tool_type, workspace_path_str);
         
-        // Parse tool params and convert to ClineToolRequest
-        let tool_params: Value = serde_json::from_str(&tool_params_json)
-            .map_err(|e| {
-                eprintln!("❌ Failed to parse tool params JSON: {} | JSON: {}", e, tool_params_json);
-                format!("Failed to parse tool params: {}", e)
-            })?;
+            // Parse tool params and convert to ClineToolRequest
+            let tool_params: Value = serde_json::from_str(&tool_params_json)
+                .map_err(|e| {
+                    eprintln!("❌ Failed to parse tool params JSON: {} | JSON: {}", e, tool_params_json);
+                    format!("Failed to parse tool params: {}", e)
+                })?;
         
-        eprintln!("📦 Tool params parsed: {:?}", tool_params);
+            eprintln!("📦 Tool params parsed: {:?}", tool_params);
         
-        let cline_tool: crate::cline::tools::ClineToolRequest = serde_json::from_value(tool_params.clone())
-            .map_err(|e| {
-                eprintln!("❌ Failed to deserialize tool: {} | Value: {:?}", e, tool_params);
-                format!("Failed to deserialize tool: {}", e)
-            })?;
+            crate::cline::checkpoints::checkpoint_before_tool(
+                db.inner(),
+                &run_id,
+                &request.tool_id,
+                step_index,
+                &tool_type,
+                &PathBuf::from(&workspace_path_str),
+            ).await;
         
-        eprintln!("🔧 Calling execute_cline_tool...");
+            eprintln!("🔧 Calling execute_cline_tool...");
 
-        // Execute the tool with a hard timeout so the UI doesn't hang forever.
-        // Many terminal scans can take a long time (or hang); this ensures we always return.
-        let timeout_secs: u64 = tool_params
-            .get("timeout_seconds")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(90);
-
-        let result = match timeout(
-            Duration::from_secs(timeout_secs),
-            crate::cline::tools::execute_cline_tool(cline_tool, &workspace_path_str),
-        )
-        .await
-        {
-            Ok(tool_result) => tool_result,
-            Err(_) => {
-                eprintln!("⏱️ Tool execution timed out after {}s", timeout_secs);
-                crate::tools::ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("Tool execution timed out after {} seconds", timeout_secs)),
-                    extra_json: Some(json!({
-                        "timeout_seconds": timeout_secs,
-                        "status": "timeout"
-                    })),
-                }
-            }
-        };
+            // Execute the tool with a hard timeout so the UI doesn't hang forever.
+            // Many terminal scans can take a long time (or hang); this ensures we always return.
+            let result = crate::cline::agent_loop::execute_tool_params(&tool_params, &workspace_path_str).await?;
         
-        eprintln!("✅ Tool execution returned: success={}, error={:?}", 
-            result.success, 
-            result.error.as_ref().map(|e| e.as_str()).unwrap_or("None"));
+            eprintln!("✅ Tool execution returned: success={}, error={:?}", 
+                result.success, 
+                result.error.as_ref().map(|e| e.as_str()).unwrap_or("None"));
         
-        let result_json = serde_json::to_value(&result)
-            .map_err(|e| {
-                eprintln!("❌ Failed to serialize result: {}", e);
-                format!("Failed to serialize result: {}", e)
-            })?;
+            let result_json = serde_json::to_value(&result)
+                .map_err(|e| {
+                    eprintln!("❌ Failed to serialize result: {}", e);
+                    format!("Failed to serialize result: {}", e)
+                })?;
         
-        eprintln!("✅ Tool execution completed: success={}", result.success);
+            eprintln!("✅ Tool execution completed: success={}", result.success);
         
-        Some(result_json)
-    } else {
-        eprintln!("❌ Tool rejected, not executing");
-        None
+            Ok(Some(result_json))
+        } else {
+            eprintln!("❌ Tool rejected, not executing");
+            Ok(None)
+        }
+    }.await;
+    
+    // A claimed tool that failed to run still gets a result, so the loop stops waiting on it.
+    let execution_result: Option<Value> = match &execution {
+        Ok(result) => result.clone(),
+        Err(e) => Some(json!({ "success": false, "output": "", "error": e, "extra_json": null })),
     };
     
     // Update database with status and result
@@ -363,7 +372,7 @@ pub async fn cline_approve_tool(
             eprintln!("💾 Updating database with result ({} chars)", result_json_str.len());
             
             conn_guard.execute(
-                "UPDATE cline_tool_executions SET approval_status = ?1, result_json = ?2, executed_at = ?3 WHERE id = ?4",
+                "UPDATE cline_tool_executions SET approval_status = ?1, result_json = ?2, executed_at = ?3 WHERE id = ?4 AND approval_status = 'executing'",
                 rusqlite::params![
                     status,
                     result_json_str,
@@ -377,18 +386,9 @@ pub async fn cline_approve_tool(
             })?;
             
             eprintln!("✅ Database updated successfully");
-        } else {
-            eprintln!("💾 Updating database status only (rejected)");
-            conn_guard.execute(
-                "UPDATE cline_tool_executions SET approval_status = ?1 WHERE id = ?2",
-                rusqlite::params![status, request.tool_id],
-            )
-            .map_err(|e| {
-                eprintln!("❌ Failed to update tool approval: {}", e);
-                format!("Failed to update tool approval: {}", e)
-            })?;
         }
     }
+    execution?;
     
     eprintln!("✅ Approve tool function completed successfully");
     
@@ -401,6 +401,25 @@ pub async fn cline_approve_tool(
     }))
 }
 
+/// Stop a running agent loop; it finishes after the current model call or tool approval wait
+#[tauri::command]
+pub async fn cline_cancel_task(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn
+        .lock()
+        .map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute(
+            "UPDATE cline_runs SET status = 'cancelled' WHERE id = ?1 AND status IN ('running', 'awaiting_approval')",
+            [&run_id],
+        )
+        .map_err(|e| format!("Failed to cancel run: {}", e))?;
+    Ok(())
+}
+
 /// Create a workspace checkpoint
 #[tauri::command]
 pub async fn cline_create_checkpoint(
@@ -499,22 +518,32 @@ pub async fn cline_compare_checkpoint(
     Ok(diff)
 }
 
+/// Checkpoints of a run, including the automatic ones taken before each tool execution
+#[tauri::command]
+pub async fn cline_list_checkpoints(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<Vec<Checkpoint>, String> {
+    crate::cline::checkpoints::list_checkpoints(db.inner(), &run_id)
+}
+
 /// Get linter/compiler errors
 #[tauri::command]
 pub async fn cline_get_errors(
-    _workspace_path: String,
+    workspace_path: String,
 ) -> Result<Vec<LinterError>, String> {
-    // TODO: Implement actual error detection
-    // For now, return empty list
-    Ok(Vec::new())
+    let mut monitor = ErrorMonitor::new(resolve_workspace_path(&workspace_path));
+    Ok(monitor.check_workspace().await)
 }
 
 /// Analyze AST of a file
 #[tauri::command]
 pub async fn cline_analyze_ast(
     path: String,
+    workspace_path: Option<String>,
 ) -> Result<Value, String> {
-    let result = crate::cline::tools::ast_tool::analyze_ast(&path).await;
+    let workspace = workspace_path.map(|w| resolve_workspace_path(&w)).unwrap_or_default();
+    let result = crate::cline::tools::ast_tool::analyze_ast(&path, &workspace).await;
     
     if result.success {
         Ok(result.extra_json.unwrap_or(json!({})))
@@ -523,6 +552,23 @@ pub async fn cline_analyze_ast(
     }
 }
 
+/// Definitions and references of a symbol name in a file or directory
+#[tauri::command]
+pub async fn cline_find_symbol(
+    name: String,
+    path: Option<String>,
+    workspace_path: String,
+) -> Result<Value, String> {
+    let workspace = resolve_workspace_path(&workspace_path);
+    let result = crate::cline::tools::ast_tool::find_symbol(&name, path.as_deref(), &workspace).await;
+    
+    if result.success {
+        Ok(result.extra_json.unwrap_or(json!({})))
+    } else {
+        Err(result.error.unwrap_or("Symbol lookup failed".to_string()))
+    }
+}
+
 /// Ingest ClineIDE conversation turn into training data
 #[tauri::command]
 pub async fn ingest_cline_turn(
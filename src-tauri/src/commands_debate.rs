@@ -1,7 +1,12 @@
 // Debate and export commands
 
 use crate::db::Database;
-use crate::debate_orchestrator::DebateOrchestrator;
+use crate::debate_orchestrator::{load_debate_config, DebateOrchestrator, DebateSettings};
+use crate::run_state::{self, RunStatus};
+use crate::consensus;
+use crate::argument_graph::{self, ArgumentGraph};
+use crate::human_turns::{self, HumanSeat, HumanTurn, TurnStatus};
+use crate::session_transfer::{self, ImportOptions, ImportSummary};
 use serde_json;
 use tauri::State;
 
@@ -40,19 +45,12 @@ pub async fn start_debate(
     language: Option<String>,
     tone: Option<String>,
     web_search_results: Option<Vec<crate::web_search::NewsResult>>,
+    settings: Option<DebateSettings>,
 ) -> Result<(), String> {
     eprintln!("[Debate] start_debate called: run_id={}, rounds={}, speaking_order.len()={}", run_id, rounds, speaking_order.len());
     
     // Immediately update status to running so UI updates
-    {
-        let conn = db.get_connection();
-        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-        conn_guard.execute(
-            "UPDATE runs SET status = 'running' WHERE id = ?1",
-            rusqlite::params![run_id],
-        )
-        .map_err(|e| format!("Database error: {}", e))?;
-    }
+    run_state::transition(&db, &run_id, RunStatus::Running, "debate_started")?;
     
     if speaking_order.is_empty() {
         return Err("speaking_order is empty - no profiles selected".to_string());
@@ -60,7 +58,7 @@ pub async fn start_debate(
     
     let db_clone = db.inner().clone();
     let run_id_clone = run_id.clone();
-    let mut orchestrator = DebateOrchestrator::new(db_clone.clone());
+    let mut orchestrator = DebateOrchestrator::new(db_clone.clone()).with_settings(settings.unwrap_or_default());
     
     // Run in background with panic handling
     tokio::spawn(async move {
@@ -89,6 +87,16 @@ pub async fn start_debate(
     Ok(())
 }
 
+/// Resume an interrupted debate in the background (run must already be marked running).
+pub fn spawn_debate_resume(db: Database, run_id: String) {
+    tokio::spawn(async move {
+        let mut orchestrator = DebateOrchestrator::new(db);
+        if let Err(e) = orchestrator.resume_debate_run(run_id.clone()).await {
+            orchestrator.handle_error(&run_id, &format!("{}", e)).await;
+        }
+    });
+}
+
 #[tauri::command]
 pub async fn get_debate_messages(
     db: State<'_, Database>,
@@ -99,7 +107,7 @@ pub async fn get_debate_messages(
     
     // Orchestrator writes usage to provider_metadata_json
     let mut stmt = conn_guard
-        .prepare("SELECT id, author_type, profile_id, round_index, turn_index, text, created_at, provider_metadata_json FROM messages WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at")
+        .prepare("SELECT id, author_type, profile_id, round_index, turn_index, text, created_at, provider_metadata_json, author_label FROM messages WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at")
         .map_err(|e| format!("Database error: {}", e))?;
     
     let rows = stmt
@@ -116,6 +124,7 @@ pub async fn get_debate_messages(
                 "text": row.get::<_, String>(5)?,
                 "created_at": row.get::<_, String>(6)?,
                 "usage": usage,
+                "author_label": row.get::<_, Option<String>>(8)?,
             }))
         })
         .map_err(|e| format!("Database error: {}", e))?;
@@ -133,16 +142,8 @@ pub async fn pause_debate(
     db: State<'_, Database>,
     run_id: String,
 ) -> Result<(), String> {
-    // Mark debate as paused in database
-    let conn = db.get_connection();
-    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    conn_guard.execute(
-        "UPDATE runs SET status = 'paused' WHERE id = ?1",
-        rusqlite::params![run_id],
-    )
-    .map_err(|e| format!("Database error: {}", e))?;
-    
-    Ok(())
+    // Mark debate as paused in database; the orchestrator waits at its next checkpoint
+    run_state::transition(&db, &run_id, RunStatus::Paused, "user_pause")
 }
 
 #[tauri::command]
@@ -150,16 +151,15 @@ pub async fn resume_debate(
     db: State<'_, Database>,
     run_id: String,
 ) -> Result<(), String> {
+    // An interrupted debate has no worker left; pick it up from the last completed turn
+    if run_state::current_status(&db, &run_id)?.as_deref() == Some("interrupted") {
+        run_state::mark_resumed(&db, &run_id, &["interrupted"])?;
+        spawn_debate_resume(db.inner().clone(), run_id);
+        return Ok(());
+    }
+
     // Mark debate as running
-    let conn = db.get_connection();
-    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    conn_guard.execute(
-        "UPDATE runs SET status = 'running' WHERE id = ?1",
-        rusqlite::params![run_id],
-    )
-    .map_err(|e| format!("Database error: {}", e))?;
-    
-    Ok(())
+    run_state::transition(&db, &run_id, RunStatus::Running, "user_resume")
 }
 
 #[tauri::command]
@@ -168,16 +168,7 @@ pub async fn cancel_debate(
     run_id: String,
 ) -> Result<(), String> {
     // Mark debate as cancelled
-    let conn = db.get_connection();
-    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    let finished_at = chrono::Utc::now().to_rfc3339();
-    conn_guard.execute(
-        "UPDATE runs SET status = 'cancelled', finished_at = ?1 WHERE id = ?2",
-        rusqlite::params![finished_at, run_id],
-    )
-    .map_err(|e| format!("Database error: {}", e))?;
-    
-    Ok(())
+    run_state::transition(&db, &run_id, RunStatus::Cancelled, "user_cancel")
 }
 
 #[tauri::command]
@@ -202,7 +193,9 @@ pub async fn add_user_message(
     run_id: String,
     text: String,
     insert_after_message_id: Option<String>,
+    author_label: Option<String>,
 ) -> Result<String, String> {
+    let author_label = author_label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
     let message_id = uuid::Uuid::new_v4().to_string();
     let created_at = chrono::Utc::now().to_rfc3339();
     
@@ -250,7 +243,7 @@ pub async fn add_user_message(
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
     conn_guard.execute(
-        "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text, created_at, author_label) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         rusqlite::params![
             message_id,
             run_id,
@@ -259,7 +252,8 @@ pub async fn add_user_message(
             round_index,
             turn_index,
             text,
-            created_at
+            created_at,
+            author_label
         ],
     )
     .map_err(|e| format!("Database error: {}", e))?;
@@ -273,41 +267,18 @@ pub   async fn continue_debate(
     run_id: String,
     rounds: i32,
 ) -> Result<(), String> {
-    // Get current debate config and continue
-    let (speaking_order_json, max_words, language, tone): (String, Option<i32>, Option<String>, Option<String>) = {
-        let conn = db.get_connection();
-        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-        let result: Result<(String, Option<i32>, Option<String>, Option<String>), _> = conn_guard.query_row(
-            "SELECT speaking_order_json, max_words, language, tone FROM debate_configs WHERE run_id = ?1",
-            [&run_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-        );
-        result.map_err(|e| format!("Failed to get debate config: {}", e))?
-    };
-    
-    let speaking_order: Vec<String> = serde_json::from_str(&speaking_order_json)
-        .map_err(|e| format!("Failed to parse speaking order: {}", e))?;
+    // Continue with the stored mode, context policy, moderator, consensus and human seats
+    let config = load_debate_config(&db, &run_id).map_err(|e| e.to_string())?;
     
     // Mark as running and restart debate
-    {
-        let conn = db.get_connection();
-        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-        conn_guard.execute(
-            "UPDATE runs SET status = 'running' WHERE id = ?1",
-            rusqlite::params![run_id],
-        )
-        .map_err(|e| format!("Database error: {}", e))?;
-    }
+    run_state::transition(&db, &run_id, RunStatus::Running, "debate_continued")?;
     
     // Start new debate continuation
-    let db_clone = db.inner().clone();
-    let mut orchestrator = DebateOrchestrator::new(db_clone);
-    let language_clone = language.clone();
-    let tone_clone = tone.clone();
+    let mut orchestrator = DebateOrchestrator::new(db.inner().clone());
     
     tokio::spawn(async move {
-        if let Err(e) = orchestrator.run_debate(run_id, rounds, speaking_order, max_words, language_clone, tone_clone, None).await {
-            eprintln!("Debate continuation error: {}", e);
+        if let Err(e) = orchestrator.continue_debate_run(run_id.clone(), config, rounds).await {
+            orchestrator.handle_error(&run_id, &format!("{}", e)).await;
         }
     });
     
@@ -377,7 +348,7 @@ pub async fn export_session_markdown(
     } else {
         // Export debate messages
         let mut stmt = conn_guard
-            .prepare("SELECT author_type, profile_id, round_index, turn_index, text FROM messages WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at")
+            .prepare("SELECT author_type, profile_id, round_index, turn_index, text, author_label FROM messages WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at")
             .map_err(|e| format!("Database error: {}", e))?;
         
         let rows = stmt
@@ -388,6 +359,7 @@ pub async fn export_session_markdown(
                     row.get::<_, Option<i32>>(2)?,
                     row.get::<_, Option<i32>>(3)?,
                     row.get::<_, String>(4)?,
+                    row.get::<_, Option<String>>(5)?,
                 ))
             })
             .map_err(|e| format!("Database error: {}", e))?;
@@ -395,7 +367,7 @@ pub async fn export_session_markdown(
         markdown.push_str("## Debate Transcript\n\n");
         let mut current_round = -1;
         for row in rows {
-            let (author_type, profile_id, round_index, _turn_index, text) = row.map_err(|e| format!("Row error: {}", e))?;
+            let (author_type, profile_id, round_index, _turn_index, text, author_label) = row.map_err(|e| format!("Row error: {}", e))?;
             
             if let Some(round) = round_index {
                 if round != current_round {
@@ -406,15 +378,23 @@ pub async fn export_session_markdown(
             
             if author_type == "agent" {
                 markdown.push_str(&format!("**Agent {}:** {}\n\n", profile_id.unwrap_or_default(), text));
+            } else if let Some(label) = author_label {
+                markdown.push_str(&format!("**{} ({}):** {}\n\n", label, author_type, text));
             } else {
                 markdown.push_str(&format!("**{}:** {}\n\n", author_type, text));
             }
         }
+
+        if let Some(verdict) = consensus::load_verdict(&conn_guard, &run_id)? {
+            let names = consensus::participant_names(&conn_guard, &run_id)?;
+            markdown.push_str(&consensus::render_verdict_markdown(&verdict, &names));
+        }
     }
     
     Ok(markdown)
 }
 
+/// Full session export (see `session_transfer`), importable with `import_session_json`
 #[tauri::command]
 pub async fn export_session_json(
     db: State<'_, Database>,
@@ -422,92 +402,131 @@ pub async fn export_session_json(
 ) -> Result<serde_json::Value, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
-    
-    // Get session info
-    let session_data: Result<(String, String, String, String), _> = conn_guard.query_row(
-        "SELECT id, title, user_question, mode FROM sessions WHERE id = ?1",
-        [&session_id],
-        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-    );
-    
-    let (id, title, user_question, mode) = session_data
-        .map_err(|e| format!("Failed to load session: {}", e))?;
-    
-    // Get run
-    let run_data: Result<(String, String, Option<String>, Option<String>), _> = conn_guard.query_row(
-        "SELECT id, status, started_at, finished_at FROM runs WHERE session_id = ?1 ORDER BY started_at DESC LIMIT 1",
-        [&session_id],
-        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-    );
-    
-    let (run_id, run_status, started_at, finished_at) = run_data
-        .map_err(|e| format!("Failed to load run: {}", e))?;
-    
-    let mut export_data = serde_json::json!({
-        "session": {
-            "id": id,
-            "title": title,
-            "user_question": user_question,
-            "mode": mode,
-        },
-        "run": {
-            "id": run_id,
-            "status": run_status,
-            "started_at": started_at,
-            "finished_at": finished_at,
-        },
-    });
-    
-    if mode == "parallel" {
-        // Export parallel results
-        let mut stmt = conn_guard
-            .prepare("SELECT profile_id, status, raw_output_text, error_message_safe, started_at, finished_at FROM run_results WHERE run_id = ?1 ORDER BY started_at")
-            .map_err(|e| format!("Database error: {}", e))?;
-        
-        let rows = stmt
-            .query_map([&run_id], |row| {
-                Ok(serde_json::json!({
-                    "profile_id": row.get::<_, String>(0)?,
-                    "status": row.get::<_, String>(1)?,
-                    "raw_output_text": row.get::<_, Option<String>>(2)?,
-                    "error_message_safe": row.get::<_, Option<String>>(3)?,
-                    "started_at": row.get::<_, String>(4)?,
-                    "finished_at": row.get::<_, Option<String>>(5)?,
-                }))
-            })
-            .map_err(|e| format!("Database error: {}", e))?;
-        
-        let mut results = Vec::new();
-        for row in rows {
-            results.push(row.map_err(|e| format!("Row error: {}", e))?);
-        }
-        export_data["results"] = serde_json::json!(results);
-    } else {
-        // Export debate messages
-        let mut stmt = conn_guard
-            .prepare("SELECT id, author_type, profile_id, round_index, turn_index, text, created_at FROM messages WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at")
-            .map_err(|e| format!("Database error: {}", e))?;
-        
-        let rows = stmt
-            .query_map([&run_id], |row| {
-                Ok(serde_json::json!({
-                    "id": row.get::<_, String>(0)?,
-                    "author_type": row.get::<_, String>(1)?,
-                    "profile_id": row.get::<_, Option<String>>(2)?,
-                    "round_index": row.get::<_, Option<i32>>(3)?,
-                    "turn_index": row.get::<_, Option<i32>>(4)?,
-                    "text": row.get::<_, String>(5)?,
-                    "created_at": row.get::<_, String>(6)?,
-                }))
-            })
-            .map_err(|e| format!("Database error: {}", e))?;
-        
-        let mut messages = Vec::new();
-        for row in rows {
-            messages.push(row.map_err(|e| format!("Row error: {}", e))?);
-        }
-        export_data["messages"] = serde_json::json!(messages);
+    session_transfer::export(&conn_guard, &session_id)
+}
+
+/// Recreate a session from an `export_session_json` file, matching or creating its profiles
+#[tauri::command]
+pub async fn import_session_json(
+    db: State<'_, Database>,
+    content: String,
+    options: ImportOptions,
+) -> Result<ImportSummary, String> {
+    session_transfer::import(&db, &content, &options)
+}
+
+/// Per-round agreement scores and the stored verdict of a debate run
+#[tauri::command]
+pub async fn get_debate_consensus(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<serde_json::Value, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    Ok(serde_json::json!({
+        "checks": consensus::load_checks(&conn_guard, &run_id)?,
+        "verdict": consensus::load_verdict(&conn_guard, &run_id)?,
+    }))
+}
+
+/// Profile used for post-debate analysis when none is given: the configured judge, then the
+/// moderator, then the first speaker.
+fn default_analyst_profile(db: &Database, run_id: &str) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let (consensus_json, moderator, order_json): (Option<String>, Option<String>, String) = conn_guard
+        .query_row(
+            "SELECT consensus_json, moderator_profile_id, speaking_order_json FROM debate_configs WHERE run_id = ?1",
+            [run_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Failed to load debate config: {}", e))?;
+    consensus_json
+        .and_then(|s| serde_json::from_str::<consensus::ConsensusConfig>(&s).ok())
+        .and_then(|c| c.judge_profile_id)
+        .or(moderator)
+        .or_else(|| {
+            serde_json::from_str::<Vec<String>>(&order_json)
+                .ok()
+                .and_then(|ids| ids.into_iter().find(|id| human_turns::seat_id(id).is_none()))
+        })
+        .ok_or_else(|| "No judge profile available".to_string())
+}
+
+/// (Re)generate the verdict of a debate on demand, e.g. for runs started without a judge step
+#[tauri::command]
+pub async fn generate_debate_verdict(
+    db: State<'_, Database>,
+    run_id: String,
+    judge_profile_id: Option<String>,
+) -> Result<consensus::Verdict, String> {
+    let judge_profile_id = match judge_profile_id {
+        Some(id) => id,
+        None => default_analyst_profile(&db, &run_id)?,
+    };
+    consensus::generate_verdict(&db, &run_id, &judge_profile_id, "manual").await
+}
+
+/// Extract claims and supports/attacks relations from a debate transcript (replaces any previous graph)
+#[tauri::command]
+pub async fn extract_argument_graph(
+    db: State<'_, Database>,
+    run_id: String,
+    extractor_profile_id: Option<String>,
+) -> Result<ArgumentGraph, String> {
+    let extractor_profile_id = match extractor_profile_id {
+        Some(id) => id,
+        None => default_analyst_profile(&db, &run_id)?,
+    };
+    argument_graph::extract(&db, &run_id, &extractor_profile_id).await
+}
+
+#[tauri::command]
+pub async fn get_argument_graph(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<ArgumentGraph, String> {
+    argument_graph::load(&db, &run_id)
+}
+
+/// Stored argument graph as "json", "graphml" or "mermaid" text
+#[tauri::command]
+pub async fn export_argument_graph(
+    db: State<'_, Database>,
+    run_id: String,
+    format: String,
+) -> Result<String, String> {
+    let graph = argument_graph::load(&db, &run_id)?;
+    argument_graph::export(&graph, &format)
+}
+
+/// Human turns the orchestrator is currently waiting on
+#[tauri::command]
+pub async fn get_pending_human_turns(
+    db: State<'_, Database>,
+    run_id: String,
+) -> Result<Vec<HumanTurn>, String> {
+    human_turns::pending_turns(&db, &run_id)
+}
+
+/// Answer a waiting human turn; the message is attributed to the seat's role
+#[tauri::command]
+pub async fn submit_human_turn(
+    db: State<'_, Database>,
+    turn_id: String,
+    text: String,
+) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
     }
-    
-    Ok(export_data)
+    human_turns::submit_turn(&db, &turn_id, text.trim())
+}
+
+/// Pass on a waiting human turn so the debate moves on
+#[tauri::command]
+pub async fn skip_human_turn(
+    db: State<'_, Database>,
+    turn_id: String,
+) -> Result<(), String> {
+    human_turns::close_turn(&db, &turn_id, TurnStatus::Skipped)
 }
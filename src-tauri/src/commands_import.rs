@@ -1157,7 +1157,7 @@ fn detect_format_from_content(content: &str) -> String {
     "txt".to_string()
 }
 
-fn extract_text_from_file(path: &str) -> Result<String, String> {
+pub(crate) fn extract_text_from_file(path: &str) -> Result<String, String> {
     let path_lower = path.to_lowercase();
     
     if path_lower.ends_with(".pdf") {
@@ -0,0 +1,58 @@
+// MCP server configuration commands
+
+use crate::commands_settings::{get_app_settings, save_app_settings};
+use crate::db::Database;
+use crate::mcp::{self, protocol::McpResource, protocol::McpTool, CatalogTool, McpClient, McpServerConfig};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use tauri::State;
+
+#[derive(Debug, Serialize)]
+pub struct McpServerProbe {
+    pub server_info: Value,
+    pub tools: Vec<McpTool>,
+    /// Empty when the server does not offer resources
+    pub resources: Vec<McpResource>,
+}
+
+#[tauri::command]
+pub async fn list_mcp_servers(db: State<'_, Database>) -> Result<Vec<McpServerConfig>, String> {
+    Ok(get_app_settings(db).await?.mcp_servers)
+}
+
+#[tauri::command]
+pub async fn save_mcp_servers(
+    db: State<'_, Database>,
+    servers: Vec<McpServerConfig>,
+) -> Result<Vec<McpServerConfig>, String> {
+    let mut names = HashSet::new();
+    for server in &servers {
+        let name = server.name.trim();
+        if name.is_empty() {
+            return Err("Every MCP server needs a name".to_string());
+        }
+        if !names.insert(name.to_string()) {
+            return Err(format!("Duplicate MCP server name '{}'", name));
+        }
+    }
+    let mut settings = get_app_settings(db).await?;
+    settings.mcp_servers = servers;
+    save_app_settings(db, settings.clone()).await?;
+    Ok(settings.mcp_servers)
+}
+
+/// Connect to a server with an unsaved config and list what it offers
+#[tauri::command]
+pub async fn test_mcp_server(server: McpServerConfig) -> Result<McpServerProbe, String> {
+    let client = McpClient::connect(&server).await?;
+    let tools = client.list_tools().await?;
+    let resources = client.list_resources().await.unwrap_or_default();
+    Ok(McpServerProbe { server_info: client.server_info.clone(), tools, resources })
+}
+
+/// Tools of all enabled servers, as offered to the Cline agent
+#[tauri::command]
+pub async fn list_mcp_tools() -> Result<Vec<CatalogTool>, String> {
+    Ok(mcp::tool_catalog().await)
+}
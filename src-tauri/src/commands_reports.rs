@@ -0,0 +1,43 @@
+// Report export commands (HTML / PDF / DOCX)
+
+use crate::db::Database;
+use crate::report::{self, ReportFile, ReportFormat};
+use tauri::State;
+
+/// Export a debate or brainstorm session as a stakeholder report
+pub fn export_session_report_impl(db: &Database, session_id: &str, format: &str) -> Result<ReportFile, String> {
+    let format = ReportFormat::parse(format)?;
+    let report = report::build_session_report(db, session_id)?;
+    report::render(&report, format)
+}
+
+#[tauri::command]
+pub async fn export_session_report(
+    db: State<'_, Database>,
+    session_id: String,
+    format: String,
+) -> Result<ReportFile, String> {
+    export_session_report_impl(&db, &session_id, &format)
+}
+
+/// Export a profile chat; without a conversation id the profile's default chat is exported
+pub fn export_conversation_report_impl(
+    db: &Database,
+    profile_id: &str,
+    conversation_id: Option<&str>,
+    format: &str,
+) -> Result<ReportFile, String> {
+    let format = ReportFormat::parse(format)?;
+    let report = report::build_conversation_report(db, profile_id, conversation_id)?;
+    report::render(&report, format)
+}
+
+#[tauri::command]
+pub async fn export_conversation_report(
+    db: State<'_, Database>,
+    profile_id: String,
+    conversation_id: Option<String>,
+    format: String,
+) -> Result<ReportFile, String> {
+    export_conversation_report_impl(&db, &profile_id, conversation_id.as_deref(), &format)
+}
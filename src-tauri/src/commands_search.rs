@@ -0,0 +1,18 @@
+// History search commands
+
+use crate::db::Database;
+use crate::search::{self, SearchHit, SearchRequest};
+use tauri::State;
+
+pub fn search_history_impl(db: &Database, request: &SearchRequest) -> Result<Vec<SearchHit>, String> {
+    search::search(db, request)
+}
+
+/// Full-text search over chats, debates, brainstorm results and coder conversations
+#[tauri::command]
+pub async fn search_history(
+    db: State<'_, Database>,
+    request: SearchRequest,
+) -> Result<Vec<SearchHit>, String> {
+    search_history_impl(&db, &request)
+}
@@ -61,6 +61,9 @@ pub struct AppSettings {
     /// Relative paths are resolved from the workspace/project root.
     #[serde(default)]
     pub global_system_prompt_file: Option<String>,
+    /// External MCP servers whose tools the Cline agent may call
+    #[serde(default)]
+    pub mcp_servers: Vec<crate::mcp::McpServerConfig>,
 }
 
 impl AppSettings {
@@ -126,6 +129,7 @@ impl Default for AppSettings {
                 train_from_debate: true,
             },
             global_system_prompt_file: None,
+            mcp_servers: Vec::new(),
         }
     }
 }
@@ -170,6 +174,7 @@ pub async fn save_app_settings(
         "INSERT OR REPLACE INTO app_settings (id, settings_json, updated_at) VALUES ('default', ?1, datetime('now'))",
         rusqlite::params![settings_json],
     ).map_err(|e| format!("Failed to save settings: {}", e))?;
+    crate::mcp::configure(&settings.mcp_servers);
     
     Ok(())
 }
@@ -209,6 +214,12 @@ pub async fn read_global_prompt_file(db: State<'_, Database>) -> Result<Option<S
     Ok(read_global_prompt_from_file(&settings))
 }
 
+/// Names of the registered prompt middleware, for building `prompt_pipeline` configs in the UI.
+#[tauri::command]
+pub async fn list_prompt_middleware() -> Result<Vec<String>, String> {
+    Ok(crate::prompt_pipeline::list_middleware())
+}
+
 #[tauri::command]
 pub async fn update_training_settings(
     db: State<'_, Database>,
@@ -0,0 +1,150 @@
+// Session template (SOP) commands
+
+use crate::commands::{self, CreateSessionRequest};
+use crate::db::Database;
+use crate::sops::{self, Sop, SopTemplate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveSopRequest {
+    pub name: String,
+    pub project_id: Option<String>,
+    pub template: SopTemplate,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstantiateSopRequest {
+    #[serde(default)]
+    pub sop_id: String,
+    /// Project of the new session; defaults to the template's project
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstantiatedSop {
+    pub run_id: String,
+    pub mode: String,
+}
+
+pub fn list_sops_impl(db: &Database, project_id: Option<String>) -> Result<Vec<Sop>, String> {
+    sops::list_latest(db, project_id.as_deref())
+}
+
+#[tauri::command]
+pub async fn list_sops(
+    db: State<'_, Database>,
+    project_id: Option<String>,
+) -> Result<Vec<Sop>, String> {
+    list_sops_impl(&db, project_id)
+}
+
+#[tauri::command]
+pub async fn list_sop_versions(
+    db: State<'_, Database>,
+    name: String,
+) -> Result<Vec<Sop>, String> {
+    sops::list_versions(&db, &name)
+}
+
+pub fn get_sop_impl(db: &Database, sop_id: &str) -> Result<Sop, String> {
+    sops::get(db, sop_id)
+}
+
+#[tauri::command]
+pub async fn get_sop(
+    db: State<'_, Database>,
+    sop_id: String,
+) -> Result<Sop, String> {
+    get_sop_impl(&db, &sop_id)
+}
+
+/// Save a template; saving under an existing name creates the next version
+pub fn save_sop_impl(db: &Database, request: SaveSopRequest) -> Result<Sop, String> {
+    sops::save(db, &request.name, request.project_id.as_deref(), &request.template)
+}
+
+#[tauri::command]
+pub async fn save_sop(
+    db: State<'_, Database>,
+    request: SaveSopRequest,
+) -> Result<Sop, String> {
+    save_sop_impl(&db, request)
+}
+
+pub fn delete_sop_impl(db: &Database, name: &str) -> Result<(), String> {
+    sops::delete(db, name)
+}
+
+#[tauri::command]
+pub async fn delete_sop(
+    db: State<'_, Database>,
+    name: String,
+) -> Result<(), String> {
+    delete_sop_impl(&db, &name)
+}
+
+/// Create a session (and its queued run) from a template with its variables filled in
+pub async fn instantiate_sop_impl(db: &Database, request: InstantiateSopRequest) -> Result<InstantiatedSop, String> {
+    let sop = sops::get(db, &request.sop_id)?;
+    let template = sop.template.instantiate(&request.variables)?;
+    let project_id = request
+        .project_id
+        .or_else(|| sop.project_id.clone())
+        .ok_or_else(|| "Select a project for the new session".to_string())?;
+    if template.profile_ids.is_empty() {
+        return Err("Template has no participant profiles".to_string());
+    }
+    let title = if template.title.trim().is_empty() {
+        template.question.chars().take(50).collect()
+    } else {
+        template.title.clone()
+    };
+    let run_id = commands::create_session_impl(
+        db,
+        CreateSessionRequest {
+            project_id,
+            title,
+            user_question: template.question.clone(),
+            mode: template.mode.clone(),
+            selected_profile_ids: template.profile_ids.clone(),
+            run_settings: Some(template.run_settings(&sop)),
+            local_model_id: None,
+        },
+    )
+    .await?;
+    Ok(InstantiatedSop { run_id, mode: template.mode })
+}
+
+#[tauri::command]
+pub async fn instantiate_sop(
+    db: State<'_, Database>,
+    request: InstantiateSopRequest,
+) -> Result<InstantiatedSop, String> {
+    instantiate_sop_impl(&db, request).await
+}
+
+/// Template as a JSON file (pretty-printed)
+#[tauri::command]
+pub async fn export_sop(
+    db: State<'_, Database>,
+    sop_id: String,
+) -> Result<String, String> {
+    let sop = sops::get(&db, &sop_id)?;
+    serde_json::to_string_pretty(&sops::export(&sop)).map_err(|e| format!("Failed to serialize template: {}", e))
+}
+
+/// Import a template file; an existing name gets a new version
+#[tauri::command]
+pub async fn import_sop(
+    db: State<'_, Database>,
+    content: String,
+    name: Option<String>,
+    project_id: Option<String>,
+) -> Result<Sop, String> {
+    let (name, template) = sops::parse_import(&content, name.as_deref())?;
+    sops::save(&db, &name, project_id.as_deref(), &template)
+}
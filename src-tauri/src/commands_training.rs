@@ -459,6 +459,14 @@ pub async fn list_training_data(
     db: State<'_, Database>,
     project_id: Option<String>,
     local_model_id: Option<String>,
+) -> Result<Vec<serde_json::Value>, String> {
+    list_training_data_impl(&db, project_id, local_model_id).await
+}
+
+pub async fn list_training_data_impl(
+    db: &Database,
+    project_id: Option<String>,
+    local_model_id: Option<String>,
 ) -> Result<Vec<serde_json::Value>, String> {
     let conn = db.get_connection();
     let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
@@ -0,0 +1,607 @@
+// Consensus detection and verdicts for debates.
+// After each round a judge profile extracts every participant's current stance. Agreement is the
+// mean of the judge's own estimate and the pairwise lexical similarity of the extracted stances;
+// the debate stops early once agreement crosses a threshold or stops improving. A structured
+// verdict (agreements, open disagreements, strongest arguments with message references) is then
+// stored in `syntheses` with method 'verdict'.
+
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::tournament::extract_json_object;
+use crate::types::PromptPacket;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Longest excerpt of a single message shown to the judge.
+const MAX_MESSAGE_CHARS: usize = 1500;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "in", "is", "it", "its",
+    "of", "on", "or", "should", "that", "the", "their", "this", "to", "was", "we", "were", "will", "with", "would",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsensusConfig {
+    pub enabled: bool,
+    /// Judge profile; defaults to the moderator, then the first speaker.
+    pub judge_profile_id: Option<String>,
+    /// Stop once agreement reaches this score (0..1).
+    pub agreement_threshold: f64,
+    /// Stop after this many consecutive rounds whose agreement improved by less than `stall_delta`.
+    pub stall_rounds: usize,
+    pub stall_delta: f64,
+    /// Never stop before this many rounds are complete.
+    pub min_rounds: i32,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        ConsensusConfig {
+            enabled: false,
+            judge_profile_id: None,
+            agreement_threshold: 0.8,
+            stall_rounds: 2,
+            stall_delta: 0.05,
+            min_rounds: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusDecision {
+    Continue,
+    Consensus,
+    Stalled,
+}
+
+impl ConsensusDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsensusDecision::Continue => "continue",
+            ConsensusDecision::Consensus => "consensus",
+            ConsensusDecision::Stalled => "stalled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stance {
+    pub profile_id: String,
+    pub stance: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsensusCheck {
+    pub round_index: i32,
+    pub agreement_score: f64,
+    pub lexical_similarity: f64,
+    pub judge_agreement: Option<f64>,
+    pub stances: Vec<Stance>,
+    pub decision: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgreementPoint {
+    pub point: String,
+    pub profile_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Position {
+    pub profile_id: String,
+    pub position: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Disagreement {
+    pub point: String,
+    pub positions: Vec<Position>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArgumentRef {
+    pub profile_id: String,
+    pub message_id: String,
+    pub argument: String,
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Verdict {
+    /// "consensus", "stalled", "rounds_exhausted" or "manual"
+    pub outcome: String,
+    pub agreement_score: Option<f64>,
+    pub summary: String,
+    pub agreements: Vec<AgreementPoint>,
+    pub disagreements: Vec<Disagreement>,
+    pub strongest_arguments: Vec<ArgumentRef>,
+}
+
+struct TranscriptMessage {
+    id: String,
+    author_type: String,
+    profile_id: Option<String>,
+    round_index: Option<i32>,
+    text: String,
+    /// Role of a human participant
+    author_label: Option<String>,
+}
+
+fn tokens(text: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() > 1 && !STOPWORDS.contains(&w.as_str()))
+    {
+        *counts.entry(word).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+/// Cosine similarity of term-frequency vectors (0 when either text has no content words).
+pub fn cosine_similarity(a: &str, b: &str) -> f64 {
+    let (ta, tb) = (tokens(a), tokens(b));
+    let dot: f64 = ta.iter().filter_map(|(w, x)| tb.get(w).map(|y| x * y)).sum();
+    let norm = |t: &HashMap<String, f64>| t.values().map(|v| v * v).sum::<f64>().sqrt();
+    let denom = norm(&ta) * norm(&tb);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+/// Mean pairwise similarity of the stances; 1.0 for a single participant.
+pub fn mean_pairwise_similarity(stances: &[Stance]) -> f64 {
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for i in 0..stances.len() {
+        for j in (i + 1)..stances.len() {
+            total += cosine_similarity(&stances[i].stance, &stances[j].stance);
+            pairs += 1;
+        }
+    }
+    if pairs == 0 {
+        1.0
+    } else {
+        total / pairs as f64
+    }
+}
+
+/// Decide whether to keep debating given this round's score and the scores of earlier rounds.
+pub fn decide(config: &ConsensusConfig, round_index: i32, agreement: f64, previous: &[f64]) -> ConsensusDecision {
+    if round_index + 1 < config.min_rounds {
+        return ConsensusDecision::Continue;
+    }
+    if agreement >= config.agreement_threshold {
+        return ConsensusDecision::Consensus;
+    }
+    if config.stall_rounds == 0 || previous.len() < config.stall_rounds {
+        return ConsensusDecision::Continue;
+    }
+    // Each of the last `stall_rounds` rounds must have improved by less than stall_delta
+    let mut scores: Vec<f64> = previous[previous.len() - config.stall_rounds..].to_vec();
+    scores.push(agreement);
+    if scores.windows(2).all(|w| w[1] - w[0] < config.stall_delta) {
+        ConsensusDecision::Stalled
+    } else {
+        ConsensusDecision::Continue
+    }
+}
+
+fn load_transcript(conn: &Connection, run_id: &str) -> Result<Vec<TranscriptMessage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, author_type, profile_id, round_index, text, author_label FROM messages
+             WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok(TranscriptMessage {
+                id: row.get(0)?,
+                author_type: row.get(1)?,
+                profile_id: row.get(2)?,
+                round_index: row.get(3)?,
+                text: row.get(4)?,
+                author_label: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut messages = Vec::new();
+    for row in rows {
+        messages.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    Ok(messages)
+}
+
+/// Names of the profiles that spoke in a run.
+pub fn participant_names(conn: &Connection, run_id: &str) -> Result<HashMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT p.id, p.name FROM messages m JOIN prompt_profiles p ON p.id = m.profile_id
+             WHERE m.run_id = ?1",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut names = HashMap::new();
+    for row in rows {
+        let (id, name) = row.map_err(|e| format!("Row error: {}", e))?;
+        names.insert(id, name);
+    }
+    Ok(names)
+}
+
+fn speaker(names: &HashMap<String, String>, m: &TranscriptMessage) -> String {
+    match m.author_type.as_str() {
+        "agent" => m
+            .profile_id
+            .as_ref()
+            .map(|id| names.get(id).cloned().unwrap_or_else(|| id.clone()))
+            .unwrap_or_else(|| "Agent".to_string()),
+        "moderator" => "Moderator".to_string(),
+        _ => m.author_label.clone().unwrap_or_else(|| "User".to_string()),
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    if text.chars().count() > MAX_MESSAGE_CHARS {
+        format!("{}…", text.chars().take(MAX_MESSAGE_CHARS).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+fn load_question(conn: &Connection, run_id: &str) -> Result<String, String> {
+    conn.query_row(
+        "SELECT s.user_question FROM runs r JOIN sessions s ON s.id = r.session_id WHERE r.id = ?1",
+        [run_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to load debate question: {}", e))
+}
+
+async fn ask_judge(db: &Database, judge_profile_id: &str, prompt: String) -> Result<Value, String> {
+    let (provider_account_id, model_name): (String, String) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .query_row(
+                "SELECT provider_account_id, model_name FROM prompt_profiles WHERE id = ?1",
+                [judge_profile_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Failed to load judge profile {}: {}", judge_profile_id, e))?
+    };
+    let packet = PromptPacket {
+        global_instructions: Some(
+            "You are an impartial judge of a debate. Reply with a single JSON object only.".to_string(),
+        ),
+        persona_instructions: "You analyse debates neutrally and precisely.".to_string(),
+        user_message: prompt,
+        conversation_context: None,
+        params_json: json!({ "temperature": 0.0 }),
+        stream: false,
+    };
+    let (response, _, _) = complete_resolving_hybrid(db, &provider_account_id, &model_name, &packet, 120, None).await?;
+    extract_json_object(&response.text).ok_or_else(|| "Judge did not return a JSON object".to_string())
+}
+
+/// Extract stances for `round_index`, score agreement, persist the check and decide whether the
+/// debate should continue.
+pub async fn check_round(
+    db: &Database,
+    run_id: &str,
+    round_index: i32,
+    judge_profile_id: &str,
+    config: &ConsensusConfig,
+) -> Result<ConsensusCheck, String> {
+    let (question, names, latest, previous_scores) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let question = load_question(&conn_guard, run_id)?;
+        let names = participant_names(&conn_guard, run_id)?;
+        // Each participant's most recent statement up to and including this round
+        let mut latest: Vec<TranscriptMessage> = Vec::new();
+        for m in load_transcript(&conn_guard, run_id)?
+            .into_iter()
+            .filter(|m| m.author_type == "agent" && m.round_index.unwrap_or(0) <= round_index)
+        {
+            latest.retain(|l| l.profile_id != m.profile_id);
+            latest.push(m);
+        }
+        let previous_scores = load_checks(&conn_guard, run_id)?
+            .into_iter()
+            .filter(|c| c.round_index < round_index)
+            .map(|c| c.agreement_score)
+            .collect::<Vec<_>>();
+        (question, names, latest, previous_scores)
+    };
+    if latest.is_empty() {
+        return Err("No participant messages to judge".to_string());
+    }
+
+    let statements = latest
+        .iter()
+        .map(|m| format!("[{}] {}:\n{}", m.profile_id.clone().unwrap_or_default(), speaker(&names, m), excerpt(&m.text)))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let prompt = format!(
+        "Debate question:\n{}\n\nLatest statement of each participant (participant id in brackets):\n{}\n\n\
+        Task: For each participant, state their current position on the question in one neutral sentence, \
+        using the same wording when two participants hold the same position. Then estimate how much the \
+        participants agree overall, from 0 (fully opposed) to 1 (full consensus).\n\
+        Reply as JSON: {{\"stances\": [{{\"profile_id\": \"<id>\", \"stance\": \"<one sentence>\"}}], \"agreement\": <0-1>}}",
+        question, statements
+    );
+    let reply = ask_judge(db, judge_profile_id, prompt).await?;
+
+    let known: HashSet<&str> = latest.iter().filter_map(|m| m.profile_id.as_deref()).collect();
+    let stances: Vec<Stance> = reply
+        .get("stances")
+        .and_then(|v| serde_json::from_value::<Vec<Stance>>(v.clone()).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| known.contains(s.profile_id.as_str()) && !s.stance.trim().is_empty())
+        .collect();
+    if stances.is_empty() {
+        return Err("Judge returned no usable stances".to_string());
+    }
+    let judge_agreement = reply.get("agreement").and_then(|v| v.as_f64()).map(|a| a.clamp(0.0, 1.0));
+    let lexical_similarity = mean_pairwise_similarity(&stances);
+    let agreement_score = match judge_agreement {
+        Some(a) => (a + lexical_similarity) / 2.0,
+        None => lexical_similarity,
+    };
+    let decision = decide(config, round_index, agreement_score, &previous_scores);
+
+    let check = ConsensusCheck {
+        round_index,
+        agreement_score,
+        lexical_similarity,
+        judge_agreement,
+        stances,
+        decision: decision.as_str().to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .execute(
+                "INSERT INTO debate_consensus_checks (id, run_id, round_index, agreement_score, lexical_similarity, judge_agreement, stances_json, decision, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    Uuid::new_v4().to_string(),
+                    run_id,
+                    round_index,
+                    check.agreement_score,
+                    check.lexical_similarity,
+                    check.judge_agreement,
+                    serde_json::to_string(&check.stances).unwrap_or_else(|_| "[]".to_string()),
+                    check.decision,
+                    check.created_at
+                ],
+            )
+            .map_err(|e| format!("Failed to save consensus check: {}", e))?;
+    }
+    Ok(check)
+}
+
+pub fn load_checks(conn: &Connection, run_id: &str) -> Result<Vec<ConsensusCheck>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT round_index, agreement_score, lexical_similarity, judge_agreement, stances_json, decision, created_at
+             FROM debate_consensus_checks WHERE run_id = ?1 ORDER BY round_index, created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok(ConsensusCheck {
+                round_index: row.get(0)?,
+                agreement_score: row.get(1)?,
+                lexical_similarity: row.get(2)?,
+                judge_agreement: row.get(3)?,
+                stances: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default(),
+                decision: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut checks = Vec::new();
+    for row in rows {
+        checks.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    Ok(checks)
+}
+
+/// Ask the judge for a structured verdict over the whole transcript and store it in `syntheses`,
+/// replacing any earlier verdict for the run.
+pub async fn generate_verdict(db: &Database, run_id: &str, judge_profile_id: &str, outcome: &str) -> Result<Verdict, String> {
+    let (question, names, transcript, last_score) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let last_score = load_checks(&conn_guard, run_id)?.last().map(|c| c.agreement_score);
+        (
+            load_question(&conn_guard, run_id)?,
+            participant_names(&conn_guard, run_id)?,
+            load_transcript(&conn_guard, run_id)?,
+            last_score,
+        )
+    };
+    if transcript.is_empty() {
+        return Err("Debate has no messages".to_string());
+    }
+
+    let lines = transcript
+        .iter()
+        .map(|m| {
+            let who = match &m.profile_id {
+                Some(id) if m.author_type == "agent" => format!("{} ({})", speaker(&names, m), id),
+                _ => speaker(&names, m),
+            };
+            format!("[msg:{}] Round {} — {}:\n{}", m.id, m.round_index.unwrap_or(0), who, excerpt(&m.text))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let prompt = format!(
+        "Debate question:\n{}\n\nTranscript (message ids in brackets, participant ids in parentheses):\n{}\n\n\
+        Task: Write the verdict of this debate.\n\
+        - summary: 2-4 sentences on where the debate ended up.\n\
+        - agreements: points every or most participants accept, with the participant ids who hold them.\n\
+        - disagreements: points still unresolved, with each participant's position.\n\
+        - strongest_arguments: up to 5 of the most convincing arguments, each citing the message id it comes from.\n\
+        Reply as JSON: {{\"summary\": \"...\", \
+        \"agreements\": [{{\"point\": \"...\", \"profile_ids\": [\"<id>\"]}}], \
+        \"disagreements\": [{{\"point\": \"...\", \"positions\": [{{\"profile_id\": \"<id>\", \"position\": \"...\"}}]}}], \
+        \"strongest_arguments\": [{{\"profile_id\": \"<id>\", \"message_id\": \"<message id>\", \"argument\": \"...\", \"rationale\": \"why it is strong\"}}]}}",
+        question, lines
+    );
+    let reply = ask_judge(db, judge_profile_id, prompt).await?;
+    let mut verdict: Verdict = serde_json::from_value(reply).map_err(|e| format!("Invalid verdict JSON: {}", e))?;
+    verdict.outcome = outcome.to_string();
+    verdict.agreement_score = last_score;
+
+    // Keep only references to messages that exist in this run; fill in the speaker from the message
+    let by_id: HashMap<&str, &TranscriptMessage> = transcript.iter().map(|m| (m.id.as_str(), m)).collect();
+    verdict.strongest_arguments.retain_mut(|a| {
+        let id = a.message_id.trim().trim_start_matches("msg:").to_string();
+        match by_id.get(id.as_str()) {
+            Some(m) => {
+                a.message_id = id;
+                if let Some(pid) = &m.profile_id {
+                    a.profile_id = pid.clone();
+                }
+                true
+            }
+            None => false,
+        }
+    });
+
+    let referenced: Vec<&str> = verdict.strongest_arguments.iter().map(|a| a.message_id.as_str()).collect();
+    let verdict_json = serde_json::to_string(&verdict).map_err(|e| format!("Failed to serialize verdict: {}", e))?;
+    {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .execute("DELETE FROM syntheses WHERE run_id = ?1 AND method = 'verdict'", [run_id])
+            .map_err(|e| format!("Failed to replace verdict: {}", e))?;
+        conn_guard
+            .execute(
+                "INSERT INTO syntheses (id, run_id, method, synthesizer_profile_id, text, source_result_ids_json) VALUES (?1, ?2, 'verdict', ?3, ?4, ?5)",
+                rusqlite::params![
+                    Uuid::new_v4().to_string(),
+                    run_id,
+                    judge_profile_id,
+                    verdict_json,
+                    serde_json::to_string(&referenced).unwrap_or_else(|_| "[]".to_string())
+                ],
+            )
+            .map_err(|e| format!("Failed to save verdict: {}", e))?;
+    }
+    Ok(verdict)
+}
+
+pub fn load_verdict(conn: &Connection, run_id: &str) -> Result<Option<Verdict>, String> {
+    let text: Option<String> = match conn.query_row(
+        "SELECT text FROM syntheses WHERE run_id = ?1 AND method = 'verdict' ORDER BY created_at DESC LIMIT 1",
+        [run_id],
+        |row| row.get(0),
+    ) {
+        Ok(t) => Some(t),
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(format!("Database error: {}", e)),
+    };
+    Ok(text.and_then(|t| serde_json::from_str(&t).ok()))
+}
+
+/// Markdown section for session exports.
+pub fn render_verdict_markdown(verdict: &Verdict, names: &HashMap<String, String>) -> String {
+    let name = |id: &String| names.get(id).cloned().unwrap_or_else(|| id.clone());
+    let mut md = String::from("## Verdict\n\n");
+    md.push_str(&format!("**Outcome:** {}", verdict.outcome.replace('_', " ")));
+    if let Some(score) = verdict.agreement_score {
+        md.push_str(&format!(" (agreement {:.0}%)", score * 100.0));
+    }
+    md.push_str("\n\n");
+    if !verdict.summary.is_empty() {
+        md.push_str(&format!("{}\n\n", verdict.summary));
+    }
+    if !verdict.agreements.is_empty() {
+        md.push_str("### Points of Agreement\n\n");
+        for a in &verdict.agreements {
+            let who = a.profile_ids.iter().map(name).collect::<Vec<_>>().join(", ");
+            if who.is_empty() {
+                md.push_str(&format!("- {}\n", a.point));
+            } else {
+                md.push_str(&format!("- {} ({})\n", a.point, who));
+            }
+        }
+        md.push('\n');
+    }
+    if !verdict.disagreements.is_empty() {
+        md.push_str("### Unresolved Disagreements\n\n");
+        for d in &verdict.disagreements {
+            md.push_str(&format!("- {}\n", d.point));
+            for p in &d.positions {
+                md.push_str(&format!("  - {}: {}\n", name(&p.profile_id), p.position));
+            }
+        }
+        md.push('\n');
+    }
+    if !verdict.strongest_arguments.is_empty() {
+        md.push_str("### Strongest Arguments\n\n");
+        for a in &verdict.strongest_arguments {
+            md.push_str(&format!("- **{}:** {} _(message {})_", name(&a.profile_id), a.argument, a.message_id));
+            if !a.rationale.is_empty() {
+                md.push_str(&format!(" — {}", a.rationale));
+            }
+            md.push('\n');
+        }
+        md.push('\n');
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stance(id: &str, text: &str) -> Stance {
+        Stance { profile_id: id.to_string(), stance: text.to_string() }
+    }
+
+    #[test]
+    fn test_similarity_of_identical_and_disjoint_stances() {
+        assert!((cosine_similarity("Adopt remote work policy", "adopt remote work policy") - 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity("Adopt remote work", "Reject office mandate"), 0.0);
+        let stances = vec![stance("a", "ban cars downtown"), stance("b", "ban cars downtown"), stance("c", "keep cars")];
+        let sim = mean_pairwise_similarity(&stances);
+        assert!(sim > 0.3 && sim < 1.0);
+    }
+
+    #[test]
+    fn test_decide_consensus_stall_and_min_rounds() {
+        let config = ConsensusConfig { enabled: true, min_rounds: 2, ..Default::default() };
+        // Below min_rounds never stops
+        assert_eq!(decide(&config, 0, 0.95, &[]), ConsensusDecision::Continue);
+        assert_eq!(decide(&config, 1, 0.85, &[0.5]), ConsensusDecision::Consensus);
+        // Two rounds without meaningful improvement
+        assert_eq!(decide(&config, 2, 0.42, &[0.40, 0.41]), ConsensusDecision::Stalled);
+        assert_eq!(decide(&config, 2, 0.60, &[0.40, 0.41]), ConsensusDecision::Continue);
+    }
+}
@@ -119,6 +119,111 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         set_version(conn, 21)?;
     }
 
+    if current_version < 22 {
+        migration_024_add_brainstorm_pipeline_stages(conn)?;
+        set_version(conn, 22)?;
+    }
+
+    if current_version < 23 {
+        migration_025_add_evaluation_ranking(conn)?;
+        set_version(conn, 23)?;
+    }
+
+    if current_version < 24 {
+        migration_026_add_run_state_machine(conn)?;
+        set_version(conn, 24)?;
+    }
+
+    if current_version < 25 {
+        migration_027_add_debate_moderator(conn)?;
+        set_version(conn, 25)?;
+    }
+
+    if current_version < 26 {
+        migration_028_add_conversation_summaries(conn)?;
+        set_version(conn, 26)?;
+    }
+
+    if current_version < 27 {
+        migration_029_add_debate_consensus(conn)?;
+        set_version(conn, 27)?;
+    }
+
+    if current_version < 28 {
+        migration_030_add_argument_graph(conn)?;
+        set_version(conn, 28)?;
+    }
+
+    if current_version < 29 {
+        migration_031_add_human_seats(conn)?;
+        set_version(conn, 29)?;
+    }
+
+    if current_version < 30 {
+        migration_032_add_sop_versions(conn)?;
+        set_version(conn, 30)?;
+    }
+
+    if current_version < 31 {
+        migration_033_add_session_origin(conn)?;
+        set_version(conn, 31)?;
+    }
+
+    if current_version < 32 {
+        migration_034_add_search_index(conn)?;
+        set_version(conn, 32)?;
+    }
+
+    if current_version < 33 {
+        migration_035_add_chat_branches(conn)?;
+        set_version(conn, 33)?;
+    }
+
+    if current_version < 34 {
+        migration_036_add_group_chats(conn)?;
+        set_version(conn, 34)?;
+    }
+
+    if current_version < 35 {
+        migration_037_add_profile_versions(conn)?;
+        set_version(conn, 35)?;
+    }
+
+    if current_version < 36 {
+        migration_038_add_profile_memories(conn)?;
+        set_version(conn, 36)?;
+    }
+
+    if current_version < 37 {
+        migration_039_add_cline_iterations(conn)?;
+        set_version(conn, 37)?;
+    }
+
+    if current_version < 38 {
+        migration_040_add_checkpoint_blobs(conn)?;
+        set_version(conn, 38)?;
+    }
+
+    if current_version < 39 {
+        migration_041_unique_debate_configs(conn)?;
+        set_version(conn, 39)?;
+    }
+
+    if current_version < 40 {
+        migration_042_add_debate_first_round(conn)?;
+        set_version(conn, 40)?;
+    }
+
+    if current_version < 41 {
+        migration_043_add_profile_chat_state(conn)?;
+        set_version(conn, 41)?;
+    }
+
+    if current_version < 42 {
+        migration_044_add_summary_covered_message(conn)?;
+        set_version(conn, 42)?;
+    }
+
     // Always run migration_013 to ensure table exists
     migration_013_add_coder_ide_conversations(conn).ok();
 
@@ -149,6 +254,586 @@ fn migration_023_add_runs_error_message(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn migration_024_add_brainstorm_pipeline_stages(conn: &Connection) -> Result<()> {
+    // Multi-stage brainstorm: each run_result belongs to a stage (generate, critique, refine)
+    // and may point at the result it critiques/refines.
+    conn.execute("ALTER TABLE run_results ADD COLUMN stage TEXT NOT NULL DEFAULT 'generate'", []).ok();
+    conn.execute("ALTER TABLE run_results ADD COLUMN parent_result_id TEXT", []).ok();
+    // Syntheses record which run_results they were built from
+    conn.execute("ALTER TABLE syntheses ADD COLUMN source_result_ids_json TEXT", []).ok();
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_run_results_run_stage ON run_results(run_id, stage)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_syntheses_run ON syntheses(run_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_025_add_evaluation_ranking(conn: &Connection) -> Result<()> {
+    // Tournament rankings: one evaluations row per ranked run_result
+    conn.execute("ALTER TABLE evaluations ADD COLUMN run_result_id TEXT REFERENCES run_results(id) ON DELETE CASCADE", []).ok();
+    conn.execute("ALTER TABLE evaluations ADD COLUMN profile_id TEXT", []).ok();
+    conn.execute("ALTER TABLE evaluations ADD COLUMN method TEXT", []).ok();
+    conn.execute("ALTER TABLE evaluations ADD COLUMN rank INTEGER", []).ok();
+    conn.execute("ALTER TABLE evaluations ADD COLUMN details_json TEXT", []).ok();
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_evaluations_run ON evaluations(run_id, rank)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_026_add_run_state_machine(conn: &Connection) -> Result<()> {
+    // Persistent run state: heartbeat for crash detection, resume counter, transition log
+    conn.execute("ALTER TABLE runs ADD COLUMN heartbeat_at TEXT", []).ok();
+    conn.execute("ALTER TABLE runs ADD COLUMN resume_count INTEGER NOT NULL DEFAULT 0", []).ok();
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS run_state_events (
+            id TEXT PRIMARY KEY,
+            run_id TEXT NOT NULL,
+            from_status TEXT,
+            to_status TEXT NOT NULL,
+            reason TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_run_state_events_run ON run_state_events(run_id, created_at)",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_runs_status ON runs(status)", [])?;
+    Ok(())
+}
+
+fn migration_027_add_debate_moderator(conn: &Connection) -> Result<()> {
+    // Moderator-led debate mode: profile that picks speakers and poses follow-ups
+    conn.execute("ALTER TABLE debate_configs ADD COLUMN moderator_profile_id TEXT", []).ok();
+    Ok(())
+}
+
+fn migration_028_add_conversation_summaries(conn: &Connection) -> Result<()> {
+    // Rolling summary memory: one stored summary per debate run or chat conversation
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS conversation_summaries (
+            id TEXT PRIMARY KEY,
+            scope_type TEXT NOT NULL,
+            scope_id TEXT NOT NULL,
+            summary_text TEXT NOT NULL DEFAULT '',
+            covered_until TEXT,
+            covered_count INTEGER NOT NULL DEFAULT 0,
+            summarizer_profile_id TEXT,
+            edited_by_user INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            UNIQUE(scope_type, scope_id)
+        )",
+        [],
+    )?;
+    conn.execute("ALTER TABLE debate_configs ADD COLUMN summarizer_profile_id TEXT", []).ok();
+    Ok(())
+}
+
+fn migration_029_add_debate_consensus(conn: &Connection) -> Result<()> {
+    // Per-round agreement scores for early stopping; the verdict itself lives in syntheses
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS debate_consensus_checks (
+            id TEXT PRIMARY KEY,
+            run_id TEXT NOT NULL,
+            round_index INTEGER NOT NULL,
+            agreement_score REAL NOT NULL,
+            lexical_similarity REAL NOT NULL,
+            judge_agreement REAL,
+            stances_json TEXT NOT NULL,
+            decision TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_debate_consensus_checks_run ON debate_consensus_checks(run_id)",
+        [],
+    )?;
+    conn.execute("ALTER TABLE debate_configs ADD COLUMN consensus_json TEXT", []).ok();
+    Ok(())
+}
+
+fn migration_030_add_argument_graph(conn: &Connection) -> Result<()> {
+    // Claims extracted from debate messages and the supports/attacks relations between them
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS argument_nodes (
+            id TEXT PRIMARY KEY,
+            run_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            claim TEXT NOT NULL,
+            message_id TEXT NOT NULL,
+            profile_id TEXT,
+            speaker TEXT NOT NULL,
+            round_index INTEGER,
+            extractor_profile_id TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS argument_edges (
+            id TEXT PRIMARY KEY,
+            run_id TEXT NOT NULL,
+            source_node_id TEXT NOT NULL,
+            target_node_id TEXT NOT NULL,
+            relation TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE,
+            FOREIGN KEY (source_node_id) REFERENCES argument_nodes(id) ON DELETE CASCADE,
+            FOREIGN KEY (target_node_id) REFERENCES argument_nodes(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_argument_nodes_run ON argument_nodes(run_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_argument_edges_run ON argument_edges(run_id)", [])?;
+    Ok(())
+}
+
+fn migration_031_add_human_seats(conn: &Connection) -> Result<()> {
+    // Human participants in debates: named roles in speaking_order and their pending turns
+    conn.execute("ALTER TABLE messages ADD COLUMN author_label TEXT", []).ok();
+    conn.execute("ALTER TABLE debate_configs ADD COLUMN human_seats_json TEXT", []).ok();
+    conn.execute("ALTER TABLE debate_configs ADD COLUMN human_turn_timeout_secs INTEGER", []).ok();
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS debate_human_turns (
+            id TEXT PRIMARY KEY,
+            run_id TEXT NOT NULL,
+            seat_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            round_index INTEGER NOT NULL,
+            turn_index INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            message_id TEXT,
+            requested_at TEXT NOT NULL,
+            expires_at TEXT,
+            resolved_at TEXT,
+            FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_debate_human_turns_run ON debate_human_turns(run_id, status)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_032_add_sop_versions(conn: &Connection) -> Result<()> {
+    // Templates are versioned by name; each save adds a row
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_sops_name_version ON sops(name, version)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_033_add_session_origin(conn: &Connection) -> Result<()> {
+    // Imported sessions keep the id of the session they were exported from
+    conn.execute("ALTER TABLE sessions ADD COLUMN origin_session_id TEXT", []).ok();
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_sessions_origin ON sessions(origin_session_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_034_add_search_index(conn: &Connection) -> Result<()> {
+    // Unified full-text index over chats, debates, brainstorm results and coder conversations.
+    // Only `text` is searchable; the other columns locate the hit (see search.rs).
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+            text,
+            source UNINDEXED,
+            item_id UNINDEXED,
+            parent_id UNINDEXED,
+            session_id UNINDEXED,
+            profile_id UNINDEXED,
+            project_id UNINDEXED,
+            created_at UNINDEXED,
+            tokenize = 'unicode61 remove_diacritics 2'
+        )",
+        [],
+    )?;
+
+    // Triggers keep the index in sync. Insert triggers clear stale rows first because
+    // INSERT OR REPLACE (used for coder chats) does not fire delete triggers.
+    let run_session = "(SELECT session_id FROM runs WHERE id = NEW.run_id)";
+    let run_project = "(SELECT s.project_id FROM runs r JOIN sessions s ON s.id = r.session_id WHERE r.id = NEW.run_id)";
+    let index_chat = "DELETE FROM search_index WHERE source = 'chat' AND item_id = NEW.id;
+        INSERT INTO search_index (text, source, item_id, parent_id, profile_id, created_at)
+        VALUES (NEW.content, 'chat', NEW.id, NEW.conversation_id, NEW.profile_id, NEW.created_at);".to_string();
+    let index_debate = format!(
+        "DELETE FROM search_index WHERE source = 'debate' AND item_id = NEW.id;
+        INSERT INTO search_index (text, source, item_id, parent_id, session_id, profile_id, project_id, created_at)
+        VALUES (NEW.text, 'debate', NEW.id, NEW.run_id, {}, NEW.profile_id, {}, NEW.created_at);",
+        run_session, run_project
+    );
+    let index_result = format!(
+        "DELETE FROM search_index WHERE source = 'run_result' AND item_id = NEW.id;
+        INSERT INTO search_index (text, source, item_id, parent_id, session_id, profile_id, project_id, created_at)
+        SELECT NEW.raw_output_text, 'run_result', NEW.id, NEW.run_id, {}, NEW.profile_id, {}, NEW.started_at
+        WHERE NEW.raw_output_text IS NOT NULL AND NEW.raw_output_text != '';",
+        run_session, run_project
+    );
+    let index_coder = |source: &str| {
+        format!(
+            "DELETE FROM search_index WHERE source = '{source}' AND parent_id = NEW.id;
+            INSERT INTO search_index (text, source, item_id, parent_id, created_at)
+            SELECT json_extract(m.value, '$.content'), '{source}', COALESCE(json_extract(m.value, '$.id'), CAST(m.key AS TEXT)),
+                NEW.id, COALESCE(json_extract(m.value, '$.timestamp'), NEW.updated_at)
+            FROM json_each(CASE WHEN json_valid(NEW.messages_json) THEN NEW.messages_json ELSE '[]' END) m
+            WHERE json_extract(m.value, '$.content') IS NOT NULL AND json_extract(m.value, '$.content') != '';",
+            source = source
+        )
+    };
+
+    let triggers = [
+        ("search_chat_ai", "AFTER INSERT ON chat_messages", index_chat.clone()),
+        ("search_chat_au", "AFTER UPDATE OF content ON chat_messages", index_chat),
+        ("search_chat_ad", "AFTER DELETE ON chat_messages", "DELETE FROM search_index WHERE source = 'chat' AND item_id = OLD.id;".to_string()),
+        ("search_debate_ai", "AFTER INSERT ON messages", index_debate.clone()),
+        ("search_debate_au", "AFTER UPDATE OF text ON messages", index_debate),
+        ("search_debate_ad", "AFTER DELETE ON messages", "DELETE FROM search_index WHERE source = 'debate' AND item_id = OLD.id;".to_string()),
+        ("search_result_ai", "AFTER INSERT ON run_results", index_result.clone()),
+        ("search_result_au", "AFTER UPDATE OF raw_output_text ON run_results", index_result),
+        ("search_result_ad", "AFTER DELETE ON run_results", "DELETE FROM search_index WHERE source = 'run_result' AND item_id = OLD.id;".to_string()),
+        ("search_coder_ai", "AFTER INSERT ON coder_chats", index_coder("coder_chat")),
+        ("search_coder_au", "AFTER UPDATE OF messages_json ON coder_chats", index_coder("coder_chat")),
+        ("search_coder_ad", "AFTER DELETE ON coder_chats", "DELETE FROM search_index WHERE source = 'coder_chat' AND parent_id = OLD.id;".to_string()),
+        ("search_ide_ai", "AFTER INSERT ON coder_ide_conversations", index_coder("coder_ide")),
+        ("search_ide_au", "AFTER UPDATE OF messages_json ON coder_ide_conversations", index_coder("coder_ide")),
+        ("search_ide_ad", "AFTER DELETE ON coder_ide_conversations", "DELETE FROM search_index WHERE source = 'coder_ide' AND parent_id = OLD.id;".to_string()),
+    ];
+    for (name, event, body) in triggers {
+        conn.execute(&format!("CREATE TRIGGER IF NOT EXISTS {} {} BEGIN {} END", name, event, body), [])?;
+    }
+
+    // Backfill existing history
+    conn.execute("DELETE FROM search_index", [])?;
+    conn.execute(
+        "INSERT INTO search_index (text, source, item_id, parent_id, profile_id, created_at)
+         SELECT content, 'chat', id, conversation_id, profile_id, created_at FROM chat_messages",
+        [],
+    )?;
+    conn.execute(
+        "INSERT INTO search_index (text, source, item_id, parent_id, session_id, profile_id, project_id, created_at)
+         SELECT m.text, 'debate', m.id, m.run_id, r.session_id, m.profile_id, s.project_id, m.created_at
+         FROM messages m LEFT JOIN runs r ON r.id = m.run_id LEFT JOIN sessions s ON s.id = r.session_id",
+        [],
+    )?;
+    conn.execute(
+        "INSERT INTO search_index (text, source, item_id, parent_id, session_id, profile_id, project_id, created_at)
+         SELECT rr.raw_output_text, 'run_result', rr.id, rr.run_id, r.session_id, rr.profile_id, s.project_id, rr.started_at
+         FROM run_results rr LEFT JOIN runs r ON r.id = rr.run_id LEFT JOIN sessions s ON s.id = r.session_id
+         WHERE rr.raw_output_text IS NOT NULL AND rr.raw_output_text != ''",
+        [],
+    )?;
+    for (table, source) in [("coder_chats", "coder_chat"), ("coder_ide_conversations", "coder_ide")] {
+        conn.execute(
+            &format!(
+                "INSERT INTO search_index (text, source, item_id, parent_id, created_at)
+                 SELECT json_extract(m.value, '$.content'), '{}', COALESCE(json_extract(m.value, '$.id'), CAST(m.key AS TEXT)),
+                     c.id, COALESCE(json_extract(m.value, '$.timestamp'), c.updated_at)
+                 FROM {} c, json_each(CASE WHEN json_valid(c.messages_json) THEN c.messages_json ELSE '[]' END) m
+                 WHERE json_extract(m.value, '$.content') IS NOT NULL AND json_extract(m.value, '$.content') != ''",
+                source, table
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migration_035_add_chat_branches(conn: &Connection) -> Result<()> {
+    // Chat messages form a tree: edits and regenerations add siblings under the same parent.
+    // The conversation remembers which message is selected; its path from the root is the active branch.
+    conn.execute("ALTER TABLE chat_messages ADD COLUMN parent_message_id TEXT", []).ok();
+    conn.execute("ALTER TABLE profile_conversations ADD COLUMN active_message_id TEXT", []).ok();
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chat_messages_parent ON chat_messages(parent_message_id)",
+        [],
+    )?;
+
+    // Existing conversations become a single chain in creation order
+    conn.execute(
+        "UPDATE chat_messages SET parent_message_id = chain.prev_id
+         FROM (
+             SELECT id, LAG(id) OVER (
+                 PARTITION BY profile_id, COALESCE(conversation_id, '') ORDER BY created_at, rowid
+             ) AS prev_id
+             FROM chat_messages
+         ) AS chain
+         WHERE chat_messages.id = chain.id AND chat_messages.parent_message_id IS NULL",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE profile_conversations SET active_message_id = (
+             SELECT id FROM chat_messages WHERE conversation_id = profile_conversations.id
+             ORDER BY created_at DESC, rowid DESC LIMIT 1
+         )
+         WHERE active_message_id IS NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_036_add_group_chats(conn: &Connection) -> Result<()> {
+    // Free-form chats between the user and several profiles
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS group_conversations (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS group_conversation_members (
+            conversation_id TEXT NOT NULL,
+            profile_id TEXT NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0,
+            auto_respond INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (conversation_id, profile_id),
+            FOREIGN KEY (conversation_id) REFERENCES group_conversations(id) ON DELETE CASCADE,
+            FOREIGN KEY (profile_id) REFERENCES prompt_profiles(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    // author_name keeps replies readable after a profile is deleted
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS group_messages (
+            id TEXT PRIMARY KEY,
+            conversation_id TEXT NOT NULL,
+            profile_id TEXT,
+            author_name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            mentions_json TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (conversation_id) REFERENCES group_conversations(id) ON DELETE CASCADE,
+            FOREIGN KEY (profile_id) REFERENCES prompt_profiles(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_group_messages_conversation ON group_messages(conversation_id, created_at)",
+        [],
+    )?;
+
+    // History search covers group chats too
+    let triggers = [
+        (
+            "search_group_ai",
+            "AFTER INSERT ON group_messages",
+            "INSERT INTO search_index (text, source, item_id, parent_id, profile_id, created_at)
+             VALUES (NEW.content, 'group_chat', NEW.id, NEW.conversation_id, NEW.profile_id, NEW.created_at);",
+        ),
+        (
+            "search_group_ad",
+            "AFTER DELETE ON group_messages",
+            "DELETE FROM search_index WHERE source = 'group_chat' AND item_id = OLD.id;",
+        ),
+    ];
+    for (name, event, body) in triggers {
+        conn.execute(&format!("CREATE TRIGGER IF NOT EXISTS {} {} BEGIN {} END", name, event, body), [])?;
+    }
+    Ok(())
+}
+
+fn migration_037_add_profile_versions(conn: &Connection) -> Result<()> {
+    // Every change to what shapes a profile's answers is kept as a numbered version
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS profile_versions (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            provider_account_id TEXT NOT NULL,
+            model_name TEXT NOT NULL,
+            persona_prompt TEXT NOT NULL,
+            params_json TEXT NOT NULL,
+            character_definition_json TEXT,
+            model_features_json TEXT,
+            note TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE (profile_id, version),
+            FOREIGN KEY (profile_id) REFERENCES prompt_profiles(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS profile_ab_tests (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL,
+            version_a_id TEXT NOT NULL,
+            version_b_id TEXT NOT NULL,
+            report_json TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (profile_id) REFERENCES prompt_profiles(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    // Which version produced a result or reply
+    conn.execute("ALTER TABLE run_results ADD COLUMN profile_version_id TEXT", []).ok();
+    conn.execute("ALTER TABLE chat_messages ADD COLUMN profile_version_id TEXT", []).ok();
+
+    // Triggers keep versions complete whichever code path writes the profile (editor, import, rollback)
+    let snapshot = "INSERT INTO profile_versions (id, profile_id, version, name, provider_account_id, model_name,
+                         persona_prompt, params_json, character_definition_json, model_features_json)
+                     SELECT lower(hex(randomblob(16))), p.id,
+                         COALESCE((SELECT MAX(version) FROM profile_versions WHERE profile_id = p.id), 0) + 1,
+                         p.name, p.provider_account_id, p.model_name, p.persona_prompt, p.params_json,
+                         p.character_definition_json, p.model_features_json
+                     FROM prompt_profiles p WHERE p.id = NEW.id;";
+    let latest = |profile: &str| {
+        format!("(SELECT id FROM profile_versions WHERE profile_id = {} ORDER BY version DESC LIMIT 1)", profile)
+    };
+    let triggers = [
+        ("profile_version_ai", "AFTER INSERT ON prompt_profiles".to_string(), snapshot.to_string()),
+        (
+            "profile_version_au",
+            "AFTER UPDATE ON prompt_profiles
+             WHEN OLD.persona_prompt IS NOT NEW.persona_prompt OR OLD.params_json IS NOT NEW.params_json
+                 OR OLD.character_definition_json IS NOT NEW.character_definition_json
+                 OR OLD.model_features_json IS NOT NEW.model_features_json
+                 OR OLD.model_name IS NOT NEW.model_name OR OLD.provider_account_id IS NOT NEW.provider_account_id"
+                .to_string(),
+            snapshot.to_string(),
+        ),
+        (
+            "profile_version_result_ai",
+            "AFTER INSERT ON run_results WHEN NEW.profile_version_id IS NULL".to_string(),
+            format!("UPDATE run_results SET profile_version_id = {} WHERE id = NEW.id;", latest("NEW.profile_id")),
+        ),
+        (
+            "profile_version_chat_ai",
+            "AFTER INSERT ON chat_messages WHEN NEW.profile_version_id IS NULL AND NEW.role = 'assistant'".to_string(),
+            format!("UPDATE chat_messages SET profile_version_id = {} WHERE id = NEW.id;", latest("NEW.profile_id")),
+        ),
+    ];
+    for (name, event, body) in triggers {
+        conn.execute(&format!("CREATE TRIGGER IF NOT EXISTS {} {} BEGIN {} END", name, event, body), [])?;
+    }
+
+    // Existing profiles start at version 1; earlier results keep an unknown version
+    conn.execute(
+        "INSERT INTO profile_versions (id, profile_id, version, name, provider_account_id, model_name,
+             persona_prompt, params_json, character_definition_json, model_features_json, created_at)
+         SELECT lower(hex(randomblob(16))), p.id, 1, p.name, p.provider_account_id, p.model_name, p.persona_prompt,
+             p.params_json, p.character_definition_json, p.model_features_json, COALESCE(p.updated_at, datetime('now'))
+         FROM prompt_profiles p WHERE NOT EXISTS (SELECT 1 FROM profile_versions v WHERE v.profile_id = p.id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_038_add_profile_memories(conn: &Connection) -> Result<()> {
+    // Long-term facts and preferences a profile learned about the user across conversations.
+    // Messages can be cleared without losing what was learned from them.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS profile_memories (
+            id TEXT PRIMARY KEY,
+            profile_id TEXT NOT NULL,
+            kind TEXT NOT NULL DEFAULT 'fact',
+            content TEXT NOT NULL,
+            source_message_id TEXT,
+            conversation_id TEXT,
+            edited_by_user INTEGER NOT NULL DEFAULT 0,
+            use_count INTEGER NOT NULL DEFAULT 0,
+            last_used_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (profile_id) REFERENCES prompt_profiles(id) ON DELETE CASCADE,
+            FOREIGN KEY (source_message_id) REFERENCES chat_messages(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_profile_memories_profile ON profile_memories(profile_id, created_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_039_add_cline_iterations(conn: &Connection) -> Result<()> {
+    // Cline runs loop over several model calls; tools are grouped by the iteration that requested them
+    conn.execute(
+        "ALTER TABLE cline_tool_executions ADD COLUMN iteration INTEGER NOT NULL DEFAULT 0",
+        [],
+    ).ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE cline_runs ADD COLUMN iterations INTEGER NOT NULL DEFAULT 0", []).ok();
+    conn.execute("ALTER TABLE cline_runs ADD COLUMN total_tokens INTEGER NOT NULL DEFAULT 0", []).ok();
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_cline_tool_executions_iteration ON cline_tool_executions(run_id, iteration, step_index)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_040_add_checkpoint_blobs(conn: &Connection) -> Result<()> {
+    // Checkpoints become manifests of content hashes; each distinct file content is stored once
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cline_checkpoint_blobs (
+            hash TEXT PRIMARY KEY,
+            size INTEGER NOT NULL,
+            content BLOB NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    conn.execute("ALTER TABLE cline_checkpoints ADD COLUMN tool_execution_id TEXT", []).ok(); // Ignore error if column already exists
+    Ok(())
+}
+
+fn migration_041_unique_debate_configs(conn: &Connection) -> Result<()> {
+    // Continuations used to add a second config row holding default settings; the first row has the
+    // settings the debate was started with. One row per run from now on.
+    conn.execute(
+        "DELETE FROM debate_configs WHERE rowid NOT IN (SELECT MIN(rowid) FROM debate_configs GROUP BY run_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_debate_configs_run ON debate_configs(run_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_042_add_debate_first_round(conn: &Connection) -> Result<()> {
+    // Continuations run the rounds after the last stored one instead of starting again at round 0
+    conn.execute("ALTER TABLE debate_configs ADD COLUMN first_round INTEGER NOT NULL DEFAULT 0", []).ok(); // Ignore error if column already exists
+    Ok(())
+}
+
+fn migration_043_add_profile_chat_state(conn: &Connection) -> Result<()> {
+    // Selected branch of a profile's default chat (messages without a conversation)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS profile_chat_state (
+            profile_id TEXT PRIMARY KEY,
+            active_message_id TEXT,
+            FOREIGN KEY (profile_id) REFERENCES prompt_profiles(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_044_add_summary_covered_message(conn: &Connection) -> Result<()> {
+    // Newest folded message, so a chat summary is only used on the branch it was written for
+    conn.execute("ALTER TABLE conversation_summaries ADD COLUMN covered_message_id TEXT", []).ok(); // Ignore error if column already exists
+    Ok(())
+}
+
 fn migration_002_add_character_features(conn: &Connection) -> Result<()> {
     // Add character_definition_json and model_features_json columns if they don't exist
     // This migration is for existing databases that were created before these columns were added
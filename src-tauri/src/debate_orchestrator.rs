@@ -1,15 +1,23 @@
 // Debate Room orchestrator with state machine
 
 use crate::db::Database;
+use crate::run_state::{self, RunSignal, RunStatus};
+use crate::summary_memory::{self, SummaryMemoryConfig, SummaryScope};
+use crate::consensus::{self, ConsensusConfig};
+use crate::human_turns::{self, HumanSeat, TurnStatus};
 use crate::provider_resolver::complete_resolving_hybrid;
 use crate::types::{PromptPacket, Message};
 use crate::token_usage::record_token_usage;
 use crate::training_ingest;
 use anyhow::Result;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use uuid::Uuid;
+use std::collections::HashMap;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
+use tokio::sync::Semaphore;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DebateState {
@@ -22,9 +30,218 @@ pub enum DebateState {
     Complete,
 }
 
+/// How speakers take turns within a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebateMode {
+    /// One speaker at a time, in a fresh random order each round.
+    #[default]
+    Sequential,
+    /// All participants answer the round concurrently, seeing only earlier rounds.
+    Parallel,
+    /// A moderator profile picks the next speaker and poses a follow-up question.
+    Moderator,
+    /// Participants question each other in pairs; the respondent must answer.
+    CrossExamination,
+}
+
+impl DebateMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DebateMode::Sequential => "sequential",
+            DebateMode::Parallel => "parallel",
+            DebateMode::Moderator => "moderator",
+            DebateMode::CrossExamination => "cross_examination",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sequential" => Some(DebateMode::Sequential),
+            "parallel" => Some(DebateMode::Parallel),
+            "moderator" => Some(DebateMode::Moderator),
+            "cross_examination" => Some(DebateMode::CrossExamination),
+            _ => None,
+        }
+    }
+}
+
+/// Which earlier messages each turn receives as `conversation_context`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContextPolicy {
+    #[default]
+    #[serde(rename = "full")]
+    Full,
+    #[serde(rename = "last_k_messages", alias = "last_k")]
+    LastK,
+    /// Summary of older messages plus the last k raw messages.
+    #[serde(rename = "rolling_summary")]
+    RollingSummary,
+}
+
+impl ContextPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContextPolicy::Full => "full",
+            ContextPolicy::LastK => "last_k_messages",
+            ContextPolicy::RollingSummary => "rolling_summary",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "last_k_messages" | "last_k" => ContextPolicy::LastK,
+            "rolling_summary" => ContextPolicy::RollingSummary,
+            _ => ContextPolicy::Full,
+        }
+    }
+}
+
+/// Per-debate settings, persisted in `debate_configs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebateSettings {
+    pub mode: DebateMode,
+    pub context_policy: ContextPolicy,
+    pub last_k: i32,
+    /// Required for `DebateMode::Moderator`.
+    pub moderator_profile_id: Option<String>,
+    /// Writes the rolling summary; defaults to the moderator, then the first speaker.
+    pub summarizer_profile_id: Option<String>,
+    /// Max concurrent turns in parallel rounds.
+    pub concurrency: i32,
+    /// Judge step after each round: stop early on consensus or stalled positions, then write a verdict.
+    pub consensus: ConsensusConfig,
+    /// Human participants; a seat takes turns when "human:<id>" is in speaking_order.
+    pub human_seats: Vec<HumanSeat>,
+    /// How long a human turn waits before it is skipped; 0 waits until answered or skipped.
+    pub human_turn_timeout_secs: u64,
+}
+
+impl Default for DebateSettings {
+    fn default() -> Self {
+        DebateSettings {
+            mode: DebateMode::Sequential,
+            context_policy: ContextPolicy::Full,
+            last_k: 6,
+            moderator_profile_id: None,
+            summarizer_profile_id: None,
+            concurrency: 3,
+            consensus: ConsensusConfig::default(),
+            human_seats: Vec::new(),
+            human_turn_timeout_secs: 300,
+        }
+    }
+}
+
+/// A debate's row in `debate_configs`.
+pub struct StoredDebateConfig {
+    /// Round index the debate stops before; a continuation extends it.
+    pub rounds: i32,
+    /// First round of the latest run segment: 0, or where the last continuation started.
+    pub first_round: i32,
+    pub speaking_order: Vec<String>,
+    pub max_words: Option<i32>,
+    pub language: Option<String>,
+    pub tone: Option<String>,
+    pub settings: DebateSettings,
+}
+
+/// Load the config a debate was started with; resumes and continuations run with the same settings.
+pub fn load_debate_config(db: &Database, run_id: &str) -> Result<StoredDebateConfig> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+    let (config, speaking_order_json, mode) = conn_guard
+        .query_row(
+            "SELECT rounds, speaking_order_json, max_words, language, tone, mode, context_policy, last_k, moderator_profile_id, concurrency, summarizer_profile_id, consensus_json, human_seats_json, human_turn_timeout_secs, first_round FROM debate_configs WHERE run_id = ?1",
+            [run_id],
+            |row| {
+                let settings = DebateSettings {
+                    mode: DebateMode::default(),
+                    context_policy: ContextPolicy::parse(&row.get::<_, String>(6)?),
+                    last_k: row.get::<_, Option<i32>>(7)?.unwrap_or(6),
+                    moderator_profile_id: row.get(8)?,
+                    summarizer_profile_id: row.get(10)?,
+                    concurrency: row.get(9)?,
+                    consensus: row
+                        .get::<_, Option<String>>(11)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                    human_seats: row
+                        .get::<_, Option<String>>(12)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                    human_turn_timeout_secs: row.get::<_, Option<i64>>(13)?.unwrap_or(300).max(0) as u64,
+                };
+                let config = StoredDebateConfig {
+                    rounds: row.get(0)?,
+                    first_round: row.get::<_, Option<i32>>(14)?.unwrap_or(0),
+                    speaking_order: Vec::new(),
+                    max_words: row.get(2)?,
+                    language: row.get(3)?,
+                    tone: row.get(4)?,
+                    settings,
+                };
+                Ok((config, row.get::<_, String>(1)?, row.get::<_, String>(5)?))
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to load debate config: {}", e))?;
+    let speaking_order = serde_json::from_str(&speaking_order_json)
+        .map_err(|e| anyhow::anyhow!("Failed to parse speaking order: {}", e))?;
+    let mode = DebateMode::parse(&mode).ok_or_else(|| anyhow::anyhow!("Unknown debate mode '{}'", mode))?;
+    Ok(StoredDebateConfig { speaking_order, settings: DebateSettings { mode, ..config.settings }, ..config })
+}
+
+/// Round a continuation starts at: one past the last round with a message or a human turn.
+/// Continuations never reuse round indexes, so spoken turns and human turns stay unambiguous.
+pub fn next_round(db: &Database, run_id: &str) -> Result<i32> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+    let last: Option<i32> = conn_guard
+        .query_row(
+            "SELECT MAX(round_index) FROM (
+                 SELECT round_index FROM messages WHERE run_id = ?1
+                 UNION ALL SELECT round_index FROM debate_human_turns WHERE run_id = ?1
+             )",
+            [run_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to load rounds: {}", e))?;
+    Ok(last.map(|r| r.max(-1) + 1).unwrap_or(0))
+}
+
+/// Run-level data shared by every turn of a debate.
+struct DebateRunContext {
+    run_id: String,
+    session_id: String,
+    project_id: String,
+    session_local_model_id: Option<String>,
+    user_question: String,
+    /// Participants in speaking order.
+    profiles: Vec<ProfileData>,
+    /// Human seats in speaking order.
+    humans: Vec<HumanSeat>,
+    moderator: Option<ProfileData>,
+    max_words: Option<i32>,
+    language: Option<String>,
+    tone: Option<String>,
+    web_search_results: Option<Vec<crate::web_search::NewsResult>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RoundOutcome {
+    Completed,
+    Cancelled,
+}
+
 pub struct DebateOrchestrator {
     db: Database,
     state: DebateState,
+    settings: DebateSettings,
+    /// Set by `resume_debate_run`: keep the existing config and messages, skip spoken turns.
+    resuming: bool,
+    /// Round the debate loop starts at; set by `continue_debate_run`.
+    first_round: i32,
 }
 
 impl DebateOrchestrator {
@@ -32,9 +249,37 @@ impl DebateOrchestrator {
         DebateOrchestrator {
             db,
             state: DebateState::Idle,
+            settings: DebateSettings::default(),
+            resuming: false,
+            first_round: 0,
         }
     }
 
+    pub fn with_settings(mut self, settings: DebateSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Resume an interrupted debate from its stored config, continuing after the last
+    /// completed turn of each round.
+    pub async fn resume_debate_run(&mut self, run_id: String) -> Result<()> {
+        let config = load_debate_config(&self.db, &run_id)?;
+        self.settings = config.settings;
+        self.first_round = config.first_round;
+        self.resuming = true;
+        self.run_debate(run_id, config.rounds, config.speaking_order, config.max_words, config.language, config.tone, None).await
+    }
+
+    /// Run `extra_rounds` more rounds of a finished debate with its stored settings. The new
+    /// rounds follow the last stored round; `rounds` in the config becomes the new end.
+    pub async fn continue_debate_run(&mut self, run_id: String, config: StoredDebateConfig, extra_rounds: i32) -> Result<()> {
+        self.first_round = next_round(&self.db, &run_id)?;
+        self.settings = config.settings;
+        let rounds = self.first_round + extra_rounds;
+        self.run_debate(run_id, rounds, config.speaking_order, config.max_words, config.language, config.tone, None).await
+    }
+
+    /// Run rounds `first_round..rounds` (0 unless continuing).
     pub async fn run_debate(
         &mut self,
         run_id: String,
@@ -78,29 +323,43 @@ impl DebateOrchestrator {
             (session_id, project_id, local_model_id, user_question)
         };
 
-        // Create debate config
+        // Create the debate config; a continuation updates the run's existing row
         let config_id = Uuid::new_v4().to_string();
-        {
+        if !self.resuming {
             let conn = self.db.get_connection();
             let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
             let speaking_order_json = serde_json::to_string(&speaking_order)
                 .map_err(|e| anyhow::anyhow!("Failed to serialize speaking order: {}", e))?;
             
             conn_guard.execute(
-                "INSERT INTO debate_configs (id, run_id, mode, rounds, speaking_order_json, context_policy, last_k, concurrency, max_words) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO debate_configs (id, run_id, mode, rounds, speaking_order_json, context_policy, last_k, concurrency, max_words, language, tone, moderator_profile_id, summarizer_profile_id, consensus_json, human_seats_json, human_turn_timeout_secs, first_round) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                 ON CONFLICT(run_id) DO UPDATE SET mode = excluded.mode, rounds = excluded.rounds, speaking_order_json = excluded.speaking_order_json,
+                     context_policy = excluded.context_policy, last_k = excluded.last_k, concurrency = excluded.concurrency, max_words = excluded.max_words,
+                     language = excluded.language, tone = excluded.tone, moderator_profile_id = excluded.moderator_profile_id,
+                     summarizer_profile_id = excluded.summarizer_profile_id, consensus_json = excluded.consensus_json,
+                     human_seats_json = excluded.human_seats_json, human_turn_timeout_secs = excluded.human_turn_timeout_secs,
+                     first_round = excluded.first_round",
                 rusqlite::params![
                     config_id,
                     run_id,
-                    "sequential",
+                    self.settings.mode.as_str(),
                     rounds,
                     speaking_order_json,
-                    "last_k_messages",
-                    6,
-                    1,
-                    max_words
+                    self.settings.context_policy.as_str(),
+                    self.settings.last_k,
+                    self.settings.concurrency,
+                    max_words,
+                    language,
+                    tone,
+                    self.settings.moderator_profile_id,
+                    self.settings.summarizer_profile_id,
+                    serde_json::to_string(&self.settings.consensus).ok(),
+                    serde_json::to_string(&self.settings.human_seats).ok(),
+                    self.settings.human_turn_timeout_secs as i64,
+                    self.first_round
                 ],
             )
-            .map_err(|e| anyhow::anyhow!("Failed to create debate config: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to save debate config: {}", e))?;
         }
 
         // Status is already set to running by start_debate command, so we don't need to update it here
@@ -121,7 +380,7 @@ impl DebateOrchestrator {
         }
 
         // Insert user question as first message so UI shows something immediately
-        {
+        if !self.resuming && self.first_round == 0 {
             let conn = self.db.get_connection();
             let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
             let msg_id = Uuid::new_v4().to_string();
@@ -144,297 +403,697 @@ impl DebateOrchestrator {
             eprintln!("[Debate] Inserted user question for run_id={}", run_id);
         }
 
+        // Human seats are "human:<seat id>" entries; everything else is a profile id
+        let profile_ids: Vec<String> = speaking_order
+            .iter()
+            .filter(|entry| human_turns::seat_id(entry).is_none())
+            .cloned()
+            .collect();
+        let humans: Vec<HumanSeat> = speaking_order
+            .iter()
+            .filter_map(|entry| human_turns::seat_id(entry))
+            .map(|id| {
+                self.settings
+                    .human_seats
+                    .iter()
+                    .find(|seat| seat.id == id)
+                    .cloned()
+                    .unwrap_or_else(|| HumanSeat { id: id.to_string(), role: "Participant".to_string() })
+            })
+            .collect();
+        if !humans.is_empty() && !matches!(self.settings.mode, DebateMode::Sequential | DebateMode::Parallel) {
+            anyhow::bail!("Human seats are supported in sequential and parallel debates only");
+        }
+
         // Load profiles
-        let profiles = self.load_profiles(&speaking_order)?;
+        let profiles = self.load_profiles(&profile_ids)?;
         eprintln!("[Debate] Loaded {} profiles for run_id={}", profiles.len(), run_id);
 
         if profiles.is_empty() {
-            anyhow::bail!("No profiles found for IDs: {:?}. Check that selected profiles exist in prompt_profiles.", speaking_order);
+            anyhow::bail!("No profiles found for IDs: {:?}. Check that selected profiles exist in prompt_profiles.", profile_ids);
+        }
+
+        // Participants in speaking order, plus the moderator for moderator-led debates
+        let mut profiles = profiles;
+        profiles.sort_by_key(|p| speaking_order.iter().position(|id| *id == p.id).unwrap_or(usize::MAX));
+        let moderator = match (&self.settings.mode, &self.settings.moderator_profile_id) {
+            (DebateMode::Moderator, Some(id)) => Some(
+                self.load_profiles(std::slice::from_ref(id))?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Moderator profile not found: {}", id))?,
+            ),
+            (DebateMode::Moderator, None) => anyhow::bail!("Moderator mode requires moderator_profile_id"),
+            _ => None,
+        };
+        if self.settings.mode == DebateMode::CrossExamination && profiles.len() < 2 {
+            anyhow::bail!("Cross-examination needs at least two profiles");
         }
 
+        let ctx = DebateRunContext {
+            run_id: run_id.clone(),
+            session_id,
+            project_id,
+            session_local_model_id,
+            user_question,
+            profiles,
+            humans,
+            moderator,
+            max_words,
+            language,
+            tone,
+            web_search_results,
+        };
+
+        // Turns already taken before an interruption: (round_index, profile id or human seat key)
+        let spoken: Vec<(i32, String)> = if self.resuming {
+            let mut spoken = self.load_spoken_turns(&run_id)?;
+            spoken.extend(human_turns::resolved_seats(&self.db, &run_id).map_err(|e| anyhow::anyhow!(e))?);
+            spoken
+        } else {
+            Vec::new()
+        };
+
+        // Rounds already judged before an interruption are not judged again
+        let judged_rounds: Vec<i32> = if self.resuming && self.settings.consensus.enabled {
+            let conn = self.db.get_connection();
+            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+            consensus::load_checks(&conn_guard, &run_id)
+                .map_err(|e| anyhow::anyhow!(e))?
+                .into_iter()
+                .map(|c| c.round_index)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        // Why the debate ended, for the verdict
+        let mut outcome_reason = "rounds_exhausted";
+
         // Execute debate rounds (0 = opening, 1.. = rebuttals; rounds=2 means 2 rounds total)
         self.state = DebateState::RoundActive;
         
-        for round_index in 0..rounds {
-            // Check if cancelled or paused
-            {
-                let status: String = {
-                    let conn = self.db.get_connection();
-                    let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-                    let status_result: Result<String, _> = conn_guard.query_row(
-                        "SELECT status FROM runs WHERE id = ?1",
-                        [&run_id],
-                        |row| row.get(0),
-                    );
-                    drop(conn_guard); // Drop before await
-                    status_result.map_err(|e| anyhow::anyhow!("Failed to get status: {}", e))?
-                };
-                
-                if status == "cancelled" {
-                    self.state = DebateState::Cancelled;
-                    break;
-                } else if status == "paused" {
-                    self.state = DebateState::Paused;
-                    // Wait for resume
-                    while self.state == DebateState::Paused {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        let status_check: String = {
-                            let conn = self.db.get_connection();
-                            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-                            let status_result: Result<String, _> = conn_guard.query_row(
-                                "SELECT status FROM runs WHERE id = ?1",
-                                [&run_id],
-                                |row| row.get(0),
-                            );
-                            drop(conn_guard); // Drop before await
-                            status_result.map_err(|e| anyhow::anyhow!("Failed to get status: {}", e))?
-                        };
-                        if status_check == "running" {
-                            self.state = DebateState::RoundActive;
-                            break;
-                        } else if status_check == "cancelled" {
-                            self.state = DebateState::Cancelled;
-                            break;
-                        }
-                    }
-                    if self.state == DebateState::Cancelled {
-                        break;
-                    }
-                }
+        for round_index in self.first_round..rounds {
+            // Check if cancelled or paused (persisted in runs.status; waits while paused)
+            if run_state::checkpoint(&self.db, &run_id).await == RunSignal::Cancel {
+                self.state = DebateState::Cancelled;
+                break;
             }
-            
-            if self.state == DebateState::Cancelled {
+
+            let done_in_round: Vec<&String> = spoken
+                .iter()
+                .filter(|(r, _)| *r == round_index)
+                .map(|(_, profile_id)| profile_id)
+                .collect();
+
+            self.state = DebateState::TurnActive;
+            let outcome = match self.settings.mode {
+                DebateMode::Sequential => self.run_sequential_round(&ctx, round_index, &done_in_round).await?,
+                DebateMode::Parallel => self.run_parallel_round(&ctx, round_index, &done_in_round).await?,
+                DebateMode::Moderator => self.run_moderated_round(&ctx, round_index).await?,
+                DebateMode::CrossExamination => self.run_cross_examination_round(&ctx, round_index).await?,
+            };
+
+            if outcome == RoundOutcome::Cancelled {
+                self.state = DebateState::Cancelled;
+                eprintln!("[Debate] Debate was cancelled, exiting round loop");
                 break;
             }
+            self.state = DebateState::RoundActive;
 
-            let mut messages: Vec<Message> = Vec::new();
-            
-            // Generate random order for this round (before any async operations)
-            // Each round gets a fresh random shuffle of all agents
-            let mut round_order: Vec<String> = speaking_order.clone();
-            {
-                let mut rng = thread_rng();
-                round_order.shuffle(&mut rng);
+            if self.settings.context_policy == ContextPolicy::RollingSummary {
+                self.refresh_rolling_summary(&ctx, false).await;
             }
             
-            // Round 0: Opening statements
-            // Round 1+: Rebuttals
-            // Use the randomly shuffled order for this round
-            for (turn_index, profile_id) in round_order.iter().enumerate() {
-                // Check DB status at start of each turn (user may have clicked Pause/Stop)
-                {
-                    let status: String = {
-                        let conn = self.db.get_connection();
-                        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-                        let status_result: Result<String, _> = conn_guard.query_row(
-                            "SELECT status FROM runs WHERE id = ?1",
-                            [&run_id],
-                            |row| row.get(0),
-                        );
-                        drop(conn_guard);
-                        status_result.map_err(|e| anyhow::anyhow!("Failed to get status: {}", e))?
-                    };
-                    if status == "cancelled" {
-                        self.state = DebateState::Cancelled;
+            eprintln!("[Debate] Round {} complete", round_index);
+
+            if self.settings.consensus.enabled && !judged_rounds.contains(&round_index) {
+                if let Some(decision) = self.check_consensus(&ctx, round_index).await {
+                    if decision != "continue" {
+                        eprintln!("[Debate] Stopping early after round {}: {}", round_index, decision);
+                        outcome_reason = if decision == "consensus" { "consensus" } else { "stalled" };
                         break;
                     }
-                    if status == "paused" {
-                        self.state = DebateState::Paused;
-                    }
                 }
+            }
+        }
 
-                if self.state == DebateState::Cancelled || self.state == DebateState::Paused {
-                    // Wait for resume or break
-                    while self.state == DebateState::Paused {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        let status_check: String = {
-                            let conn = self.db.get_connection();
-                            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-                            let status_result: Result<String, _> = conn_guard.query_row(
-                                "SELECT status FROM runs WHERE id = ?1",
-                                [&run_id],
-                                |row| row.get(0),
-                            );
-                            drop(conn_guard);
-                            status_result.map_err(|e| anyhow::anyhow!("Failed to get status: {}", e))?
-                        };
-                        if status_check == "running" {
-                            self.state = DebateState::RoundActive;
-                            break;
-                        } else if status_check == "cancelled" {
-                            self.state = DebateState::Cancelled;
-                            break;
-                        }
-                    }
-                    if self.state == DebateState::Cancelled {
-                        break;
-                    }
+        if self.settings.consensus.enabled && self.state != DebateState::Cancelled {
+            if let Some(judge_id) = self.judge_profile_id(&ctx) {
+                if let Err(e) = consensus::generate_verdict(&self.db, &run_id, &judge_id, outcome_reason).await {
+                    eprintln!("[Debate] Verdict failed: {}", e);
                 }
+            }
+        }
+        
+        eprintln!("[Debate] All rounds complete, about to update run status");
 
-                self.state = DebateState::TurnActive;
-
-                let profile = profiles.iter()
-                    .find(|p| p.id == *profile_id)
-                    .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", profile_id))?;
-
-                // Build context: load ALL previous messages from database
-                let context_messages: Vec<Message> = {
-                    let conn = self.db.get_connection();
-                    let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-                    
-                    let mut stmt = conn_guard
-                        .prepare("SELECT id, author_type, profile_id, round_index, turn_index, text, created_at FROM messages WHERE run_id = ?1 AND (round_index < ?2 OR (round_index = ?2 AND turn_index < ?3)) ORDER BY round_index, turn_index, created_at")
-                        .map_err(|e| anyhow::anyhow!("Failed to prepare query: {}", e))?;
-                    
-                    let rows = stmt
-                        .query_map(rusqlite::params![run_id, round_index, turn_index], |row| {
-                            Ok(Message {
-                                id: row.get(0)?,
-                                run_id: run_id.clone(),
-                                author_type: row.get(1)?,
-                                profile_id: row.get(2)?,
-                                round_index: row.get(3)?,
-                                turn_index: row.get(4)?,
-                                text: row.get(5)?,
-                                created_at: row.get(6)?,
-                                provider_metadata_json: None,
-                            })
-                        })
-                        .map_err(|e| anyhow::anyhow!("Failed to query messages: {}", e))?;
-                    
-                    let mut context = Vec::new();
-                    for row in rows {
-                        context.push(row.map_err(|e| anyhow::anyhow!("Row error: {}", e))?);
-                    }
-                    context
-                };
+        // Update run status (a cancelled debate keeps its cancelled status)
+        if self.state != DebateState::Cancelled {
+            self.state = DebateState::Complete;
+        }
+        run_state::finish(&self.db, &run_id, RunStatus::Complete).map_err(|e| anyhow::anyhow!(e))?;
 
-                // Execute turn
-                eprintln!("[Debate] Executing turn round={} turn={} profile={} run_id={}", round_index, turn_index, profile_id, run_id);
-                let turn_result = self.execute_turn(
-                    &run_id,
-                    round_index,
-                    turn_index as i32,
-                    profile,
-                    &user_question,
-                    &context_messages,
-                    max_words,
-                    language.clone(),
-                    tone.clone(),
-                    if round_index == 0 { web_search_results.clone() } else { None },
-                ).await;
-
-                match turn_result {
-                    Ok((response_text, usage_json)) => {
-                        eprintln!("[Debate] Turn completed SUCCESS: run_id={} round={} turn={} profile={} text_len={}", run_id, round_index, turn_index, profile.id, response_text.len());
-                        // Create message
-                        let message_id = Uuid::new_v4().to_string();
-                        let created_at = chrono::Utc::now().to_rfc3339();
-                        
-                        // Save message with usage data
-                        {
-                            let conn = self.db.get_connection();
-                            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-                            
-                            let usage_json_str = usage_json.as_ref()
-                                .and_then(|u| serde_json::to_string(u).ok());
-                            
-                            match conn_guard.execute(
-                                "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text, created_at, provider_metadata_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-                                rusqlite::params![
-                                    message_id,
-                                    run_id,
-                                    "agent",
-                                    profile_id,
-                                    round_index,
-                                    turn_index,
-                                    response_text,
-                                    created_at,
-                                    usage_json_str
-                                ],
-                            ) {
-                                Ok(_) => eprintln!("[Debate] Message saved to DB: id={}", message_id),
-                                Err(e) => eprintln!("[Debate] FAILED to save message: {}", e),
-                            }
-                        }
+        Ok(())
+    }
 
-                        // Record token usage for this debate turn (if usage info is available)
-                        if let Some(usage) = &usage_json {
-                            eprintln!("[Debate] Recording token usage...");
-                            // We don't currently track provider_id/model_name per turn here,
-                            // so we record without provider_id and with a generic model name.
-                            let _ = record_token_usage(
-                                &self.db,
-                                None,
-                                "debate_model",
-                                &Some(usage.clone()),
-                                "debate",
-                                None,
-                                None,
-                            );
-                            eprintln!("[Debate] Token usage recorded");
-                        }
+    /// Sequential: every participant speaks once, in a fresh random order each round.
+    async fn run_sequential_round(&self, ctx: &DebateRunContext, round_index: i32, done: &[&String]) -> Result<RoundOutcome> {
+        // Generate random order for this round (before any async operations).
+        // When resuming, speakers who already have a message in this round are skipped.
+        let mut round_order: Vec<Seat> = ctx
+            .profiles
+            .iter()
+            .filter(|p| !done.contains(&&p.id))
+            .map(Seat::Agent)
+            .chain(
+                ctx.humans
+                    .iter()
+                    .filter(|h| !done.contains(&&h.speaking_order_key()))
+                    .map(Seat::Human),
+            )
+            .collect();
+        {
+            let mut rng = thread_rng();
+            round_order.shuffle(&mut rng);
+        }
 
-                        // Auto-training ingest: debate turn → training_data (best-effort)
-                        eprintln!("[Debate] Starting training ingest...");
-                        let _ = training_ingest::ingest_debate_turn(
-                            &self.db,
-                            &project_id,
-                            session_local_model_id.as_deref(),
-                            &user_question,
-                            &response_text,
-                            &session_id,
-                            &run_id,
-                        );
-                        eprintln!("[Debate] Training ingest complete");
-                        
-                        let message = Message {
-                            id: message_id.clone(),
-                            run_id: run_id.clone(),
-                            author_type: "agent".to_string(),
-                            profile_id: Some(profile_id.clone()),
-                            round_index: Some(round_index),
-                            turn_index: Some(turn_index as i32),
-                            text: response_text.clone(),
-                            created_at,
-                            provider_metadata_json: usage_json,
-                        };
-
-                        messages.push(message);
-                    }
-                    Err(e) => {
-                        eprintln!("[Debate] Turn execution error run_id={} round={} turn={} profile={}: {}", run_id, round_index, turn_index, profile.id, e);
-                        // Continue to next turn even on error
+        for (offset, seat) in round_order.into_iter().enumerate() {
+            // Check DB status at start of each turn (user may have clicked Pause/Stop)
+            if run_state::checkpoint(&self.db, &ctx.run_id).await == RunSignal::Cancel {
+                return Ok(RoundOutcome::Cancelled);
+            }
+            let turn_index = (done.len() + offset) as i32;
+            match seat {
+                Seat::Agent(profile) => {
+                    self.take_turn(ctx, round_index, turn_index, turn_index, profile, None).await?;
+                }
+                Seat::Human(human) => {
+                    if self.take_human_turn(ctx, round_index, turn_index, human).await? == RoundOutcome::Cancelled {
+                        return Ok(RoundOutcome::Cancelled);
                     }
                 }
-                
-                eprintln!("[Debate] Finished processing turn, messages.len()={}", messages.len());
             }
+        }
+        Ok(RoundOutcome::Completed)
+    }
 
-            if self.state == DebateState::Cancelled {
-                eprintln!("[Debate] Debate was cancelled, exiting round loop");
-                break;
+    /// Parallel: all participants answer the round concurrently; each sees only earlier rounds.
+    /// Human seats are asked at the same time as the AI participants.
+    async fn run_parallel_round(&self, ctx: &DebateRunContext, round_index: i32, done: &[&String]) -> Result<RoundOutcome> {
+        if run_state::checkpoint(&self.db, &ctx.run_id).await == RunSignal::Cancel {
+            return Ok(RoundOutcome::Cancelled);
+        }
+
+        let semaphore = Semaphore::new(self.settings.concurrency.max(1) as usize);
+        let pending_agents: Vec<&ProfileData> = ctx.profiles.iter().filter(|p| !done.contains(&&p.id)).collect();
+        let agent_count = pending_agents.len();
+        let turns = pending_agents.into_iter().enumerate().map(|(offset, profile)| {
+            let turn_index = (done.len() + offset) as i32;
+            let semaphore = &semaphore;
+            async move {
+                let _permit = semaphore.acquire().await.map_err(|e| anyhow::anyhow!("Failed to acquire semaphore: {}", e))?;
+                self.take_turn(ctx, round_index, turn_index, 0, profile, None).await
             }
-            
-            eprintln!("[Debate] Round {} complete, messages collected: {}", round_index, messages.len());
+        });
+        let human_turns = ctx
+            .humans
+            .iter()
+            .filter(|h| !done.contains(&&h.speaking_order_key()))
+            .enumerate()
+            .map(|(offset, human)| self.take_human_turn(ctx, round_index, (done.len() + agent_count + offset) as i32, human));
+        let (agent_results, human_results) = futures::join!(join_all(turns), join_all(human_turns));
+        for result in agent_results {
+            result?;
+        }
+        for result in human_results {
+            if result? == RoundOutcome::Cancelled {
+                return Ok(RoundOutcome::Cancelled);
+            }
+        }
+        Ok(RoundOutcome::Completed)
+    }
+
+    /// Open a turn for a human seat and wait until it is answered, skipped or timed out.
+    /// Pausing the run does not stop the clock.
+    async fn take_human_turn(&self, ctx: &DebateRunContext, round_index: i32, turn_index: i32, seat: &HumanSeat) -> Result<RoundOutcome> {
+        let turn = human_turns::request_turn(&self.db, &ctx.run_id, seat, round_index, turn_index, self.settings.human_turn_timeout_secs)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        eprintln!("[Debate] Waiting for human seat '{}' round={} turn={}", seat.role, round_index, turn_index);
+        loop {
+            let current = human_turns::get_turn(&self.db, &turn.id).map_err(|e| anyhow::anyhow!(e))?;
+            if TurnStatus::parse(&current.status) != TurnStatus::Waiting {
+                return Ok(RoundOutcome::Completed);
+            }
+            if human_turns::is_expired(&current) {
+                eprintln!("[Debate] Human turn for '{}' timed out", seat.role);
+                human_turns::close_turn(&self.db, &turn.id, TurnStatus::TimedOut).map_err(|e| anyhow::anyhow!(e))?;
+                return Ok(RoundOutcome::Completed);
+            }
+            if run_state::signal(&self.db, &ctx.run_id) == RunSignal::Cancel {
+                human_turns::close_turn(&self.db, &turn.id, TurnStatus::Cancelled).map_err(|e| anyhow::anyhow!(e))?;
+                return Ok(RoundOutcome::Cancelled);
+            }
+            run_state::heartbeat(&self.db, &ctx.run_id);
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Moderator-led: before each turn the moderator picks the next speaker and poses a follow-up,
+    /// which is stored as a `moderator` message the speaker then answers. On resume, answered
+    /// steps are skipped and an unanswered follow-up is put to its speaker again.
+    async fn run_moderated_round(&self, ctx: &DebateRunContext, round_index: i32) -> Result<RoundOutcome> {
+        let moderator = ctx.moderator.as_ref().ok_or_else(|| anyhow::anyhow!("Moderator profile not loaded"))?;
+        let participants = ctx
+            .profiles
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let stored = self.load_round_turns(&ctx.run_id, round_index)?;
+
+        for step in 0..ctx.profiles.len() {
+            let question_turn = (step * 2) as i32;
+            if stored.contains_key(&(question_turn + 1)) {
+                continue;
+            }
+            if run_state::checkpoint(&self.db, &ctx.run_id).await == RunSignal::Cancel {
+                return Ok(RoundOutcome::Cancelled);
+            }
+            // Asked before an interruption: "<speaker name>: <question>"
+            let asked = stored.get(&question_turn).and_then(|text| {
+                ctx.profiles.iter().find_map(|p| {
+                    text.strip_prefix(&format!("{}: ", p.name)).map(|question| (p, question.to_string()))
+                })
+            });
+            let (speaker, question) = match asked {
+                Some(asked) => asked,
+                None => self.ask_moderator(ctx, moderator, &participants, round_index, step).await?,
+            };
+
+            let directive = format!(
+                "The moderator asks you directly: \"{}\"\nAnswer this question first, then add anything essential.",
+                question
+            );
+            self.take_turn(ctx, round_index, question_turn + 1, question_turn + 1, speaker, Some(directive)).await?;
+        }
+        Ok(RoundOutcome::Completed)
+    }
+
+    /// Let the moderator pick the speaker of `step` and store its follow-up question.
+    async fn ask_moderator<'a>(
+        &self,
+        ctx: &'a DebateRunContext,
+        moderator: &ProfileData,
+        participants: &str,
+        round_index: i32,
+        step: usize,
+    ) -> Result<(&'a ProfileData, String)> {
+        let question_turn = (step * 2) as i32;
+        let transcript = self.build_context(ctx, round_index, question_turn)?;
+        let prompt = format!(
+            "Debate question:\n{}\n\nParticipants: {}\n\nTranscript so far:\n{}\n\n\
+            Task: As moderator, choose who should speak next and pose them one pointed follow-up \
+            question that moves the debate forward. Prefer participants who have spoken least. \
+            Respond with JSON only: {{\"next_speaker\": \"<participant name>\", \"question\": \"<follow-up>\"}}",
+            ctx.user_question,
+            participants,
+            self.format_transcript(ctx, &transcript)
+        );
+        // The moderator's own settings, kept focused
+        let mut params = moderator.params_json.clone();
+        if !params.is_object() {
+            params = json!({});
         }
+        params["temperature"] = json!(0.3);
+        let packet = PromptPacket {
+            global_instructions: None,
+            persona_instructions: moderator.persona_prompt.clone(),
+            user_message: prompt,
+            conversation_context: None,
+            params_json: params,
+            stream: false,
+        };
+        let timeout_secs = self.resolve_timeout_secs(&moderator.provider_account_id);
+        let (response, _, _) = complete_resolving_hybrid(&self.db, &moderator.provider_account_id, &moderator.model_name, &packet, timeout_secs, None)
+            .await
+            .map_err(|e| anyhow::anyhow!("Moderator failed: {}", e))?;
+
+        let parsed = crate::tournament::extract_json_object(&response.text);
+        let requested = parsed
+            .as_ref()
+            .and_then(|v| v.get("next_speaker"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let speaker = ctx
+            .profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(requested.trim()) || p.id == requested.trim())
+            .unwrap_or(&ctx.profiles[step % ctx.profiles.len()]);
+        let question = parsed
+            .as_ref()
+            .and_then(|v| v.get("question"))
+            .and_then(|v| v.as_str())
+            .map(|q| q.trim().to_string())
+            .filter(|q| !q.is_empty())
+            .unwrap_or_else(|| response.text.trim().to_string());
+
+        self.save_message(
+            &ctx.run_id,
+            "moderator",
+            Some(&moderator.id),
+            (round_index, question_turn),
+            &format!("{}: {}", speaker.name, question),
+            None,
+        )?;
+        Ok((speaker, question))
+    }
+
+    /// Cross-examination: each participant questions another, who must answer before the next pair.
+    /// Respondents rotate between rounds so everyone gets examined by different participants.
+    /// On resume, answered pairs are skipped and a stored question is answered without asking again.
+    async fn run_cross_examination_round(&self, ctx: &DebateRunContext, round_index: i32) -> Result<RoundOutcome> {
+        let n = ctx.profiles.len();
+        let shift = 1 + (round_index as usize % (n - 1));
+        let stored = self.load_round_turns(&ctx.run_id, round_index)?;
+
+        for pair in 0..n {
+            let question_turn = (pair * 2) as i32;
+            if stored.contains_key(&(question_turn + 1)) {
+                continue;
+            }
+            if run_state::checkpoint(&self.db, &ctx.run_id).await == RunSignal::Cancel {
+                return Ok(RoundOutcome::Cancelled);
+            }
+            let asker = &ctx.profiles[pair];
+            let respondent = &ctx.profiles[(pair + shift) % n];
+
+            // Resuming after the question was asked but before it was answered
+            let question = if let Some(question) = stored.get(&question_turn) {
+                Some(question.clone())
+            } else {
+                let directive = format!(
+                    "Cross-examination: ask {} exactly one pointed question that tests the weakest part of \
+                    their position. Output only the question.",
+                    respondent.name
+                );
+                self.take_turn(ctx, round_index, question_turn, question_turn, asker, Some(directive)).await?
+            };
+            let question = match question {
+                Some(q) => q,
+                None => continue,
+            };
+
+            if run_state::checkpoint(&self.db, &ctx.run_id).await == RunSignal::Cancel {
+                return Ok(RoundOutcome::Cancelled);
+            }
+            let directive = format!(
+                "Cross-examination: {} asks you: \"{}\"\nAnswer directly and honestly. Concede points you cannot defend.",
+                asker.name, question
+            );
+            self.take_turn(ctx, round_index, question_turn + 1, question_turn + 1, respondent, Some(directive)).await?;
+        }
+        Ok(RoundOutcome::Completed)
+    }
+
+    /// Run one agent turn: build context under the configured policy, call the model, and persist
+    /// the message. Returns the response text, or None if the provider call failed (the debate
+    /// continues with the next turn). `context_turn_bound` limits context within the current round.
+    async fn take_turn(
+        &self,
+        ctx: &DebateRunContext,
+        round_index: i32,
+        turn_index: i32,
+        context_turn_bound: i32,
+        profile: &ProfileData,
+        directive: Option<String>,
+    ) -> Result<Option<String>> {
+        let context_messages = self.build_context(ctx, round_index, context_turn_bound)?;
+
+        // Execute turn
+        eprintln!("[Debate] Executing turn round={} turn={} profile={} run_id={}", round_index, turn_index, profile.id, ctx.run_id);
+        let turn_result = self.execute_turn(
+            &ctx.run_id,
+            round_index,
+            turn_index,
+            profile,
+            &ctx.user_question,
+            &context_messages,
+            ctx.max_words,
+            ctx.language.clone(),
+            ctx.tone.clone(),
+            if round_index == 0 { ctx.web_search_results.clone() } else { None },
+            directive.as_deref(),
+        ).await;
+
+        match turn_result {
+            Ok((response_text, usage_json)) => {
+                eprintln!("[Debate] Turn completed SUCCESS: run_id={} round={} turn={} profile={} text_len={}", ctx.run_id, round_index, turn_index, profile.id, response_text.len());
+                // Save message with usage data
+                self.save_message(&ctx.run_id, "agent", Some(&profile.id), (round_index, turn_index), &response_text, usage_json.as_ref())?;
+
+                // Record token usage for this debate turn (if usage info is available)
+                if let Some(usage) = &usage_json {
+                    // We don't currently track provider_id/model_name per turn here,
+                    // so we record without provider_id and with a generic model name.
+                    let _ = record_token_usage(
+                        &self.db,
+                        None,
+                        "debate_model",
+                        &Some(usage.clone()),
+                        "debate",
+                        None,
+                        None,
+                    );
+                }
+
+                // Auto-training ingest: debate turn → training_data (best-effort)
+                let _ = training_ingest::ingest_debate_turn(
+                    &self.db,
+                    &ctx.project_id,
+                    ctx.session_local_model_id.as_deref(),
+                    &ctx.user_question,
+                    &response_text,
+                    &ctx.session_id,
+                    &ctx.run_id,
+                );
+
+                Ok(Some(response_text))
+            }
+            Err(e) => {
+                eprintln!("[Debate] Turn execution error run_id={} round={} turn={} profile={}: {}", ctx.run_id, round_index, turn_index, profile.id, e);
+                // Continue to next turn even on error
+                Ok(None)
+            }
+        }
+    }
+
+    /// Persist a debate message at `(round_index, turn_index)`.
+    fn save_message(
+        &self,
+        run_id: &str,
+        author_type: &str,
+        profile_id: Option<&str>,
+        (round_index, turn_index): (i32, i32),
+        text: &str,
+        usage_json: Option<&Value>,
+    ) -> Result<String> {
+        let message_id = Uuid::new_v4().to_string();
+        let created_at = chrono::Utc::now().to_rfc3339();
+        let usage_json_str = usage_json.and_then(|u| serde_json::to_string(u).ok());
+
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        conn_guard.execute(
+            "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text, created_at, provider_metadata_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                message_id,
+                run_id,
+                author_type,
+                profile_id,
+                round_index,
+                turn_index,
+                text,
+                created_at,
+                usage_json_str
+            ],
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to save message: {}", e))?;
+        Ok(message_id)
+    }
+
+    /// Messages before (round_index, turn_bound), ordered as spoken.
+    fn load_prior_messages(&self, run_id: &str, round_index: i32, turn_bound: i32) -> Result<Vec<Message>> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
         
-        eprintln!("[Debate] All rounds complete, about to update run status");
+        let mut stmt = conn_guard
+            .prepare("SELECT id, author_type, profile_id, round_index, turn_index, text, created_at, author_label FROM messages WHERE run_id = ?1 AND (round_index < ?2 OR (round_index = ?2 AND turn_index < ?3)) ORDER BY round_index, turn_index, created_at")
+            .map_err(|e| anyhow::anyhow!("Failed to prepare query: {}", e))?;
+        
+        let rows = stmt
+            .query_map(rusqlite::params![run_id, round_index, turn_bound], |row| {
+                Ok(Message {
+                    id: row.get(0)?,
+                    run_id: run_id.to_string(),
+                    author_type: row.get(1)?,
+                    profile_id: row.get(2)?,
+                    round_index: row.get(3)?,
+                    turn_index: row.get(4)?,
+                    // Human participants are attributed to their role, e.g. "[Product Owner] ..."
+                    text: match row.get::<_, Option<String>>(7)? {
+                        Some(label) => format!("[{}] {}", label, row.get::<_, String>(5)?),
+                        None => row.get(5)?,
+                    },
+                    created_at: row.get(6)?,
+                    provider_metadata_json: None,
+                })
+            })
+            .map_err(|e| anyhow::anyhow!("Failed to query messages: {}", e))?;
+        
+        let mut context = Vec::new();
+        for row in rows {
+            context.push(row.map_err(|e| anyhow::anyhow!("Row error: {}", e))?);
+        }
+        Ok(context)
+    }
 
-        // Update run status
-        self.state = DebateState::Complete;
+    /// Conversation context for a turn under the configured context policy.
+    fn build_context(&self, ctx: &DebateRunContext, round_index: i32, turn_bound: i32) -> Result<Vec<Message>> {
+        let messages = self.load_prior_messages(&ctx.run_id, round_index, turn_bound)?;
+        let last_k = self.settings.last_k.max(1) as usize;
+        let tail = |msgs: Vec<Message>| -> Vec<Message> {
+            let skip = msgs.len().saturating_sub(last_k);
+            msgs.into_iter().skip(skip).collect()
+        };
+
+        Ok(match self.settings.context_policy {
+            ContextPolicy::Full => messages,
+            ContextPolicy::LastK => tail(messages),
+            ContextPolicy::RollingSummary => {
+                let summary = summary_memory::load_summary(&self.db, SummaryScope::Run, &ctx.run_id)
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                let mut context: Vec<Message> = summary_memory::summary_message(summary.as_ref(), "debate")
+                    .into_iter()
+                    .collect();
+                context.extend(tail(summary_memory::uncovered_messages(summary.as_ref(), &messages)));
+                context
+            }
+        })
+    }
+
+    /// Fold every message outside the last-k window into the run's stored summary. Best-effort:
+    /// on failure the previous summary is kept and the raw messages stay in the window.
+    async fn refresh_rolling_summary(&self, ctx: &DebateRunContext, force: bool) {
+        let summarizer_id = self
+            .settings
+            .summarizer_profile_id
+            .clone()
+            .or_else(|| ctx.moderator.as_ref().map(|p| p.id.clone()))
+            .or_else(|| ctx.profiles.first().map(|p| p.id.clone()));
+        let config = SummaryMemoryConfig {
+            enabled: true,
+            summarizer_profile_id: summarizer_id,
+            keep_last_k: self.settings.last_k.max(1) as usize,
+            refresh_threshold: 1,
+            ..Default::default()
+        };
+        let speaker = |m: &Message| Self::speaker_name(ctx, m);
+        if let Err(e) = summary_memory::refresh(
+            &self.db,
+            SummaryScope::Run,
+            &ctx.run_id,
+            &config,
+            Some(&ctx.user_question),
+            &speaker,
+            force,
+        )
+        .await
         {
-            let conn = self.db.get_connection();
-            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-            let finished_at = chrono::Utc::now().to_rfc3339();
-            conn_guard.execute(
-                "UPDATE runs SET status = 'complete', finished_at = ?1 WHERE id = ?2",
-                rusqlite::params![finished_at, run_id],
+            eprintln!("[Debate] Rolling summary failed: {}", e);
+        }
+    }
+
+    fn judge_profile_id(&self, ctx: &DebateRunContext) -> Option<String> {
+        self.settings
+            .consensus
+            .judge_profile_id
+            .clone()
+            .or_else(|| ctx.moderator.as_ref().map(|p| p.id.clone()))
+            .or_else(|| ctx.profiles.first().map(|p| p.id.clone()))
+    }
+
+    /// Judge step after a round. Best-effort: a failed check lets the debate continue.
+    async fn check_consensus(&self, ctx: &DebateRunContext, round_index: i32) -> Option<String> {
+        let judge_id = self.judge_profile_id(ctx)?;
+        match consensus::check_round(&self.db, &ctx.run_id, round_index, &judge_id, &self.settings.consensus).await {
+            Ok(check) => {
+                eprintln!("[Debate] Round {} agreement {:.2} -> {}", round_index, check.agreement_score, check.decision);
+                Some(check.decision)
+            }
+            Err(e) => {
+                eprintln!("[Debate] Consensus check failed: {}", e);
+                None
+            }
+        }
+    }
+
+    fn speaker_name(ctx: &DebateRunContext, m: &Message) -> String {
+        match m.author_type.as_str() {
+            "user" => "User".to_string(),
+            "moderator" => "Moderator".to_string(),
+            _ => m
+                .profile_id
+                .as_ref()
+                .and_then(|id| ctx.profiles.iter().find(|p| p.id == *id))
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| "Agent".to_string()),
+        }
+    }
+
+    /// "Name: text" lines for moderator and summarizer prompts.
+    fn format_transcript(&self, ctx: &DebateRunContext, messages: &[Message]) -> String {
+        messages
+            .iter()
+            .map(|m| format!("{}: {}", Self::speaker_name(ctx, m), m.text))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Agent and moderator messages already stored for a round, by turn index
+    fn load_round_turns(&self, run_id: &str, round_index: i32) -> Result<HashMap<i32, String>> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        let mut stmt = conn_guard
+            .prepare(
+                "SELECT turn_index, text FROM messages
+                 WHERE run_id = ?1 AND round_index = ?2 AND author_type IN ('agent', 'moderator') ORDER BY created_at",
             )
-            .map_err(|e| anyhow::anyhow!("Failed to update run status: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![run_id, round_index], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| anyhow::anyhow!("Failed to query messages: {}", e))?;
+        let mut turns = HashMap::new();
+        for row in rows {
+            let (turn_index, text) = row.map_err(|e| anyhow::anyhow!("Row error: {}", e))?;
+            turns.insert(turn_index, text);
         }
+        Ok(turns)
+    }
 
-        Ok(())
+    fn load_spoken_turns(&self, run_id: &str) -> Result<Vec<(i32, String)>> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        let mut stmt = conn_guard
+            .prepare("SELECT round_index, profile_id FROM messages WHERE run_id = ?1 AND author_type = 'agent' AND profile_id IS NOT NULL")
+            .map_err(|e| anyhow::anyhow!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map([run_id], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| anyhow::anyhow!("Failed to query messages: {}", e))?;
+        let mut spoken = Vec::new();
+        for row in rows {
+            spoken.push(row.map_err(|e| anyhow::anyhow!("Row error: {}", e))?);
+        }
+        Ok(spoken)
     }
 
     fn load_profiles(&self, profile_ids: &[String]) -> Result<Vec<ProfileData>> {
@@ -504,6 +1163,7 @@ impl DebateOrchestrator {
         language: Option<String>,
         tone: Option<String>,
         web_search_results: Option<Vec<crate::web_search::NewsResult>>,
+        directive: Option<&str>,
     ) -> Result<(String, Option<serde_json::Value>)> {
         eprintln!("[Debate] execute_turn start: run_id={} round={} turn={} profile={} provider={} model={}", run_id, round_index, turn_index, profile.id, profile.provider_account_id, profile.model_name);
         let turn_id = Uuid::new_v4().to_string();
@@ -584,7 +1244,7 @@ impl DebateOrchestrator {
             }
         }
         
-        let persona_instruction = if round_index == 0 {
+        let mut persona_instruction = if round_index == 0 {
             format!("{}\n\nAnswer the following question with your perspective. Be conversational, natural, and human-like. Avoid overly formal or robotic language. Use contractions, natural pauses, and speak as if you're having a real discussion. Engage naturally with the topic.{}{}{}{}", 
                 profile.persona_prompt, 
                 word_limit_instruction,
@@ -598,6 +1258,10 @@ impl DebateOrchestrator {
                 language_instruction,
                 tone_instruction)
         };
+        // Mode-specific task for this turn (moderator follow-up, cross-examination question/answer)
+        if let Some(d) = directive {
+            persona_instruction.push_str(&format!("\n\nYOUR TASK THIS TURN: {}", d));
+        }
 
         // Build conversation context
         let conversation_context: Option<Vec<Message>> = if context_messages.is_empty() {
@@ -726,13 +1390,9 @@ impl DebateOrchestrator {
     }
     
     pub async fn handle_error(&self, run_id: &str, error: &str) {
+        let _ = run_state::transition(&self.db, run_id, RunStatus::Failed, "worker_error");
         let conn = self.db.get_connection();
         if let Ok(conn_guard) = conn.lock() {
-            let finished_at = chrono::Utc::now().to_rfc3339();
-            let _ = conn_guard.execute(
-                "UPDATE runs SET status = 'failed', finished_at = ?1 WHERE id = ?2",
-                rusqlite::params![finished_at, run_id],
-            );
             // Store error for UI retrieval (runs.error_message_safe if column exists)
             let _ = conn_guard.execute(
                 "UPDATE runs SET error_message_safe = ?1 WHERE id = ?2",
@@ -743,12 +1403,276 @@ impl DebateOrchestrator {
     }
 }
 
+/// One entry of a round's speaking order.
+enum Seat<'a> {
+    Agent(&'a ProfileData),
+    Human(&'a HumanSeat),
+}
+
 struct ProfileData {
     id: String,
-    #[allow(dead_code)]
     name: String,
     provider_account_id: String,
     model_name: String,
     persona_prompt: String,
     params_json: Value,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debate_settings_from_json() {
+        let settings: DebateSettings = serde_json::from_value(json!({
+            "mode": "cross_examination",
+            "context_policy": "last_k",
+            "last_k": 4
+        }))
+        .unwrap();
+        assert_eq!(settings.mode, DebateMode::CrossExamination);
+        assert_eq!(settings.context_policy, ContextPolicy::LastK);
+        assert_eq!(settings.last_k, 4);
+        assert_eq!(settings.concurrency, 3);
+
+        // Values written to debate_configs parse back to the same settings
+        assert_eq!(DebateMode::parse(settings.mode.as_str()), Some(settings.mode));
+        assert_eq!(DebateMode::parse("round_robin"), None);
+        assert_eq!(ContextPolicy::parse(ContextPolicy::RollingSummary.as_str()), ContextPolicy::RollingSummary);
+        assert_eq!(ContextPolicy::parse("unknown"), ContextPolicy::Full);
+    }
+
+    /// Debate with one profile and a human seat "po" whose first two rounds are over
+    fn finished_debate() -> (Database, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("panther-debate-{}.db", Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                     VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Q', 'debate');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('r1', 's1', '[\"p1\"]', 'complete', '{}');
+                 INSERT INTO debate_configs (id, run_id, mode, rounds, speaking_order_json, context_policy, last_k, concurrency, human_seats_json, human_turn_timeout_secs)
+                     VALUES ('c1', 'r1', 'sequential', 2, '[\"p1\", \"human:po\"]', 'last_k', 4, 3, '[{\"id\":\"po\",\"role\":\"Product Owner\"}]', 1);
+                 INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text) VALUES
+                     ('q', 'r1', 'user', NULL, -1, -1, 'Q'),
+                     ('m0', 'r1', 'agent', 'p1', 0, 0, 'opening'),
+                     ('m1', 'r1', 'agent', 'p1', 1, 0, 'rebuttal');",
+            )
+            .unwrap();
+        }
+        let seat = HumanSeat { id: "po".to_string(), role: "Product Owner".to_string() };
+        for round in 0..2 {
+            let turn = human_turns::request_turn(&db, "r1", &seat, round, 1, 60).unwrap();
+            human_turns::close_turn(&db, &turn.id, TurnStatus::Skipped).unwrap();
+        }
+        (db, path)
+    }
+
+    #[test]
+    fn test_continuation_rounds_follow_stored_rounds() {
+        let (db, path) = finished_debate();
+        assert_eq!(next_round(&db, "r1").unwrap(), 2);
+
+        // Human turns of a continuation are new turns, not the answered ones of the same seat
+        let seat = HumanSeat { id: "po".to_string(), role: "Product Owner".to_string() };
+        let turn = human_turns::request_turn(&db, "r1", &seat, next_round(&db, "r1").unwrap(), 1, 60).unwrap();
+        assert_eq!(TurnStatus::parse(&turn.status), TurnStatus::Waiting);
+        assert_eq!(next_round(&db, "r1").unwrap(), 3);
+
+        let config = load_debate_config(&db, "r1").unwrap();
+        assert_eq!((config.first_round, config.rounds), (0, 2));
+        assert_eq!(config.settings.context_policy, ContextPolicy::LastK);
+        assert_eq!(config.settings.human_seats.len(), 1);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_continue() {
+        let (db, path) = finished_debate();
+        // The seat never answered in round 1 (the debate was stopped early), then a one-round
+        // continuation was interrupted after Ana spoke in round 2, before the seat's turn
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "DELETE FROM debate_human_turns WHERE run_id = 'r1' AND round_index = 1;
+                 UPDATE debate_configs SET first_round = 2, rounds = 3 WHERE run_id = 'r1';
+                 INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text)
+                     VALUES ('m2', 'r1', 'agent', 'p1', 2, 0, 'continued');
+                 UPDATE runs SET status = 'running' WHERE id = 'r1';",
+            )
+            .unwrap();
+        }
+
+        let mut orchestrator = DebateOrchestrator::new(db.clone());
+        orchestrator.resume_debate_run("r1".to_string()).await.unwrap();
+
+        // Only the seat's round-2 turn was opened (and timed out); rounds before the continuation are not re-run
+        let turns: Vec<(i32, String)> = {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT round_index, status FROM debate_human_turns WHERE run_id = 'r1' ORDER BY round_index")
+                .unwrap();
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+            rows.map(|r| r.unwrap()).collect()
+        };
+        assert_eq!(
+            turns,
+            vec![(0, "skipped".to_string()), (2, "timed_out".to_string())]
+        );
+        assert_eq!(orchestrator.load_spoken_turns("r1").unwrap().len(), 3);
+        assert_eq!(run_state::current_status(&db, "r1").unwrap().as_deref(), Some("complete"));
+
+        // Cancelling the finished run is a no-op
+        let brainstorms = crate::orchestrator::Orchestrator::new(db.clone());
+        crate::commands::cancel_run_impl(&db, &brainstorms, "r1").await.unwrap();
+        assert_eq!(run_state::current_status(&db, "r1").unwrap().as_deref(), Some("complete"));
+        std::fs::remove_file(&path).ok();
+    }
+    /// Ollama-compatible server: model "mod" moderates (always picking Ben), every other model
+    /// answers "<model> speaking". Returns the base URL and the request bodies it received.
+    async fn mock_provider() -> (String, std::sync::Arc<std::sync::Mutex<Vec<Value>>>) {
+        type Requests = std::sync::Arc<std::sync::Mutex<Vec<Value>>>;
+        async fn chat(axum::extract::State(requests): axum::extract::State<Requests>, axum::Json(body): axum::Json<Value>) -> axum::Json<Value> {
+            let content = if body["model"] == "mod" {
+                json!({ "next_speaker": "Ben", "question": "What is the cost?" }).to_string()
+            } else {
+                format!("{} speaking", body["model"].as_str().unwrap_or_default())
+            };
+            requests.lock().unwrap().push(body);
+            axum::Json(json!({ "message": { "content": content }, "done": true }))
+        }
+        let requests: Requests = Default::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let app = axum::Router::new().route("/api/chat", axum::routing::post(chat)).with_state(requests.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.ok() });
+        (format!("http://{}", address), requests)
+    }
+
+    /// One-round debate between Ana and Ben in `mode`, interrupted after `seed_messages`
+    async fn interrupted_debate(mode: &str, seed_messages: &str) -> (Database, std::path::PathBuf, std::sync::Arc<std::sync::Mutex<Vec<Value>>>) {
+        let (base_url, requests) = mock_provider().await;
+        let path = std::env::temp_dir().join(format!("panther-debate-{}.db", Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO provider_accounts (id, provider_type, display_name, base_url) VALUES ('acc', 'ollama', 'Ollama', ?1)",
+                [base_url],
+            )
+            .unwrap();
+            conn.execute_batch(&format!(
+                "INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json) VALUES
+                     ('ana', 'Ana', 'acc', 'ana', 'You are Ana', '{{}}'),
+                     ('ben', 'Ben', 'acc', 'ben', 'You are Ben', '{{}}'),
+                     ('mod', 'Mo', 'acc', 'mod', 'You moderate', '{{\"temperature\": 1.0, \"max_tokens\": 77}}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Raise prices?', 'debate');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('r1', 's1', '[\"ana\", \"ben\"]', 'running', '{{}}');
+                 {}",
+                seed_messages
+            ))
+            .unwrap();
+            conn.execute(
+                "INSERT INTO debate_configs (id, run_id, mode, rounds, speaking_order_json, context_policy, last_k, concurrency, moderator_profile_id)
+                 VALUES ('c1', 'r1', ?1, 1, '[\"ana\", \"ben\"]', 'full', 6, 2, 'mod')",
+                [mode],
+            )
+            .unwrap();
+        }
+        (db, path, requests)
+    }
+
+    /// (turn_index, author_type, profile_id) of round 0, in turn order
+    fn round_turns(db: &Database) -> Vec<(i32, String, Option<String>)> {
+        let conn = db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT turn_index, author_type, profile_id FROM messages WHERE run_id = 'r1' AND round_index = 0 ORDER BY turn_index, created_at")
+            .unwrap();
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).unwrap();
+        rows.map(|r| r.unwrap()).collect()
+    }
+
+    fn turn(index: i32, author_type: &str, profile_id: &str) -> (i32, String, Option<String>) {
+        (index, author_type.to_string(), Some(profile_id.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_parallel_resume_runs_only_missing_speakers() {
+        let (db, path, requests) = interrupted_debate(
+            "parallel",
+            "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text) VALUES ('m0', 'r1', 'agent', 'ana', 0, 0, 'ana speaking');",
+        )
+        .await;
+        DebateOrchestrator::new(db.clone()).resume_debate_run("r1".to_string()).await.unwrap();
+
+        assert_eq!(round_turns(&db), vec![turn(0, "agent", "ana"), turn(1, "agent", "ben")]);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(run_state::current_status(&db, "r1").unwrap().as_deref(), Some("complete"));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_moderator_resume_answers_the_stored_question_once() {
+        // The moderator had asked Ben, but Ben's answer was not stored yet
+        let (db, path, requests) = interrupted_debate(
+            "moderator",
+            "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text) VALUES ('q0', 'r1', 'moderator', 'mod', 0, 0, 'Ben: Why now?');",
+        )
+        .await;
+        DebateOrchestrator::new(db.clone()).resume_debate_run("r1".to_string()).await.unwrap();
+
+        assert_eq!(
+            round_turns(&db),
+            vec![turn(0, "moderator", "mod"), turn(1, "agent", "ben"), turn(2, "moderator", "mod"), turn(3, "agent", "ben")]
+        );
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().any(|r| r["model"] == "ben" && r.to_string().contains("Why now?")));
+        let moderator_calls: Vec<&Value> = requests.iter().filter(|r| r["model"] == "mod").collect();
+        assert_eq!(moderator_calls.len(), 1);
+        // The moderator profile's params, with the moderator temperature
+        assert_eq!(moderator_calls[0]["options"]["num_predict"], 77);
+        assert_eq!(moderator_calls[0]["options"]["temperature"], 0.3);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_cross_examination_resume_after_a_failed_answer() {
+        // Ben's answer to Ana failed, then Ben asked his question and the run was interrupted
+        let (db, path, requests) = interrupted_debate(
+            "cross_examination",
+            "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text) VALUES
+                 ('m0', 'r1', 'agent', 'ana', 0, 0, 'Is it affordable?'),
+                 ('m2', 'r1', 'agent', 'ben', 0, 2, 'Who pays?');",
+        )
+        .await;
+        DebateOrchestrator::new(db.clone()).resume_debate_run("r1".to_string()).await.unwrap();
+
+        assert_eq!(
+            round_turns(&db),
+            vec![turn(0, "agent", "ana"), turn(1, "agent", "ben"), turn(2, "agent", "ben"), turn(3, "agent", "ana")]
+        );
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2, "both stored questions are answered, none is asked again");
+        assert!(requests.iter().any(|r| r["model"] == "ana" && r.to_string().contains("Who pays?")));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_stored_mode_is_rejected() {
+        let (db, path, requests) = interrupted_debate("round_robin", "").await;
+        let err = DebateOrchestrator::new(db.clone()).resume_debate_run("r1".to_string()).await.unwrap_err();
+        assert!(err.to_string().contains("Unknown debate mode 'round_robin'"), "{}", err);
+        assert!(requests.lock().unwrap().is_empty());
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,510 @@
+// Group chat: the user and several profiles in one free-form, persistent conversation
+//
+// Unlike the Debate Room there are no rounds or speaking order. A user message addressed with
+// @Name is answered by the mentioned profiles; otherwise members marked as auto-responders reply.
+// Replies may @mention other members, who then answer as well, up to a per-message reply cap.
+// Every profile is called through its own provider, resolving hybrid providers like Profile Chat.
+
+use crate::commands_chat::build_persona;
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::token_usage::record_token_usage;
+use crate::types::{CharacterDefinition, PromptPacket};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::VecDeque;
+
+pub const DEFAULT_MAX_REPLIES: usize = 4;
+const MAX_REPLIES_LIMIT: usize = 10;
+/// Most recent messages shown to a responder
+const HISTORY_LIMIT: usize = 40;
+const USER_NAME: &str = "User";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMemberInput {
+    pub profile_id: String,
+    /// Reply to user messages that mention nobody
+    #[serde(default)]
+    pub auto_respond: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupMember {
+    pub profile_id: String,
+    pub name: String,
+    pub photo_url: Option<String>,
+    pub auto_respond: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupConversation {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub members: Vec<GroupMember>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupMessage {
+    pub id: String,
+    pub conversation_id: String,
+    /// None for the user
+    pub profile_id: Option<String>,
+    pub author_name: String,
+    pub content: String,
+    /// Profile ids addressed with @Name
+    pub mentions: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatRequest {
+    /// Taken from the URL over HTTP
+    #[serde(default)]
+    pub conversation_id: String,
+    pub content: String,
+    pub language: Option<String>,
+    /// Cap on profile replies triggered by this message (default 4)
+    pub max_replies: Option<usize>,
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupChatFailure {
+    pub profile_id: String,
+    pub name: String,
+    pub error: String,
+}
+
+/// Messages stored for one user turn: the user's message first, then the replies
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupChatTurn {
+    pub messages: Vec<GroupMessage>,
+    pub failures: Vec<GroupChatFailure>,
+}
+
+/// Members addressed as @Full Name or @FirstName (case-insensitive), in order of first mention
+pub fn parse_mentions(text: &str, members: &[GroupMember]) -> Vec<String> {
+    let mut labels: Vec<(String, &str)> = Vec::new();
+    for m in members {
+        labels.push((m.name.to_lowercase(), &m.profile_id));
+        if let Some(first) = m.name.split_whitespace().next().filter(|f| f.len() < m.name.len()) {
+            labels.push((first.to_lowercase(), &m.profile_id));
+        }
+    }
+    // Longest label first so "@Ana Maria" wins over "@Ana"
+    labels.sort_by_key(|(label, _)| std::cmp::Reverse(label.chars().count()));
+
+    let mut mentioned: Vec<String> = Vec::new();
+    for (i, _) in text.match_indices('@') {
+        // Skip e-mail addresses and the like
+        if text[..i].chars().last().is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+        let rest = &text[i + 1..];
+        for (label, profile_id) in &labels {
+            let len = label.chars().count();
+            let candidate: String = rest.chars().take(len).collect();
+            let boundary = rest.chars().nth(len).map(|c| !c.is_alphanumeric()).unwrap_or(true);
+            if len > 0 && boundary && candidate.to_lowercase() == *label {
+                if !mentioned.iter().any(|m| m == profile_id) {
+                    mentioned.push(profile_id.to_string());
+                }
+                break;
+            }
+        }
+    }
+    mentioned
+}
+
+/// Who answers a user message: the mentioned members, or else the auto-responders
+pub fn responders(conversation: &GroupConversation, mentions: &[String]) -> Vec<String> {
+    if mentions.is_empty() {
+        conversation.members.iter().filter(|m| m.auto_respond).map(|m| m.profile_id.clone()).collect()
+    } else {
+        mentions.to_vec()
+    }
+}
+
+fn load_members(conn: &Connection, conversation_id: &str) -> Result<Vec<GroupMember>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.profile_id, p.name, p.photo_url, m.auto_respond
+             FROM group_conversation_members m JOIN prompt_profiles p ON p.id = m.profile_id
+             WHERE m.conversation_id = ?1 ORDER BY m.position",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([conversation_id], |row| {
+            Ok(GroupMember {
+                profile_id: row.get(0)?,
+                name: row.get(1)?,
+                photo_url: row.get(2)?,
+                auto_respond: row.get::<_, i64>(3)? != 0,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Row error: {}", e))
+}
+
+fn load_conversation(conn: &Connection, id: &str) -> Result<GroupConversation, String> {
+    let (title, created_at, updated_at) = conn
+        .query_row(
+            "SELECT title, created_at, updated_at FROM group_conversations WHERE id = ?1",
+            [id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Group chat not found".to_string())?;
+    Ok(GroupConversation { id: id.to_string(), title, created_at, updated_at, members: load_members(conn, id)? })
+}
+
+fn replace_members(conn: &Connection, conversation_id: &str, members: &[GroupMemberInput]) -> Result<(), String> {
+    if members.is_empty() {
+        return Err("A group chat needs at least one profile".to_string());
+    }
+    conn.execute("DELETE FROM group_conversation_members WHERE conversation_id = ?1", [conversation_id])
+        .map_err(|e| format!("Database error: {}", e))?;
+    for (position, member) in members.iter().enumerate() {
+        conn.execute(
+            "INSERT OR REPLACE INTO group_conversation_members (conversation_id, profile_id, position, auto_respond)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![conversation_id, member.profile_id, position as i64, member.auto_respond],
+        )
+        .map_err(|e| format!("Failed to add profile {}: {}", member.profile_id, e))?;
+    }
+    Ok(())
+}
+
+pub fn create(db: &Database, title: Option<String>, members: &[GroupMemberInput]) -> Result<GroupConversation, String> {
+    let conn = db.get_connection();
+    let mut conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let tx = conn_guard.transaction().map_err(|e| format!("Database error: {}", e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    tx.execute(
+        "INSERT INTO group_conversations (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        rusqlite::params![id, title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "New group chat".to_string()), now],
+    )
+    .map_err(|e| format!("Failed to create group chat: {}", e))?;
+    replace_members(&tx, &id, members)?;
+    let conversation = load_conversation(&tx, &id)?;
+    tx.commit().map_err(|e| format!("Database error: {}", e))?;
+    Ok(conversation)
+}
+
+pub fn list(db: &Database) -> Result<Vec<GroupConversation>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let ids: Vec<String> = {
+        let mut stmt = conn_guard
+            .prepare("SELECT id FROM group_conversations ORDER BY updated_at DESC")
+            .map_err(|e| format!("Database error: {}", e))?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| format!("Database error: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Row error: {}", e))?
+    };
+    ids.iter().map(|id| load_conversation(&conn_guard, id)).collect()
+}
+
+pub fn get(db: &Database, id: &str) -> Result<GroupConversation, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    load_conversation(&conn_guard, id)
+}
+
+/// Rename and/or replace the member list (order, auto-responders)
+pub fn update(
+    db: &Database,
+    id: &str,
+    title: Option<String>,
+    members: Option<Vec<GroupMemberInput>>,
+) -> Result<GroupConversation, String> {
+    let conn = db.get_connection();
+    let mut conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let tx = conn_guard.transaction().map_err(|e| format!("Database error: {}", e))?;
+    load_conversation(&tx, id)?;
+    if let Some(title) = title.filter(|t| !t.trim().is_empty()) {
+        tx.execute("UPDATE group_conversations SET title = ?1 WHERE id = ?2", rusqlite::params![title, id])
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+    if let Some(members) = members {
+        replace_members(&tx, id, &members)?;
+    }
+    tx.execute(
+        "UPDATE group_conversations SET updated_at = ?1 WHERE id = ?2",
+        rusqlite::params![chrono::Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+    let conversation = load_conversation(&tx, id)?;
+    tx.commit().map_err(|e| format!("Database error: {}", e))?;
+    Ok(conversation)
+}
+
+pub fn delete(db: &Database, id: &str) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute("DELETE FROM group_conversations WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete group chat: {}", e))?;
+    Ok(())
+}
+
+fn map_message(row: &rusqlite::Row) -> rusqlite::Result<GroupMessage> {
+    let mentions_json: String = row.get(5)?;
+    Ok(GroupMessage {
+        id: row.get(0)?,
+        conversation_id: row.get(1)?,
+        profile_id: row.get(2)?,
+        author_name: row.get(3)?,
+        content: row.get(4)?,
+        mentions: serde_json::from_str(&mentions_json).unwrap_or_default(),
+        created_at: row.get(6)?,
+    })
+}
+
+/// Messages oldest first; with `limit`, only the most recent ones
+fn query_messages(conn: &Connection, conversation_id: &str, limit: Option<usize>) -> Result<Vec<GroupMessage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, conversation_id, profile_id, author_name, content, mentions_json, created_at
+             FROM group_messages WHERE conversation_id = ?1 ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let limit = limit.map(|l| l as i64).unwrap_or(-1);
+    let rows = stmt
+        .query_map(rusqlite::params![conversation_id, limit], map_message)
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut messages = rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Row error: {}", e))?;
+    messages.reverse();
+    Ok(messages)
+}
+
+pub fn load_messages(db: &Database, conversation_id: &str) -> Result<Vec<GroupMessage>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    query_messages(&conn_guard, conversation_id, None)
+}
+
+fn store_message(
+    db: &Database,
+    conversation_id: &str,
+    profile_id: Option<&str>,
+    author_name: &str,
+    content: &str,
+    mentions: Vec<String>,
+) -> Result<GroupMessage, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let message = GroupMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        conversation_id: conversation_id.to_string(),
+        profile_id: profile_id.map(str::to_string),
+        author_name: author_name.to_string(),
+        content: content.to_string(),
+        mentions,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    conn_guard
+        .execute(
+            "INSERT INTO group_messages (id, conversation_id, profile_id, author_name, content, mentions_json, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                message.id,
+                message.conversation_id,
+                message.profile_id,
+                message.author_name,
+                message.content,
+                serde_json::to_string(&message.mentions).unwrap_or_else(|_| "[]".to_string()),
+                message.created_at
+            ],
+        )
+        .map_err(|e| format!("Failed to save group message: {}", e))?;
+    conn_guard
+        .execute(
+            "UPDATE group_conversations SET updated_at = ?1 WHERE id = ?2",
+            rusqlite::params![message.created_at, conversation_id],
+        )
+        .ok();
+    Ok(message)
+}
+
+/// Persona for one member's turn: the profile's chat persona (character definition included)
+/// plus the group-conversation rules
+fn member_persona(
+    persona_prompt: &str,
+    character_definition_json: Option<&str>,
+    conversation: &GroupConversation,
+    member: &GroupMember,
+    language: Option<&str>,
+) -> String {
+    let character_definition: Option<CharacterDefinition> =
+        character_definition_json.and_then(|json| serde_json::from_str(json).ok());
+    let others: Vec<&str> = conversation
+        .members
+        .iter()
+        .filter(|m| m.profile_id != member.profile_id)
+        .map(|m| m.name.as_str())
+        .collect();
+    let mut persona = format!(
+        "{}\n\nYou are {name}, taking part in a group conversation with the user{others}. \
+         Write only {name}'s next message: do not speak for anyone else and do not prefix it with your name. \
+         To address another participant directly, @mention them by name.",
+        build_persona(persona_prompt, character_definition.as_ref()),
+        name = member.name,
+        others = if others.is_empty() { String::new() } else { format!(", {}", others.join(", ")) },
+    );
+    if let Some(language) = language.filter(|l| !l.is_empty() && *l != "English") {
+        persona.push_str(&format!("\n\nRespond in {}.", language));
+    }
+    persona
+}
+
+/// Generate and store one profile's next message
+async fn reply_as(
+    db: &Database,
+    conversation: &GroupConversation,
+    member: &GroupMember,
+    language: Option<&str>,
+    timeout_secs: u64,
+) -> Result<GroupMessage, String> {
+    let (provider_account_id, model_name, persona_prompt, character_definition_json, params_json, history) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let (provider_account_id, model_name, persona_prompt, character_definition_json, params_json): (String, String, String, Option<String>, String) = conn_guard
+            .query_row(
+                "SELECT provider_account_id, model_name, persona_prompt, character_definition_json, params_json FROM prompt_profiles WHERE id = ?1",
+                [&member.profile_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .map_err(|e| format!("Failed to load profile: {}", e))?;
+        let history = query_messages(&conn_guard, &conversation.id, Some(HISTORY_LIMIT))?;
+        (provider_account_id, model_name, persona_prompt, character_definition_json, params_json, history)
+    };
+    let persona = member_persona(&persona_prompt, character_definition_json.as_deref(), conversation, member, language);
+    let transcript: Vec<String> = history.iter().map(|m| format!("{}: {}", m.author_name, m.content)).collect();
+    let packet = PromptPacket {
+        global_instructions: None,
+        persona_instructions: persona,
+        user_message: format!(
+            "Conversation so far:\n\n{}\n\nWrite {}'s next message.",
+            transcript.join("\n\n"),
+            member.name
+        ),
+        conversation_context: None,
+        params_json: serde_json::from_str(&params_json).unwrap_or(json!({})),
+        stream: false,
+    };
+
+    let (response, used_provider, used_model) =
+        complete_resolving_hybrid(db, &provider_account_id, &model_name, &packet, timeout_secs, None).await?;
+    let _ = record_token_usage(
+        db,
+        Some(&used_provider.id),
+        &used_model,
+        &response.usage_json,
+        "group_chat",
+        None,
+        Some(json!({ "profile_id": member.profile_id, "group_conversation_id": conversation.id })),
+    );
+
+    // Models sometimes echo the transcript format
+    let text = response.text.trim();
+    let text = text.strip_prefix(&format!("{}:", member.name)).map(str::trim_start).unwrap_or(text);
+    let mentions = parse_mentions(text, &conversation.members)
+        .into_iter()
+        .filter(|id| *id != member.profile_id)
+        .collect();
+    store_message(db, &conversation.id, Some(&member.profile_id), &member.name, text, mentions)
+}
+
+/// Store the user's message and collect the replies it triggers
+pub async fn send(db: &Database, request: &GroupChatRequest) -> Result<GroupChatTurn, String> {
+    let content = request.content.trim();
+    if content.is_empty() {
+        return Err("Message is empty".to_string());
+    }
+    let conversation = get(db, &request.conversation_id)?;
+    let mentions = parse_mentions(content, &conversation.members);
+    let user_message = store_message(db, &conversation.id, None, USER_NAME, content, mentions.clone())?;
+
+    let max_replies = request.max_replies.unwrap_or(DEFAULT_MAX_REPLIES).min(MAX_REPLIES_LIMIT);
+    let timeout_secs = request.timeout_seconds.unwrap_or(90);
+    let mut queue: VecDeque<String> = responders(&conversation, &mentions).into();
+    let mut answered: Vec<String> = Vec::new();
+    let mut turn = GroupChatTurn { messages: vec![user_message], failures: Vec::new() };
+
+    while let Some(profile_id) = queue.pop_front() {
+        if answered.len() >= max_replies {
+            break;
+        }
+        if answered.contains(&profile_id) {
+            continue;
+        }
+        answered.push(profile_id.clone());
+        let Some(member) = conversation.members.iter().find(|m| m.profile_id == profile_id) else { continue };
+        match reply_as(db, &conversation, member, request.language.as_deref(), timeout_secs).await {
+            Ok(reply) => {
+                // Members addressed in a reply answer next
+                queue.extend(reply.mentions.iter().filter(|id| !answered.contains(id)).cloned());
+                turn.messages.push(reply);
+            }
+            Err(error) => turn.failures.push(GroupChatFailure {
+                profile_id: member.profile_id.clone(),
+                name: member.name.clone(),
+                error,
+            }),
+        }
+    }
+    Ok(turn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(id: &str, name: &str, auto_respond: bool) -> GroupMember {
+        GroupMember { profile_id: id.to_string(), name: name.to_string(), photo_url: None, auto_respond }
+    }
+
+    #[test]
+    fn test_mentions_pick_responders() {
+        let conversation = GroupConversation {
+            id: "g".to_string(),
+            title: "Team".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            members: vec![member("a", "Ana", true), member("am", "Ana Maria", false), member("b", "Bob", false)],
+        };
+        let members = &conversation.members;
+        assert_eq!(parse_mentions("@bob and @ana maria, thoughts?", members), ["b", "am"]);
+        assert_eq!(parse_mentions("@Ana: and again @ana", members), ["a"]);
+        assert!(parse_mentions("@Bobby mail me at x@ana.org", members).is_empty());
+
+        assert_eq!(responders(&conversation, &[]), ["a"]);
+        assert_eq!(responders(&conversation, &["b".to_string()]), ["b"]);
+    }
+
+    #[test]
+    fn test_member_persona_includes_character_definition() {
+        let conversation = GroupConversation {
+            id: "g".to_string(),
+            title: "Team".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            members: vec![member("a", "Ana", true), member("b", "Bob", false)],
+        };
+        let character = r#"{"name":"Ana","role":"Data engineer","personality":["curious"],"expertise":["Rust"],"communication_style":"Direct"}"#;
+        let persona = member_persona("You are Ana", Some(character), &conversation, &conversation.members[0], Some("German"));
+        assert!(persona.starts_with("You are Ana"));
+        assert!(persona.contains("Your role is: Data engineer"));
+        assert!(persona.contains("Your areas of expertise: Rust"));
+        assert!(persona.contains("group conversation with the user, Bob"));
+        assert!(persona.ends_with("Respond in German."));
+
+        let plain = member_persona("You are Ana", None, &conversation, &conversation.members[0], None);
+        assert!(!plain.contains("Your role is"));
+    }
+}
@@ -12,15 +12,29 @@ use tower_http::cors::{Any, CorsLayer};
 use crate::commands::{self, CreateProfileRequest, CreateProviderRequest, CreateSessionRequest};
 use crate::commands_chat::{self, ChatRequest, ImproveWithCloudRequest};
 use crate::commands_auth::{self, SignupRequest, LoginRequest};
+use crate::commands_sops::{self, InstantiateSopRequest, SaveSopRequest};
+use crate::commands_profile;
+use crate::commands_reports;
+use crate::commands_search;
+use crate::search::SearchRequest;
+use crate::session_transfer::{self, ImportOptions};
+use crate::group_chat::{GroupChatRequest, GroupMemberInput};
+use crate::profile_versions::AbTestRequest;
+use crate::character_card::CardImportRequest;
+use crate::character_sources::{self, GenerateFromSourcesRequest};
 use crate::db::Database;
+use crate::orchestrator::Orchestrator;
+use crate::summary_memory::{SummaryMemoryConfig, SummaryScope};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Database,
+    /// Run control is persisted in SQLite, so this instance can drive runs started from the UI.
+    pub orchestrator: Orchestrator,
 }
 
 pub async fn run_http_server(db: Database, port: u16) {
-    let state = AppState { db };
+    let state = AppState { orchestrator: Orchestrator::new(db.clone()), db };
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -45,21 +59,57 @@ pub async fn run_http_server(db: Database, port: u16) {
         .route("/api/providers/:id/models", get(list_provider_models))
         // Profiles
         .route("/api/profiles", get(list_profiles).post(create_profile))
+        .route("/api/profiles/import-card", post(import_profile_card))
+        .route("/api/profiles/generate-character", post(generate_character_from_sources))
         .route("/api/profiles/:id", get(get_profile).put(update_profile))
+        .route("/api/profiles/:id/card", get(export_profile_card))
+        .route("/api/profiles/:id/character/refresh", post(refresh_profile_character))
+        .route("/api/profiles/:id/versions", get(list_profile_versions))
+        .route("/api/profiles/:id/versions/diff", get(diff_profile_versions))
+        .route("/api/profiles/:id/rollback", post(rollback_profile))
+        .route("/api/profiles/:id/ab-tests", get(list_profile_ab_tests).post(run_profile_ab_test))
         // Projects
         .route("/api/projects", get(list_projects))
         // Sessions
         .route("/api/sessions", get(list_sessions).post(create_session))
+        .route("/api/sessions/import", post(import_session))
         .route("/api/sessions/:id", delete(delete_session))
+        .route("/api/sessions/:id/export", get(export_session))
         .route("/api/sessions/:id/run", get(get_session_run))
+        .route("/api/sessions/:id/report", get(export_session_report))
+        // Run control (persistent run state)
+        .route("/api/runs/interrupted", get(list_interrupted_runs))
+        .route("/api/runs/:id/state", get(get_run_state))
+        .route("/api/runs/:id/pause", post(pause_run))
+        .route("/api/runs/:id/resume", post(resume_run))
+        .route("/api/runs/:id/cancel", post(cancel_run))
         // Chat
         .route("/api/chat/:profile_id", post(chat_with_profile))
         .route("/api/chat/:profile_id/improve", post(improve_with_cloud))
         .route("/api/chat/:profile_id/messages", get(load_chat_messages).post(insert_chat_message).delete(clear_chat_messages))
         .route("/api/chat/:profile_id/conversations", get(list_profile_conversations).post(create_profile_conversation))
+        .route("/api/chat/:profile_id/report", get(export_conversation_report))
+        .route("/api/chat/:profile_id/branches", get(list_chat_branches))
+        .route("/api/chat/:profile_id/active-message", put(switch_default_chat_branch))
         .route("/api/chat/conversations/:conversation_id", delete(delete_profile_conversation))
         .route("/api/chat/conversations/:conversation_id/messages", delete(clear_conversation_messages))
+        .route("/api/chat/conversations/:conversation_id/active-message", put(switch_chat_branch))
         .route("/api/chat/messages/:id", put(update_chat_message))
+        .route("/api/group-chats", get(list_group_conversations).post(create_group_conversation))
+        .route("/api/group-chats/:id", put(update_group_conversation).delete(delete_group_conversation))
+        .route("/api/group-chats/:id/messages", get(load_group_messages).post(send_group_message))
+        .route("/api/profiles/:id/memories", get(list_profile_memories).post(add_profile_memory).delete(clear_profile_memories))
+        .route("/api/memories/:id", put(update_profile_memory).delete(delete_profile_memory))
+        // Rolling summary memory (scope: run | conversation | profile)
+        .route("/api/summaries/:scope/:scope_id", get(get_conversation_summary).put(update_conversation_summary).delete(clear_conversation_summary))
+        .route("/api/summaries/:scope/:scope_id/refresh", post(refresh_conversation_summary))
+        // Session templates (SOPs)
+        .route("/api/sops", get(list_sops).post(save_sop))
+        .route("/api/sops/:id", get(get_sop))
+        .route("/api/sops/:id/instantiate", post(instantiate_sop))
+        .route("/api/sops/by-name/:name", delete(delete_sop))
+        // History search
+        .route("/api/search", get(search_history))
         // Voice (local STT/TTS)
         .route("/api/voice/transcribe", post(voice_transcribe))
         .route("/api/voice/synthesize", post(voice_synthesize))
@@ -97,6 +147,7 @@ async fn root() -> impl IntoResponse {
             },
             "providers": "/api/providers",
             "profiles": "/api/profiles",
+            "runs": "/api/runs/:id/state",
             "chat": "/api/chat/:profile_id"
         },
         "docs": "Use /api/health to check server status"
@@ -444,6 +495,54 @@ async fn get_session_run(
     }
 }
 
+// Run control
+async fn list_interrupted_runs(State(state): State<AppState>) -> impl IntoResponse {
+    match commands::list_interrupted_runs_impl(&state.db).await {
+        Ok(runs) => (StatusCode::OK, Json(runs)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn get_run_state(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match commands::get_run_state_impl(&state.db, &id).await {
+        Ok(run) => (StatusCode::OK, Json(run)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn pause_run(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match commands::pause_run_impl(&state.db, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::CONFLICT, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn resume_run(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match commands::resume_run_impl(&state.db, &state.orchestrator, &id).await {
+        Ok(resume_point) => (StatusCode::OK, Json(resume_point)).into_response(),
+        Err(e) => (StatusCode::CONFLICT, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn cancel_run(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match commands::cancel_run_impl(&state.db, &state.orchestrator, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::CONFLICT, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
 // Chat
 async fn chat_with_profile(
     State(state): State<AppState>,
@@ -489,6 +588,43 @@ async fn load_chat_messages(
     }
 }
 
+async fn list_chat_branches(
+    State(state): State<AppState>,
+    axum::extract::Path(profile_id): axum::extract::Path<String>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let conversation_id = params.get("conversation_id").cloned();
+    match commands_chat::list_chat_branches_impl(&state.db, profile_id, conversation_id).await {
+        Ok(points) => Json(serde_json::json!(points)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn switch_chat_branch(
+    State(state): State<AppState>,
+    axum::extract::Path(conversation_id): axum::extract::Path<String>,
+    Json(req): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let message_id = req.get("message_id").and_then(|v| v.as_str()).unwrap_or("");
+    match commands_chat::switch_chat_branch_impl(&state.db, Some(conversation_id), message_id.to_string()).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+/// Branch selection of a profile's default chat; the message identifies the profile
+async fn switch_default_chat_branch(
+    State(state): State<AppState>,
+    axum::extract::Path(_profile_id): axum::extract::Path<String>,
+    Json(req): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let message_id = req.get("message_id").and_then(|v| v.as_str()).unwrap_or("");
+    match commands_chat::switch_chat_branch_impl(&state.db, None, message_id.to_string()).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
 async fn list_profile_conversations(State(state): State<AppState>, axum::extract::Path(profile_id): axum::extract::Path<String>) -> impl IntoResponse {
     match commands_chat::list_profile_conversations_impl(&state.db, profile_id).await {
         Ok(list) => (StatusCode::OK, Json(list)).into_response(),
@@ -522,6 +658,184 @@ async fn clear_conversation_messages(State(state): State<AppState>, axum::extrac
     }
 }
 
+async fn get_conversation_summary(
+    State(state): State<AppState>,
+    axum::extract::Path((scope, scope_id)): axum::extract::Path<(SummaryScope, String)>,
+) -> impl IntoResponse {
+    match commands_chat::get_conversation_summary_impl(&state.db, scope, scope_id).await {
+        Ok(summary) => Json(serde_json::json!(summary)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn update_conversation_summary(
+    State(state): State<AppState>,
+    axum::extract::Path((scope, scope_id)): axum::extract::Path<(SummaryScope, String)>,
+    Json(req): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let summary_text = req.get("summary_text").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    match commands_chat::update_conversation_summary_impl(&state.db, scope, scope_id, summary_text).await {
+        Ok(summary) => Json(serde_json::json!(summary)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn refresh_conversation_summary(
+    State(state): State<AppState>,
+    axum::extract::Path((scope, scope_id)): axum::extract::Path<(SummaryScope, String)>,
+    Json(config): Json<Option<SummaryMemoryConfig>>,
+) -> impl IntoResponse {
+    match commands_chat::refresh_conversation_summary_impl(&state.db, scope, scope_id, config).await {
+        Ok(summary) => Json(serde_json::json!(summary)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn clear_conversation_summary(
+    State(state): State<AppState>,
+    axum::extract::Path((scope, scope_id)): axum::extract::Path<(SummaryScope, String)>,
+) -> impl IntoResponse {
+    match commands_chat::clear_conversation_summary_impl(&state.db, scope, scope_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn list_sops(
+    State(state): State<AppState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    match commands_sops::list_sops_impl(&state.db, params.get("project_id").cloned()) {
+        Ok(sops) => Json(serde_json::json!(sops)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn save_sop(
+    State(state): State<AppState>,
+    Json(req): Json<SaveSopRequest>,
+) -> impl IntoResponse {
+    match commands_sops::save_sop_impl(&state.db, req) {
+        Ok(sop) => (StatusCode::CREATED, Json(serde_json::json!(sop))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn get_sop(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match commands_sops::get_sop_impl(&state.db, &id) {
+        Ok(sop) => Json(serde_json::json!(sop)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn instantiate_sop(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(mut req): Json<InstantiateSopRequest>,
+) -> impl IntoResponse {
+    req.sop_id = id;
+    match commands_sops::instantiate_sop_impl(&state.db, req).await {
+        Ok(created) => (StatusCode::CREATED, Json(serde_json::json!(created))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn delete_sop(
+    State(state): State<AppState>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match commands_sops::delete_sop_impl(&state.db, &name) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+/// GET /api/search?q=...&sources=chat,debate&profile_id=&project_id=&date_from=&date_to=&limit=&offset=
+async fn search_history(
+    State(state): State<AppState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let request = SearchRequest {
+        query: params.get("q").cloned().unwrap_or_default(),
+        sources: params
+            .get("sources")
+            .map(|s| s.split(',').filter(|s| !s.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default(),
+        profile_id: params.get("profile_id").cloned(),
+        project_id: params.get("project_id").cloned(),
+        date_from: params.get("date_from").cloned(),
+        date_to: params.get("date_to").cloned(),
+        limit: params.get("limit").and_then(|v| v.parse().ok()),
+        offset: params.get("offset").and_then(|v| v.parse().ok()),
+    };
+    match commands_search::search_history_impl(&state.db, &request) {
+        Ok(hits) => Json(serde_json::json!(hits)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn export_session(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let conn = state.db.get_connection();
+    let export = match conn.lock() {
+        Ok(conn_guard) => session_transfer::export(&conn_guard, &id),
+        Err(e) => Err(format!("Database lock error: {}", e)),
+    };
+    match export {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn import_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let mut options: ImportOptions = match serde_json::from_value(req.get("options").cloned().unwrap_or_default()) {
+        Ok(options) => options,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": format!("Invalid options: {}", e) }))).into_response(),
+    };
+    if let Some(user_id) = headers.get("x-user-id").and_then(|v| v.to_str().ok()) {
+        options.user_id = Some(user_id.to_string());
+    }
+    let content = req.get("content").and_then(|v| v.as_str()).unwrap_or_default();
+    match session_transfer::import(&state.db, content, &options) {
+        Ok(summary) => (StatusCode::CREATED, Json(serde_json::json!(summary))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn export_session_report(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let format = params.get("format").map(String::as_str).unwrap_or("html");
+    match commands_reports::export_session_report_impl(&state.db, &id, format) {
+        Ok(file) => Json(serde_json::json!(file)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn export_conversation_report(
+    State(state): State<AppState>,
+    axum::extract::Path(profile_id): axum::extract::Path<String>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let format = params.get("format").map(String::as_str).unwrap_or("html");
+    let conversation_id = params.get("conversation_id").map(String::as_str);
+    match commands_reports::export_conversation_report_impl(&state.db, &profile_id, conversation_id, format) {
+        Ok(file) => Json(serde_json::json!(file)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
 async fn insert_chat_message(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -535,7 +849,8 @@ async fn insert_chat_message(
         .and_then(|v| v.to_str().ok())
         .map(|s: &str| s.to_string())
         .or_else(|| req.get("user_id").and_then(|v| v.as_str()).map(|s: &str| s.to_string()));
-    match commands_chat::insert_chat_message_impl(&state.db, profile_id, role.to_string(), content.to_string(), user_id).await {
+    let conversation_id = req.get("conversation_id").and_then(|v| v.as_str()).map(|s| s.to_string());
+    match commands_chat::insert_chat_message_impl(&state.db, profile_id, conversation_id, role.to_string(), content.to_string(), user_id).await {
         Ok(id) => (StatusCode::CREATED, Json(serde_json::json!({ "id": id }))).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
     }
@@ -560,6 +875,63 @@ async fn update_chat_message(
     }
 }
 
+#[derive(serde::Deserialize)]
+struct GroupConversationBody {
+    title: Option<String>,
+    members: Option<Vec<GroupMemberInput>>,
+}
+
+async fn list_group_conversations(State(state): State<AppState>) -> impl IntoResponse {
+    match commands_chat::list_group_conversations_impl(&state.db).await {
+        Ok(list) => Json(serde_json::json!(list)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn create_group_conversation(State(state): State<AppState>, Json(req): Json<GroupConversationBody>) -> impl IntoResponse {
+    match commands_chat::create_group_conversation_impl(&state.db, req.title, req.members.unwrap_or_default()).await {
+        Ok(conversation) => (StatusCode::CREATED, Json(serde_json::json!(conversation))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn update_group_conversation(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(req): Json<GroupConversationBody>,
+) -> impl IntoResponse {
+    match commands_chat::update_group_conversation_impl(&state.db, id, req.title, req.members).await {
+        Ok(conversation) => Json(serde_json::json!(conversation)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn delete_group_conversation(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_chat::delete_group_conversation_impl(&state.db, id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn load_group_messages(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_chat::load_group_messages_impl(&state.db, id).await {
+        Ok(messages) => Json(serde_json::json!(messages)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn send_group_message(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(mut req): Json<GroupChatRequest>,
+) -> impl IntoResponse {
+    req.conversation_id = id;
+    match commands_chat::send_group_message_impl(&state.db, req).await {
+        Ok(turn) => Json(serde_json::json!(turn)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
 // Voice handlers (local STT/TTS)
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -588,3 +960,175 @@ async fn voice_synthesize(Json(req): Json<VoiceSynthesizeRequest>) -> impl IntoR
         Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
     }
 }
+
+// Profile versions
+
+async fn list_profile_versions(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_profile::list_profile_versions_impl(&state.db, id).await {
+        Ok(versions) => Json(serde_json::json!(versions)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct VersionDiffQuery {
+    from: String,
+    to: String,
+}
+
+async fn diff_profile_versions(State(state): State<AppState>, Query(query): Query<VersionDiffQuery>) -> impl IntoResponse {
+    match commands_profile::diff_profile_versions_impl(&state.db, query.from, query.to).await {
+        Ok(diff) => Json(serde_json::json!(diff)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RollbackBody {
+    version_id: String,
+}
+
+async fn rollback_profile(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(req): Json<RollbackBody>,
+) -> impl IntoResponse {
+    match commands_profile::rollback_profile_impl(&state.db, id, req.version_id).await {
+        Ok(version) => Json(serde_json::json!(version)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn list_profile_ab_tests(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_profile::list_profile_ab_tests_impl(&state.db, id).await {
+        Ok(reports) => Json(serde_json::json!(reports)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn run_profile_ab_test(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(mut req): Json<AbTestRequest>,
+) -> impl IntoResponse {
+    req.profile_id = id;
+    match commands_profile::run_profile_ab_test_impl(&state.db, req).await {
+        Ok(report) => Json(serde_json::json!(report)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+// Character cards
+
+async fn export_profile_card(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> impl IntoResponse {
+    let format = params.get("format").cloned().unwrap_or_else(|| "json".to_string());
+    match commands_profile::export_profile_card_impl(&state.db, id, format).await {
+        Ok(file) => Json(serde_json::json!(file)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn import_profile_card(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let user_id: Option<String> = headers
+        .get("x-user-id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s: &str| s.to_string())
+        .or_else(|| req.get("user_id").and_then(|v| v.as_str()).map(|s: &str| s.to_string()));
+    let request: CardImportRequest = match serde_json::from_value(req) {
+        Ok(r) => r,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    };
+    match commands_profile::import_profile_card_impl(&state.db, request, user_id).await {
+        Ok(result) => (StatusCode::CREATED, Json(serde_json::json!(result))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+// Character generation from sources
+
+async fn generate_character_from_sources(State(state): State<AppState>, Json(req): Json<GenerateFromSourcesRequest>) -> impl IntoResponse {
+    if let Err(e) = character_sources::reject_local_documents(&req.sources) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response();
+    }
+    match commands_profile::generate_character_from_sources_impl(&state.db, req, None).await {
+        Ok(generated) => Json(serde_json::json!(generated)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RefreshCharacterBody {
+    #[serde(default)]
+    force: bool,
+}
+
+async fn refresh_profile_character(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(req): Json<RefreshCharacterBody>,
+) -> impl IntoResponse {
+    match commands_profile::refresh_profile_character_impl(&state.db, id, req.force, false).await {
+        Ok(result) => Json(serde_json::json!(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+// Profile memory
+
+async fn list_profile_memories(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_chat::list_profile_memories_impl(&state.db, id).await {
+        Ok(memories) => Json(serde_json::json!(memories)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct MemoryBody {
+    content: String,
+    kind: Option<String>,
+}
+
+async fn add_profile_memory(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(req): Json<MemoryBody>,
+) -> impl IntoResponse {
+    let kind = req.kind.unwrap_or_else(|| "fact".to_string());
+    match commands_chat::add_profile_memory_impl(&state.db, id, kind, req.content).await {
+        Ok(memory) => (StatusCode::CREATED, Json(serde_json::json!(memory))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn update_profile_memory(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(req): Json<MemoryBody>,
+) -> impl IntoResponse {
+    match commands_chat::update_profile_memory_impl(&state.db, id, req.content, req.kind).await {
+        Ok(memory) => Json(serde_json::json!(memory)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn delete_profile_memory(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_chat::delete_profile_memory_impl(&state.db, id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
+
+async fn clear_profile_memories(State(state): State<AppState>, axum::extract::Path(id): axum::extract::Path<String>) -> impl IntoResponse {
+    match commands_chat::clear_profile_memories_impl(&state.db, id).await {
+        Ok(count) => Json(serde_json::json!({ "deleted": count })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response(),
+    }
+}
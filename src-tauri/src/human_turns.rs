@@ -0,0 +1,265 @@
+// Human seats in debates.
+// A human seat appears in `speaking_order` as "human:<seat id>". When its turn comes the
+// orchestrator opens a row in `debate_human_turns` and waits; the Debate Room answers it through
+// `submit_human_turn` (which writes the message, attributed to the seat's role) or skips it.
+// Unanswered turns time out after the configured number of seconds.
+
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const HUMAN_SEAT_PREFIX: &str = "human:";
+
+/// A named human participant, e.g. role "Product Owner".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HumanSeat {
+    pub id: String,
+    pub role: String,
+}
+
+impl HumanSeat {
+    pub fn speaking_order_key(&self) -> String {
+        format!("{}{}", HUMAN_SEAT_PREFIX, self.id)
+    }
+}
+
+/// Seat id of a speaking_order entry, if it is a human seat.
+pub fn seat_id(entry: &str) -> Option<&str> {
+    entry.strip_prefix(HUMAN_SEAT_PREFIX)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnStatus {
+    Waiting,
+    Answered,
+    Skipped,
+    TimedOut,
+    Cancelled,
+}
+
+impl TurnStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TurnStatus::Waiting => "waiting",
+            TurnStatus::Answered => "answered",
+            TurnStatus::Skipped => "skipped",
+            TurnStatus::TimedOut => "timed_out",
+            TurnStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "answered" => TurnStatus::Answered,
+            "skipped" => TurnStatus::Skipped,
+            "timed_out" => TurnStatus::TimedOut,
+            "cancelled" => TurnStatus::Cancelled,
+            _ => TurnStatus::Waiting,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HumanTurn {
+    pub id: String,
+    pub run_id: String,
+    pub seat_id: String,
+    pub role: String,
+    pub round_index: i32,
+    pub turn_index: i32,
+    pub status: String,
+    pub message_id: Option<String>,
+    pub requested_at: String,
+    /// None when the turn waits until answered or skipped.
+    pub expires_at: Option<String>,
+}
+
+fn map_turn(row: &rusqlite::Row) -> Result<HumanTurn, rusqlite::Error> {
+    Ok(HumanTurn {
+        id: row.get(0)?,
+        run_id: row.get(1)?,
+        seat_id: row.get(2)?,
+        role: row.get(3)?,
+        round_index: row.get(4)?,
+        turn_index: row.get(5)?,
+        status: row.get(6)?,
+        message_id: row.get(7)?,
+        requested_at: row.get(8)?,
+        expires_at: row.get(9)?,
+    })
+}
+
+const TURN_COLUMNS: &str =
+    "id, run_id, seat_id, role, round_index, turn_index, status, message_id, requested_at, expires_at";
+
+/// Open (or reopen after a restart) the turn of `seat` in `round_index`.
+pub fn request_turn(
+    db: &Database,
+    run_id: &str,
+    seat: &HumanSeat,
+    round_index: i32,
+    turn_index: i32,
+    timeout_secs: u64,
+) -> Result<HumanTurn, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let existing = conn_guard.query_row(
+        &format!(
+            "SELECT {} FROM debate_human_turns WHERE run_id = ?1 AND seat_id = ?2 AND round_index = ?3",
+            TURN_COLUMNS
+        ),
+        rusqlite::params![run_id, seat.id, round_index],
+        map_turn,
+    );
+    match existing {
+        Ok(turn) => return Ok(turn),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {}
+        Err(e) => return Err(format!("Database error: {}", e)),
+    }
+
+    let now = chrono::Utc::now();
+    let turn = HumanTurn {
+        id: Uuid::new_v4().to_string(),
+        run_id: run_id.to_string(),
+        seat_id: seat.id.clone(),
+        role: seat.role.clone(),
+        round_index,
+        turn_index,
+        status: TurnStatus::Waiting.as_str().to_string(),
+        message_id: None,
+        requested_at: now.to_rfc3339(),
+        expires_at: (timeout_secs > 0).then(|| (now + chrono::Duration::seconds(timeout_secs as i64)).to_rfc3339()),
+    };
+    conn_guard
+        .execute(
+            &format!("INSERT INTO debate_human_turns ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", TURN_COLUMNS),
+            rusqlite::params![
+                turn.id,
+                turn.run_id,
+                turn.seat_id,
+                turn.role,
+                turn.round_index,
+                turn.turn_index,
+                turn.status,
+                turn.message_id,
+                turn.requested_at,
+                turn.expires_at
+            ],
+        )
+        .map_err(|e| format!("Failed to open human turn: {}", e))?;
+    Ok(turn)
+}
+
+pub fn get_turn(db: &Database, turn_id: &str) -> Result<HumanTurn, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .query_row(
+            &format!("SELECT {} FROM debate_human_turns WHERE id = ?1", TURN_COLUMNS),
+            [turn_id],
+            map_turn,
+        )
+        .map_err(|e| format!("Human turn not found: {}", e))
+}
+
+/// Close a waiting turn without a message (skip, timeout or cancel). No-op if already resolved.
+pub fn close_turn(db: &Database, turn_id: &str, status: TurnStatus) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute(
+            "UPDATE debate_human_turns SET status = ?1, resolved_at = ?2 WHERE id = ?3 AND status = 'waiting'",
+            rusqlite::params![status.as_str(), chrono::Utc::now().to_rfc3339(), turn_id],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Answer a waiting turn: store the message attributed to the seat's role and close the turn.
+pub fn submit_turn(db: &Database, turn_id: &str, text: &str) -> Result<String, String> {
+    let conn = db.get_connection();
+    let mut conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let turn = conn_guard
+        .query_row(
+            &format!("SELECT {} FROM debate_human_turns WHERE id = ?1", TURN_COLUMNS),
+            [turn_id],
+            map_turn,
+        )
+        .map_err(|e| format!("Human turn not found: {}", e))?;
+    if TurnStatus::parse(&turn.status) != TurnStatus::Waiting {
+        return Err(format!("This turn is already {}", turn.status.replace('_', " ")));
+    }
+
+    let message_id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    let tx = conn_guard.transaction().map_err(|e| format!("Database error: {}", e))?;
+    tx.execute(
+        "INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text, created_at, author_label) VALUES (?1, ?2, 'user', NULL, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![message_id, turn.run_id, turn.round_index, turn.turn_index, text, now, turn.role],
+    )
+    .map_err(|e| format!("Failed to save message: {}", e))?;
+    tx.execute(
+        "UPDATE debate_human_turns SET status = 'answered', message_id = ?1, resolved_at = ?2 WHERE id = ?3",
+        rusqlite::params![message_id, now, turn_id],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+    tx.commit().map_err(|e| format!("Database error: {}", e))?;
+    Ok(message_id)
+}
+
+pub fn pending_turns(db: &Database, run_id: &str) -> Result<Vec<HumanTurn>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare(&format!(
+            "SELECT {} FROM debate_human_turns WHERE run_id = ?1 AND status = 'waiting' ORDER BY round_index, turn_index",
+            TURN_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+    let turns = stmt
+        .query_map([run_id], map_turn)
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    Ok(turns)
+}
+
+/// Resolved human turns as (round_index, speaking_order key), for skipping them on resume.
+pub fn resolved_seats(db: &Database, run_id: &str) -> Result<Vec<(i32, String)>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare("SELECT round_index, seat_id FROM debate_human_turns WHERE run_id = ?1 AND status != 'waiting'")
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok((row.get::<_, i32>(0)?, format!("{}{}", HUMAN_SEAT_PREFIX, row.get::<_, String>(1)?)))
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    Ok(rows)
+}
+
+/// Whether a waiting turn has passed its deadline.
+pub fn is_expired(turn: &HumanTurn) -> bool {
+    turn.expires_at
+        .as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|deadline| chrono::Utc::now() > deadline)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seat_keys_round_trip() {
+        let seat = HumanSeat { id: "po".to_string(), role: "Product Owner".to_string() };
+        assert_eq!(seat.speaking_order_key(), "human:po");
+        assert_eq!(seat_id(&seat.speaking_order_key()), Some("po"));
+        assert_eq!(seat_id("3f2a-profile-id"), None);
+        assert_eq!(TurnStatus::parse(TurnStatus::TimedOut.as_str()), TurnStatus::TimedOut);
+    }
+}
@@ -5,6 +5,7 @@ mod keychain;
 mod providers;
 mod provider_resolver;
 mod prompt_transform;
+mod prompt_pipeline;
 mod rag;
 mod cache;
 mod types;
@@ -27,23 +28,45 @@ mod commands_privacy;
 mod commands_settings;
 mod commands_workspace;
 mod commands_voice;
+mod commands_sops;
 mod token_usage;
 mod voice;
 mod training_ingest;
 mod web_search;
 mod orchestrator;
+mod tournament;
+mod consensus;
+mod argument_graph;
+mod run_state;
+mod summary_memory;
+mod chat_branches;
+mod group_chat;
+mod profile_versions;
+mod character_card;
+mod character_sources;
+mod profile_memory;
+mod sops;
+mod human_turns;
+mod session_transfer;
+mod search;
+mod commands_search;
+mod report;
+mod commands_reports;
 mod debate_orchestrator;
 mod native_agent;
 mod privacy;
 mod tools;
 mod cline;
 mod commands_cline;
+mod mcp;
+mod commands_mcp;
 pub mod http_server;
 
 // Re-export necessary items for external binary
 pub use db::Database;
 pub use providers::get_adapter;
 pub use types::{PromptPacket, ProviderAccount, NormalizedResponse};
+pub use mcp::server::run_stdio_server as run_mcp_server;
 
 use tauri::Manager;
 use std::path::PathBuf;
@@ -85,7 +108,19 @@ pub fn run() {
             
             let db = db::Database::new(db_path.clone())
                 .expect("Failed to initialize database");
+
+            // Startup recovery: runs left running/paused by a quit or crash have no worker anymore
+            match run_state::recover_orphaned_runs(&db) {
+                Ok(recovered) if !recovered.is_empty() => {
+                    eprintln!("Marked {} orphaned run(s) as interrupted: {:?}", recovered.len(), recovered);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Run recovery failed: {}", e),
+            }
             
+            // External MCP servers are connected lazily, on first use by the agent
+            mcp::configure(&commands_settings::load_settings_sync(&db).mcp_servers);
+
             let orchestrator = orchestrator::Orchestrator::new(db.clone());
             
             // Store for training processes (model_id -> Process ID as string for cancellation)
@@ -131,10 +166,23 @@ pub fn run() {
             commands::get_session,
             commands::get_session_run,
             commands::delete_session,
+            commands_sops::list_sops,
+            commands_sops::list_sop_versions,
+            commands_sops::get_sop,
+            commands_sops::save_sop,
+            commands_sops::delete_sop,
+            commands_sops::instantiate_sop,
+            commands_sops::export_sop,
+            commands_sops::import_sop,
             commands::start_run,
             commands::get_run_status,
             commands::get_run_results,
+            commands::get_run_syntheses,
             commands::cancel_run,
+            commands::pause_run,
+            commands::resume_run,
+            commands::list_interrupted_runs,
+            commands::get_run_state,
             commands::cancel_run_result,
             commands::delete_run_result,
             commands::rerun_single_agent,
@@ -144,12 +192,26 @@ pub fn run() {
             commands_debate::pause_debate,
             commands_debate::resume_debate,
             commands_debate::cancel_debate,
+            commands_debate::get_pending_human_turns,
+            commands_debate::submit_human_turn,
+            commands_debate::skip_human_turn,
             commands_debate::delete_debate_message,
             commands_debate::add_user_message,
             commands_debate::continue_debate,
             commands_debate::export_session_markdown,
             commands_debate::export_session_json,
+            commands_debate::import_session_json,
+            commands_reports::export_session_report,
+            commands_reports::export_conversation_report,
+            commands_search::search_history,
+            commands_debate::get_debate_consensus,
+            commands_debate::generate_debate_verdict,
+            commands_debate::extract_argument_graph,
+            commands_debate::get_argument_graph,
+            commands_debate::export_argument_graph,
             commands::generate_comparison_table,
+            commands::run_tournament,
+            commands::get_run_evaluations,
             commands::store_api_key,
             commands::retrieve_api_key,
             commands::delete_api_key,
@@ -162,16 +224,42 @@ pub fn run() {
             commands_profile::generate_character_from_url,
             commands_profile::get_latest_profile,
             commands_profile::cancel_character_generation,
+            commands_profile::generate_character_from_sources,
+            commands_profile::refresh_profile_character,
+            commands_profile::list_profile_versions,
+            commands_profile::diff_profile_versions,
+            commands_profile::rollback_profile,
+            commands_profile::run_profile_ab_test,
+            commands_profile::list_profile_ab_tests,
+            commands_profile::export_profile_card,
+            commands_profile::import_profile_card,
             commands_chat::chat_with_profile,
             commands_chat::improve_response_with_cloud,
             commands_chat::load_chat_messages,
             commands_chat::insert_chat_message,
             commands_chat::update_chat_message_content,
+            commands_chat::list_chat_branches,
+            commands_chat::switch_chat_branch,
+            commands_chat::create_group_conversation,
+            commands_chat::list_group_conversations,
+            commands_chat::update_group_conversation,
+            commands_chat::delete_group_conversation,
+            commands_chat::load_group_messages,
+            commands_chat::send_group_message,
+            commands_chat::list_profile_memories,
+            commands_chat::add_profile_memory,
+            commands_chat::update_profile_memory,
+            commands_chat::delete_profile_memory,
+            commands_chat::clear_profile_memories,
             commands_chat::clear_chat_messages,
             commands_chat::list_profile_conversations,
             commands_chat::create_profile_conversation,
             commands_chat::delete_profile_conversation,
             commands_chat::clear_conversation_messages,
+            commands_chat::get_conversation_summary,
+            commands_chat::update_conversation_summary,
+            commands_chat::refresh_conversation_summary,
+            commands_chat::clear_conversation_summary,
             commands_chat::export_chat_messages_to_training,
             commands_coder::export_coder_chats_to_training,
             commands_web::search_web,
@@ -280,6 +368,7 @@ pub fn run() {
             commands_settings::get_default_global_prompt_path,
             commands_settings::update_global_system_prompt_file,
             commands_settings::read_global_prompt_file,
+            commands_settings::list_prompt_middleware,
             commands_settings::update_cache_settings,
             commands_settings::update_training_settings,
             // Voice commands (local STT/TTS)
@@ -290,11 +379,18 @@ pub fn run() {
             // Cline IDE commands
             commands_cline::cline_agent_task,
             commands_cline::cline_approve_tool,
+            commands_cline::cline_cancel_task,
+            commands_mcp::list_mcp_servers,
+            commands_mcp::save_mcp_servers,
+            commands_mcp::test_mcp_server,
+            commands_mcp::list_mcp_tools,
             commands_cline::cline_create_checkpoint,
             commands_cline::cline_restore_checkpoint,
             commands_cline::cline_compare_checkpoint,
+            commands_cline::cline_list_checkpoints,
             commands_cline::cline_get_errors,
             commands_cline::cline_analyze_ast,
+            commands_cline::cline_find_symbol,
             commands_cline::ingest_cline_turn,
         ])
         .run(tauri::generate_context!())
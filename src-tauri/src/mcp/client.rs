@@ -0,0 +1,352 @@
+// MCP client over stdio (newline-delimited JSON-RPC to a child process) or streamable HTTP
+// (JSON-RPC POSTs answered with JSON or an SSE stream, session tracked by `Mcp-Session-Id`).
+
+use crate::mcp::protocol::{self, JsonRpcRequest, McpResource, McpTool, PROTOCOL_VERSION};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "transport", rename_all = "snake_case")]
+pub enum McpTransportConfig {
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+        #[serde(default)]
+        cwd: Option<String>,
+    },
+    Http {
+        url: String,
+        /// e.g. Authorization for hosted servers
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+/// One configured server, stored in `AppSettings::mcp_servers`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpServerConfig {
+    /// Unique name the agent uses in `mcp_call` requests
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub transport: McpTransportConfig,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+type BoxedReader = BufReader<Box<dyn AsyncRead + Send + Unpin>>;
+type BoxedWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+enum Transport {
+    Stdio {
+        reader: BoxedReader,
+        writer: BoxedWriter,
+        /// Killed when the client is dropped
+        _child: Option<tokio::process::Child>,
+    },
+    Http {
+        http: reqwest::Client,
+        url: String,
+        headers: HashMap<String, String>,
+        session_id: Option<String>,
+    },
+}
+
+pub struct McpClient {
+    pub name: String,
+    /// `serverInfo` and capabilities from `initialize`
+    pub server_info: Value,
+    transport: Mutex<Transport>,
+    next_id: AtomicU64,
+    timeout: Duration,
+}
+
+impl McpClient {
+    /// Start (stdio) or reach (HTTP) the server and run the `initialize` handshake.
+    pub async fn connect(config: &McpServerConfig) -> Result<Self, String> {
+        let transport = match &config.transport {
+            McpTransportConfig::Stdio { command, args, env, cwd } => {
+                let mut cmd = tokio::process::Command::new(command);
+                cmd.args(args)
+                    .envs(env)
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::inherit())
+                    .kill_on_drop(true);
+                if let Some(cwd) = cwd.as_ref().filter(|c| !c.trim().is_empty()) {
+                    cmd.current_dir(cwd);
+                }
+                let mut child = cmd
+                    .spawn()
+                    .map_err(|e| format!("Failed to start MCP server '{}' ({}): {}", config.name, command, e))?;
+                let stdin = child.stdin.take().ok_or("MCP server stdin unavailable")?;
+                let stdout = child.stdout.take().ok_or("MCP server stdout unavailable")?;
+                Transport::Stdio {
+                    reader: BufReader::new(Box::new(stdout)),
+                    writer: Box::new(stdin),
+                    _child: Some(child),
+                }
+            }
+            McpTransportConfig::Http { url, headers } => Transport::Http {
+                http: reqwest::Client::new(),
+                url: url.clone(),
+                headers: headers.clone(),
+                session_id: None,
+            },
+        };
+        let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        Self::initialize(config.name.clone(), transport, timeout).await
+    }
+
+    /// Client over an already-open byte stream pair speaking the stdio framing
+    #[cfg(test)]
+    pub(crate) async fn from_streams(
+        name: &str,
+        reader: Box<dyn AsyncRead + Send + Unpin>,
+        writer: BoxedWriter,
+    ) -> Result<Self, String> {
+        let transport = Transport::Stdio { reader: BufReader::new(reader), writer, _child: None };
+        Self::initialize(name.to_string(), transport, Duration::from_secs(DEFAULT_TIMEOUT_SECS)).await
+    }
+
+    async fn initialize(name: String, transport: Transport, timeout: Duration) -> Result<Self, String> {
+        let mut client = McpClient {
+            name,
+            server_info: Value::Null,
+            transport: Mutex::new(transport),
+            next_id: AtomicU64::new(1),
+            timeout,
+        };
+        let result = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "panther", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await?;
+        client.notify("notifications/initialized", None).await?;
+        client.server_info = result;
+        Ok(client)
+    }
+
+    /// Send a request and wait for its response (bounded by the server timeout)
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let message = JsonRpcRequest::new(id, method, Some(params));
+        let response = tokio::time::timeout(self.timeout, self.exchange(&message))
+            .await
+            .map_err(|_| format!("MCP server '{}' did not answer {} within {:?}", self.name, method, self.timeout))??;
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+            return Err(format!("MCP error from '{}' ({}): {}", self.name, method, message));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    pub async fn notify(&self, method: &str, params: Option<Value>) -> Result<(), String> {
+        let message = JsonRpcRequest::notification(method, params);
+        let mut transport = self.transport.lock().await;
+        match &mut *transport {
+            Transport::Stdio { writer, .. } => write_line(writer, &message).await,
+            Transport::Http { http, url, headers, session_id } => {
+                post(http, url, headers, session_id.as_deref(), &message).await.map(|_| ())
+            }
+        }
+    }
+
+    async fn exchange(&self, message: &JsonRpcRequest) -> Result<Value, String> {
+        let id = message.id.clone().unwrap_or(Value::Null);
+        let mut transport = self.transport.lock().await;
+        match &mut *transport {
+            Transport::Stdio { reader, writer, .. } => {
+                write_line(writer, message).await?;
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    let read = reader.read_line(&mut line).await.map_err(|e| format!("MCP read error: {}", e))?;
+                    if read == 0 {
+                        return Err(format!("MCP server '{}' closed the connection", self.name));
+                    }
+                    let Ok(incoming) = serde_json::from_str::<Value>(line.trim()) else {
+                        eprintln!("[MCP] {}: ignoring non-JSON output: {}", self.name, line.trim());
+                        continue;
+                    };
+                    if incoming.get("method").is_some() {
+                        // Server-initiated request or notification: answer pings, decline the rest
+                        if let Some(request_id) = incoming.get("id").cloned() {
+                            let reply = if incoming["method"] == "ping" {
+                                protocol::JsonRpcResponse::success(request_id, json!({}))
+                            } else {
+                                protocol::JsonRpcResponse::failure(request_id, protocol::METHOD_NOT_FOUND, "Not supported by Panther")
+                            };
+                            write_line(writer, &reply).await?;
+                        }
+                        continue;
+                    }
+                    if incoming.get("id") == Some(&id) {
+                        return Ok(incoming);
+                    }
+                }
+            }
+            Transport::Http { http, url, headers, session_id } => {
+                let response = post(http, url, headers, session_id.as_deref(), message).await?;
+                if let Some(session) = response.headers().get("mcp-session-id").and_then(|v| v.to_str().ok()) {
+                    *session_id = Some(session.to_string());
+                }
+                let is_sse = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|ct| ct.starts_with("text/event-stream"));
+                let body = response.text().await.map_err(|e| format!("MCP HTTP read error: {}", e))?;
+                let messages = if is_sse {
+                    protocol::parse_sse_messages(&body)
+                } else {
+                    match serde_json::from_str::<Value>(&body).map_err(|e| format!("Invalid MCP HTTP response: {}", e))? {
+                        Value::Array(batch) => batch,
+                        single => vec![single],
+                    }
+                };
+                messages
+                    .into_iter()
+                    .find(|m| m.get("id") == Some(&id))
+                    .ok_or_else(|| format!("MCP server '{}' sent no response to {}", self.name, message.method))
+            }
+        }
+    }
+
+    /// All tools, following `nextCursor` pagination
+    pub async fn list_tools(&self) -> Result<Vec<McpTool>, String> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = cursor.as_ref().map(|c| json!({ "cursor": c })).unwrap_or_else(|| json!({}));
+            let result = self.request("tools/list", params).await?;
+            let page: Vec<McpTool> = serde_json::from_value(result.get("tools").cloned().unwrap_or(json!([])))
+                .map_err(|e| format!("Invalid tools/list result: {}", e))?;
+            tools.extend(page);
+            cursor = result.get("nextCursor").and_then(|c| c.as_str()).map(|c| c.to_string());
+            if cursor.is_none() {
+                return Ok(tools);
+            }
+        }
+    }
+
+    pub async fn call_tool(&self, tool: &str, arguments: Value) -> Result<Value, String> {
+        let arguments = if arguments.is_null() { json!({}) } else { arguments };
+        self.request("tools/call", json!({ "name": tool, "arguments": arguments })).await
+    }
+
+    pub async fn list_resources(&self) -> Result<Vec<McpResource>, String> {
+        let result = self.request("resources/list", json!({})).await?;
+        serde_json::from_value(result.get("resources").cloned().unwrap_or(json!([])))
+            .map_err(|e| format!("Invalid resources/list result: {}", e))
+    }
+
+    pub async fn read_resource(&self, uri: &str) -> Result<Value, String> {
+        self.request("resources/read", json!({ "uri": uri })).await
+    }
+}
+
+async fn write_line<T: Serialize>(writer: &mut BoxedWriter, message: &T) -> Result<(), String> {
+    let mut line = serde_json::to_string(message).map_err(|e| format!("JSON error: {}", e))?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await.map_err(|e| format!("MCP write error: {}", e))?;
+    writer.flush().await.map_err(|e| format!("MCP write error: {}", e))
+}
+
+async fn post(
+    http: &reqwest::Client,
+    url: &str,
+    headers: &HashMap<String, String>,
+    session_id: Option<&str>,
+    message: &JsonRpcRequest,
+) -> Result<reqwest::Response, String> {
+    let mut request = http
+        .post(url)
+        .header(reqwest::header::ACCEPT, "application/json, text/event-stream")
+        .json(message);
+    for (key, value) in headers {
+        request = request.header(key.as_str(), value.as_str());
+    }
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    let response = request.send().await.map_err(|e| format!("MCP HTTP error: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("MCP HTTP {}: {}", status, body.chars().take(300).collect::<String>()));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal stdio MCP server: one `echo` tool and one text resource
+    async fn echo_server(stream: tokio::io::DuplexStream) {
+        let (read, mut write) = tokio::io::split(stream);
+        let mut lines = BufReader::new(read).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let request: Value = serde_json::from_str(&line).unwrap();
+            let Some(id) = request.get("id").cloned() else { continue };
+            let result = match request["method"].as_str().unwrap() {
+                "initialize" => json!({ "protocolVersion": PROTOCOL_VERSION, "capabilities": { "tools": {} }, "serverInfo": { "name": "echo" } }),
+                "tools/list" => json!({ "tools": [{ "name": "echo", "description": "Echo text", "inputSchema": { "type": "object" } }] }),
+                "tools/call" => json!({ "content": [{ "type": "text", "text": request["params"]["arguments"]["text"] }], "isError": false }),
+                "resources/read" => json!({ "contents": [{ "uri": request["params"]["uri"], "text": "resource body" }] }),
+                _ => {
+                    let reply = protocol::JsonRpcResponse::failure(id, protocol::METHOD_NOT_FOUND, "nope");
+                    write.write_all(format!("{}\n", serde_json::to_string(&reply).unwrap()).as_bytes()).await.unwrap();
+                    continue;
+                }
+            };
+            // A notification before the response must be skipped by the client
+            write.write_all(b"{\"jsonrpc\":\"2.0\",\"method\":\"notifications/message\",\"params\":{}}\n").await.unwrap();
+            let reply = protocol::JsonRpcResponse::success(id, result);
+            write.write_all(format!("{}\n", serde_json::to_string(&reply).unwrap()).as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stdio_client_against_echo_server() {
+        let (client_side, server_side) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(echo_server(server_side));
+        let (read, write) = tokio::io::split(client_side);
+        let client = McpClient::from_streams("echo", Box::new(read), Box::new(write)).await.unwrap();
+        assert_eq!(client.server_info["serverInfo"]["name"], "echo");
+
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "echo");
+
+        let result = client.call_tool("echo", json!({ "text": "hello" })).await.unwrap();
+        assert_eq!(protocol::result_text(&result), "hello");
+        let resource = client.read_resource("panther://notes").await.unwrap();
+        assert_eq!(protocol::result_text(&resource), "resource body");
+        assert!(client.list_resources().await.unwrap_err().contains("nope"));
+
+        let sse = "event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":7,\"result\":{}}\n\n";
+        assert_eq!(protocol::parse_sse_messages(sse)[0]["id"], 7);
+    }
+}
@@ -0,0 +1,100 @@
+// Model Context Protocol support.
+//
+// `client` talks to external MCP servers configured in `AppSettings::mcp_servers`; the registry
+// below keeps one live connection per server (started lazily, replaced when its config changes)
+// so the Cline agent can list and call their tools. `server` is the other direction: Panther's own
+// profiles and knowledge served to external MCP clients (see the `panther-mcp-server` binary).
+
+pub mod client;
+pub mod protocol;
+pub mod server;
+
+pub use client::{McpClient, McpServerConfig};
+
+use protocol::McpTool;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[derive(Default)]
+struct Registry {
+    configs: RwLock<HashMap<String, McpServerConfig>>,
+    /// Live connections with the config they were opened with
+    clients: tokio::sync::Mutex<HashMap<String, (McpServerConfig, Arc<McpClient>)>>,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::default)
+}
+
+/// Replace the configured servers; connections to removed or changed servers are closed on next use.
+pub fn configure(servers: &[McpServerConfig]) {
+    if let Ok(mut configs) = registry().configs.write() {
+        *configs = servers.iter().map(|s| (s.name.clone(), s.clone())).collect();
+    }
+}
+
+pub fn configured_servers() -> Vec<McpServerConfig> {
+    let mut servers: Vec<McpServerConfig> = registry()
+        .configs
+        .read()
+        .map(|c| c.values().cloned().collect())
+        .unwrap_or_default();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    servers
+}
+
+/// Connected client for an enabled server, connecting (or reconnecting after a config change) if needed
+pub async fn client(name: &str) -> Result<Arc<McpClient>, String> {
+    let config = registry()
+        .configs
+        .read()
+        .map_err(|e| format!("MCP registry lock error: {}", e))?
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("No MCP server named '{}' is configured", name))?;
+    if !config.enabled {
+        return Err(format!("MCP server '{}' is disabled", name));
+    }
+
+    let mut clients = registry().clients.lock().await;
+    clients.retain(|n, (c, _)| registry().configs.read().map(|configs| configs.get(n) == Some(c)).unwrap_or(false));
+    if let Some((_, client)) = clients.get(name) {
+        return Ok(client.clone());
+    }
+    let client = Arc::new(McpClient::connect(&config).await?);
+    clients.insert(name.to_string(), (config, client.clone()));
+    Ok(client)
+}
+
+/// Drop a connection (e.g. after a transport error) so the next call reconnects
+pub async fn disconnect(name: &str) {
+    registry().clients.lock().await.remove(name);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogTool {
+    pub server: String,
+    #[serde(flatten)]
+    pub tool: McpTool,
+}
+
+/// Tools of every enabled server. Servers that fail to start are skipped with a warning.
+pub async fn tool_catalog() -> Vec<CatalogTool> {
+    let mut catalog = Vec::new();
+    for server in configured_servers().into_iter().filter(|s| s.enabled) {
+        let tools = match client(&server.name).await {
+            Ok(client) => client.list_tools().await,
+            Err(e) => Err(e),
+        };
+        match tools {
+            Ok(tools) => catalog.extend(tools.into_iter().map(|tool| CatalogTool { server: server.name.clone(), tool })),
+            Err(e) => {
+                eprintln!("[MCP] Skipping server '{}': {}", server.name, e);
+                disconnect(&server.name).await;
+            }
+        }
+    }
+    catalog
+}
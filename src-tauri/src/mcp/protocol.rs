@@ -0,0 +1,131 @@
+// JSON-RPC 2.0 message types and the MCP shapes Panther reads and writes.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Protocol revision sent in `initialize`; servers answer with the revision they speak
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    /// None for notifications
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcRequest {
+    pub fn new(id: u64, method: &str, params: Option<Value>) -> Self {
+        JsonRpcRequest { jsonrpc: "2.0".to_string(), id: Some(json!(id)), method: method.to_string(), params }
+    }
+
+    pub fn notification(method: &str, params: Option<Value>) -> Self {
+        JsonRpcRequest { jsonrpc: "2.0".to_string(), id: None, method: method.to_string(), params }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        JsonRpcResponse { jsonrpc: "2.0".to_string(), id, result: Some(result), error: None }
+    }
+
+    pub fn failure(id: Value, code: i64, message: impl Into<String>) -> Self {
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError { code, message: message.into(), data: None }),
+        }
+    }
+}
+
+/// A tool advertised by `tools/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "inputSchema", default)]
+    pub input_schema: Value,
+}
+
+/// A resource advertised by `resources/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpResource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// `tools/call` result with a single text part. Tool failures are reported this way
+/// (`isError`), not as JSON-RPC errors, so the calling model can read them.
+pub fn text_tool_result(text: impl Into<String>, is_error: bool) -> Value {
+    json!({ "content": [{ "type": "text", "text": text.into() }], "isError": is_error })
+}
+
+/// Text parts of a `tools/call` result or `resources/read` contents, joined by newlines.
+/// Non-text parts are summarized by type so the agent knows they exist.
+pub fn result_text(result: &Value) -> String {
+    let items = result
+        .get("content")
+        .or_else(|| result.get("contents"))
+        .and_then(|c| c.as_array())
+        .cloned()
+        .unwrap_or_default();
+    items
+        .iter()
+        .map(|item| match item.get("text").and_then(|t| t.as_str()) {
+            Some(text) => text.to_string(),
+            None => format!("[{} content]", item.get("type").and_then(|t| t.as_str()).unwrap_or("binary")),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// JSON-RPC messages in a `text/event-stream` body (streamable HTTP transport)
+pub fn parse_sse_messages(body: &str) -> Vec<Value> {
+    let mut messages = Vec::new();
+    let mut data = String::new();
+    for line in body.lines().chain(std::iter::once("")) {
+        if let Some(rest) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+        } else if line.trim().is_empty() && !data.is_empty() {
+            if let Ok(value) = serde_json::from_str(&data) {
+                messages.push(value);
+            }
+            data.clear();
+        }
+    }
+    messages
+}
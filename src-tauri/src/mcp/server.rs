@@ -0,0 +1,507 @@
+// Panther as an MCP server: profiles, brainstorm runs, project RAG, sessions and training data
+// exposed as tools and resources over newline-delimited JSON-RPC (stdio transport).
+//
+// Handlers reuse the same `*_impl` functions as the HTTP server, so behaviour matches the app.
+
+use super::protocol::{self, JsonRpcRequest, JsonRpcResponse, PROTOCOL_VERSION};
+use crate::commands::{self, CreateSessionRequest};
+use crate::commands_chat::{self, ChatRequest};
+use crate::commands_training;
+use crate::db::Database;
+use crate::orchestrator::Orchestrator;
+use crate::rag;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+
+/// Longest `start_brainstorm` waits for a run before returning its id instead
+const MAX_WAIT_SECS: u64 = 900;
+
+#[derive(Clone)]
+pub struct McpServer {
+    db: Database,
+    orchestrator: Orchestrator,
+    /// Profiles and projects owned by this user are visible besides shared ones
+    user_id: Option<String>,
+}
+
+impl McpServer {
+    pub fn new(db: Database, user_id: Option<String>) -> Self {
+        McpServer { orchestrator: Orchestrator::new(db.clone()), db, user_id }
+    }
+
+    /// Serve until the client closes `input`. Requests are handled concurrently so a
+    /// long brainstorm does not block quick lookups; responses carry the request id.
+    pub async fn serve<R, W>(self, input: R, output: W)
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let output = Arc::new(Mutex::new(output));
+        let mut pending = Vec::new();
+        let mut lines = BufReader::new(input).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let request: JsonRpcRequest = match serde_json::from_str(&line) {
+                Ok(request) => request,
+                Err(e) => {
+                    eprintln!("[MCP server] Ignoring malformed message: {}", e);
+                    continue;
+                }
+            };
+            // Notifications (e.g. notifications/initialized) need no reply
+            let Some(id) = request.id.clone() else { continue };
+            let server = self.clone();
+            let output = output.clone();
+            pending.retain(|task: &tokio::task::JoinHandle<()>| !task.is_finished());
+            pending.push(tokio::spawn(async move {
+                let params = request.params.unwrap_or_else(|| json!({}));
+                let response = match server.handle(&request.method, &params).await {
+                    Ok(result) => JsonRpcResponse::success(id, result),
+                    Err((code, message)) => JsonRpcResponse::failure(id, code, message),
+                };
+                let Ok(mut text) = serde_json::to_string(&response) else { return };
+                text.push('\n');
+                let mut output = output.lock().await;
+                if output.write_all(text.as_bytes()).await.is_ok() {
+                    let _ = output.flush().await;
+                }
+            }));
+        }
+        // Answer what is in flight before the process exits
+        for task in pending {
+            let _ = task.await;
+        }
+    }
+
+    async fn handle(&self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": params.get("protocolVersion").and_then(|v| v.as_str()).unwrap_or(PROTOCOL_VERSION),
+                "capabilities": { "tools": {}, "resources": {} },
+                "serverInfo": { "name": "panther", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => {
+                let name = params
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .ok_or((protocol::INVALID_PARAMS, "tools/call needs a tool name".to_string()))?;
+                let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+                Ok(match self.call_tool(name, &arguments).await {
+                    Ok(text) => protocol::text_tool_result(text, false),
+                    Err(e) => protocol::text_tool_result(e, true),
+                })
+            }
+            "resources/list" => self.list_resources().await.map_err(|e| (protocol::INTERNAL_ERROR, e)),
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": [
+                { "uriTemplate": "panther://profiles/{profile_id}", "name": "Profile", "mimeType": "application/json" },
+                { "uriTemplate": "panther://sessions/{session_id}", "name": "Session with its latest run results", "mimeType": "application/json" },
+            ] })),
+            "resources/read" => {
+                let uri = params
+                    .get("uri")
+                    .and_then(|u| u.as_str())
+                    .ok_or((protocol::INVALID_PARAMS, "resources/read needs a uri".to_string()))?;
+                let contents = self.read_resource(uri).await.map_err(|e| (protocol::INVALID_PARAMS, e))?;
+                Ok(json!({ "contents": [{
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": serde_json::to_string_pretty(&contents).unwrap_or_default(),
+                }] }))
+            }
+            _ => Err((protocol::METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        }
+    }
+
+    async fn call_tool(&self, name: &str, args: &Value) -> Result<String, String> {
+        let value = match name {
+            "list_profiles" => Value::Array(self.profiles().await?.iter().map(profile_summary).collect()),
+            "chat_with_profile" => {
+                let profile = self.resolve_profile(required_str(args, "profile")?).await?;
+                let request: ChatRequest = serde_json::from_value(json!({
+                    "profile_id": profile["id"],
+                    "user_message": required_str(args, "message")?,
+                    "conversation_id": args.get("conversation_id"),
+                }))
+                .map_err(|e| format!("Invalid chat request: {}", e))?;
+                // The reply is plain text; return it as-is rather than JSON-quoted
+                return commands_chat::chat_with_profile_impl(&self.db, request).await;
+            }
+            "start_brainstorm" => self.start_brainstorm(args).await?,
+            "get_brainstorm_results" => self.run_report(required_str(args, "run_id")?).await?,
+            "query_project_rag" => {
+                let project_id = required_str(args, "project_id")?;
+                self.ensure_project(project_id).await?;
+                let limit = args.get("limit").and_then(|l| l.as_u64()).unwrap_or(8) as usize;
+                let context = rag::retrieve_context_for_query(&self.db, project_id, required_str(args, "query")?, limit)
+                    .map_err(|e| format!("RAG query failed: {}", e))?;
+                if context.chunks.is_empty() {
+                    return Ok("No matching document chunks in this project.".to_string());
+                }
+                return Ok(context.combined_text);
+            }
+            "list_projects" => Value::Array(commands::list_projects_impl(&self.db, self.user_id.clone()).await?),
+            "list_sessions" => {
+                let mut sessions = self.sessions().await?;
+                if let Some(project_id) = args.get("project_id").and_then(|p| p.as_str()) {
+                    sessions.retain(|s| s["project_id"] == project_id);
+                }
+                sessions.truncate(limit_arg(args));
+                Value::Array(sessions)
+            }
+            "list_training_data" => {
+                let project_id = optional_string(args, "project_id");
+                if let Some(project_id) = &project_id {
+                    self.ensure_project(project_id).await?;
+                }
+                let mut data = self.training_data(project_id, optional_string(args, "local_model_id")).await?;
+                data.truncate(limit_arg(args));
+                Value::Array(data)
+            }
+            _ => return Err(format!("Unknown tool: {}", name)),
+        };
+        serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+    }
+
+    async fn profiles(&self) -> Result<Vec<Value>, String> {
+        commands::list_profiles_impl(&self.db, self.user_id.clone()).await
+    }
+
+    /// Ids of the projects this user may read (own and shared)
+    async fn project_ids(&self) -> Result<Vec<String>, String> {
+        Ok(commands::list_projects_impl(&self.db, self.user_id.clone())
+            .await?
+            .iter()
+            .filter_map(|p| p["id"].as_str().map(String::from))
+            .collect())
+    }
+
+    async fn ensure_project(&self, project_id: &str) -> Result<(), String> {
+        if self.project_ids().await?.iter().any(|id| id == project_id) {
+            Ok(())
+        } else {
+            Err(format!("No project '{}'", project_id))
+        }
+    }
+
+    /// Sessions carry no user; they belong to whoever can read their project
+    async fn sessions(&self) -> Result<Vec<Value>, String> {
+        let project_ids = self.project_ids().await?;
+        let mut sessions = commands::list_sessions_impl(&self.db, self.user_id.clone()).await?;
+        sessions.retain(|s| s["project_id"].as_str().is_some_and(|p| project_ids.iter().any(|id| id == p)));
+        Ok(sessions)
+    }
+
+    async fn training_data(&self, project_id: Option<String>, local_model_id: Option<String>) -> Result<Vec<Value>, String> {
+        let project_ids = self.project_ids().await?;
+        let mut data = commands_training::list_training_data_impl(&self.db, project_id, local_model_id).await?;
+        data.retain(|d| d["project_id"].as_str().is_some_and(|p| project_ids.iter().any(|id| id == p)));
+        Ok(data)
+    }
+
+    /// Profile by id, or by case-insensitive name
+    async fn resolve_profile(&self, key: &str) -> Result<Value, String> {
+        let profiles = self.profiles().await?;
+        profiles
+            .iter()
+            .find(|p| p["id"] == key)
+            .or_else(|| profiles.iter().find(|p| p["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(key))))
+            .cloned()
+            .ok_or_else(|| format!("No profile with id or name '{}'", key))
+    }
+
+    async fn start_brainstorm(&self, args: &Value) -> Result<Value, String> {
+        let question = required_str(args, "question")?;
+        let keys = args
+            .get("profiles")
+            .and_then(|p| p.as_array())
+            .ok_or("start_brainstorm needs a 'profiles' array")?;
+        let mut profile_ids = Vec::new();
+        for key in keys.iter().filter_map(|k| k.as_str()) {
+            profile_ids.push(self.resolve_profile(key).await?["id"].as_str().unwrap_or_default().to_string());
+        }
+        if profile_ids.is_empty() {
+            return Err("Select at least one profile".to_string());
+        }
+        // Same default as the session builder: the most recent project
+        let project_id = match optional_string(args, "project_id") {
+            Some(id) => {
+                self.ensure_project(&id).await?;
+                id
+            }
+            None => commands::list_projects_impl(&self.db, self.user_id.clone())
+                .await?
+                .first()
+                .and_then(|p| p["id"].as_str().map(String::from))
+                .ok_or("No project exists yet; create one in Panther first")?,
+        };
+
+        let request = CreateSessionRequest {
+            project_id,
+            title: optional_string(args, "title").unwrap_or_else(|| question.chars().take(50).collect()),
+            user_question: question.to_string(),
+            mode: "parallel".to_string(),
+            selected_profile_ids: profile_ids,
+            run_settings: None,
+            local_model_id: None,
+        };
+        let run_id = commands::create_session_impl(&self.db, request).await?;
+        commands::start_run_impl(&self.db, &self.orchestrator, run_id.clone()).await?;
+
+        if !args.get("wait").and_then(|w| w.as_bool()).unwrap_or(true) {
+            return Ok(json!({ "run_id": run_id, "status": "running" }));
+        }
+        let wait = args.get("timeout_secs").and_then(|t| t.as_u64()).unwrap_or(300).min(MAX_WAIT_SECS);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(wait);
+        loop {
+            let status = commands::get_run_status_impl(&self.db, &run_id).await?;
+            let running = matches!(status["status"].as_str(), Some("queued" | "running"));
+            if !running || tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        self.run_report(&run_id).await
+    }
+
+    /// Run status with each profile's answer, labelled by profile name
+    async fn run_report(&self, run_id: &str) -> Result<Value, String> {
+        let status = commands::get_run_status_impl(&self.db, run_id).await?;
+        if !self.sessions().await?.iter().any(|s| s["id"] == status["session_id"]) {
+            return Err(format!("No run '{}'", run_id));
+        }
+        let profiles = self.profiles().await?;
+        let results: Vec<Value> = commands::get_run_results_impl(&self.db, run_id)
+            .await?
+            .into_iter()
+            .map(|r| {
+                let name = profiles.iter().find(|p| p["id"] == r["profile_id"]).map(|p| p["name"].clone());
+                json!({
+                    "profile": name.unwrap_or_else(|| r["profile_id"].clone()),
+                    "status": r["status"],
+                    "stage": r["stage"],
+                    "output": r["raw_output_text"],
+                    "error": r["error_message_safe"],
+                })
+            })
+            .collect();
+        Ok(json!({ "run_id": run_id, "status": status["status"], "session_id": status["session_id"], "results": results }))
+    }
+
+    async fn list_resources(&self) -> Result<Value, String> {
+        let mut resources = vec![
+            json!({ "uri": "panther://profiles", "name": "Profiles", "mimeType": "application/json" }),
+            json!({ "uri": "panther://projects", "name": "Projects", "mimeType": "application/json" }),
+            json!({ "uri": "panther://sessions", "name": "Brainstorm sessions", "mimeType": "application/json" }),
+            json!({ "uri": "panther://training-data", "name": "Training data", "mimeType": "application/json" }),
+        ];
+        for profile in self.profiles().await? {
+            resources.push(json!({
+                "uri": format!("panther://profiles/{}", profile["id"].as_str().unwrap_or_default()),
+                "name": format!("Profile: {}", profile["name"].as_str().unwrap_or_default()),
+                "mimeType": "application/json",
+            }));
+        }
+        Ok(json!({ "resources": resources }))
+    }
+
+    async fn read_resource(&self, uri: &str) -> Result<Value, String> {
+        let path = uri.strip_prefix("panther://").ok_or_else(|| format!("Unknown resource: {}", uri))?;
+        match path.split_once('/') {
+            None => match path {
+                "profiles" => Ok(Value::Array(self.profiles().await?.iter().map(profile_summary).collect())),
+                "projects" => Ok(Value::Array(commands::list_projects_impl(&self.db, self.user_id.clone()).await?)),
+                "sessions" => Ok(Value::Array(self.sessions().await?)),
+                "training-data" => Ok(Value::Array(self.training_data(None, None).await?)),
+                _ => Err(format!("Unknown resource: {}", uri)),
+            },
+            Some(("profiles", id)) => {
+                let profiles = self.profiles().await?;
+                profiles.into_iter().find(|p| p["id"] == id).ok_or_else(|| format!("No profile '{}'", id))
+            }
+            Some(("sessions", id)) => {
+                let session = self
+                    .sessions()
+                    .await?
+                    .into_iter()
+                    .find(|s| s["id"] == id)
+                    .ok_or_else(|| format!("No session '{}'", id))?;
+                let run = match commands::get_session_run_impl(&self.db, id).await? {
+                    Some(run) => match run["run_id"].as_str() {
+                        Some(run_id) => self.run_report(run_id).await?,
+                        None => run,
+                    },
+                    None => Value::Null,
+                };
+                Ok(json!({ "session": session, "run": run }))
+            }
+            _ => Err(format!("Unknown resource: {}", uri)),
+        }
+    }
+}
+
+/// Serve MCP on this process's stdin/stdout (stdout carries protocol messages only)
+pub async fn run_stdio_server(db: Database, user_id: Option<String>) {
+    McpServer::new(db, user_id).serve(tokio::io::stdin(), tokio::io::stdout()).await;
+}
+
+/// What an MCP client needs to pick a profile; the full record is the profile resource
+fn profile_summary(profile: &Value) -> Value {
+    let persona: String = profile["persona_prompt"].as_str().unwrap_or_default().chars().take(200).collect();
+    json!({ "id": profile["id"], "name": profile["name"], "model": profile["model_name"], "persona": persona })
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| format!("Missing required argument '{}'", key))
+}
+
+fn optional_string(args: &Value, key: &str) -> Option<String> {
+    args.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()).map(String::from)
+}
+
+fn limit_arg(args: &Value) -> usize {
+    args.get("limit").and_then(|l| l.as_u64()).unwrap_or(50) as usize
+}
+
+fn tool_definitions() -> Value {
+    let object = |properties: Value, required: &[&str]| json!({ "type": "object", "properties": properties, "required": required });
+    json!([
+        {
+            "name": "list_profiles",
+            "description": "List Panther profiles (personas bound to a model) with id, name, model and a persona excerpt.",
+            "inputSchema": object(json!({}), &[]),
+        },
+        {
+            "name": "chat_with_profile",
+            "description": "Send a message to a Panther profile and get its reply. The exchange is stored in the profile's chat history.",
+            "inputSchema": object(json!({
+                "profile": { "type": "string", "description": "Profile id or name" },
+                "message": { "type": "string" },
+                "conversation_id": { "type": "string", "description": "Continue a specific conversation (default: the profile's default one)" },
+            }), &["profile", "message"]),
+        },
+        {
+            "name": "start_brainstorm",
+            "description": "Ask several profiles the same question in parallel. Waits for the answers unless wait is false; then use get_brainstorm_results with the returned run_id.",
+            "inputSchema": object(json!({
+                "question": { "type": "string" },
+                "profiles": { "type": "array", "items": { "type": "string" }, "description": "Profile ids or names" },
+                "project_id": { "type": "string", "description": "Default: the most recent project" },
+                "title": { "type": "string" },
+                "wait": { "type": "boolean", "default": true },
+                "timeout_secs": { "type": "integer", "default": 300, "maximum": MAX_WAIT_SECS },
+            }), &["question", "profiles"]),
+        },
+        {
+            "name": "get_brainstorm_results",
+            "description": "Status and per-profile answers of a brainstorm run.",
+            "inputSchema": object(json!({ "run_id": { "type": "string" } }), &["run_id"]),
+        },
+        {
+            "name": "query_project_rag",
+            "description": "Search a project's knowledge base (ingested document chunks) for passages matching a query.",
+            "inputSchema": object(json!({
+                "project_id": { "type": "string" },
+                "query": { "type": "string" },
+                "limit": { "type": "integer", "default": 8 },
+            }), &["project_id", "query"]),
+        },
+        {
+            "name": "list_projects",
+            "description": "List Panther projects.",
+            "inputSchema": object(json!({}), &[]),
+        },
+        {
+            "name": "list_sessions",
+            "description": "List brainstorm and debate sessions, newest first.",
+            "inputSchema": object(json!({
+                "project_id": { "type": "string" },
+                "limit": { "type": "integer", "default": 50 },
+            }), &[]),
+        },
+        {
+            "name": "list_training_data",
+            "description": "List curated training examples (input/output pairs), newest first.",
+            "inputSchema": object(json!({
+                "project_id": { "type": "string" },
+                "local_model_id": { "type": "string" },
+                "limit": { "type": "integer", "default": 50 },
+            }), &[]),
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::McpClient;
+
+    #[tokio::test]
+    async fn test_server_tools_and_resources() {
+        let path = std::env::temp_dir().join(format!("panther-mcp-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                     VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO document_chunks (id, project_id, source_id, chunk_index, text)
+                     VALUES ('d1', 'pr', 'pricing.md', 0, 'Annual pricing gets a discount'),
+                            ('d2', 'pr', 'team.md', 0, 'The team meets on Mondays');
+                 INSERT INTO users (id, username, email, password_hash) VALUES ('u2', 'bea', 'bea@example.com', 'x');
+                 INSERT INTO projects (id, name, user_id, created_at, updated_at) VALUES ('other', 'O', 'u2', datetime('now'), datetime('now'));
+                 INSERT INTO document_chunks (id, project_id, source_id, chunk_index, text)
+                     VALUES ('d3', 'other', 'secret.md', 0, 'Private pricing notes');
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s2', 'other', 'Private', 'Q', 'parallel');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('r2', 's2', '[]', 'completed', '{}');",
+            )
+            .unwrap();
+        }
+
+        let (client_side, server_side) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_side);
+        tokio::spawn(McpServer::new(db, None).serve(server_read, server_write));
+        let (read, write) = tokio::io::split(client_side);
+        let client = McpClient::from_streams("panther", Box::new(read), Box::new(write)).await.unwrap();
+        assert_eq!(client.server_info["serverInfo"]["name"], "panther");
+
+        let tools = client.list_tools().await.unwrap();
+        assert!(tools.iter().any(|t| t.name == "start_brainstorm"));
+
+        let profiles = client.call_tool("list_profiles", json!({})).await.unwrap();
+        assert!(protocol::result_text(&profiles).contains("\"Ana\""));
+
+        let rag = client.call_tool("query_project_rag", json!({ "project_id": "pr", "query": "pricing discount" })).await.unwrap();
+        let text = protocol::result_text(&rag);
+        assert!(text.contains("pricing.md") && !text.contains("team.md"));
+
+        // Another user's project, its runs and its sessions stay hidden
+        let foreign = client.call_tool("query_project_rag", json!({ "project_id": "other", "query": "pricing" })).await.unwrap();
+        assert_eq!(foreign["isError"], true);
+        let foreign = client.call_tool("get_brainstorm_results", json!({ "run_id": "r2" })).await.unwrap();
+        assert_eq!(foreign["isError"], true);
+        assert!(client.read_resource("panther://sessions/s2").await.is_err());
+
+        // Tool failures come back as isError results, not protocol errors
+        let missing = client.call_tool("chat_with_profile", json!({ "profile": "Bob", "message": "hi" })).await.unwrap();
+        assert_eq!(missing["isError"], true);
+
+        let resource = client.read_resource("panther://profiles/p1").await.unwrap();
+        assert!(protocol::result_text(&resource).contains("You are Ana"));
+        assert!(client.read_resource("panther://nope").await.is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -4,8 +4,13 @@ use crate::db::Database;
 use crate::provider_resolver::complete_resolving_hybrid;
 use crate::types::PromptPacket;
 use crate::rag;
+use crate::run_state::{self, RunSignal, RunStatus};
+use crate::web_search::{NewsResult, WebSearch};
 use anyhow::Result;
+use futures::future::join_all;
+use serde::Deserialize;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{Semaphore, RwLock};
 use uuid::Uuid;
@@ -36,10 +41,9 @@ impl Orchestrator {
         cancelled.insert(result_id.to_string());
     }
 
-    #[allow(dead_code)]
     async fn is_run_cancelled(&self, run_id: &str) -> bool {
         let cancelled = self.cancelled_runs.read().await;
-        cancelled.contains(run_id)
+        cancelled.contains(run_id) || run_state::signal(&self.db, run_id) == RunSignal::Cancel
     }
 
     #[allow(dead_code)]
@@ -84,25 +88,43 @@ impl Orchestrator {
             (session_id, profile_ids, user_question, run_settings)
         };
 
-        // Update run status to running
-        {
-            let conn = self.db.get_connection();
-            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-            conn_guard.execute(
-                "UPDATE runs SET status = 'running' WHERE id = ?1",
-                [&run_id],
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to update run status: {}", e))?;
-        }
+        // Update run status to running (no-op when resuming an already-running run)
+        run_state::transition(&self.db, &run_id, RunStatus::Running, "brainstorm_started")
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        // Follow-up stages (critique / refine / synthesize) from run_settings_json.pipeline
+        let pipeline = match BrainstormPipeline::from_run_settings(&run_settings) {
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                run_state::finish(&self.db, &run_id, RunStatus::Failed).map_err(|e| anyhow::anyhow!(e))?;
+                return Err(anyhow::anyhow!(e));
+            }
+        };
 
         // Load profiles
         let profiles = self.load_profiles(&profile_ids)?;
+        let stage_profiles = profiles.clone();
         
         // Get concurrency limit
         let concurrency = run_settings
             .get("concurrency")
             .and_then(|v| v.as_u64())
             .unwrap_or(3) as usize;
+        // Documents of this project are retrieved as context (set by session templates)
+        let rag_project_id: Option<String> = run_settings
+            .get("rag_project_id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        // Language, tone, word limit and web search of the session template
+        let style = ResponseStyle::from_run_settings(&run_settings);
+        let style_instructions = style.instructions();
+        let mut answer_instructions = style_instructions.clone();
+        if style.web_search {
+            match WebSearch::new().search_recent_news(&user_question, 5).await {
+                Ok(results) => answer_instructions.push_str(&format_news(&results)),
+                Err(e) => eprintln!("Web search failed, answering without it: {}", e),
+            }
+        }
         
         let semaphore = Arc::new(Semaphore::new(concurrency));
         let mut handles = Vec::new();
@@ -116,6 +138,8 @@ impl Orchestrator {
             let db_clone = self.db.clone();
             let semaphore_clone = Arc::clone(&semaphore);
             let user_question_clone = user_question.clone();
+            let rag_project_task = rag_project_id.clone();
+            let answer_instructions_task = answer_instructions.clone();
             let cancelled_runs_task = Arc::clone(&cancelled_runs_clone);
             let cancelled_results_task = Arc::clone(&cancelled_results_clone);
             
@@ -131,10 +155,10 @@ impl Orchestrator {
                 let _permit = semaphore_clone.acquire().await
                     .map_err(|e| anyhow::anyhow!("Failed to acquire semaphore: {}", e))?;
                 
-                // Check again after acquiring permit
+                // Check again after acquiring permit; waits here while the run is paused
                 {
-                    let cancelled = cancelled_runs_task.read().await;
-                    if cancelled.contains(&run_id_clone) {
+                    let cancelled = cancelled_runs_task.read().await.contains(&run_id_clone);
+                    if cancelled || run_state::checkpoint(&db_clone, &run_id_clone).await == RunSignal::Cancel {
                         return Err(anyhow::anyhow!("Run was cancelled"));
                     }
                 }
@@ -144,6 +168,8 @@ impl Orchestrator {
                     &run_id_clone,
                     &profile,
                     &user_question_clone,
+                    rag_project_task.as_deref(),
+                    &answer_instructions_task,
                     &cancelled_runs_task,
                     &cancelled_results_task,
                 ).await?;
@@ -172,25 +198,32 @@ impl Orchestrator {
             }
         }
 
-        // Update run status
+        // Failed stage tasks count toward Partial / Failed like failed profiles
+        if completed > 0 && pipeline.has_followup_stages() && !self.is_run_cancelled(&run_id).await {
+            match self
+                .run_pipeline_stages(&run_id, &user_question, &stage_profiles, &pipeline, concurrency, rag_project_id.as_deref(), &style_instructions)
+                .await
+            {
+                Ok(tally) => {
+                    completed += tally.completed;
+                    failed += tally.failed;
+                }
+                Err(e) => {
+                    eprintln!("Brainstorm pipeline error: {}", e);
+                    failed += 1;
+                }
+            }
+        }
+
+        // Update run status (a cancelled run keeps its cancelled status)
         let final_status = if failed == 0 {
-            "complete"
+            RunStatus::Complete
         } else if completed > 0 {
-            "partial"
+            RunStatus::Partial
         } else {
-            "failed"
+            RunStatus::Failed
         };
-        
-        {
-            let conn = self.db.get_connection();
-            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-            let finished_at = chrono::Utc::now().to_rfc3339();
-            conn_guard.execute(
-                "UPDATE runs SET status = ?1, finished_at = ?2 WHERE id = ?3",
-                rusqlite::params![final_status, finished_at, run_id],
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to update run status: {}", e))?;
-        }
+        run_state::finish(&self.db, &run_id, final_status).map_err(|e| anyhow::anyhow!(e))?;
 
         Ok(())
     }
@@ -229,25 +262,36 @@ impl Orchestrator {
         Ok(profiles)
     }
 
+    /// `extra_instructions` (template style, web results) is appended to the global instructions.
+    #[allow(clippy::too_many_arguments)]
     async fn execute_profile(
         db: &Database,
         run_id: &str,
         profile: &ProfileData,
         user_question: &str,
+        rag_project_id: Option<&str>,
+        extra_instructions: &str,
         cancelled_runs: &Arc<RwLock<std::collections::HashSet<String>>>,
         cancelled_results: &Arc<RwLock<std::collections::HashSet<String>>>,
     ) -> Result<String> {
         // Check if result already exists for this profile in this run
-        let existing_result: Option<String> = {
+        let existing: Option<(String, String)> = {
             let conn = db.get_connection();
             let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
-            let result: Result<Option<String>, _> = conn_guard.query_row(
-                "SELECT id FROM run_results WHERE run_id = ?1 AND profile_id = ?2 AND status != 'cancelled' LIMIT 1",
+            conn_guard.query_row(
+                "SELECT id, status FROM run_results WHERE run_id = ?1 AND profile_id = ?2 AND stage = 'generate' AND status != 'cancelled' LIMIT 1",
                 rusqlite::params![run_id, profile.id],
-                |row| Ok(Some(row.get(0)?)),
-            );
-            result.unwrap_or(None)
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).ok()
         };
+
+        // Resuming: answers completed before the interruption are kept as-is
+        if let Some((result_id, status)) = &existing {
+            if status == "complete" {
+                return Ok(result_id.clone());
+            }
+        }
+        let existing_result = existing.map(|(id, _)| id);
         
         let result_id = existing_result.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let started_at = chrono::Utc::now().to_rfc3339();
@@ -308,27 +352,9 @@ impl Orchestrator {
             );
         }
         
-        // Optionally retrieve simple RAG context (project-aware retrieval can be added later)
-        let rag_context = rag::retrieve_simple_context_for_project(&db, None, 8)
-            .unwrap_or_else(|_| rag::RagContext {
-                combined_text: String::new(),
-                chunks: Vec::new(),
-            });
-
-        // Global instructions for citations & groundedness
-        let mut global_instructions = String::from(
-            "You are an expert assistant. Your job is to provide answers that are strictly grounded in the provided context and your own reasoning.\n\
-            - When you make a factual claim, cite the supporting source using the format [source:SOURCE_ID chunk:INDEX].\n\
-            - If the context does not support a claim, explicitly say that the information is not available.\n\
-            - Do not invent citations.",
-        );
-
-        if !rag_context.combined_text.is_empty() {
-            global_instructions.push_str(
-                "\n\nCONTEXT (from retrieved documents):\n====================================\n",
-            );
-            global_instructions.push_str(&rag_context.combined_text);
-        }
+        // Citation rules plus the run project's retrieved documents
+        let mut global_instructions = grounding_instructions(db, rag_project_id);
+        global_instructions.push_str(extra_instructions);
 
         // Build prompt packet
         let packet = PromptPacket {
@@ -400,13 +426,14 @@ impl Orchestrator {
         let cancelled_results_clone = Arc::clone(cancelled_results);
         let run_id_string = run_id.to_string();
         let result_id_clone = result_id.clone();
+        let db_for_check = db.clone();
         
         let cancellation_check = async move {
             loop {
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                let runs_cancelled = cancelled_runs_clone.read().await;
-                let results_cancelled = cancelled_results_clone.read().await;
-                if runs_cancelled.contains(&run_id_string) || results_cancelled.contains(&result_id_clone) {
+                let runs_cancelled = cancelled_runs_clone.read().await.contains(&run_id_string);
+                let results_cancelled = cancelled_results_clone.read().await.contains(&result_id_clone);
+                if runs_cancelled || results_cancelled || run_state::signal(&db_for_check, &run_id_string) == RunSignal::Cancel {
                     return true;
                 }
             }
@@ -485,6 +512,304 @@ impl Orchestrator {
         Ok(result_id)
     }
 
+    /// Run the critique / refine / synthesize stages after the initial fan-out.
+    ///
+    /// Each stage reads the latest answer per profile (generate, then refine once it has run),
+    /// writes its own `run_results` rows tagged with `stage` and `parent_result_id`, and the
+    /// synthesize stage writes a row to `syntheses`. `style_instructions` is appended to every stage
+    /// prompt, and every stage gets the same grounding and RAG context as the generate stage.
+    /// Returns how many stage tasks completed and failed.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_pipeline_stages(
+        &self,
+        run_id: &str,
+        user_question: &str,
+        profiles: &[ProfileData],
+        pipeline: &BrainstormPipeline,
+        concurrency: usize,
+        rag_project_id: Option<&str>,
+        style_instructions: &str,
+    ) -> Result<StageTally> {
+        let mut tally = StageTally::default();
+        let grounding = grounding_instructions(&self.db, rag_project_id);
+        let mut latest = self.load_stage_outputs(run_id, "generate")?;
+        // Stage outputs completed before an interruption are reused when the run is resumed
+        let mut critiques = self.load_stage_outputs(run_id, "critique")?;
+        let refined = self.load_stage_outputs(run_id, "refine")?;
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+        for stage in &pipeline.stages {
+            if self.is_run_cancelled(run_id).await {
+                return Err(anyhow::anyhow!("Run was cancelled"));
+            }
+
+            match stage.as_str() {
+                "generate" => {}
+                "critique" => {
+                    let tasks = profiles
+                        .iter()
+                        .filter(|profile| !critiques.contains_key(&profile.id))
+                        .filter_map(|profile| {
+                            let (own_result_id, _) = latest.get(&profile.id)?;
+                            let others = format_answers(profiles, &latest, Some(&profile.id));
+                            if others.is_empty() {
+                                return None;
+                            }
+                            let prompt = format!(
+                                "Original question:\n{}\n\nAnswers from the other participants:\n\n{}\n\
+                                Task: Critique these answers. Point out factual errors, gaps, weak reasoning and \
+                                unsupported claims, and name what is worth keeping. Refer to participants by name.{}",
+                                user_question, others, style_instructions
+                            );
+                            Some(self.run_stage_task(run_id, profile, "critique", Some(own_result_id.clone()), prompt, &grounding, &semaphore))
+                        })
+                        .collect::<Vec<_>>();
+                    for (profile_id, output) in tally.record(join_all(tasks).await) {
+                        critiques.insert(profile_id, output);
+                    }
+                }
+                "refine" => {
+                    let tasks = profiles
+                        .iter()
+                        .filter(|profile| !refined.contains_key(&profile.id))
+                        .filter_map(|profile| {
+                            let (own_result_id, own_answer) = latest.get(&profile.id)?;
+                            let received = profiles
+                                .iter()
+                                .filter(|p| p.id != profile.id)
+                                .filter_map(|p| critiques.get(&p.id).map(|(_, text)| format!("### {}\n{}\n", p.name, text)))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            let prompt = format!(
+                                "Original question:\n{}\n\nYour previous answer:\n{}\n\nCritiques from the other participants:\n\n{}\n\
+                                Task: Write an improved answer. Address the valid critiques, keep what was correct, and \
+                                output only the refined answer.{}",
+                                user_question,
+                                own_answer,
+                                if received.is_empty() { "(none)".to_string() } else { received },
+                                style_instructions
+                            );
+                            Some(self.run_stage_task(run_id, profile, "refine", Some(own_result_id.clone()), prompt, &grounding, &semaphore))
+                        })
+                        .collect::<Vec<_>>();
+                    let outputs = join_all(tasks).await;
+                    latest.extend(refined.clone());
+                    for (profile_id, output) in tally.record(outputs) {
+                        latest.insert(profile_id, output);
+                    }
+                }
+                "synthesize" => {
+                    if self.has_pipeline_synthesis(run_id)? {
+                        continue;
+                    }
+                    let synthesizer = match &pipeline.synthesizer_profile_id {
+                        Some(id) => self
+                            .load_profiles(std::slice::from_ref(id))?
+                            .into_iter()
+                            .next()
+                            .ok_or_else(|| anyhow::anyhow!("Synthesizer profile not found: {}", id)),
+                        None => profiles
+                            .first()
+                            .cloned()
+                            .ok_or_else(|| anyhow::anyhow!("No profiles available for synthesis")),
+                    };
+                    let outcome = match synthesizer {
+                        Ok(synthesizer) => self.synthesize(run_id, user_question, &synthesizer, profiles, &latest, &grounding, style_instructions).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = &outcome {
+                        eprintln!("Stage 'synthesize' failed for run {}: {}", run_id, e);
+                    }
+                    tally.record(vec![outcome]);
+                }
+                other => return Err(anyhow::anyhow!("Unknown brainstorm pipeline stage '{}'", other)),
+            }
+        }
+
+        Ok(tally)
+    }
+
+    /// Run one profile's stage prompt under the shared semaphore. Returns (profile_id, (result_id, text)).
+    #[allow(clippy::too_many_arguments)]
+    async fn run_stage_task(
+        &self,
+        run_id: &str,
+        profile: &ProfileData,
+        stage: &str,
+        parent_result_id: Option<String>,
+        prompt: String,
+        grounding: &str,
+        semaphore: &Arc<Semaphore>,
+    ) -> Result<(String, (String, String))> {
+        let _permit = semaphore
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to acquire semaphore: {}", e))?;
+        if self.is_run_cancelled(run_id).await {
+            return Err(anyhow::anyhow!("Run was cancelled"));
+        }
+        match Self::execute_stage(&self.db, run_id, profile, stage, parent_result_id.as_deref(), &prompt, grounding).await {
+            Ok(output) => Ok((profile.id.clone(), output)),
+            Err(e) => {
+                eprintln!("Stage '{}' failed for profile {}: {}", stage, profile.id, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Execute a single stage prompt for a profile and persist it as a `run_results` row.
+    async fn execute_stage(
+        db: &Database,
+        run_id: &str,
+        profile: &ProfileData,
+        stage: &str,
+        parent_result_id: Option<&str>,
+        prompt: &str,
+        grounding: &str,
+    ) -> Result<(String, String)> {
+        let result_id = Uuid::new_v4().to_string();
+        let started_at = chrono::Utc::now().to_rfc3339();
+        {
+            let conn = db.get_connection();
+            let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+            conn_guard.execute(
+                "INSERT INTO run_results (id, run_id, profile_id, status, started_at, stage, parent_result_id) VALUES (?1, ?2, ?3, 'running', ?4, ?5, ?6)",
+                rusqlite::params![result_id, run_id, profile.id, started_at, stage, parent_result_id],
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to create run result: {}", e))?;
+        }
+
+        let packet = PromptPacket {
+            global_instructions: Some(grounding.to_string()),
+            persona_instructions: profile.persona_prompt.clone(),
+            user_message: prompt.to_string(),
+            conversation_context: None,
+            params_json: profile.params_json.clone(),
+            stream: false,
+        };
+
+        let timeout_secs = 120u64;
+        let result = complete_resolving_hybrid(db, &profile.provider_account_id, &profile.model_name, &packet, timeout_secs, None).await;
+
+        let finished_at = chrono::Utc::now().to_rfc3339();
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        match result {
+            Ok((response, _, _)) => {
+                let usage = response.usage_json.as_ref().and_then(|u| serde_json::to_string(u).ok());
+                conn_guard.execute(
+                    "UPDATE run_results SET status = 'complete', raw_output_text = ?1, usage_json = ?2, finished_at = ?3 WHERE id = ?4",
+                    rusqlite::params![response.text, usage, finished_at, result_id],
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to update run result: {}", e))?;
+                Ok((result_id, response.text))
+            }
+            Err(e) => {
+                conn_guard.execute(
+                    "UPDATE run_results SET status = 'failed', error_code = 'provider_error', error_message_safe = ?1, finished_at = ?2 WHERE id = ?3",
+                    rusqlite::params![e, finished_at, result_id],
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to update run result: {}", e))?;
+                Err(anyhow::anyhow!(e))
+            }
+        }
+    }
+
+    /// Final synthesis over the latest answer of every profile, written to `syntheses`.
+    #[allow(clippy::too_many_arguments)]
+    async fn synthesize(
+        &self,
+        run_id: &str,
+        user_question: &str,
+        synthesizer: &ProfileData,
+        profiles: &[ProfileData],
+        latest: &HashMap<String, (String, String)>,
+        grounding: &str,
+        style_instructions: &str,
+    ) -> Result<String> {
+        let answers = format_answers(profiles, latest, None);
+        if answers.is_empty() {
+            return Err(anyhow::anyhow!("No answers available for synthesis"));
+        }
+        let prompt = format!(
+            "Original question:\n{}\n\nFinal answers from all participants:\n\n{}\n\
+            Task: Synthesize these into a single best answer. Consolidate points of agreement, resolve \
+            conflicts with explicit reasoning, and state any remaining uncertainty.{}",
+            user_question, answers, style_instructions
+        );
+
+        let packet = PromptPacket {
+            global_instructions: Some(grounding.to_string()),
+            persona_instructions: synthesizer.persona_prompt.clone(),
+            user_message: prompt,
+            conversation_context: None,
+            params_json: synthesizer.params_json.clone(),
+            stream: false,
+        };
+        let (response, _, _) = complete_resolving_hybrid(&self.db, &synthesizer.provider_account_id, &synthesizer.model_name, &packet, 120, None)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let source_ids: Vec<&String> = profiles
+            .iter()
+            .filter_map(|p| latest.get(&p.id).map(|(result_id, _)| result_id))
+            .collect();
+        let synthesis_id = Uuid::new_v4().to_string();
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        conn_guard.execute(
+            "INSERT INTO syntheses (id, run_id, method, synthesizer_profile_id, text, source_result_ids_json) VALUES (?1, ?2, 'pipeline', ?3, ?4, ?5)",
+            rusqlite::params![
+                synthesis_id,
+                run_id,
+                synthesizer.id,
+                response.text,
+                serde_json::to_string(&source_ids)?
+            ],
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to save synthesis: {}", e))?;
+
+        Ok(synthesis_id)
+    }
+
+    fn has_pipeline_synthesis(&self, run_id: &str) -> Result<bool> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        let count: i64 = conn_guard
+            .query_row(
+                "SELECT COUNT(*) FROM syntheses WHERE run_id = ?1 AND method = 'pipeline'",
+                [run_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to query syntheses: {}", e))?;
+        Ok(count > 0)
+    }
+
+    /// Latest completed output per profile for a stage: profile_id -> (result_id, text).
+    fn load_stage_outputs(&self, run_id: &str, stage: &str) -> Result<HashMap<String, (String, String)>> {
+        let conn = self.db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        let mut stmt = conn_guard
+            .prepare(
+                "SELECT profile_id, id, raw_output_text FROM run_results
+                 WHERE run_id = ?1 AND stage = ?2 AND status = 'complete' AND raw_output_text IS NOT NULL
+                 ORDER BY finished_at",
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![run_id, stage], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            })
+            .map_err(|e| anyhow::anyhow!("Failed to query run results: {}", e))?;
+
+        let mut outputs = HashMap::new();
+        for row in rows {
+            let (profile_id, result_id, text) = row.map_err(|e| anyhow::anyhow!("Row error: {}", e))?;
+            outputs.insert(profile_id, (result_id, text));
+        }
+        Ok(outputs)
+    }
+
     /// Run a single agent (for rerun functionality)
     pub async fn run_single_agent(
         &self,
@@ -703,12 +1028,291 @@ impl Orchestrator {
     }
 }
 
+/// Multi-stage brainstorm configuration from `run_settings_json.pipeline`.
+///
+/// ```json
+/// { "pipeline": { "stages": ["generate", "critique", "refine", "synthesize"], "synthesizer_profile_id": "..." } }
+/// ```
+/// Without a pipeline the run is the single parallel fan-out ("generate").
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrainstormPipeline {
+    #[serde(default)]
+    pub stages: Vec<String>,
+    /// Profile used for the synthesize stage; defaults to the first selected profile.
+    #[serde(default)]
+    pub synthesizer_profile_id: Option<String>,
+}
+
+/// Stage names accepted in `pipeline.stages`.
+pub const PIPELINE_STAGES: &[&str] = &["generate", "critique", "refine", "synthesize"];
+
+impl BrainstormPipeline {
+    /// Parse `run_settings_json.pipeline`; a malformed pipeline or an unknown stage name is an error.
+    pub fn from_run_settings(run_settings: &Value) -> Result<Self, String> {
+        match run_settings.get("pipeline") {
+            None | Some(Value::Null) => Ok(BrainstormPipeline {
+                stages: vec!["generate".to_string()],
+                synthesizer_profile_id: None,
+            }),
+            Some(pipeline) => Self::parse(pipeline),
+        }
+    }
+
+    pub fn parse(pipeline: &Value) -> Result<Self, String> {
+        let pipeline: BrainstormPipeline = serde_json::from_value(pipeline.clone())
+            .map_err(|e| format!("Invalid brainstorm pipeline: {}", e))?;
+        if let Some(unknown) = pipeline.stages.iter().find(|s| !PIPELINE_STAGES.contains(&s.as_str())) {
+            return Err(format!(
+                "Unknown brainstorm pipeline stage '{}' (expected one of: {})",
+                unknown,
+                PIPELINE_STAGES.join(", ")
+            ));
+        }
+        Ok(pipeline)
+    }
+
+    pub fn has_followup_stages(&self) -> bool {
+        self.stages.iter().any(|s| s != "generate")
+    }
+}
+
+/// Answer style a session template stores in `run_settings_json.template`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ResponseStyle {
+    pub language: Option<String>,
+    pub tone: Option<String>,
+    pub max_words: Option<i32>,
+    /// Recent news on the question is added to the initial answers
+    pub web_search: bool,
+}
+
+impl ResponseStyle {
+    pub fn from_run_settings(run_settings: &Value) -> Self {
+        run_settings
+            .get("template")
+            .and_then(|t| serde_json::from_value(t.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Prompt suffix for every answer of the run; empty without template settings.
+    pub fn instructions(&self) -> String {
+        let mut instructions = String::new();
+        if let Some(language) = self.language.as_deref().filter(|l| !l.trim().is_empty()) {
+            instructions.push_str(&format!("\n\nIMPORTANT: Respond in {} language.", language));
+        }
+        if let Some(tone) = self.tone.as_deref().filter(|t| !t.trim().is_empty()) {
+            instructions.push_str(&format!("\n\nIMPORTANT: Maintain a {} tone throughout your response.", tone));
+        }
+        // Soft limit as in debates; very strict limits are skipped
+        if let Some(max) = self.max_words.filter(|max| *max >= 50) {
+            instructions.push_str(&format!("\n\nKeep your response to approximately {} words or fewer. Be concise but complete.", max));
+        }
+        instructions
+    }
+}
+
+/// Web search results as a prompt section; empty when nothing was found.
+fn format_news(results: &[NewsResult]) -> String {
+    if results.is_empty() {
+        return String::new();
+    }
+    let mut context = "\n\nRECENT NEWS AND INFORMATION:\n".to_string();
+    for (i, result) in results.iter().enumerate() {
+        let snippet: String = result.snippet.chars().take(200).collect();
+        context.push_str(&format!("{}. {}\n   Source: {}\n   Summary: {}\n\n", i + 1, result.title, result.url, snippet));
+    }
+    context.push_str("Use this recent information where it is relevant and cite the sources you rely on.\n");
+    context
+}
+
+/// Completed and failed stage tasks of the follow-up pipeline.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct StageTally {
+    completed: usize,
+    failed: usize,
+}
+
+impl StageTally {
+    /// Count the outcomes of one stage and keep the successful outputs.
+    fn record<T>(&mut self, outcomes: Vec<Result<T>>) -> Vec<T> {
+        outcomes
+            .into_iter()
+            .filter_map(|outcome| match outcome {
+                Ok(output) => {
+                    self.completed += 1;
+                    Some(output)
+                }
+                Err(_) => {
+                    self.failed += 1;
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Citation rules for every brainstorm prompt, followed by the retrieved documents of the run's project.
+fn grounding_instructions(db: &Database, rag_project_id: Option<&str>) -> String {
+    let rag_context = rag::retrieve_simple_context_for_project(db, rag_project_id, 8)
+        .unwrap_or_else(|_| rag::RagContext {
+            combined_text: String::new(),
+            chunks: Vec::new(),
+        });
+
+    let mut instructions = String::from(
+        "You are an expert assistant. Your job is to provide answers that are strictly grounded in the provided context and your own reasoning.\n\
+        - When you make a factual claim, cite the supporting source using the format [source:SOURCE_ID chunk:INDEX].\n\
+        - If the context does not support a claim, explicitly say that the information is not available.\n\
+        - Do not invent citations.",
+    );
+    if !rag_context.combined_text.is_empty() {
+        instructions.push_str(
+            "\n\nCONTEXT (from retrieved documents):\n====================================\n",
+        );
+        instructions.push_str(&rag_context.combined_text);
+    }
+    instructions
+}
+
+/// Format the latest answer of each profile as Markdown sections, optionally skipping one profile.
+fn format_answers(profiles: &[ProfileData], latest: &HashMap<String, (String, String)>, exclude_profile_id: Option<&str>) -> String {
+    profiles
+        .iter()
+        .filter(|p| Some(p.id.as_str()) != exclude_profile_id)
+        .filter_map(|p| latest.get(&p.id).map(|(_, text)| format!("### {}\n{}\n", p.name, text)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Clone)]
 struct ProfileData {
     id: String,
-    #[allow(dead_code)]
     name: String,
     provider_account_id: String,
     model_name: String,
     persona_prompt: String,
     params_json: Value,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn test_db(run_settings: &str) -> (Database, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("panther-brainstorm-{}.db", Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json) VALUES
+                     ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}'),
+                     ('p2', 'Ben', 'acc', 'gpt', 'You are Ben', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Q', 'parallel');",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('r1', 's1', '[\"p1\", \"p2\"]', 'running', ?1)",
+                [run_settings],
+            )
+            .unwrap();
+        }
+        (db, path)
+    }
+
+    #[test]
+    fn test_pipeline_from_run_settings() {
+        let default = BrainstormPipeline::from_run_settings(&json!({ "concurrency": 3 })).unwrap();
+        assert_eq!(default.stages, vec!["generate"]);
+        assert!(!default.has_followup_stages());
+
+        let pipeline = BrainstormPipeline::from_run_settings(&json!({
+            "pipeline": { "stages": ["generate", "critique", "synthesize"], "synthesizer_profile_id": "p2" }
+        }))
+        .unwrap();
+        assert_eq!(pipeline.stages, vec!["generate", "critique", "synthesize"]);
+        assert_eq!(pipeline.synthesizer_profile_id.as_deref(), Some("p2"));
+        assert!(pipeline.has_followup_stages());
+
+        let err = BrainstormPipeline::from_run_settings(&json!({ "pipeline": { "stages": ["generate", "critque"] } })).unwrap_err();
+        assert!(err.contains("'critque'"), "{}", err);
+        assert!(BrainstormPipeline::from_run_settings(&json!({ "pipeline": { "stages": "critique" } })).is_err());
+    }
+
+    #[test]
+    fn test_response_style_from_template() {
+        let style = ResponseStyle::from_run_settings(&json!({
+            "template": { "name": "Review", "language": "German", "tone": "formal", "max_words": 150, "web_search": true, "debate": null }
+        }));
+        assert!(style.web_search);
+        let instructions = style.instructions();
+        assert!(instructions.contains("Respond in German language"));
+        assert!(instructions.contains("formal tone"));
+        assert!(instructions.contains("approximately 150 words"));
+
+        let plain = ResponseStyle::from_run_settings(&json!({ "template": { "language": null, "max_words": 20 } }));
+        assert_eq!(plain.instructions(), "");
+        assert_eq!(ResponseStyle::from_run_settings(&json!({})).instructions(), "");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_stage_fails_run() {
+        let (db, path) = test_db(r#"{"pipeline": {"stages": ["generate", "vote"]}}"#);
+        let orchestrator = Orchestrator::new(db.clone());
+        assert!(orchestrator.run_parallel_brainstorm("r1".to_string()).await.is_err());
+
+        assert_eq!(run_state::current_status(&db, "r1").unwrap().as_deref(), Some("failed"));
+        let conn = db.get_connection();
+        let results: i64 = conn.lock().unwrap().query_row("SELECT COUNT(*) FROM run_results", [], |row| row.get(0)).unwrap();
+        assert_eq!(results, 0);
+        drop(conn);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_stages_chain_to_parent_results_and_count_failures() {
+        let (db, path) = test_db("{}");
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            // Ana's critique survived an earlier attempt; the provider is unsupported, so new stage calls fail
+            conn.execute_batch(
+                "INSERT INTO run_results (id, run_id, profile_id, status, raw_output_text, finished_at, stage) VALUES
+                     ('g1', 'r1', 'p1', 'complete', 'Ana answer', '2026-01-01T00:00:01Z', 'generate'),
+                     ('g2', 'r1', 'p2', 'complete', 'Ben answer', '2026-01-01T00:00:02Z', 'generate');
+                 INSERT INTO run_results (id, run_id, profile_id, status, raw_output_text, finished_at, stage, parent_result_id) VALUES
+                     ('c1', 'r1', 'p1', 'complete', 'Ana critique', '2026-01-01T00:00:03Z', 'critique', 'g1');",
+            )
+            .unwrap();
+        }
+        let orchestrator = Orchestrator::new(db.clone());
+        let profiles = orchestrator.load_profiles(&["p1".to_string(), "p2".to_string()]).unwrap();
+        let pipeline = BrainstormPipeline::parse(&json!({ "stages": ["generate", "critique", "refine"] })).unwrap();
+
+        let tally = orchestrator.run_pipeline_stages("r1", "Q", &profiles, &pipeline, 2, None, "").await.unwrap();
+        assert_eq!(tally, StageTally { completed: 0, failed: 3 });
+
+        let conn = db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT stage, profile_id, parent_result_id, status FROM run_results WHERE id NOT IN ('g1', 'g2', 'c1') ORDER BY stage, profile_id")
+            .unwrap();
+        let rows: Vec<(String, String, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        let row = |stage: &str, profile: &str, parent: &str| (stage.to_string(), profile.to_string(), parent.to_string(), "failed".to_string());
+        assert_eq!(
+            rows,
+            vec![row("critique", "p2", "g2"), row("refine", "p1", "g1"), row("refine", "p2", "g2")]
+        );
+        drop(stmt);
+        drop(conn);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,400 @@
+// Long-term profile memory: facts and preferences about the user that outlive a conversation.
+//
+// Opt-in per profile (`profile_memory.enabled` in params_json). After a chat turn whose user
+// message looks like it says something about the user, the profile's model extracts durable facts
+// and preferences; each is stored with the id of the message it came from. Later turns get the
+// most relevant memories (keyword overlap, preferences first among equals) appended to the
+// persona. With `redact_pii` on, text sent to the extractor, stored memories and memories put back
+// into prompts all pass through `PiiRedactor`.
+
+use crate::commands_privacy::PrivacySettings;
+use crate::db::Database;
+use crate::privacy::PiiRedactor;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::types::PromptPacket;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+
+const MEMORY_COLUMNS: &str = "id, profile_id, kind, content, source_message_id, conversation_id, edited_by_user,
+     use_count, last_used_at, created_at, updated_at";
+
+/// Existing memories shown to the extractor so it does not repeat them
+const EXTRACTOR_KNOWN_LIMIT: usize = 40;
+const MAX_MEMORY_CHARS: usize = 300;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "your", "with", "have", "has", "had", "this", "that", "was",
+    "were", "they", "them", "what", "when", "where", "which", "who", "why", "how", "can", "could", "would", "should",
+    "will", "about", "from", "into", "just", "like", "some", "than", "then", "there", "their", "its", "our", "out",
+    "all", "any", "also", "been", "being", "does", "did", "doing", "user", "users", "prefers", "likes",
+];
+
+/// First-person cues that make a message worth running the extractor on
+const SELF_DISCLOSURE_CUES: &[&str] = &[
+    "i am", "i'm", "im ", "my ", "i have", "i've", "i work", "i live", "i prefer", "i like", "i love", "i hate",
+    "i don't", "i dont", "i never", "i always", "i usually", "call me", "remember", "please always", "please never",
+    "from now on",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileMemoryConfig {
+    pub enabled: bool,
+    /// Memories added to the persona per turn
+    pub max_retrieved: usize,
+    /// Oldest memories (not edited by the user) are dropped beyond this
+    pub max_stored: usize,
+}
+
+impl Default for ProfileMemoryConfig {
+    fn default() -> Self {
+        ProfileMemoryConfig { enabled: false, max_retrieved: 8, max_stored: 200 }
+    }
+}
+
+impl ProfileMemoryConfig {
+    /// `profile_memory` object from a profile's params_json, if present.
+    pub fn from_params(params_json: &serde_json::Value) -> Option<Self> {
+        params_json.get("profile_memory").and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileMemory {
+    pub id: String,
+    pub profile_id: String,
+    /// "fact" or "preference"
+    pub kind: String,
+    pub content: String,
+    /// Chat message the memory was learned from; None for manual entries or deleted messages
+    pub source_message_id: Option<String>,
+    pub conversation_id: Option<String>,
+    pub edited_by_user: bool,
+    pub use_count: i64,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn map_memory(row: &rusqlite::Row) -> rusqlite::Result<ProfileMemory> {
+    Ok(ProfileMemory {
+        id: row.get(0)?,
+        profile_id: row.get(1)?,
+        kind: row.get(2)?,
+        content: row.get(3)?,
+        source_message_id: row.get(4)?,
+        conversation_id: row.get(5)?,
+        edited_by_user: row.get::<_, i64>(6)? != 0,
+        use_count: row.get(7)?,
+        last_used_at: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn normalize_kind(kind: &str) -> Result<&'static str, String> {
+    match kind.trim().to_ascii_lowercase().as_str() {
+        "fact" => Ok("fact"),
+        "preference" => Ok("preference"),
+        other => Err(format!("Unknown memory kind '{}': use fact or preference", other)),
+    }
+}
+
+/// Text as it may be stored or sent: PII replaced by placeholders when `redact_pii` is on
+pub fn redact(privacy: &PrivacySettings, text: &str, context_id: &str) -> String {
+    if privacy.redact_pii {
+        PiiRedactor::new().redact_text(text, &privacy.custom_identifiers, context_id).redacted_text
+    } else {
+        text.to_string()
+    }
+}
+
+/// Newest first
+pub fn list(conn: &Connection, profile_id: &str) -> Result<Vec<ProfileMemory>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM profile_memories WHERE profile_id = ?1 ORDER BY created_at DESC, rowid DESC",
+            MEMORY_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt.query_map([profile_id], map_memory).map_err(|e| format!("Database error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Row error: {}", e))
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<ProfileMemory, String> {
+    conn.query_row(&format!("SELECT {} FROM profile_memories WHERE id = ?1", MEMORY_COLUMNS), [id], map_memory)
+        .optional()
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Memory not found".to_string())
+}
+
+fn normalized(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").trim_end_matches('.').to_lowercase()
+}
+
+/// Store a memory (already redacted by the caller); returns None for an exact duplicate
+pub fn add(
+    conn: &Connection,
+    profile_id: &str,
+    kind: &str,
+    content: &str,
+    source_message_id: Option<&str>,
+    conversation_id: Option<&str>,
+    edited_by_user: bool,
+) -> Result<Option<ProfileMemory>, String> {
+    let kind = normalize_kind(kind)?;
+    let content: String = content.trim().chars().take(MAX_MEMORY_CHARS).collect();
+    if content.is_empty() {
+        return Err("Memory text is empty".to_string());
+    }
+    let key = normalized(&content);
+    if list(conn, profile_id)?.iter().any(|m| normalized(&m.content) == key) {
+        return Ok(None);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO profile_memories (id, profile_id, kind, content, source_message_id, conversation_id, edited_by_user, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+        rusqlite::params![id, profile_id, kind, content, source_message_id, conversation_id, edited_by_user as i64, now],
+    )
+    .map_err(|e| format!("Failed to save memory: {}", e))?;
+    get(conn, &id).map(Some)
+}
+
+pub fn update(conn: &Connection, id: &str, content: &str, kind: Option<&str>) -> Result<ProfileMemory, String> {
+    let current = get(conn, id)?;
+    let kind = match kind {
+        Some(kind) => normalize_kind(kind)?,
+        None => normalize_kind(&current.kind)?,
+    };
+    let content: String = content.trim().chars().take(MAX_MEMORY_CHARS).collect();
+    if content.is_empty() {
+        return Err("Memory text is empty".to_string());
+    }
+    conn.execute(
+        "UPDATE profile_memories SET content = ?1, kind = ?2, edited_by_user = 1, updated_at = ?3 WHERE id = ?4",
+        rusqlite::params![content, kind, chrono::Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|e| format!("Failed to update memory: {}", e))?;
+    get(conn, id)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM profile_memories WHERE id = ?1", [id])
+        .map_err(|e| format!("Failed to delete memory: {}", e))?;
+    Ok(())
+}
+
+pub fn clear(conn: &Connection, profile_id: &str) -> Result<usize, String> {
+    conn.execute("DELETE FROM profile_memories WHERE profile_id = ?1", [profile_id])
+        .map_err(|e| format!("Failed to clear memories: {}", e))
+}
+
+/// Drop the oldest learned memories beyond `max_stored`; user-edited ones are kept
+fn prune(conn: &Connection, profile_id: &str, max_stored: usize) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM profile_memories WHERE id IN (
+             SELECT id FROM profile_memories WHERE profile_id = ?1 AND edited_by_user = 0
+             ORDER BY created_at DESC, rowid DESC LIMIT -1 OFFSET ?2
+         )",
+        rusqlite::params![profile_id, max_stored as i64],
+    )
+    .map_err(|e| format!("Failed to prune memories: {}", e))?;
+    Ok(())
+}
+
+fn keywords(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Memories worth adding for `query`: ones sharing keywords with it, then preferences,
+/// most recent first among equals
+pub fn relevant<'a>(memories: &'a [ProfileMemory], query: &str, limit: usize) -> Vec<&'a ProfileMemory> {
+    let query_words = keywords(query);
+    let mut scored: Vec<(usize, bool, &ProfileMemory)> = memories
+        .iter()
+        .map(|m| (keywords(&m.content).intersection(&query_words).count(), m.kind == "preference", m))
+        .filter(|(overlap, preference, _)| *overlap > 0 || *preference)
+        .collect();
+    // `memories` is newest first and the sort is stable, so recency breaks ties
+    scored.sort_by_key(|(overlap, preference, _)| std::cmp::Reverse((*overlap, *preference)));
+    scored.into_iter().take(limit).map(|(_, _, m)| m).collect()
+}
+
+/// Persona addition listing the retrieved memories
+pub fn persona_block(memories: &[&ProfileMemory]) -> String {
+    if memories.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = memories.iter().map(|m| format!("- ({}) {}", m.kind, m.content)).collect();
+    format!(
+        "\n\n## What you remember about the user\nFrom earlier conversations; use it when relevant and don't recite it unprompted:\n{}",
+        lines.join("\n")
+    )
+}
+
+/// Retrieve memories for a turn and record that they were used
+pub fn recall(db: &Database, profile_id: &str, query: &str, config: &ProfileMemoryConfig) -> Result<String, String> {
+    let privacy = PrivacySettings::load_sync(db).unwrap_or_default();
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let memories = list(&conn_guard, profile_id)?;
+    let chosen = relevant(&memories, query, config.max_retrieved);
+    let now = chrono::Utc::now().to_rfc3339();
+    for memory in &chosen {
+        conn_guard
+            .execute(
+                "UPDATE profile_memories SET use_count = use_count + 1, last_used_at = ?1 WHERE id = ?2",
+                rusqlite::params![now, memory.id],
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+    Ok(redact(&privacy, &persona_block(&chosen), profile_id))
+}
+
+pub fn worth_extracting(user_message: &str) -> bool {
+    let text = format!(" {} ", user_message.to_lowercase());
+    SELF_DISCLOSURE_CUES.iter().any(|cue| text.contains(&format!(" {}", cue)))
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractedMemory {
+    #[serde(default = "default_kind")]
+    kind: String,
+    content: String,
+}
+
+fn default_kind() -> String {
+    "fact".to_string()
+}
+
+/// JSON array in the extractor's reply (tolerates code fences and surrounding prose)
+fn parse_extracted(text: &str) -> Vec<ExtractedMemory> {
+    let start = text.find('[');
+    let end = text.rfind(']');
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => serde_json::from_str(&text[start..=end]).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Extract durable facts and preferences from one exchange and store the new ones
+pub async fn extract_and_store(
+    db: &Database,
+    profile_id: &str,
+    conversation_id: Option<&str>,
+    source_message_id: &str,
+    user_message: &str,
+    assistant_reply: &str,
+    config: &ProfileMemoryConfig,
+) -> Result<Vec<ProfileMemory>, String> {
+    if !worth_extracting(user_message) {
+        return Ok(Vec::new());
+    }
+    let privacy = PrivacySettings::load_sync(db).unwrap_or_default();
+    let (provider_account_id, model_name, known) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let (provider, model): (String, String) = conn_guard
+            .query_row(
+                "SELECT provider_account_id, model_name FROM prompt_profiles WHERE id = ?1",
+                [profile_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Failed to load profile: {}", e))?;
+        let known: Vec<String> =
+            list(&conn_guard, profile_id)?.into_iter().take(EXTRACTOR_KNOWN_LIMIT).map(|m| m.content).collect();
+        (provider, model, known)
+    };
+
+    let packet = PromptPacket {
+        global_instructions: None,
+        persona_instructions: "You extract long-term memories about a user from a chat exchange. You are strict and concise.".to_string(),
+        user_message: format!(
+            "Already remembered:\n{}\n\nUser said:\n{}\n\nAssistant replied:\n{}\n\n\
+            Task: List facts about the user and the user's preferences that will still be true and useful in \
+            future conversations (e.g. their job, projects, tools, constraints, how they want answers). Use only \
+            what the USER stated; ignore one-off requests, questions and anything already remembered. Write each \
+            item as a short third-person sentence (\"The user ...\"). Output only a JSON array like \
+            [{{\"kind\": \"fact\", \"content\": \"...\"}}, {{\"kind\": \"preference\", \"content\": \"...\"}}], \
+            or [] if there is nothing durable.",
+            if known.is_empty() {
+                "(nothing)".to_string()
+            } else {
+                known.iter().map(|k| format!("- {}", redact(&privacy, k, profile_id))).collect::<Vec<_>>().join("\n")
+            },
+            redact(&privacy, user_message, profile_id),
+            redact(&privacy, assistant_reply, profile_id)
+        ),
+        conversation_context: None,
+        params_json: json!({ "temperature": 0.0, "max_tokens": 400 }),
+        stream: false,
+    };
+    let (response, _, _) = complete_resolving_hybrid(db, &provider_account_id, &model_name, &packet, 60, None).await?;
+
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stored = Vec::new();
+    for item in parse_extracted(&response.text) {
+        let Ok(kind) = normalize_kind(&item.kind) else { continue };
+        let content = redact(&privacy, &item.content, profile_id);
+        if content.trim().is_empty() {
+            continue;
+        }
+        if let Some(memory) =
+            add(&conn_guard, profile_id, kind, &content, Some(source_message_id), conversation_id, false)?
+        {
+            stored.push(memory);
+        }
+    }
+    prune(&conn_guard, profile_id, config.max_stored)?;
+    Ok(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(kind: &str, content: &str) -> ProfileMemory {
+        ProfileMemory {
+            id: content.to_string(),
+            profile_id: "p".to_string(),
+            kind: kind.to_string(),
+            content: content.to_string(),
+            source_message_id: None,
+            conversation_id: None,
+            edited_by_user: false,
+            use_count: 0,
+            last_used_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_relevant_memories_and_extraction_gate() {
+        let memories = vec![
+            memory("fact", "The user maintains a Rust web service."),
+            memory("preference", "The user wants short answers."),
+            memory("fact", "The user has a dog named Pixel."),
+        ];
+        let chosen: Vec<&str> =
+            relevant(&memories, "How do I speed up my Rust service?", 8).iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(chosen, ["The user maintains a Rust web service.", "The user wants short answers."]);
+        assert_eq!(relevant(&memories, "Rust", 1).len(), 1);
+
+        assert!(worth_extracting("I'm a nurse and I work nights"));
+        assert!(worth_extracting("From now on answer in French"));
+        assert!(!worth_extracting("What is the capital of Peru?"));
+
+        let parsed = parse_extracted("Sure:\n```json\n[{\"kind\": \"preference\", \"content\": \"The user wants French.\"}]\n```");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].kind, "preference");
+    }
+}
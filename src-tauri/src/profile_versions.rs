@@ -0,0 +1,408 @@
+// Profile versions: history, diff, rollback and A/B comparison
+//
+// Triggers from migration_037 add a version whenever a profile's prompt, parameters, character or
+// model change, and stamp new run_results and assistant chat_messages with the version in effect.
+// Rolling back writes an old version into the profile, which itself becomes the newest version.
+
+use crate::commands_chat::build_persona;
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::token_usage::record_token_usage;
+use crate::types::{CharacterDefinition, PromptPacket};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const VERSION_COLUMNS: &str = "id, profile_id, version, name, provider_account_id, model_name, persona_prompt,
+     params_json, character_definition_json, model_features_json, note, created_at";
+
+/// Beyond this many line pairs the diff falls back to whole-text replacement
+const MAX_DIFF_CELLS: usize = 4_000_000;
+const MAX_AB_QUESTIONS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileVersion {
+    pub id: String,
+    pub profile_id: String,
+    pub version: i64,
+    pub name: String,
+    pub provider_account_id: String,
+    pub model_name: String,
+    pub persona_prompt: String,
+    pub params_json: serde_json::Value,
+    pub character_definition: Option<serde_json::Value>,
+    pub model_features: Option<serde_json::Value>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileVersionDiff {
+    pub from: ProfileVersion,
+    pub to: ProfileVersion,
+    /// Only fields that differ
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AbTestRequest {
+    #[serde(default)]
+    pub profile_id: String,
+    pub version_a_id: String,
+    pub version_b_id: String,
+    pub questions: Vec<String>,
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbAnswer {
+    pub text: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+    pub total_tokens: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbRow {
+    pub question: String,
+    pub a: AbAnswer,
+    pub b: AbAnswer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbTestReport {
+    pub id: String,
+    pub profile_id: String,
+    pub version_a_id: String,
+    pub version_b_id: String,
+    pub version_a: i64,
+    pub version_b: i64,
+    pub rows: Vec<AbRow>,
+    pub created_at: String,
+}
+
+fn parse_json(text: Option<String>) -> Option<serde_json::Value> {
+    text.and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn map_version(row: &rusqlite::Row) -> rusqlite::Result<ProfileVersion> {
+    Ok(ProfileVersion {
+        id: row.get(0)?,
+        profile_id: row.get(1)?,
+        version: row.get(2)?,
+        name: row.get(3)?,
+        provider_account_id: row.get(4)?,
+        model_name: row.get(5)?,
+        persona_prompt: row.get(6)?,
+        params_json: parse_json(row.get(7)?).unwrap_or(json!({})),
+        character_definition: parse_json(row.get(8)?),
+        model_features: parse_json(row.get(9)?),
+        note: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+/// Newest first
+pub fn list(conn: &Connection, profile_id: &str) -> Result<Vec<ProfileVersion>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM profile_versions WHERE profile_id = ?1 ORDER BY version DESC",
+            VERSION_COLUMNS
+        ))
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt.query_map([profile_id], map_version).map_err(|e| format!("Database error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Row error: {}", e))
+}
+
+pub fn get(conn: &Connection, version_id: &str) -> Result<ProfileVersion, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM profile_versions WHERE id = ?1", VERSION_COLUMNS),
+        [version_id],
+        map_version,
+    )
+    .optional()
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| "Profile version not found".to_string())
+}
+
+/// Line diff (longest common subsequence)
+pub fn diff_lines(before: &str, after: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+    let line = |kind: DiffKind, text: &str| DiffLine { kind, text: text.to_string() };
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        return a.iter().map(|t| line(DiffKind::Removed, t)).chain(b.iter().map(|t| line(DiffKind::Added, t))).collect();
+    }
+
+    // lcs[i][j] = common lines in a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(line(DiffKind::Same, a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(line(DiffKind::Removed, a[i]));
+            i += 1;
+        } else {
+            out.push(line(DiffKind::Added, b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|t| line(DiffKind::Removed, t)));
+    out.extend(b[j..].iter().map(|t| line(DiffKind::Added, t)));
+    out
+}
+
+fn pretty(value: &Option<serde_json::Value>) -> String {
+    value.as_ref().map(|v| serde_json::to_string_pretty(v).unwrap_or_default()).unwrap_or_default()
+}
+
+pub fn diff(conn: &Connection, from_id: &str, to_id: &str) -> Result<ProfileVersionDiff, String> {
+    let from = get(conn, from_id)?;
+    let to = get(conn, to_id)?;
+    if from.profile_id != to.profile_id {
+        return Err("Versions belong to different profiles".to_string());
+    }
+    let fields = [
+        ("provider_account_id", from.provider_account_id.clone(), to.provider_account_id.clone()),
+        ("model_name", from.model_name.clone(), to.model_name.clone()),
+        ("persona_prompt", from.persona_prompt.clone(), to.persona_prompt.clone()),
+        ("params_json", pretty(&Some(from.params_json.clone())), pretty(&Some(to.params_json.clone()))),
+        ("character_definition", pretty(&from.character_definition), pretty(&to.character_definition)),
+        ("model_features", pretty(&from.model_features), pretty(&to.model_features)),
+    ];
+    let changes = fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange { field: field.to_string(), lines: diff_lines(&before, &after) })
+        .collect();
+    Ok(ProfileVersionDiff { from, to, changes })
+}
+
+/// Restore a version's settings; the restored state is recorded as a new version
+pub fn rollback(conn: &Connection, profile_id: &str, version_id: &str) -> Result<ProfileVersion, String> {
+    let target = get(conn, version_id)?;
+    if target.profile_id != profile_id {
+        return Err("Version belongs to another profile".to_string());
+    }
+    let latest_before: i64 = conn
+        .query_row("SELECT COALESCE(MAX(version), 0) FROM profile_versions WHERE profile_id = ?1", [profile_id], |row| row.get(0))
+        .map_err(|e| format!("Database error: {}", e))?;
+    let to_text = |v: &Option<serde_json::Value>| v.as_ref().and_then(|v| serde_json::to_string(v).ok());
+    conn.execute(
+        "UPDATE prompt_profiles SET provider_account_id = ?1, model_name = ?2, persona_prompt = ?3, params_json = ?4,
+             character_definition_json = ?5, model_features_json = ?6, updated_at = ?7
+         WHERE id = ?8",
+        rusqlite::params![
+            target.provider_account_id,
+            target.model_name,
+            target.persona_prompt,
+            serde_json::to_string(&target.params_json).unwrap_or_else(|_| "{}".to_string()),
+            to_text(&target.character_definition),
+            to_text(&target.model_features),
+            chrono::Utc::now().to_rfc3339(),
+            profile_id
+        ],
+    )
+    .map_err(|e| format!("Failed to roll back profile: {}", e))?;
+    if conn.changes() == 0 {
+        return Err("Profile not found".to_string());
+    }
+    conn.execute(
+        "UPDATE profile_versions SET note = ?1 WHERE profile_id = ?2 AND version > ?3",
+        rusqlite::params![format!("Rolled back to v{}", target.version), profile_id, latest_before],
+    )
+    .map_err(|e| format!("Database error: {}", e))?;
+    list(conn, profile_id)?.into_iter().next().ok_or_else(|| "Profile has no versions".to_string())
+}
+
+fn total_tokens(usage: &Option<serde_json::Value>) -> Option<i64> {
+    let usage = usage.as_ref()?;
+    usage.get("total_tokens").and_then(|v| v.as_i64()).or_else(|| {
+        let prompt = usage.get("prompt_tokens").and_then(|v| v.as_i64())?;
+        Some(prompt + usage.get("completion_tokens").and_then(|v| v.as_i64()).unwrap_or(0))
+    })
+}
+
+async fn ask(db: &Database, version: &ProfileVersion, question: &str, timeout_secs: u64) -> AbAnswer {
+    let character: Option<CharacterDefinition> =
+        version.character_definition.clone().and_then(|v| serde_json::from_value(v).ok());
+    let packet = PromptPacket {
+        global_instructions: None,
+        persona_instructions: build_persona(&version.persona_prompt, character.as_ref()),
+        user_message: question.to_string(),
+        conversation_context: None,
+        params_json: version.params_json.clone(),
+        stream: false,
+    };
+    let started = std::time::Instant::now();
+    let result =
+        complete_resolving_hybrid(db, &version.provider_account_id, &version.model_name, &packet, timeout_secs, None).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok((response, provider, model)) => {
+            let _ = record_token_usage(
+                db,
+                Some(&provider.id),
+                &model,
+                &response.usage_json,
+                "profile_ab_test",
+                None,
+                Some(json!({ "profile_id": version.profile_id, "profile_version_id": version.id })),
+            );
+            AbAnswer { text: Some(response.text), error: None, latency_ms, total_tokens: total_tokens(&response.usage_json) }
+        }
+        Err(e) => AbAnswer { text: None, error: Some(e), latency_ms, total_tokens: None },
+    }
+}
+
+/// Ask both versions every question (the two versions concurrently) and store the report
+pub async fn run_ab_test(db: &Database, request: &AbTestRequest) -> Result<AbTestReport, String> {
+    let questions: Vec<&str> = request.questions.iter().map(|q| q.trim()).filter(|q| !q.is_empty()).collect();
+    if questions.is_empty() {
+        return Err("Add at least one question".to_string());
+    }
+    if questions.len() > MAX_AB_QUESTIONS {
+        return Err(format!("At most {} questions per A/B test", MAX_AB_QUESTIONS));
+    }
+    let (version_a, version_b) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        (get(&conn_guard, &request.version_a_id)?, get(&conn_guard, &request.version_b_id)?)
+    };
+    if version_a.profile_id != request.profile_id || version_b.profile_id != request.profile_id {
+        return Err("Versions must belong to the profile".to_string());
+    }
+
+    let timeout_secs = request.timeout_seconds.unwrap_or(90);
+    let mut rows = Vec::with_capacity(questions.len());
+    for question in questions {
+        let (a, b) = tokio::join!(
+            ask(db, &version_a, question, timeout_secs),
+            ask(db, &version_b, question, timeout_secs)
+        );
+        rows.push(AbRow { question: question.to_string(), a, b });
+    }
+
+    let report = AbTestReport {
+        id: uuid::Uuid::new_v4().to_string(),
+        profile_id: request.profile_id.clone(),
+        version_a_id: version_a.id,
+        version_b_id: version_b.id,
+        version_a: version_a.version,
+        version_b: version_b.version,
+        rows,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute(
+            "INSERT INTO profile_ab_tests (id, profile_id, version_a_id, version_b_id, report_json, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                report.id,
+                report.profile_id,
+                report.version_a_id,
+                report.version_b_id,
+                serde_json::to_string(&report).map_err(|e| format!("Failed to serialize report: {}", e))?,
+                report.created_at
+            ],
+        )
+        .map_err(|e| format!("Failed to save A/B test: {}", e))?;
+    Ok(report)
+}
+
+/// Stored A/B reports for a profile, newest first
+pub fn list_ab_tests(conn: &Connection, profile_id: &str) -> Result<Vec<AbTestReport>, String> {
+    let mut stmt = conn
+        .prepare("SELECT report_json FROM profile_ab_tests WHERE profile_id = ?1 ORDER BY created_at DESC")
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([profile_id], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut reports = Vec::new();
+    for row in rows {
+        let text = row.map_err(|e| format!("Row error: {}", e))?;
+        if let Ok(report) = serde_json::from_str(&text) {
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_follow_profile_changes() {
+        let path = std::env::temp_dir().join(format!("panther-versions-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                     VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana.\nBe brief.', '{}');
+                 UPDATE prompt_profiles SET photo_url = 'a.png' WHERE id = 'p1';
+                 UPDATE prompt_profiles SET persona_prompt = 'You are Ana.\nBe thorough.' WHERE id = 'p1';
+                 INSERT INTO chat_messages (id, profile_id, role, content) VALUES ('m1', 'p1', 'assistant', 'Hi');",
+            )
+            .unwrap();
+
+            let versions = list(&conn, "p1").unwrap();
+            assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<_>>(), [2, 1]);
+            let stamped: String = conn
+                .query_row("SELECT profile_version_id FROM chat_messages WHERE id = 'm1'", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(stamped, versions[0].id);
+
+            let changes = diff(&conn, &versions[1].id, &versions[0].id).unwrap().changes;
+            assert_eq!(changes.len(), 1);
+            assert_eq!(
+                changes[0].lines,
+                [
+                    DiffLine { kind: DiffKind::Same, text: "You are Ana.".to_string() },
+                    DiffLine { kind: DiffKind::Removed, text: "Be brief.".to_string() },
+                    DiffLine { kind: DiffKind::Added, text: "Be thorough.".to_string() },
+                ]
+            );
+
+            let restored = rollback(&conn, "p1", &versions[1].id).unwrap();
+            assert_eq!(restored.version, 3);
+            assert_eq!(restored.persona_prompt, "You are Ana.\nBe brief.");
+            assert_eq!(restored.note.as_deref(), Some("Rolled back to v1"));
+        }
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,408 @@
+// Prompt pipeline middleware.
+// Every packet sent through `complete_resolving_hybrid` runs through an ordered list of
+// middleware before it reaches a provider adapter; the response runs back through the same
+// list in reverse order. The stages that used to be hard-coded in the resolver are built-ins
+// here, and additional stages can be registered at startup without touching the resolver.
+//
+// Pipeline order is configured (first match wins):
+// 1. Profile: `params_json.prompt_pipeline`
+// 2. Provider: `provider_metadata_json.prompt_pipeline` (hybrid or plain provider account)
+// 3. `DEFAULT_PIPELINE`
+//
+// A profile pipeline cannot drop the stages the hybrid provider requires (`privacy_transform`
+// when its privacy transform is enabled, `safety_control_block` when required): they are kept
+// from the provider pipeline, ahead of every stage the provider pipeline runs after them.
+//
+// Each entry is either a middleware name or an object:
+//   { "name": "input_preprocess", "enabled": true, "config": { ... } }
+
+use crate::commands_settings::{load_settings_sync, read_global_prompt_from_file};
+use crate::db::Database;
+use crate::prompt_transform;
+use crate::provider_resolver::{
+    apply_input_preprocess, apply_privacy_transform, apply_safety_control_block_requirement,
+    ResolvedProviderChain,
+};
+use crate::types::{NormalizedResponse, PromptPacket};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Built-in stage names, in the order the resolver has always applied them.
+pub const DEFAULT_PIPELINE: &[&str] = &[
+    "global_prompt_file",
+    "input_preprocess",
+    "privacy_transform",
+    "safety_control_block",
+    "polymorphic_transform",
+    "obfuscation",
+];
+
+/// Per-call state shared by all middleware in a pipeline run.
+pub struct PipelineContext<'a> {
+    pub db: &'a Database,
+    pub chain: &'a ResolvedProviderChain,
+    /// provider_type of the first provider the packet will be sent to.
+    pub target_provider_type: &'a str,
+}
+
+/// A single stage of the prompt pipeline.
+///
+/// `before_send` may rewrite any part of the packet; returning an error aborts the call.
+/// `after_receive` may post-process the provider response (e.g. undo a substitution).
+pub trait PromptMiddleware: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn before_send(&self, packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String>;
+
+    fn after_receive(&self, response: NormalizedResponse, _ctx: &PipelineContext) -> NormalizedResponse {
+        response
+    }
+}
+
+/// Builds a middleware instance from its `config` object in the pipeline spec.
+pub type MiddlewareFactory = Arc<dyn Fn(&Value) -> Box<dyn PromptMiddleware> + Send + Sync>;
+
+fn registry() -> &'static RwLock<HashMap<String, MiddlewareFactory>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, MiddlewareFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut map: HashMap<String, MiddlewareFactory> = HashMap::new();
+        map.insert("global_prompt_file".to_string(), Arc::new(|_| Box::new(GlobalPromptFile)));
+        map.insert("input_preprocess".to_string(), Arc::new(|_| Box::new(InputPreprocess)));
+        map.insert("privacy_transform".to_string(), Arc::new(|_| Box::new(PrivacyTransform)));
+        map.insert("safety_control_block".to_string(), Arc::new(|_| Box::new(SafetyControlBlock)));
+        map.insert("polymorphic_transform".to_string(), Arc::new(|_| Box::new(PolymorphicTransform)));
+        map.insert("obfuscation".to_string(), Arc::new(|_| Box::new(Obfuscation)));
+        RwLock::new(map)
+    })
+}
+
+/// Register (or replace) a middleware under `name` so pipeline specs can reference it.
+#[allow(dead_code)]
+pub fn register_middleware<F>(name: &str, factory: F)
+where
+    F: Fn(&Value) -> Box<dyn PromptMiddleware> + Send + Sync + 'static,
+{
+    if let Ok(mut map) = registry().write() {
+        map.insert(name.to_string(), Arc::new(factory));
+    }
+}
+
+/// Names of all registered middleware (built-ins and custom), sorted.
+pub fn list_middleware() -> Vec<String> {
+    let mut names: Vec<String> = registry()
+        .read()
+        .map(|m| m.keys().cloned().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// One entry of a pipeline spec after parsing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineEntry {
+    pub name: String,
+    pub config: Value,
+}
+
+/// Parse a `prompt_pipeline` array. Disabled entries are dropped. Returns None when `spec` is not an array.
+pub fn parse_pipeline_spec(spec: &Value) -> Option<Vec<PipelineEntry>> {
+    let arr = spec.as_array()?;
+    let entries = arr
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(name) => Some(PipelineEntry {
+                name: name.clone(),
+                config: serde_json::json!({}),
+            }),
+            Value::Object(o) => {
+                let name = o.get("name").and_then(|v| v.as_str())?;
+                let enabled = o.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+                if !enabled {
+                    return None;
+                }
+                Some(PipelineEntry {
+                    name: name.to_string(),
+                    config: o.get("config").cloned().unwrap_or_else(|| serde_json::json!({})),
+                })
+            }
+            _ => None,
+        })
+        .collect();
+    Some(entries)
+}
+
+/// Resolve the pipeline spec for a call: profile params override provider metadata, which overrides the
+/// default. Stages the provider requires are kept in a profile pipeline (see `with_mandated_stages`).
+pub fn resolve_pipeline_spec(packet: &PromptPacket, chain: &ResolvedProviderChain) -> Vec<PipelineEntry> {
+    let provider = chain
+        .prompt_pipeline
+        .as_ref()
+        .and_then(parse_pipeline_spec)
+        .unwrap_or_else(|| {
+            DEFAULT_PIPELINE
+                .iter()
+                .map(|name| PipelineEntry {
+                    name: name.to_string(),
+                    config: serde_json::json!({}),
+                })
+                .collect()
+        });
+    match packet.params_json.get("prompt_pipeline").and_then(parse_pipeline_spec) {
+        Some(profile) => with_mandated_stages(profile, &provider, &mandated_stages(chain)),
+        None => provider,
+    }
+}
+
+/// Stages the provider configuration requires on every call.
+fn mandated_stages(chain: &ResolvedProviderChain) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    if chain.privacy.enabled {
+        names.push("privacy_transform");
+    }
+    if chain.require_safety_control_block {
+        names.push("safety_control_block");
+    }
+    names
+}
+
+/// Merge a profile pipeline with the `mandated` stages of the provider pipeline.
+///
+/// The profile may add, drop and reorder any other stage; its own entries for mandated stages are
+/// ignored. Each mandated stage keeps the provider's config and is placed before the first profile
+/// stage that comes after it in the provider pipeline, so it runs no later than there.
+fn with_mandated_stages(profile: Vec<PipelineEntry>, provider: &[PipelineEntry], mandated: &[&str]) -> Vec<PipelineEntry> {
+    let position = |name: &str| provider.iter().position(|e| e.name == name);
+    let mut pending: Vec<(usize, &PipelineEntry)> = provider
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| mandated.contains(&e.name.as_str()))
+        .collect();
+    let mut merged = Vec::with_capacity(profile.len() + pending.len());
+    for entry in profile.into_iter().filter(|e| !mandated.contains(&e.name.as_str())) {
+        if let Some(index) = position(&entry.name) {
+            let (due, later): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(i, _)| *i < index);
+            merged.extend(due.into_iter().map(|(_, e)| e.clone()));
+            pending = later;
+        }
+        merged.push(entry);
+    }
+    merged.extend(pending.into_iter().map(|(_, e)| e.clone()));
+    merged
+}
+
+/// An instantiated, ordered middleware list.
+pub struct PromptPipeline {
+    stages: Vec<Box<dyn PromptMiddleware>>,
+}
+
+impl PromptPipeline {
+    pub fn build(entries: &[PipelineEntry]) -> Result<Self, String> {
+        let map = registry()
+            .read()
+            .map_err(|e| format!("Prompt middleware registry lock error: {}", e))?;
+        let mut stages = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let factory = map
+                .get(&entry.name)
+                .ok_or_else(|| format!("Unknown prompt middleware '{}' in prompt_pipeline", entry.name))?;
+            stages.push(factory(&entry.config));
+        }
+        Ok(Self { stages })
+    }
+
+    pub fn before_send(&self, packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        let mut packet = packet;
+        for stage in &self.stages {
+            packet = stage
+                .before_send(packet, ctx)
+                .map_err(|e| format!("Prompt middleware '{}' failed: {}", stage.name(), e))?;
+        }
+        Ok(packet)
+    }
+
+    pub fn after_receive(&self, response: NormalizedResponse, ctx: &PipelineContext) -> NormalizedResponse {
+        self.stages
+            .iter()
+            .rev()
+            .fold(response, |resp, stage| stage.after_receive(resp, ctx))
+    }
+}
+
+// ============================================================================
+// Built-in middleware
+// ============================================================================
+
+/// Prepends the global system prompt from the file linked in app settings.
+struct GlobalPromptFile;
+
+impl PromptMiddleware for GlobalPromptFile {
+    fn name(&self) -> &str {
+        "global_prompt_file"
+    }
+
+    fn before_send(&self, mut packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        let settings = load_settings_sync(ctx.db);
+        if let Some(global) = read_global_prompt_from_file(&settings) {
+            if !global.trim().is_empty() {
+                packet.global_instructions = Some(match &packet.global_instructions {
+                    Some(existing) => format!("{}\n\n---\n\n{}", global.trim(), existing),
+                    None => global.trim().to_string(),
+                });
+            }
+        }
+        Ok(packet)
+    }
+}
+
+/// Hybrid `input_preprocess` normalization (BOM, control chars, whitespace, punctuation, max_chars).
+struct InputPreprocess;
+
+impl PromptMiddleware for InputPreprocess {
+    fn name(&self) -> &str {
+        "input_preprocess"
+    }
+
+    fn before_send(&self, packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        Ok(apply_input_preprocess(&packet, &ctx.chain.preprocess, ctx.chain.privacy.scrub_context))
+    }
+}
+
+/// Hybrid `privacy_transform` scrubbing of PII and secrets.
+struct PrivacyTransform;
+
+impl PromptMiddleware for PrivacyTransform {
+    fn name(&self) -> &str {
+        "privacy_transform"
+    }
+
+    fn before_send(&self, packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        Ok(apply_privacy_transform(&packet, &ctx.chain.privacy))
+    }
+}
+
+/// Appends the SAFETY_CONTROL_BLOCK requirement when the hybrid provider asks for it.
+struct SafetyControlBlock;
+
+impl PromptMiddleware for SafetyControlBlock {
+    fn name(&self) -> &str {
+        "safety_control_block"
+    }
+
+    fn before_send(&self, packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        Ok(apply_safety_control_block_requirement(&packet, ctx.chain.require_safety_control_block))
+    }
+}
+
+/// Provider-specific formatting, PII masking and chunking from `prompt_transform`.
+struct PolymorphicTransform;
+
+impl PromptMiddleware for PolymorphicTransform {
+    fn name(&self) -> &str {
+        "polymorphic_transform"
+    }
+
+    fn before_send(&self, packet: PromptPacket, ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        let transform_config = prompt_transform::TransformConfig {
+            enabled: true,
+            sensitivity: 0.5,
+            mask_pii: ctx.chain.privacy.scrub_pii,
+            context_window: 4096,
+            target_provider: prompt_transform::ProviderType::from_provider_type(ctx.target_provider_type),
+        };
+        Ok(prompt_transform::apply_polymorphic_transform(packet, &transform_config))
+    }
+}
+
+/// Semantic obfuscation of system instructions, keyed deterministically on packet content.
+struct Obfuscation;
+
+impl PromptMiddleware for Obfuscation {
+    fn name(&self) -> &str {
+        "obfuscation"
+    }
+
+    fn before_send(&self, packet: PromptPacket, _ctx: &PipelineContext) -> Result<PromptPacket, String> {
+        let obfuscation_key: Vec<u8> = [
+            packet.user_message.as_bytes(),
+            packet.persona_instructions.as_bytes(),
+        ]
+        .concat();
+        Ok(prompt_transform::synthesize_obfuscated_instructions(packet, &obfuscation_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pipeline_spec_mixed_entries() {
+        let spec = serde_json::json!([
+            "input_preprocess",
+            { "name": "privacy_transform", "enabled": false },
+            { "name": "glossary", "config": { "terms": { "LLM": "large language model" } } },
+            42
+        ]);
+        let entries = parse_pipeline_spec(&spec).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "input_preprocess");
+        assert_eq!(entries[1].name, "glossary");
+        assert_eq!(entries[1].config["terms"]["LLM"], "large language model");
+    }
+
+    #[test]
+    fn test_profile_pipeline_keeps_mandated_stages() {
+        let names = |entries: &[PipelineEntry]| entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        let provider = parse_pipeline_spec(&serde_json::json!(DEFAULT_PIPELINE)).unwrap();
+        let profile = parse_pipeline_spec(&serde_json::json!([
+            "obfuscation",
+            { "name": "privacy_transform", "enabled": false },
+            "glossary",
+            "input_preprocess"
+        ]))
+        .unwrap();
+        let merged = with_mandated_stages(profile.clone(), &provider, &["privacy_transform", "safety_control_block"]);
+        assert_eq!(
+            names(&merged),
+            ["privacy_transform", "safety_control_block", "obfuscation", "glossary", "input_preprocess"]
+        );
+
+        // Without requirements the profile pipeline is used as-is
+        assert_eq!(names(&with_mandated_stages(profile.clone(), &provider, &[])), names(&profile));
+
+        // Stages the provider pipeline runs after every profile stage are appended
+        let profile = parse_pipeline_spec(&serde_json::json!(["global_prompt_file"])).unwrap();
+        assert_eq!(
+            names(&with_mandated_stages(profile, &provider, &["safety_control_block"])),
+            ["global_prompt_file", "safety_control_block"]
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_unknown_middleware() {
+        let entries = vec![PipelineEntry {
+            name: "does_not_exist".to_string(),
+            config: serde_json::json!({}),
+        }];
+        assert!(PromptPipeline::build(&entries).is_err());
+    }
+
+    #[test]
+    fn test_registered_middleware_is_available() {
+        struct Upper;
+        impl PromptMiddleware for Upper {
+            fn name(&self) -> &str {
+                "test_upper"
+            }
+            fn before_send(&self, mut packet: PromptPacket, _ctx: &PipelineContext) -> Result<PromptPacket, String> {
+                packet.user_message = packet.user_message.to_uppercase();
+                Ok(packet)
+            }
+        }
+        register_middleware("test_upper", |_| Box::new(Upper));
+        assert!(list_middleware().contains(&"test_upper".to_string()));
+        let entries = parse_pipeline_spec(&serde_json::json!(["test_upper"])).unwrap();
+        assert!(PromptPipeline::build(&entries).is_ok());
+    }
+}
@@ -1,6 +1,5 @@
-use crate::commands_settings::load_settings_sync;
 use crate::db::Database;
-use crate::prompt_transform;
+use crate::prompt_pipeline::{resolve_pipeline_spec, PipelineContext, PromptPipeline};
 use crate::providers::get_adapter;
 use crate::types::{NormalizedResponse, PromptPacket, ProviderAccount};
 use regex::Regex;
@@ -26,6 +25,9 @@ pub struct ResolvedProviderChain {
     pub local_first: bool,
     /// When set (e.g. hybrid provider with primary_model in metadata), use this for cloud model instead of profile's model_name.
     pub cloud_model_override: Option<String>,
+    /// `prompt_pipeline` from provider metadata (see `prompt_pipeline`); profile params take precedence
+    /// except for the stages the provider requires.
+    pub prompt_pipeline: Option<Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -302,7 +304,7 @@ fn truncate_head_tail(s: &str, max_chars: usize) -> String {
     format!("{}{}{}", take_first_chars(s, head_n), marker, take_last_chars(s, tail_n))
 }
 
-fn apply_input_preprocess(packet: &PromptPacket, preprocess: &HybridInputPreprocess, scrub_context: bool) -> PromptPacket {
+pub(crate) fn apply_input_preprocess(packet: &PromptPacket, preprocess: &HybridInputPreprocess, scrub_context: bool) -> PromptPacket {
     if !preprocess.enabled {
         return packet.clone();
     }
@@ -407,7 +409,7 @@ fn scrub_text_for_privacy(text: &str, privacy: &HybridPrivacyTransform) -> Strin
     out
 }
 
-fn apply_privacy_transform(packet: &PromptPacket, privacy: &HybridPrivacyTransform) -> PromptPacket {
+pub(crate) fn apply_privacy_transform(packet: &PromptPacket, privacy: &HybridPrivacyTransform) -> PromptPacket {
     if !privacy.enabled {
         return packet.clone();
     }
@@ -441,7 +443,7 @@ fn packet_for_cloud_fallback(packet: &PromptPacket) -> PromptPacket {
     }
 }
 
-fn apply_safety_control_block_requirement(packet: &PromptPacket, enabled: bool) -> PromptPacket {
+pub(crate) fn apply_safety_control_block_requirement(packet: &PromptPacket, enabled: bool) -> PromptPacket {
     if !enabled {
         return packet.clone();
     }
@@ -492,6 +494,11 @@ pub fn load_provider_account(db: &Database, provider_id: &str) -> Result<Provide
 pub fn resolve_provider_chain(db: &Database, provider_id: &str) -> Result<ResolvedProviderChain, String> {
     let provider = load_provider_account(db, provider_id)?;
     if provider.provider_type != "hybrid" {
+        let prompt_pipeline = provider
+            .provider_metadata_json
+            .as_ref()
+            .and_then(|m| m.get("prompt_pipeline"))
+            .cloned();
         return Ok(ResolvedProviderChain {
             primary: provider,
             fallback: None,
@@ -501,6 +508,7 @@ pub fn resolve_provider_chain(db: &Database, provider_id: &str) -> Result<Resolv
             require_safety_control_block: false,
             local_first: false,
             cloud_model_override: None,
+            prompt_pipeline,
         });
     }
 
@@ -539,6 +547,8 @@ pub fn resolve_provider_chain(db: &Database, provider_id: &str) -> Result<Resolv
         .map(|s| s.to_string())
         .filter(|s| !s.trim().is_empty());
 
+    let prompt_pipeline = meta.get("prompt_pipeline").cloned();
+
     Ok(ResolvedProviderChain {
         primary,
         fallback: Some((fallback_provider, fallback_model)),
@@ -548,6 +558,7 @@ pub fn resolve_provider_chain(db: &Database, provider_id: &str) -> Result<Resolv
         require_safety_control_block,
         local_first,
         cloud_model_override,
+        prompt_pipeline,
     })
 }
 
@@ -593,58 +604,22 @@ pub async fn complete_resolving_hybrid(
 ) -> Result<(NormalizedResponse, ProviderAccount, String), String> {
     let chain = resolve_provider_chain(db, provider_id)?;
 
-    // Prepend global system prompt from linked file (applies to all LLM calls)
-    let packet = {
-        let settings = load_settings_sync(db);
-        if let Some(global) = crate::commands_settings::read_global_prompt_from_file(&settings) {
-            if !global.trim().is_empty() {
-                let merged = match &packet.global_instructions {
-                    Some(existing) => format!("{}\n\n---\n\n{}", global.trim(), existing),
-                    None => global.trim().to_string(),
-                };
-                PromptPacket {
-                    global_instructions: Some(merged),
-                    persona_instructions: packet.persona_instructions.clone(),
-                    user_message: packet.user_message.clone(),
-                    conversation_context: packet.conversation_context.clone(),
-                    params_json: packet.params_json.clone(),
-                    stream: packet.stream,
-                }
-            } else {
-                packet.clone()
-            }
-        } else {
-            packet.clone()
-        }
-    };
-
-    let preprocess_scrub_context = chain.privacy.scrub_context;
-    let packet_preprocessed = apply_input_preprocess(&packet, &chain.preprocess, preprocess_scrub_context);
-    let packet_privacy = apply_privacy_transform(&packet_preprocessed, &chain.privacy);
-    let packet_after_safety = apply_safety_control_block_requirement(&packet_privacy, chain.require_safety_control_block);
-
-    // Determine provider type for polymorphic transform (first provider we'll try)
+    // Provider type for provider-aware middleware (first provider we'll try)
     let first_provider_type = if chain.local_first && chain.fallback.is_some() {
         chain.fallback.as_ref().unwrap().0.provider_type.as_str()
     } else {
         chain.primary.provider_type.as_str()
     };
-    let transform_config = prompt_transform::TransformConfig {
-        enabled: true,
-        sensitivity: 0.5,
-        mask_pii: chain.privacy.scrub_pii,
-        context_window: 4096,
-        target_provider: prompt_transform::ProviderType::from_provider_type(first_provider_type),
-    };
-    let packet_after_poly = prompt_transform::apply_polymorphic_transform(packet_after_safety, &transform_config);
 
-    // Deterministic obfuscation key from packet content
-    let obfuscation_key: Vec<u8> = [
-        packet_after_poly.user_message.as_bytes(),
-        packet_after_poly.persona_instructions.as_bytes(),
-    ]
-    .concat();
-    let packet_to_send = prompt_transform::synthesize_obfuscated_instructions(packet_after_poly, &obfuscation_key);
+    // Run the configured middleware pipeline (global prompt, preprocess, privacy, safety,
+    // polymorphic transform, obfuscation by default; see prompt_pipeline.rs)
+    let pipeline = PromptPipeline::build(&resolve_pipeline_spec(packet, &chain))?;
+    let pipeline_ctx = PipelineContext {
+        db,
+        chain: &chain,
+        target_provider_type: first_provider_type,
+    };
+    let packet_to_send = pipeline.before_send(packet.clone(), &pipeline_ctx)?;
 
     let fallback_allowed = safety_gateway_allows_fallback(&packet.user_message);
 
@@ -700,12 +675,12 @@ pub async fn complete_resolving_hybrid(
                     if let Ok(second_resp) =
                         complete_with_timeout(second_prov, second_mod, &cloud_packet, timeout_secs).await
                     {
-                        return Ok((second_resp, second_prov.clone(), second_mod.clone()));
+                        return Ok((pipeline.after_receive(second_resp, &pipeline_ctx), second_prov.clone(), second_mod.clone()));
                     }
                 }
             }
 
-            Ok((resp, first_provider, first_model))
+            Ok((pipeline.after_receive(resp, &pipeline_ctx), first_provider, first_model))
         }
         Err(first_err) => {
             // Error/timeout: try second provider with minimal packet to save tokens
@@ -715,7 +690,7 @@ pub async fn complete_resolving_hybrid(
                     if let Ok(second_resp) =
                         complete_with_timeout(second_prov, second_mod, &cloud_packet, timeout_secs).await
                     {
-                        return Ok((second_resp, second_prov.clone(), second_mod.clone()));
+                        return Ok((pipeline.after_receive(second_resp, &pipeline_ctx), second_prov.clone(), second_mod.clone()));
                     }
                 }
             }
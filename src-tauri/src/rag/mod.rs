@@ -10,6 +10,7 @@
 use crate::db::Database;
 use anyhow::Result;
 use rusqlite::params;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 pub struct RetrievedChunk {
@@ -108,3 +109,54 @@ pub fn retrieve_simple_context_for_project(
     })
 }
 
+fn query_terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Keyword retrieval: the project's chunks sharing the most distinct terms with `query`.
+/// Used where there is a concrete question (e.g. the MCP `query_project_rag` tool) rather than a run prompt.
+pub fn retrieve_context_for_query(
+    db: &Database,
+    project_id: &str,
+    query: &str,
+    limit: usize,
+) -> Result<RagContext> {
+    let terms = query_terms(query);
+    let chunks: Vec<RetrievedChunk> = {
+        let conn = db.get_connection();
+        let conn_guard = conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock error: {}", e))?;
+        let mut stmt = conn_guard.prepare(
+            "SELECT id, source_id, chunk_index, text FROM document_chunks
+             WHERE project_id = ?1 ORDER BY created_at DESC, chunk_index ASC",
+        )?;
+        let rows = stmt.query_map(params![project_id], |row| {
+            Ok(RetrievedChunk {
+                id: row.get(0)?,
+                source_id: row.get(1)?,
+                chunk_index: row.get(2)?,
+                text: row.get(3)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    let mut scored: Vec<(usize, RetrievedChunk)> = chunks
+        .into_iter()
+        .map(|chunk| (query_terms(&chunk.text).intersection(&terms).count(), chunk))
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    // Stable sort keeps newest first among equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    let chunks: Vec<RetrievedChunk> = scored.into_iter().take(limit).map(|(_, c)| c).collect();
+    let combined_text = chunks
+        .iter()
+        .map(|c| format!("[source:{} chunk:{}]\n{}\n\n", c.source_id, c.chunk_index, c.text))
+        .collect();
+    Ok(RagContext { combined_text, chunks })
+}
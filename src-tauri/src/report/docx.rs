@@ -0,0 +1,176 @@
+// DOCX report: a minimal Office Open XML package (content types, relationships, styles, document).
+
+use super::{blocks, citation_label, Block, Report, Table};
+use std::io::{Cursor, Write};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+</Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>"#;
+
+const DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+</Relationships>"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault>
+<w:pPrDefault><w:pPr><w:spacing w:after="120" w:line="276" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults>
+<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+<w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:rPr><w:b/><w:sz w:val="48"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Subtitle"><w:name w:val="Subtitle"/><w:basedOn w:val="Normal"/><w:rPr><w:color w:val="616E7C"/><w:sz w:val="18"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="360"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="200"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="24"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Code"><w:name w:val="Code"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="0"/></w:pPr><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas"/><w:sz w:val="18"/></w:rPr></w:style>
+<w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders>
+<w:top w:val="single" w:sz="4" w:color="CBD2D9"/><w:left w:val="single" w:sz="4" w:color="CBD2D9"/><w:bottom w:val="single" w:sz="4" w:color="CBD2D9"/><w:right w:val="single" w:sz="4" w:color="CBD2D9"/>
+<w:insideH w:val="single" w:sz="4" w:color="CBD2D9"/><w:insideV w:val="single" w:sz="4" w:color="CBD2D9"/></w:tblBorders></w:tblPr></w:style>
+</w:styles>"#;
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            // Control characters are not allowed in XML 1.0
+            c if (c as u32) < 0x20 && c != '\t' && c != '\n' && c != '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn run(text: &str, bold: bool) -> String {
+    format!(
+        r#"<w:r>{}<w:t xml:space="preserve">{}</w:t></w:r>"#,
+        if bold { "<w:rPr><w:b/></w:rPr>" } else { "" },
+        escape(text)
+    )
+}
+
+fn paragraph(style: Option<&str>, runs: &str) -> String {
+    match style {
+        Some(style) => format!(r#"<w:p><w:pPr><w:pStyle w:val="{}"/></w:pPr>{}</w:p>"#, style, runs),
+        None => format!("<w:p>{}</w:p>", runs),
+    }
+}
+
+fn markdown(text: &str, body: &mut String) {
+    for block in blocks(text) {
+        match block {
+            Block::Heading(_, t) => body.push_str(&paragraph(None, &run(&t, true))),
+            Block::Paragraph(t) => body.push_str(&paragraph(None, &run(&t, false))),
+            Block::Bullet(t) => body.push_str(&format!(
+                r#"<w:p><w:pPr><w:ind w:left="360" w:hanging="240"/></w:pPr>{}</w:p>"#,
+                run(&format!("• {}", t), false)
+            )),
+            Block::Code(t) => {
+                for line in t.lines() {
+                    body.push_str(&paragraph(Some("Code"), &run(line, false)));
+                }
+            }
+        }
+    }
+}
+
+fn table(table: &Table, body: &mut String) {
+    body.push_str(r#"<w:tbl><w:tblPr><w:tblStyle w:val="TableGrid"/><w:tblW w:w="5000" w:type="pct"/></w:tblPr>"#);
+    let rows = std::iter::once((&table.headers, true)).chain(table.rows.iter().map(|r| (r, false)));
+    for (row, bold) in rows {
+        body.push_str("<w:tr>");
+        for cell in row {
+            body.push_str(&format!("<w:tc>{}</w:tc>", paragraph(None, &run(cell, bold))));
+        }
+        body.push_str("</w:tr>");
+    }
+    body.push_str("</w:tbl>");
+    body.push_str(&paragraph(None, ""));
+}
+
+fn document_xml(report: &Report) -> String {
+    let mut body = String::new();
+    body.push_str(&paragraph(Some("Title"), &run(&report.title, false)));
+    body.push_str(&paragraph(Some("Subtitle"), &run(&format!("{} · {}", report.kind, report.created_at), false)));
+    if let Some(question) = &report.question {
+        body.push_str(&paragraph(None, &format!("{}{}", run("Question: ", true), run(question, false))));
+    }
+    if !report.participants.is_empty() {
+        body.push_str(&paragraph(Some("Heading1"), &run("Participants", false)));
+        for p in &report.participants {
+            let model = if p.model_name.is_empty() { String::new() } else { format!(" ({})", p.model_name) };
+            body.push_str(&paragraph(None, &format!("{}{}", run(&p.name, true), run(&model, false))));
+        }
+    }
+
+    if let Some(comparison) = &report.comparison {
+        body.push_str(&paragraph(Some("Heading1"), &run("Comparison", false)));
+        table(comparison, &mut body);
+    }
+
+    for section in &report.sections {
+        body.push_str(&paragraph(Some("Heading1"), &run(&section.title, false)));
+        for entry in &section.entries {
+            let tokens = entry.total_tokens.map(|t| format!("  ({} tokens)", t)).unwrap_or_default();
+            body.push_str(&paragraph(Some("Heading2"), &format!("{}{}", run(&entry.speaker, false), run(&tokens, false))));
+            markdown(&entry.text, &mut body);
+            if !entry.citations.is_empty() {
+                let refs: Vec<String> = entry.citations.iter().map(|i| format!("[{}]", i + 1)).collect();
+                body.push_str(&paragraph(None, &run(&format!("Sources: {}", refs.join(" ")), false)));
+            }
+        }
+    }
+
+    if let Some(conclusion) = &report.conclusion {
+        body.push_str(&paragraph(Some("Heading1"), &run("Conclusion", false)));
+        markdown(conclusion, &mut body);
+    }
+
+    if !report.usage.is_empty() {
+        body.push_str(&paragraph(Some("Heading1"), &run("Token usage", false)));
+        table(&report.usage_table(), &mut body);
+    }
+
+    if !report.citations.is_empty() {
+        body.push_str(&paragraph(Some("Heading1"), &run("Citations", false)));
+        for (i, c) in report.citations.iter().enumerate() {
+            body.push_str(&paragraph(None, &run(&format!("[{}] {}", i + 1, citation_label(c)), true)));
+            body.push_str(&format!(r#"<w:p><w:pPr><w:ind w:left="360"/></w:pPr>{}</w:p>"#, run(&c.text, false)));
+        }
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1134" w:right="1134" w:bottom="1134" w:left="1134" w:header="708" w:footer="708" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body
+    )
+}
+
+pub fn render(report: &Report) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", ROOT_RELS.to_string()),
+        ("word/_rels/document.xml.rels", DOCUMENT_RELS.to_string()),
+        ("word/styles.xml", STYLES.to_string()),
+        ("word/document.xml", document_xml(report)),
+    ];
+    for (name, content) in parts {
+        zip.start_file(name, options).map_err(|e| format!("Failed to write DOCX: {}", e))?;
+        zip.write_all(content.as_bytes()).map_err(|e| format!("Failed to write DOCX: {}", e))?;
+    }
+    let cursor = zip.finish().map_err(|e| format!("Failed to write DOCX: {}", e))?;
+    Ok(cursor.into_inner())
+}
@@ -0,0 +1,208 @@
+// Self-contained HTML report: inline CSS, avatars embedded as data: URLs, no external requests.
+
+use super::{blocks, citation_label, Block, Participant, Report, Table};
+use base64::Engine;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Roboto, Helvetica, Arial, sans-serif; color: #1f2933; max-width: 860px; margin: 40px auto; padding: 0 24px; line-height: 1.55; }
+header { border-bottom: 2px solid #e4e7eb; margin-bottom: 24px; }
+h1 { margin: 0 0 6px; font-size: 28px; }
+h2 { margin-top: 36px; font-size: 21px; border-bottom: 1px solid #e4e7eb; padding-bottom: 4px; }
+.meta { color: #616e7c; font-size: 13px; margin-bottom: 16px; }
+.question { background: #f5f7fa; border-left: 4px solid #3e7bfa; padding: 12px 16px; margin: 16px 0; }
+.participants { display: flex; flex-wrap: wrap; gap: 12px; margin: 16px 0; }
+.participant { display: flex; align-items: center; gap: 8px; background: #f5f7fa; border-radius: 20px; padding: 4px 12px 4px 4px; font-size: 13px; }
+.avatar { width: 36px; height: 36px; border-radius: 50%; object-fit: cover; flex-shrink: 0; display: inline-flex; align-items: center; justify-content: center; background: #3e7bfa; color: #fff; font-weight: 600; font-size: 14px; }
+.entry { display: flex; gap: 12px; margin: 16px 0; }
+.bubble { flex: 1; background: #fff; border: 1px solid #e4e7eb; border-radius: 8px; padding: 10px 14px; }
+.speaker { font-weight: 600; margin-bottom: 4px; }
+.tokens { color: #9aa5b1; font-size: 12px; font-weight: normal; margin-left: 8px; }
+.bubble p { margin: 6px 0; }
+pre { background: #f5f7fa; padding: 10px; border-radius: 4px; overflow-x: auto; font-size: 13px; }
+table { border-collapse: collapse; width: 100%; margin: 12px 0; font-size: 14px; }
+th, td { border: 1px solid #e4e7eb; padding: 6px 10px; text-align: left; }
+th { background: #f5f7fa; }
+tr.total td { font-weight: 600; }
+.cite { font-size: 12px; color: #3e7bfa; }
+ol.citations li { margin-bottom: 8px; font-size: 13px; }
+ol.citations blockquote { margin: 4px 0 0; color: #616e7c; }
+footer { margin-top: 48px; color: #9aa5b1; font-size: 12px; text-align: center; }
+"#;
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn initials(name: &str) -> String {
+    let letters: String = name
+        .split_whitespace()
+        .filter_map(|w| w.chars().next())
+        .take(2)
+        .collect();
+    if letters.is_empty() {
+        "?".to_string()
+    } else {
+        letters.to_uppercase()
+    }
+}
+
+/// Photo as an embeddable data: URL. Local files are inlined; remote URLs are not fetched
+/// (the report must not depend on the network) and fall back to initials.
+pub(crate) fn avatar_data_url(photo_url: &str) -> Option<String> {
+    if photo_url.starts_with("data:image/") {
+        return Some(photo_url.to_string());
+    }
+    if photo_url.starts_with("http://") || photo_url.starts_with("https://") {
+        return None;
+    }
+    let path = photo_url.strip_prefix("file://").unwrap_or(photo_url);
+    let mime = match std::path::Path::new(path).extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes)))
+}
+
+fn avatar(name: &str, participant: Option<&Participant>) -> String {
+    match participant.and_then(|p| p.photo_url.as_deref()).and_then(avatar_data_url) {
+        Some(url) => format!(r#"<img class="avatar" src="{}" alt="{}">"#, escape(&url), escape(name)),
+        None => format!(r#"<span class="avatar">{}</span>"#, escape(&initials(name))),
+    }
+}
+
+fn markdown(text: &str, out: &mut String) {
+    let mut in_list = false;
+    for block in blocks(text) {
+        let is_bullet = matches!(block, Block::Bullet(_));
+        if in_list && !is_bullet {
+            out.push_str("</ul>");
+        } else if !in_list && is_bullet {
+            out.push_str("<ul>");
+        }
+        in_list = is_bullet;
+        match block {
+            Block::Heading(level, t) => out.push_str(&format!("<h{0}>{1}</h{0}>", (level + 2).min(6), escape(&t))),
+            Block::Paragraph(t) => out.push_str(&format!("<p>{}</p>", escape(&t))),
+            Block::Bullet(t) => out.push_str(&format!("<li>{}</li>", escape(&t))),
+            Block::Code(t) => out.push_str(&format!("<pre>{}</pre>", escape(&t))),
+        }
+    }
+    if in_list {
+        out.push_str("</ul>");
+    }
+}
+
+fn table(table: &Table, out: &mut String, total_row: bool) {
+    out.push_str("<table><thead><tr>");
+    for h in &table.headers {
+        out.push_str(&format!("<th>{}</th>", escape(h)));
+    }
+    out.push_str("</tr></thead><tbody>");
+    for (i, row) in table.rows.iter().enumerate() {
+        let class = if total_row && i + 1 == table.rows.len() { r#" class="total""# } else { "" };
+        out.push_str(&format!("<tr{}>", class));
+        for cell in row {
+            out.push_str(&format!("<td>{}</td>", escape(cell)));
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</tbody></table>");
+}
+
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">");
+    out.push_str(&format!("<title>{}</title><style>{}</style></head><body>", escape(&report.title), STYLE));
+
+    out.push_str(&format!("<header><h1>{}</h1>", escape(&report.title)));
+    out.push_str(&format!(r#"<div class="meta">{} · {}</div></header>"#, escape(&report.kind), escape(&report.created_at)));
+    if let Some(question) = &report.question {
+        out.push_str(&format!(r#"<div class="question"><strong>Question:</strong> {}</div>"#, escape(question)));
+    }
+    if !report.participants.is_empty() {
+        out.push_str(r#"<div class="participants">"#);
+        for p in &report.participants {
+            out.push_str(&format!(
+                r#"<div class="participant">{}<span><strong>{}</strong><br>{}</span></div>"#,
+                avatar(&p.name, Some(p)),
+                escape(&p.name),
+                escape(&p.model_name)
+            ));
+        }
+        out.push_str("</div>");
+    }
+
+    if let Some(comparison) = &report.comparison {
+        out.push_str("<h2>Comparison</h2>");
+        table(comparison, &mut out, false);
+    }
+
+    for section in &report.sections {
+        out.push_str(&format!("<h2>{}</h2>", escape(&section.title)));
+        for entry in &section.entries {
+            let participant = entry.participant_id.as_deref().and_then(|id| report.participant(id));
+            out.push_str(r#"<div class="entry">"#);
+            out.push_str(&avatar(&entry.speaker, participant));
+            out.push_str(&format!(r#"<div class="bubble"><div class="speaker">{}"#, escape(&entry.speaker)));
+            if let Some(tokens) = entry.total_tokens {
+                out.push_str(&format!(r#"<span class="tokens">{} tokens</span>"#, tokens));
+            }
+            out.push_str("</div>");
+            markdown(&entry.text, &mut out);
+            if !entry.citations.is_empty() {
+                let refs: Vec<String> = entry
+                    .citations
+                    .iter()
+                    .map(|i| format!(r##"<a href="#cite-{0}">[{0}]</a>"##, i + 1))
+                    .collect();
+                out.push_str(&format!(r#"<div class="cite">Sources: {}</div>"#, refs.join(" ")));
+            }
+            out.push_str("</div></div>");
+        }
+    }
+
+    if let Some(conclusion) = &report.conclusion {
+        out.push_str("<h2>Conclusion</h2>");
+        markdown(conclusion, &mut out);
+    }
+
+    if !report.usage.is_empty() {
+        out.push_str("<h2>Token usage</h2>");
+        table(&report.usage_table(), &mut out, true);
+    }
+
+    if !report.citations.is_empty() {
+        out.push_str(r#"<h2>Citations</h2><ol class="citations">"#);
+        for (i, c) in report.citations.iter().enumerate() {
+            out.push_str(&format!(
+                r#"<li id="cite-{}"><strong>{}</strong><blockquote>{}</blockquote></li>"#,
+                i + 1,
+                escape(&citation_label(c)),
+                escape(&c.text)
+            ));
+        }
+        out.push_str("</ol>");
+    }
+
+    out.push_str(&format!(
+        "<footer>Generated by Panther on {}</footer></body></html>\n",
+        escape(&chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string())
+    ));
+    out
+}
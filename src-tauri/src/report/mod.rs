@@ -0,0 +1,760 @@
+// Session reports - polished exports for stakeholders
+//
+// A report is built once from the database (debate run, brainstorm run or profile conversation)
+// and then rendered by one of the writers:
+// - html: self-contained page with inline CSS and embedded speaker avatars
+// - pdf: paginated text document (lopdf, built-in Helvetica)
+// - docx: Word document (Office Open XML written with zip)
+//
+// Token counts come from the stored usage of each turn/result. Costs are only shown when the
+// provider account lists prices under `provider_metadata_json.pricing`:
+// `{ "<model>": { "input_per_1m": 2.5, "output_per_1m": 10.0 } }` (USD per million tokens).
+
+pub mod docx;
+pub mod html;
+pub mod pdf;
+
+use crate::consensus;
+use crate::db::Database;
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Pdf,
+    Docx,
+}
+
+impl ReportFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "html" => Ok(ReportFormat::Html),
+            "pdf" => Ok(ReportFormat::Pdf),
+            "docx" => Ok(ReportFormat::Docx),
+            other => Err(format!("Unknown report format '{}' (expected html, pdf or docx)", other)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Pdf => "pdf",
+            ReportFormat::Docx => "docx",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "text/html",
+            ReportFormat::Pdf => "application/pdf",
+            ReportFormat::Docx => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Participant {
+    pub id: String,
+    pub name: String,
+    pub model_name: String,
+    /// data: URL, http(s) URL or local file path
+    pub photo_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReportEntry {
+    /// Participant id for agents; None for users, moderators and system text
+    pub participant_id: Option<String>,
+    pub speaker: String,
+    pub text: String,
+    pub total_tokens: Option<i64>,
+    /// Indexes into `Report::citations`
+    pub citations: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReportSection {
+    pub title: String,
+    pub entries: Vec<ReportEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageRow {
+    pub participant: String,
+    pub model_name: String,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportCitation {
+    pub source_id: String,
+    pub chunk_index: Option<i32>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub title: String,
+    /// "Debate", "Brainstorm" or "Conversation"
+    pub kind: String,
+    pub question: Option<String>,
+    pub created_at: String,
+    pub participants: Vec<Participant>,
+    pub sections: Vec<ReportSection>,
+    pub comparison: Option<Table>,
+    pub usage: Vec<UsageRow>,
+    pub citations: Vec<ReportCitation>,
+    /// Closing text such as a debate verdict or synthesis (lightweight Markdown)
+    pub conclusion: Option<String>,
+}
+
+impl Report {
+    pub fn participant(&self, id: &str) -> Option<&Participant> {
+        self.participants.iter().find(|p| p.id == id)
+    }
+
+    pub fn usage_totals(&self) -> UsageRow {
+        let costs: Vec<f64> = self.usage.iter().filter_map(|u| u.cost_usd).collect();
+        UsageRow {
+            participant: "Total".to_string(),
+            model_name: String::new(),
+            prompt_tokens: self.usage.iter().map(|u| u.prompt_tokens).sum(),
+            completion_tokens: self.usage.iter().map(|u| u.completion_tokens).sum(),
+            total_tokens: self.usage.iter().map(|u| u.total_tokens).sum(),
+            cost_usd: (!costs.is_empty()).then(|| costs.iter().sum()),
+        }
+    }
+
+    /// Token/cost table rows (including the total), formatted for display.
+    pub fn usage_table(&self) -> Table {
+        let fmt_cost = |c: Option<f64>| c.map(|c| format!("${:.4}", c)).unwrap_or_else(|| "—".to_string());
+        let mut rows: Vec<Vec<String>> = self
+            .usage
+            .iter()
+            .map(|u| {
+                vec![
+                    u.participant.clone(),
+                    u.model_name.clone(),
+                    u.prompt_tokens.to_string(),
+                    u.completion_tokens.to_string(),
+                    u.total_tokens.to_string(),
+                    fmt_cost(u.cost_usd),
+                ]
+            })
+            .collect();
+        let total = self.usage_totals();
+        rows.push(vec![
+            total.participant,
+            String::new(),
+            total.prompt_tokens.to_string(),
+            total.completion_tokens.to_string(),
+            total.total_tokens.to_string(),
+            fmt_cost(total.cost_usd),
+        ]);
+        Table {
+            headers: ["Participant", "Model", "Prompt tokens", "Completion tokens", "Total tokens", "Est. cost"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows,
+        }
+    }
+}
+
+/// Rendered report file.
+#[derive(Debug, Serialize)]
+pub struct ReportFile {
+    pub file_name: String,
+    pub mime_type: String,
+    /// Base64 for pdf/docx, plain text for html
+    pub content: String,
+    pub base64: bool,
+}
+
+pub fn render(report: &Report, format: ReportFormat) -> Result<ReportFile, String> {
+    use base64::Engine;
+    let stem: String = report
+        .title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+        .take(60)
+        .collect();
+    let file_name = format!("{}.{}", if stem.is_empty() { "report".to_string() } else { stem }, format.extension());
+    let (content, base64) = match format {
+        ReportFormat::Html => (html::render(report), false),
+        ReportFormat::Pdf => (base64::engine::general_purpose::STANDARD.encode(pdf::render(report)?), true),
+        ReportFormat::Docx => (base64::engine::general_purpose::STANDARD.encode(docx::render(report)?), true),
+    };
+    Ok(ReportFile { file_name, mime_type: format.mime_type().to_string(), content, base64 })
+}
+
+// ---------------------------------------------------------------------------
+// Building reports from the database
+// ---------------------------------------------------------------------------
+
+/// Model name -> (input, output) USD per million tokens
+type Prices = HashMap<String, (f64, f64)>;
+
+struct UsageAccumulator {
+    rows: Vec<UsageRow>,
+    index: HashMap<String, usize>,
+}
+
+impl UsageAccumulator {
+    fn new() -> Self {
+        UsageAccumulator { rows: Vec::new(), index: HashMap::new() }
+    }
+
+    fn add(&mut self, key: &str, participant: &str, model_name: &str, usage: Option<&Value>) {
+        let Some(usage) = usage else { return };
+        let get = |k: &str| usage.get(k).and_then(|v| v.as_i64()).unwrap_or(0);
+        let (prompt, completion) = (get("prompt_tokens"), get("completion_tokens"));
+        let total = usage.get("total_tokens").and_then(|v| v.as_i64()).unwrap_or(prompt + completion);
+        let i = *self.index.entry(key.to_string()).or_insert_with(|| {
+            self.rows.push(UsageRow {
+                participant: participant.to_string(),
+                model_name: model_name.to_string(),
+                ..Default::default()
+            });
+            self.rows.len() - 1
+        });
+        let row = &mut self.rows[i];
+        row.prompt_tokens += prompt;
+        row.completion_tokens += completion;
+        row.total_tokens += total;
+    }
+
+    /// Attach costs from the provider price lists, per participant's model.
+    fn finish(mut self, prices: &Prices) -> Vec<UsageRow> {
+        for row in &mut self.rows {
+            if let Some((input, output)) = prices.get(&row.model_name) {
+                row.cost_usd = Some(
+                    (row.prompt_tokens as f64 * input + row.completion_tokens as f64 * output) / 1_000_000.0,
+                );
+            }
+        }
+        self.rows
+    }
+}
+
+fn total_tokens(usage: Option<&Value>) -> Option<i64> {
+    let usage = usage?;
+    usage.get("total_tokens").and_then(|v| v.as_i64()).or_else(|| {
+        let get = |k: &str| usage.get(k).and_then(|v| v.as_i64());
+        match (get("prompt_tokens"), get("completion_tokens")) {
+            (None, None) => None,
+            (p, c) => Some(p.unwrap_or(0) + c.unwrap_or(0)),
+        }
+    })
+}
+
+fn load_participants(conn: &Connection, ids: &[String]) -> Result<(Vec<Participant>, Prices), String> {
+    let mut participants = Vec::new();
+    let mut prices = HashMap::new();
+    for id in ids {
+        let row = conn.query_row(
+            "SELECT p.name, p.model_name, p.photo_url, a.provider_metadata_json
+             FROM prompt_profiles p LEFT JOIN provider_accounts a ON a.id = p.provider_account_id WHERE p.id = ?1",
+            [id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        );
+        let (name, model_name, photo_url, metadata) = match row {
+            Ok(r) => r,
+            Err(rusqlite::Error::QueryReturnedNoRows) => (id.clone(), String::new(), None, None),
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        let price = metadata
+            .and_then(|m| serde_json::from_str::<Value>(&m).ok())
+            .and_then(|m| m.get("pricing")?.get(&model_name).cloned())
+            .and_then(|p| Some((p.get("input_per_1m")?.as_f64()?, p.get("output_per_1m")?.as_f64()?)));
+        if let Some(price) = price {
+            prices.insert(model_name.clone(), price);
+        }
+        participants.push(Participant { id: id.clone(), name, model_name, photo_url });
+    }
+    Ok((participants, prices))
+}
+
+fn load_session(conn: &Connection, session_id: &str) -> Result<(String, String, String, String), String> {
+    conn.query_row(
+        "SELECT title, user_question, mode, created_at FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )
+    .map_err(|e| format!("Session not found: {}", e))
+}
+
+fn latest_run(conn: &Connection, session_id: &str) -> Result<(String, Vec<String>), String> {
+    let (run_id, ids_json): (String, String) = conn
+        .query_row(
+            "SELECT id, selected_profile_ids_json FROM runs WHERE session_id = ?1 ORDER BY started_at DESC LIMIT 1",
+            [session_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("No run found for session: {}", e))?;
+    Ok((run_id, serde_json::from_str(&ids_json).unwrap_or_default()))
+}
+
+/// Report for a session: debate transcript or brainstorm results, depending on its mode.
+pub fn build_session_report(db: &Database, session_id: &str) -> Result<Report, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let (title, question, mode, created_at) = load_session(&conn_guard, session_id)?;
+    let (run_id, profile_ids) = latest_run(&conn_guard, session_id)?;
+    let mut report = Report {
+        title,
+        question: Some(question),
+        created_at,
+        ..Default::default()
+    };
+    if mode == "debate" {
+        build_debate(&conn_guard, &run_id, &profile_ids, &mut report)?;
+    } else {
+        build_brainstorm(&conn_guard, &run_id, &profile_ids, &mut report)?;
+    }
+    Ok(report)
+}
+
+fn build_debate(conn: &Connection, run_id: &str, profile_ids: &[String], report: &mut Report) -> Result<(), String> {
+    report.kind = "Debate".to_string();
+    let mut stmt = conn
+        .prepare(
+            "SELECT author_type, profile_id, round_index, text, provider_metadata_json, author_label FROM messages
+             WHERE run_id = ?1 ORDER BY round_index, turn_index, created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<i32>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+
+    // Speakers include profiles that joined after the run was created (e.g. moderators)
+    let mut ids: Vec<String> = profile_ids.iter().filter(|id| !id.starts_with("human:")).cloned().collect();
+    for (_, profile_id, ..) in &rows {
+        if let Some(id) = profile_id {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+    }
+    let (participants, prices) = load_participants(conn, &ids)?;
+    report.participants = participants;
+
+    let mut usage = UsageAccumulator::new();
+    for (author_type, profile_id, round_index, text, metadata, author_label) in rows {
+        let usage_json: Option<Value> = metadata.and_then(|m| serde_json::from_str(&m).ok());
+        let (speaker, model) = match (author_type.as_str(), profile_id.as_deref().and_then(|id| report.participant(id))) {
+            ("agent", Some(p)) => (p.name.clone(), p.model_name.clone()),
+            ("moderator", Some(p)) => (format!("{} (moderator)", p.name), p.model_name.clone()),
+            ("moderator", None) => ("Moderator".to_string(), String::new()),
+            _ => (author_label.unwrap_or_else(|| "User".to_string()), String::new()),
+        };
+        if let Some(id) = &profile_id {
+            usage.add(id, &speaker, &model, usage_json.as_ref());
+        }
+        let title = match round_index {
+            Some(r) => format!("Round {}", r + 1),
+            None => "Opening".to_string(),
+        };
+        if report.sections.last().map(|s| s.title != title).unwrap_or(true) {
+            report.sections.push(ReportSection { title, entries: Vec::new() });
+        }
+        if let Some(section) = report.sections.last_mut() {
+            section.entries.push(ReportEntry {
+                participant_id: profile_id.filter(|_| author_type == "agent"),
+                speaker,
+                text,
+                total_tokens: total_tokens(usage_json.as_ref()),
+                citations: Vec::new(),
+            });
+        }
+    }
+    report.usage = usage.finish(&prices);
+
+    if let Some(verdict) = consensus::load_verdict(conn, run_id)? {
+        let names = consensus::participant_names(conn, run_id)?;
+        report.conclusion = Some(consensus::render_verdict_markdown(&verdict, &names));
+    }
+    Ok(())
+}
+
+fn build_brainstorm(conn: &Connection, run_id: &str, profile_ids: &[String], report: &mut Report) -> Result<(), String> {
+    report.kind = "Brainstorm".to_string();
+    let (participants, prices) = load_participants(conn, profile_ids)?;
+    report.participants = participants;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.profile_id, r.status, r.raw_output_text, r.usage_json, COALESCE(r.stage, 'generate'),
+                    (SELECT score FROM groundedness_scores g WHERE g.run_result_id = r.id ORDER BY g.created_at DESC LIMIT 1)
+             FROM run_results r WHERE r.run_id = ?1 AND r.status != 'cancelled' ORDER BY r.started_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let results = stmt
+        .query_map([run_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Option<f64>>(6)?,
+            ))
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+
+    let mut cite_stmt = conn
+        .prepare(
+            "SELECT c.source_id, d.chunk_index, c.raw_citation_text FROM citations c
+             LEFT JOIN document_chunks d ON d.id = c.chunk_id WHERE c.run_result_id = ?1 ORDER BY c.created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut usage = UsageAccumulator::new();
+    let mut comparison = Table {
+        headers: ["Participant", "Model", "Status", "Words", "Tokens", "Citations", "Groundedness"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect(),
+        rows: Vec::new(),
+    };
+    for (result_id, profile_id, status, text, usage_json, stage, groundedness) in results {
+        let usage_json: Option<Value> = usage_json.and_then(|u| serde_json::from_str(&u).ok());
+        let (name, model) = report
+            .participant(&profile_id)
+            .map(|p| (p.name.clone(), p.model_name.clone()))
+            .unwrap_or_else(|| (profile_id.clone(), String::new()));
+        usage.add(&profile_id, &name, &model, usage_json.as_ref());
+
+        let citations: Vec<ReportCitation> = cite_stmt
+            .query_map([&result_id], |row| {
+                Ok(ReportCitation {
+                    source_id: row.get(0)?,
+                    chunk_index: row.get(1)?,
+                    text: row.get(2)?,
+                })
+            })
+            .map_err(|e| format!("Database error: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Row error: {}", e))?;
+        let first = report.citations.len();
+        let cited: Vec<usize> = (first..first + citations.len()).collect();
+        report.citations.extend(citations);
+
+        let text = text.unwrap_or_default();
+        if stage == "generate" {
+            comparison.rows.push(vec![
+                name.clone(),
+                model,
+                status,
+                text.split_whitespace().count().to_string(),
+                total_tokens(usage_json.as_ref()).map(|t| t.to_string()).unwrap_or_else(|| "—".to_string()),
+                cited.len().to_string(),
+                groundedness.map(|g| format!("{:.0}%", g * 100.0)).unwrap_or_else(|| "—".to_string()),
+            ]);
+        }
+        let title = match stage.as_str() {
+            "generate" => "Answers".to_string(),
+            other => format!("{}{}", other[..1].to_uppercase(), &other[1..]),
+        };
+        if !report.sections.iter().any(|s| s.title == title) {
+            report.sections.push(ReportSection { title: title.clone(), entries: Vec::new() });
+        }
+        if let Some(section) = report.sections.iter_mut().find(|s| s.title == title) {
+            section.entries.push(ReportEntry {
+                participant_id: Some(profile_id),
+                speaker: name,
+                text,
+                total_tokens: total_tokens(usage_json.as_ref()),
+                citations: cited,
+            });
+        }
+    }
+    report.usage = usage.finish(&prices);
+    report.comparison = (!comparison.rows.is_empty()).then_some(comparison);
+
+    let synthesis: Option<String> = conn
+        .query_row(
+            "SELECT text FROM syntheses WHERE run_id = ?1 AND method != 'verdict' ORDER BY created_at DESC LIMIT 1",
+            [run_id],
+            |row| row.get(0),
+        )
+        .ok();
+    report.conclusion = synthesis.map(|s| format!("## Synthesis\n\n{}", s));
+    Ok(())
+}
+
+/// Report for a profile chat: one conversation, or the profile's default chat when `conversation_id` is None.
+pub fn build_conversation_report(db: &Database, profile_id: &str, conversation_id: Option<&str>) -> Result<Report, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let (participants, prices) = load_participants(&conn_guard, &[profile_id.to_string()])?;
+    let profile = participants.first().cloned().unwrap_or_default();
+
+    let (title, created_at): (String, String) = match conversation_id {
+        Some(id) => conn_guard
+            .query_row("SELECT title, created_at FROM profile_conversations WHERE id = ?1", [id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|e| format!("Conversation not found: {}", e))?,
+        None => (format!("Chat with {}", profile.name), chrono::Utc::now().to_rfc3339()),
+    };
+
+    // Only the selected branch, like the chat view
+    let messages: Vec<(String, String)> = crate::chat_branches::active_path(&conn_guard, profile_id, conversation_id)?
+        .into_iter()
+        .map(|m| (m.role, m.content))
+        .collect();
+
+    // Chat usage is recorded per call in token_usage, tagged with the conversation
+    let mut usage = UsageAccumulator::new();
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT prompt_tokens, completion_tokens, total_tokens FROM token_usage
+             WHERE source = 'profile_chat' AND json_extract(metadata_json, '$.profile_id') = ?1
+             AND COALESCE(json_extract(metadata_json, '$.conversation_id'), '') = ?2",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let usage_rows = stmt
+        .query_map(rusqlite::params![profile_id, conversation_id.unwrap_or("")], |row| {
+            Ok(serde_json::json!({
+                "prompt_tokens": row.get::<_, Option<i64>>(0)?,
+                "completion_tokens": row.get::<_, Option<i64>>(1)?,
+                "total_tokens": row.get::<_, Option<i64>>(2)?,
+            }))
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    for u in &usage_rows {
+        usage.add(profile_id, &profile.name, &profile.model_name, Some(u));
+    }
+
+    let entries = messages
+        .into_iter()
+        .map(|(role, content)| {
+            let is_assistant = role == "assistant";
+            ReportEntry {
+                participant_id: is_assistant.then(|| profile_id.to_string()),
+                speaker: if is_assistant { profile.name.clone() } else { "User".to_string() },
+                text: content,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(Report {
+        title,
+        kind: "Conversation".to_string(),
+        question: None,
+        created_at,
+        participants,
+        sections: vec![ReportSection { title: "Conversation".to_string(), entries }],
+        comparison: None,
+        usage: usage.finish(&prices),
+        citations: Vec::new(),
+        conclusion: None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Shared text helpers for the writers
+// ---------------------------------------------------------------------------
+
+/// A block of lightweight Markdown as used in model answers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Bullet(String),
+    Code(String),
+}
+
+/// Split text into headings, bullets, code blocks and paragraphs. Inline markup is kept as is
+/// apart from `**bold**` markers, which are removed.
+pub fn blocks(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(strip_inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            match code.take() {
+                Some(lines) => blocks.push(Block::Code(lines.join("\n"))),
+                None => {
+                    flush(&mut paragraph, &mut blocks);
+                    code = Some(Vec::new());
+                }
+            }
+            continue;
+        }
+        if let Some(lines) = code.as_mut() {
+            lines.push(line);
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some(level) = (1..=4).find(|n| trimmed.starts_with(&format!("{} ", "#".repeat(*n)))) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(level, strip_inline(trimmed[level + 1..].trim())));
+        } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Bullet(strip_inline(item)));
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn strip_inline(text: &str) -> String {
+    text.replace("**", "")
+}
+
+pub fn citation_label(citation: &ReportCitation) -> String {
+    match citation.chunk_index {
+        Some(i) => format!("{} (chunk {})", citation.source_id, i),
+        None => citation.source_id.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_split_markdown() {
+        let text = "# Plan\nFirst **bold** line\ncontinues\n\n- one\n* two\n```\nlet x = 1;\n```";
+        assert_eq!(
+            blocks(text),
+            vec![
+                Block::Heading(1, "Plan".to_string()),
+                Block::Paragraph("First bold line continues".to_string()),
+                Block::Bullet("one".to_string()),
+                Block::Bullet("two".to_string()),
+                Block::Code("let x = 1;".to_string()),
+            ]
+        );
+    }
+
+    fn sample_report() -> Report {
+        Report {
+            title: "Pricing & <strategy>".to_string(),
+            kind: "Debate".to_string(),
+            question: Some("Should we raise prices?".to_string()),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            participants: vec![Participant { id: "p1".to_string(), name: "Ana".to_string(), model_name: "m".to_string(), photo_url: None }],
+            sections: vec![ReportSection {
+                title: "Round 1".to_string(),
+                entries: vec![ReportEntry {
+                    participant_id: Some("p1".to_string()),
+                    speaker: "Ana".to_string(),
+                    text: "Yes — a 5% raise is fine.\n\n- churn is low".to_string(),
+                    total_tokens: Some(42),
+                    citations: vec![0],
+                }],
+            }],
+            comparison: None,
+            usage: vec![UsageRow {
+                participant: "Ana".to_string(),
+                model_name: "m".to_string(),
+                prompt_tokens: 30,
+                completion_tokens: 12,
+                total_tokens: 42,
+                cost_usd: Some(0.001),
+            }],
+            citations: vec![ReportCitation { source_id: "handbook".to_string(), chunk_index: Some(2), text: "Churn is 2%".to_string() }],
+            conclusion: Some("## Verdict\n\nRaise prices.".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_writers_produce_valid_documents() {
+        let report = sample_report();
+
+        let html = html::render(&report);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Pricing &amp; &lt;strategy&gt;"));
+        assert!(html.contains("$0.0010"));
+
+        let pdf_bytes = pdf::render(&report).unwrap();
+        assert!(pdf_bytes.starts_with(b"%PDF-"));
+        assert_eq!(lopdf::Document::load_mem(&pdf_bytes).unwrap().get_pages().len(), 1);
+
+        let docx_bytes = docx::render(&report).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes)).unwrap();
+        let mut document = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("word/document.xml").unwrap(), &mut document).unwrap();
+        assert!(document.contains("Pricing &amp; &lt;strategy&gt;"));
+        assert!(archive.by_name("[Content_Types].xml").is_ok());
+    }
+
+    #[test]
+    fn test_conversation_report_follows_the_selected_branch() {
+        let path = std::env::temp_dir().join(format!("panther-report-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json) VALUES
+                     ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}');",
+            )
+            .unwrap();
+            let question = crate::chat_branches::append(&conn, "p1", None, None, "user", "Q", "2026-01-01T00:00:01Z").unwrap();
+            let first = crate::chat_branches::append(&conn, "p1", None, Some(&question), "assistant", "first", "2026-01-01T00:00:02Z").unwrap();
+            crate::chat_branches::append(&conn, "p1", None, Some(&question), "assistant", "regenerated", "2026-01-01T00:00:03Z").unwrap();
+            crate::chat_branches::select(&conn, None, &first).unwrap();
+        }
+
+        let report = build_conversation_report(&db, "p1", None).unwrap();
+        let texts: Vec<&str> = report.sections[0].entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["Q", "first"]);
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+}
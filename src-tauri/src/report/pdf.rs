@@ -0,0 +1,298 @@
+// PDF report: A4 pages of wrapped text using the standard Helvetica fonts (no embedded fonts).
+// Text is encoded as WinAnsi; characters outside it are replaced with '?'.
+
+use super::{blocks, citation_label, Block, Report, Table};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    /// Average glyph width as a fraction of the font size (close enough for line wrapping)
+    fn char_width(&self) -> f32 {
+        match self {
+            Font::Regular => 0.52,
+            Font::Bold => 0.57,
+            Font::Mono => 0.6,
+        }
+    }
+}
+
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20}'..='\u{7e}' => c as u8,
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '\t' => b' ',
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let max_chars = ((width / (size * font.char_width())) as usize).max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        // Hard-break words longer than a line (URLs, hashes)
+        while word.chars().count() > max_chars {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let head: String = word.chars().take(max_chars).collect();
+            word = word.chars().skip(max_chars).collect();
+            lines.push(head);
+        }
+        if line.is_empty() {
+            line = word;
+        } else if line.chars().count() + 1 + word.chars().count() <= max_chars {
+            line.push(' ');
+            line.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut line, word));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct Writer {
+    pages: Vec<Vec<Operation>>,
+    y: f32,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Writer { pages: vec![Vec::new()], y: PAGE_HEIGHT - MARGIN }
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(Vec::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text_at(&mut self, text: &str, font: Font, size: f32, x: f32) {
+        let ops = self.pages.last_mut().expect("writer always has a page");
+        ops.push(Operation::new("BT", vec![]));
+        ops.push(Operation::new("Tf", vec![font.resource().into(), size.into()]));
+        ops.push(Operation::new("Td", vec![x.into(), self.y.into()]));
+        ops.push(Operation::new("Tj", vec![Object::string_literal(win_ansi(text))]));
+        ops.push(Operation::new("ET", vec![]));
+    }
+
+    fn paragraph(&mut self, text: &str, font: Font, size: f32, indent: f32) {
+        let leading = size * 1.4;
+        for line in wrap(text, font, size, TEXT_WIDTH - indent) {
+            self.ensure_space(leading);
+            self.y -= leading;
+            self.text_at(&line, font, size, MARGIN + indent);
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.ensure_space(size * 3.0);
+        self.gap(size * 0.6);
+        self.paragraph(text, Font::Bold, size, 0.0);
+        self.gap(size * 0.3);
+    }
+
+    fn markdown(&mut self, text: &str, indent: f32) {
+        for block in blocks(text) {
+            match block {
+                Block::Heading(_, t) => self.paragraph(&t, Font::Bold, 11.0, indent),
+                Block::Paragraph(t) => self.paragraph(&t, Font::Regular, 10.0, indent),
+                Block::Bullet(t) => self.paragraph(&format!("• {}", t), Font::Regular, 10.0, indent + 8.0),
+                Block::Code(t) => {
+                    for line in t.lines() {
+                        self.paragraph(if line.trim().is_empty() { " " } else { line }, Font::Mono, 8.5, indent + 8.0);
+                    }
+                }
+            }
+            self.gap(3.0);
+        }
+    }
+
+    /// Fixed-width columns; cells that do not fit are shortened with an ellipsis.
+    fn table(&mut self, table: &Table) {
+        let columns = table.headers.len().max(1);
+        let col_width = TEXT_WIDTH / columns as f32;
+        let size = 8.5;
+        let max_chars = (col_width / (size * Font::Bold.char_width())) as usize;
+        let fit = |cell: &str| {
+            if cell.chars().count() <= max_chars {
+                cell.to_string()
+            } else {
+                let mut s: String = cell.chars().take(max_chars.saturating_sub(1)).collect();
+                s.push('…');
+                s
+            }
+        };
+        let rows = std::iter::once((&table.headers, Font::Bold)).chain(table.rows.iter().map(|r| (r, Font::Regular)));
+        for (row, font) in rows {
+            self.ensure_space(size * 1.6);
+            self.y -= size * 1.6;
+            for (i, cell) in row.iter().enumerate() {
+                self.text_at(&fit(cell), font, size, MARGIN + i as f32 * col_width);
+            }
+        }
+        self.gap(6.0);
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font = |name: &str| {
+            dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => Object::Name(name.as_bytes().to_vec()),
+                "Encoding" => "WinAnsiEncoding",
+            }
+        };
+        let regular = doc.add_object(font("Helvetica"));
+        let bold = doc.add_object(font("Helvetica-Bold"));
+        let mono = doc.add_object(font("Courier"));
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => regular, "F2" => bold, "F3" => mono },
+        });
+
+        let total = self.pages.len();
+        let mut kids: Vec<Object> = Vec::with_capacity(total);
+        for (i, mut operations) in self.pages.into_iter().enumerate() {
+            // Page number footer
+            let label = format!("{} / {}", i + 1, total);
+            operations.push(Operation::new("BT", vec![]));
+            operations.push(Operation::new("Tf", vec!["F1".into(), 8.into()]));
+            operations.push(Operation::new("Td", vec![(PAGE_WIDTH / 2.0 - 10.0).into(), (MARGIN / 2.0).into()]));
+            operations.push(Operation::new("Tj", vec![Object::string_literal(label)]));
+            operations.push(Operation::new("ET", vec![]));
+
+            let content = Content { operations }.encode().map_err(|e| format!("Failed to encode PDF page: {}", e))?;
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+            let page_id: ObjectId = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => total as i64,
+                "Kids" => kids,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.compress();
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).map_err(|e| format!("Failed to write PDF: {}", e))?;
+        Ok(bytes)
+    }
+}
+
+pub fn render(report: &Report) -> Result<Vec<u8>, String> {
+    let mut w = Writer::new();
+    w.paragraph(&report.title, Font::Bold, 20.0, 0.0);
+    w.gap(4.0);
+    w.paragraph(&format!("{} · {}", report.kind, report.created_at), Font::Regular, 9.0, 0.0);
+    if let Some(question) = &report.question {
+        w.gap(8.0);
+        w.paragraph(&format!("Question: {}", question), Font::Bold, 11.0, 0.0);
+    }
+    if !report.participants.is_empty() {
+        w.heading("Participants", 14.0);
+        for p in &report.participants {
+            let line = if p.model_name.is_empty() { p.name.clone() } else { format!("{} ({})", p.name, p.model_name) };
+            w.paragraph(&format!("• {}", line), Font::Regular, 10.0, 8.0);
+        }
+    }
+
+    if let Some(comparison) = &report.comparison {
+        w.heading("Comparison", 14.0);
+        w.table(comparison);
+    }
+
+    for section in &report.sections {
+        w.heading(&section.title, 14.0);
+        for entry in &section.entries {
+            w.ensure_space(40.0);
+            let header = match entry.total_tokens {
+                Some(t) => format!("{}  ({} tokens)", entry.speaker, t),
+                None => entry.speaker.clone(),
+            };
+            w.paragraph(&header, Font::Bold, 10.5, 0.0);
+            w.markdown(&entry.text, 12.0);
+            if !entry.citations.is_empty() {
+                let refs: Vec<String> = entry.citations.iter().map(|i| format!("[{}]", i + 1)).collect();
+                w.paragraph(&format!("Sources: {}", refs.join(" ")), Font::Regular, 8.5, 12.0);
+            }
+            w.gap(6.0);
+        }
+    }
+
+    if let Some(conclusion) = &report.conclusion {
+        w.heading("Conclusion", 14.0);
+        w.markdown(conclusion, 0.0);
+    }
+
+    if !report.usage.is_empty() {
+        w.heading("Token usage", 14.0);
+        w.table(&report.usage_table());
+    }
+
+    if !report.citations.is_empty() {
+        w.heading("Citations", 14.0);
+        for (i, c) in report.citations.iter().enumerate() {
+            w.paragraph(&format!("[{}] {}", i + 1, citation_label(c)), Font::Bold, 9.5, 0.0);
+            w.paragraph(&c.text, Font::Regular, 9.0, 12.0);
+            w.gap(4.0);
+        }
+    }
+
+    w.finish()
+}
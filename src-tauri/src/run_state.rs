@@ -0,0 +1,497 @@
+// Persistent run state machine: status transitions, pause/cancel signals, startup recovery
+// and resume points for brainstorm and debate runs. All state lives in SQLite so a run can be
+// driven from the Tauri UI, the HTTP server, or a fresh process after a restart.
+
+use crate::db::Database;
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Queued,
+    Running,
+    Paused,
+    Cancelled,
+    Complete,
+    Partial,
+    Failed,
+    /// The process exited while the run was running or paused; it can be resumed.
+    Interrupted,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Queued => "queued",
+            RunStatus::Running => "running",
+            RunStatus::Paused => "paused",
+            RunStatus::Cancelled => "cancelled",
+            RunStatus::Complete => "complete",
+            RunStatus::Partial => "partial",
+            RunStatus::Failed => "failed",
+            RunStatus::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "queued" | "pending" => Some(RunStatus::Queued),
+            "running" => Some(RunStatus::Running),
+            "paused" => Some(RunStatus::Paused),
+            "cancelled" => Some(RunStatus::Cancelled),
+            "complete" => Some(RunStatus::Complete),
+            "partial" => Some(RunStatus::Partial),
+            "failed" => Some(RunStatus::Failed),
+            "interrupted" => Some(RunStatus::Interrupted),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            RunStatus::Cancelled | RunStatus::Complete | RunStatus::Partial | RunStatus::Failed
+        )
+    }
+
+    /// Allowed edges of the run state machine. Finished runs may be restarted (continue a
+    /// debate, retry a failed brainstorm), but nothing but a restart leaves a terminal state.
+    pub fn can_transition_to(&self, to: RunStatus) -> bool {
+        use RunStatus::*;
+        if *self == to {
+            return true;
+        }
+        match self {
+            Queued => matches!(to, Running | Cancelled | Failed),
+            Running => matches!(to, Paused | Cancelled | Complete | Partial | Failed | Interrupted),
+            Paused => matches!(to, Running | Cancelled | Complete | Partial | Failed | Interrupted),
+            Interrupted => matches!(to, Running | Cancelled | Failed),
+            Cancelled | Complete | Partial | Failed => to == Running,
+        }
+    }
+}
+
+/// What a worker should do at its next checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunSignal {
+    Continue,
+    Pause,
+    Cancel,
+}
+
+/// Where an interrupted run would pick up again.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumePoint {
+    /// "brainstorm" or "debate" (from `sessions.mode`).
+    pub kind: String,
+    /// Brainstorm: profiles with a completed `generate` result.
+    pub completed_profile_ids: Vec<String>,
+    /// Brainstorm: profiles that still need an answer.
+    pub pending_profile_ids: Vec<String>,
+    /// Debate: last completed agent turn as (round_index, turn_index).
+    pub last_completed_turn: Option<(i32, i32)>,
+    /// Debate: configured number of rounds.
+    pub rounds: Option<i32>,
+}
+
+pub fn current_status(db: &Database, run_id: &str) -> Result<Option<String>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let status: Result<String, _> = conn_guard.query_row(
+        "SELECT status FROM runs WHERE id = ?1",
+        [run_id],
+        |row| row.get(0),
+    );
+    match status {
+        Ok(s) => Ok(Some(s)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Database error: {}", e)),
+    }
+}
+
+/// Move a run to `to`, validating the edge and recording it in `run_state_events`.
+pub fn transition(db: &Database, run_id: &str, to: RunStatus, reason: &str) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let from: String = conn_guard
+        .query_row("SELECT status FROM runs WHERE id = ?1", [run_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to load run: {}", e))?;
+
+    if let Some(from_status) = RunStatus::parse(&from) {
+        if !from_status.can_transition_to(to) {
+            return Err(format!(
+                "Invalid run state transition {} -> {} for run {}",
+                from,
+                to.as_str(),
+                run_id
+            ));
+        }
+    }
+    if from == to.as_str() {
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    if to.is_terminal() {
+        conn_guard.execute(
+            "UPDATE runs SET status = ?1, finished_at = ?2, heartbeat_at = ?2 WHERE id = ?3",
+            rusqlite::params![to.as_str(), now, run_id],
+        )
+    } else {
+        conn_guard.execute(
+            "UPDATE runs SET status = ?1, finished_at = NULL, heartbeat_at = ?2 WHERE id = ?3",
+            rusqlite::params![to.as_str(), now, run_id],
+        )
+    }
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    conn_guard
+        .execute(
+            "INSERT INTO run_state_events (id, run_id, from_status, to_status, reason, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![Uuid::new_v4().to_string(), run_id, from, to.as_str(), reason, now],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Set the final status of a worker's run, unless it was cancelled meanwhile.
+pub fn finish(db: &Database, run_id: &str, to: RunStatus) -> Result<(), String> {
+    match current_status(db, run_id)?.as_deref() {
+        Some("running") | Some("paused") => transition(db, run_id, to, "worker_finished"),
+        _ => Ok(()),
+    }
+}
+
+/// Read the persisted pause/cancel flag for a run. A run that no longer exists is cancelled.
+pub fn signal(db: &Database, run_id: &str) -> RunSignal {
+    match current_status(db, run_id) {
+        Ok(Some(s)) => match s.as_str() {
+            "paused" => RunSignal::Pause,
+            "cancelled" | "interrupted" => RunSignal::Cancel,
+            _ => RunSignal::Continue,
+        },
+        Ok(None) => RunSignal::Cancel,
+        Err(_) => RunSignal::Continue,
+    }
+}
+
+pub fn heartbeat(db: &Database, run_id: &str) {
+    let conn = db.get_connection();
+    if let Ok(conn_guard) = conn.lock() {
+        let _ = conn_guard.execute(
+            "UPDATE runs SET heartbeat_at = ?1 WHERE id = ?2",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), run_id],
+        );
+    };
+}
+
+/// Worker checkpoint: heartbeat, then block while the run is paused (still heartbeating, so
+/// the paused worker is not taken for an orphan). Returns `Cancel` if the run was cancelled
+/// (before or during the pause).
+pub async fn checkpoint(db: &Database, run_id: &str) -> RunSignal {
+    heartbeat(db, run_id);
+    let mut waited = 0u32;
+    loop {
+        match signal(db, run_id) {
+            RunSignal::Pause => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                waited += 1;
+                if waited.is_multiple_of(20) {
+                    heartbeat(db, run_id);
+                }
+            }
+            other => return other,
+        }
+    }
+}
+
+/// A running or paused run whose worker has not heartbeated for this long has no worker left.
+/// Well above the provider timeouts, so a worker waiting on one slow call is not mistaken for dead.
+pub const STALE_HEARTBEAT_SECS: i64 = 600;
+
+/// Startup recovery: a run still marked running or paused without a recent heartbeat lost its
+/// worker (this app was closed or crashed). Mark those runs (and their in-flight results / turns)
+/// as interrupted. Runs with a live heartbeat may belong to the HTTP or MCP server on the same
+/// database and are left alone.
+pub fn recover_orphaned_runs(db: &Database) -> Result<Vec<String>, String> {
+    let orphaned: Vec<String> = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let mut stmt = conn_guard
+            .prepare(
+                "SELECT id FROM runs WHERE status IN ('running', 'paused')
+                 AND (COALESCE(heartbeat_at, started_at) IS NULL
+                      OR julianday(COALESCE(heartbeat_at, started_at)) < julianday('now', ?1))",
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
+        let rows = stmt
+            .query_map([format!("-{} seconds", STALE_HEARTBEAT_SECS)], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Database error: {}", e))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    for run_id in &orphaned {
+        transition(db, run_id, RunStatus::Interrupted, "startup_recovery")?;
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .execute(
+                "UPDATE run_results SET status = 'interrupted' WHERE run_id = ?1 AND status IN ('running', 'pending')",
+                [run_id],
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
+        conn_guard
+            .execute(
+                "UPDATE debate_turns SET status = 'interrupted' WHERE run_id = ?1 AND status = 'running'",
+                [run_id],
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
+    }
+    Ok(orphaned)
+}
+
+/// "brainstorm" for parallel sessions, "debate" otherwise.
+pub fn run_kind(db: &Database, run_id: &str) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mode: String = conn_guard
+        .query_row(
+            "SELECT s.mode FROM sessions s JOIN runs r ON r.session_id = s.id WHERE r.id = ?1",
+            [run_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to load run: {}", e))?;
+    Ok(if mode == "parallel" { "brainstorm".to_string() } else { "debate".to_string() })
+}
+
+pub fn resume_point(db: &Database, run_id: &str) -> Result<ResumePoint, String> {
+    let kind = run_kind(db, run_id)?;
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    if kind == "brainstorm" {
+        let profile_ids_json: String = conn_guard
+            .query_row("SELECT selected_profile_ids_json FROM runs WHERE id = ?1", [run_id], |row| row.get(0))
+            .map_err(|e| format!("Failed to load run: {}", e))?;
+        let selected: Vec<String> = serde_json::from_str(&profile_ids_json).unwrap_or_default();
+        let mut stmt = conn_guard
+            .prepare("SELECT DISTINCT profile_id FROM run_results WHERE run_id = ?1 AND stage = 'generate' AND status = 'complete'")
+            .map_err(|e| format!("Database error: {}", e))?;
+        let completed: Vec<String> = stmt
+            .query_map([run_id], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Database error: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        let pending = selected.into_iter().filter(|id| !completed.contains(id)).collect();
+        return Ok(ResumePoint {
+            kind,
+            completed_profile_ids: completed,
+            pending_profile_ids: pending,
+            last_completed_turn: None,
+            rounds: None,
+        });
+    }
+
+    let last_completed_turn: Option<(i32, i32)> = conn_guard
+        .query_row(
+            "SELECT round_index, turn_index FROM messages WHERE run_id = ?1 AND author_type = 'agent' ORDER BY round_index DESC, turn_index DESC LIMIT 1",
+            [run_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let rounds: Option<i32> = conn_guard
+        .query_row("SELECT rounds FROM debate_configs WHERE run_id = ?1", [run_id], |row| row.get(0))
+        .ok();
+    Ok(ResumePoint {
+        kind,
+        completed_profile_ids: Vec::new(),
+        pending_profile_ids: Vec::new(),
+        last_completed_turn,
+        rounds,
+    })
+}
+
+/// Runs left behind by a crash or quit, with their resume point, for the "resume?" prompt.
+pub fn list_interrupted_runs(db: &Database) -> Result<Vec<Value>, String> {
+    // (run_id, session_id, session_title, started_at, heartbeat_at)
+    type InterruptedRow = (String, String, Option<String>, Option<String>, Option<String>);
+    let runs: Vec<InterruptedRow> = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let mut stmt = conn_guard
+            .prepare(
+                "SELECT r.id, r.session_id, s.title, r.started_at, r.heartbeat_at FROM runs r
+                 LEFT JOIN sessions s ON s.id = r.session_id
+                 WHERE r.status = 'interrupted' ORDER BY r.heartbeat_at DESC",
+            )
+            .map_err(|e| format!("Database error: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+            .map_err(|e| format!("Database error: {}", e))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    let mut out = Vec::new();
+    for (run_id, session_id, title, started_at, heartbeat_at) in runs {
+        let resume = resume_point(db, &run_id).ok();
+        out.push(serde_json::json!({
+            "run_id": run_id,
+            "session_id": session_id,
+            "session_title": title,
+            "started_at": started_at,
+            "last_heartbeat_at": heartbeat_at,
+            "resume_point": resume,
+        }));
+    }
+    Ok(out)
+}
+
+/// State-transition history of a run, oldest first.
+pub fn state_history(db: &Database, run_id: &str) -> Result<Vec<Value>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare("SELECT from_status, to_status, reason, created_at FROM run_state_events WHERE run_id = ?1 ORDER BY created_at")
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok(serde_json::json!({
+                "from_status": row.get::<_, Option<String>>(0)?,
+                "to_status": row.get::<_, String>(1)?,
+                "reason": row.get::<_, Option<String>>(2)?,
+                "created_at": row.get::<_, String>(3)?,
+            }))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut events = Vec::new();
+    for row in rows {
+        events.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    Ok(events)
+}
+
+/// Claim a run for a new worker: move it from one of the `from` states to running in a single
+/// conditional update. Fails when the run is in another state, e.g. because a concurrent resume
+/// (in this or another process on the same database) already claimed it.
+pub fn mark_resumed(db: &Database, run_id: &str, from: &[&str]) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let previous: String = conn_guard
+        .query_row("SELECT status FROM runs WHERE id = ?1", [run_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to load run: {}", e))?;
+    if !from.contains(&previous.as_str()) {
+        return Err(format!("Run {} cannot be resumed from status '{}'", run_id, previous));
+    }
+    let now = chrono::Utc::now().to_rfc3339();
+    let claimed = conn_guard
+        .execute(
+            "UPDATE runs SET status = 'running', finished_at = NULL, heartbeat_at = ?1,
+             resume_count = COALESCE(resume_count, 0) + 1 WHERE id = ?2 AND status = ?3",
+            rusqlite::params![now, run_id, previous],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    if claimed == 0 {
+        return Err(format!("Run {} is already being resumed", run_id));
+    }
+    conn_guard
+        .execute(
+            "INSERT INTO run_state_events (id, run_id, from_status, to_status, reason, created_at) VALUES (?1, ?2, ?3, 'running', 'resumed', ?4)",
+            rusqlite::params![Uuid::new_v4().to_string(), run_id, previous, now],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_states_only_restart() {
+        assert!(RunStatus::Complete.can_transition_to(RunStatus::Running));
+        assert!(!RunStatus::Complete.can_transition_to(RunStatus::Paused));
+        assert!(!RunStatus::Cancelled.can_transition_to(RunStatus::Complete));
+        assert!(RunStatus::Running.can_transition_to(RunStatus::Interrupted));
+        assert!(RunStatus::Interrupted.can_transition_to(RunStatus::Running));
+        assert!(!RunStatus::Queued.can_transition_to(RunStatus::Paused));
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for s in ["queued", "running", "paused", "cancelled", "complete", "partial", "failed", "interrupted"] {
+            assert_eq!(RunStatus::parse(s).unwrap().as_str(), s);
+        }
+        assert_eq!(RunStatus::parse("pending"), Some(RunStatus::Queued));
+        assert_eq!(RunStatus::parse("bogus"), None);
+    }
+
+    fn test_db() -> (Database, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("panther-run-state-{}.db", Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'T', 'Q', 'parallel');",
+            )
+            .unwrap();
+        }
+        (db, path)
+    }
+
+    fn insert_run(db: &Database, id: &str, status: &str, heartbeat_at: Option<String>) {
+        let conn = db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json, heartbeat_at) VALUES (?1, 's1', '[]', ?2, '{}', ?3)",
+            rusqlite::params![id, status, heartbeat_at],
+        )
+        .unwrap();
+    }
+
+    fn status_of(db: &Database, id: &str) -> String {
+        let conn = db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.query_row("SELECT status FROM runs WHERE id = ?1", [id], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_recovery_leaves_runs_with_a_live_heartbeat() {
+        let (db, path) = test_db();
+        let stale = (chrono::Utc::now() - chrono::Duration::seconds(STALE_HEARTBEAT_SECS + 60)).to_rfc3339();
+        insert_run(&db, "live", "running", Some(chrono::Utc::now().to_rfc3339()));
+        insert_run(&db, "stale", "running", Some(stale.clone()));
+        insert_run(&db, "stale-paused", "paused", Some(stale));
+
+        let mut recovered = recover_orphaned_runs(&db).unwrap();
+        recovered.sort();
+        assert_eq!(recovered, vec!["stale".to_string(), "stale-paused".to_string()]);
+        assert_eq!(status_of(&db, "live"), "running");
+        assert_eq!(status_of(&db, "stale"), "interrupted");
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_resume_claims_the_run_once() {
+        let (db, path) = test_db();
+        insert_run(&db, "r1", "interrupted", None);
+
+        mark_resumed(&db, "r1", &["interrupted"]).unwrap();
+        assert_eq!(status_of(&db, "r1"), "running");
+        assert!(mark_resumed(&db, "r1", &["interrupted"]).is_err());
+
+        let resumes: i64 = {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.query_row("SELECT resume_count FROM runs WHERE id = 'r1'", [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(resumes, 1);
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+}
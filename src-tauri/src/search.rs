@@ -0,0 +1,264 @@
+// Full-text search across conversation history
+//
+// Backed by the `search_index` FTS5 table, which triggers keep in sync with chat_messages, group
+// chats, debate messages, run_results and coder conversations (see migration_034 and _036). Each hit
+// carries the ids needed to jump back to its context: the item itself, its parent (conversation, run
+// or coder chat) and, for sessions, the session and project.
+
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+
+/// Hit sources, as stored in `search_index.source`
+pub const SOURCES: &[&str] = &["chat", "group_chat", "debate", "run_result", "coder_chat", "coder_ide"];
+
+// FTS markers around matched terms; replaced after HTML-escaping the snippet
+const MARK_START: &str = "\u{2}";
+const MARK_END: &str = "\u{3}";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchRequest {
+    pub query: String,
+    /// Restrict to these sources (default: all)
+    #[serde(default)]
+    pub sources: Vec<String>,
+    pub profile_id: Option<String>,
+    pub project_id: Option<String>,
+    /// Inclusive dates (YYYY-MM-DD)
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub source: String,
+    /// Message / result id (message index for older coder chats)
+    pub item_id: String,
+    /// Conversation id (chat, group_chat), run id (debate, run_result) or coder chat id
+    pub parent_id: Option<String>,
+    pub session_id: Option<String>,
+    pub profile_id: Option<String>,
+    pub project_id: Option<String>,
+    /// Session mode, to pick the right view for runs
+    pub mode: Option<String>,
+    /// Session, conversation or coder chat title
+    pub title: Option<String>,
+    pub profile_name: Option<String>,
+    pub created_at: Option<String>,
+    /// Plain-text excerpt
+    pub snippet: String,
+    /// HTML-escaped excerpt with matches wrapped in <mark>
+    pub snippet_html: String,
+    /// bm25 rank (lower is better)
+    pub score: f64,
+}
+
+/// Turn free text into an FTS5 query: every word must match, the last one as a prefix.
+/// Quoting each word keeps punctuation and FTS operators in user input from breaking the query.
+pub fn fts_query(input: &str) -> Option<String> {
+    let words: Vec<String> = input
+        .split_whitespace()
+        .map(|w| w.replace('"', ""))
+        .filter(|w| w.chars().any(|c| c.is_alphanumeric()))
+        .collect();
+    let last = words.len().checked_sub(1)?;
+    Some(
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == last { format!("\"{}\"*", w) } else { format!("\"{}\"", w) })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn highlight(raw: &str) -> (String, String) {
+    let plain = raw.replace(MARK_START, "").replace(MARK_END, "");
+    let html = escape_html(raw).replace(MARK_START, "<mark>").replace(MARK_END, "</mark>");
+    (plain, html)
+}
+
+pub fn search(db: &Database, request: &SearchRequest) -> Result<Vec<SearchHit>, String> {
+    let Some(query) = fts_query(&request.query) else {
+        return Ok(Vec::new());
+    };
+    for source in &request.sources {
+        if !SOURCES.contains(&source.as_str()) {
+            return Err(format!("Unknown search source '{}'", source));
+        }
+    }
+
+    let mut sql = format!(
+        "SELECT source, item_id, parent_id, session_id, profile_id, project_id, created_at,
+                snippet(search_index, 0, '{}', '{}', '…', 24), bm25(search_index)
+         FROM search_index WHERE search_index MATCH ?1",
+        MARK_START, MARK_END
+    );
+    let mut params: Vec<String> = vec![query];
+    let mut filter = |clause: &str, value: &str, params: &mut Vec<String>| {
+        params.push(value.to_string());
+        sql.push_str(&clause.replace('?', &format!("?{}", params.len())));
+    };
+    if let Some(profile_id) = &request.profile_id {
+        filter(" AND profile_id = ?", profile_id, &mut params);
+    }
+    if let Some(project_id) = &request.project_id {
+        filter(" AND project_id = ?", project_id, &mut params);
+    }
+    // Timestamps are stored both as RFC 3339 and as SQLite datetime; the date prefix compares either way
+    if let Some(from) = &request.date_from {
+        filter(" AND substr(created_at, 1, 10) >= ?", from, &mut params);
+    }
+    if let Some(to) = &request.date_to {
+        filter(" AND substr(created_at, 1, 10) <= ?", to, &mut params);
+    }
+    if !request.sources.is_empty() {
+        let first = params.len() + 1;
+        let placeholders: Vec<String> = (first..first + request.sources.len()).map(|i| format!("?{}", i)).collect();
+        sql.push_str(&format!(" AND source IN ({})", placeholders.join(", ")));
+        params.extend(request.sources.iter().cloned());
+    }
+    let limit = request.limit.unwrap_or(50).clamp(1, 500);
+    let offset = request.offset.unwrap_or(0).max(0);
+    sql.push_str(&format!(" ORDER BY rank LIMIT {} OFFSET {}", limit, offset));
+
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard.prepare(&sql).map_err(|e| format!("Database error: {}", e))?;
+    let mut hits = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            let (snippet, snippet_html) = highlight(&row.get::<_, String>(7)?);
+            Ok(SearchHit {
+                source: row.get(0)?,
+                item_id: row.get(1)?,
+                parent_id: row.get(2)?,
+                session_id: row.get(3)?,
+                profile_id: row.get(4)?,
+                project_id: row.get(5)?,
+                mode: None,
+                title: None,
+                profile_name: None,
+                created_at: row.get(6)?,
+                snippet,
+                snippet_html,
+                score: row.get(8)?,
+            })
+        })
+        .map_err(|e| format!("Search failed: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Search failed: {}", e))?;
+
+    // Context for display: titles and names
+    for hit in &mut hits {
+        if let Some(profile_id) = &hit.profile_id {
+            hit.profile_name = conn_guard
+                .query_row("SELECT name FROM prompt_profiles WHERE id = ?1", [profile_id], |row| row.get(0))
+                .ok();
+        }
+        let Some(parent_id) = hit.parent_id.as_deref() else { continue };
+        match hit.source.as_str() {
+            "chat" => {
+                hit.title = conn_guard
+                    .query_row("SELECT title FROM profile_conversations WHERE id = ?1", [parent_id], |row| row.get(0))
+                    .ok();
+            }
+            "group_chat" => {
+                hit.title = conn_guard
+                    .query_row("SELECT title FROM group_conversations WHERE id = ?1", [parent_id], |row| row.get(0))
+                    .ok();
+            }
+            "debate" | "run_result" => {
+                if let Some(session_id) = &hit.session_id {
+                    if let Ok((title, mode)) = conn_guard.query_row(
+                        "SELECT title, mode FROM sessions WHERE id = ?1",
+                        [session_id],
+                        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                    ) {
+                        hit.title = Some(title);
+                        hit.mode = Some(mode);
+                    }
+                }
+            }
+            "coder_chat" => {
+                hit.title = conn_guard
+                    .query_row("SELECT title FROM coder_chats WHERE id = ?1", [parent_id], |row| row.get(0))
+                    .ok();
+            }
+            "coder_ide" => {
+                hit.title = conn_guard
+                    .query_row("SELECT title FROM coder_ide_conversations WHERE id = ?1", [parent_id], |row| row.get(0))
+                    .ok();
+            }
+            _ => {}
+        }
+    }
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_words() {
+        assert_eq!(fts_query("rust OR \"async\" tra").as_deref(), Some("\"rust\" \"OR\" \"async\" \"tra\"*"));
+        assert_eq!(fts_query("  - ** "), None);
+    }
+
+    #[test]
+    fn test_index_follows_history() {
+        let path = std::env::temp_dir().join(format!("panther-search-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                     VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Q', 'debate');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('r1', 's1', '[]', 'completed', '{}');
+                 INSERT INTO messages (id, run_id, author_type, profile_id, round_index, turn_index, text)
+                     VALUES ('m1', 'r1', 'agent', 'p1', 0, 0, 'Subscriptions <beat> one-off licences');
+                 INSERT INTO chat_messages (id, profile_id, role, content, created_at) VALUES ('c1', 'p1', 'user', 'What about licences?', '2026-03-01T10:00:00Z');
+                 INSERT OR REPLACE INTO coder_chats (id, title, messages_json) VALUES ('k1', 'Fix', '[{\"id\":\"x\",\"content\":\"licence check fails\"}]');
+                 INSERT OR REPLACE INTO coder_chats (id, title, messages_json) VALUES ('k1', 'Fix', '[{\"id\":\"y\",\"content\":\"licence check passes\"}]');",
+            )
+            .unwrap();
+        }
+        let find = |query: &str, sources: &[&str]| {
+            let request = SearchRequest {
+                query: query.to_string(),
+                sources: sources.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            };
+            search(&db, &request).unwrap()
+        };
+
+        let hits = find("licen", &[]);
+        assert_eq!(hits.len(), 3);
+        let debate = find("beat", &["debate"]);
+        assert_eq!(debate[0].session_id.as_deref(), Some("s1"));
+        assert_eq!(debate[0].project_id.as_deref(), Some("pr"));
+        assert_eq!(debate[0].title.as_deref(), Some("Pricing"));
+        assert!(debate[0].snippet_html.contains("&lt;<mark>beat</mark>&gt;"));
+        assert_eq!(find("fails", &[]).len(), 0);
+        assert_eq!(find("passes", &[])[0].item_id, "y");
+
+        let dated = SearchRequest { query: "licences".to_string(), date_from: Some("2026-03-02".to_string()), sources: vec!["chat".to_string()], ..Default::default() };
+        assert!(search(&db, &dated).unwrap().is_empty());
+
+        {
+            let conn = db.get_connection();
+            conn.lock().unwrap().execute("DELETE FROM sessions WHERE id = 's1'", []).unwrap();
+        }
+        assert!(find("beat", &[]).is_empty());
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,725 @@
+// Session export/import for sharing complete debates and brainstorm runs between installs.
+//
+// The export file carries the session, all of its runs (settings, results, debate messages,
+// syntheses, debate config; oldest first) and the non-secret definition of every participating
+// profile. Version 1 files held only the latest run, under `run` with its rows at the top level.
+// On import every row gets a fresh ID; references between rows (profile ids, parent results, ids
+// inside JSON settings) are rewritten through an old -> new map. Profiles are matched to local
+// ones the importing user can see (by id and name, then name and model), or created for that user
+// under one of their provider accounts.
+//
+// Imported sessions remember the id of the session they were exported from
+// (`sessions.origin_session_id`), so importing the same file twice can be detected.
+
+use crate::consensus;
+use crate::db::Database;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+pub const EXPORT_FORMAT: &str = "panther-session";
+pub const FORMAT_VERSION: i64 = 2;
+
+const RESULT_COLUMNS: &[&str] = &[
+    "id", "profile_id", "status", "raw_output_text", "normalized_output_json", "usage_json", "error_code",
+    "error_message_safe", "started_at", "finished_at", "stage", "parent_result_id",
+];
+const MESSAGE_COLUMNS: &[&str] = &[
+    "id", "author_type", "profile_id", "round_index", "turn_index", "text", "created_at", "provider_metadata_json",
+    "usage_json", "author_label",
+];
+const SYNTHESIS_COLUMNS: &[&str] = &["id", "method", "synthesizer_profile_id", "text", "source_result_ids_json", "created_at"];
+const DEBATE_CONFIG_COLUMNS: &[&str] = &[
+    "mode", "rounds", "speaking_order_json", "context_policy", "last_k", "per_turn_budget_json", "concurrency",
+    "max_words", "language", "tone", "moderator_profile_id", "summarizer_profile_id", "consensus_json",
+    "human_seats_json", "human_turn_timeout_secs", "first_round",
+];
+const PROFILE_COLUMNS: &[&str] = &[
+    "id", "name", "model_name", "persona_prompt", "character_definition_json", "model_features_json", "params_json",
+    "photo_url", "voice_gender", "voice_uri",
+];
+
+/// What to do when the file was already imported (or comes from this install)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Import as another session
+    #[default]
+    Copy,
+    /// Keep the existing session and import nothing
+    Skip,
+    /// Delete earlier imports of the same session, then import
+    Replace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportOptions {
+    pub project_id: String,
+    #[serde(default)]
+    pub on_conflict: ConflictPolicy,
+    /// Provider account for profiles that have to be created; defaults to an account of the
+    /// same provider type as in the source install, then to the first account
+    pub provider_account_id: Option<String>,
+    /// Importing user; profiles and provider accounts of other users are neither matched nor used
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileMapping {
+    pub source_id: String,
+    pub name: String,
+    pub profile_id: String,
+    /// "matched" or "created"
+    pub action: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub session_id: String,
+    /// Latest imported run
+    pub run_id: String,
+    pub runs: usize,
+    pub mode: String,
+    /// "imported", "skipped" or "replaced"
+    pub status: String,
+    pub profiles: Vec<ProfileMapping>,
+    pub results: usize,
+    pub messages: usize,
+}
+
+fn sql_to_json(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Integer(i) => json!(i),
+        SqlValue::Real(f) => json!(f),
+        SqlValue::Text(s) => Value::String(s),
+        SqlValue::Blob(_) => Value::Null,
+    }
+}
+
+fn json_to_sql(value: Option<&Value>) -> SqlValue {
+    match value {
+        None | Some(Value::Null) => SqlValue::Null,
+        Some(Value::Bool(b)) => SqlValue::Integer(*b as i64),
+        Some(Value::Number(n)) => n
+            .as_i64()
+            .map(SqlValue::Integer)
+            .unwrap_or_else(|| SqlValue::Real(n.as_f64().unwrap_or(0.0))),
+        Some(Value::String(s)) => SqlValue::Text(s.clone()),
+        // Nested JSON is stored as text in *_json columns
+        Some(other) => SqlValue::Text(other.to_string()),
+    }
+}
+
+/// Rows of `table` as JSON objects keyed by column name.
+fn select_rows(conn: &Connection, table: &str, columns: &[&str], filter: &str, key: &str, order: &str) -> Result<Vec<Value>, String> {
+    let sql = format!("SELECT {} FROM {} WHERE {} = ?1 ORDER BY {}", columns.join(", "), table, filter, order);
+    let mut stmt = conn.prepare(&sql).map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([key], |row| {
+            let mut object = Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                object.insert(column.to_string(), sql_to_json(row.get(i)?));
+            }
+            Ok(Value::Object(object))
+        })
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    Ok(rows)
+}
+
+/// Build the export document for a session with all of its runs.
+pub fn export(conn: &Connection, session_id: &str) -> Result<Value, String> {
+    let (title, user_question, mode, created_at): (String, String, String, String) = conn
+        .query_row(
+            "SELECT title, user_question, mode, created_at FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| format!("Failed to load session: {}", e))?;
+    let origin: Option<String> = conn
+        .query_row("SELECT origin_session_id FROM sessions WHERE id = ?1", [session_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to load session: {}", e))?;
+    let run_ids: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT id FROM runs WHERE session_id = ?1 ORDER BY started_at, rowid")
+            .map_err(|e| format!("Database error: {}", e))?;
+        let ids = stmt
+            .query_map([session_id], |row| row.get(0))
+            .map_err(|e| format!("Database error: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Row error: {}", e))?;
+        ids
+    };
+    if run_ids.is_empty() {
+        return Err("Session has no runs to export".to_string());
+    }
+    let runs = run_ids
+        .iter()
+        .map(|run_id| export_run(conn, run_id, &mode))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Every profile referenced anywhere in the runs, without provider credentials
+    let mut profile_ids: Vec<String> = Vec::new();
+    for run in &runs {
+        let selected = run["selected_profile_ids"].as_array().into_iter().flatten();
+        let referenced = ["results", "messages", "syntheses"]
+            .iter()
+            .flat_map(|key| rows(run, key))
+            .filter_map(|row| row.get("profile_id").or_else(|| row.get("synthesizer_profile_id")));
+        let config = run
+            .get("debate_config")
+            .into_iter()
+            .flat_map(|c| [c.get("moderator_profile_id"), c.get("summarizer_profile_id")])
+            .flatten();
+        for id in selected.chain(referenced).chain(config).filter_map(|v| v.as_str()) {
+            if !profile_ids.iter().any(|p| p == id) {
+                profile_ids.push(id.to_string());
+            }
+        }
+    }
+    let mut profiles = Vec::new();
+    for profile_id in profile_ids.iter().filter(|id| !id.starts_with("human:")) {
+        let mut rows = select_rows(conn, "prompt_profiles", PROFILE_COLUMNS, "id", profile_id, "id")?;
+        if let Some(Value::Object(mut profile)) = rows.pop() {
+            let provider_type: Option<String> = conn
+                .query_row(
+                    "SELECT a.provider_type FROM provider_accounts a JOIN prompt_profiles p ON p.provider_account_id = a.id WHERE p.id = ?1",
+                    [profile_id],
+                    |row| row.get(0),
+                )
+                .ok();
+            profile.insert("provider_type".to_string(), json!(provider_type));
+            profiles.push(Value::Object(profile));
+        }
+    }
+
+    Ok(json!({
+        "format": EXPORT_FORMAT,
+        "format_version": FORMAT_VERSION,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "session": {
+            "id": session_id,
+            "title": title,
+            "user_question": user_question,
+            "mode": mode,
+            "created_at": created_at,
+            "origin_session_id": origin,
+        },
+        "profiles": profiles,
+        "runs": runs,
+    }))
+}
+
+/// One run of the export document with its results, messages, syntheses and debate config.
+fn export_run(conn: &Connection, run_id: &str, mode: &str) -> Result<Value, String> {
+    let (run_status, started_at, finished_at, profile_ids_json, settings_json): (String, Option<String>, Option<String>, String, String) = conn
+        .query_row(
+            "SELECT status, started_at, finished_at, selected_profile_ids_json, run_settings_json FROM runs WHERE id = ?1",
+            [run_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| format!("Failed to load run: {}", e))?;
+    let selected_profile_ids: Vec<String> = serde_json::from_str(&profile_ids_json).unwrap_or_default();
+
+    let mut run = json!({
+        "id": run_id,
+        "status": run_status,
+        "started_at": started_at,
+        "finished_at": finished_at,
+        "selected_profile_ids": selected_profile_ids,
+        "run_settings": serde_json::from_str::<Value>(&settings_json).unwrap_or(Value::Null),
+        "results": select_rows(conn, "run_results", RESULT_COLUMNS, "run_id", run_id, "started_at")?,
+        "messages": select_rows(conn, "messages", MESSAGE_COLUMNS, "run_id", run_id, "round_index, turn_index, created_at")?,
+        "syntheses": select_rows(conn, "syntheses", SYNTHESIS_COLUMNS, "run_id", run_id, "created_at")?,
+        "debate_config": select_rows(conn, "debate_configs", DEBATE_CONFIG_COLUMNS, "run_id", run_id, "run_id")?.into_iter().next(),
+    });
+    if mode == "debate" {
+        run["consensus_checks"] = json!(consensus::load_checks(conn, run_id)?);
+        run["verdict"] = json!(consensus::load_verdict(conn, run_id)?);
+    }
+    Ok(run)
+}
+
+/// Old -> new ids; rewrites exact matches and ids embedded in JSON text columns.
+struct IdMap(HashMap<String, String>);
+
+impl IdMap {
+    fn remap(&self, column: &str, value: Option<&Value>) -> SqlValue {
+        match value {
+            Some(Value::String(s)) => {
+                if let Some(new_id) = self.0.get(s) {
+                    return SqlValue::Text(new_id.clone());
+                }
+                if column.ends_with("_json") {
+                    let mut text = s.clone();
+                    for (old, new) in &self.0 {
+                        if text.contains(old.as_str()) {
+                            text = text.replace(&format!("\"{}\"", old), &format!("\"{}\"", new));
+                        }
+                    }
+                    return SqlValue::Text(text);
+                }
+                SqlValue::Text(s.clone())
+            }
+            Some(v @ (Value::Array(_) | Value::Object(_))) => self.remap(column, Some(&Value::String(v.to_string()))),
+            other => json_to_sql(other),
+        }
+    }
+
+    fn remap_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.0.get(s).cloned().unwrap_or_else(|| s.clone())),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.remap_value(v)).collect()),
+            Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), self.remap_value(v))).collect()),
+            other => other.clone(),
+        }
+    }
+}
+
+fn insert_row(conn: &Connection, table: &str, columns: &[&str], row: &Value, fixed: &[(&str, SqlValue)], ids: &IdMap) -> Result<(), String> {
+    let mut names: Vec<&str> = fixed.iter().map(|(c, _)| *c).collect();
+    let mut values: Vec<SqlValue> = fixed.iter().map(|(_, v)| v.clone()).collect();
+    for column in columns {
+        if names.contains(column) {
+            continue;
+        }
+        if let Some(value) = row.get(*column) {
+            names.push(column);
+            values.push(ids.remap(column, Some(value)));
+        }
+    }
+    let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+    let sql = format!("INSERT INTO {} ({}) VALUES ({})", table, names.join(", "), placeholders.join(", "));
+    conn.execute(&sql, rusqlite::params_from_iter(values))
+        .map_err(|e| format!("Failed to import {}: {}", table, e))?;
+    Ok(())
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+/// Local profile for an exported one: same id and name, then same name and model, else a new profile.
+/// Only profiles and provider accounts the importing user can see (theirs and shared ones) are used.
+fn resolve_profile(conn: &Connection, profile: &Value, options: &ImportOptions, now: &str) -> Result<ProfileMapping, String> {
+    let source_id = str_field(profile, "id").unwrap_or_default().to_string();
+    let name = str_field(profile, "name").unwrap_or("Imported participant").to_string();
+    let model_name = str_field(profile, "model_name").unwrap_or_default();
+
+    let matched: Option<String> = conn
+        .query_row(
+            "SELECT id FROM prompt_profiles WHERE ((id = ?1 AND name = ?2) OR (name = ?2 AND model_name = ?3))
+             AND (user_id IS NULL OR user_id = ?4) ORDER BY id = ?1 DESC LIMIT 1",
+            params![source_id, name, model_name, options.user_id],
+            |row| row.get(0),
+        )
+        .ok();
+    if let Some(profile_id) = matched {
+        return Ok(ProfileMapping { source_id, name, profile_id, action: "matched".to_string() });
+    }
+
+    let provider_account_id: String = match &options.provider_account_id {
+        Some(id) => conn
+            .query_row(
+                "SELECT id FROM provider_accounts WHERE id = ?1 AND (user_id IS NULL OR user_id = ?2)",
+                params![id, options.user_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| format!("Provider account {} not found", id))?,
+        None => conn
+            .query_row(
+                "SELECT id FROM provider_accounts WHERE user_id IS NULL OR user_id = ?2
+                 ORDER BY provider_type = ?1 DESC, created_at LIMIT 1",
+                params![str_field(profile, "provider_type").unwrap_or_default(), options.user_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| format!("Add a provider before importing: profile '{}' has to be created", name))?,
+    };
+    let profile_id = uuid::Uuid::new_v4().to_string();
+    let ids = IdMap(HashMap::new());
+    insert_row(
+        conn,
+        "prompt_profiles",
+        PROFILE_COLUMNS,
+        profile,
+        &[
+            ("id", SqlValue::Text(profile_id.clone())),
+            ("name", SqlValue::Text(name.clone())),
+            ("provider_account_id", SqlValue::Text(provider_account_id)),
+            ("model_name", SqlValue::Text(model_name.to_string())),
+            ("persona_prompt", SqlValue::Text(str_field(profile, "persona_prompt").unwrap_or_default().to_string())),
+            ("params_json", SqlValue::Text(str_field(profile, "params_json").unwrap_or("{}").to_string())),
+            ("user_id", options.user_id.clone().map(SqlValue::Text).unwrap_or(SqlValue::Null)),
+            ("created_at", SqlValue::Text(now.to_string())),
+            ("updated_at", SqlValue::Text(now.to_string())),
+        ],
+        &ids,
+    )?;
+    Ok(ProfileMapping { source_id, name, profile_id, action: "created".to_string() })
+}
+
+fn rows<'a>(export: &'a Value, key: &str) -> &'a [Value] {
+    export.get(key).and_then(|v| v.as_array()).map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Runs of an export, oldest first; a version 1 file has one `run` with its rows at the top level.
+fn export_runs(export: &Value) -> Result<Vec<Value>, String> {
+    if let Some(runs) = export.get("runs").and_then(|v| v.as_array()) {
+        if runs.is_empty() {
+            return Err("Export has no run".to_string());
+        }
+        return Ok(runs.clone());
+    }
+    let mut run = export.get("run").filter(|r| r.is_object()).cloned().ok_or("Export has no run")?;
+    for key in ["results", "messages", "syntheses", "debate_config"] {
+        if let Some(value) = export.get(key) {
+            run[key] = value.clone();
+        }
+    }
+    Ok(vec![run])
+}
+
+/// Recreate a session from an export file (see `export`; older exports without profiles are
+/// accepted as long as the referenced profiles exist locally).
+pub fn import(db: &Database, content: &str, options: &ImportOptions) -> Result<ImportSummary, String> {
+    let export: Value = serde_json::from_str(content).map_err(|e| format!("Invalid session JSON: {}", e))?;
+    if let Some(format) = str_field(&export, "format") {
+        if format != EXPORT_FORMAT {
+            return Err(format!("Not a session export (format '{}')", format));
+        }
+    }
+    let version = export.get("format_version").and_then(|v| v.as_i64()).unwrap_or(FORMAT_VERSION);
+    if version > FORMAT_VERSION {
+        return Err(format!("Session export version {} is newer than this version of Panther supports", version));
+    }
+    let session = export.get("session").ok_or("Export has no session")?;
+    let runs = export_runs(&export)?;
+    let source_session_id = str_field(session, "id").ok_or("Export session has no id")?;
+    let origin_id = str_field(session, "origin_session_id").unwrap_or(source_session_id).to_string();
+    let mode = str_field(session, "mode").unwrap_or("parallel").to_string();
+
+    let conn = db.get_connection();
+    let mut conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let project_exists: bool = conn_guard
+        .query_row("SELECT COUNT(*) FROM projects WHERE id = ?1", [&options.project_id], |row| row.get::<_, i64>(0))
+        .map(|n| n > 0)
+        .map_err(|e| format!("Database error: {}", e))?;
+    if !project_exists {
+        return Err("Project not found".to_string());
+    }
+
+    let existing: Vec<String> = {
+        let mut stmt = conn_guard
+            .prepare("SELECT id FROM sessions WHERE id = ?1 OR origin_session_id = ?1 ORDER BY created_at DESC")
+            .map_err(|e| format!("Database error: {}", e))?;
+        let ids = stmt
+            .query_map([&origin_id], |row| row.get(0))
+            .map_err(|e| format!("Database error: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Row error: {}", e))?;
+        ids
+    };
+    if options.on_conflict == ConflictPolicy::Skip {
+        if let Some(session_id) = existing.first() {
+            let run_id: String = conn_guard
+                .query_row(
+                    "SELECT id FROM runs WHERE session_id = ?1 ORDER BY started_at DESC LIMIT 1",
+                    [session_id],
+                    |row| row.get(0),
+                )
+                .unwrap_or_default();
+            return Ok(ImportSummary {
+                session_id: session_id.clone(),
+                run_id,
+                runs: 0,
+                mode,
+                status: "skipped".to_string(),
+                profiles: Vec::new(),
+                results: 0,
+                messages: 0,
+            });
+        }
+    }
+
+    let tx = conn_guard.transaction().map_err(|e| format!("Database error: {}", e))?;
+    let mut status = "imported";
+    if options.on_conflict == ConflictPolicy::Replace {
+        // Only earlier imports are replaced, never the original session of this install
+        let removed = tx
+            .execute("DELETE FROM sessions WHERE origin_session_id = ?1", [&origin_id])
+            .map_err(|e| format!("Failed to replace session: {}", e))?;
+        if removed > 0 {
+            status = "replaced";
+        }
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut ids = IdMap(HashMap::new());
+    let mut profiles = Vec::new();
+    for profile in rows(&export, "profiles") {
+        let mapping = resolve_profile(&tx, profile, options, &now)?;
+        ids.0.insert(mapping.source_id.clone(), mapping.profile_id.clone());
+        profiles.push(mapping);
+    }
+
+    // Profiles referenced without a definition (older exports) must already exist here
+    let referenced = runs.iter().flat_map(|run| {
+        run.get("selected_profile_ids")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .chain(rows(run, "results").iter().filter_map(|r| r.get("profile_id")))
+            .filter_map(|v| v.as_str())
+    });
+    for profile_id in referenced {
+        if profile_id.starts_with("human:") || ids.0.contains_key(profile_id) {
+            continue;
+        }
+        let exists: bool = tx
+            .query_row(
+                "SELECT COUNT(*) FROM prompt_profiles WHERE id = ?1 AND (user_id IS NULL OR user_id = ?2)",
+                params![profile_id, options.user_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|n| n > 0)
+            .map_err(|e| format!("Database error: {}", e))?;
+        if !exists {
+            return Err(format!("Profile {} is not in the export and does not exist locally", profile_id));
+        }
+    }
+
+    // Fresh ids up front so rows can point at rows imported after them
+    for run in &runs {
+        for key in ["results", "messages", "syntheses"] {
+            for row in rows(run, key) {
+                if let Some(old) = str_field(row, "id") {
+                    ids.0.insert(old.to_string(), uuid::Uuid::new_v4().to_string());
+                }
+            }
+        }
+    }
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let mut title = str_field(session, "title").unwrap_or("Imported session").to_string();
+    if !existing.is_empty() && status != "replaced" {
+        title = format!("{} (imported)", title);
+    }
+    tx.execute(
+        "INSERT INTO sessions (id, project_id, title, user_question, mode, created_at, updated_at, origin_session_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            session_id,
+            options.project_id,
+            title,
+            str_field(session, "user_question").unwrap_or_default(),
+            mode,
+            str_field(session, "created_at").unwrap_or(&now),
+            now,
+            origin_id,
+        ],
+    )
+    .map_err(|e| format!("Failed to import session: {}", e))?;
+
+    let new_id = |row: &Value| -> SqlValue {
+        let id = str_field(row, "id").and_then(|old| ids.0.get(old).cloned()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        SqlValue::Text(id)
+    };
+    let mut run_id = String::new();
+    for run in &runs {
+        run_id = uuid::Uuid::new_v4().to_string();
+        // A run that was still in flight when exported cannot continue here unattended
+        let run_status = match str_field(run, "status").unwrap_or("completed") {
+            "running" | "paused" | "queued" | "pending" => "interrupted",
+            other => other,
+        };
+        let selected = ids.remap_value(run.get("selected_profile_ids").unwrap_or(&json!([])));
+        let settings = ids.remap_value(run.get("run_settings").unwrap_or(&json!({})));
+        tx.execute(
+            "INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json, started_at, finished_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                run_id,
+                session_id,
+                selected.to_string(),
+                run_status,
+                settings.to_string(),
+                str_field(run, "started_at"),
+                str_field(run, "finished_at"),
+            ],
+        )
+        .map_err(|e| format!("Failed to import run: {}", e))?;
+
+        for row in rows(run, "results") {
+            let fixed = [("id", new_id(row)), ("run_id", SqlValue::Text(run_id.clone()))];
+            insert_row(&tx, "run_results", RESULT_COLUMNS, row, &fixed, &ids)?;
+        }
+        for row in rows(run, "messages") {
+            let fixed = [("id", new_id(row)), ("run_id", SqlValue::Text(run_id.clone()))];
+            insert_row(&tx, "messages", MESSAGE_COLUMNS, row, &fixed, &ids)?;
+        }
+        for row in rows(run, "syntheses") {
+            let fixed = [("id", new_id(row)), ("run_id", SqlValue::Text(run_id.clone()))];
+            insert_row(&tx, "syntheses", SYNTHESIS_COLUMNS, row, &fixed, &ids)?;
+        }
+        if let Some(config) = run.get("debate_config").filter(|c| c.is_object()) {
+            let fixed = [
+                ("id", SqlValue::Text(uuid::Uuid::new_v4().to_string())),
+                ("run_id", SqlValue::Text(run_id.clone())),
+            ];
+            insert_row(&tx, "debate_configs", DEBATE_CONFIG_COLUMNS, config, &fixed, &ids)?;
+        }
+    }
+    tx.commit().map_err(|e| format!("Failed to import session: {}", e))?;
+
+    Ok(ImportSummary {
+        session_id,
+        run_id,
+        runs: runs.len(),
+        mode,
+        status: status.to_string(),
+        profiles,
+        results: runs.iter().map(|run| rows(run, "results").len()).sum(),
+        messages: runs.iter().map(|run| rows(run, "messages").len()).sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let path = std::env::temp_dir().join(format!("panther-transfer-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO provider_accounts (id, provider_type, display_name) VALUES ('acc', 'openai', 'OpenAI');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json)
+                     VALUES ('p1', 'Ana', 'acc', 'gpt', 'You are Ana', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Raise?', 'parallel');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json, started_at) VALUES
+                     ('r0', 's1', '[\"p1\"]', 'failed', '{}', datetime('now', '-1 hour')),
+                     ('r1', 's1', '[\"p1\"]', 'completed', '{\"judge\":\"p1\"}', datetime('now'));
+                 INSERT INTO run_results (id, run_id, profile_id, status, error_code, started_at)
+                     VALUES ('x', 'r0', 'p1', 'failed', 'timeout', datetime('now', '-1 hour'));
+                 INSERT INTO run_results (id, run_id, profile_id, status, raw_output_text, started_at)
+                     VALUES ('a', 'r1', 'p1', 'completed', 'Yes', datetime('now'));
+                 INSERT INTO run_results (id, run_id, profile_id, status, raw_output_text, started_at, stage, parent_result_id)
+                     VALUES ('b', 'r1', 'p1', 'completed', 'Refined', datetime('now', '+1 second'), 'refine', 'a');",
+            )
+            .unwrap();
+        }
+        let file = {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            export(&conn, "s1").unwrap().to_string()
+        };
+        let options = ImportOptions { project_id: "pr".to_string(), on_conflict: ConflictPolicy::Copy, provider_account_id: None, user_id: None };
+
+        let first = import(&db, &file, &options).unwrap();
+        assert_eq!(first.status, "imported");
+        assert_eq!((first.runs, first.results), (2, 3));
+        assert_eq!(first.profiles[0].action, "matched");
+        assert_eq!(first.profiles[0].profile_id, "p1");
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            let (parent, settings): (String, String) = conn
+                .query_row(
+                    "SELECT r.parent_result_id, (SELECT run_settings_json FROM runs WHERE id = ?1) FROM run_results r
+                     WHERE r.run_id = ?1 AND r.stage = 'refine'",
+                    [&first.run_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap();
+            let parent_run: String = conn
+                .query_row("SELECT run_id FROM run_results WHERE id = ?1", [&parent], |row| row.get(0))
+                .unwrap();
+            assert_eq!(parent_run, first.run_id);
+            assert_eq!(settings, r#"{"judge":"p1"}"#);
+            let statuses: Vec<String> = conn
+                .prepare("SELECT status FROM runs WHERE session_id = ?1 ORDER BY started_at")
+                .unwrap()
+                .query_map([&first.session_id], |row| row.get(0))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(statuses, ["failed", "completed"]);
+        }
+
+        let skipped = import(&db, &file, &ImportOptions { on_conflict: ConflictPolicy::Skip, ..options.clone() }).unwrap();
+        assert_eq!(skipped.status, "skipped");
+        let replaced = import(&db, &file, &ImportOptions { on_conflict: ConflictPolicy::Replace, ..options }).unwrap();
+        assert_eq!(replaced.status, "replaced");
+        let conn = db.get_connection();
+        let count: i64 = conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM sessions WHERE origin_session_id = 's1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_import_matches_and_creates_profiles_for_the_importing_user() {
+        let path = std::env::temp_dir().join(format!("panther-transfer-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            // Ana exists only for another user, so importing as u1 has to create her under u1's account
+            conn.execute_batch(
+                "INSERT INTO users (id, username, email, password_hash) VALUES
+                     ('u1', 'u1', 'u1@example.com', 'x'), ('u2', 'u2', 'u2@example.com', 'x'), ('u3', 'u3', 'u3@example.com', 'x');
+                 INSERT INTO provider_accounts (id, provider_type, display_name, user_id, created_at) VALUES
+                     ('acc-u2', 'openai', 'OpenAI', 'u2', '2026-01-01'),
+                     ('acc-u1', 'anthropic', 'Anthropic', 'u1', '2026-02-01');
+                 INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json, user_id)
+                     VALUES ('p1', 'Ana', 'acc-u2', 'gpt', 'You are Ana', '{}', 'u2');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Raise?', 'parallel');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json, started_at)
+                     VALUES ('r1', 's1', '[\"p1\"]', 'completed', '{}', datetime('now'));",
+            )
+            .unwrap();
+        }
+        let file = {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            export(&conn, "s1").unwrap().to_string()
+        };
+        let options = ImportOptions {
+            project_id: "pr".to_string(),
+            on_conflict: ConflictPolicy::Copy,
+            provider_account_id: None,
+            user_id: Some("u1".to_string()),
+        };
+
+        let summary = import(&db, &file, &options).unwrap();
+        assert_eq!(summary.profiles[0].action, "created");
+        let conn = db.get_connection();
+        let (account, user): (String, String) = conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT provider_account_id, user_id FROM prompt_profiles WHERE id = ?1",
+                [&summary.profiles[0].profile_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((account.as_str(), user.as_str()), ("acc-u1", "u1"));
+
+        let foreign = ImportOptions { provider_account_id: Some("acc-u2".to_string()), user_id: Some("u3".to_string()), ..options };
+        assert!(import(&db, &file, &foreign).is_err());
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,354 @@
+// Session templates (SOPs).
+// A template is a reusable run recipe: participants, speaking order, rounds, language, tone,
+// word limit, web search, RAG project and brainstorm pipeline stages, plus debate settings.
+// Templates live in `sops`; saving under an existing name adds a new version. Text fields may
+// contain `{{variables}}` that are filled in when the template is instantiated into a session.
+
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Marker for exported template files.
+pub const EXPORT_FORMAT: &str = "panther-sop";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SopVariable {
+    pub name: String,
+    pub description: Option<String>,
+    /// Used when no value is given; variables without a default are required.
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SopTemplate {
+    pub description: Option<String>,
+    /// "parallel" or "debate"
+    pub mode: String,
+    pub title: String,
+    pub question: String,
+    pub profile_ids: Vec<String>,
+    /// Debate speaking order (profile ids and "human:<id>" seats); defaults to `profile_ids`.
+    pub speaking_order: Vec<String>,
+    pub rounds: Option<i32>,
+    pub language: Option<String>,
+    pub tone: Option<String>,
+    pub max_words: Option<i32>,
+    pub web_search: bool,
+    /// Project whose documents are retrieved as context.
+    pub rag_project_id: Option<String>,
+    /// Brainstorm stages, as in `run_settings_json.pipeline`.
+    pub pipeline: Option<Value>,
+    /// Debate settings (mode, context policy, consensus, human seats, ...).
+    pub debate: Option<Value>,
+    pub variables: Vec<SopVariable>,
+}
+
+impl Default for SopTemplate {
+    fn default() -> Self {
+        SopTemplate {
+            description: None,
+            mode: "debate".to_string(),
+            title: String::new(),
+            question: String::new(),
+            profile_ids: Vec::new(),
+            speaking_order: Vec::new(),
+            rounds: None,
+            language: None,
+            tone: None,
+            max_words: None,
+            web_search: false,
+            rag_project_id: None,
+            pipeline: None,
+            debate: None,
+            variables: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Sop {
+    pub id: String,
+    pub name: String,
+    pub version: i64,
+    pub project_id: Option<String>,
+    pub template: SopTemplate,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Names of the `{{variables}}` used in `text`, in order of first use.
+pub fn placeholders(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let name = after[..end].trim();
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Replace `{{name}}` placeholders; unknown names are left untouched.
+pub fn render(text: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        match values.get(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+impl SopTemplate {
+    fn text_fields(&self) -> Vec<&str> {
+        let mut fields = vec![self.title.as_str(), self.question.as_str()];
+        fields.extend(self.tone.as_deref());
+        fields.extend(self.language.as_deref());
+        fields
+    }
+
+    /// Declared variables plus any placeholder used in the text fields.
+    pub fn all_variables(&self) -> Vec<SopVariable> {
+        let mut variables = self.variables.clone();
+        for field in self.text_fields() {
+            for name in placeholders(field) {
+                if !variables.iter().any(|v| v.name == name) {
+                    variables.push(SopVariable { name, ..Default::default() });
+                }
+            }
+        }
+        variables
+    }
+
+    /// Fill in the variables, falling back to defaults. Errors name every missing variable.
+    pub fn instantiate(&self, values: &HashMap<String, String>) -> Result<SopTemplate, String> {
+        let mut resolved = HashMap::new();
+        let mut missing = Vec::new();
+        for variable in self.all_variables() {
+            match values
+                .get(&variable.name)
+                .filter(|v| !v.trim().is_empty())
+                .or(variable.default.as_ref())
+            {
+                Some(value) => {
+                    resolved.insert(variable.name.clone(), value.clone());
+                }
+                None => missing.push(variable.name),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(format!("Missing template variables: {}", missing.join(", ")));
+        }
+        Ok(SopTemplate {
+            title: render(&self.title, &resolved),
+            question: render(&self.question, &resolved),
+            tone: self.tone.as_deref().map(|t| render(t, &resolved)),
+            language: self.language.as_deref().map(|l| render(l, &resolved)),
+            ..self.clone()
+        })
+    }
+
+    /// `run_settings_json` for a run created from this (instantiated) template. Debates apply the
+    /// `template` style in the debate room; brainstorms read it in `orchestrator::ResponseStyle`.
+    pub fn run_settings(&self, sop: &Sop) -> Value {
+        let mut settings = json!({
+            "concurrency": 3,
+            "streaming": true,
+            "template": {
+                "sop_id": sop.id,
+                "name": sop.name,
+                "version": sop.version,
+                "speaking_order": if self.speaking_order.is_empty() { &self.profile_ids } else { &self.speaking_order },
+                "rounds": self.rounds,
+                "language": self.language,
+                "tone": self.tone,
+                "max_words": self.max_words,
+                "web_search": self.web_search,
+                "debate": self.debate,
+            },
+        });
+        if let Some(pipeline) = &self.pipeline {
+            settings["pipeline"] = pipeline.clone();
+        }
+        if let Some(project_id) = &self.rag_project_id {
+            settings["rag_project_id"] = json!(project_id);
+        }
+        settings
+    }
+}
+
+fn map_sop(row: &rusqlite::Row) -> Result<Sop, rusqlite::Error> {
+    let sop_json: String = row.get(4)?;
+    Ok(Sop {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        version: row.get(2)?,
+        project_id: row.get(3)?,
+        template: serde_json::from_str(&sop_json).unwrap_or_default(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+const SOP_COLUMNS: &str = "id, name, version, project_id, sop_json, created_at, updated_at";
+
+fn query_sops(db: &Database, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Sop>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard.prepare(sql).map_err(|e| format!("Database error: {}", e))?;
+    let sops = stmt
+        .query_map(params, map_sop)
+        .map_err(|e| format!("Database error: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Row error: {}", e))?;
+    Ok(sops)
+}
+
+/// Save a template; an existing name gets the next version.
+pub fn save(db: &Database, name: &str, project_id: Option<&str>, template: &SopTemplate) -> Result<Sop, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name is required".to_string());
+    }
+    if template.mode != "parallel" && template.mode != "debate" {
+        return Err(format!("Unknown template mode '{}'", template.mode));
+    }
+    if let Some(pipeline) = &template.pipeline {
+        crate::orchestrator::BrainstormPipeline::parse(pipeline)?;
+    }
+    let sop_json = serde_json::to_string(template).map_err(|e| format!("Failed to serialize template: {}", e))?;
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        let version: i64 = conn_guard
+            .query_row("SELECT COALESCE(MAX(version), 0) + 1 FROM sops WHERE name = ?1", [name], |row| row.get(0))
+            .map_err(|e| format!("Database error: {}", e))?;
+        conn_guard
+            .execute(
+                &format!("INSERT INTO sops ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)", SOP_COLUMNS),
+                rusqlite::params![id, name, version, project_id, sop_json, now],
+            )
+            .map_err(|e| format!("Failed to save template: {}", e))?;
+    }
+    get(db, &id)
+}
+
+pub fn get(db: &Database, sop_id: &str) -> Result<Sop, String> {
+    query_sops(db, &format!("SELECT {} FROM sops WHERE id = ?1", SOP_COLUMNS), &[&sop_id])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Template not found".to_string())
+}
+
+/// Latest version of every template, optionally limited to a project (plus global templates).
+pub fn list_latest(db: &Database, project_id: Option<&str>) -> Result<Vec<Sop>, String> {
+    query_sops(
+        db,
+        &format!(
+            "SELECT {} FROM sops s WHERE version = (SELECT MAX(version) FROM sops WHERE name = s.name)
+             AND (?1 IS NULL OR project_id IS NULL OR project_id = ?1) ORDER BY name",
+            SOP_COLUMNS
+        ),
+        &[&project_id],
+    )
+}
+
+pub fn list_versions(db: &Database, name: &str) -> Result<Vec<Sop>, String> {
+    query_sops(
+        db,
+        &format!("SELECT {} FROM sops WHERE name = ?1 ORDER BY version DESC", SOP_COLUMNS),
+        &[&name],
+    )
+}
+
+/// Delete a template with all of its versions.
+pub fn delete(db: &Database, name: &str) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute("DELETE FROM sops WHERE name = ?1", [name])
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Portable JSON for a template file.
+pub fn export(sop: &Sop) -> Value {
+    json!({
+        "format": EXPORT_FORMAT,
+        "name": sop.name,
+        "version": sop.version,
+        "template": sop.template,
+    })
+}
+
+/// Parse a template file produced by `export` (or a bare template object) into (name, template).
+pub fn parse_import(content: &str, name_override: Option<&str>) -> Result<(String, SopTemplate), String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("Invalid template JSON: {}", e))?;
+    let (file_name, template_value) = match value.get("template") {
+        Some(template) => (value.get("name").and_then(|n| n.as_str()).map(str::to_string), template.clone()),
+        None => (None, value),
+    };
+    let template: SopTemplate =
+        serde_json::from_value(template_value).map_err(|e| format!("Invalid template: {}", e))?;
+    let name = name_override
+        .map(str::to_string)
+        .or(file_name)
+        .filter(|n| !n.trim().is_empty())
+        .ok_or_else(|| "Template name is required".to_string())?;
+    Ok((name, template))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_placeholders() {
+        let text = "Should we adopt {{ topic }} in {{team}}? ({{topic}}, {{unknown}})";
+        assert_eq!(placeholders(text), vec!["topic", "team", "unknown"]);
+        let values = HashMap::from([
+            ("topic".to_string(), "Rust".to_string()),
+            ("team".to_string(), "payments".to_string()),
+        ]);
+        assert_eq!(render(text, &values), "Should we adopt Rust in payments? (Rust, {{unknown}})");
+        assert_eq!(render("unterminated {{topic", &values), "unterminated {{topic");
+    }
+
+    #[test]
+    fn test_instantiate_uses_defaults_and_reports_missing() {
+        let template = SopTemplate {
+            title: "Review: {{topic}}".to_string(),
+            question: "Debate {{topic}} for {{audience}}".to_string(),
+            variables: vec![SopVariable { name: "audience".to_string(), default: Some("engineers".to_string()), ..Default::default() }],
+            ..Default::default()
+        };
+        let err = template.instantiate(&HashMap::new()).unwrap_err();
+        assert_eq!(err, "Missing template variables: topic");
+
+        let filled = template
+            .instantiate(&HashMap::from([("topic".to_string(), "monorepos".to_string())]))
+            .unwrap();
+        assert_eq!(filled.title, "Review: monorepos");
+        assert_eq!(filled.question, "Debate monorepos for engineers");
+    }
+}
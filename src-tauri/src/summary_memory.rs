@@ -0,0 +1,509 @@
+// Rolling summary memory for long debates and profile chats.
+//
+// Older messages of a debate run or chat conversation are folded into one stored summary per
+// scope (`conversation_summaries`). Turns then receive "summary + recent messages" as
+// conversation_context instead of the raw history. Refreshes are incremental: only messages
+// after `covered_message_id` are summarized, on top of the previous (possibly user-edited) text.
+// A chat summary whose last folded message is not on the active branch is ignored and rebuilt.
+
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::types::{Message, PromptPacket};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+/// What a summary belongs to: a debate run, a chat conversation, or a profile's default chat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryScope {
+    Run,
+    Conversation,
+    Profile,
+}
+
+impl SummaryScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryScope::Run => "run",
+            SummaryScope::Conversation => "conversation",
+            SummaryScope::Profile => "profile",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryMemoryConfig {
+    pub enabled: bool,
+    /// Profile whose model writes the summary; callers fall back to the speaking profile.
+    pub summarizer_profile_id: Option<String>,
+    /// Most recent messages always sent verbatim.
+    pub keep_last_k: usize,
+    /// Minimum number of messages outside the recent window before a refresh runs.
+    pub refresh_threshold: usize,
+    /// Soft length limit for the summary text.
+    pub max_summary_words: usize,
+}
+
+impl Default for SummaryMemoryConfig {
+    fn default() -> Self {
+        SummaryMemoryConfig {
+            enabled: false,
+            summarizer_profile_id: None,
+            keep_last_k: 6,
+            refresh_threshold: 4,
+            max_summary_words: 250,
+        }
+    }
+}
+
+impl SummaryMemoryConfig {
+    /// `summary_memory` object from a profile's params_json, if present.
+    pub fn from_params(params_json: &serde_json::Value) -> Option<Self> {
+        params_json
+            .get("summary_memory")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredSummary {
+    pub scope_type: String,
+    pub scope_id: String,
+    pub summary_text: String,
+    /// created_at of the newest message folded into the summary.
+    pub covered_until: Option<String>,
+    /// Id of the newest message folded into the summary.
+    pub covered_message_id: Option<String>,
+    pub covered_count: i64,
+    pub summarizer_profile_id: Option<String>,
+    pub edited_by_user: bool,
+    pub updated_at: String,
+}
+
+pub fn load_summary(db: &Database, scope: SummaryScope, scope_id: &str) -> Result<Option<StoredSummary>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let result = conn_guard.query_row(
+        "SELECT summary_text, covered_until, covered_count, summarizer_profile_id, edited_by_user, updated_at, covered_message_id
+         FROM conversation_summaries WHERE scope_type = ?1 AND scope_id = ?2",
+        rusqlite::params![scope.as_str(), scope_id],
+        |row| {
+            Ok(StoredSummary {
+                scope_type: scope.as_str().to_string(),
+                scope_id: scope_id.to_string(),
+                summary_text: row.get(0)?,
+                covered_until: row.get(1)?,
+                covered_count: row.get(2)?,
+                summarizer_profile_id: row.get(3)?,
+                edited_by_user: row.get::<_, i64>(4)? != 0,
+                updated_at: row.get(5)?,
+                covered_message_id: row.get(6)?,
+            })
+        },
+    );
+    match result {
+        Ok(s) => Ok(Some(s)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Database error: {}", e)),
+    }
+}
+
+/// Newest folded message and the total number of folded messages.
+struct Coverage<'a> {
+    message: &'a Message,
+    count: i64,
+}
+
+fn upsert_summary(
+    db: &Database,
+    scope: SummaryScope,
+    scope_id: &str,
+    summary_text: &str,
+    covered: Option<Coverage>,
+    summarizer_profile_id: Option<&str>,
+    edited_by_user: bool,
+) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let (covered_until, covered_message_id, covered_count) = match covered {
+        Some(c) => (Some(c.message.created_at.as_str()), Some(c.message.id.as_str()), Some(c.count)),
+        None => (None, None, None),
+    };
+    conn_guard
+        .execute(
+            "INSERT INTO conversation_summaries (id, scope_type, scope_id, summary_text, covered_until, covered_count, summarizer_profile_id, edited_by_user, updated_at, covered_message_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, 0), ?7, ?8, ?9, ?10)
+             ON CONFLICT(scope_type, scope_id) DO UPDATE SET
+                summary_text = excluded.summary_text,
+                covered_until = COALESCE(?5, conversation_summaries.covered_until),
+                covered_message_id = COALESCE(?10, conversation_summaries.covered_message_id),
+                covered_count = COALESCE(?6, conversation_summaries.covered_count),
+                summarizer_profile_id = COALESCE(excluded.summarizer_profile_id, conversation_summaries.summarizer_profile_id),
+                edited_by_user = excluded.edited_by_user,
+                updated_at = excluded.updated_at",
+            rusqlite::params![
+                Uuid::new_v4().to_string(),
+                scope.as_str(),
+                scope_id,
+                summary_text,
+                covered_until,
+                covered_count,
+                summarizer_profile_id,
+                edited_by_user as i64,
+                now,
+                covered_message_id
+            ],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// Replace the summary text with a user edit. Later refreshes build on the edited text.
+pub fn save_user_edit(db: &Database, scope: SummaryScope, scope_id: &str, summary_text: &str) -> Result<(), String> {
+    upsert_summary(db, scope, scope_id, summary_text, None, None, true)
+}
+
+pub fn clear_summary(db: &Database, scope: SummaryScope, scope_id: &str) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    conn_guard
+        .execute(
+            "DELETE FROM conversation_summaries WHERE scope_type = ?1 AND scope_id = ?2",
+            rusqlite::params![scope.as_str(), scope_id],
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+/// All messages of a scope, oldest first: a debate's `messages`, or the active branch of a chat.
+pub fn load_scope_messages(db: &Database, scope: SummaryScope, scope_id: &str) -> Result<Vec<Message>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let (profile_id, conversation_id) = match scope {
+        SummaryScope::Run => return load_run_messages(&conn_guard, scope_id),
+        SummaryScope::Conversation => {
+            let profile_id: String = conn_guard
+                .query_row("SELECT profile_id FROM profile_conversations WHERE id = ?1", [scope_id], |row| row.get(0))
+                .map_err(|e| format!("Conversation not found: {}", e))?;
+            (profile_id, Some(scope_id))
+        }
+        SummaryScope::Profile => (scope_id.to_string(), None),
+    };
+    let path = crate::chat_branches::active_path(&conn_guard, &profile_id, conversation_id)?;
+    Ok(path
+        .into_iter()
+        .map(|m| Message {
+            id: m.id,
+            run_id: scope_id.to_string(),
+            profile_id: Some(profile_id.clone()),
+            author_type: m.role,
+            round_index: None,
+            turn_index: None,
+            text: m.content,
+            created_at: m.created_at,
+            provider_metadata_json: None,
+        })
+        .collect())
+}
+
+fn load_run_messages(conn: &rusqlite::Connection, run_id: &str) -> Result<Vec<Message>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, author_type, profile_id, round_index, turn_index,
+                    CASE WHEN author_label IS NOT NULL THEN '[' || author_label || '] ' || text ELSE text END, created_at
+             FROM messages WHERE run_id = ?1 ORDER BY created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok(Message {
+                id: row.get(0)?,
+                run_id: run_id.to_string(),
+                author_type: row.get(1)?,
+                profile_id: row.get(2)?,
+                round_index: row.get(3)?,
+                turn_index: row.get(4)?,
+                text: row.get(5)?,
+                created_at: row.get(6)?,
+                provider_metadata_json: None,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut messages = Vec::new();
+    for row in rows {
+        messages.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    Ok(messages)
+}
+
+/// How many leading `messages` the summary covers, or None when it was written for messages
+/// that are no longer there (a chat summary after switching to another branch).
+fn covered_len(summary: &StoredSummary, messages: &[Message]) -> Option<usize> {
+    match (&summary.covered_message_id, &summary.covered_until) {
+        (Some(id), _) => messages.iter().position(|m| &m.id == id).map(|i| i + 1),
+        // Summaries from before covered_message_id was stored
+        (None, Some(until)) => Some(messages.iter().take_while(|m| m.created_at.as_str() <= until.as_str()).count()),
+        (None, None) => Some(0),
+    }
+}
+
+/// The summary if it applies to `messages`, with the number of messages it covers.
+fn applicable<'a>(summary: Option<&'a StoredSummary>, messages: &[Message]) -> Option<(&'a StoredSummary, usize)> {
+    let summary = summary?;
+    covered_len(summary, messages).map(|len| (summary, len))
+}
+
+/// The stored summary as a leading context message, if it has any text.
+pub fn summary_message(summary: Option<&StoredSummary>, label: &str) -> Option<Message> {
+    let s = summary.filter(|s| !s.summary_text.trim().is_empty())?;
+    Some(Message {
+        id: format!("summary:{}", s.scope_id),
+        run_id: s.scope_id.clone(),
+        author_type: "user".to_string(),
+        profile_id: None,
+        round_index: None,
+        turn_index: None,
+        text: format!("Summary of the {} so far:\n{}", label, s.summary_text),
+        created_at: s.updated_at.clone(),
+        provider_metadata_json: None,
+    })
+}
+
+/// Messages not yet folded into the summary (all of them when the summary does not apply).
+pub fn uncovered_messages(summary: Option<&StoredSummary>, messages: &[Message]) -> Vec<Message> {
+    let covered = applicable(summary, messages).map(|(_, len)| len).unwrap_or(0);
+    messages[covered..].to_vec()
+}
+
+/// "Summary + recent messages": the stored summary followed by every message it does not
+/// cover yet (at most keep_last_k + refresh_threshold after a refresh).
+pub fn compose_context(summary: Option<&StoredSummary>, messages: &[Message], label: &str) -> Vec<Message> {
+    let summary = applicable(summary, messages).map(|(s, _)| s);
+    let mut context: Vec<Message> = summary_message(summary, label).into_iter().collect();
+    context.extend(uncovered_messages(summary, messages));
+    context
+}
+
+/// Messages that a refresh would fold: uncovered, outside the last-k window. Empty when fewer
+/// than `refresh_threshold` are pending (unless `force`).
+fn pending_fold<'a>(summary: Option<&StoredSummary>, messages: &'a [Message], config: &SummaryMemoryConfig, force: bool) -> &'a [Message] {
+    let first_uncovered = applicable(summary, messages).map(|(_, len)| len).unwrap_or(0);
+    let uncovered = &messages[first_uncovered..];
+    let fold_len = uncovered.len().saturating_sub(config.keep_last_k);
+    if fold_len == 0 || (!force && fold_len < config.refresh_threshold.max(1)) {
+        return &[];
+    }
+    &uncovered[..fold_len]
+}
+
+/// Fold older messages into the stored summary using the summarizer profile.
+/// Returns the updated summary, or the current one when nothing needed folding.
+pub async fn refresh(
+    db: &Database,
+    scope: SummaryScope,
+    scope_id: &str,
+    config: &SummaryMemoryConfig,
+    topic: Option<&str>,
+    speaker_name: &(dyn Fn(&Message) -> String + Sync),
+    force: bool,
+) -> Result<Option<StoredSummary>, String> {
+    let stored = load_summary(db, scope, scope_id)?;
+    let messages = load_scope_messages(db, scope, scope_id)?;
+    // A summary of another branch is rebuilt from the start of this one
+    let covered = applicable(stored.as_ref(), &messages);
+    let summary = covered.map(|(s, _)| s.clone());
+    let to_fold = pending_fold(summary.as_ref(), &messages, config, force);
+    if to_fold.is_empty() {
+        return Ok(stored);
+    }
+    let covered_count = (covered.map(|(_, len)| len).unwrap_or(0) + to_fold.len()) as i64;
+
+    let summarizer_id = config
+        .summarizer_profile_id
+        .clone()
+        .or_else(|| stored.as_ref().and_then(|s| s.summarizer_profile_id.clone()))
+        .ok_or_else(|| "No summarizer profile configured".to_string())?;
+    let (provider_account_id, model_name, params_json): (String, String, String) = {
+        let conn = db.get_connection();
+        let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+        conn_guard
+            .query_row(
+                "SELECT provider_account_id, model_name, params_json FROM prompt_profiles WHERE id = ?1",
+                [&summarizer_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| format!("Failed to load summarizer profile: {}", e))?
+    };
+    // The summarizer's own settings (max_tokens, num_ctx, ...) with a low temperature for faithful summaries
+    let mut params: serde_json::Value = serde_json::from_str(&params_json).unwrap_or_else(|_| json!({}));
+    if !params.is_object() {
+        params = json!({});
+    }
+    params["temperature"] = json!(0.2);
+
+    let transcript = to_fold
+        .iter()
+        .map(|m| format!("{}: {}", speaker_name(m), m.text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let previous = summary
+        .as_ref()
+        .map(|s| s.summary_text.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or("(none)");
+    let packet = PromptPacket {
+        global_instructions: None,
+        persona_instructions: "You maintain a running summary of a conversation. You are neutral and precise.".to_string(),
+        user_message: format!(
+            "{}Current summary:\n{}\n\nNew messages:\n{}\n\n\
+            Task: Update the summary so it also covers the new messages. Keep facts, decisions, each \
+            participant's positions and open questions; drop pleasantries. Stay under {} words. \
+            Output only the updated summary.",
+            topic.map(|t| format!("Topic:\n{}\n\n", t)).unwrap_or_default(),
+            previous,
+            transcript,
+            config.max_summary_words
+        ),
+        conversation_context: None,
+        params_json: params,
+        stream: false,
+    };
+    let (response, _, _) = complete_resolving_hybrid(db, &provider_account_id, &model_name, &packet, 120, None).await?;
+
+    let Some(last) = to_fold.last() else {
+        return Ok(stored);
+    };
+    upsert_summary(
+        db,
+        scope,
+        scope_id,
+        response.text.trim(),
+        Some(Coverage { message: last, count: covered_count }),
+        Some(&summarizer_id),
+        false,
+    )?;
+    load_summary(db, scope, scope_id)
+}
+
+/// Summarizer used when none is configured: the chat's own profile, or for a debate run its
+/// configured summarizer, moderator or first speaker.
+pub fn default_summarizer(db: &Database, scope: SummaryScope, scope_id: &str) -> Option<String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().ok()?;
+    match scope {
+        SummaryScope::Profile => Some(scope_id.to_string()),
+        SummaryScope::Conversation => conn_guard
+            .query_row("SELECT profile_id FROM profile_conversations WHERE id = ?1", [scope_id], |row| row.get(0))
+            .ok(),
+        SummaryScope::Run => {
+            let (summarizer, moderator, order_json): (Option<String>, Option<String>, String) = conn_guard
+                .query_row(
+                    "SELECT summarizer_profile_id, moderator_profile_id, speaking_order_json FROM debate_configs WHERE run_id = ?1",
+                    [scope_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .ok()?;
+            summarizer.or(moderator).or_else(|| {
+                serde_json::from_str::<Vec<String>>(&order_json).ok().and_then(|ids| ids.into_iter().next())
+            })
+        }
+    }
+}
+
+/// Speaker label for summarizer transcripts: profile name for agents, "User" for users.
+pub fn profile_speaker_names(db: &Database) -> impl Fn(&Message) -> String + Send + Sync {
+    let names: std::collections::HashMap<String, String> = {
+        let conn = db.get_connection();
+        let names = conn.lock().ok().and_then(|conn_guard| {
+            let mut stmt = conn_guard.prepare("SELECT id, name FROM prompt_profiles").ok()?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .ok()?;
+            Some(rows.filter_map(|r| r.ok()).collect())
+        });
+        names.unwrap_or_default()
+    };
+    move |m: &Message| match m.author_type.as_str() {
+        "user" => "User".to_string(),
+        "moderator" => "Moderator".to_string(),
+        _ => m
+            .profile_id
+            .as_ref()
+            .and_then(|id| names.get(id).cloned())
+            .unwrap_or_else(|| "Assistant".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(id: &str, created_at: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            run_id: "r".to_string(),
+            author_type: "agent".to_string(),
+            profile_id: None,
+            round_index: None,
+            turn_index: None,
+            text: id.to_string(),
+            created_at: created_at.to_string(),
+            provider_metadata_json: None,
+        }
+    }
+
+    fn summary(covered_until: Option<&str>) -> StoredSummary {
+        StoredSummary {
+            scope_type: "run".to_string(),
+            scope_id: "r".to_string(),
+            summary_text: "so far".to_string(),
+            covered_until: covered_until.map(|s| s.to_string()),
+            covered_message_id: None,
+            covered_count: 0,
+            summarizer_profile_id: None,
+            edited_by_user: false,
+            updated_at: "t".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compose_context_skips_covered_messages() {
+        let messages = vec![msg("a", "2024-01-01T00:00:01Z"), msg("b", "2024-01-01T00:00:02Z"), msg("c", "2024-01-01T00:00:03Z")];
+        let s = summary(Some("2024-01-01T00:00:02Z"));
+        let context = compose_context(Some(&s), &messages, "debate");
+        assert_eq!(context.len(), 2);
+        assert!(context[0].text.contains("so far"));
+        assert_eq!(context[1].id, "c");
+    }
+
+    #[test]
+    fn test_pending_fold_respects_window_and_threshold() {
+        let messages: Vec<Message> = (0..10).map(|i| msg(&i.to_string(), &format!("2024-01-01T00:00:{:02}Z", i))).collect();
+        let config = SummaryMemoryConfig { keep_last_k: 4, refresh_threshold: 3, ..Default::default() };
+        assert_eq!(pending_fold(None, &messages, &config, false).len(), 6);
+
+        // Only 2 uncovered outside the window: below threshold unless forced
+        let s = summary(Some("2024-01-01T00:00:03Z"));
+        assert!(pending_fold(Some(&s), &messages, &config, false).is_empty());
+        assert_eq!(pending_fold(Some(&s), &messages, &config, true).len(), 2);
+    }
+
+    #[test]
+    fn test_summary_of_another_branch_is_ignored() {
+        // Same timestamps on both branches: only the folded message id tells them apart
+        let branch_a = vec![msg("root", "2024-01-01T00:00:01Z"), msg("a1", "2024-01-01T00:00:02Z"), msg("a2", "2024-01-01T00:00:03Z")];
+        let branch_b = vec![msg("root", "2024-01-01T00:00:01Z"), msg("b1", "2024-01-01T00:00:02Z"), msg("b2", "2024-01-01T00:00:03Z")];
+        let s = StoredSummary { covered_message_id: Some("a1".to_string()), ..summary(Some("2024-01-01T00:00:02Z")) };
+
+        let on_a = compose_context(Some(&s), &branch_a, "conversation");
+        assert_eq!(on_a.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), vec!["summary:r", "a2"]);
+
+        let on_b = compose_context(Some(&s), &branch_b, "conversation");
+        assert_eq!(on_b.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), vec!["root", "b1", "b2"]);
+        let config = SummaryMemoryConfig { keep_last_k: 1, refresh_threshold: 1, ..Default::default() };
+        assert_eq!(pending_fold(Some(&s), &branch_b, &config, false).len(), 2, "rebuilt from the branch start");
+    }
+}
@@ -0,0 +1,646 @@
+// Tournament / ranking mode for parallel brainstorm answers.
+// One or more judge profiles compare the final answers of a run pairwise (or score them against a
+// rubric). Pairwise outcomes are turned into an Elo or Bradley–Terry rating, and the per-answer
+// ranking plus per-dimension scores are stored in `evaluations`.
+
+use crate::db::Database;
+use crate::provider_resolver::complete_resolving_hybrid;
+use crate::types::PromptPacket;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+const DEFAULT_DIMENSIONS: &[&str] = &["accuracy", "novelty", "actionability"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    pub judge_profile_ids: Vec<String>,
+    /// "pairwise" (default) or "rubric"
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// "elo" (default) or "bradley_terry"; only used in pairwise mode
+    #[serde(default = "default_rating")]
+    pub rating: String,
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    #[serde(default = "default_k_factor")]
+    pub k_factor: f64,
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+}
+
+impl TournamentConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !matches!(self.mode.as_str(), "pairwise" | "rubric") {
+            return Err(format!("Unknown tournament mode '{}'; use 'pairwise' or 'rubric'", self.mode));
+        }
+        if !matches!(self.rating.as_str(), "elo" | "bradley_terry") {
+            return Err(format!("Unknown rating '{}'; use 'elo' or 'bradley_terry'", self.rating));
+        }
+        Ok(())
+    }
+}
+
+fn default_mode() -> String {
+    "pairwise".to_string()
+}
+
+fn default_rating() -> String {
+    "elo".to_string()
+}
+
+fn default_k_factor() -> f64 {
+    32.0
+}
+
+fn default_concurrency() -> usize {
+    3
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedAnswer {
+    pub evaluation_id: String,
+    pub run_result_id: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    pub rank: i64,
+    pub overall_score: f64,
+    pub dimensions: HashMap<String, f64>,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+/// One judged comparison: score_a is 1.0 (A wins), 0.5 (tie) or 0.0 (B wins).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchOutcome {
+    pub a: usize,
+    pub b: usize,
+    pub score_a: f64,
+}
+
+struct Candidate {
+    run_result_id: String,
+    profile_id: String,
+    profile_name: String,
+    text: String,
+}
+
+struct JudgeProfile {
+    id: String,
+    provider_account_id: String,
+    model_name: String,
+    persona_prompt: String,
+    params_json: Value,
+}
+
+/// Sequential Elo over `matches`, starting every player at 1500.
+pub fn compute_elo(n: usize, matches: &[MatchOutcome], k_factor: f64) -> Vec<f64> {
+    let mut ratings = vec![1500.0; n];
+    for m in matches {
+        let expected_a = 1.0 / (1.0 + 10f64.powf((ratings[m.b] - ratings[m.a]) / 400.0));
+        let delta = k_factor * (m.score_a - expected_a);
+        ratings[m.a] += delta;
+        ratings[m.b] -= delta;
+    }
+    ratings
+}
+
+/// Bradley–Terry strengths via the MM algorithm (ties count as half a win each).
+/// Returned strengths are normalized to sum to `n`.
+pub fn compute_bradley_terry(n: usize, matches: &[MatchOutcome], iterations: usize) -> Vec<f64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut wins = vec![0.0f64; n];
+    let mut games = vec![vec![0.0f64; n]; n];
+    for m in matches {
+        wins[m.a] += m.score_a;
+        wins[m.b] += 1.0 - m.score_a;
+        games[m.a][m.b] += 1.0;
+        games[m.b][m.a] += 1.0;
+    }
+
+    let mut strength = vec![1.0f64; n];
+    for _ in 0..iterations {
+        let mut next = vec![0.0f64; n];
+        for i in 0..n {
+            let denom: f64 = (0..n)
+                .filter(|&j| j != i && games[i][j] > 0.0)
+                .map(|j| games[i][j] / (strength[i] + strength[j]))
+                .sum();
+            // Small prior keeps winless players from collapsing to zero
+            next[i] = if denom > 0.0 { (wins[i] + 0.1) / denom } else { strength[i] };
+        }
+        let total: f64 = next.iter().sum();
+        if total > 0.0 {
+            for s in &mut next {
+                *s *= n as f64 / total;
+            }
+        }
+        strength = next;
+    }
+    strength
+}
+
+/// Extract the first JSON object from a judge reply (models often wrap JSON in prose or code fences).
+pub fn extract_json_object(text: &str) -> Option<Value> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    serde_json::from_str(&text[start..=end]).ok()
+}
+
+fn dimensions_or_default(config: &TournamentConfig) -> Vec<String> {
+    if config.dimensions.is_empty() {
+        DEFAULT_DIMENSIONS.iter().map(|d| d.to_string()).collect()
+    } else {
+        config.dimensions.clone()
+    }
+}
+
+fn load_candidates(db: &Database, run_id: &str) -> Result<(String, Vec<Candidate>), String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+
+    let question: String = conn_guard
+        .query_row(
+            "SELECT s.user_question FROM sessions s JOIN runs r ON r.session_id = s.id WHERE r.id = ?1",
+            [run_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to get session: {}", e))?;
+
+    // Latest answer per profile (refine supersedes generate; critiques are not answers)
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT rr.id, rr.profile_id, pp.name, rr.raw_output_text
+             FROM run_results rr
+             JOIN prompt_profiles pp ON pp.id = rr.profile_id
+             WHERE rr.run_id = ?1 AND rr.status = 'complete' AND rr.raw_output_text IS NOT NULL
+               AND rr.stage != 'critique'
+             ORDER BY rr.finished_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok(Candidate {
+                run_result_id: row.get(0)?,
+                profile_id: row.get(1)?,
+                profile_name: row.get(2)?,
+                text: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut by_profile: Vec<Candidate> = Vec::new();
+    for row in rows {
+        let candidate = row.map_err(|e| format!("Row error: {}", e))?;
+        by_profile.retain(|c| c.profile_id != candidate.profile_id);
+        by_profile.push(candidate);
+    }
+    Ok((question, by_profile))
+}
+
+fn load_judges(db: &Database, judge_profile_ids: &[String]) -> Result<Vec<JudgeProfile>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    judge_profile_ids
+        .iter()
+        .map(|id| {
+            conn_guard
+                .query_row(
+                    "SELECT id, provider_account_id, model_name, persona_prompt, params_json FROM prompt_profiles WHERE id = ?1",
+                    [id],
+                    |row| {
+                        Ok(JudgeProfile {
+                            id: row.get(0)?,
+                            provider_account_id: row.get(1)?,
+                            model_name: row.get(2)?,
+                            persona_prompt: row.get(3)?,
+                            params_json: serde_json::from_str(&row.get::<_, String>(4)?)
+                                .unwrap_or_else(|_| serde_json::json!({})),
+                        })
+                    },
+                )
+                .map_err(|e| format!("Failed to load judge profile {}: {}", id, e))
+        })
+        .collect()
+}
+
+async fn ask_judge(db: &Database, judge: &JudgeProfile, prompt: String, semaphore: &Arc<Semaphore>) -> Option<Value> {
+    let _permit = semaphore.acquire().await.ok()?;
+    let mut params = judge.params_json.clone();
+    // Judging should be as deterministic as the provider allows
+    params["temperature"] = serde_json::json!(0.0);
+    let packet = PromptPacket {
+        global_instructions: Some(
+            "You are an impartial judge. Evaluate strictly on the merits and reply with a single JSON object only.".to_string(),
+        ),
+        persona_instructions: judge.persona_prompt.clone(),
+        user_message: prompt,
+        conversation_context: None,
+        params_json: params,
+        stream: false,
+    };
+    match complete_resolving_hybrid(db, &judge.provider_account_id, &judge.model_name, &packet, 120, None).await {
+        Ok((resp, _, _)) => extract_json_object(&resp.text),
+        Err(e) => {
+            eprintln!("Judge {} failed: {}", judge.id, e);
+            None
+        }
+    }
+}
+
+fn pairwise_prompt(question: &str, first: &Candidate, second: &Candidate, dimensions: &[String]) -> String {
+    format!(
+        "Question:\n{}\n\n## Answer A\n{}\n\n## Answer B\n{}\n\n\
+        Compare the two answers. Score each on these dimensions from 1 to 10: {}.\n\
+        Reply with JSON only, in this shape:\n\
+        {{\"winner\": \"A\" | \"B\" | \"tie\", \"dimensions\": {{\"<dimension>\": {{\"A\": <score>, \"B\": <score>}}}}, \"reason\": \"<one sentence>\"}}",
+        question,
+        first.text,
+        second.text,
+        dimensions.join(", ")
+    )
+}
+
+fn rubric_prompt(question: &str, candidate: &Candidate, dimensions: &[String]) -> String {
+    format!(
+        "Question:\n{}\n\n## Answer\n{}\n\n\
+        Score the answer on these dimensions from 1 to 10: {}.\n\
+        Reply with JSON only, in this shape:\n\
+        {{\"dimensions\": {{\"<dimension>\": <score>}}, \"reason\": \"<one sentence>\"}}",
+        question,
+        candidate.text,
+        dimensions.join(", ")
+    )
+}
+
+/// Run a judged tournament over the final answers of `run_id` and persist the ranking.
+pub async fn run_tournament(db: &Database, run_id: &str, config: &TournamentConfig) -> Result<Vec<RankedAnswer>, String> {
+    config.validate()?;
+    if config.judge_profile_ids.is_empty() {
+        return Err("At least one judge profile is required".to_string());
+    }
+    let (question, candidates) = load_candidates(db, run_id)?;
+    if candidates.len() < 2 {
+        return Err("A tournament needs at least two completed answers".to_string());
+    }
+    let judges = load_judges(db, &config.judge_profile_ids)?;
+    let dimensions = dimensions_or_default(config);
+    let semaphore = Arc::new(Semaphore::new(config.concurrency.max(1)));
+    let n = candidates.len();
+
+    // dimension -> per-candidate (sum, count)
+    let mut dim_totals: HashMap<String, Vec<(f64, u32)>> = dimensions
+        .iter()
+        .map(|d| (d.clone(), vec![(0.0, 0); n]))
+        .collect();
+    let mut record = vec![(0u32, 0u32, 0u32); n];
+    let mut add_dim = |dim: &str, idx: usize, score: f64| {
+        if let Some(totals) = dim_totals.get_mut(dim) {
+            totals[idx].0 += score;
+            totals[idx].1 += 1;
+        }
+    };
+
+    let ratings: Vec<f64> = if config.mode == "rubric" {
+        let jobs: Vec<(usize, _)> = judges
+            .iter()
+            .flat_map(|judge| (0..n).map(move |i| (i, judge)))
+            .map(|(i, judge)| (i, ask_judge(db, judge, rubric_prompt(&question, &candidates[i], &dimensions), &semaphore)))
+            .collect();
+        let (indices, futures): (Vec<usize>, Vec<_>) = jobs.into_iter().unzip();
+        let replies = join_all(futures).await;
+        let mut scored = 0;
+        for (i, reply) in indices.into_iter().zip(replies) {
+            if let Some(dims) = reply.as_ref().and_then(|r| r.get("dimensions")).and_then(|d| d.as_object()) {
+                let mut any = false;
+                for (dim, score) in dims {
+                    if let Some(score) = score.as_f64() {
+                        add_dim(dim, i, score);
+                        any = true;
+                    }
+                }
+                scored += any as usize;
+            }
+        }
+        if scored == 0 {
+            return Err("No judge produced a usable verdict".to_string());
+        }
+        (0..n)
+            .map(|i| {
+                let scores: Vec<f64> = dim_totals
+                    .values()
+                    .filter(|t| t[i].1 > 0)
+                    .map(|t| t[i].0 / t[i].1 as f64)
+                    .collect();
+                if scores.is_empty() { 0.0 } else { scores.iter().sum::<f64>() / scores.len() as f64 }
+            })
+            .collect()
+    } else {
+        // Every pair is judged once per judge; alternate presentation order to offset position bias
+        let mut pairs = Vec::new();
+        for (j_idx, judge) in judges.iter().enumerate() {
+            for a in 0..n {
+                for b in (a + 1)..n {
+                    let (first, second) = if (a + b + j_idx) % 2 == 0 { (a, b) } else { (b, a) };
+                    pairs.push((first, second, judge));
+                }
+            }
+        }
+        let (orders, futures): (Vec<(usize, usize)>, Vec<_>) = pairs
+            .into_iter()
+            .map(|(first, second, judge)| {
+                let prompt = pairwise_prompt(&question, &candidates[first], &candidates[second], &dimensions);
+                ((first, second), ask_judge(db, judge, prompt, &semaphore))
+            })
+            .unzip();
+        let replies = join_all(futures).await;
+
+        let mut matches = Vec::new();
+        for ((first, second), reply) in orders.into_iter().zip(replies) {
+            let Some(reply) = reply else { continue };
+            // A verdict without a recognisable winner is not a tie; leave the pair unjudged
+            let winner = reply.get("winner").and_then(|w| w.as_str()).unwrap_or_default().trim().to_uppercase();
+            let score_first = match winner.as_str() {
+                "A" => 1.0,
+                "B" => 0.0,
+                "TIE" => 0.5,
+                _ => {
+                    eprintln!("Judge reply without a winner: {}", reply);
+                    continue;
+                }
+            };
+            matches.push(MatchOutcome { a: first, b: second, score_a: score_first });
+            match winner.as_str() {
+                "A" => {
+                    record[first].0 += 1;
+                    record[second].1 += 1;
+                }
+                "B" => {
+                    record[second].0 += 1;
+                    record[first].1 += 1;
+                }
+                _ => {
+                    record[first].2 += 1;
+                    record[second].2 += 1;
+                }
+            }
+            if let Some(dims) = reply.get("dimensions").and_then(|d| d.as_object()) {
+                for (dim, scores) in dims {
+                    if let Some(s) = scores.get("A").and_then(|v| v.as_f64()) {
+                        add_dim(dim, first, s);
+                    }
+                    if let Some(s) = scores.get("B").and_then(|v| v.as_f64()) {
+                        add_dim(dim, second, s);
+                    }
+                }
+            }
+        }
+        if matches.is_empty() {
+            return Err("No judge produced a usable verdict".to_string());
+        }
+        if config.rating == "bradley_terry" {
+            compute_bradley_terry(n, &matches, 100)
+        } else {
+            compute_elo(n, &matches, config.k_factor)
+        }
+    };
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&x, &y| ratings[y].partial_cmp(&ratings[x]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let method = if config.mode == "rubric" {
+        "tournament_rubric".to_string()
+    } else {
+        format!("tournament_{}", config.rating)
+    };
+
+    let ranked: Vec<RankedAnswer> = order
+        .iter()
+        .enumerate()
+        .map(|(pos, &i)| RankedAnswer {
+            evaluation_id: Uuid::new_v4().to_string(),
+            run_result_id: candidates[i].run_result_id.clone(),
+            profile_id: candidates[i].profile_id.clone(),
+            profile_name: candidates[i].profile_name.clone(),
+            rank: pos as i64 + 1,
+            overall_score: ratings[i],
+            dimensions: dim_totals
+                .iter()
+                .filter(|(_, t)| t[i].1 > 0)
+                .map(|(d, t)| (d.clone(), t[i].0 / t[i].1 as f64))
+                .collect(),
+            wins: record[i].0,
+            losses: record[i].1,
+            ties: record[i].2,
+        })
+        .collect();
+
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    // A new tournament replaces the previous ranking for this run
+    conn_guard
+        .execute("DELETE FROM evaluations WHERE run_id = ?1 AND method LIKE 'tournament_%'", [run_id])
+        .map_err(|e| format!("Database error: {}", e))?;
+    for r in &ranked {
+        let details = serde_json::json!({
+            "wins": r.wins,
+            "losses": r.losses,
+            "ties": r.ties,
+            "judge_profile_ids": config.judge_profile_ids,
+        });
+        conn_guard
+            .execute(
+                "INSERT INTO evaluations (id, run_id, run_result_id, profile_id, method, rank, dimensions_json, overall_score, details_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    r.evaluation_id,
+                    run_id,
+                    r.run_result_id,
+                    r.profile_id,
+                    method,
+                    r.rank,
+                    serde_json::to_string(&r.dimensions).unwrap_or_else(|_| "{}".to_string()),
+                    r.overall_score,
+                    details.to_string(),
+                ],
+            )
+            .map_err(|e| format!("Failed to save evaluation: {}", e))?;
+    }
+
+    Ok(ranked)
+}
+
+/// Load the stored ranking for a run, best first.
+pub fn load_evaluations(db: &Database, run_id: &str) -> Result<Vec<Value>, String> {
+    let conn = db.get_connection();
+    let conn_guard = conn.lock().map_err(|e| format!("Database lock error: {}", e))?;
+    let mut stmt = conn_guard
+        .prepare(
+            "SELECT e.id, e.run_result_id, e.profile_id, pp.name, e.method, e.rank, e.dimensions_json, e.overall_score, e.details_json, e.created_at
+             FROM evaluations e
+             LEFT JOIN prompt_profiles pp ON pp.id = e.profile_id
+             WHERE e.run_id = ?1
+             ORDER BY e.rank IS NULL, e.rank, e.created_at",
+        )
+        .map_err(|e| format!("Database error: {}", e))?;
+    let rows = stmt
+        .query_map([run_id], |row| {
+            Ok(serde_json::json!({
+                "id": row.get::<_, String>(0)?,
+                "run_result_id": row.get::<_, Option<String>>(1)?,
+                "profile_id": row.get::<_, Option<String>>(2)?,
+                "profile_name": row.get::<_, Option<String>>(3)?,
+                "method": row.get::<_, Option<String>>(4)?,
+                "rank": row.get::<_, Option<i64>>(5)?,
+                "dimensions": serde_json::from_str::<Value>(&row.get::<_, String>(6)?).unwrap_or_else(|_| serde_json::json!({})),
+                "overall_score": row.get::<_, Option<f64>>(7)?,
+                "details": row.get::<_, Option<String>>(8)?.and_then(|s| serde_json::from_str::<Value>(&s).ok()),
+                "created_at": row.get::<_, String>(9)?,
+            }))
+        })
+        .map_err(|e| format!("Database error: {}", e))?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| format!("Row error: {}", e))?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elo_winner_gains_rating() {
+        let matches = vec![
+            MatchOutcome { a: 0, b: 1, score_a: 1.0 },
+            MatchOutcome { a: 0, b: 2, score_a: 1.0 },
+            MatchOutcome { a: 1, b: 2, score_a: 0.5 },
+        ];
+        let ratings = compute_elo(3, &matches, 32.0);
+        assert!(ratings[0] > ratings[1]);
+        assert!(ratings[0] > ratings[2]);
+        let total: f64 = ratings.iter().sum();
+        assert!((total - 4500.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bradley_terry_orders_by_strength() {
+        let matches = vec![
+            MatchOutcome { a: 0, b: 1, score_a: 1.0 },
+            MatchOutcome { a: 1, b: 2, score_a: 1.0 },
+            MatchOutcome { a: 0, b: 2, score_a: 1.0 },
+            MatchOutcome { a: 2, b: 1, score_a: 0.5 },
+        ];
+        let strength = compute_bradley_terry(3, &matches, 200);
+        assert!(strength[0] > strength[1]);
+        assert!(strength[1] > strength[2]);
+    }
+
+    #[test]
+    fn test_extract_json_object_from_fenced_reply() {
+        let reply = "Here is my verdict:\n```json\n{\"winner\": \"B\", \"reason\": \"clearer\"}\n```";
+        let v = extract_json_object(reply).unwrap();
+        assert_eq!(v["winner"], "B");
+        assert!(extract_json_object("no json here").is_none());
+    }
+
+    /// Ollama-compatible judge: model "judge" prefers the answer starting with "Strong",
+    /// model "vague" replies without a winner or scores
+    async fn mock_judge() -> String {
+        async fn chat(axum::Json(body): axum::Json<Value>) -> axum::Json<Value> {
+            let prompt = body["messages"].as_array().and_then(|m| m.last()).and_then(|m| m["content"].as_str()).unwrap_or_default().to_string();
+            let verdict = if body["model"] == "vague" {
+                serde_json::json!({ "reason": "Both have merit." })
+            } else if prompt.contains("## Answer A") {
+                let winner = if prompt.contains("## Answer A\nStrong") { "A" } else { "B" };
+                serde_json::json!({ "winner": winner, "dimensions": { "accuracy": { "A": 8, "B": 4 } } })
+            } else {
+                serde_json::json!({ "dimensions": { "accuracy": if prompt.contains("## Answer\nStrong") { 9 } else { 3 } } })
+            };
+            axum::Json(serde_json::json!({ "message": { "content": verdict.to_string() }, "done": true }))
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let app = axum::Router::new().route("/api/chat", axum::routing::post(chat));
+        tokio::spawn(async move { axum::serve(listener, app).await.ok() });
+        format!("http://{}", address)
+    }
+
+    async fn test_db() -> (Database, std::path::PathBuf) {
+        let base_url = mock_judge().await;
+        let path = std::env::temp_dir().join(format!("panther-tournament-{}.db", Uuid::new_v4()));
+        let db = Database::new(path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO provider_accounts (id, provider_type, display_name, base_url) VALUES ('acc', 'ollama', 'Ollama', ?1)",
+                [base_url],
+            )
+            .unwrap();
+            conn.execute_batch(
+                "INSERT INTO prompt_profiles (id, name, provider_account_id, model_name, persona_prompt, params_json) VALUES
+                     ('ana', 'Ana', 'acc', 'llama', 'You are Ana', '{}'),
+                     ('ben', 'Ben', 'acc', 'llama', 'You are Ben', '{}'),
+                     ('judge', 'Judge', 'acc', 'judge', 'You judge', '{}'),
+                     ('vague', 'Vague', 'acc', 'vague', 'You judge', '{}');
+                 INSERT INTO projects (id, name, created_at, updated_at) VALUES ('pr', 'P', datetime('now'), datetime('now'));
+                 INSERT INTO sessions (id, project_id, title, user_question, mode) VALUES ('s1', 'pr', 'Pricing', 'Q', 'parallel');
+                 INSERT INTO runs (id, session_id, selected_profile_ids_json, status, run_settings_json) VALUES ('r1', 's1', '[]', 'complete', '{}');
+                 INSERT INTO run_results (id, run_id, profile_id, status, raw_output_text, finished_at, stage) VALUES
+                     ('res-ana', 'r1', 'ana', 'complete', 'Weak answer', '2026-01-01T00:00:01Z', 'generate'),
+                     ('res-ben', 'r1', 'ben', 'complete', 'Strong answer', '2026-01-01T00:00:02Z', 'generate');",
+            )
+            .unwrap();
+        }
+        (db, path)
+    }
+
+    fn config(mode: &str, judges: &[&str]) -> TournamentConfig {
+        let mut config: TournamentConfig = serde_json::from_value(serde_json::json!({
+            "judge_profile_ids": judges,
+            "mode": mode,
+        }))
+        .unwrap();
+        config.concurrency = 2;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_run_tournament_ranks_judged_answers() {
+        let (db, path) = test_db().await;
+
+        let ranked = run_tournament(&db, "r1", &config("pairwise", &["judge", "vague"])).await.unwrap();
+        assert_eq!(ranked[0].profile_id, "ben");
+        // The winnerless verdict is dropped, not counted as a tie
+        assert_eq!((ranked[0].wins, ranked[0].losses, ranked[0].ties), (1, 0, 0));
+        assert_eq!(ranked[0].dimensions["accuracy"], 8.0);
+        assert_eq!(load_evaluations(&db, "r1").unwrap().len(), 2);
+
+        let ranked = run_tournament(&db, "r1", &config("rubric", &["judge"])).await.unwrap();
+        assert_eq!((ranked[0].profile_id.as_str(), ranked[0].overall_score), ("ben", 9.0));
+
+        for mode in ["pairwise", "rubric"] {
+            let err = run_tournament(&db, "r1", &config(mode, &["vague"])).await.unwrap_err();
+            assert!(err.contains("No judge produced a usable verdict"), "{}: {}", mode, err);
+        }
+        assert!(run_tournament(&db, "r1", &config("knockout", &["judge"])).await.unwrap_err().contains("Unknown tournament mode"));
+        let mut bad_rating = config("pairwise", &["judge"]);
+        bad_rating.rating = "glicko".to_string();
+        assert!(run_tournament(&db, "r1", &bad_rating).await.unwrap_err().contains("Unknown rating"));
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -60,4 +60,56 @@ pub struct CharacterDefinition {
     pub goals: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub constraints: Option<Vec<String>>,
+    /// Set when the definition was generated from sources (see `character_sources`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<CharacterProvenance>,
+}
+
+/// Material a character definition can be generated from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CharacterSource {
+    Url { url: String },
+    /// Local file read with the training-import extractors (PDF, DOCX, RTF, text)
+    Document { path: String },
+    /// Pasted text (interview, meeting or chat transcript); kept so it can be re-used on refresh
+    Transcript {
+        #[serde(default)]
+        label: Option<String>,
+        text: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRecord {
+    /// Short id the model cites ("S1", "S2", ...)
+    pub id: String,
+    #[serde(flatten)]
+    pub source: CharacterSource,
+    /// SHA-256 of the extracted text, used to detect changed sources
+    pub content_hash: String,
+    pub chars: usize,
+    pub extracted_at: String,
+}
+
+/// Sources that support one value of the definition (e.g. one expertise entry)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldProvenance {
+    /// role | personality | expertise | communication_style | background | goals | constraints
+    pub field: String,
+    pub value: String,
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterProvenance {
+    pub sources: Vec<SourceRecord>,
+    pub fields: Vec<FieldProvenance>,
+    #[serde(default)]
+    pub person_name: Option<String>,
+    /// Empty in shared character cards; refresh then uses the profile's provider
+    #[serde(default)]
+    pub provider_account_id: String,
+    pub model_name: String,
+    pub generated_at: String,
 }
@@ -10,11 +10,13 @@ import { ParallelBrainstorm } from './pages/ParallelBrainstorm';
 import { DebateRoom } from './pages/DebateRoom';
 import { Compare } from './pages/Compare';
 import { ProfileChat } from './pages/ProfileChat';
+import { GroupChat } from './pages/GroupChat';
 import { ProjectTraining } from './pages/ProjectTraining';
+import { Search } from './pages/Search';
 // Lazy-load Monaco-heavy pages so the main bundle stays smaller
 const SimpleCoder = lazy(() => import('./pages/SimpleCoder').then((m) => ({ default: m.SimpleCoder })));
 const AgentRuns = lazy(() => import('./pages/AgentRuns').then((m) => ({ default: m.AgentRuns })));
-import { PrivacyModal, DependenciesModal, OllamaModal, TrainingCacheModal, TokenUsageModal, VoiceModal } from './pages/Settings';
+import { PrivacyModal, DependenciesModal, OllamaModal, TrainingCacheModal, TokenUsageModal, VoiceModal, McpModal } from './pages/Settings';
 import { MenuBar } from './components/MenuBar';
 import { AuthModal } from './components/AuthModal';
 import { ErrorBoundary } from './components/ErrorBoundary';
@@ -27,7 +29,7 @@ import './App.css';
 // Authentication is now optional - no protected routes
 
 function AppContent() {
-  const _navigate = useNavigate(); // Keep for potential future use
+  const navigate = useNavigate();
   const { isAuthenticated, setUser, theme, language } = useAppStore();
   const userId = localStorage.getItem('userId');
   const [authModalOpen, setAuthModalOpen] = useState(false);
@@ -40,6 +42,8 @@ function AppContent() {
   const [trainingCacheModalOpen, setTrainingCacheModalOpen] = useState(false);
   const [tokenUsageModalOpen, setTokenUsageModalOpen] = useState(false);
   const [voiceModalOpen, setVoiceModalOpen] = useState(false);
+  const [mcpModalOpen, setMcpModalOpen] = useState(false);
+  const [interruptedRuns, setInterruptedRuns] = useState<any[]>([]);
 
   // Check if running in browser mode - now a supported mode with HTTP backend
   useEffect(() => {
@@ -51,6 +55,24 @@ function AppContent() {
     }
   }, []);
 
+  // Runs left behind by a quit or crash (marked interrupted on backend startup)
+  useEffect(() => {
+    api.listInterruptedRuns()
+      .then(runs => setInterruptedRuns(runs || []))
+      .catch(() => setInterruptedRuns([]));
+  }, []);
+
+  const handleResumeRun = async (run: any) => {
+    try {
+      await api.resumeRun(run.run_id);
+      setInterruptedRuns(prev => prev.filter(r => r.run_id !== run.run_id));
+      const kind = run.resume_point?.kind === 'brainstorm' ? 'parallel-brainstorm' : 'debate-room';
+      navigate(`/${kind}/${run.run_id}`);
+    } catch (err) {
+      alert(`Failed to resume run: ${err}`);
+    }
+  };
+
   useEffect(() => {
     // Apply theme on mount
     document.documentElement.setAttribute('data-theme', theme);
@@ -137,6 +159,8 @@ function AppContent() {
         setTokenUsageModalOpen(true);
       } else if (tab === 'voice') {
         setVoiceModalOpen(true);
+      } else if (tab === 'mcp') {
+        setMcpModalOpen(true);
       }
     };
     
@@ -214,9 +238,59 @@ function AppContent() {
               </div>
             </div>
           )}
+          {interruptedRuns.length > 0 && (
+            <div style={{
+              background: '#d1ecf1',
+              border: '1px solid #bee5eb',
+              padding: '12px 20px',
+              display: 'flex',
+              justifyContent: 'space-between',
+              alignItems: 'center',
+              position: 'relative'
+            }}>
+              <div style={{ flex: 1, color: '#0c5460' }}>
+                <strong>Interrupted runs</strong>
+                <span style={{ marginLeft: '10px', fontSize: '14px' }}>
+                  {interruptedRuns.length} run(s) stopped when the app quit:
+                </span>
+                {interruptedRuns.map(run => (
+                  <button
+                    key={run.run_id}
+                    onClick={() => handleResumeRun(run)}
+                    style={{
+                      marginLeft: '10px',
+                      background: '#fff',
+                      border: '1px solid #0c5460',
+                      borderRadius: '4px',
+                      padding: '4px 12px',
+                      cursor: 'pointer',
+                      fontSize: '12px',
+                      color: '#0c5460'
+                    }}
+                  >
+                    Resume {run.session_title || run.run_id.slice(0, 8)}
+                  </button>
+                ))}
+              </div>
+              <button
+                onClick={() => setInterruptedRuns([])}
+                style={{
+                  background: 'transparent',
+                  border: '1px solid #0c5460',
+                  borderRadius: '4px',
+                  padding: '4px 12px',
+                  cursor: 'pointer',
+                  fontSize: '12px',
+                  color: '#0c5460'
+                }}
+              >
+                Dismiss
+              </button>
+            </div>
+          )}
           <MenuBar />
           <div style={{
-            height: `calc(100vh - 40px - ${backendDown ? 52 : 0}px - ${showBrowserWarning ? 52 : 0}px)`
+            height: `calc(100vh - 40px - ${backendDown ? 52 : 0}px - ${showBrowserWarning ? 52 : 0}px - ${interruptedRuns.length > 0 ? 52 : 0}px)`
           }}>
             <main className="main-content" style={{ width: '100%', height: '100%', overflow: 'auto' }}>
               <Suspense fallback={<div className="main-content" style={{ display: 'flex', alignItems: 'center', justifyContent: 'center', minHeight: 200 }}>Loading…</div>}>
@@ -234,8 +308,10 @@ function AppContent() {
                 <Route path="/debate-room/:runId" element={<DebateRoom />} />
                 <Route path="/compare/:runId" element={<Compare />} />
                 <Route path="/profile-chat/:profileId" element={<ProfileChat />} />
+                <Route path="/group-chat" element={<GroupChat />} />
                 <Route path="/project-training/:projectId" element={<ProjectTraining />} />
                 <Route path="/agent-runs" element={<AgentRuns />} />
+                <Route path="/search" element={<Search />} />
               </Routes>
               </Suspense>
             </main>
@@ -263,6 +339,9 @@ function AppContent() {
             {voiceModalOpen && (
               <VoiceModal onClose={() => setVoiceModalOpen(false)} />
             )}
+            {mcpModalOpen && (
+              <McpModal onClose={() => setMcpModalOpen(false)} />
+            )}
           </div>
         </div>
       </ErrorBoundary>
@@ -2,6 +2,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { isTauri } from '../utils/tauri';
+import type { CharacterDefinition, CharacterSource } from '../types';
 
 // Check if running in Tauri
 const TAURI_AVAILABLE = isTauri();
@@ -96,6 +97,367 @@ async function httpFetch<T>(
   return (text ? JSON.parse(text) : undefined) as T;
 }
 
+export type DebateMode = 'sequential' | 'parallel' | 'moderator' | 'cross_examination';
+export type DebateContextPolicy = 'full' | 'last_k_messages' | 'rolling_summary';
+
+export interface DebateSettings {
+  mode?: DebateMode;
+  context_policy?: DebateContextPolicy;
+  last_k?: number;
+  moderator_profile_id?: string;
+  /** Writes the rolling summary; defaults to the moderator, then the first speaker */
+  summarizer_profile_id?: string;
+  concurrency?: number;
+  /** Judge step after each round with early stop and a final verdict */
+  consensus?: ConsensusConfig;
+  /** Human participants; listed in speakingOrder as `human:<id>` */
+  human_seats?: HumanSeat[];
+  /** Seconds a human turn waits before it is skipped; 0 waits indefinitely */
+  human_turn_timeout_secs?: number;
+}
+
+export interface HumanSeat {
+  id: string;
+  /** Role the seat's messages are attributed to, e.g. "Product Owner" */
+  role: string;
+}
+
+export interface HumanTurn {
+  id: string;
+  run_id: string;
+  seat_id: string;
+  role: string;
+  round_index: number;
+  turn_index: number;
+  status: 'waiting' | 'answered' | 'skipped' | 'timed_out' | 'cancelled';
+  message_id?: string;
+  requested_at: string;
+  expires_at?: string;
+}
+
+export interface ConsensusConfig {
+  enabled?: boolean;
+  judge_profile_id?: string;
+  agreement_threshold?: number;
+  stall_rounds?: number;
+  stall_delta?: number;
+  min_rounds?: number;
+}
+
+export interface ConsensusCheck {
+  round_index: number;
+  agreement_score: number;
+  lexical_similarity: number;
+  judge_agreement?: number;
+  stances: Array<{ profile_id: string; stance: string }>;
+  decision: 'continue' | 'consensus' | 'stalled';
+  created_at: string;
+}
+
+export interface DebateVerdict {
+  outcome: 'consensus' | 'stalled' | 'rounds_exhausted' | 'manual';
+  agreement_score?: number;
+  summary: string;
+  agreements: Array<{ point: string; profile_ids: string[] }>;
+  disagreements: Array<{ point: string; positions: Array<{ profile_id: string; position: string }> }>;
+  strongest_arguments: Array<{ profile_id: string; message_id: string; argument: string; rationale: string }>;
+}
+
+export type SummaryScope = 'run' | 'conversation' | 'profile';
+
+export interface SummaryMemoryConfig {
+  enabled?: boolean;
+  summarizer_profile_id?: string;
+  keep_last_k?: number;
+  refresh_threshold?: number;
+  max_summary_words?: number;
+}
+
+export interface ConversationSummary {
+  scope_type: SummaryScope;
+  scope_id: string;
+  summary_text: string;
+  covered_until?: string;
+  covered_message_id?: string;
+  covered_count: number;
+  summarizer_profile_id?: string;
+  edited_by_user: boolean;
+  updated_at: string;
+}
+
+export interface ArgumentGraph {
+  run_id: string;
+  nodes: Array<{ id: string; claim: string; message_id: string; profile_id?: string; speaker: string; round_index?: number }>;
+  edges: Array<{ id: string; source: string; target: string; relation: 'supports' | 'attacks' }>;
+}
+
+export type ArgumentGraphFormat = 'json' | 'graphml' | 'mermaid';
+
+export interface SopVariable {
+  name: string;
+  description?: string;
+  /** Variables without a default must be filled in */
+  default?: string;
+}
+
+/** Reusable session recipe; text fields may contain {{variables}} */
+export interface SopTemplate {
+  description?: string;
+  mode: 'parallel' | 'debate';
+  title: string;
+  question: string;
+  profile_ids: string[];
+  /** Debate speaking order; defaults to profile_ids */
+  speaking_order?: string[];
+  rounds?: number;
+  language?: string;
+  tone?: string;
+  max_words?: number;
+  web_search?: boolean;
+  rag_project_id?: string;
+  /** Brainstorm stages, e.g. { stages: ['generate', 'critique', 'synthesize'] } */
+  pipeline?: { stages: string[]; synthesizer_profile_id?: string };
+  debate?: DebateSettings;
+  variables?: SopVariable[];
+}
+
+export interface Sop {
+  id: string;
+  name: string;
+  version: number;
+  project_id?: string;
+  template: SopTemplate;
+  created_at: string;
+  updated_at: string;
+}
+
+export type SearchSource = 'chat' | 'group_chat' | 'debate' | 'run_result' | 'coder_chat' | 'coder_ide';
+
+export interface SearchRequest {
+  query: string;
+  sources?: SearchSource[];
+  profile_id?: string;
+  project_id?: string;
+  /** Inclusive, YYYY-MM-DD */
+  date_from?: string;
+  date_to?: string;
+  limit?: number;
+  offset?: number;
+}
+
+export interface SearchHit {
+  source: SearchSource;
+  item_id: string;
+  parent_id?: string;
+  session_id?: string;
+  profile_id?: string;
+  project_id?: string;
+  mode?: string;
+  title?: string;
+  profile_name?: string;
+  created_at?: string;
+  snippet: string;
+  /** HTML-escaped, matches wrapped in <mark> */
+  snippet_html: string;
+  score: number;
+}
+
+export type SessionImportConflict = 'copy' | 'skip' | 'replace';
+
+export interface SessionImportSummary {
+  session_id: string;
+  /** Latest imported run */
+  run_id: string;
+  runs: number;
+  mode: string;
+  status: 'imported' | 'skipped' | 'replaced';
+  profiles: { source_id: string; name: string; profile_id: string; action: 'matched' | 'created' }[];
+  results: number;
+  messages: number;
+}
+
+export type ReportFormat = 'html' | 'pdf' | 'docx';
+
+export interface ReportFile {
+  file_name: string;
+  mime_type: string;
+  /** Base64 when `base64` is set (pdf/docx), otherwise the document text (html) */
+  content: string;
+  base64: boolean;
+}
+
+export function downloadReportFile(file: ReportFile) {
+  const data = file.base64
+    ? Uint8Array.from(atob(file.content), (c) => c.charCodeAt(0))
+    : file.content;
+  const blob = new Blob([data], { type: file.mime_type });
+  const url = URL.createObjectURL(blob);
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = file.file_name;
+  document.body.appendChild(a);
+  a.click();
+  document.body.removeChild(a);
+  URL.revokeObjectURL(url);
+}
+
+/** A fork on the active branch of a profile conversation */
+export interface ChatBranchPoint {
+  parent_message_id: string | null;
+  options: Array<{ id: string; role: 'user' | 'assistant'; preview: string; created_at: string }>;
+  active_index: number;
+}
+
+export interface GroupMemberInput {
+  profile_id: string;
+  /** Reply to user messages that mention nobody */
+  auto_respond: boolean;
+}
+
+export interface GroupConversation {
+  id: string;
+  title: string;
+  created_at: string;
+  updated_at: string;
+  members: Array<GroupMemberInput & { name: string; photo_url: string | null }>;
+}
+
+export interface GroupMessage {
+  id: string;
+  conversation_id: string;
+  /** null for the user */
+  profile_id: string | null;
+  author_name: string;
+  content: string;
+  mentions: string[];
+  created_at: string;
+}
+
+export interface GroupChatTurn {
+  messages: GroupMessage[];
+  failures: Array<{ profile_id: string; name: string; error: string }>;
+}
+
+export interface ProfileVersion {
+  id: string;
+  profile_id: string;
+  version: number;
+  name: string;
+  provider_account_id: string;
+  model_name: string;
+  persona_prompt: string;
+  params_json: Record<string, unknown>;
+  character_definition: Record<string, unknown> | null;
+  model_features: Record<string, unknown> | null;
+  note: string | null;
+  created_at: string;
+}
+
+export interface DiffLine {
+  kind: 'same' | 'added' | 'removed';
+  text: string;
+}
+
+export interface ProfileVersionDiff {
+  from: ProfileVersion;
+  to: ProfileVersion;
+  /** Only the fields that differ */
+  changes: Array<{ field: string; lines: DiffLine[] }>;
+}
+
+export interface AbAnswer {
+  text: string | null;
+  error: string | null;
+  latency_ms: number;
+  total_tokens: number | null;
+}
+
+export interface AbTestReport {
+  id: string;
+  profile_id: string;
+  version_a_id: string;
+  version_b_id: string;
+  version_a: number;
+  version_b: number;
+  rows: Array<{ question: string; a: AbAnswer; b: AbAnswer }>;
+  created_at: string;
+}
+
+export interface CardImportResult {
+  profile_id: string;
+  name: string;
+  provider_account_id: string;
+  model_name: string;
+  /** Exported from Panther (exact profile) rather than another tool */
+  panther_card: boolean;
+  warnings: string[];
+}
+
+export interface ProfileMemory {
+  id: string;
+  profile_id: string;
+  kind: 'fact' | 'preference';
+  content: string;
+  /** Chat message the memory was learned from; null for manual entries */
+  source_message_id: string | null;
+  conversation_id: string | null;
+  edited_by_user: boolean;
+  use_count: number;
+  last_used_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export type McpServerConfig = {
+  name: string;
+  enabled: boolean;
+  timeout_secs?: number | null;
+} & (
+  | { transport: 'stdio'; command: string; args: string[]; env: Record<string, string>; cwd?: string | null }
+  | { transport: 'http'; url: string; headers: Record<string, string> }
+);
+
+export interface McpTool {
+  name: string;
+  description?: string;
+  inputSchema: Record<string, unknown>;
+}
+
+export interface McpServerProbe {
+  server_info: Record<string, any>;
+  tools: McpTool[];
+  resources: Array<{ uri: string; name: string; description?: string; mimeType?: string }>;
+}
+
+export interface ClineCheckpoint {
+  id: string;
+  run_id: string;
+  step_index: number;
+  /** Set for the automatic checkpoints taken before a tool ran */
+  tool_execution_id: string | null;
+  file_count: number;
+  created_at: string;
+}
+
+export interface ClineFileDiff {
+  path: string;
+  status: 'added' | 'modified' | 'deleted';
+  binary: boolean;
+  additions: number;
+  deletions: number;
+  /** Unified line diff; null for binary files */
+  diff: string | null;
+  truncated?: boolean;
+}
+
+export interface ClineCheckpointDiff {
+  added: string[];
+  modified: string[];
+  deleted: string[];
+  files: ClineFileDiff[];
+  partial: boolean;
+}
+
 export interface CreateProviderRequest {
   provider_type: string;
   display_name: string;
@@ -193,6 +555,77 @@ export const api = {
     await httpFetch<void>('PUT', `/api/profiles/${id}`, { ...request, user_id: userId });
   },
 
+  async listProfileVersions(profileId: string): Promise<ProfileVersion[]> {
+    if (TAURI_AVAILABLE) return invoke('list_profile_versions', { profileId });
+    return httpFetch<ProfileVersion[]>('GET', `/api/profiles/${profileId}/versions`);
+  },
+
+  async diffProfileVersions(profileId: string, fromVersionId: string, toVersionId: string): Promise<ProfileVersionDiff> {
+    if (TAURI_AVAILABLE) return invoke('diff_profile_versions', { fromVersionId, toVersionId });
+    const q = `?from=${encodeURIComponent(fromVersionId)}&to=${encodeURIComponent(toVersionId)}`;
+    return httpFetch<ProfileVersionDiff>('GET', `/api/profiles/${profileId}/versions/diff${q}`);
+  },
+
+  async rollbackProfile(profileId: string, versionId: string): Promise<ProfileVersion> {
+    if (TAURI_AVAILABLE) return invoke('rollback_profile', { profileId, versionId });
+    return httpFetch<ProfileVersion>('POST', `/api/profiles/${profileId}/rollback`, { version_id: versionId });
+  },
+
+  async runProfileAbTest(profileId: string, versionAId: string, versionBId: string, questions: string[]): Promise<AbTestReport> {
+    const request = { profile_id: profileId, version_a_id: versionAId, version_b_id: versionBId, questions };
+    if (TAURI_AVAILABLE) return invoke('run_profile_ab_test', { request });
+    return httpFetch<AbTestReport>('POST', `/api/profiles/${profileId}/ab-tests`, request);
+  },
+
+  async listProfileAbTests(profileId: string): Promise<AbTestReport[]> {
+    if (TAURI_AVAILABLE) return invoke('list_profile_ab_tests', { profileId });
+    return httpFetch<AbTestReport[]>('GET', `/api/profiles/${profileId}/ab-tests`);
+  },
+
+  async exportProfileCard(profileId: string, format: 'json' | 'png'): Promise<ReportFile> {
+    if (TAURI_AVAILABLE) return invoke('export_profile_card', { profileId, format });
+    return httpFetch<ReportFile>('GET', `/api/profiles/${profileId}/card?format=${format}`);
+  },
+
+  /** `content` is the card JSON, or the base64 PNG when `base64` is set */
+  async importProfileCard(request: {
+    content: string;
+    base64: boolean;
+    provider_account_id?: string;
+    model_name?: string;
+  }): Promise<CardImportResult> {
+    const userId = getCurrentUserId();
+    if (TAURI_AVAILABLE) return invoke('import_profile_card', { request, userId });
+    return httpFetch<CardImportResult>('POST', '/api/profiles/import-card', { ...request, user_id: userId });
+  },
+
+  async listProfileMemories(profileId: string): Promise<ProfileMemory[]> {
+    if (TAURI_AVAILABLE) return invoke('list_profile_memories', { profileId });
+    return httpFetch<ProfileMemory[]>('GET', `/api/profiles/${profileId}/memories`);
+  },
+
+  async addProfileMemory(profileId: string, kind: ProfileMemory['kind'], content: string): Promise<ProfileMemory | null> {
+    if (TAURI_AVAILABLE) return invoke('add_profile_memory', { profileId, kind, content });
+    return httpFetch<ProfileMemory | null>('POST', `/api/profiles/${profileId}/memories`, { kind, content });
+  },
+
+  async updateProfileMemory(memoryId: string, content: string, kind?: ProfileMemory['kind']): Promise<ProfileMemory> {
+    if (TAURI_AVAILABLE) return invoke('update_profile_memory', { memoryId, content, kind });
+    return httpFetch<ProfileMemory>('PUT', `/api/memories/${memoryId}`, { content, kind });
+  },
+
+  async deleteProfileMemory(memoryId: string): Promise<void> {
+    if (TAURI_AVAILABLE) return invoke('delete_profile_memory', { memoryId });
+    await httpFetch<void>('DELETE', `/api/memories/${memoryId}`);
+  },
+
+  /** Returns the number of memories removed */
+  async clearProfileMemories(profileId: string): Promise<number> {
+    if (TAURI_AVAILABLE) return invoke('clear_profile_memories', { profileId });
+    const result = await httpFetch<{ deleted: number }>('DELETE', `/api/profiles/${profileId}/memories`);
+    return result.deleted;
+  },
+
   async listProfiles(): Promise<any[]> {
     const userId = getCurrentUserId();
     if (TAURI_AVAILABLE) return invoke('list_profiles', { userId });
@@ -209,6 +642,29 @@ export const api = {
     return invoke('generate_character_from_url', { request: { urls, person_name: personName, provider_account_id: providerAccountId, model_name: modelName, cancellation_token: cancellationToken } });
   },
 
+  async generateCharacterFromSources(request: {
+    sources: CharacterSource[];
+    person_name?: string;
+    provider_account_id: string;
+    model_name: string;
+    cancellation_token?: string;
+  }): Promise<{ character: CharacterDefinition; extracted_text: string; warnings: string[] }> {
+    if (TAURI_AVAILABLE) return invoke('generate_character_from_sources', { request });
+    return httpFetch('POST', '/api/profiles/generate-character', request);
+  },
+
+  /** Re-read the sources behind a generated character; regenerates only when one changed unless `force` */
+  async refreshProfileCharacter(profileId: string, force = false): Promise<{
+    regenerated: boolean;
+    changed_sources: string[];
+    failed_sources: string[];
+    character: CharacterDefinition;
+    warnings: string[];
+  }> {
+    if (TAURI_AVAILABLE) return invoke('refresh_profile_character', { profileId, force });
+    return httpFetch('POST', `/api/profiles/${profileId}/character/refresh`, { force });
+  },
+
   async getLatestProfile(): Promise<any | null> {
     if (TAURI_AVAILABLE) return invoke('get_latest_profile');
     // In browser mode, get latest from the list
@@ -268,12 +724,38 @@ export const api = {
     model_preference?: 'default' | 'local' | 'cloud';
     /** Documents to include as context (name + content) */
     attached_documents?: Array<{ name: string; content: string }>;
+    /** Send stored summary + recent messages instead of the full history */
+    summary_memory?: SummaryMemoryConfig;
+    /** Send as an alternative to this user message (starts a new branch) */
+    edit_message_id?: string;
+    /** Answer the question before this assistant message again (user_message is ignored) */
+    regenerate_message_id?: string;
   }): Promise<string> {
     if (TAURI_AVAILABLE) return invoke('chat_with_profile', { request });
     const r = await httpFetch<{ text: string }>('POST', `/api/chat/${request.profile_id}`, request);
     return r.text;
   },
 
+  async getConversationSummary(scope: SummaryScope, scopeId: string): Promise<ConversationSummary | null> {
+    if (TAURI_AVAILABLE) return invoke('get_conversation_summary', { scope, scopeId });
+    return httpFetch<ConversationSummary | null>('GET', `/api/summaries/${scope}/${scopeId}`);
+  },
+
+  async updateConversationSummary(scope: SummaryScope, scopeId: string, summaryText: string): Promise<ConversationSummary | null> {
+    if (TAURI_AVAILABLE) return invoke('update_conversation_summary', { scope, scopeId, summaryText });
+    return httpFetch<ConversationSummary | null>('PUT', `/api/summaries/${scope}/${scopeId}`, { summary_text: summaryText });
+  },
+
+  async refreshConversationSummary(scope: SummaryScope, scopeId: string, config?: SummaryMemoryConfig): Promise<ConversationSummary | null> {
+    if (TAURI_AVAILABLE) return invoke('refresh_conversation_summary', { scope, scopeId, config });
+    return httpFetch<ConversationSummary | null>('POST', `/api/summaries/${scope}/${scopeId}/refresh`, config ?? null);
+  },
+
+  async clearConversationSummary(scope: SummaryScope, scopeId: string): Promise<void> {
+    if (TAURI_AVAILABLE) return invoke('clear_conversation_summary', { scope, scopeId });
+    await httpFetch<void>('DELETE', `/api/summaries/${scope}/${scopeId}`);
+  },
+
   async improveResponseWithCloud(request: {
     profile_id: string;
     assistant_message: string;
@@ -284,10 +766,11 @@ export const api = {
     return r.text;
   },
 
-  async insertChatMessage(profileId: string, role: string, content: string): Promise<string> {
+  /** Appends to the end of the active branch of the conversation (or the default chat) */
+  async insertChatMessage(profileId: string, role: string, content: string, conversationId?: string): Promise<string> {
     const userId = getCurrentUserId();
-    if (TAURI_AVAILABLE) return invoke('insert_chat_message', { profile_id: profileId, role, content, userId });
-    const r = await httpFetch<{ id: string }>('POST', `/api/chat/${profileId}/messages`, { role, content, user_id: userId });
+    if (TAURI_AVAILABLE) return invoke('insert_chat_message', { profileId, conversationId: conversationId ?? null, role, content, userId });
+    const r = await httpFetch<{ id: string }>('POST', `/api/chat/${profileId}/messages`, { role, content, user_id: userId, conversation_id: conversationId ?? null });
     return r.id;
   },
 
@@ -296,6 +779,50 @@ export const api = {
     await httpFetch<void>('PUT', `/api/chat/messages/${messageId}`, { content });
   },
 
+  async listChatBranches(profileId: string, conversationId?: string): Promise<ChatBranchPoint[]> {
+    if (TAURI_AVAILABLE) return invoke('list_chat_branches', { profileId, conversationId: conversationId ?? null });
+    const q = conversationId ? `?conversation_id=${encodeURIComponent(conversationId)}` : '';
+    return httpFetch<ChatBranchPoint[]>('GET', `/api/chat/${profileId}/branches${q}`);
+  },
+
+  /** Without a conversation the default chat of `profileId` switches */
+  async switchChatBranch(profileId: string, conversationId: string | null, messageId: string): Promise<void> {
+    if (TAURI_AVAILABLE) return invoke('switch_chat_branch', { conversationId, messageId });
+    const path = conversationId ? `/api/chat/conversations/${conversationId}` : `/api/chat/${profileId}`;
+    await httpFetch<void>('PUT', `${path}/active-message`, { message_id: messageId });
+  },
+
+  // Group chat
+  async listGroupConversations(): Promise<GroupConversation[]> {
+    if (TAURI_AVAILABLE) return invoke('list_group_conversations');
+    return httpFetch<GroupConversation[]>('GET', '/api/group-chats');
+  },
+
+  async createGroupConversation(members: GroupMemberInput[], title?: string): Promise<GroupConversation> {
+    if (TAURI_AVAILABLE) return invoke('create_group_conversation', { title: title ?? null, members });
+    return httpFetch<GroupConversation>('POST', '/api/group-chats', { title, members });
+  },
+
+  async updateGroupConversation(conversationId: string, update: { title?: string; members?: GroupMemberInput[] }): Promise<GroupConversation> {
+    if (TAURI_AVAILABLE) return invoke('update_group_conversation', { conversationId, title: update.title ?? null, members: update.members ?? null });
+    return httpFetch<GroupConversation>('PUT', `/api/group-chats/${conversationId}`, update);
+  },
+
+  async deleteGroupConversation(conversationId: string): Promise<void> {
+    if (TAURI_AVAILABLE) return invoke('delete_group_conversation', { conversationId });
+    await httpFetch<void>('DELETE', `/api/group-chats/${conversationId}`);
+  },
+
+  async loadGroupMessages(conversationId: string): Promise<GroupMessage[]> {
+    if (TAURI_AVAILABLE) return invoke('load_group_messages', { conversationId });
+    return httpFetch<GroupMessage[]>('GET', `/api/group-chats/${conversationId}/messages`);
+  },
+
+  async sendGroupMessage(request: { conversation_id: string; content: string; language?: string; max_replies?: number }): Promise<GroupChatTurn> {
+    if (TAURI_AVAILABLE) return invoke('send_group_message', { request });
+    return httpFetch<GroupChatTurn>('POST', `/api/group-chats/${request.conversation_id}/messages`, request);
+  },
+
   async searchWeb(query: string, maxResults?: number): Promise<any[]> {
     return invoke('search_web', { request: { query, max_results: maxResults } });
   },
@@ -635,6 +1162,48 @@ export const api = {
     await httpFetch<void>('DELETE', `/api/sessions/${sessionId}`);
   },
 
+  // Session templates (SOPs)
+  async listSops(projectId?: string): Promise<Sop[]> {
+    if (TAURI_AVAILABLE) return invoke('list_sops', { projectId });
+    const q = projectId ? `?project_id=${encodeURIComponent(projectId)}` : '';
+    return httpFetch<Sop[]>('GET', `/api/sops${q}`);
+  },
+
+  async listSopVersions(name: string): Promise<Sop[]> {
+    return invoke('list_sop_versions', { name });
+  },
+
+  async getSop(sopId: string): Promise<Sop> {
+    if (TAURI_AVAILABLE) return invoke('get_sop', { sopId });
+    return httpFetch<Sop>('GET', `/api/sops/${sopId}`);
+  },
+
+  async saveSop(name: string, template: SopTemplate, projectId?: string): Promise<Sop> {
+    const request = { name, template, project_id: projectId };
+    if (TAURI_AVAILABLE) return invoke('save_sop', { request });
+    return httpFetch<Sop>('POST', '/api/sops', request);
+  },
+
+  async deleteSop(name: string): Promise<void> {
+    if (TAURI_AVAILABLE) return invoke('delete_sop', { name });
+    await httpFetch<void>('DELETE', `/api/sops/by-name/${encodeURIComponent(name)}`);
+  },
+
+  /** Create a session from a template; returns the new run id */
+  async instantiateSop(sopId: string, variables: Record<string, string>, projectId?: string): Promise<{ run_id: string; mode: 'parallel' | 'debate' }> {
+    const request = { sop_id: sopId, variables, project_id: projectId };
+    if (TAURI_AVAILABLE) return invoke('instantiate_sop', { request });
+    return httpFetch('POST', `/api/sops/${sopId}/instantiate`, request);
+  },
+
+  async exportSop(sopId: string): Promise<string> {
+    return invoke('export_sop', { sopId });
+  },
+
+  async importSop(content: string, name?: string, projectId?: string): Promise<Sop> {
+    return invoke('import_sop', { content, name, projectId });
+  },
+
   // Run execution
   async startRun(runId: string): Promise<void> {
     return invoke('start_run', { runId });
@@ -648,10 +1217,34 @@ export const api = {
     return invoke('get_run_results', { runId });
   },
 
+  async getRunSyntheses(runId: string): Promise<any[]> {
+    return invoke('get_run_syntheses', { runId });
+  },
+
   async cancelRun(runId: string): Promise<void> {
     return invoke('cancel_run', { runId });
   },
 
+  async pauseRun(runId: string): Promise<void> {
+    if (TAURI_AVAILABLE) return invoke('pause_run', { runId });
+    await httpFetch<void>('POST', `/api/runs/${runId}/pause`);
+  },
+
+  async resumeRun(runId: string): Promise<any> {
+    if (TAURI_AVAILABLE) return invoke('resume_run', { runId });
+    return httpFetch<any>('POST', `/api/runs/${runId}/resume`);
+  },
+
+  async listInterruptedRuns(): Promise<any[]> {
+    if (TAURI_AVAILABLE) return invoke('list_interrupted_runs');
+    return httpFetch<any[]>('GET', '/api/runs/interrupted');
+  },
+
+  async getRunState(runId: string): Promise<any> {
+    if (TAURI_AVAILABLE) return invoke('get_run_state', { runId });
+    return httpFetch<any>('GET', `/api/runs/${runId}/state`);
+  },
+
   async cancelRunResult(resultId: string): Promise<void> {
     return invoke('cancel_run_result', { resultId });
   },
@@ -669,8 +1262,8 @@ export const api = {
   },
 
   // Debate
-  async startDebate(runId: string, rounds: number, speakingOrder: string[], maxWords?: number, language?: string, tone?: string, webSearchResults?: any[]): Promise<void> {
-    return invoke('start_debate', { runId, rounds, speakingOrder, maxWords, language, tone, webSearchResults });
+  async startDebate(runId: string, rounds: number, speakingOrder: string[], maxWords?: number, language?: string, tone?: string, webSearchResults?: any[], settings?: DebateSettings): Promise<void> {
+    return invoke('start_debate', { runId, rounds, speakingOrder, maxWords, language, tone, webSearchResults, settings });
   },
 
   async getDebateMessages(runId: string): Promise<any[]> {
@@ -699,8 +1292,20 @@ export const api = {
     return invoke('delete_debate_message', { messageId });
   },
 
-  async addUserMessage(runId: string, text: string, insertAfterMessageId?: string): Promise<string> {
-    return invoke('add_user_message', { runId, text, insertAfterMessageId });
+  async addUserMessage(runId: string, text: string, insertAfterMessageId?: string, authorLabel?: string): Promise<string> {
+    return invoke('add_user_message', { runId, text, insertAfterMessageId, authorLabel });
+  },
+
+  async getPendingHumanTurns(runId: string): Promise<HumanTurn[]> {
+    return invoke('get_pending_human_turns', { runId });
+  },
+
+  async submitHumanTurn(turnId: string, text: string): Promise<string> {
+    return invoke('submit_human_turn', { turnId, text });
+  },
+
+  async skipHumanTurn(turnId: string): Promise<void> {
+    return invoke('skip_human_turn', { turnId });
   },
 
   async continueDebate(runId: string, rounds: number): Promise<void> {
@@ -708,6 +1313,26 @@ export const api = {
   },
 
   // Export
+  async getDebateConsensus(runId: string): Promise<{ checks: ConsensusCheck[]; verdict: DebateVerdict | null }> {
+    return invoke('get_debate_consensus', { runId });
+  },
+
+  async generateDebateVerdict(runId: string, judgeProfileId?: string): Promise<DebateVerdict> {
+    return invoke('generate_debate_verdict', { runId, judgeProfileId });
+  },
+
+  async extractArgumentGraph(runId: string, extractorProfileId?: string): Promise<ArgumentGraph> {
+    return invoke('extract_argument_graph', { runId, extractorProfileId });
+  },
+
+  async getArgumentGraph(runId: string): Promise<ArgumentGraph> {
+    return invoke('get_argument_graph', { runId });
+  },
+
+  async exportArgumentGraph(runId: string, format: ArgumentGraphFormat): Promise<string> {
+    return invoke('export_argument_graph', { runId, format });
+  },
+
   async exportSessionMarkdown(sessionId: string): Promise<string> {
     return invoke('export_session_markdown', { sessionId });
   },
@@ -716,10 +1341,56 @@ export const api = {
     return invoke('export_session_json', { sessionId });
   },
 
+  async searchHistory(request: SearchRequest): Promise<SearchHit[]> {
+    if (TAURI_AVAILABLE) return invoke('search_history', { request });
+    const params = new URLSearchParams({ q: request.query });
+    if (request.sources?.length) params.set('sources', request.sources.join(','));
+    for (const key of ['profile_id', 'project_id', 'date_from', 'date_to', 'limit', 'offset'] as const) {
+      const value = request[key];
+      if (value !== undefined && value !== '') params.set(key, String(value));
+    }
+    return httpFetch<SearchHit[]>('GET', `/api/search?${params}`);
+  },
+
+  async importSessionJson(
+    content: string,
+    options: { project_id: string; on_conflict?: SessionImportConflict; provider_account_id?: string },
+  ): Promise<SessionImportSummary> {
+    const scoped = { ...options, user_id: getCurrentUserId() };
+    if (TAURI_AVAILABLE) return invoke('import_session_json', { content, options: scoped });
+    return httpFetch<SessionImportSummary>('POST', '/api/sessions/import', { content, options: scoped });
+  },
+
+  async exportSessionReport(sessionId: string, format: ReportFormat): Promise<ReportFile> {
+    if (TAURI_AVAILABLE) return invoke('export_session_report', { sessionId, format });
+    return httpFetch<ReportFile>('GET', `/api/sessions/${sessionId}/report?format=${format}`);
+  },
+
+  async exportConversationReport(profileId: string, conversationId: string | null, format: ReportFormat): Promise<ReportFile> {
+    if (TAURI_AVAILABLE) return invoke('export_conversation_report', { profileId, conversationId, format });
+    const q = conversationId ? `&conversation_id=${encodeURIComponent(conversationId)}` : '';
+    return httpFetch<ReportFile>('GET', `/api/chat/${profileId}/report?format=${format}${q}`);
+  },
+
   async generateComparisonTable(runId: string): Promise<string> {
     return invoke('generate_comparison_table', { runId });
   },
 
+  async runTournament(runId: string, config: {
+    judge_profile_ids: string[];
+    mode?: 'pairwise' | 'rubric';
+    rating?: 'elo' | 'bradley_terry';
+    dimensions?: string[];
+    k_factor?: number;
+    concurrency?: number;
+  }): Promise<any[]> {
+    return invoke('run_tournament', { runId, config });
+  },
+
+  async getRunEvaluations(runId: string): Promise<any[]> {
+    return invoke('get_run_evaluations', { runId });
+  },
+
   // Authentication
   async signup(username: string, email: string, password: string): Promise<any> {
     if (TAURI_AVAILABLE) {
@@ -1291,6 +1962,10 @@ export const api = {
     return invoke('read_global_prompt_file');
   },
 
+  async listPromptMiddleware(): Promise<string[]> {
+    return invoke('list_prompt_middleware');
+  },
+
   async updateCacheSettings(cacheSettings: any): Promise<any> {
     return invoke('update_cache_settings', { cacheSettings });
   },
@@ -1307,10 +1982,40 @@ export const api = {
     workspace_path: string;
     target_paths?: string[];
     conversation_context?: Array<{ role: string; content: string }>;
+    /** Chosen by the caller to match `panther://cline_progress` events */
+    run_id?: string;
+    loop_config?: {
+      max_iterations?: number;
+      max_total_tokens?: number | null;
+      /** Tool types run without asking, e.g. workspace_read */
+      auto_approve?: string[];
+      approval_timeout_secs?: number;
+    };
   }): Promise<any> {
     return invoke('cline_agent_task', { request });
   },
 
+  async clineCancelTask(runId: string): Promise<void> {
+    return invoke('cline_cancel_task', { runId });
+  },
+
+  async listMcpServers(): Promise<McpServerConfig[]> {
+    return invoke('list_mcp_servers');
+  },
+
+  async saveMcpServers(servers: McpServerConfig[]): Promise<McpServerConfig[]> {
+    return invoke('save_mcp_servers', { servers });
+  },
+
+  /** Connects with an unsaved config and lists its tools and resources */
+  async testMcpServer(server: McpServerConfig): Promise<McpServerProbe> {
+    return invoke('test_mcp_server', { server });
+  },
+
+  async listMcpTools(): Promise<Array<McpTool & { server: string }>> {
+    return invoke('list_mcp_tools');
+  },
+
   async clineApproveTool(request: {
     tool_id: string;
     approved: boolean;
@@ -1331,16 +2036,24 @@ export const api = {
     return invoke('cline_restore_checkpoint', { request });
   },
 
-  async clineCompareCheckpoint(checkpoint_id: string): Promise<any> {
-    return invoke('cline_compare_checkpoint', { checkpoint_id });
+  async clineCompareCheckpoint(checkpoint_id: string): Promise<ClineCheckpointDiff> {
+    return invoke('cline_compare_checkpoint', { checkpointId: checkpoint_id });
+  },
+
+  async clineListCheckpoints(runId: string): Promise<ClineCheckpoint[]> {
+    return invoke('cline_list_checkpoints', { runId });
   },
 
   async clineGetErrors(workspace_path: string): Promise<any[]> {
-    return invoke('cline_get_errors', { workspace_path });
+    return invoke('cline_get_errors', { workspacePath: workspace_path });
+  },
+
+  async clineAnalyzeAST(path: string, workspace_path?: string): Promise<any> {
+    return invoke('cline_analyze_ast', { path, workspacePath: workspace_path });
   },
 
-  async clineAnalyzeAST(path: string): Promise<any> {
-    return invoke('cline_analyze_ast', { path });
+  async clineFindSymbol(name: string, workspace_path: string, path?: string): Promise<any> {
+    return invoke('cline_find_symbol', { name, path, workspacePath: workspace_path });
   },
 
   async ingestClineTurn(request: {
@@ -0,0 +1,117 @@
+import { useState } from 'react';
+import { api } from '../api';
+import type { CharacterDefinition, CharacterProvenance, SourceRecord } from '../types';
+
+interface CharacterProvenancePanelProps {
+  provenance: CharacterProvenance;
+  /** Saved profile; enables refreshing from the sources */
+  profileId?: string;
+  onRefreshed: (character: CharacterDefinition) => void;
+}
+
+const fieldLabels: Record<string, string> = {
+  role: 'Role',
+  personality: 'Personality',
+  expertise: 'Expertise',
+  communication_style: 'Communication style',
+  background: 'Background',
+  goals: 'Goals',
+  constraints: 'Constraints',
+};
+
+function sourceLabel(source: SourceRecord): string {
+  switch (source.kind) {
+    case 'url':
+      return source.url;
+    case 'document':
+      return source.path.split(/[\\/]/).pop() || source.path;
+    case 'transcript':
+      return source.label || 'Pasted transcript';
+  }
+}
+
+export function CharacterProvenancePanel({ provenance, profileId, onRefreshed }: CharacterProvenancePanelProps) {
+  const [expanded, setExpanded] = useState(false);
+  const [refreshing, setRefreshing] = useState(false);
+
+  const refresh = async (force: boolean) => {
+    if (!profileId) return;
+    setRefreshing(true);
+    try {
+      const result = await api.refreshProfileCharacter(profileId, force);
+      const warnings = result.warnings.length > 0 ? `\n\n${result.warnings.join('\n')}` : '';
+      if (result.regenerated) {
+        onRefreshed(result.character);
+        alert(
+          (result.changed_sources.length > 0
+            ? `Sources changed (${result.changed_sources.join(', ')}); the character was regenerated.`
+            : 'The character was regenerated.') + warnings
+        );
+      } else if (result.failed_sources.length > 0) {
+        alert(
+          `Some sources could not be read (${result.failed_sources.join(', ')}), so the character was not regenerated. ` +
+            `Use Regenerate to rebuild it from the remaining sources.${warnings}`
+        );
+      } else {
+        alert(`No source changed since ${new Date(provenance.generated_at).toLocaleString()}.${warnings}`);
+      }
+    } catch (error) {
+      alert(`Failed to refresh character: ${error instanceof Error ? error.message : String(error)}`);
+    } finally {
+      setRefreshing(false);
+    }
+  };
+
+  const fields = Object.keys(fieldLabels)
+    .map((field) => ({ field, entries: provenance.fields.filter((f) => f.field === field) }))
+    .filter((group) => group.entries.length > 0);
+
+  return (
+    <div style={{ marginTop: '8px', padding: '8px 10px', border: '1px solid var(--border-color)', borderRadius: '4px', fontSize: '12px' }}>
+      <div style={{ display: 'flex', alignItems: 'center', gap: '8px', flexWrap: 'wrap' }}>
+        <button type="button" className="btn btn-secondary" style={{ fontSize: '11px', padding: '3px 8px' }} onClick={() => setExpanded(!expanded)}>
+          {expanded ? '▾' : '▸'} Sources ({provenance.sources.length})
+        </button>
+        <span style={{ color: 'var(--text-secondary)' }}>
+          Generated {new Date(provenance.generated_at).toLocaleString()} with {provenance.model_name}
+        </span>
+        {profileId && (
+          <>
+            <button type="button" className="btn btn-secondary" style={{ fontSize: '11px', padding: '3px 8px' }} disabled={refreshing} onClick={() => refresh(false)}>
+              {refreshing ? '⏳ Checking...' : '↻ Refresh if sources changed'}
+            </button>
+            <button type="button" className="btn btn-secondary" style={{ fontSize: '11px', padding: '3px 8px' }} disabled={refreshing} onClick={() => refresh(true)}>
+              Regenerate
+            </button>
+          </>
+        )}
+      </div>
+      {expanded && (
+        <div style={{ marginTop: '8px' }}>
+          <ul style={{ margin: '0 0 8px 18px', padding: 0 }}>
+            {provenance.sources.map((source) => (
+              <li key={source.id}>
+                <strong>{source.id}</strong> {sourceLabel(source)}{' '}
+                <span style={{ color: 'var(--text-secondary)' }}>({source.chars.toLocaleString()} chars)</span>
+              </li>
+            ))}
+          </ul>
+          {fields.map(({ field, entries }) => (
+            <div key={field} style={{ marginBottom: '4px' }}>
+              <span style={{ color: 'var(--text-secondary)' }}>{fieldLabels[field]}:</span>{' '}
+              {entries.map((entry, i) => (
+                <span key={i} title={entry.value}>
+                  {i > 0 && '; '}
+                  {entry.value.length > 60 ? `${entry.value.slice(0, 60)}…` : entry.value}{' '}
+                  <span style={{ color: entry.sources.length > 0 ? 'var(--text-secondary)' : '#dc3545' }}>
+                    [{entry.sources.length > 0 ? entry.sources.join(', ') : 'uncited'}]
+                  </span>
+                </span>
+              ))}
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,130 @@
+import { useState, useEffect } from 'react';
+import { api, type ConsensusCheck, type DebateVerdict } from '../api';
+
+interface DebateVerdictPanelProps {
+  runId: string;
+  /** profile id -> display name */
+  profileNames: Record<string, string>;
+  /** Re-load when this changes (e.g. run status) */
+  refreshKey?: string;
+}
+
+const OUTCOME_LABELS: Record<DebateVerdict['outcome'], string> = {
+  consensus: '🤝 Consensus reached',
+  stalled: '⏸️ Positions stalled',
+  rounds_exhausted: '🏁 All rounds completed',
+  manual: '⚖️ Verdict',
+};
+
+export function DebateVerdictPanel({ runId, profileNames, refreshKey }: DebateVerdictPanelProps) {
+  const [checks, setChecks] = useState<ConsensusCheck[]>([]);
+  const [verdict, setVerdict] = useState<DebateVerdict | null>(null);
+  const [generating, setGenerating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    api.getDebateConsensus(runId)
+      .then((data) => {
+        setChecks(data.checks);
+        setVerdict(data.verdict);
+      })
+      .catch((e) => console.warn('Failed to load consensus:', e));
+  }, [runId, refreshKey]);
+
+  const name = (id: string) => profileNames[id] || id;
+
+  const generate = async () => {
+    setGenerating(true);
+    setError(null);
+    try {
+      setVerdict(await api.generateDebateVerdict(runId));
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setGenerating(false);
+    }
+  };
+
+  return (
+    <div className="card" style={{ marginBottom: '20px', padding: '15px 20px' }}>
+      <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '10px', flexWrap: 'wrap' }}>
+        <h3 style={{ margin: 0 }}>{verdict ? OUTCOME_LABELS[verdict.outcome] ?? 'Verdict' : 'Verdict'}</h3>
+        <button type="button" className="btn btn-secondary" disabled={generating} onClick={generate}>
+          {generating ? 'Judging...' : verdict ? '🔄 Regenerate verdict' : '⚖️ Generate verdict'}
+        </button>
+      </div>
+
+      {checks.length > 0 && (
+        <div style={{ display: 'flex', gap: '8px', flexWrap: 'wrap', marginTop: '10px', fontSize: '12px', color: 'var(--text-secondary)' }}>
+          {checks.map((c) => (
+            <span
+              key={`${c.round_index}-${c.created_at}`}
+              title={c.stances.map((s) => `${name(s.profile_id)}: ${s.stance}`).join('\n')}
+              style={{ padding: '2px 8px', borderRadius: '10px', border: '1px solid var(--border-color)' }}
+            >
+              Round {c.round_index + 1}: {Math.round(c.agreement_score * 100)}% agreement
+            </span>
+          ))}
+        </div>
+      )}
+
+      {error && <div style={{ color: '#dc3545', marginTop: '8px' }}>{error}</div>}
+
+      {verdict && (
+        <div style={{ marginTop: '12px', fontSize: '14px' }}>
+          {verdict.summary && <p style={{ marginTop: 0 }}>{verdict.summary}</p>}
+
+          {verdict.agreements.length > 0 && (
+            <>
+              <h4 style={{ marginBottom: '6px' }}>Points of agreement</h4>
+              <ul style={{ marginTop: 0 }}>
+                {verdict.agreements.map((a, i) => (
+                  <li key={i}>
+                    {a.point}
+                    {a.profile_ids.length > 0 && (
+                      <span style={{ color: 'var(--text-secondary)' }}> ({a.profile_ids.map(name).join(', ')})</span>
+                    )}
+                  </li>
+                ))}
+              </ul>
+            </>
+          )}
+
+          {verdict.disagreements.length > 0 && (
+            <>
+              <h4 style={{ marginBottom: '6px' }}>Unresolved disagreements</h4>
+              <ul style={{ marginTop: 0 }}>
+                {verdict.disagreements.map((d, i) => (
+                  <li key={i}>
+                    {d.point}
+                    <ul>
+                      {d.positions.map((p, j) => (
+                        <li key={j}><strong>{name(p.profile_id)}:</strong> {p.position}</li>
+                      ))}
+                    </ul>
+                  </li>
+                ))}
+              </ul>
+            </>
+          )}
+
+          {verdict.strongest_arguments.length > 0 && (
+            <>
+              <h4 style={{ marginBottom: '6px' }}>Strongest arguments</h4>
+              <ul style={{ marginTop: 0 }}>
+                {verdict.strongest_arguments.map((a, i) => (
+                  <li key={i}>
+                    <strong>{name(a.profile_id)}:</strong> {a.argument}
+                    {a.rationale && <span style={{ color: 'var(--text-secondary)' }}> — {a.rationale}</span>}
+                    {' '}
+                    <a href={`#msg-${a.message_id}`} style={{ fontSize: '12px' }}>↗ message</a>
+                  </li>
+                ))}
+              </ul>
+            </>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
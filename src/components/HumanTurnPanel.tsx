@@ -0,0 +1,119 @@
+import { useState, useEffect } from 'react';
+import { api, type HumanTurn } from '../api';
+
+interface HumanTurnPanelProps {
+  runId: string;
+  /** Poll only while the debate can still ask for turns */
+  active: boolean;
+}
+
+function secondsLeft(turn: HumanTurn): number | null {
+  if (!turn.expires_at) return null;
+  return Math.max(0, Math.round((new Date(turn.expires_at).getTime() - Date.now()) / 1000));
+}
+
+export function HumanTurnPanel({ runId, active }: HumanTurnPanelProps) {
+  const [turns, setTurns] = useState<HumanTurn[]>([]);
+  const [drafts, setDrafts] = useState<Record<string, string>>({});
+  const [busyId, setBusyId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [, setTick] = useState(0);
+
+  useEffect(() => {
+    if (!active) {
+      setTurns([]);
+      return;
+    }
+    let cancelled = false;
+    const poll = async () => {
+      try {
+        const pending = await api.getPendingHumanTurns(runId);
+        if (!cancelled) setTurns(pending);
+      } catch (e) {
+        console.warn('Failed to load human turns:', e);
+      }
+      if (!cancelled) setTick((t) => t + 1);
+    };
+    poll();
+    const intervalId = setInterval(poll, 1000);
+    return () => {
+      cancelled = true;
+      clearInterval(intervalId);
+    };
+  }, [runId, active]);
+
+  const resolve = async (turn: HumanTurn, action: () => Promise<unknown>) => {
+    setBusyId(turn.id);
+    setError(null);
+    try {
+      await action();
+      setTurns((prev) => prev.filter((t) => t.id !== turn.id));
+      setDrafts((prev) => {
+        const next = { ...prev };
+        delete next[turn.id];
+        return next;
+      });
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  if (turns.length === 0) return null;
+
+  return (
+    <div className="card" style={{ marginBottom: '20px', padding: '15px 20px', border: '2px solid #388e3c' }}>
+      {turns.map((turn) => {
+        const left = secondsLeft(turn);
+        const draft = drafts[turn.id] ?? '';
+        return (
+          <div key={turn.id} style={{ marginBottom: '12px' }}>
+            <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '10px', flexWrap: 'wrap' }}>
+              <strong>🙋 Your turn as {turn.role}</strong>
+              <span style={{ fontSize: '12px', color: 'var(--text-secondary)' }}>
+                Round {turn.round_index + 1}
+                {left !== null && ` · skipped in ${Math.floor(left / 60)}:${String(left % 60).padStart(2, '0')}`}
+              </span>
+            </div>
+            <textarea
+              value={draft}
+              onChange={(e) => setDrafts((prev) => ({ ...prev, [turn.id]: e.target.value }))}
+              placeholder={`Speak as ${turn.role}...`}
+              rows={4}
+              style={{
+                width: '100%',
+                marginTop: '8px',
+                padding: '10px',
+                borderRadius: '4px',
+                border: '1px solid var(--border-color)',
+                fontSize: '14px',
+                resize: 'vertical',
+                boxSizing: 'border-box',
+              }}
+            />
+            <div style={{ display: 'flex', gap: '8px', justifyContent: 'flex-end', marginTop: '8px' }}>
+              <button
+                type="button"
+                className="btn btn-secondary"
+                disabled={busyId === turn.id}
+                onClick={() => resolve(turn, () => api.skipHumanTurn(turn.id))}
+              >
+                Skip turn
+              </button>
+              <button
+                type="button"
+                className="btn btn-primary"
+                disabled={busyId === turn.id || !draft.trim()}
+                onClick={() => resolve(turn, () => api.submitHumanTurn(turn.id, draft))}
+              >
+                {busyId === turn.id ? 'Sending...' : 'Submit'}
+              </button>
+            </div>
+          </div>
+        );
+      })}
+      {error && <div style={{ color: '#dc3545' }}>{error}</div>}
+    </div>
+  );
+}
@@ -222,6 +222,7 @@ export function MenuBar() {
           <Menu label="File">
             <MenuItem label="New Session" onClick={handleNewSession} />
             <MenuItem label="Open Session" onClick={() => { navigate('/sessions'); setActiveMenu(null); }} />
+            <MenuItem label="Group Chat" onClick={() => { navigate('/group-chat'); setActiveMenu(null); }} />
             <div style={{ height: '1px', background: 'var(--border-color)', margin: '4px 0' }} />
             <MenuItem label="Save" onClick={handleSave} disabled={!currentRun} />
             <MenuItem label="Save As..." onClick={handleSaveAs} disabled={!currentRun} />
@@ -233,7 +234,7 @@ export function MenuBar() {
                   const runStatus = await api.getRunStatus(currentRun.id);
                   if (runStatus.session_id) {
                     const json = await api.exportSessionJson(runStatus.session_id);
-                    const blob = new Blob([json], { type: 'application/json' });
+                    const blob = new Blob([JSON.stringify(json, null, 2)], { type: 'application/json' });
                     const url = URL.createObjectURL(blob);
                     const a = document.createElement('a');
                     a.href = url;
@@ -254,6 +255,7 @@ export function MenuBar() {
           <Menu label="Edit">
             <MenuItem label="Find..." onClick={handleFind} />
             <MenuItem label="Find Next" onClick={handleFindNext} disabled={!findText} />
+            <MenuItem label="Search History..." onClick={() => { navigate('/search'); setActiveMenu(null); }} />
             <div style={{ height: '1px', background: 'var(--border-color)', margin: '4px 0' }} />
             <MenuItem label="Cut" onClick={() => { document.execCommand('cut'); setActiveMenu(null); }} />
             <MenuItem label="Copy" onClick={() => { document.execCommand('copy'); setActiveMenu(null); }} />
@@ -286,6 +288,10 @@ export function MenuBar() {
               window.dispatchEvent(new CustomEvent('openSettingsModal', { detail: { tab: 'ollama' } }));
               setActiveMenu(null); 
             }} />
+            <MenuItem label="MCP Servers" onClick={() => {
+              window.dispatchEvent(new CustomEvent('openSettingsModal', { detail: { tab: 'mcp' } }));
+              setActiveMenu(null);
+            }} />
             <MenuItem label="Training Cache" onClick={() => { 
               window.dispatchEvent(new CustomEvent('openSettingsModal', { detail: { tab: 'training-cache' } }));
               setActiveMenu(null); 
@@ -0,0 +1,158 @@
+import { useState, useEffect, useCallback } from 'react';
+import { api, type ProfileMemory } from '../api';
+
+interface ProfileMemoryPanelProps {
+  profileId: string;
+  /** Re-load when this changes (e.g. message count) */
+  refreshKey?: number;
+}
+
+export function ProfileMemoryPanel({ profileId, refreshKey }: ProfileMemoryPanelProps) {
+  const [memories, setMemories] = useState<ProfileMemory[]>([]);
+  const [expanded, setExpanded] = useState(false);
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [draft, setDraft] = useState('');
+  const [newContent, setNewContent] = useState('');
+  const [newKind, setNewKind] = useState<ProfileMemory['kind']>('fact');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      setMemories(await api.listProfileMemories(profileId));
+    } catch (e) {
+      console.warn('Failed to load profile memories:', e);
+    }
+  }, [profileId]);
+
+  useEffect(() => {
+    if (!editingId) load();
+  }, [load, refreshKey, editingId]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+      setEditingId(null);
+      await load();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div style={{
+      marginBottom: '15px',
+      padding: '10px 15px',
+      background: 'var(--surface-elevated)',
+      borderRadius: '8px',
+      border: '1px solid var(--border-color)',
+      fontSize: '13px',
+    }}>
+      <div style={{ display: 'flex', alignItems: 'center', gap: '10px', flexWrap: 'wrap' }}>
+        <button type="button" className="btn btn-secondary" style={{ padding: '4px 10px' }} onClick={() => setExpanded(!expanded)}>
+          {expanded ? '▾' : '▸'} Long-term memory
+        </button>
+        <span style={{ color: 'var(--text-secondary)', fontSize: '12px' }}>
+          {memories.length === 0 ? 'Nothing remembered yet' : `${memories.length} remembered`}
+        </span>
+      </div>
+
+      {expanded && (
+        <div style={{ marginTop: '10px' }}>
+          {memories.length === 0 && (
+            <div style={{ color: 'var(--text-secondary)' }}>
+              Facts and preferences you share in chat are remembered here and reused in later conversations.
+            </div>
+          )}
+          {memories.map((memory) => (
+            <div key={memory.id} style={{ display: 'flex', gap: '8px', alignItems: 'flex-start', padding: '4px 0', borderTop: '1px solid var(--border-color)' }}>
+              <span style={{ fontSize: '11px', color: 'var(--text-secondary)', minWidth: '70px', paddingTop: '2px' }}>
+                {memory.kind}
+              </span>
+              {editingId === memory.id ? (
+                <input value={draft} onChange={(e) => setDraft(e.target.value)} style={{ flex: 1 }} />
+              ) : (
+                <span
+                  style={{ flex: 1 }}
+                  title={`Learned ${new Date(memory.created_at).toLocaleString()}${memory.source_message_id ? ' from a chat message' : ''} · used ${memory.use_count}×`}
+                >
+                  {memory.content}
+                  {memory.edited_by_user && <span style={{ color: 'var(--text-secondary)', fontSize: '11px' }}> · edited</span>}
+                </span>
+              )}
+              {editingId === memory.id ? (
+                <>
+                  <button type="button" className="btn btn-primary" style={{ padding: '2px 8px' }} disabled={busy} onClick={() => run(() => api.updateProfileMemory(memory.id, draft))}>
+                    Save
+                  </button>
+                  <button type="button" className="btn btn-secondary" style={{ padding: '2px 8px' }} disabled={busy} onClick={() => setEditingId(null)}>
+                    Cancel
+                  </button>
+                </>
+              ) : (
+                <>
+                  <button
+                    type="button"
+                    className="btn btn-secondary"
+                    style={{ padding: '2px 8px' }}
+                    disabled={busy}
+                    onClick={() => {
+                      setDraft(memory.content);
+                      setEditingId(memory.id);
+                    }}
+                  >
+                    ✏️
+                  </button>
+                  <button type="button" className="btn btn-secondary" style={{ padding: '2px 8px', color: '#dc3545' }} disabled={busy} onClick={() => run(() => api.deleteProfileMemory(memory.id))}>
+                    🗑️
+                  </button>
+                </>
+              )}
+            </div>
+          ))}
+
+          <div style={{ display: 'flex', gap: '8px', marginTop: '8px' }}>
+            <select value={newKind} onChange={(e) => setNewKind(e.target.value as ProfileMemory['kind'])}>
+              <option value="fact">Fact</option>
+              <option value="preference">Preference</option>
+            </select>
+            <input value={newContent} onChange={(e) => setNewContent(e.target.value)} placeholder="Add something to remember" style={{ flex: 1 }} />
+            <button
+              type="button"
+              className="btn btn-secondary"
+              disabled={busy || !newContent.trim()}
+              onClick={() =>
+                run(async () => {
+                  await api.addProfileMemory(profileId, newKind, newContent);
+                  setNewContent('');
+                })
+              }
+            >
+              Add
+            </button>
+            {memories.length > 0 && (
+              <button
+                type="button"
+                className="btn btn-secondary"
+                disabled={busy}
+                style={{ color: '#dc3545' }}
+                onClick={() => {
+                  if (!window.confirm('Forget everything this profile remembers about you?')) return;
+                  run(() => api.clearProfileMemories(profileId));
+                }}
+              >
+                Clear
+              </button>
+            )}
+          </div>
+
+          {error && <div style={{ color: '#dc3545', marginTop: '6px' }}>{error}</div>}
+        </div>
+      )}
+    </div>
+  );
+}
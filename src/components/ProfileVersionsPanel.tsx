@@ -0,0 +1,198 @@
+import { useState, useEffect, useCallback } from 'react';
+import { api, type ProfileVersion, type ProfileVersionDiff, type AbTestReport, type AbAnswer } from '../api';
+
+interface ProfileVersionsPanelProps {
+  profileId: string;
+  /** Called after a rollback so the editor can reload or close */
+  onRolledBack: () => void;
+}
+
+const lineColors = {
+  same: 'transparent',
+  added: 'rgba(46, 160, 67, 0.18)',
+  removed: 'rgba(248, 81, 73, 0.18)',
+};
+
+function AnswerCell({ answer }: { answer: AbAnswer }) {
+  return (
+    <td style={{ verticalAlign: 'top', padding: '6px', whiteSpace: 'pre-wrap', width: '40%' }}>
+      {answer.error ? <span style={{ color: 'var(--error-color, #f85149)' }}>{answer.error}</span> : answer.text}
+      <div style={{ color: 'var(--text-secondary)', fontSize: '11px', marginTop: '4px' }}>
+        {answer.latency_ms} ms{answer.total_tokens != null ? ` · ${answer.total_tokens} tokens` : ''}
+      </div>
+    </td>
+  );
+}
+
+export function ProfileVersionsPanel({ profileId, onRolledBack }: ProfileVersionsPanelProps) {
+  const [versions, setVersions] = useState<ProfileVersion[]>([]);
+  const [expanded, setExpanded] = useState(false);
+  const [fromId, setFromId] = useState('');
+  const [toId, setToId] = useState('');
+  const [diff, setDiff] = useState<ProfileVersionDiff | null>(null);
+  const [questions, setQuestions] = useState('');
+  const [reports, setReports] = useState<AbTestReport[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const [list, tests] = await Promise.all([api.listProfileVersions(profileId), api.listProfileAbTests(profileId)]);
+      setVersions(list);
+      setReports(tests);
+      if (list.length > 0) {
+        setToId(list[0].id);
+        setFromId(list[Math.min(1, list.length - 1)].id);
+      }
+    } catch (e) {
+      console.warn('Failed to load profile versions:', e);
+    }
+  }, [profileId]);
+
+  useEffect(() => {
+    if (expanded) load();
+  }, [expanded, load]);
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const label = (v: ProfileVersion) =>
+    `v${v.version} · ${new Date(v.created_at).toLocaleString()}${v.note ? ` · ${v.note}` : ''}`;
+
+  const versionSelect = (value: string, onChange: (id: string) => void) => (
+    <select value={value} onChange={(e) => onChange(e.target.value)} style={{ flex: 1 }}>
+      {versions.map((v) => (
+        <option key={v.id} value={v.id}>{label(v)}</option>
+      ))}
+    </select>
+  );
+
+  return (
+    <div style={{
+      marginTop: '20px',
+      padding: '10px 15px',
+      background: 'var(--surface-elevated)',
+      borderRadius: '8px',
+      border: '1px solid var(--border-color)',
+      fontSize: '13px',
+    }}>
+      <button type="button" className="btn btn-secondary" style={{ padding: '4px 10px' }} onClick={() => setExpanded(!expanded)}>
+        {expanded ? '▾' : '▸'} Versions
+      </button>
+      {expanded && (
+        <div style={{ marginTop: '10px' }}>
+          {versions.length === 0 && <p style={{ color: 'var(--text-secondary)' }}>No versions recorded yet.</p>}
+          {versions.length > 0 && (
+            <>
+              <div style={{ display: 'flex', gap: '8px', alignItems: 'center', marginBottom: '8px' }}>
+                <span>A</span>
+                {versionSelect(fromId, setFromId)}
+                <span>B</span>
+                {versionSelect(toId, setToId)}
+              </div>
+              <div style={{ display: 'flex', gap: '8px', flexWrap: 'wrap' }}>
+                <button
+                  type="button"
+                  className="btn btn-secondary"
+                  disabled={busy || !fromId || !toId}
+                  onClick={() => run(async () => setDiff(await api.diffProfileVersions(profileId, fromId, toId)))}
+                >
+                  Diff A → B
+                </button>
+                <button
+                  type="button"
+                  className="btn btn-secondary"
+                  disabled={busy || !fromId}
+                  onClick={() => {
+                    const target = versions.find((v) => v.id === fromId);
+                    if (!target || !confirm(`Roll this profile back to v${target.version}? Unsaved edits will be lost.`)) return;
+                    run(async () => {
+                      await api.rollbackProfile(profileId, fromId);
+                      onRolledBack();
+                    });
+                  }}
+                >
+                  Roll back to A
+                </button>
+              </div>
+
+              {diff && (
+                <div style={{ marginTop: '10px' }}>
+                  {diff.changes.length === 0 && <p style={{ color: 'var(--text-secondary)' }}>No differences.</p>}
+                  {diff.changes.map((change) => (
+                    <div key={change.field} style={{ marginBottom: '10px' }}>
+                      <strong>{change.field}</strong>
+                      <pre style={{ margin: '4px 0', maxHeight: '240px', overflow: 'auto', fontSize: '12px' }}>
+                        {change.lines.map((line, i) => (
+                          <div key={i} style={{ background: lineColors[line.kind] }}>
+                            {line.kind === 'added' ? '+ ' : line.kind === 'removed' ? '- ' : '  '}
+                            {line.text}
+                          </div>
+                        ))}
+                      </pre>
+                    </div>
+                  ))}
+                </div>
+              )}
+
+              <div style={{ marginTop: '15px' }}>
+                <label style={{ fontSize: '12px' }}>A/B test questions (one per line)</label>
+                <textarea value={questions} onChange={(e) => setQuestions(e.target.value)} rows={4} style={{ width: '100%' }} />
+                <button
+                  type="button"
+                  className="btn btn-primary"
+                  style={{ marginTop: '6px' }}
+                  disabled={busy || !questions.trim() || fromId === toId}
+                  onClick={() =>
+                    run(async () => {
+                      const report = await api.runProfileAbTest(profileId, fromId, toId, questions.split('\n'));
+                      setReports([report, ...reports]);
+                    })
+                  }
+                >
+                  {busy ? 'Running…' : 'Run A/B test'}
+                </button>
+              </div>
+
+              {reports.map((report) => (
+                <details key={report.id} open={report.id === reports[0]?.id} style={{ marginTop: '10px' }}>
+                  <summary>
+                    v{report.version_a} vs v{report.version_b} · {new Date(report.created_at).toLocaleString()}
+                  </summary>
+                  <table style={{ width: '100%', borderCollapse: 'collapse', marginTop: '6px' }}>
+                    <thead>
+                      <tr>
+                        <th style={{ textAlign: 'left' }}>Question</th>
+                        <th style={{ textAlign: 'left' }}>v{report.version_a}</th>
+                        <th style={{ textAlign: 'left' }}>v{report.version_b}</th>
+                      </tr>
+                    </thead>
+                    <tbody>
+                      {report.rows.map((row, i) => (
+                        <tr key={i} style={{ borderTop: '1px solid var(--border-color)' }}>
+                          <td style={{ verticalAlign: 'top', padding: '6px', width: '20%' }}>{row.question}</td>
+                          <AnswerCell answer={row.a} />
+                          <AnswerCell answer={row.b} />
+                        </tr>
+                      ))}
+                    </tbody>
+                  </table>
+                </details>
+              ))}
+            </>
+          )}
+          {error && <div style={{ color: 'var(--error-color, #f85149)', marginTop: '8px' }}>{error}</div>}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,148 @@
+import { useState, useEffect, useCallback } from 'react';
+import { api, type ConversationSummary, type SummaryScope, type SummaryMemoryConfig } from '../api';
+
+interface SummaryMemoryPanelProps {
+  scope: SummaryScope;
+  scopeId: string;
+  /** Used for manual refreshes (summarizer, K) */
+  config?: SummaryMemoryConfig;
+  /** Re-load when this changes (e.g. message count) */
+  refreshKey?: number;
+}
+
+export function SummaryMemoryPanel({ scope, scopeId, config, refreshKey }: SummaryMemoryPanelProps) {
+  const [summary, setSummary] = useState<ConversationSummary | null>(null);
+  const [expanded, setExpanded] = useState(false);
+  const [editing, setEditing] = useState(false);
+  const [draft, setDraft] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      setSummary(await api.getConversationSummary(scope, scopeId));
+    } catch (e) {
+      console.warn('Failed to load summary:', e);
+    }
+  }, [scope, scopeId]);
+
+  useEffect(() => {
+    if (!editing) load();
+  }, [load, refreshKey, editing]);
+
+  const run = async (action: () => Promise<ConversationSummary | null | void>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      const result = await action();
+      setSummary(result ?? null);
+      setEditing(false);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div style={{
+      marginBottom: '15px',
+      padding: '10px 15px',
+      background: 'var(--surface-elevated)',
+      borderRadius: '8px',
+      border: '1px solid var(--border-color)',
+      fontSize: '13px',
+    }}>
+      <div style={{ display: 'flex', alignItems: 'center', gap: '10px', flexWrap: 'wrap' }}>
+        <button type="button" className="btn btn-secondary" style={{ padding: '4px 10px' }} onClick={() => setExpanded(!expanded)}>
+          {expanded ? '▾' : '▸'} Rolling summary
+        </button>
+        <span style={{ color: 'var(--text-secondary)', fontSize: '12px' }}>
+          {summary
+            ? `${summary.covered_count} messages summarized${summary.edited_by_user ? ' · edited' : ''}`
+            : 'No summary yet'}
+        </span>
+      </div>
+
+      {expanded && (
+        <div style={{ marginTop: '10px' }}>
+          {editing ? (
+            <textarea
+              value={draft}
+              onChange={(e) => setDraft(e.target.value)}
+              rows={6}
+              style={{
+                width: '100%',
+                padding: '8px',
+                borderRadius: '4px',
+                border: '1px solid var(--border-color)',
+                fontSize: '13px',
+                resize: 'vertical',
+              }}
+            />
+          ) : (
+            <div style={{ whiteSpace: 'pre-wrap', color: summary?.summary_text ? 'inherit' : 'var(--text-secondary)' }}>
+              {summary?.summary_text || 'Older messages are summarized here once the conversation grows beyond the recent window.'}
+            </div>
+          )}
+
+          {error && <div style={{ color: '#dc3545', marginTop: '6px' }}>{error}</div>}
+
+          <div style={{ display: 'flex', gap: '8px', marginTop: '8px', flexWrap: 'wrap' }}>
+            {editing ? (
+              <>
+                <button
+                  type="button"
+                  className="btn btn-primary"
+                  disabled={busy}
+                  onClick={() => run(() => api.updateConversationSummary(scope, scopeId, draft))}
+                >
+                  Save
+                </button>
+                <button type="button" className="btn btn-secondary" disabled={busy} onClick={() => setEditing(false)}>
+                  Cancel
+                </button>
+              </>
+            ) : (
+              <>
+                <button
+                  type="button"
+                  className="btn btn-secondary"
+                  disabled={busy}
+                  onClick={() => {
+                    setDraft(summary?.summary_text ?? '');
+                    setEditing(true);
+                  }}
+                >
+                  ✏️ Edit
+                </button>
+                <button
+                  type="button"
+                  className="btn btn-secondary"
+                  disabled={busy}
+                  onClick={() => run(() => api.refreshConversationSummary(scope, scopeId, config))}
+                >
+                  {busy ? 'Summarizing...' : '🔄 Refresh now'}
+                </button>
+                {summary && (
+                  <button
+                    type="button"
+                    className="btn btn-secondary"
+                    disabled={busy}
+                    style={{ color: '#dc3545' }}
+                    onClick={() => {
+                      if (!window.confirm('Clear the summary? The full history will be sent again until the next refresh.')) return;
+                      run(() => api.clearConversationSummary(scope, scopeId));
+                    }}
+                  >
+                    Clear
+                  </button>
+                )}
+              </>
+            )}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,23 @@
+import { useEffect } from 'react';
+import { useLocation } from 'react-router-dom';
+
+/**
+ * Scroll to the element named by the URL hash (e.g. `#msg-<id>` from a search hit) once
+ * `ready` is true, and briefly highlight it.
+ */
+export function useScrollToHash(ready: boolean) {
+  const { hash } = useLocation();
+
+  useEffect(() => {
+    if (!ready || !hash) return;
+    const el = document.getElementById(decodeURIComponent(hash.slice(1)));
+    if (!el) return;
+    el.scrollIntoView({ behavior: 'smooth', block: 'center' });
+    const previous = el.style.outline;
+    el.style.outline = '2px solid #f5a623';
+    const timer = setTimeout(() => {
+      el.style.outline = previous;
+    }, 2500);
+    return () => clearTimeout(timer);
+  }, [ready, hash]);
+}
@@ -1,6 +1,6 @@
 import { useState, useCallback, useRef, useEffect } from 'react';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
-import { api } from '../api';
+import { api, type SummaryMemoryConfig } from '../api';
 
 export type StreamingLLMConfig =
   | {
@@ -18,6 +18,8 @@ export type StreamingLLMConfig =
       webSearchResults?: any[];
       modelPreference?: 'default' | 'local' | 'cloud';
       attachedDocuments?: Array<{ name: string; content: string }>;
+      /** Backend sends stored summary + recent messages instead of conversationContext */
+      summaryMemory?: SummaryMemoryConfig;
     };
 
 export interface StreamOverrides {
@@ -27,6 +29,9 @@ export interface StreamOverrides {
   attachedDocuments?: Array<{ name: string; content: string }>;
   /** For coder: override system prompt per call */
   systemPrompt?: string;
+  /** For profile: branch off an edited user message or regenerate a reply */
+  editMessageId?: string;
+  regenerateMessageId?: string;
 }
 
 export interface UseStreamingLLMResult {
@@ -166,6 +171,9 @@ export function useStreamingLLM(config: StreamingLLMConfig | null): UseStreaming
               timeout_seconds: 90,
               model_preference: config.modelPreference,
               attached_documents: attachedDocs,
+              summary_memory: config.summaryMemory,
+              edit_message_id: overrides?.editMessageId,
+              regenerate_message_id: overrides?.regenerateMessageId,
             }),
             new Promise<string>((_, reject) => {
               setTimeout(() => reject(new Error('Model timed out after 90 seconds')), 90_000);
@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
-import { api } from '../api';
+import { api, downloadReportFile, type ArgumentGraphFormat, type ReportFormat } from '../api';
 import { useAppStore } from '../store';
 import { ResponseCard } from '../components/ResponseCard';
 
@@ -24,6 +24,8 @@ export function Compare() {
   const [synthesizing, setSynthesizing] = useState(false);
   const [comparisonTable, setComparisonTable] = useState<string>('');
   const [generatingComparison, setGeneratingComparison] = useState(false);
+  const [argumentGraphSize, setArgumentGraphSize] = useState<{ nodes: number; edges: number } | null>(null);
+  const [extractingGraph, setExtractingGraph] = useState(false);
 
   useEffect(() => {
     if (!runId) {
@@ -49,6 +51,11 @@ export function Compare() {
         
         const runResults = await api.getRunResults(runId);
         setResults(runResults);
+
+        const graph = await api.getArgumentGraph(runId).catch(() => null);
+        if (graph && graph.nodes.length > 0) {
+          setArgumentGraphSize({ nodes: graph.nodes.length, edges: graph.edges.length });
+        }
       } catch (error) {
         console.error('Failed to load comparison data:', error);
       }
@@ -85,6 +92,55 @@ export function Compare() {
     }
   };
 
+  const handleExportReport = async (format: ReportFormat) => {
+    if (!sessionId) return;
+    try {
+      downloadReportFile(await api.exportSessionReport(sessionId, format));
+    } catch (error) {
+      console.error('Failed to export report:', error);
+      alert(`Failed to export report: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
+  const handleExtractArgumentGraph = async () => {
+    if (!runId) return;
+    setExtractingGraph(true);
+    try {
+      const graph = await api.extractArgumentGraph(runId);
+      setArgumentGraphSize({ nodes: graph.nodes.length, edges: graph.edges.length });
+    } catch (error) {
+      console.error('Failed to extract argument graph:', error);
+      alert(`Failed to extract argument graph: ${error instanceof Error ? error.message : String(error)}`);
+    } finally {
+      setExtractingGraph(false);
+    }
+  };
+
+  const handleExportArgumentGraph = async (format: ArgumentGraphFormat) => {
+    if (!runId) return;
+    const types: Record<ArgumentGraphFormat, [string, string]> = {
+      json: ['application/json', 'json'],
+      graphml: ['application/xml', 'graphml'],
+      mermaid: ['text/plain', 'mmd'],
+    };
+    try {
+      const content = await api.exportArgumentGraph(runId, format);
+      const [mime, ext] = types[format];
+      const blob = new Blob([content], { type: mime });
+      const url = URL.createObjectURL(blob);
+      const a = document.createElement('a');
+      a.href = url;
+      a.download = `panther-arguments-${runId.substring(0, 8)}.${ext}`;
+      document.body.appendChild(a);
+      a.click();
+      document.body.removeChild(a);
+      URL.revokeObjectURL(url);
+    } catch (error) {
+      console.error('Failed to export argument graph:', error);
+      alert('Failed to export argument graph. Please check the console for details.');
+    }
+  };
+
   const handleExportJson = async () => {
     if (!sessionId) {
       alert('Session ID not available. Please try again.');
@@ -192,6 +248,43 @@ export function Compare() {
         >
           Export JSON
         </button>
+        <select
+          value=""
+          onChange={(e) => {
+            if (e.target.value) handleExportReport(e.target.value as ReportFormat);
+          }}
+          disabled={!sessionId}
+          title="Stakeholder report with transcript, token usage and citations"
+          style={{ padding: '6px 10px', borderRadius: '4px', border: '1px solid var(--border-color)' }}
+        >
+          <option value="">Export Report…</option>
+          <option value="html">HTML</option>
+          <option value="pdf">PDF</option>
+          <option value="docx">Word (DOCX)</option>
+        </select>
+        <button
+          className="btn btn-secondary"
+          onClick={handleExtractArgumentGraph}
+          disabled={extractingGraph}
+          title="Extract claims and supports/attacks relations from the debate transcript"
+        >
+          {extractingGraph ? 'Extracting Arguments...' : argumentGraphSize ? 'Re-extract Argument Graph' : 'Extract Argument Graph'}
+        </button>
+        {argumentGraphSize && (
+          <select
+            value=""
+            onChange={(e) => {
+              if (e.target.value) handleExportArgumentGraph(e.target.value as ArgumentGraphFormat);
+            }}
+            title={`${argumentGraphSize.nodes} claims, ${argumentGraphSize.edges} relations`}
+            style={{ padding: '6px 10px', borderRadius: '4px', border: '1px solid var(--border-color)' }}
+          >
+            <option value="">Export Graph ({argumentGraphSize.nodes} claims)…</option>
+            <option value="json">JSON</option>
+            <option value="graphml">GraphML</option>
+            <option value="mermaid">Mermaid</option>
+          </select>
+        )}
         <button
           className="btn btn-primary"
           onClick={handleGenerateComparison}
@@ -1,9 +1,13 @@
 import { useState, useEffect, useRef } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
-import { api } from '../api';
+import { api, type DebateMode, type DebateContextPolicy, type DebateSettings, type HumanSeat } from '../api';
 import { useAppStore } from '../store';
 import { WebSearchModal } from '../components/WebSearchModal';
 import { ResponseCard } from '../components/ResponseCard';
+import { SummaryMemoryPanel } from '../components/SummaryMemoryPanel';
+import { DebateVerdictPanel } from '../components/DebateVerdictPanel';
+import { HumanTurnPanel } from '../components/HumanTurnPanel';
+import { useScrollToHash } from '../hooks/useScrollToHash';
 
 interface DebateMessage {
   id: string;
@@ -13,6 +17,8 @@ interface DebateMessage {
   turn_index?: number;
   text: string;
   created_at: string;
+  /** Role of a human participant */
+  author_label?: string;
   usage?: {
     prompt_tokens?: number;
     completion_tokens?: number;
@@ -23,12 +29,13 @@ interface DebateMessage {
 interface AddMessageModalProps {
   isOpen: boolean;
   onClose: () => void;
-  onSubmit: (text: string, afterMessageId?: string) => void;
+  onSubmit: (text: string, afterMessageId?: string, role?: string) => void;
   afterMessageId?: string;
 }
 
 function AddMessageModal({ isOpen, onClose, onSubmit, afterMessageId }: AddMessageModalProps) {
   const [text, setText] = useState('');
+  const [role, setRole] = useState('');
 
   if (!isOpen) return null;
 
@@ -77,6 +84,21 @@ function AddMessageModal({ isOpen, onClose, onSubmit, afterMessageId }: AddMessa
             boxSizing: 'border-box',
           }}
         />
+        <input
+          type="text"
+          value={role}
+          onChange={(e) => setRole(e.target.value)}
+          placeholder="Speaking as (optional role, e.g. Product Owner)"
+          style={{
+            width: '100%',
+            marginTop: '10px',
+            padding: '8px 10px',
+            borderRadius: '4px',
+            border: '1px solid var(--border-color)',
+            fontSize: '14px',
+            boxSizing: 'border-box',
+          }}
+        />
         <div style={{ display: 'flex', gap: '10px', justifyContent: 'flex-end', marginTop: '16px' }}>
           <button type="button" className="btn btn-secondary" onClick={onClose}>
             Cancel
@@ -86,7 +108,7 @@ function AddMessageModal({ isOpen, onClose, onSubmit, afterMessageId }: AddMessa
             className="btn btn-primary"
             onClick={() => {
               if (text.trim()) {
-                onSubmit(text, afterMessageId);
+                onSubmit(text, afterMessageId, role.trim() || undefined);
                 setText('');
               }
             }}
@@ -105,12 +127,25 @@ export function DebateRoom() {
   const navigate = useNavigate();
   const { profiles, setProfiles } = useAppStore();
   const [messages, setMessages] = useState<DebateMessage[]>([]);
+  useScrollToHash(messages.length > 0);
   const [runStatus, setRunStatus] = useState<any>(null);
   const [started, setStarted] = useState(false);
-  const [rounds] = useState(2);
+  const [rounds, setRounds] = useState(2);
   const [maxWords, setMaxWords] = useState<number | undefined>(undefined);
   const [debateLanguage, setDebateLanguage] = useState<string>('');
   const [debateTone, setDebateTone] = useState<string>('');
+  const [debateMode, setDebateMode] = useState<DebateMode>('sequential');
+  const [contextPolicy, setContextPolicy] = useState<DebateContextPolicy>('full');
+  const [lastK, setLastK] = useState<number>(6);
+  const [summarizerProfileId, setSummarizerProfileId] = useState<string>('');
+  const [stopOnConsensus, setStopOnConsensus] = useState(false);
+  const [judgeProfileId, setJudgeProfileId] = useState<string>('');
+  const [moderatorProfileId, setModeratorProfileId] = useState<string>('');
+  const [humanSeats, setHumanSeats] = useState<HumanSeat[]>([]);
+  const [newSeatRole, setNewSeatRole] = useState('');
+  const [humanTurnTimeout, setHumanTurnTimeout] = useState<number>(300);
+  /** Speaking order from the session template, if the run was created from one */
+  const [templateOrder, setTemplateOrder] = useState<string[] | null>(null);
   const [webSearchModalOpen, setWebSearchModalOpen] = useState(false);
   const [webSearchResults, setWebSearchResults] = useState<any[]>([]);
   const [sessionTitle, setSessionTitle] = useState<string>('');
@@ -128,6 +163,40 @@ export function DebateRoom() {
   const [longWait, setLongWait] = useState(false);
   const isMountedRef = useRef(true);
 
+  // Prefill the start form from the session template the run was created from
+  const applyTemplate = (template: {
+    speaking_order?: string[];
+    rounds?: number;
+    language?: string;
+    tone?: string;
+    max_words?: number;
+    web_search?: boolean;
+    debate?: DebateSettings;
+  }) => {
+    if (template.rounds) setRounds(template.rounds);
+    if (template.max_words) setMaxWords(template.max_words);
+    if (template.language) setDebateLanguage(template.language);
+    if (template.tone) setDebateTone(template.tone);
+    if (template.speaking_order && template.speaking_order.length > 0) {
+      setTemplateOrder(template.speaking_order.filter(entry => !entry.startsWith('human:')));
+    }
+    const debate = template.debate;
+    if (debate) {
+      if (debate.mode) setDebateMode(debate.mode);
+      if (debate.context_policy) setContextPolicy(debate.context_policy);
+      if (debate.last_k) setLastK(debate.last_k);
+      if (debate.moderator_profile_id) setModeratorProfileId(debate.moderator_profile_id);
+      if (debate.summarizer_profile_id) setSummarizerProfileId(debate.summarizer_profile_id);
+      if (debate.consensus?.enabled) {
+        setStopOnConsensus(true);
+        setJudgeProfileId(debate.consensus.judge_profile_id || '');
+      }
+      if (debate.human_seats) setHumanSeats(debate.human_seats);
+      if (debate.human_turn_timeout_secs !== undefined) setHumanTurnTimeout(debate.human_turn_timeout_secs);
+    }
+    if (template.web_search) setWebSearchModalOpen(true);
+  };
+
   const initializeDebate = async () => {
     if (!runId) return;
     setLoading(true);
@@ -145,6 +214,9 @@ export function DebateRoom() {
 
       const status = await api.getRunStatus(runId);
       setRunStatus(status);
+      if (status.status === 'queued' && status.template) {
+        applyTemplate(status.template);
+      }
 
       // Load session title
       if (status.session_id) {
@@ -451,6 +523,206 @@ export function DebateRoom() {
                       </select>
                     </div>
                     
+                    <div style={{ display: 'flex', gap: '8px', alignItems: 'center', flex: '1', minWidth: '200px' }}>
+                      <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>Mode:</label>
+                      <select
+                        value={debateMode}
+                        onChange={(e) => setDebateMode(e.target.value as DebateMode)}
+                        style={{
+                          flex: 1,
+                          padding: '5px 10px',
+                          borderRadius: '4px',
+                          border: '1px solid var(--border-color)',
+                          fontSize: '13px'
+                        }}
+                      >
+                        <option value="sequential">Sequential</option>
+                        <option value="parallel">Parallel rounds</option>
+                        <option value="moderator">Moderator-led</option>
+                        <option value="cross_examination">Cross-examination</option>
+                      </select>
+                    </div>
+
+                    {debateMode === 'moderator' && (
+                      <div style={{ display: 'flex', gap: '8px', alignItems: 'center', flex: '1', minWidth: '200px' }}>
+                        <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>Moderator:</label>
+                        <select
+                          value={moderatorProfileId}
+                          onChange={(e) => setModeratorProfileId(e.target.value)}
+                          style={{
+                            flex: 1,
+                            padding: '5px 10px',
+                            borderRadius: '4px',
+                            border: '1px solid var(--border-color)',
+                            fontSize: '13px'
+                          }}
+                        >
+                          <option value="">Select profile…</option>
+                          {profiles.map(p => (
+                            <option key={p.id} value={p.id}>{p.name}</option>
+                          ))}
+                        </select>
+                      </div>
+                    )}
+
+                    <div style={{ display: 'flex', gap: '8px', alignItems: 'center', flex: '1', minWidth: '200px' }}>
+                      <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>Context:</label>
+                      <select
+                        value={contextPolicy}
+                        onChange={(e) => setContextPolicy(e.target.value as DebateContextPolicy)}
+                        style={{
+                          flex: 1,
+                          padding: '5px 10px',
+                          borderRadius: '4px',
+                          border: '1px solid var(--border-color)',
+                          fontSize: '13px'
+                        }}
+                      >
+                        <option value="full">Full history</option>
+                        <option value="last_k_messages">Last K messages</option>
+                        <option value="rolling_summary">Rolling summary + last K</option>
+                      </select>
+                      {contextPolicy !== 'full' && (
+                        <input
+                          type="number"
+                          value={lastK}
+                          onChange={(e) => setLastK(Math.max(1, parseInt(e.target.value) || 1))}
+                          min="1"
+                          title="K"
+                          style={{
+                            width: '60px',
+                            padding: '5px 10px',
+                            borderRadius: '4px',
+                            border: '1px solid var(--border-color)',
+                          }}
+                        />
+                      )}
+                    </div>
+
+                    {contextPolicy === 'rolling_summary' && (
+                      <div style={{ display: 'flex', gap: '8px', alignItems: 'center', flex: '1', minWidth: '200px' }}>
+                        <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>Summarizer:</label>
+                        <select
+                          value={summarizerProfileId}
+                          onChange={(e) => setSummarizerProfileId(e.target.value)}
+                          style={{
+                            flex: 1,
+                            padding: '5px 10px',
+                            borderRadius: '4px',
+                            border: '1px solid var(--border-color)',
+                            fontSize: '13px'
+                          }}
+                        >
+                          <option value="">Moderator / first speaker</option>
+                          {profiles.map(p => (
+                            <option key={p.id} value={p.id}>{p.name}</option>
+                          ))}
+                        </select>
+                      </div>
+                    )}
+
+                    <div style={{ display: 'flex', gap: '8px', alignItems: 'center', flex: '1', minWidth: '200px' }}>
+                      <label
+                        style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap', display: 'flex', alignItems: 'center', gap: '4px', cursor: 'pointer' }}
+                        title="After each round a judge measures agreement; the debate stops early on consensus or when positions stall, then a verdict is written"
+                      >
+                        <input
+                          type="checkbox"
+                          checked={stopOnConsensus}
+                          onChange={(e) => setStopOnConsensus(e.target.checked)}
+                        />
+                        Stop on consensus
+                      </label>
+                      {stopOnConsensus && (
+                        <select
+                          value={judgeProfileId}
+                          onChange={(e) => setJudgeProfileId(e.target.value)}
+                          style={{
+                            flex: 1,
+                            padding: '5px 10px',
+                            borderRadius: '4px',
+                            border: '1px solid var(--border-color)',
+                            fontSize: '13px'
+                          }}
+                        >
+                          <option value="">Judge: moderator / first speaker</option>
+                          {profiles.map(p => (
+                            <option key={p.id} value={p.id}>Judge: {p.name}</option>
+                          ))}
+                        </select>
+                      )}
+                    </div>
+
+                    {(debateMode === 'sequential' || debateMode === 'parallel') && (
+                      <div style={{ display: 'flex', gap: '8px', alignItems: 'center', flex: '2', minWidth: '300px', flexWrap: 'wrap' }}>
+                        <label
+                          style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}
+                          title="Human participants take turns after the agents; the debate waits for their answer"
+                        >
+                          Human seats:
+                        </label>
+                        {humanSeats.map(seat => (
+                          <span
+                            key={seat.id}
+                            style={{ padding: '2px 8px', borderRadius: '10px', border: '1px solid var(--border-color)', fontSize: '12px' }}
+                          >
+                            🙋 {seat.role}{' '}
+                            <button
+                              type="button"
+                              onClick={() => setHumanSeats(humanSeats.filter(s => s.id !== seat.id))}
+                              style={{ border: 'none', background: 'transparent', cursor: 'pointer', padding: 0 }}
+                              title="Remove seat"
+                            >
+                              ✕
+                            </button>
+                          </span>
+                        ))}
+                        <input
+                          type="text"
+                          placeholder="Role, e.g. Product Owner"
+                          value={newSeatRole}
+                          onChange={(e) => setNewSeatRole(e.target.value)}
+                          style={{
+                            width: '180px',
+                            padding: '5px 10px',
+                            borderRadius: '4px',
+                            border: '1px solid var(--border-color)',
+                            fontSize: '13px'
+                          }}
+                        />
+                        <button
+                          type="button"
+                          className="btn btn-secondary"
+                          style={{ padding: '4px 10px', fontSize: '12px' }}
+                          disabled={!newSeatRole.trim()}
+                          onClick={() => {
+                            setHumanSeats([...humanSeats, { id: crypto.randomUUID(), role: newSeatRole.trim() }]);
+                            setNewSeatRole('');
+                          }}
+                        >
+                          + Add seat
+                        </button>
+                        {humanSeats.length > 0 && (
+                          <>
+                            <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>Turn timeout (s):</label>
+                            <input
+                              type="number"
+                              value={humanTurnTimeout}
+                              onChange={(e) => setHumanTurnTimeout(Math.max(0, parseInt(e.target.value) || 0))}
+                              min="0"
+                              title="0 waits until the turn is answered or skipped"
+                              style={{
+                                width: '80px',
+                                padding: '5px 10px',
+                                borderRadius: '4px',
+                                border: '1px solid var(--border-color)',
+                              }}
+                            />
+                          </>
+                        )}
+                      </div>
+                    )}
+
                     <div style={{ display: 'flex', gap: '8px', alignItems: 'flex-start', flex: '2', minWidth: '300px' }}>
                       <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap', marginTop: '5px' }}>Tone:</label>
                       <textarea
@@ -514,17 +786,34 @@ export function DebateRoom() {
                         alert('No profiles selected');
                         return;
                       }
+                      if (debateMode === 'moderator' && !moderatorProfileId) {
+                        alert('Select a moderator profile for moderator-led debates');
+                        return;
+                      }
+                      const seats = debateMode === 'sequential' || debateMode === 'parallel' ? humanSeats : [];
                       setStarting(true);
                       try {
                         console.log('Starting debate with:', { runId, rounds, profileIds: runStatus.selected_profile_ids, maxWords, language: debateLanguage, tone: debateTone });
                         await api.startDebate(
                           runId!, 
                           rounds, 
-                          runStatus.selected_profile_ids, 
+                          [...(templateOrder ?? runStatus.selected_profile_ids), ...seats.map(seat => `human:${seat.id}`)],
                           maxWords,
                           debateLanguage || undefined,
                           debateTone || undefined,
-                          webSearchResults.length > 0 ? webSearchResults : undefined
+                          webSearchResults.length > 0 ? webSearchResults : undefined,
+                          {
+                            mode: debateMode,
+                            context_policy: contextPolicy,
+                            last_k: lastK,
+                            summarizer_profile_id: summarizerProfileId || undefined,
+                            consensus: stopOnConsensus
+                              ? { enabled: true, judge_profile_id: judgeProfileId || undefined }
+                              : undefined,
+                            moderator_profile_id: moderatorProfileId || undefined,
+                            human_seats: seats.length > 0 ? seats : undefined,
+                            human_turn_timeout_secs: humanTurnTimeout,
+                          }
                         );
                         setStarted(true);
                         // Give it a moment to update status
@@ -672,6 +961,19 @@ export function DebateRoom() {
         </div>
       )}
 
+      {runId && runStatus && (
+        <HumanTurnPanel runId={runId} active={runStatus.status === 'running' || runStatus.status === 'paused'} />
+      )}
+
+      {runId && runStatus && runStatus.status !== 'queued' && (
+        <SummaryMemoryPanel
+          scope="run"
+          scopeId={runId}
+          config={{ keep_last_k: lastK, summarizer_profile_id: summarizerProfileId || undefined }}
+          refreshKey={messages.length}
+        />
+      )}
+
       <div style={{ 
         maxHeight: '600px', 
         overflowY: 'auto', 
@@ -694,7 +996,7 @@ export function DebateRoom() {
               new Date(msg.created_at).toDateString() !== new Date(prevMsg.created_at).toDateString();
             
             return (
-              <div key={msg.id}>
+              <div key={msg.id} id={`msg-${msg.id}`}>
                 {showDateSeparator && (
                   <div style={{ 
                     textAlign: 'center', 
@@ -749,7 +1051,7 @@ export function DebateRoom() {
                         color: isAgent ? '#1976d2' : isUser ? '#388e3c' : '#666',
                         fontSize: '14px'
                       }}>
-                        {isAgent ? getProfileName(msg.profile_id) : isUser ? 'You' : msg.author_type}
+                        {isAgent ? getProfileName(msg.profile_id) : isUser ? msg.author_label || 'You' : msg.author_type}
                       </strong>
                       <span style={{ fontSize: '11px', color: 'var(--text-secondary)' }}>
                         {formatDateTime(msg.created_at)}
@@ -848,6 +1150,14 @@ export function DebateRoom() {
         )}
       </div>
 
+      {runId && runStatus && (runStatus.status === 'complete' || runStatus.status === 'partial') && (
+        <DebateVerdictPanel
+          runId={runId}
+          profileNames={Object.fromEntries(profiles.map(p => [p.id, p.name]))}
+          refreshKey={runStatus.status}
+        />
+      )}
+
       {runStatus && (
       <div style={{ marginBottom: '20px', display: 'flex', gap: '10px', alignItems: 'center' }}>
         <button
@@ -918,9 +1228,9 @@ export function DebateRoom() {
       <AddMessageModal
         isOpen={addMessageModal.isOpen}
         onClose={() => setAddMessageModal({ isOpen: false })}
-        onSubmit={async (text, afterMessageId) => {
+        onSubmit={async (text, afterMessageId, role) => {
           try {
-            await api.addUserMessage(runId!, text, afterMessageId);
+            await api.addUserMessage(runId!, text, afterMessageId, role);
             setAddMessageModal({ isOpen: false });
           } catch (error) {
             console.error('Failed to add message:', error);
@@ -0,0 +1,320 @@
+import { useEffect, useRef, useState } from 'react';
+import { useSearchParams } from 'react-router-dom';
+import { api, type GroupConversation, type GroupMemberInput, type GroupMessage } from '../api';
+import { useAppStore } from '../store';
+import { useScrollToHash } from '../hooks/useScrollToHash';
+
+export function GroupChat() {
+  const { profiles, setProfiles, language } = useAppStore();
+  const [searchParams, setSearchParams] = useSearchParams();
+  const [conversations, setConversations] = useState<GroupConversation[]>([]);
+  const [messages, setMessages] = useState<GroupMessage[]>([]);
+  const [input, setInput] = useState('');
+  const [sending, setSending] = useState(false);
+  const [failures, setFailures] = useState<string[]>([]);
+  const [creating, setCreating] = useState(false);
+  const [newTitle, setNewTitle] = useState('');
+  const [newMembers, setNewMembers] = useState<GroupMemberInput[]>([]);
+  const messagesEndRef = useRef<HTMLDivElement>(null);
+  // Search hits link here with ?conversation=<id>#group-msg-<id>
+  useScrollToHash(messages.length > 0);
+
+  const currentId = searchParams.get('conversation');
+  const current = conversations.find((c) => c.id === currentId) ?? null;
+  const selectConversation = (id: string) => setSearchParams({ conversation: id });
+
+  useEffect(() => {
+    if (profiles.length === 0) {
+      api.listProfiles().then(setProfiles).catch((e) => console.error('Failed to load profiles:', e));
+    }
+    api.listGroupConversations()
+      .then((list) => {
+        setConversations(list);
+        if (!currentId && list.length > 0) selectConversation(list[0].id);
+        if (list.length === 0) setCreating(true);
+      })
+      .catch((e) => console.error('Failed to load group chats:', e));
+  }, []);
+
+  useEffect(() => {
+    setFailures([]);
+    if (!currentId) {
+      setMessages([]);
+      return;
+    }
+    api.loadGroupMessages(currentId).then(setMessages).catch(() => setMessages([]));
+  }, [currentId]);
+
+  useEffect(() => {
+    if (!window.location.hash) messagesEndRef.current?.scrollIntoView({ behavior: 'smooth' });
+  }, [messages]);
+
+  const replaceConversation = (updated: GroupConversation) =>
+    setConversations((prev) => prev.map((c) => (c.id === updated.id ? updated : c)));
+
+  const handleCreate = async () => {
+    if (newMembers.length === 0) return;
+    try {
+      const created = await api.createGroupConversation(newMembers, newTitle.trim() || undefined);
+      setConversations((prev) => [created, ...prev]);
+      setCreating(false);
+      setNewTitle('');
+      setNewMembers([]);
+      selectConversation(created.id);
+    } catch (e) {
+      alert(e instanceof Error ? e.message : String(e));
+    }
+  };
+
+  const handleDelete = async (conversation: GroupConversation) => {
+    if (!confirm(`Delete "${conversation.title}" and all its messages?`)) return;
+    try {
+      await api.deleteGroupConversation(conversation.id);
+      setConversations((prev) => prev.filter((c) => c.id !== conversation.id));
+      if (conversation.id === currentId) setSearchParams({});
+    } catch (e) {
+      alert(e instanceof Error ? e.message : String(e));
+    }
+  };
+
+  const toggleAutoRespond = async (profileId: string) => {
+    if (!current) return;
+    const members = current.members.map((m) => ({
+      profile_id: m.profile_id,
+      auto_respond: m.profile_id === profileId ? !m.auto_respond : m.auto_respond,
+    }));
+    try {
+      replaceConversation(await api.updateGroupConversation(current.id, { members }));
+    } catch (e) {
+      alert(e instanceof Error ? e.message : String(e));
+    }
+  };
+
+  const handleSend = async () => {
+    if (!current || !input.trim() || sending) return;
+    const content = input.trim();
+    setInput('');
+    setSending(true);
+    setFailures([]);
+    // Show the user's message right away; replaced by the stored copy below
+    const pending: GroupMessage = {
+      id: `pending-${Date.now()}`,
+      conversation_id: current.id,
+      profile_id: null,
+      author_name: 'User',
+      content,
+      mentions: [],
+      created_at: new Date().toISOString(),
+    };
+    setMessages((prev) => [...prev, pending]);
+    try {
+      const turn = await api.sendGroupMessage({
+        conversation_id: current.id,
+        content,
+        language: language && language !== 'English' ? language : undefined,
+      });
+      setMessages((prev) => [...prev.filter((m) => m.id !== pending.id), ...turn.messages]);
+      setFailures(turn.failures.map((f) => `${f.name}: ${f.error}`));
+    } catch (e) {
+      setMessages((prev) => prev.filter((m) => m.id !== pending.id));
+      setInput(content);
+      setFailures([e instanceof Error ? e.message : String(e)]);
+    } finally {
+      setSending(false);
+    }
+  };
+
+  const photoFor = (profileId: string | null) =>
+    profileId ? current?.members.find((m) => m.profile_id === profileId)?.photo_url ?? null : null;
+
+  const inputStyle = { padding: '6px 10px', borderRadius: '4px', border: '1px solid var(--border-color)' };
+
+  return (
+    <div style={{ display: 'flex', gap: '20px', height: 'calc(100vh - 120px)' }}>
+      <div style={{ width: '240px', flexShrink: 0, overflowY: 'auto' }}>
+        <button type="button" className="btn btn-primary" style={{ width: '100%', marginBottom: '12px' }} onClick={() => setCreating(true)}>
+          + New group chat
+        </button>
+        {conversations.map((c) => (
+          <div
+            key={c.id}
+            className="card"
+            onClick={() => {
+              setCreating(false);
+              selectConversation(c.id);
+            }}
+            style={{
+              marginBottom: '8px',
+              padding: '10px',
+              cursor: 'pointer',
+              border: c.id === currentId && !creating ? '2px solid var(--primary)' : undefined,
+            }}
+          >
+            <div style={{ display: 'flex', justifyContent: 'space-between', gap: '6px' }}>
+              <strong style={{ fontSize: '14px' }}>{c.title}</strong>
+              <button
+                type="button"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  handleDelete(c);
+                }}
+                style={{ background: 'none', border: 'none', cursor: 'pointer', color: 'var(--text-secondary)' }}
+                aria-label="Delete group chat"
+              >
+                ×
+              </button>
+            </div>
+            <div style={{ fontSize: '12px', color: 'var(--text-secondary)' }}>{c.members.map((m) => m.name).join(', ')}</div>
+          </div>
+        ))}
+      </div>
+
+      <div style={{ flex: 1, display: 'flex', flexDirection: 'column', minWidth: 0 }}>
+        {creating ? (
+          <div className="card">
+            <h2 style={{ marginTop: 0 }}>New group chat</h2>
+            <input
+              type="text"
+              value={newTitle}
+              onChange={(e) => setNewTitle(e.target.value)}
+              placeholder="Title (optional)"
+              style={{ ...inputStyle, width: '100%', marginBottom: '12px' }}
+            />
+            <p style={{ fontSize: '13px', color: 'var(--text-secondary)' }}>
+              Pick the profiles to invite. Auto-responders reply to every message that doesn't @mention anyone.
+            </p>
+            {profiles.map((p) => {
+              const member = newMembers.find((m) => m.profile_id === p.id);
+              return (
+                <div key={p.id} style={{ display: 'flex', alignItems: 'center', gap: '12px', padding: '4px 0', fontSize: '14px' }}>
+                  <label style={{ display: 'flex', alignItems: 'center', gap: '6px', minWidth: '200px', cursor: 'pointer' }}>
+                    <input
+                      type="checkbox"
+                      checked={!!member}
+                      onChange={() =>
+                        setNewMembers((prev) =>
+                          member ? prev.filter((m) => m.profile_id !== p.id) : [...prev, { profile_id: p.id, auto_respond: prev.length === 0 }]
+                        )
+                      }
+                    />
+                    {p.name}
+                  </label>
+                  {member && (
+                    <label style={{ display: 'flex', alignItems: 'center', gap: '4px', fontSize: '12px', color: 'var(--text-secondary)', cursor: 'pointer' }}>
+                      <input
+                        type="checkbox"
+                        checked={member.auto_respond}
+                        onChange={() =>
+                          setNewMembers((prev) => prev.map((m) => (m.profile_id === p.id ? { ...m, auto_respond: !m.auto_respond } : m)))
+                        }
+                      />
+                      Auto-respond
+                    </label>
+                  )}
+                </div>
+              );
+            })}
+            <div style={{ display: 'flex', gap: '8px', marginTop: '16px' }}>
+              <button type="button" className="btn btn-primary" disabled={newMembers.length === 0} onClick={handleCreate}>
+                Create
+              </button>
+              {conversations.length > 0 && (
+                <button type="button" className="btn btn-secondary" onClick={() => setCreating(false)}>
+                  Cancel
+                </button>
+              )}
+            </div>
+          </div>
+        ) : current ? (
+          <>
+            <div className="page-header" style={{ marginBottom: '10px' }}>
+              <h1 style={{ marginBottom: '6px' }}>{current.title}</h1>
+              <div style={{ display: 'flex', gap: '8px', flexWrap: 'wrap', fontSize: '12px' }}>
+                {current.members.map((m) => (
+                  <button
+                    key={m.profile_id}
+                    type="button"
+                    className="btn btn-secondary"
+                    onClick={() => toggleAutoRespond(m.profile_id)}
+                    title={m.auto_respond ? 'Replies to unaddressed messages (click to turn off)' : 'Replies only when @mentioned (click to auto-respond)'}
+                    style={{ fontSize: '12px', padding: '3px 10px', opacity: m.auto_respond ? 1 : 0.7 }}
+                  >
+                    {m.auto_respond ? '🔔' : '🔕'} {m.name}
+                  </button>
+                ))}
+              </div>
+            </div>
+
+            <div style={{ flex: 1, overflowY: 'auto', padding: '10px 0' }}>
+              {messages.length === 0 && (
+                <p style={{ color: 'var(--text-secondary)', textAlign: 'center', marginTop: '40px' }}>
+                  Say hello, or address someone with @{current.members[0]?.name ?? 'Name'}.
+                </p>
+              )}
+              {messages.map((msg) => {
+                const isUser = msg.profile_id === null;
+                const photo = photoFor(msg.profile_id);
+                return (
+                  <div
+                    key={msg.id}
+                    id={`group-msg-${msg.id}`}
+                    style={{ display: 'flex', justifyContent: isUser ? 'flex-end' : 'flex-start', marginBottom: '10px' }}
+                  >
+                    <div
+                      style={{
+                        maxWidth: '70%',
+                        padding: '10px 14px',
+                        borderRadius: '12px',
+                        background: isUser ? 'var(--highlight-bg)' : 'var(--surface-elevated)',
+                        border: `1px solid ${isUser ? 'var(--highlight-bg)' : 'var(--border-color)'}`,
+                        wordWrap: 'break-word',
+                      }}
+                    >
+                      {!isUser && (
+                        <div style={{ display: 'flex', alignItems: 'center', gap: '6px', marginBottom: '4px' }}>
+                          {photo && <img src={photo} alt="" style={{ width: '20px', height: '20px', borderRadius: '50%', objectFit: 'cover' }} />}
+                          <strong style={{ fontSize: '13px', color: 'var(--primary)' }}>{msg.author_name}</strong>
+                        </div>
+                      )}
+                      <div style={{ whiteSpace: 'pre-wrap', fontSize: '14px', lineHeight: '1.5' }}>{msg.content}</div>
+                      <div style={{ fontSize: '11px', color: 'var(--text-secondary)', marginTop: '4px' }}>
+                        {new Date(msg.created_at).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}
+                      </div>
+                    </div>
+                  </div>
+                );
+              })}
+              {sending && <p style={{ color: 'var(--text-secondary)', fontSize: '14px' }}>Waiting for replies...</p>}
+              {failures.map((f, i) => (
+                <div key={i} style={{ color: '#dc3545', fontSize: '13px', marginBottom: '4px' }}>{f}</div>
+              ))}
+              <div ref={messagesEndRef} />
+            </div>
+
+            <form
+              onSubmit={(e) => {
+                e.preventDefault();
+                handleSend();
+              }}
+              style={{ display: 'flex', gap: '10px' }}
+            >
+              <input
+                type="text"
+                value={input}
+                onChange={(e) => setInput(e.target.value)}
+                placeholder={`Message the group (use @${current.members[0]?.name ?? 'Name'} to address one profile)`}
+                disabled={sending}
+                style={{ ...inputStyle, flex: 1, fontSize: '14px' }}
+              />
+              <button type="submit" className="btn btn-primary" disabled={sending || !input.trim()}>
+                {sending ? 'Sending...' : 'Send'}
+              </button>
+            </form>
+          </>
+        ) : (
+          <p style={{ color: 'var(--text-secondary)' }}>Select or create a group chat.</p>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -3,6 +3,7 @@ import { useParams, useNavigate } from 'react-router-dom';
 import { api } from '../api';
 import { useAppStore } from '../store';
 import { ResponseCard } from '../components/ResponseCard';
+import { useScrollToHash } from '../hooks/useScrollToHash';
 
 interface RunResult {
   id: string;
@@ -12,6 +13,9 @@ interface RunResult {
   error_message_safe?: string;
   started_at: string;
   finished_at?: string;
+  /** Pipeline stage: generate | critique | refine */
+  stage?: string;
+  parent_result_id?: string;
 }
 
 interface ContinueModalProps {
@@ -99,6 +103,7 @@ export function ParallelBrainstorm() {
   const { profiles, setProfiles } = useAppStore();
   const [runStatus, setRunStatus] = useState<any>(null);
   const [results, setResults] = useState<RunResult[]>([]);
+  useScrollToHash(results.length > 0);
   const [started, setStarted] = useState(false);
   const [sessionTitle, setSessionTitle] = useState<string>('');
   const [continueModal, setContinueModal] = useState<{ isOpen: boolean; resultId: string; profileName: string }>({
@@ -142,7 +147,7 @@ export function ParallelBrainstorm() {
         // Load initial results
         try {
           const runResults = await api.getRunResults(runId);
-          const uniqueResults = runResults.reduce((acc: RunResult[], result: RunResult) => {
+          const uniqueResults = runResults.filter((r: RunResult) => r.stage !== 'critique').reduce((acc: RunResult[], result: RunResult) => {
             const existing = acc.find(r => r.profile_id === result.profile_id);
             if (!existing || new Date(result.started_at) > new Date(existing.started_at)) {
               return [...acc.filter(r => r.profile_id !== result.profile_id), result];
@@ -177,8 +182,8 @@ export function ParallelBrainstorm() {
         setRunStatus(status);
 
         const runResults = await api.getRunResults(runId);
-        // Filter out duplicates by profile_id (keep the most recent one)
-        const uniqueResults = runResults.reduce((acc: RunResult[], result: RunResult) => {
+        // Filter out duplicates by profile_id (keep the most recent one; critiques are not answers)
+        const uniqueResults = runResults.filter((r: RunResult) => r.stage !== 'critique').reduce((acc: RunResult[], result: RunResult) => {
           const existing = acc.find(r => r.profile_id === result.profile_id);
           if (!existing || new Date(result.started_at) > new Date(existing.started_at)) {
             return [...acc.filter(r => r.profile_id !== result.profile_id), result];
@@ -317,6 +322,7 @@ export function ParallelBrainstorm() {
           return (
             <div 
               key={result.id} 
+              id={`result-${result.id}`}
               className="card"
               style={{
                 display: 'flex',
@@ -1,11 +1,14 @@
 import { useState, useEffect, useRef, useMemo, useCallback } from 'react';
-import { useParams, useNavigate } from 'react-router-dom';
-import { api } from '../api';
+import { useParams, useNavigate, useSearchParams } from 'react-router-dom';
+import { api, downloadReportFile, type ReportFormat } from '../api';
 import { useAppStore } from '../store';
 import { ExportChatModal } from '../components/ExportChatModal';
+import { SummaryMemoryPanel } from '../components/SummaryMemoryPanel';
+import { ProfileMemoryPanel } from '../components/ProfileMemoryPanel';
 import { VoiceInput } from '../components/VoiceInput';
 import { VoiceOutput } from '../components/VoiceOutput';
 import { useStreamingLLM } from '../hooks/useStreamingLLM';
+import { useScrollToHash } from '../hooks/useScrollToHash';
 import { startRecording, startRecordingWithSilenceDetection, type RecordingSession } from '../utils/audioRecorder';
 interface ChatMessage {
   id: string;
@@ -13,6 +16,19 @@ interface ChatMessage {
   content: string;
   timestamp: string;
   profile_id?: string;
+  /** Alternatives at this position (edits / regenerations), set for stored messages */
+  sibling_ids?: string[];
+}
+
+function toChatMessage(msg: any): ChatMessage {
+  return {
+    id: msg.id,
+    role: msg.role as 'user' | 'assistant',
+    content: msg.content,
+    timestamp: msg.timestamp,
+    profile_id: msg.profile_id,
+    sibling_ids: msg.sibling_ids,
+  };
 }
 
 type ModelPreference = 'default' | 'local' | 'cloud';
@@ -22,10 +38,14 @@ export function ProfileChat() {
   const navigate = useNavigate();
   const { profiles, providers, projects, setProjects, language, setProviders, continuousAutoSend, setContinuousAutoSend, autoSpeakResponses } = useAppStore();
   const [messages, setMessages] = useState<ChatMessage[]>([]);
+  useScrollToHash(messages.length > 0);
   const [inputMessage, setInputMessage] = useState('');
   const [streamingAssistantId, setStreamingAssistantId] = useState<string | null>(null);
   const [conversations, setConversations] = useState<Array<{ id: string; title: string; created_at: string; updated_at: string }>>([]);
   const [currentConversationId, setCurrentConversationId] = useState<string | null>(null);
+  // Search hits link here with ?conversation=<id>#chat-msg-<id>
+  const [searchParams] = useSearchParams();
+  const requestedConversationId = searchParams.get('conversation');
   const [selectedMessages, setSelectedMessages] = useState<Set<string>>(new Set());
   const [improvementPrompts, setImprovementPrompts] = useState<Record<string, string>>({});
   const [showExportModal, setShowExportModal] = useState(false);
@@ -33,6 +53,7 @@ export function ProfileChat() {
   const [webSearchResults, setWebSearchResults] = useState<any[]>([]);
   const [searchWeb, setSearchWeb] = useState(false);
   const [modelPreference, setModelPreference] = useState<ModelPreference>('default');
+  const [summaryMemoryEnabled, setSummaryMemoryEnabled] = useState(false);
   const [improvingMessageId, setImprovingMessageId] = useState<string | null>(null);
   const [attachedDocuments, setAttachedDocuments] = useState<Array<{ name: string; content: string; fromTraining?: boolean }>>([]);
   const [showLoadTrainingData, setShowLoadTrainingData] = useState(false);
@@ -65,6 +86,7 @@ export function ProfileChat() {
   const profile = profiles.find(p => p.id === profileId);
   const profileProvider = profile ? providers.find(p => p.id === profile.provider_account_id) : null;
   const isHybrid = profileProvider?.provider_type === 'hybrid';
+  const profileMemoryEnabled = Boolean((profile?.params_json?.profile_memory as { enabled?: boolean } | undefined)?.enabled);
 
   const streamingConfig = useMemo(
     () =>
@@ -76,9 +98,10 @@ export function ProfileChat() {
             conversationContext: [] as any[],
             language: chatLanguage !== 'English' ? chatLanguage : undefined,
             modelPreference: isHybrid ? modelPreference : undefined,
+            summaryMemory: summaryMemoryEnabled ? { enabled: true } : undefined,
           }
         : null,
-    [profileId, profile, currentConversationId, chatLanguage, isHybrid, modelPreference]
+    [profileId, profile, currentConversationId, chatLanguage, isHybrid, modelPreference, summaryMemoryEnabled]
   );
   const { streamedText, isStreaming, error, startStream } = useStreamingLLM(streamingConfig);
 
@@ -109,7 +132,9 @@ export function ProfileChat() {
       try {
         const list = await api.listProfileConversations(profileId!);
         setConversations(list);
-        if (list.length > 0 && !currentConversationId) {
+        if (requestedConversationId && list.some((c: { id: string }) => c.id === requestedConversationId)) {
+          setCurrentConversationId(requestedConversationId);
+        } else if (list.length > 0 && !currentConversationId) {
           setCurrentConversationId(list[0].id);
         }
       } catch (error) {
@@ -121,14 +146,7 @@ export function ProfileChat() {
     const loadMessages = async (convId: string | null) => {
       try {
         const savedMessages = await api.loadChatMessages(profileId!, convId ?? undefined);
-        const chatMessages: ChatMessage[] = savedMessages.map((msg: any) => ({
-          id: msg.id,
-          role: msg.role as 'user' | 'assistant',
-          content: msg.content,
-          timestamp: msg.timestamp,
-          profile_id: msg.profile_id,
-        }));
-        setMessages(chatMessages);
+        setMessages(savedMessages.map(toChatMessage));
       } catch (error) {
         console.error('Failed to load chat messages:', error);
         setMessages([]);
@@ -138,26 +156,27 @@ export function ProfileChat() {
     setCurrentConversationId(null);
     loadConversations();
     setWebSearchResults([]);
-  }, [profileId, navigate, profiles.length]);
+  }, [profileId, navigate, profiles.length, requestedConversationId]);
 
-  // Load messages when conversation changes
-  useEffect(() => {
+  // Active branch of the current conversation
+  const reloadMessages = useCallback(async () => {
     if (!profileId || !currentConversationId) {
       setMessages([]);
       return;
     }
-    api.loadChatMessages(profileId, currentConversationId).then((savedMessages) => {
-      const chatMessages: ChatMessage[] = savedMessages.map((msg: any) => ({
-        id: msg.id,
-        role: msg.role as 'user' | 'assistant',
-        content: msg.content,
-        timestamp: msg.timestamp,
-        profile_id: msg.profile_id,
-      }));
-      setMessages(chatMessages);
-    }).catch(() => setMessages([]));
+    try {
+      const savedMessages = await api.loadChatMessages(profileId, currentConversationId);
+      setMessages(savedMessages.map(toChatMessage));
+    } catch {
+      setMessages([]);
+    }
   }, [profileId, currentConversationId]);
 
+  // Load messages when conversation changes
+  useEffect(() => {
+    reloadMessages();
+  }, [reloadMessages]);
+
   useEffect(() => {
     messagesEndRef.current?.scrollIntoView({ behavior: 'smooth' });
   }, [messages]);
@@ -183,8 +202,10 @@ export function ProfileChat() {
       setStreamingAssistantId(null);
     } else if (!isStreaming && streamingAssistantId) {
       setStreamingAssistantId(null);
+      // Pick up stored ids and branch info for the new turn
+      reloadMessages();
     }
-  }, [isStreaming, streamingAssistantId, error]);
+  }, [isStreaming, streamingAssistantId, error, reloadMessages]);
 
   // When streaming completes, auto-play the new assistant message if enabled
   useEffect(() => {
@@ -338,6 +359,50 @@ export function ProfileChat() {
     setCurrentConversationId(convId);
   };
 
+  // Editing a user message or regenerating a reply keeps the original as another branch
+  const [editingMessageId, setEditingMessageId] = useState<string | null>(null);
+  const [editDraft, setEditDraft] = useState('');
+
+  const handleSubmitEdit = async (msg: ChatMessage) => {
+    const text = editDraft.trim();
+    if (!text || !profileId || !currentConversationId || isStreaming) return;
+    setEditingMessageId(null);
+    const assistantId = `pending-${Date.now()}`;
+    setMessages(prev => [
+      ...prev.slice(0, prev.findIndex(m => m.id === msg.id)),
+      { id: `edit-${Date.now()}`, role: 'user', content: text, timestamp: new Date().toISOString() },
+      { id: assistantId, role: 'assistant', content: '', timestamp: new Date().toISOString(), profile_id: profileId },
+    ]);
+    setStreamingAssistantId(assistantId);
+    await startStream(text, { conversationId: currentConversationId, editMessageId: msg.id });
+  };
+
+  const handleRegenerate = async (msg: ChatMessage) => {
+    if (!profileId || !currentConversationId || isStreaming) return;
+    const idx = messages.findIndex(m => m.id === msg.id);
+    const question = messages[idx - 1];
+    if (!question || question.role !== 'user') return;
+    const assistantId = `pending-${Date.now()}`;
+    setMessages(prev => [
+      ...prev.slice(0, idx),
+      { id: assistantId, role: 'assistant', content: '', timestamp: new Date().toISOString(), profile_id: profileId },
+    ]);
+    setStreamingAssistantId(assistantId);
+    await startStream(question.content, { conversationId: currentConversationId, regenerateMessageId: msg.id });
+  };
+
+  const handleSwitchBranch = async (msg: ChatMessage, step: number) => {
+    const siblings = msg.sibling_ids ?? [];
+    const target = siblings[siblings.indexOf(msg.id) + step];
+    if (!target || !profileId || isStreaming) return;
+    try {
+      await api.switchChatBranch(profileId, currentConversationId ?? null, target);
+      await reloadMessages();
+    } catch (err) {
+      console.error('Failed to switch branch:', err);
+    }
+  };
+
   const stopContinuousListeningAndSendRef = useRef<() => Promise<void>>(() => Promise.resolve());
 
   const handleSendWithText = useCallback(async (textToSend: string) => {
@@ -569,6 +634,17 @@ export function ProfileChat() {
                 </select>
               </>
             )}
+            <label
+              style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap', display: 'flex', alignItems: 'center', gap: '4px', cursor: 'pointer' }}
+              title="Send a rolling summary of older messages plus the most recent ones instead of the full history"
+            >
+              <input
+                type="checkbox"
+                checked={summaryMemoryEnabled}
+                onChange={(e) => setSummaryMemoryEnabled(e.target.checked)}
+              />
+              Summary memory
+            </label>
             <label style={{ fontSize: '12px', color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>Language:</label>
             <select
               value={chatLanguage}
@@ -608,6 +684,35 @@ export function ProfileChat() {
             >
               Export ({selectedMessages.size})
             </button>
+            <select
+              value=""
+              onChange={async (e) => {
+                const format = e.target.value as ReportFormat;
+                if (!format) return;
+                try {
+                  downloadReportFile(await api.exportConversationReport(profileId!, currentConversationId, format));
+                } catch (error) {
+                  console.error('Failed to export report:', error);
+                  alert('Failed to export conversation report');
+                }
+              }}
+              disabled={messages.length === 0}
+              title="Download this conversation as a report"
+              style={{
+                padding: '6px 10px',
+                borderRadius: '4px',
+                border: '1px solid var(--border-color)',
+                fontSize: '12px',
+                background: 'var(--bg-primary)',
+                color: 'var(--text-primary)',
+                cursor: 'pointer'
+              }}
+            >
+              <option value="">Report…</option>
+              <option value="html">HTML</option>
+              <option value="pdf">PDF</option>
+              <option value="docx">Word (DOCX)</option>
+            </select>
             <button
               onClick={async () => {
                 if (window.confirm(currentConversationId ? 'Clear this conversation?' : 'Clear all chat messages? This cannot be undone.')) {
@@ -712,6 +817,14 @@ export function ProfileChat() {
           </div>
         </div>
 
+        {summaryMemoryEnabled && currentConversationId && (
+          <SummaryMemoryPanel scope="conversation" scopeId={currentConversationId} refreshKey={messages.length} />
+        )}
+
+        {profileMemoryEnabled && profileId && (
+          <ProfileMemoryPanel profileId={profileId} refreshKey={messages.length} />
+        )}
+
         {/* Messages area */}
         <div style={{ 
           flex: 1, 
@@ -744,6 +857,7 @@ export function ProfileChat() {
           {messages.map((msg) => (
             <div
               key={msg.id}
+              id={`chat-msg-${msg.id}`}
               style={{
                 display: 'flex',
                 justifyContent: msg.role === 'user' ? 'flex-end' : 'flex-start',
@@ -805,9 +919,29 @@ export function ProfileChat() {
                     )}
                   </div>
                 )}
-                <div style={{ whiteSpace: 'pre-wrap', fontSize: '14px', lineHeight: '1.5' }}>
-                  {msg.content}
-                </div>
+                {editingMessageId === msg.id ? (
+                  <div>
+                    <textarea
+                      value={editDraft}
+                      onChange={(e) => setEditDraft(e.target.value)}
+                      rows={Math.min(8, Math.max(2, editDraft.split('\n').length))}
+                      autoFocus
+                      style={{ width: '100%', minWidth: '260px', fontSize: '14px', padding: '6px 8px', borderRadius: '4px', border: '1px solid var(--border-color)', background: 'var(--surface)', color: 'var(--text-primary)' }}
+                    />
+                    <div style={{ display: 'flex', gap: '6px', justifyContent: 'flex-end', marginTop: '6px' }}>
+                      <button type="button" className="btn btn-secondary" style={{ fontSize: '11px', padding: '3px 8px' }} onClick={() => setEditingMessageId(null)}>
+                        Cancel
+                      </button>
+                      <button type="button" className="btn btn-primary" style={{ fontSize: '11px', padding: '3px 8px' }} disabled={!editDraft.trim() || isStreaming} onClick={() => handleSubmitEdit(msg)}>
+                        Send as new branch
+                      </button>
+                    </div>
+                  </div>
+                ) : (
+                  <div style={{ whiteSpace: 'pre-wrap', fontSize: '14px', lineHeight: '1.5' }}>
+                    {msg.content}
+                  </div>
+                )}
                 <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '8px', marginTop: '6px', flexWrap: 'wrap' }}>
                   <span style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
                     <span style={{ fontSize: '11px', color: 'var(--text-secondary)' }}>
@@ -820,6 +954,28 @@ export function ProfileChat() {
                         voiceUri={profile?.voice_uri}
                       />
                     )}
+                    {msg.sibling_ids && msg.sibling_ids.length > 1 && (
+                      <span style={{ display: 'flex', alignItems: 'center', gap: '2px', fontSize: '11px', color: 'var(--text-secondary)' }} title="Alternative versions of this message">
+                        <button type="button" onClick={() => handleSwitchBranch(msg, -1)} disabled={isStreaming || msg.sibling_ids.indexOf(msg.id) === 0} style={{ background: 'none', border: 'none', cursor: 'pointer', color: 'inherit', padding: '0 4px' }} aria-label="Previous version">
+                          ‹
+                        </button>
+                        {msg.sibling_ids.indexOf(msg.id) + 1}/{msg.sibling_ids.length}
+                        <button type="button" onClick={() => handleSwitchBranch(msg, 1)} disabled={isStreaming || msg.sibling_ids.indexOf(msg.id) === msg.sibling_ids.length - 1} style={{ background: 'none', border: 'none', cursor: 'pointer', color: 'inherit', padding: '0 4px' }} aria-label="Next version">
+                          ›
+                        </button>
+                      </span>
+                    )}
+                    {msg.sibling_ids && !isStreaming && editingMessageId !== msg.id && (
+                      msg.role === 'user' ? (
+                        <button type="button" onClick={() => { setEditingMessageId(msg.id); setEditDraft(msg.content); }} style={{ background: 'none', border: 'none', cursor: 'pointer', fontSize: '11px', color: 'var(--text-secondary)', padding: 0 }} title="Edit and resend; the original stays as another branch">
+                          ✏️ Edit
+                        </button>
+                      ) : (
+                        <button type="button" onClick={() => handleRegenerate(msg)} style={{ background: 'none', border: 'none', cursor: 'pointer', fontSize: '11px', color: 'var(--text-secondary)', padding: 0 }} title="Generate another reply; this one stays as another branch">
+                          🔄 Regenerate
+                        </button>
+                      )
+                    )}
                   </span>
                   {msg.role === 'assistant' && isHybrid && msg.content && (
                     <div style={{ display: 'flex', alignItems: 'center', gap: '6px', flexWrap: 'wrap' }}>
@@ -866,7 +1022,9 @@ export function ProfileChat() {
                             return [...prev.slice(0, idx + 1), newMsg, ...prev.slice(idx + 1)];
                           });
                           try {
-                            await api.insertChatMessage(profileId!, 'assistant', improved);
+                            await api.insertChatMessage(profileId!, 'assistant', improved, currentConversationId ?? undefined);
+                            // Stored at the end of the active branch; show it where it was saved
+                            if (currentConversationId) await reloadMessages();
                           } catch (_) {
                             // Persistence optional; UI shows the new message
                           }
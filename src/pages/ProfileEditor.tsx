@@ -1,8 +1,12 @@
 import { useState, useEffect } from 'react';
 import { useAppStore } from '../store';
 import { api } from '../api';
-import type { CharacterDefinition, ModelFeatures, GenerationParams } from '../types';
+import type { CharacterDefinition, CharacterSource, ModelFeatures, GenerationParams } from '../types';
 import { VoiceSettings, type VoiceGender } from '../components/VoiceSettings';
+import { ProfileVersionsPanel } from '../components/ProfileVersionsPanel';
+import { CharacterProvenancePanel } from '../components/CharacterProvenancePanel';
+import { open } from '@tauri-apps/plugin-dialog';
+import { isTauri } from '../utils/tauri';
 
 interface ProfileEditorProps {
   profileId?: string;
@@ -36,6 +40,8 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
   const [modelInputMode, setModelInputMode] = useState<'select' | 'manual'>('select');
   const [profileUrls, setProfileUrls] = useState<string>('');
   const [personName, setPersonName] = useState<string>('');
+  const [documentPaths, setDocumentPaths] = useState<string[]>([]);
+  const [transcript, setTranscript] = useState<string>('');
   const [loadingCharacter, setLoadingCharacter] = useState(false);
   const [photoUrl, setPhotoUrl] = useState<string>('');
   const [abortController, setAbortController] = useState<AbortController | null>(null);
@@ -849,7 +855,7 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
               border: '1px solid #dee2e6'
             }}>
               <label style={{ fontSize: '13px', fontWeight: 'bold', marginBottom: '8px', display: 'block' }}>
-                🌐 Generate Character from Profile URLs
+                🌐 Generate Character from Sources
               </label>
               
               <div style={{ marginBottom: '10px' }}>
@@ -886,6 +892,58 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
                   disabled={loadingCharacter}
                 />
               </div>
+
+              {isTauri() && (
+                <div style={{ marginBottom: '10px' }}>
+                  <label style={{ fontSize: '12px', color: 'var(--text-secondary)', display: 'block', marginBottom: '5px' }}>
+                    Documents (CV, bio, articles — PDF, DOCX, RTF or text):
+                  </label>
+                  {documentPaths.map((path) => (
+                    <div key={path} style={{ display: 'flex', alignItems: 'center', gap: '8px', fontSize: '12px', marginBottom: '3px' }}>
+                      <span style={{ fontFamily: 'monospace', overflow: 'hidden', textOverflow: 'ellipsis' }}>{path}</span>
+                      <button
+                        type="button"
+                        className="btn btn-secondary"
+                        style={{ fontSize: '11px', padding: '2px 6px' }}
+                        onClick={() => setDocumentPaths(documentPaths.filter((p) => p !== path))}
+                        disabled={loadingCharacter}
+                      >
+                        ✕
+                      </button>
+                    </div>
+                  ))}
+                  <button
+                    type="button"
+                    className="btn btn-secondary"
+                    style={{ fontSize: '12px', padding: '5px 10px' }}
+                    disabled={loadingCharacter}
+                    onClick={async () => {
+                      const selected = await open({
+                        multiple: true,
+                        filters: [{ name: 'Documents', extensions: ['pdf', 'docx', 'rtf', 'txt', 'md'] }],
+                      });
+                      const paths = Array.isArray(selected) ? selected : selected ? [selected] : [];
+                      setDocumentPaths([...documentPaths, ...paths.filter((p) => !documentPaths.includes(p))]);
+                    }}
+                  >
+                    📄 Add documents
+                  </button>
+                </div>
+              )}
+
+              <div style={{ marginBottom: '10px' }}>
+                <label style={{ fontSize: '12px', color: 'var(--text-secondary)', display: 'block', marginBottom: '5px' }}>
+                  Transcript (interview, talk or chat — pasted text):
+                </label>
+                <textarea
+                  value={transcript}
+                  onChange={(e) => setTranscript(e.target.value)}
+                  placeholder="Paste a transcript of the person speaking or writing..."
+                  rows={4}
+                  style={{ width: '100%', padding: '8px', borderRadius: '4px', border: '1px solid var(--border-color)', fontSize: '12px' }}
+                  disabled={loadingCharacter}
+                />
+              </div>
               
               <div style={{ display: 'flex', gap: '10px', alignItems: 'center' }}>
                 <button
@@ -894,8 +952,13 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
                   style={{ fontSize: '12px', padding: '8px 15px' }}
                   onClick={async () => {
                     const urls = profileUrls.split('\n').map(u => u.trim()).filter(u => u.length > 0);
-                    if (urls.length === 0) {
-                      alert('Please enter at least one URL');
+                    const sources: CharacterSource[] = [
+                      ...urls.map((url): CharacterSource => ({ kind: 'url', url })),
+                      ...documentPaths.map((path): CharacterSource => ({ kind: 'document', path })),
+                      ...(transcript.trim() ? [{ kind: 'transcript', text: transcript.trim() } as CharacterSource] : []),
+                    ];
+                    if (sources.length === 0) {
+                      alert('Please add at least one URL, document or transcript');
                       return;
                     }
                     const sel = providers.find((x: any) => x.id === formData.provider_account_id);
@@ -913,14 +976,13 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
                     setLoadingCharacter(true);
                     
                     try {
-                      const response = await api.generateCharacterFromUrl(
-                        urls,
-                        personName.trim() || undefined,
-                        formData.provider_account_id,
-                        modelToUse,
-                        controller.signal,
-                        token
-                      );
+                      const response = await api.generateCharacterFromSources({
+                        sources,
+                        person_name: personName.trim() || undefined,
+                        provider_account_id: formData.provider_account_id,
+                        model_name: modelToUse,
+                        cancellation_token: token,
+                      });
                       
                       // Check if cancelled
                       if (controller.signal.aborted) {
@@ -936,7 +998,12 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
                       setShowCharacterEditor(true);
                       setProfileUrls('');
                       setPersonName('');
-                      alert('Character definition generated successfully! The extracted text has been added to the Persona Prompt field.');
+                      setDocumentPaths([]);
+                      setTranscript('');
+                      alert(
+                        'Character definition generated successfully! The extracted text has been added to the Persona Prompt field.' +
+                          (response.warnings.length > 0 ? `\n\nSkipped sources:\n${response.warnings.join('\n')}` : '')
+                      );
                     } catch (error: any) {
                       if (controller.signal.aborted || error?.message === 'Request cancelled' || error?.includes('cancelled')) {
                         console.log('Generation cancelled by user');
@@ -950,7 +1017,7 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
                       setCancellationToken(null);
                     }
                   }}
-                  disabled={loadingCharacter || !profileUrls.trim() || !formData.provider_account_id || (!formData.model_name && providers.find((x: any) => x.id === formData.provider_account_id)?.provider_type !== 'hybrid')}
+                  disabled={loadingCharacter || (!profileUrls.trim() && documentPaths.length === 0 && !transcript.trim()) || !formData.provider_account_id || (!formData.model_name && providers.find((x: any) => x.id === formData.provider_account_id)?.provider_type !== 'hybrid')}
                 >
                   {loadingCharacter ? '⏳ Generating...' : '✨ Generate'}
                 </button>
@@ -988,9 +1055,9 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
                 )}
               </div>
               <small style={{ color: 'var(--text-secondary)', fontSize: '11px', display: 'block', marginTop: '6px' }}>
-                Enter one or more URLs (one per line) to profiles, websites, or social media pages about the person. 
+                Enter one or more URLs (one per line) to profiles, websites, or social media pages about the person, and/or add documents or a transcript.
                 {personName && ` The system will look for information about "${personName}" specifically.`}
-                The system will automatically extract and create a character definition from all provided sources.
+                The system will extract a character definition from all provided sources and record which source supports each trait.
               </small>
             </div>
           )}
@@ -999,6 +1066,13 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
               <strong>{formData.character_definition.name}</strong> - {formData.character_definition.role}
             </div>
           )}
+          {formData.character_definition?.provenance && !showCharacterEditor && (
+            <CharacterProvenancePanel
+              provenance={formData.character_definition.provenance}
+              profileId={profileId}
+              onRefreshed={(character) => setFormData({ ...formData, character_definition: character })}
+            />
+          )}
           {showCharacterEditor && (
             <CharacterEditor
               character={formData.character_definition}
@@ -1109,8 +1183,31 @@ export function ProfileEditor({ profileId, onClose, onSave }: ProfileEditorProps
               />
             </div>
           </div>
+          <label style={{ display: 'flex', alignItems: 'center', gap: '10px', marginTop: '10px', fontSize: '13px' }}>
+            <input
+              type="checkbox"
+              checked={Boolean((formData.params.profile_memory as { enabled?: boolean } | undefined)?.enabled)}
+              onChange={(e) =>
+                setFormData({
+                  ...formData,
+                  params: { ...formData.params, profile_memory: { enabled: e.target.checked } },
+                })
+              }
+            />
+            Remember facts and preferences I share across conversations
+          </label>
         </div>
 
+        {profileId && (
+          <ProfileVersionsPanel
+            profileId={profileId}
+            onRolledBack={() => {
+              onSave();
+              onClose();
+            }}
+          />
+        )}
+
         <div style={{ display: 'flex', gap: '10px', marginTop: '20px' }}>
           <button type="button" className="btn btn-primary" onClick={handleSave}>
             Save Profile
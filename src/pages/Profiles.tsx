@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { useAppStore } from '../store';
-import { api } from '../api';
+import { api, downloadReportFile } from '../api';
 import { ProfileEditor } from './ProfileEditor';
 
 export function Profiles() {
@@ -9,6 +9,7 @@ export function Profiles() {
   const { profiles, setProfiles, providers } = useAppStore();
   const [showModal, setShowModal] = useState(false);
   const [editingProfile, setEditingProfile] = useState<string | null>(null);
+  const [importing, setImporting] = useState(false);
 
   useEffect(() => {
     loadProfiles();
@@ -23,6 +24,40 @@ export function Profiles() {
     }
   };
 
+  const handleImportCard = async (file: File) => {
+    setImporting(true);
+    try {
+      const isPng = file.type === 'image/png' || file.name.toLowerCase().endsWith('.png');
+      const content = isPng
+        ? await new Promise<string>((resolve, reject) => {
+            const reader = new FileReader();
+            reader.onload = () => resolve(String(reader.result).split(',')[1] ?? '');
+            reader.onerror = () => reject(reader.error);
+            reader.readAsDataURL(file);
+          })
+        : await file.text();
+      const result = await api.importProfileCard({ content, base64: isPng });
+      await loadProfiles();
+      const provider = providers.find((p) => p.id === result.provider_account_id)?.display_name || 'a local provider';
+      alert(
+        `Imported "${result.name}" using ${result.model_name} on ${provider}.` +
+          (result.warnings.length > 0 ? `\n${result.warnings.join('\n')}` : '')
+      );
+    } catch (error) {
+      alert(`Failed to import character card: ${error instanceof Error ? error.message : String(error)}`);
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const handleExportCard = async (profileId: string, format: 'json' | 'png') => {
+    try {
+      downloadReportFile(await api.exportProfileCard(profileId, format));
+    } catch (error) {
+      alert(`Failed to export character card: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
   return (
     <div>
       <div className="page-header">
@@ -50,6 +85,20 @@ export function Profiles() {
         <button className="btn btn-primary" onClick={() => setShowModal(true)}>
           Create Profile
         </button>
+        <label className="btn btn-secondary" style={{ cursor: importing ? 'wait' : 'pointer', margin: '0 0 0 10px' }}>
+          {importing ? 'Importing...' : '📥 Import Character Card'}
+          <input
+            type="file"
+            accept="application/json,.json,image/png,.png"
+            style={{ display: 'none' }}
+            disabled={importing}
+            onChange={(e) => {
+              const file = e.target.files?.[0];
+              if (file) handleImportCard(file);
+              e.target.value = '';
+            }}
+          />
+        </label>
       </div>
 
       <div className="grid grid-3">
@@ -132,6 +181,22 @@ export function Profiles() {
                 >
                   Edit
                 </button>
+                <button
+                  className="btn btn-secondary"
+                  style={{ fontSize: '12px', padding: '5px 10px' }}
+                  title="Export as a JSON character card"
+                  onClick={() => handleExportCard(profile.id, 'json')}
+                >
+                  Card JSON
+                </button>
+                <button
+                  className="btn btn-secondary"
+                  style={{ fontSize: '12px', padding: '5px 10px' }}
+                  title="Export as a PNG character card (image with embedded metadata)"
+                  onClick={() => handleExportCard(profile.id, 'png')}
+                >
+                  Card PNG
+                </button>
               </div>
             </div>
           ))
@@ -0,0 +1,188 @@
+import { useEffect, useState } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { api, type SearchHit, type SearchSource } from '../api';
+import { useAppStore } from '../store';
+
+const SOURCE_LABELS: Record<SearchSource, string> = {
+  chat: 'Profile chats',
+  group_chat: 'Group chats',
+  debate: 'Debates',
+  run_result: 'Brainstorm results',
+  coder_chat: 'Coder chats',
+  coder_ide: 'Coder IDE',
+};
+
+const PAGE_SIZE = 50;
+
+function hitLink(hit: SearchHit): string | null {
+  switch (hit.source) {
+    case 'chat':
+      if (!hit.profile_id) return null;
+      return `/profile-chat/${hit.profile_id}${hit.parent_id ? `?conversation=${hit.parent_id}` : ''}#chat-msg-${hit.item_id}`;
+    case 'group_chat':
+      return hit.parent_id ? `/group-chat?conversation=${hit.parent_id}#group-msg-${hit.item_id}` : '/group-chat';
+    case 'debate':
+      return hit.parent_id ? `/debate-room/${hit.parent_id}#msg-${hit.item_id}` : null;
+    case 'run_result':
+      if (!hit.parent_id) return null;
+      return hit.mode === 'debate'
+        ? `/debate-room/${hit.parent_id}`
+        : `/parallel-brainstorm/${hit.parent_id}#result-${hit.item_id}`;
+    case 'coder_chat':
+    case 'coder_ide':
+      return '/coder';
+    default:
+      return null;
+  }
+}
+
+export function Search() {
+  const navigate = useNavigate();
+  const { profiles, setProfiles, projects, setProjects } = useAppStore();
+  const [query, setQuery] = useState('');
+  const [sources, setSources] = useState<SearchSource[]>([]);
+  const [profileId, setProfileId] = useState('');
+  const [projectId, setProjectId] = useState('');
+  const [dateFrom, setDateFrom] = useState('');
+  const [dateTo, setDateTo] = useState('');
+  const [hits, setHits] = useState<SearchHit[]>([]);
+  const [searched, setSearched] = useState(false);
+  const [hasMore, setHasMore] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    Promise.all([api.listProfiles(), api.listProjects()])
+      .then(([profilesData, projectsData]) => {
+        setProfiles(profilesData);
+        setProjects(projectsData);
+      })
+      .catch((e) => console.error('Failed to load filters:', e));
+  }, []);
+
+  const runSearch = async (offset: number) => {
+    if (!query.trim()) return;
+    setLoading(true);
+    setError(null);
+    try {
+      const page = await api.searchHistory({
+        query,
+        sources,
+        profile_id: profileId || undefined,
+        project_id: projectId || undefined,
+        date_from: dateFrom || undefined,
+        date_to: dateTo || undefined,
+        limit: PAGE_SIZE,
+        offset,
+      });
+      setHits((prev) => (offset === 0 ? page : [...prev, ...page]));
+      setHasMore(page.length === PAGE_SIZE);
+      setSearched(true);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const toggleSource = (source: SearchSource) => {
+    setSources((prev) => (prev.includes(source) ? prev.filter((s) => s !== source) : [...prev, source]));
+  };
+
+  const inputStyle = { padding: '6px 10px', borderRadius: '4px', border: '1px solid var(--border-color)' };
+
+  return (
+    <div>
+      <div className="page-header">
+        <h1>Search History</h1>
+        <p>Find anything said in chats, debates, brainstorms and coder conversations</p>
+      </div>
+
+      <form
+        className="card"
+        style={{ marginBottom: '20px' }}
+        onSubmit={(e) => {
+          e.preventDefault();
+          runSearch(0);
+        }}
+      >
+        <div style={{ display: 'flex', gap: '10px' }}>
+          <input
+            type="search"
+            value={query}
+            onChange={(e) => setQuery(e.target.value)}
+            placeholder="Search words..."
+            autoFocus
+            style={{ ...inputStyle, flex: 1, fontSize: '15px' }}
+          />
+          <button type="submit" className="btn btn-primary" disabled={loading || !query.trim()}>
+            {loading ? 'Searching...' : 'Search'}
+          </button>
+        </div>
+        <div style={{ display: 'flex', gap: '14px', flexWrap: 'wrap', marginTop: '12px', fontSize: '13px' }}>
+          {(Object.keys(SOURCE_LABELS) as SearchSource[]).map((source) => (
+            <label key={source} style={{ display: 'flex', alignItems: 'center', gap: '4px', cursor: 'pointer' }}>
+              <input type="checkbox" checked={sources.includes(source)} onChange={() => toggleSource(source)} />
+              {SOURCE_LABELS[source]}
+            </label>
+          ))}
+          {sources.length === 0 && <span style={{ color: 'var(--text-secondary)' }}>(all sources)</span>}
+        </div>
+        <div style={{ display: 'flex', gap: '10px', flexWrap: 'wrap', marginTop: '12px', alignItems: 'center', fontSize: '13px' }}>
+          <select value={profileId} onChange={(e) => setProfileId(e.target.value)} style={inputStyle}>
+            <option value="">Any profile</option>
+            {profiles.map((p) => (
+              <option key={p.id} value={p.id}>{p.name}</option>
+            ))}
+          </select>
+          <select value={projectId} onChange={(e) => setProjectId(e.target.value)} style={inputStyle}>
+            <option value="">Any project</option>
+            {projects.map((p) => (
+              <option key={p.id} value={p.id}>{p.name}</option>
+            ))}
+          </select>
+          <label>
+            From <input type="date" value={dateFrom} onChange={(e) => setDateFrom(e.target.value)} style={inputStyle} />
+          </label>
+          <label>
+            To <input type="date" value={dateTo} onChange={(e) => setDateTo(e.target.value)} style={inputStyle} />
+          </label>
+        </div>
+      </form>
+
+      {error && <div style={{ color: '#dc3545', marginBottom: '12px' }}>{error}</div>}
+      {searched && hits.length === 0 && !loading && (
+        <div className="card"><p style={{ color: 'var(--text-secondary)', margin: 0 }}>No matches.</p></div>
+      )}
+
+      {hits.map((hit) => {
+        const link = hitLink(hit);
+        return (
+          <div
+            key={`${hit.source}-${hit.item_id}-${hit.parent_id ?? ''}`}
+            className="card"
+            onClick={() => link && navigate(link)}
+            style={{ marginBottom: '10px', cursor: link ? 'pointer' : 'default' }}
+          >
+            <div style={{ display: 'flex', justifyContent: 'space-between', gap: '10px', fontSize: '12px', color: 'var(--text-secondary)' }}>
+              <span>
+                <strong>{SOURCE_LABELS[hit.source]}</strong>
+                {hit.title && ` · ${hit.title}`}
+                {hit.profile_name && ` · ${hit.profile_name}`}
+              </span>
+              {hit.created_at && <span>{new Date(hit.created_at).toLocaleString()}</span>}
+            </div>
+            {/* snippet_html is escaped by the backend; only <mark> tags are added */}
+            <div style={{ marginTop: '6px', fontSize: '14px' }} dangerouslySetInnerHTML={{ __html: hit.snippet_html }} />
+          </div>
+        );
+      })}
+
+      {hasMore && (
+        <button type="button" className="btn btn-secondary" disabled={loading} onClick={() => runSearch(hits.length)}>
+          {loading ? 'Loading...' : 'Load more'}
+        </button>
+      )}
+    </div>
+  );
+}
@@ -1,7 +1,17 @@
 import { useState, useEffect } from 'react';
 import { useAppStore } from '../store';
 import { useNavigate } from 'react-router-dom';
-import { api } from '../api';
+import { api, type Sop, type SopTemplate, type SopVariable } from '../api';
+
+/** Variables used as {{name}} in a template's text fields, plus declared ones */
+function templateVariables(template: SopTemplate): SopVariable[] {
+  const declared = template.variables ?? [];
+  const names = new Set(declared.map((v) => v.name));
+  const used = [template.title, template.question, template.tone ?? '', template.language ?? '']
+    .flatMap((text) => Array.from(text.matchAll(/\{\{\s*([^}]+?)\s*\}\}/g), (m) => m[1]))
+    .filter((name) => !names.has(name) && names.add(name));
+  return [...declared, ...used.map((name) => ({ name }))];
+}
 
 export function SessionBuilder() {
   const { profiles, projects } = useAppStore();
@@ -12,6 +22,96 @@ export function SessionBuilder() {
   const [mode, setMode] = useState<'parallel' | 'debate'>('parallel');
   const [selectedProfiles, setSelectedProfiles] = useState<string[]>([]);
   const [loading, setLoading] = useState(false);
+  const [sops, setSops] = useState<Sop[]>([]);
+  const [selectedSopId, setSelectedSopId] = useState('');
+  const [variableValues, setVariableValues] = useState<Record<string, string>>({});
+  const [templateName, setTemplateName] = useState('');
+
+  const loadSops = () => {
+    api.listSops()
+      .then(setSops)
+      .catch((err) => console.error('Failed to load templates:', err));
+  };
+
+  useEffect(loadSops, []);
+
+  const selectedSop = sops.find((s) => s.id === selectedSopId);
+
+  const openRun = (runId: string, runMode: 'parallel' | 'debate') => {
+    navigate(runMode === 'parallel' ? `/parallel-brainstorm/${runId}` : `/debate-room/${runId}`);
+  };
+
+  const handleUseTemplate = async () => {
+    if (!selectedSop) return;
+    setLoading(true);
+    try {
+      const created = await api.instantiateSop(selectedSop.id, variableValues, projectId || projects[0]?.id);
+      openRun(created.run_id, created.mode);
+    } catch (error) {
+      alert(`Failed to create session from template: ${error instanceof Error ? error.message : String(error)}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleSaveTemplate = async () => {
+    if (!templateName.trim() || !question.trim()) {
+      alert('Enter a template name and a question (use {{topic}} style variables for the parts that change)');
+      return;
+    }
+    try {
+      const sop = await api.saveSop(templateName.trim(), {
+        mode,
+        title,
+        question,
+        profile_ids: selectedProfiles,
+        speaking_order: selectedProfiles,
+      });
+      setTemplateName('');
+      loadSops();
+      alert(`Saved template "${sop.name}" (version ${sop.version})`);
+    } catch (error) {
+      alert(`Failed to save template: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
+  const handleExportTemplate = async () => {
+    if (!selectedSop) return;
+    try {
+      const json = await api.exportSop(selectedSop.id);
+      const blob = new Blob([json], { type: 'application/json' });
+      const url = URL.createObjectURL(blob);
+      const a = document.createElement('a');
+      a.href = url;
+      a.download = `${selectedSop.name.replace(/[^a-z0-9-_]+/gi, '_')}.v${selectedSop.version}.json`;
+      a.click();
+      URL.revokeObjectURL(url);
+    } catch (error) {
+      alert(`Failed to export template: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
+  const handleImportTemplate = async (file: File) => {
+    try {
+      const sop = await api.importSop(await file.text());
+      loadSops();
+      setSelectedSopId(sop.id);
+      setVariableValues({});
+    } catch (error) {
+      alert(`Failed to import template: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
+  const handleDeleteTemplate = async () => {
+    if (!selectedSop || !window.confirm(`Delete template "${selectedSop.name}" and all its versions?`)) return;
+    try {
+      await api.deleteSop(selectedSop.name);
+      setSelectedSopId('');
+      loadSops();
+    } catch (error) {
+      alert(`Failed to delete template: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
 
   // Load profiles if store is empty (e.g. user navigated directly to session builder)
   useEffect(() => {
@@ -80,11 +180,7 @@ export function SessionBuilder() {
         ),
       ]);
 
-      if (mode === 'parallel') {
-        navigate(`/parallel-brainstorm/${runId}`);
-      } else {
-        navigate(`/debate-room/${runId}`);
-      }
+      openRun(runId, mode);
     } catch (error) {
       console.error('Failed to create session:', error);
       const msg = error instanceof Error ? error.message : String(error);
@@ -125,6 +221,84 @@ export function SessionBuilder() {
         <p>Configure your AI session</p>
       </div>
 
+      <div className="card" style={{ marginBottom: '20px' }}>
+        <div className="form-group">
+          <label>Start from a template</label>
+          <div style={{ display: 'flex', gap: '10px', alignItems: 'center', flexWrap: 'wrap' }}>
+            <select
+              value={selectedSopId}
+              onChange={(e) => {
+                setSelectedSopId(e.target.value);
+                setVariableValues({});
+              }}
+              style={{ flex: 1, minWidth: '200px' }}
+            >
+              <option value="">Select a template…</option>
+              {sops.map((sop) => (
+                <option key={sop.id} value={sop.id}>
+                  {sop.name} (v{sop.version}, {sop.template.mode})
+                </option>
+              ))}
+            </select>
+            <label className="btn btn-secondary" style={{ cursor: 'pointer', margin: 0 }}>
+              📥 Import
+              <input
+                type="file"
+                accept="application/json,.json"
+                style={{ display: 'none' }}
+                onChange={(e) => {
+                  const file = e.target.files?.[0];
+                  if (file) handleImportTemplate(file);
+                  e.target.value = '';
+                }}
+              />
+            </label>
+            {selectedSop && (
+              <>
+                <button type="button" className="btn btn-secondary" onClick={handleExportTemplate}>
+                  📤 Export
+                </button>
+                <button type="button" className="btn btn-secondary" style={{ color: '#dc3545' }} onClick={handleDeleteTemplate}>
+                  Delete
+                </button>
+              </>
+            )}
+          </div>
+        </div>
+
+        {selectedSop && (
+          <>
+            {selectedSop.template.description && (
+              <p style={{ color: 'var(--text-secondary)', fontSize: '13px' }}>{selectedSop.template.description}</p>
+            )}
+            {templateVariables(selectedSop.template).map((variable) => (
+              <div className="form-group" key={variable.name}>
+                <label>
+                  {variable.name}
+                  {variable.description && (
+                    <span style={{ color: 'var(--text-secondary)', fontWeight: 'normal' }}> — {variable.description}</span>
+                  )}
+                </label>
+                <input
+                  type="text"
+                  value={variableValues[variable.name] ?? ''}
+                  placeholder={variable.default || 'Required'}
+                  onChange={(e) => setVariableValues({ ...variableValues, [variable.name]: e.target.value })}
+                />
+              </div>
+            ))}
+            <button
+              type="button"
+              className="btn btn-primary"
+              onClick={handleUseTemplate}
+              disabled={loading || !(projectId || projects[0]?.id)}
+            >
+              {loading ? 'Creating...' : 'Create Session from Template'}
+            </button>
+          </>
+        )}
+      </div>
+
       <div className="card">
         <div className="form-group">
           <label>Session Title</label>
@@ -198,6 +372,21 @@ export function SessionBuilder() {
             {loading ? 'Creating...' : 'Start Session'}
           </button>
         </div>
+        <div style={{ display: 'flex', gap: '10px', alignItems: 'center', marginTop: '15px', flexWrap: 'wrap' }}>
+          <input
+            type="text"
+            value={templateName}
+            onChange={(e) => setTemplateName(e.target.value)}
+            placeholder="Template name"
+            style={{ width: '220px' }}
+          />
+          <button type="button" className="btn btn-secondary" onClick={handleSaveTemplate}>
+            💾 Save as Template
+          </button>
+          <span style={{ fontSize: '12px', color: 'var(--text-secondary)' }}>
+            Use {'{{topic}}'} in the title or question for values filled in later; saving an existing name adds a new version.
+          </span>
+        </div>
       </div>
     </div>
   );
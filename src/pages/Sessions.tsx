@@ -1,7 +1,7 @@
 import { Link, useNavigate } from 'react-router-dom';
 import { useEffect, useState } from 'react';
 import { useAppStore } from '../store';
-import { api } from '../api';
+import { api, type SessionImportConflict } from '../api';
 
 interface Session {
   id: string;
@@ -18,6 +18,9 @@ export function Sessions() {
   const navigate = useNavigate();
   const [deleteModal, setDeleteModal] = useState<{ session: Session } | null>(null);
   const [deleting, setDeleting] = useState(false);
+  const [importProjectId, setImportProjectId] = useState('');
+  const [importConflict, setImportConflict] = useState<SessionImportConflict>('copy');
+  const [importing, setImporting] = useState(false);
 
   useEffect(() => {
     loadData();
@@ -38,6 +41,33 @@ export function Sessions() {
     }
   };
 
+  const handleImportSession = async (file: File) => {
+    const projectId = importProjectId || projects[0]?.id;
+    if (!projectId) {
+      alert('Create a project before importing a session.');
+      return;
+    }
+    setImporting(true);
+    try {
+      const summary = await api.importSessionJson(await file.text(), {
+        project_id: projectId,
+        on_conflict: importConflict,
+      });
+      await loadData();
+      const created = summary.profiles.filter((p) => p.action === 'created').map((p) => p.name);
+      alert(
+        summary.status === 'skipped'
+          ? 'This session was already imported; nothing changed.'
+          : `Session ${summary.status}: ${summary.runs} runs, ${summary.results} results, ${summary.messages} messages.` +
+              (created.length > 0 ? `\nNew profiles: ${created.join(', ')}` : '')
+      );
+    } catch (error) {
+      alert(`Failed to import session: ${error instanceof Error ? error.message : String(error)}`);
+    } finally {
+      setImporting(false);
+    }
+  };
+
   const getProjectName = (projectId: string) => {
     const project = projects.find(p => p.id === projectId);
     return project?.name || 'Unknown Project';
@@ -82,10 +112,45 @@ export function Sessions() {
         </p>
       </div>
 
-      <div style={{ marginBottom: '20px' }}>
+      <div style={{ marginBottom: '20px', display: 'flex', gap: '10px', alignItems: 'center', flexWrap: 'wrap' }}>
         <Link to="/session-builder" className="btn btn-primary">
           New Session
         </Link>
+        <select
+          value={importProjectId}
+          onChange={(e) => setImportProjectId(e.target.value)}
+          title="Project for imported sessions"
+          style={{ padding: '6px 10px', borderRadius: '4px', border: '1px solid var(--border-color)' }}
+        >
+          {projects.length === 0 && <option value="">No projects</option>}
+          {projects.map((p) => (
+            <option key={p.id} value={p.id}>{p.name}</option>
+          ))}
+        </select>
+        <select
+          value={importConflict}
+          onChange={(e) => setImportConflict(e.target.value as SessionImportConflict)}
+          title="When this session was imported before"
+          style={{ padding: '6px 10px', borderRadius: '4px', border: '1px solid var(--border-color)' }}
+        >
+          <option value="copy">Import as copy</option>
+          <option value="skip">Skip if already imported</option>
+          <option value="replace">Replace earlier import</option>
+        </select>
+        <label className="btn btn-secondary" style={{ cursor: importing ? 'wait' : 'pointer', margin: 0 }}>
+          {importing ? 'Importing...' : '📥 Import Session'}
+          <input
+            type="file"
+            accept="application/json,.json"
+            style={{ display: 'none' }}
+            disabled={importing}
+            onChange={(e) => {
+              const file = e.target.files?.[0];
+              if (file) handleImportSession(file);
+              e.target.value = '';
+            }}
+          />
+        </label>
       </div>
 
       {sessions.length === 0 ? (
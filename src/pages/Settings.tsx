@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { api } from '../api';
+import { api, type McpServerConfig, type McpServerProbe } from '../api';
 import { useAppStore } from '../store';
 import { VoiceSettings, type VoiceGender } from '../components/VoiceSettings';
 
@@ -987,6 +987,154 @@ function PrivacyTab() {
 }
 
 // Modal wrapper component
+/** KEY=VALUE lines <-> record, for env vars and HTTP headers */
+function parsePairs(text: string): Record<string, string> {
+  const pairs: Record<string, string> = {};
+  for (const line of text.split('\n')) {
+    const idx = line.indexOf('=');
+    if (idx > 0) pairs[line.slice(0, idx).trim()] = line.slice(idx + 1).trim();
+  }
+  return pairs;
+}
+
+function formatPairs(pairs: Record<string, string>): string {
+  return Object.entries(pairs).map(([k, v]) => `${k}=${v}`).join('\n');
+}
+
+function McpTab() {
+  const [servers, setServers] = useState<McpServerConfig[]>([]);
+  const [probes, setProbes] = useState<Record<number, McpServerProbe | string>>({});
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    api.listMcpServers().then(setServers).catch((e) => setMessage(`Failed to load MCP servers: ${e}`));
+  }, []);
+
+  const update = (index: number, patch: Partial<McpServerConfig>) => {
+    setServers(servers.map((s, i) => (i === index ? ({ ...s, ...patch } as McpServerConfig) : s)));
+  };
+
+  const setTransport = (index: number, transport: 'stdio' | 'http') => {
+    const current = servers[index];
+    const base = { name: current.name, enabled: current.enabled, timeout_secs: current.timeout_secs };
+    const next: McpServerConfig = transport === 'stdio'
+      ? { ...base, transport, command: '', args: [], env: {} }
+      : { ...base, transport, url: '', headers: {} };
+    setServers(servers.map((s, i) => (i === index ? next : s)));
+  };
+
+  const save = async () => {
+    setSaving(true);
+    try {
+      setServers(await api.saveMcpServers(servers));
+      setMessage('MCP servers saved');
+    } catch (e) {
+      setMessage(`Failed to save: ${e}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const test = async (index: number) => {
+    try {
+      const probe = await api.testMcpServer(servers[index]);
+      setProbes({ ...probes, [index]: probe });
+    } catch (e) {
+      setProbes({ ...probes, [index]: String(e) });
+    }
+  };
+
+  const inputStyle = { padding: '6px 8px', border: '1px solid var(--border-color)', borderRadius: '4px', fontSize: '13px' };
+
+  return (
+    <div>
+      <p style={{ color: 'var(--text-secondary)', marginBottom: '20px', fontSize: '13px' }}>
+        Model Context Protocol servers whose tools the Cline agent can call. Every call still needs your approval.
+      </p>
+      {servers.map((server, index) => {
+        const probe = probes[index];
+        return (
+          <div key={index} style={{ padding: '15px', background: 'var(--bg-secondary)', borderRadius: '4px', marginBottom: '15px' }}>
+            <div style={{ display: 'flex', gap: '10px', alignItems: 'center', flexWrap: 'wrap', marginBottom: '10px' }}>
+              <input value={server.name} onChange={(e) => update(index, { name: e.target.value })} placeholder="Name" style={{ ...inputStyle, width: '160px' }} />
+              <select value={server.transport} onChange={(e) => setTransport(index, e.target.value as 'stdio' | 'http')} style={inputStyle}>
+                <option value="stdio">stdio (local command)</option>
+                <option value="http">Streamable HTTP</option>
+              </select>
+              <label style={{ display: 'flex', alignItems: 'center', gap: '6px', fontSize: '13px' }}>
+                <input type="checkbox" checked={server.enabled} onChange={(e) => update(index, { enabled: e.target.checked })} />
+                Enabled
+              </label>
+              <button className="btn btn-secondary" onClick={() => test(index)}>Test</button>
+              <button className="btn btn-secondary" style={{ color: '#dc3545' }} onClick={() => setServers(servers.filter((_, i) => i !== index))}>
+                Remove
+              </button>
+            </div>
+            {server.transport === 'stdio' ? (
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: '10px' }}>
+                <input value={server.command} onChange={(e) => update(index, { command: e.target.value })} placeholder="Command, e.g. npx" style={inputStyle} />
+                <input
+                  value={server.args.join(' ')}
+                  onChange={(e) => update(index, { args: e.target.value.split(' ').filter(Boolean) })}
+                  placeholder="Arguments, e.g. -y @modelcontextprotocol/server-filesystem ."
+                  style={inputStyle}
+                />
+                <textarea
+                  defaultValue={formatPairs(server.env)}
+                  onBlur={(e) => update(index, { env: parsePairs(e.target.value) })}
+                  placeholder="Environment (KEY=VALUE per line)"
+                  rows={2}
+                  style={inputStyle}
+                />
+                <input value={server.cwd ?? ''} onChange={(e) => update(index, { cwd: e.target.value || null })} placeholder="Working directory (optional)" style={inputStyle} />
+              </div>
+            ) : (
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: '10px' }}>
+                <input value={server.url} onChange={(e) => update(index, { url: e.target.value })} placeholder="https://example.com/mcp" style={inputStyle} />
+                <textarea
+                  defaultValue={formatPairs(server.headers)}
+                  onBlur={(e) => update(index, { headers: parsePairs(e.target.value) })}
+                  placeholder="Headers (Name=value per line)"
+                  rows={2}
+                  style={inputStyle}
+                />
+              </div>
+            )}
+            {typeof probe === 'string' && <div style={{ color: '#dc3545', fontSize: '12px', marginTop: '8px' }}>{probe}</div>}
+            {probe && typeof probe !== 'string' && (
+              <div style={{ fontSize: '12px', marginTop: '8px' }}>
+                <strong>{probe.server_info?.serverInfo?.name ?? server.name}</strong>: {probe.tools.length} tool(s)
+                {probe.resources.length > 0 && `, ${probe.resources.length} resource(s)`}
+                <ul style={{ margin: '4px 0 0 18px', padding: 0 }}>
+                  {probe.tools.map((tool) => (
+                    <li key={tool.name}>
+                      <code>{tool.name}</code>
+                      {tool.description && <span style={{ color: 'var(--text-secondary)' }}> – {tool.description}</span>}
+                    </li>
+                  ))}
+                </ul>
+              </div>
+            )}
+          </div>
+        );
+      })}
+      <div style={{ display: 'flex', gap: '10px', alignItems: 'center' }}>
+        <button
+          className="btn btn-secondary"
+          onClick={() => setServers([...servers, { name: '', enabled: true, transport: 'stdio', command: '', args: [], env: {} }])}
+        >
+          + Add server
+        </button>
+        <button className="btn btn-primary" onClick={save} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </button>
+        {message && <span style={{ fontSize: '13px', color: 'var(--text-secondary)' }}>{message}</span>}
+      </div>
+    </div>
+  );
+}
+
 function ModalWrapper({ title, onClose, children }: { title: string; onClose: () => void; children: React.ReactNode }) {
   return (
     <div
@@ -1078,6 +1226,15 @@ export function VoiceModal({ onClose }: { onClose: () => void }) {
   );
 }
 
+// MCP Servers Modal
+export function McpModal({ onClose }: { onClose: () => void }) {
+  return (
+    <ModalWrapper title="MCP Servers" onClose={onClose}>
+      <McpTab />
+    </ModalWrapper>
+  );
+}
+
 // Ollama Modal
 export function OllamaModal({ onClose }: { onClose: () => void }) {
   const [ollamaHealth, setOllamaHealth] = useState<OllamaHealth | null>(null);
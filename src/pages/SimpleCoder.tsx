@@ -1,6 +1,7 @@
 import { useState, useEffect, useRef, useMemo } from 'react';
 import { useNavigate } from 'react-router-dom';
 import Editor, { DiffEditor } from '@monaco-editor/react';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { api } from '../api';
 import { useAppStore } from '../store';
 import { VoiceInput } from '../components/VoiceInput';
@@ -74,10 +75,23 @@ interface ClineToolExecution {
   step_index: number;
   tool_type: string;
   tool_params: any;
-  approval_status: 'pending' | 'approved' | 'rejected';
+  approval_status: 'pending' | 'executing' | 'approved' | 'rejected';
   result?: any;
+  iteration?: number;
 }
 
+interface ClineProgressEvent {
+  run_id: string;
+  iteration: number;
+  stage: string;
+  message: string;
+  tool_executions: ClineToolExecution[];
+  total_tokens: number;
+}
+
+/** Read-only tools the agent loop may run without asking */
+const CLINE_AUTO_APPROVE = ['workspace_read', 'search_files', 'search_code', 'analyze_ast', 'find_symbol'];
+
 export function SimpleCoder() {
   const navigate = useNavigate();
   // State
@@ -112,6 +126,8 @@ export function SimpleCoder() {
   const [clineConversationMessages, setClineConversationMessages] = useState<Array<{ role: 'user' | 'assistant'; content: string }>>([]);
   const [agentResponseInput, setAgentResponseInput] = useState('');
   const [toolBusyIds, setToolBusyIds] = useState<string[]>([]);
+  const [clineProgress, setClineProgress] = useState<string | null>(null);
+  const clineUnlistenRef = useRef<UnlistenFn | null>(null);
   const [agentSummary, setAgentSummary] = useState<string | null>(null);
   const [agentError, setAgentError] = useState<string | null>(null);
   const [agentChanges, setAgentChanges] = useState<{
@@ -200,6 +216,7 @@ export function SimpleCoder() {
     setAgentChanges(null);
     setPendingTools([]);
     setClineRunId(null);
+    setClineProgress(null);
     setClineConversationMessages([]);
   };
 
@@ -1107,6 +1124,80 @@ ${context}`;
     }
   };
 
+  /** Merge tool executions from a progress event or result into the list by id */
+  const mergeTools = (tools: ClineToolExecution[]) => {
+    setPendingTools(prev => {
+      const next = [...prev];
+      for (const tool of tools) {
+        const idx = next.findIndex(t => t.id === tool.id);
+        if (idx >= 0) next[idx] = { ...next[idx], ...tool };
+        else next.push(tool);
+      }
+      return next;
+    });
+  };
+
+  /** Run the Cline observe-act loop, following its progress events until it finishes */
+  const runClineTask = async (
+    task: string,
+    workingDir: string,
+    conversationContext?: Array<{ role: string; content: string }>,
+  ) => {
+    const runId = crypto.randomUUID();
+    setClineRunId(runId);
+    setClineProgress('Starting agent...');
+    if (clineUnlistenRef.current) clineUnlistenRef.current();
+    clineUnlistenRef.current = await listen<ClineProgressEvent>('panther://cline_progress', (event) => {
+      const progress = event.payload;
+      if (progress.run_id !== runId) return;
+      setClineProgress(`Iteration ${progress.iteration} · ${progress.stage.replace(/_/g, ' ')} · ${progress.total_tokens} tokens`);
+      if (progress.stage === 'iteration_complete' && progress.message) setAgentSummary(progress.message);
+      if (progress.tool_executions.length > 0) mergeTools(progress.tool_executions);
+      if (progress.stage === 'tool_result' || progress.stage === 'iteration_complete') {
+        loadDirectory(workingDir);
+      }
+    });
+
+    try {
+      const response = await api.clineAgentTask({
+        provider_id: selectedProvider,
+        model_name: selectedModel,
+        task_description: task,
+        workspace_path: workingDir,
+        target_paths: undefined,
+        conversation_context: conversationContext,
+        run_id: runId,
+        loop_config: { auto_approve: CLINE_AUTO_APPROVE },
+      });
+
+      setAgentSummary(response.summary);
+      mergeTools(response.tool_executions || []);
+      setClineProgress(
+        `${response.status.replace(/_/g, ' ')} after ${response.iterations} iteration(s) · ${response.total_tokens} tokens`
+      );
+      setClineConversationMessages(prev => [
+        ...prev,
+        { role: 'user', content: task },
+        { role: 'assistant', content: response.summary },
+      ]);
+    } finally {
+      if (clineUnlistenRef.current) {
+        clineUnlistenRef.current();
+        clineUnlistenRef.current = null;
+      }
+    }
+  };
+
+  const cancelClineTask = async () => {
+    if (!clineRunId) return;
+    try {
+      await api.clineCancelTask(clineRunId);
+      setClineProgress('Cancelling...');
+    } catch (error: any) {
+      setAgentError(error?.message || String(error));
+    }
+  };
+
   const handleAgentRun = async () => {
     if (!agentTask.trim() || agentRunning) return;
     if (!selectedProvider || !selectedModel) {
@@ -1125,6 +1216,7 @@ ${context}`;
     setAgentError(null);
     setAgentChanges(null);
     setPendingTools([]);
+    setClineProgress(null);
 
     try {
       if (agentStyle === 'approve') {
@@ -1133,23 +1225,7 @@ ${context}`;
           ? clineConversationMessages.map(m => ({ role: m.role, content: m.content }))
           : undefined;
 
-        const response = await api.clineAgentTask({
-          provider_id: selectedProvider,
-          model_name: selectedModel,
-          task_description: agentTask.trim(),
-          workspace_path: workingDir,
-          target_paths: undefined,
-          conversation_context: conversationContext,
-        });
-
-        setAgentSummary(response.summary);
-        setClineRunId(response.run_id);
-        setPendingTools(response.tool_executions || []);
-        setClineConversationMessages(prev => [
-          ...prev,
-          { role: 'user', content: agentTask.trim() },
-          { role: 'assistant', content: response.summary },
-        ]);
+        await runClineTask(agentTask.trim(), workingDir, conversationContext);
       } else {
         // Panther-style: propose changes
         let targetPaths: string[] | undefined;
@@ -1210,21 +1286,13 @@ ${context}`;
     const msg = agentResponseInput.trim();
     setAgentResponseInput('');
     setAgentRunning(true);
+    setPendingTools([]);
     try {
-      const response = await api.clineAgentTask({
-        provider_id: selectedProvider,
-        model_name: selectedModel,
-        task_description: msg,
-        workspace_path: currentPath || workspacePath || '',
-        conversation_context: clineConversationMessages.map(m => ({ role: m.role, content: m.content })),
-      });
-      setAgentSummary(response.summary);
-      setPendingTools(response.tool_executions || []);
-      setClineConversationMessages(prev => [
-        ...prev,
-        { role: 'user', content: msg },
-        { role: 'assistant', content: response.summary },
-      ]);
+      await runClineTask(
+        msg,
+        currentPath || workspacePath || '',
+        clineConversationMessages.map(m => ({ role: m.role, content: m.content })),
+      );
     } catch (error: any) {
       setAgentError(error?.message || String(error));
     } finally {
@@ -2667,7 +2735,7 @@ ${context}`;
             ))}
 
             {/* Agent Mode summary, proposed changes, or tool approvals */}
-            {(agentSummary || agentError || agentChanges || pendingTools.length > 0) && (
+            {(agentSummary || agentError || agentChanges || pendingTools.length > 0 || clineProgress) && (
               <div style={{
                 marginTop: '16px',
                 padding: '10px 12px',
@@ -2762,6 +2830,17 @@ ${context}`;
                     </div>
                   </div>
                 )}
+                {agentStyle === 'approve' && clineProgress && (
+                  <div style={{ display: 'flex', gap: '8px', alignItems: 'center', marginBottom: '6px', color: 'var(--text-secondary)' }}>
+                    <span>{clineProgress}</span>
+                    {agentRunning && clineRunId && (
+                      <button onClick={cancelClineTask}
+                        style={{ padding: '2px 8px', fontSize: '10px', background: 'transparent', color: '#dc3545', border: '1px solid #dc3545', borderRadius: '3px', cursor: 'pointer' }}>
+                        Stop
+                      </button>
+                    )}
+                  </div>
+                )}
                 {/* Cline (Approve mode) pending tools */}
                 {agentStyle === 'approve' && pendingTools.length > 0 && (
                   <div style={{ marginTop: '12px' }}>
@@ -2783,7 +2862,13 @@ ${context}`;
                             fontSize: '11px',
                             background: 'var(--bg-primary)',
                           }}>
-                            <div style={{ fontWeight: 500, marginBottom: '4px' }}>{desc}</div>
+                            <div style={{ fontWeight: 500, marginBottom: '4px' }}>
+                              {tool.iteration ? <span style={{ color: 'var(--text-secondary)', fontWeight: 400 }}>#{tool.iteration} </span> : null}
+                              {desc}
+                              {!isPending && (
+                                <span style={{ color: 'var(--text-secondary)', fontWeight: 400 }}> · {tool.approval_status}</span>
+                              )}
+                            </div>
                             {isPending && (
                               <div style={{ display: 'flex', gap: '6px' }}>
                                 <button onClick={() => approveTool(tool.id, true)} disabled={toolBusyIds.includes(tool.id)}
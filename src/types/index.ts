@@ -61,6 +61,30 @@ export interface CharacterDefinition {
   background?: string;
   goals?: string[];
   constraints?: string[];
+  /** Present when generated from sources */
+  provenance?: CharacterProvenance;
+}
+
+export type CharacterSource =
+  | { kind: 'url'; url: string }
+  | { kind: 'document'; path: string }
+  | { kind: 'transcript'; label?: string; text: string };
+
+export type SourceRecord = CharacterSource & {
+  /** "S1", "S2", ... as cited in `fields` */
+  id: string;
+  content_hash: string;
+  chars: number;
+  extracted_at: string;
+};
+
+export interface CharacterProvenance {
+  sources: SourceRecord[];
+  fields: Array<{ field: string; value: string; sources: string[] }>;
+  person_name?: string | null;
+  provider_account_id: string;
+  model_name: string;
+  generated_at: string;
 }
 
 export interface ModelFeatures {